@@ -57,4 +57,10 @@ impl crate::node_management::NodeManagementAPI for super::CoapClient {
             Err(err) => Err(anyhow::anyhow!(String::from_utf8(err).unwrap())),
         }
     }
+    async fn check_function_health(
+        &mut self,
+        _request: crate::node_management::FunctionHealthRequest,
+    ) -> anyhow::Result<crate::node_management::FunctionHealthResponse> {
+        anyhow::bail!("Checking the health of functions is not supported via CoAP")
+    }
 }
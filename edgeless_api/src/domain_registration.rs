@@ -81,6 +81,8 @@ pub struct UpdateDomainRequest {
     // allows the domain register to detect service restarts.
     // It is drawn randomly when the node starts and never changes.
     pub nonce: u64,
+    // Health of the functions with restarts or probes in the domain.
+    pub component_health: Vec<crate::function_lifecycle::ComponentHealth>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
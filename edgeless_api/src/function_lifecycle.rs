@@ -0,0 +1,275 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Lifecycle settings of function instances, as specified by annotations:
+//! restart policy applied by the node when a function instance exits, and
+//! the liveness/readiness probes run by the orchestrator.
+
+/// What to do when a function instance exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// The function instance is never restarted.
+    #[default]
+    Never,
+    /// The function instance is restarted only if it exited with an error.
+    OnFailure,
+    /// The function instance is always restarted, unless stopped explicitly.
+    Always,
+}
+
+impl std::fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                RestartPolicy::Never => "never",
+                RestartPolicy::OnFailure => "on-failure",
+                RestartPolicy::Always => "always",
+            }
+        )
+    }
+}
+
+impl RestartPolicy {
+    pub fn from_string(val: &str) -> Self {
+        match val.to_lowercase().replace('_', "-").as_str() {
+            "on-failure" => RestartPolicy::OnFailure,
+            "always" => RestartPolicy::Always,
+            _ => RestartPolicy::Never,
+        }
+    }
+}
+
+/// Restart settings of a function instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestartSpec {
+    pub policy: RestartPolicy,
+    /// Delay before the first restart, doubled at every new restart.
+    pub backoff: std::time::Duration,
+    /// Maximum delay between two consecutive restarts.
+    pub max_backoff: std::time::Duration,
+    /// Maximum number of restarts, if any.
+    pub max_restarts: Option<u32>,
+}
+
+impl Default for RestartSpec {
+    fn default() -> Self {
+        Self {
+            policy: RestartPolicy::Never,
+            backoff: std::time::Duration::from_millis(1000),
+            max_backoff: std::time::Duration::from_millis(60000),
+            max_restarts: None,
+        }
+    }
+}
+
+impl RestartSpec {
+    /// Restart settings from the annotations in the function's spawn request.
+    ///
+    /// Invalid values are ignored and the defaults are used instead.
+    pub fn from_annotations(annotations: &std::collections::HashMap<String, String>) -> Self {
+        let mut spec = Self::default();
+        if let Some(val) = annotations.get("restart_policy") {
+            spec.policy = RestartPolicy::from_string(val);
+        }
+        if let Some(val) = annotations.get("restart_backoff_ms").and_then(|x| x.parse::<u64>().ok()) {
+            spec.backoff = std::time::Duration::from_millis(val);
+        }
+        if let Some(val) = annotations.get("restart_max_backoff_ms").and_then(|x| x.parse::<u64>().ok()) {
+            spec.max_backoff = std::time::Duration::from_millis(val);
+        }
+        if let Some(val) = annotations.get("restart_max_count").and_then(|x| x.parse::<u32>().ok()) {
+            spec.max_restarts = Some(val);
+        }
+        spec
+    }
+
+    /// Return true if a function instance that has already been restarted
+    /// `restarts` times must be restarted again after exiting.
+    pub fn should_restart(&self, failed: bool, restarts: u32) -> bool {
+        let allowed = match self.policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => failed,
+            RestartPolicy::Always => true,
+        };
+        allowed && self.max_restarts.is_none_or(|max_restarts| restarts < max_restarts)
+    }
+
+    /// Delay before the next restart of a function instance that has already
+    /// been restarted `restarts` times.
+    pub fn next_backoff(&self, restarts: u32) -> std::time::Duration {
+        self.backoff.saturating_mul(2_u32.saturating_pow(restarts)).min(self.max_backoff)
+    }
+}
+
+/// Kind of a probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProbeKind {
+    /// If failing, the function instance is restarted.
+    Liveness,
+    /// If failing, the function instance is reported as not ready.
+    Readiness,
+}
+
+impl std::fmt::Display for ProbeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ProbeKind::Liveness => "liveness",
+                ProbeKind::Readiness => "readiness",
+            }
+        )
+    }
+}
+
+/// Probe of a function instance: a `call()` with a given payload, which
+/// succeeds if the function replies within the timeout with the expected
+/// reply, if specified, or with any reply, otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeSpec {
+    pub kind: ProbeKind,
+    /// Payload of the call.
+    pub payload: String,
+    /// Expected reply, if any.
+    pub expected_reply: Option<String>,
+    /// Interval between consecutive probes.
+    pub period: std::time::Duration,
+    /// Maximum time to wait for the reply.
+    pub timeout: std::time::Duration,
+    /// Number of consecutive failures after which the probe is failed.
+    pub failure_threshold: u32,
+}
+
+impl ProbeSpec {
+    /// Probe of a given kind from the annotations in the function's spawn
+    /// request, if present.
+    ///
+    /// For instance, for a liveness probe the annotations are:
+    ///
+    /// - `liveness_probe`: the payload of the call (mandatory)
+    /// - `liveness_probe_reply`: the expected reply
+    /// - `probe_period_ms`, `probe_timeout_ms`, `probe_failure_threshold`:
+    ///   shared by liveness and readiness probes
+    pub fn from_annotations(annotations: &std::collections::HashMap<String, String>, kind: ProbeKind) -> Option<Self> {
+        let payload = annotations.get(format!("{}_probe", kind).as_str())?;
+        let get_u64 = |name: &str, default_val: u64| annotations.get(name).and_then(|x| x.parse::<u64>().ok()).unwrap_or(default_val);
+        Some(Self {
+            kind,
+            payload: payload.clone(),
+            expected_reply: annotations.get(format!("{}_probe_reply", kind).as_str()).cloned(),
+            period: std::time::Duration::from_millis(get_u64("probe_period_ms", 5000).max(1)),
+            timeout: std::time::Duration::from_millis(get_u64("probe_timeout_ms", 1000)),
+            failure_threshold: (get_u64("probe_failure_threshold", 3) as u32).max(1),
+        })
+    }
+
+    /// All the probes found in the annotations of a function's spawn request.
+    pub fn all_from_annotations(annotations: &std::collections::HashMap<String, String>) -> Vec<Self> {
+        [ProbeKind::Liveness, ProbeKind::Readiness]
+            .into_iter()
+            .filter_map(|kind| Self::from_annotations(annotations, kind))
+            .collect()
+    }
+}
+
+/// Health of a function/resource, as tracked by the orchestrator.
+#[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct ComponentHealth {
+    /// Logical identifier of the function/resource in the orchestration domain.
+    pub component_id: crate::function_instance::ComponentId,
+    /// Total number of restarts, both by the nodes and by the orchestrator.
+    pub restarts: u32,
+    /// Outcome of the readiness probe, if configured.
+    pub ready: Option<bool>,
+    /// Outcome of the liveness probe, if configured.
+    pub live: Option<bool>,
+}
+
+impl std::fmt::Display for ComponentHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let to_string = |val: Option<bool>| match val {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "n/a",
+        };
+        write!(
+            f,
+            "restarts {}, ready {}, live {}",
+            self.restarts,
+            to_string(self.ready),
+            to_string(self.live)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_spec() {
+        let spec = RestartSpec::from_annotations(&std::collections::HashMap::new());
+        assert_eq!(RestartSpec::default(), spec);
+        assert!(!spec.should_restart(true, 0));
+
+        let spec = RestartSpec::from_annotations(&std::collections::HashMap::from([
+            ("restart_policy".to_string(), "on-failure".to_string()),
+            ("restart_backoff_ms".to_string(), "100".to_string()),
+            ("restart_max_backoff_ms".to_string(), "500".to_string()),
+            ("restart_max_count".to_string(), "3".to_string()),
+        ]));
+        assert_eq!(RestartPolicy::OnFailure, spec.policy);
+        assert!(spec.should_restart(true, 0));
+        assert!(spec.should_restart(true, 2));
+        assert!(!spec.should_restart(true, 3));
+        assert!(!spec.should_restart(false, 0));
+        assert_eq!(std::time::Duration::from_millis(100), spec.next_backoff(0));
+        assert_eq!(std::time::Duration::from_millis(400), spec.next_backoff(2));
+        assert_eq!(std::time::Duration::from_millis(500), spec.next_backoff(3));
+        assert_eq!(std::time::Duration::from_millis(500), spec.next_backoff(100));
+
+        let spec = RestartSpec::from_annotations(&std::collections::HashMap::from([
+            ("restart_policy".to_string(), "always".to_string()),
+            ("restart_backoff_ms".to_string(), "not-a-number".to_string()),
+        ]));
+        assert!(spec.should_restart(false, 1000));
+        assert_eq!(std::time::Duration::from_millis(1000), spec.backoff);
+    }
+
+    #[test]
+    fn test_probe_spec() {
+        assert!(ProbeSpec::all_from_annotations(&std::collections::HashMap::new()).is_empty());
+
+        let probes = ProbeSpec::all_from_annotations(&std::collections::HashMap::from([
+            ("liveness_probe".to_string(), "ping".to_string()),
+            ("liveness_probe_reply".to_string(), "pong".to_string()),
+            ("readiness_probe".to_string(), "ready?".to_string()),
+            ("probe_period_ms".to_string(), "2000".to_string()),
+            ("probe_failure_threshold".to_string(), "0".to_string()),
+        ]));
+        assert_eq!(
+            vec![
+                ProbeSpec {
+                    kind: ProbeKind::Liveness,
+                    payload: "ping".to_string(),
+                    expected_reply: Some("pong".to_string()),
+                    period: std::time::Duration::from_millis(2000),
+                    timeout: std::time::Duration::from_millis(1000),
+                    failure_threshold: 1,
+                },
+                ProbeSpec {
+                    kind: ProbeKind::Readiness,
+                    payload: "ready?".to_string(),
+                    expected_reply: None,
+                    period: std::time::Duration::from_millis(2000),
+                    timeout: std::time::Duration::from_millis(1000),
+                    failure_threshold: 1,
+                }
+            ],
+            probes
+        );
+    }
+}
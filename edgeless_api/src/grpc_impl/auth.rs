@@ -0,0 +1,536 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Token-based authentication and role-based authorization of the public
+//! WorkflowInstance API of the ε-CON and of the Introspection API of the
+//! ε-ORC.
+//!
+//! Authentication is done by a tonic interceptor, which validates the bearer
+//! token found in the `authorization` metadata of the request, either against
+//! a list of static tokens or as a signed JWT verified offline with a local
+//! key set.
+//! The resulting [`Principal`] is stored in the request extensions and then
+//! checked by the server against the role required by each RPC.
+
+/// Role of an authenticated user.
+///
+/// Roles are ordered: a role grants all the permissions of the roles that
+/// precede it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Can list and inspect workflows and domains.
+    ReadOnly,
+    /// Can also start and stop workflows.
+    Deploy,
+    /// Can also migrate workflows across domains and add deploy intents,
+    /// e.g., cordon or drain nodes, in an orchestration domain.
+    Admin,
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Role::ReadOnly => "read_only",
+                Role::Deploy => "deploy",
+                Role::Admin => "admin",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "read_only" | "readonly" => Ok(Role::ReadOnly),
+            "deploy" => Ok(Role::Deploy),
+            "admin" => Ok(Role::Admin),
+            _ => anyhow::bail!("invalid role: {}", s),
+        }
+    }
+}
+
+/// Static token accepted by the server.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct StaticToken {
+    /// Name of the user owning the token, only used for logging.
+    pub subject: String,
+    /// Secret bearer token.
+    pub token: String,
+    /// Role assigned to the user.
+    pub role: Role,
+}
+
+// Secrets are never printed, since settings are logged.
+impl std::fmt::Debug for StaticToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticToken")
+            .field("subject", &self.subject)
+            .field("token", &"<redacted>")
+            .field("role", &self.role)
+            .finish()
+    }
+}
+
+/// Settings to validate signed JWTs offline.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct JwtConfig {
+    /// Path of a JSON Web Key Set file with the public keys of the issuers.
+    #[serde(default)]
+    pub jwks_path: Option<String>,
+    /// Shared secret for tokens signed with HMAC algorithms.
+    #[serde(default)]
+    pub hmac_secret: Option<String>,
+    /// If set, the `iss` claim must match this value.
+    #[serde(default)]
+    pub issuer: Option<String>,
+    /// If set, the `aud` claim must contain this value.
+    #[serde(default)]
+    pub audience: Option<String>,
+    /// Name of the claim containing the role of the user.
+    #[serde(default = "default_role_claim")]
+    pub role_claim: String,
+}
+
+impl std::fmt::Debug for JwtConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtConfig")
+            .field("jwks_path", &self.jwks_path)
+            .field("hmac_secret", &self.hmac_secret.as_ref().map(|_| "<redacted>"))
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .field("role_claim", &self.role_claim)
+            .finish()
+    }
+}
+
+fn default_role_claim() -> String {
+    String::from("role")
+}
+
+/// Authentication and authorization settings of a gRPC server.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct AuthConfig {
+    /// Static tokens accepted.
+    #[serde(default)]
+    pub tokens: Vec<StaticToken>,
+    /// JWT validation settings, if JWTs are accepted.
+    #[serde(default)]
+    pub jwt: Option<JwtConfig>,
+    /// Override of the minimum role required per RPC, e.g., `start = "admin"`.
+    /// The RPCs not listed use the default permissions, see
+    /// [`default_required_role`].
+    #[serde(default)]
+    pub permissions: std::collections::HashMap<String, Role>,
+}
+
+/// Names of the WorkflowInstance and Introspection RPCs that require
+/// authorization, which can be used as keys of [`AuthConfig::permissions`].
+pub const RPCS: [&str; 12] = [
+    "start",
+    "stop",
+    "list",
+    "inspect",
+    "domains",
+    "migrate",
+    "dry_run",
+    "logs",
+    "snapshot",
+    "add_deploy_intents",
+    "function_logs",
+    "query_metrics",
+];
+
+/// Minimum role required by default to invoke a WorkflowInstance or
+/// Introspection RPC.
+pub fn default_required_role(rpc: &str) -> Role {
+    match rpc {
+        "start" | "stop" => Role::Deploy,
+        "migrate" | "add_deploy_intents" => Role::Admin,
+        _ => Role::ReadOnly,
+    }
+}
+
+/// Authenticated user, stored in the extensions of the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub subject: String,
+    pub role: Role,
+}
+
+/// Validates tokens and checks permissions, according to an [`AuthConfig`].
+pub struct Authorizer {
+    tokens: std::collections::HashMap<String, StaticToken>,
+    jwt: Option<JwtValidator>,
+    permissions: std::collections::HashMap<String, Role>,
+}
+
+struct JwtValidator {
+    jwks: Option<jsonwebtoken::jwk::JwkSet>,
+    hmac_key: Option<jsonwebtoken::DecodingKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+    role_claim: String,
+}
+
+impl Authorizer {
+    pub fn new(config: &AuthConfig) -> anyhow::Result<Self> {
+        let jwt = match &config.jwt {
+            Some(jwt_config) => {
+                let jwks = match &jwt_config.jwks_path {
+                    Some(path) => Some(
+                        serde_json::from_str::<jsonwebtoken::jwk::JwkSet>(
+                            &std::fs::read_to_string(path).map_err(|err| anyhow::anyhow!("cannot read the JWT key set from '{}': {}", path, err))?,
+                        )
+                        .map_err(|err| anyhow::anyhow!("invalid JWT key set in '{}': {}", path, err))?,
+                    ),
+                    None => None,
+                };
+                let hmac_key = jwt_config
+                    .hmac_secret
+                    .as_ref()
+                    .map(|secret| jsonwebtoken::DecodingKey::from_secret(secret.as_bytes()));
+                anyhow::ensure!(
+                    jwks.is_some() || hmac_key.is_some(),
+                    "JWT authentication enabled without a key set or an HMAC secret"
+                );
+                Some(JwtValidator {
+                    jwks,
+                    hmac_key,
+                    issuer: jwt_config.issuer.clone(),
+                    audience: jwt_config.audience.clone(),
+                    role_claim: jwt_config.role_claim.clone(),
+                })
+            }
+            None => None,
+        };
+        for token in &config.tokens {
+            anyhow::ensure!(!token.token.is_empty(), "empty static token for subject '{}'", token.subject);
+        }
+        for rpc in config.permissions.keys() {
+            anyhow::ensure!(
+                RPCS.contains(&rpc.as_str()),
+                "unknown RPC '{}' in the permissions, valid ones are: {}",
+                rpc,
+                RPCS.join(", ")
+            );
+        }
+
+        Ok(Self {
+            tokens: config.tokens.iter().map(|token| (token.token.clone(), token.clone())).collect(),
+            jwt,
+            permissions: config.permissions.clone(),
+        })
+    }
+
+    /// Return the principal associated with the given bearer token.
+    #[allow(clippy::result_large_err)]
+    pub fn authenticate(&self, token: &str) -> Result<Principal, tonic::Status> {
+        if let Some(static_token) = self.tokens.get(token) {
+            return Ok(Principal {
+                subject: static_token.subject.clone(),
+                role: static_token.role,
+            });
+        }
+        if let Some(jwt) = &self.jwt {
+            return jwt.validate(token).map_err(|err| {
+                log::info!("JWT rejected: {}", err);
+                tonic::Status::unauthenticated("invalid token")
+            });
+        }
+        Err(tonic::Status::unauthenticated("invalid token"))
+    }
+
+    /// Minimum role required to invoke the given RPC.
+    pub fn required_role(&self, rpc: &str) -> Role {
+        self.permissions.get(rpc).copied().unwrap_or_else(|| default_required_role(rpc))
+    }
+
+    /// Check that the request has been issued by a principal that is allowed
+    /// to invoke the given RPC.
+    #[allow(clippy::result_large_err)]
+    pub fn authorize<T>(&self, request: &tonic::Request<T>, rpc: &str) -> Result<(), tonic::Status> {
+        let principal = match request.extensions().get::<Principal>() {
+            Some(principal) => principal,
+            None => return Err(tonic::Status::unauthenticated("missing credentials")),
+        };
+        let required = self.required_role(rpc);
+        if principal.role >= required {
+            Ok(())
+        } else {
+            log::info!(
+                "user '{}' with role {} denied access to {}, which requires {}",
+                principal.subject,
+                principal.role,
+                rpc,
+                required
+            );
+            Err(tonic::Status::permission_denied(format!("{} requires role {}", rpc, required)))
+        }
+    }
+}
+
+impl JwtValidator {
+    fn validate(&self, token: &str) -> anyhow::Result<Principal> {
+        let header = jsonwebtoken::decode_header(token)?;
+        let key = match (&header.kid, &self.jwks) {
+            (Some(kid), Some(jwks)) => match jwks.find(kid) {
+                Some(jwk) => jsonwebtoken::DecodingKey::from_jwk(jwk)?,
+                None => anyhow::bail!("unknown key id: {}", kid),
+            },
+            _ => match &self.hmac_key {
+                Some(hmac_key) => hmac_key.clone(),
+                None => anyhow::bail!("no key to verify the token"),
+            },
+        };
+
+        // The decoding key is checked to be of the same family as the
+        // algorithm in the header, which prevents algorithm-confusion attacks.
+        let mut validation = jsonwebtoken::Validation::new(header.alg);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+            validation.required_spec_claims.insert(String::from("iss"));
+        }
+        match &self.audience {
+            Some(audience) => {
+                validation.set_audience(&[audience]);
+                validation.required_spec_claims.insert(String::from("aud"));
+            }
+            None => validation.validate_aud = false,
+        }
+
+        let claims = jsonwebtoken::decode::<serde_json::Map<String, serde_json::Value>>(token, &key, &validation)?.claims;
+        let role = match claims.get(&self.role_claim) {
+            Some(serde_json::Value::String(role)) => role.parse::<Role>()?,
+            // If multiple roles are assigned, the most powerful one wins.
+            Some(serde_json::Value::Array(roles)) => roles
+                .iter()
+                .filter_map(|role| role.as_str().and_then(|role| role.parse::<Role>().ok()))
+                .max()
+                .ok_or_else(|| anyhow::anyhow!("no valid role in claim '{}'", self.role_claim))?,
+            _ => anyhow::bail!("missing claim '{}'", self.role_claim),
+        };
+        let subject = match claims.get("sub") {
+            Some(serde_json::Value::String(subject)) => subject.clone(),
+            _ => String::from("unknown"),
+        };
+
+        Ok(Principal { subject, role })
+    }
+}
+
+/// Server-side interceptor authenticating the requests.
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    authorizer: std::sync::Arc<Authorizer>,
+}
+
+impl AuthInterceptor {
+    pub fn new(authorizer: std::sync::Arc<Authorizer>) -> Self {
+        Self { authorizer }
+    }
+}
+
+impl tonic::service::Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        let token = match request.metadata().get("authorization") {
+            Some(value) => match value.to_str().ok().and_then(|value| value.strip_prefix("Bearer ")) {
+                Some(token) => token.trim().to_string(),
+                None => return Err(tonic::Status::unauthenticated("malformed authorization header")),
+            },
+            None => return Err(tonic::Status::unauthenticated("missing credentials")),
+        };
+        let principal = self.authorizer.authenticate(&token)?;
+        request.extensions_mut().insert(principal);
+        Ok(request)
+    }
+}
+
+/// Client-side interceptor adding a bearer token to all the requests, if set.
+#[derive(Clone, Default)]
+pub struct BearerTokenInterceptor {
+    header: Option<tonic::metadata::MetadataValue<tonic::metadata::Ascii>>,
+}
+
+impl BearerTokenInterceptor {
+    pub fn new(token: Option<String>) -> anyhow::Result<Self> {
+        let header = match token {
+            Some(token) if !token.is_empty() => Some(format!("Bearer {}", token).parse()?),
+            _ => None,
+        };
+        Ok(Self { header })
+    }
+}
+
+impl tonic::service::Interceptor for BearerTokenInterceptor {
+    fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        if let Some(header) = &self.header {
+            request.metadata_mut().insert("authorization", header.clone());
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tonic::service::Interceptor;
+
+    fn test_config() -> AuthConfig {
+        AuthConfig {
+            tokens: vec![
+                StaticToken {
+                    subject: "alice".to_string(),
+                    token: "alice-token".to_string(),
+                    role: Role::ReadOnly,
+                },
+                StaticToken {
+                    subject: "bob".to_string(),
+                    token: "bob-token".to_string(),
+                    role: Role::Deploy,
+                },
+            ],
+            jwt: Some(JwtConfig {
+                jwks_path: None,
+                hmac_secret: Some("top-secret".to_string()),
+                issuer: Some("edgeless".to_string()),
+                audience: None,
+                role_claim: default_role_claim(),
+            }),
+            permissions: std::collections::HashMap::from([("list".to_string(), Role::Deploy)]),
+        }
+    }
+
+    fn sign(claims: serde_json::Value, secret: &str) -> String {
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn intercept(authorizer: &std::sync::Arc<Authorizer>, token: Option<&str>) -> Result<tonic::Request<()>, tonic::Status> {
+        let mut interceptor = AuthInterceptor::new(authorizer.clone());
+        let mut client_interceptor = BearerTokenInterceptor::new(token.map(|x| x.to_string())).unwrap();
+        interceptor.call(client_interceptor.call(tonic::Request::new(())).unwrap())
+    }
+
+    #[test]
+    fn test_role_parse_and_order() {
+        assert_eq!(Role::ReadOnly, "read-only".parse().unwrap());
+        assert_eq!(Role::Admin, "ADMIN".parse().unwrap());
+        assert!("root".parse::<Role>().is_err());
+        assert!(Role::Admin > Role::Deploy);
+        assert!(Role::Deploy > Role::ReadOnly);
+    }
+
+    #[test]
+    fn test_static_tokens() {
+        let authorizer = std::sync::Arc::new(Authorizer::new(&test_config()).unwrap());
+
+        assert_eq!(
+            tonic::Code::Unauthenticated,
+            intercept(&authorizer, None).expect_err("accepted without token").code()
+        );
+        assert_eq!(
+            tonic::Code::Unauthenticated,
+            intercept(&authorizer, Some("mallory-token")).expect_err("accepted invalid token").code()
+        );
+
+        let request = intercept(&authorizer, Some("alice-token")).unwrap();
+        assert!(authorizer.authorize(&request, "inspect").is_ok());
+        assert_eq!(
+            tonic::Code::PermissionDenied,
+            authorizer.authorize(&request, "start").expect_err("start allowed").code()
+        );
+        // Overridden permission.
+        assert!(authorizer.authorize(&request, "list").is_err());
+
+        let request = intercept(&authorizer, Some("bob-token")).unwrap();
+        assert!(authorizer.authorize(&request, "start").is_ok());
+        assert!(authorizer.authorize(&request, "list").is_ok());
+        assert!(authorizer.authorize(&request, "migrate").is_err());
+        assert!(authorizer.authorize(&request, "add_deploy_intents").is_err());
+        assert!(authorizer.authorize(&request, "snapshot").is_ok());
+
+        // Requests that did not go through the interceptor are rejected.
+        assert!(authorizer.authorize(&tonic::Request::new(()), "inspect").is_err());
+    }
+
+    #[test]
+    fn test_jwt() {
+        let authorizer = std::sync::Arc::new(Authorizer::new(&test_config()).unwrap());
+        let exp = jsonwebtoken::get_current_timestamp() + 3600;
+
+        let token = sign(
+            serde_json::json!({"sub": "carol", "iss": "edgeless", "exp": exp, "role": "admin"}),
+            "top-secret",
+        );
+        let request = intercept(&authorizer, Some(&token)).unwrap();
+        assert_eq!(
+            Some(&Principal {
+                subject: "carol".to_string(),
+                role: Role::Admin
+            }),
+            request.extensions().get::<Principal>()
+        );
+        assert!(authorizer.authorize(&request, "migrate").is_ok());
+        assert!(authorizer.authorize(&request, "add_deploy_intents").is_ok());
+
+        let token = sign(
+            serde_json::json!({"sub": "dave", "iss": "edgeless", "exp": exp, "role": ["read_only", "deploy"]}),
+            "top-secret",
+        );
+        let request = intercept(&authorizer, Some(&token)).unwrap();
+        assert_eq!(Role::Deploy, request.extensions().get::<Principal>().unwrap().role);
+
+        // Wrong secret, wrong issuer, expired, no role.
+        for token in [
+            sign(serde_json::json!({"sub": "eve", "iss": "edgeless", "exp": exp, "role": "admin"}), "guess"),
+            sign(
+                serde_json::json!({"sub": "eve", "iss": "other", "exp": exp, "role": "admin"}),
+                "top-secret",
+            ),
+            sign(
+                serde_json::json!({"sub": "eve", "iss": "edgeless", "exp": 1000, "role": "admin"}),
+                "top-secret",
+            ),
+            sign(serde_json::json!({"sub": "eve", "iss": "edgeless", "exp": exp}), "top-secret"),
+        ] {
+            assert!(intercept(&authorizer, Some(&token)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mut config = test_config();
+        config.jwt.as_mut().unwrap().hmac_secret = None;
+        assert!(Authorizer::new(&config).is_err());
+
+        let mut config = test_config();
+        config.tokens[0].token.clear();
+        assert!(Authorizer::new(&config).is_err());
+
+        // Unknown RPC in the permissions.
+        let mut config = test_config();
+        config.permissions.insert("strat".to_string(), Role::Admin);
+        assert!(Authorizer::new(&config).is_err());
+
+        // Unknown role in the permissions.
+        assert!(toml::from_str::<AuthConfig>("[permissions]\nstart = \"root\"\n").is_err());
+        assert_eq!(
+            Some(&Role::Admin),
+            toml::from_str::<AuthConfig>("[permissions]\nstart = \"admin\"\n")
+                .unwrap()
+                .permissions
+                .get("start")
+        );
+    }
+}
@@ -116,6 +116,17 @@ impl CommonConverters {
         })
     }
 
+    pub fn parse_function_metric_kind(api_kind: i32) -> anyhow::Result<crate::node_registration::FunctionMetricKind> {
+        match api_kind {
+            x if x == crate::grpc_impl::api::FunctionMetricKind::MetricCounter as i32 => Ok(crate::node_registration::FunctionMetricKind::Counter),
+            x if x == crate::grpc_impl::api::FunctionMetricKind::MetricGauge as i32 => Ok(crate::node_registration::FunctionMetricKind::Gauge),
+            x if x == crate::grpc_impl::api::FunctionMetricKind::MetricHistogram as i32 => {
+                Ok(crate::node_registration::FunctionMetricKind::Histogram)
+            }
+            x => Err(anyhow::anyhow!("invalid function metric kind: {}", x)),
+        }
+    }
+
     pub fn serialize_response_error(crate_function: &crate::common::ResponseError) -> crate::grpc_impl::api::ResponseError {
         crate::grpc_impl::api::ResponseError {
             summary: crate_function.summary.clone(),
@@ -130,6 +141,14 @@ impl CommonConverters {
         }
     }
 
+    pub fn serialize_function_metric_kind(kind: &crate::node_registration::FunctionMetricKind) -> i32 {
+        match kind {
+            crate::node_registration::FunctionMetricKind::Counter => crate::grpc_impl::api::FunctionMetricKind::MetricCounter as i32,
+            crate::node_registration::FunctionMetricKind::Gauge => crate::grpc_impl::api::FunctionMetricKind::MetricGauge as i32,
+            crate::node_registration::FunctionMetricKind::Histogram => crate::grpc_impl::api::FunctionMetricKind::MetricHistogram as i32,
+        }
+    }
+
     pub fn serialize_event_timestamp(ts: &crate::function_instance::EventTimestamp) -> crate::grpc_impl::api::EventTimestamp {
         crate::grpc_impl::api::EventTimestamp {
             secs: ts.secs,
@@ -156,9 +156,33 @@ fn parse_update_domain_request(
         refresh_deadline: std::time::UNIX_EPOCH + std::time::Duration::from_secs(api_instance.refresh_deadline),
         counter: api_instance.counter,
         nonce: api_instance.nonce,
+        component_health: api_instance
+            .component_health
+            .iter()
+            .map(parse_component_health)
+            .collect::<anyhow::Result<Vec<_>>>()?,
     })
 }
 
+pub fn parse_component_health(api_instance: &crate::grpc_impl::api::ComponentHealth) -> anyhow::Result<crate::function_lifecycle::ComponentHealth> {
+    Ok(crate::function_lifecycle::ComponentHealth {
+        component_id: uuid::Uuid::parse_str(&api_instance.component_id)
+            .map_err(|_| anyhow::anyhow!("Ill-formed ComponentHealth: invalid UUID as component_id"))?,
+        restarts: api_instance.restarts,
+        ready: api_instance.ready,
+        live: api_instance.live,
+    })
+}
+
+pub fn serialize_component_health(req: &crate::function_lifecycle::ComponentHealth) -> crate::grpc_impl::api::ComponentHealth {
+    crate::grpc_impl::api::ComponentHealth {
+        component_id: req.component_id.to_string(),
+        restarts: req.restarts,
+        ready: req.ready,
+        live: req.live,
+    }
+}
+
 fn serialize_update_domain_response(req: &crate::domain_registration::UpdateDomainResponse) -> crate::grpc_impl::api::UpdateDomainResponse {
     match req {
         crate::domain_registration::UpdateDomainResponse::ResponseError(err) => crate::grpc_impl::api::UpdateDomainResponse {
@@ -204,6 +228,7 @@ fn serialize_update_domain_request(req: &crate::domain_registration::UpdateDomai
         refresh_deadline: req.refresh_deadline.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs(),
         counter: req.counter,
         nonce: req.nonce,
+        component_health: req.component_health.iter().map(serialize_component_health).collect(),
     }
 }
 
@@ -212,6 +237,7 @@ mod test {
     use crate::domain_registration::DomainCapabilities;
     use crate::domain_registration::UpdateDomainRequest;
     use crate::domain_registration::UpdateDomainResponse;
+    use crate::function_lifecycle::ComponentHealth;
     use crate::grpc_impl::inner::domain_registration::parse_update_domain_request;
     use crate::grpc_impl::inner::domain_registration::parse_update_domain_response;
     use crate::grpc_impl::inner::domain_registration::serialize_update_domain_request;
@@ -227,6 +253,7 @@ mod test {
                 refresh_deadline: std::time::UNIX_EPOCH + std::time::Duration::from_secs(313714800),
                 counter: 1,
                 nonce: 2,
+                component_health: vec![],
             },
             UpdateDomainRequest {
                 domain_id: "my-domain".to_string(),
@@ -249,6 +276,20 @@ mod test {
                 refresh_deadline: std::time::UNIX_EPOCH + std::time::Duration::from_secs(313714800),
                 counter: 42,
                 nonce: u64::MAX,
+                component_health: vec![
+                    ComponentHealth {
+                        component_id: uuid::Uuid::new_v4(),
+                        restarts: 3,
+                        ready: Some(true),
+                        live: Some(false),
+                    },
+                    ComponentHealth {
+                        component_id: uuid::Uuid::new_v4(),
+                        restarts: 0,
+                        ready: None,
+                        live: None,
+                    },
+                ],
             },
         ];
         for msg in messages {
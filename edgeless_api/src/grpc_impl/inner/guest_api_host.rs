@@ -70,6 +70,16 @@ impl crate::guest_api_host::GuestAPIHost for GuestAPIHostClient {
             Err(err) => Err(anyhow::anyhow!("Communication error while emitting a telemetry log event: {}", err)),
         }
     }
+    async fn telemetry_metric(&mut self, event: crate::guest_api_host::TelemetryMetricEvent) -> anyhow::Result<()> {
+        match self
+            .client
+            .telemetry_metric(tonic::Request::new(serialize_telemetry_metric_event(&event)))
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => Err(anyhow::anyhow!("Communication error while emitting a telemetry metric event: {}", err)),
+        }
+    }
     async fn slf(&mut self) -> anyhow::Result<edgeless_api_core::instance_id::InstanceId> {
         match self.client.slf(tonic::Request::new(())).await {
             Ok(msg) => crate::grpc_impl::common::CommonConverters::parse_instance_id(&msg.into_inner()),
@@ -178,6 +188,25 @@ impl crate::grpc_impl::api::guest_api_host_server::GuestApiHost for GuestAPIHost
         }
     }
 
+    async fn telemetry_metric(
+        &self,
+        event: tonic::Request<crate::grpc_impl::api::TelemetryMetricEvent>,
+    ) -> Result<tonic::Response<()>, tonic::Status> {
+        let parsed_request = match parse_telemetry_metric_event(&event.into_inner()) {
+            Ok(parsed_request) => parsed_request,
+            Err(err) => {
+                return Err(tonic::Status::invalid_argument(format!(
+                    "Error when parsing a TelemetryMetricEvent message: {}",
+                    err
+                )));
+            }
+        };
+        match self.guest_api_host.lock().await.telemetry_metric(parsed_request).await {
+            Ok(_) => Ok(tonic::Response::new(())),
+            Err(err) => Err(tonic::Status::internal(format!("Error when emitting a telemetry metric event: {}", err))),
+        }
+    }
+
     async fn slf(&self, _request: tonic::Request<()>) -> Result<tonic::Response<crate::grpc_impl::api::InstanceId>, tonic::Status> {
         match self.guest_api_host.lock().await.slf().await {
             Ok(msg) => Ok(tonic::Response::new(crate::grpc_impl::common::CommonConverters::serialize_instance_id(
@@ -272,6 +301,25 @@ fn parse_telemetry_log_event(api_instance: &crate::grpc_impl::api::TelemetryLogE
         },
         target: api_instance.target.clone(),
         msg: api_instance.msg.clone(),
+        fields: api_instance.fields.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+    })
+}
+
+fn parse_telemetry_metric_event(
+    api_instance: &crate::grpc_impl::api::TelemetryMetricEvent,
+) -> anyhow::Result<crate::guest_api_host::TelemetryMetricEvent> {
+    Ok(crate::guest_api_host::TelemetryMetricEvent {
+        originator: match &api_instance.originator {
+            Some(instance_id) => match crate::grpc_impl::common::CommonConverters::parse_instance_id(instance_id) {
+                Ok(originator) => originator,
+                Err(err) => return Err(anyhow::anyhow!("invalid originator field: {}", err)),
+            },
+            None => return Err(anyhow::anyhow!("missing originator field")),
+        },
+        kind: crate::grpc_impl::common::CommonConverters::parse_function_metric_kind(api_instance.kind)?,
+        name: api_instance.name.clone(),
+        labels: api_instance.labels.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+        value: api_instance.value,
     })
 }
 
@@ -335,6 +383,17 @@ fn serialize_telemetry_log_event(event: &crate::guest_api_host::TelemetryLogEven
         },
         msg: event.msg.clone(),
         target: event.target.clone(),
+        fields: event.fields.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+    }
+}
+
+fn serialize_telemetry_metric_event(event: &crate::guest_api_host::TelemetryMetricEvent) -> crate::grpc_impl::api::TelemetryMetricEvent {
+    crate::grpc_impl::api::TelemetryMetricEvent {
+        originator: Some(crate::grpc_impl::common::CommonConverters::serialize_instance_id(&event.originator)),
+        kind: crate::grpc_impl::common::CommonConverters::serialize_function_metric_kind(&event.kind),
+        name: event.name.clone(),
+        labels: event.labels.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+        value: event.value,
     }
 }
 
@@ -364,6 +423,7 @@ mod test {
     use crate::guest_api_host::SyncData;
     use crate::guest_api_host::TelemetryLogEvent;
     use crate::guest_api_host::TelemetryLogLevel;
+    use crate::guest_api_host::TelemetryMetricEvent;
     use edgeless_api_core::instance_id::InstanceId;
 
     #[test]
@@ -425,12 +485,14 @@ mod test {
                 log_level: log_level.clone(),
                 msg: "".to_string(),
                 target: "".to_string(),
+                fields: std::collections::BTreeMap::new(),
             });
             messages.push(TelemetryLogEvent {
                 originator: edgeless_api_core::instance_id::InstanceId::new(uuid::Uuid::new_v4()),
                 log_level,
                 msg: "my-event".to_string(),
                 target: "my-target".to_string(),
+                fields: std::collections::BTreeMap::from([("request_id".to_string(), "42".to_string()), ("camera".to_string(), "front".to_string())]),
             });
         }
         for msg in messages {
@@ -441,6 +503,37 @@ mod test {
         }
     }
 
+    #[test]
+    fn serialize_deserialize_telemetry_metric_event() {
+        let mut messages = vec![];
+        for kind in [
+            crate::node_registration::FunctionMetricKind::Counter,
+            crate::node_registration::FunctionMetricKind::Gauge,
+            crate::node_registration::FunctionMetricKind::Histogram,
+        ] {
+            messages.push(TelemetryMetricEvent {
+                originator: edgeless_api_core::instance_id::InstanceId::new(uuid::Uuid::new_v4()),
+                kind,
+                name: "".to_string(),
+                labels: std::collections::BTreeMap::new(),
+                value: 0.0,
+            });
+            messages.push(TelemetryMetricEvent {
+                originator: edgeless_api_core::instance_id::InstanceId::new(uuid::Uuid::new_v4()),
+                kind,
+                name: "my-metric".to_string(),
+                labels: std::collections::BTreeMap::from([("key1".to_string(), "value1".to_string()), ("key2".to_string(), "".to_string())]),
+                value: -42.5,
+            });
+        }
+        for msg in messages {
+            match parse_telemetry_metric_event(&serialize_telemetry_metric_event(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
     #[test]
     fn serialize_deserialize_delayed_event_data() {
         let messages = vec![
@@ -0,0 +1,787 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use crate::grpc_impl::common::CommonConverters;
+
+type InterceptedChannel = tonic::service::interceptor::InterceptedService<tonic::transport::Channel, crate::grpc_impl::auth::BearerTokenInterceptor>;
+
+#[derive(Clone)]
+pub struct IntrospectionAPIClient {
+    client: Option<crate::grpc_impl::api::introspection_client::IntrospectionClient<InterceptedChannel>>,
+    server_addr: String,
+    tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>,
+    interceptor: crate::grpc_impl::auth::BearerTokenInterceptor,
+}
+
+impl IntrospectionAPIClient {
+    pub fn new(
+        server_addr: String,
+        tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>,
+        interceptor: crate::grpc_impl::auth::BearerTokenInterceptor,
+    ) -> Self {
+        Self {
+            client: None,
+            server_addr,
+            tls_config,
+            interceptor,
+        }
+    }
+
+    /// Try connecting, if not already connected.
+    ///
+    /// If an error is returned, then the client is set to None (disconnected).
+    /// Otherwise, the client is set to some value (connected).
+    async fn try_connect(&mut self) -> anyhow::Result<()> {
+        if self.client.is_none() {
+            let tls_config = if let Some(config) = &self.tls_config {
+                config.clone()
+            } else {
+                crate::grpc_impl::tls_config::TlsConfig::global_client().clone()
+            };
+
+            let channel = tls_config.create_client_channel(&self.server_addr).await?;
+
+            self.client = Some(
+                crate::grpc_impl::api::introspection_client::IntrospectionClient::with_interceptor(channel, self.interceptor.clone())
+                    .max_decoding_message_size(usize::MAX),
+            );
+        }
+        Ok(())
+    }
+
+    /// Disconnect the client.
+    fn disconnect(&mut self) {
+        self.client = None;
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::introspection::IntrospectionAPI for IntrospectionAPIClient {
+    async fn snapshot(&mut self) -> anyhow::Result<crate::introspection::DomainSnapshot> {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client.snapshot(tonic::Request::new(())).await {
+                        Ok(res) => parse_domain_snapshot(&res.into_inner()),
+                        Err(err) => {
+                            self.disconnect();
+                            Err(anyhow::anyhow!("Error when retrieving a snapshot at {}: {}", self.server_addr, err))
+                        }
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
+
+    async fn add_deploy_intents(&mut self, intents: Vec<crate::introspection::DeployIntent>) -> anyhow::Result<()> {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client.add_deploy_intents(tonic::Request::new(serialize_deploy_intents(&intents))).await {
+                        Err(err) => {
+                            self.disconnect();
+                            anyhow::bail!("Error when adding deploy intents at {}: {}", self.server_addr, err);
+                        }
+                        _ => Ok(()),
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
+
+    async fn function_logs(
+        &mut self,
+        request: crate::introspection::FunctionLogsRequest,
+    ) -> anyhow::Result<Vec<crate::introspection::FunctionLogRecord>> {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client.function_logs(tonic::Request::new(serialize_function_logs_request(&request))).await {
+                        Ok(res) => parse_function_log_records(&res.into_inner()),
+                        Err(err) => {
+                            self.disconnect();
+                            Err(anyhow::anyhow!("Error when retrieving function logs at {}: {}", self.server_addr, err))
+                        }
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
+
+    async fn query_metrics(&mut self, query: crate::introspection::MetricsQuery) -> anyhow::Result<Vec<crate::introspection::MetricsSeries>> {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client.query_metrics(tonic::Request::new(serialize_metrics_query(&query))).await {
+                        Ok(res) => parse_metrics_series_list(&res.into_inner()),
+                        Err(err) => {
+                            self.disconnect();
+                            Err(anyhow::anyhow!("Error when querying metrics at {}: {}", self.server_addr, err))
+                        }
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
+}
+
+pub struct IntrospectionAPIServer {
+    pub root_api: tokio::sync::Mutex<Box<dyn crate::introspection::IntrospectionAPI>>,
+    /// If set, all the requests must be authorized.
+    pub authorizer: Option<std::sync::Arc<crate::grpc_impl::auth::Authorizer>>,
+}
+
+impl IntrospectionAPIServer {
+    #[allow(clippy::result_large_err)]
+    fn authorize<T>(&self, request: &tonic::Request<T>, rpc: &str) -> Result<(), tonic::Status> {
+        match &self.authorizer {
+            Some(authorizer) => authorizer.authorize(request, rpc),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::grpc_impl::api::introspection_server::Introspection for IntrospectionAPIServer {
+    async fn snapshot(&self, request: tonic::Request<()>) -> Result<tonic::Response<crate::grpc_impl::api::DomainSnapshot>, tonic::Status> {
+        self.authorize(&request, "snapshot")?;
+        match self.root_api.lock().await.snapshot().await {
+            Ok(snapshot) => Ok(tonic::Response::new(serialize_domain_snapshot(&snapshot))),
+            Err(err) => Err(tonic::Status::internal(format!("Error when retrieving a snapshot: {}", err))),
+        }
+    }
+
+    async fn add_deploy_intents(&self, request: tonic::Request<crate::grpc_impl::api::DeployIntents>) -> Result<tonic::Response<()>, tonic::Status> {
+        self.authorize(&request, "add_deploy_intents")?;
+        let intents = match parse_deploy_intents(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => return Err(tonic::Status::invalid_argument(format!("Error when parsing deploy intents: {}", err))),
+        };
+        match self.root_api.lock().await.add_deploy_intents(intents).await {
+            Ok(_) => Ok(tonic::Response::new(())),
+            Err(err) => Err(tonic::Status::internal(format!("Error when adding deploy intents: {}", err))),
+        }
+    }
+
+    async fn function_logs(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::FunctionLogsRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::FunctionLogRecords>, tonic::Status> {
+        self.authorize(&request, "function_logs")?;
+        let request = match parse_function_logs_request(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => return Err(tonic::Status::invalid_argument(format!("Error when parsing a log request: {}", err))),
+        };
+        match self.root_api.lock().await.function_logs(request).await {
+            Ok(records) => Ok(tonic::Response::new(serialize_function_log_records(&records))),
+            Err(err) => Err(tonic::Status::internal(format!("Error when retrieving function logs: {}", err))),
+        }
+    }
+
+    async fn query_metrics(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::MetricsQuery>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::MetricsSeriesList>, tonic::Status> {
+        self.authorize(&request, "query_metrics")?;
+        let query = match parse_metrics_query(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => return Err(tonic::Status::invalid_argument(format!("Error when parsing a metrics query: {}", err))),
+        };
+        match self.root_api.lock().await.query_metrics(query).await {
+            Ok(series) => Ok(tonic::Response::new(serialize_metrics_series_list(&series))),
+            Err(err) => Err(tonic::Status::internal(format!("Error when querying metrics: {}", err))),
+        }
+    }
+}
+
+fn parse_node_info(api_node: &crate::grpc_impl::api::IntrospectionNode) -> anyhow::Result<crate::introspection::NodeInfo> {
+    Ok(crate::introspection::NodeInfo {
+        node_id: uuid::Uuid::parse_str(&api_node.node_id)?,
+        agent_url: api_node.agent_url.clone(),
+        invocation_url: api_node.invocation_url.clone(),
+        capabilities: match &api_node.capabilities {
+            Some(capabilities) => super::node_registration::parse_node_capabilities(capabilities),
+            None => anyhow::bail!("Node {} does not contain capabilities", api_node.node_id),
+        },
+        cordoned: api_node.cordoned,
+        health: api_node.health.as_ref().map(super::node_registration::parse_node_health_status),
+        drain: match &api_node.drain {
+            Some(drain) => Some(parse_node_drain_status(drain)?),
+            None => None,
+        },
+    })
+}
+
+fn serialize_node_info(crate_node: &crate::introspection::NodeInfo) -> crate::grpc_impl::api::IntrospectionNode {
+    crate::grpc_impl::api::IntrospectionNode {
+        node_id: crate_node.node_id.to_string(),
+        agent_url: crate_node.agent_url.clone(),
+        invocation_url: crate_node.invocation_url.clone(),
+        capabilities: Some(super::node_registration::serialize_node_capabilities(&crate_node.capabilities)),
+        cordoned: crate_node.cordoned,
+        health: crate_node.health.as_ref().map(super::node_registration::serialize_node_health_status),
+        drain: crate_node.drain.as_ref().map(serialize_node_drain_status),
+    }
+}
+
+fn parse_node_drain_status(api_drain: &crate::grpc_impl::api::IntrospectionNodeDrain) -> anyhow::Result<crate::introspection::NodeDrainStatus> {
+    Ok(crate::introspection::NodeDrainStatus {
+        migrated: api_drain.migrated,
+        remaining: api_drain
+            .remaining
+            .iter()
+            .map(|x| uuid::Uuid::parse_str(x))
+            .collect::<Result<Vec<uuid::Uuid>, uuid::Error>>()?,
+        safe_to_remove: api_drain.safe_to_remove,
+    })
+}
+
+fn serialize_node_drain_status(crate_drain: &crate::introspection::NodeDrainStatus) -> crate::grpc_impl::api::IntrospectionNodeDrain {
+    crate::grpc_impl::api::IntrospectionNodeDrain {
+        migrated: crate_drain.migrated,
+        remaining: crate_drain.remaining.iter().map(|x| x.to_string()).collect(),
+        safe_to_remove: crate_drain.safe_to_remove,
+    }
+}
+
+fn parse_resource_provider_info(
+    api_provider: &crate::grpc_impl::api::IntrospectionResourceProvider,
+) -> anyhow::Result<crate::introspection::ResourceProviderInfo> {
+    Ok(crate::introspection::ResourceProviderInfo {
+        provider_id: api_provider.provider_id.clone(),
+        class_type: api_provider.class_type.clone(),
+        node_id: uuid::Uuid::parse_str(&api_provider.node_id)?,
+        outputs: api_provider.outputs.clone(),
+    })
+}
+
+fn serialize_resource_provider_info(
+    crate_provider: &crate::introspection::ResourceProviderInfo,
+) -> crate::grpc_impl::api::IntrospectionResourceProvider {
+    crate::grpc_impl::api::IntrospectionResourceProvider {
+        provider_id: crate_provider.provider_id.clone(),
+        class_type: crate_provider.class_type.clone(),
+        node_id: crate_provider.node_id.to_string(),
+        outputs: crate_provider.outputs.clone(),
+    }
+}
+
+fn parse_active_instance_info(
+    api_instance: &crate::grpc_impl::api::IntrospectionInstance,
+) -> anyhow::Result<crate::introspection::ActiveInstanceInfo> {
+    Ok(crate::introspection::ActiveInstanceInfo {
+        component_id: uuid::Uuid::parse_str(&api_instance.component_id)?,
+        spec: match &api_instance.spec {
+            Some(crate::grpc_impl::api::introspection_instance::Spec::Function(spawn_req)) => {
+                crate::introspection::ActiveInstanceSpec::Function(super::function_instance::parse_spawn_function_request(spawn_req)?)
+            }
+            Some(crate::grpc_impl::api::introspection_instance::Spec::Resource(resource_spec)) => crate::introspection::ActiveInstanceSpec::Resource(
+                super::resource_configuration::parse_resource_instance_specification(resource_spec)?,
+            ),
+            None => anyhow::bail!("Instance {} does not contain a specification", api_instance.component_id),
+        },
+        instances: api_instance
+            .instances
+            .iter()
+            .map(CommonConverters::parse_instance_id)
+            .collect::<anyhow::Result<Vec<crate::function_instance::InstanceId>>>()?,
+        output_mapping: api_instance
+            .output_mapping
+            .iter()
+            .map(|(output, target)| Ok((output.clone(), uuid::Uuid::parse_str(target)?)))
+            .collect::<anyhow::Result<std::collections::HashMap<String, crate::function_instance::ComponentId>>>()?,
+    })
+}
+
+fn serialize_active_instance_info(crate_instance: &crate::introspection::ActiveInstanceInfo) -> crate::grpc_impl::api::IntrospectionInstance {
+    crate::grpc_impl::api::IntrospectionInstance {
+        component_id: crate_instance.component_id.to_string(),
+        spec: Some(match &crate_instance.spec {
+            crate::introspection::ActiveInstanceSpec::Function(spawn_req) => {
+                crate::grpc_impl::api::introspection_instance::Spec::Function(super::function_instance::serialize_spawn_function_request(spawn_req))
+            }
+            crate::introspection::ActiveInstanceSpec::Resource(resource_spec) => crate::grpc_impl::api::introspection_instance::Spec::Resource(
+                super::resource_configuration::serialize_resource_instance_specification(resource_spec),
+            ),
+        }),
+        instances: crate_instance.instances.iter().map(CommonConverters::serialize_instance_id).collect(),
+        output_mapping: crate_instance
+            .output_mapping
+            .iter()
+            .map(|(output, target)| (output.clone(), target.to_string()))
+            .collect(),
+    }
+}
+
+pub fn parse_domain_snapshot(api_snapshot: &crate::grpc_impl::api::DomainSnapshot) -> anyhow::Result<crate::introspection::DomainSnapshot> {
+    Ok(crate::introspection::DomainSnapshot {
+        nodes: api_snapshot
+            .nodes
+            .iter()
+            .map(parse_node_info)
+            .collect::<anyhow::Result<Vec<crate::introspection::NodeInfo>>>()?,
+        resource_providers: api_snapshot
+            .resource_providers
+            .iter()
+            .map(parse_resource_provider_info)
+            .collect::<anyhow::Result<Vec<crate::introspection::ResourceProviderInfo>>>()?,
+        instances: api_snapshot
+            .instances
+            .iter()
+            .map(parse_active_instance_info)
+            .collect::<anyhow::Result<Vec<crate::introspection::ActiveInstanceInfo>>>()?,
+    })
+}
+
+pub fn serialize_domain_snapshot(crate_snapshot: &crate::introspection::DomainSnapshot) -> crate::grpc_impl::api::DomainSnapshot {
+    crate::grpc_impl::api::DomainSnapshot {
+        nodes: crate_snapshot.nodes.iter().map(serialize_node_info).collect(),
+        resource_providers: crate_snapshot.resource_providers.iter().map(serialize_resource_provider_info).collect(),
+        instances: crate_snapshot.instances.iter().map(serialize_active_instance_info).collect(),
+    }
+}
+
+fn parse_deploy_intent(api_intent: &crate::grpc_impl::api::DeployIntent) -> anyhow::Result<crate::introspection::DeployIntent> {
+    match &api_intent.deploy_intent_type {
+        Some(crate::grpc_impl::api::deploy_intent::DeployIntentType::Migrate(migrate)) => Ok(crate::introspection::DeployIntent::Migrate(
+            uuid::Uuid::parse_str(&migrate.component_id)?,
+            migrate
+                .targets
+                .iter()
+                .map(|x| uuid::Uuid::parse_str(x))
+                .collect::<Result<Vec<uuid::Uuid>, uuid::Error>>()?,
+        )),
+        Some(crate::grpc_impl::api::deploy_intent::DeployIntentType::Cordon(node_id)) => {
+            Ok(crate::introspection::DeployIntent::Cordon(uuid::Uuid::parse_str(node_id)?))
+        }
+        Some(crate::grpc_impl::api::deploy_intent::DeployIntentType::Uncordon(node_id)) => {
+            Ok(crate::introspection::DeployIntent::Uncordon(uuid::Uuid::parse_str(node_id)?))
+        }
+        Some(crate::grpc_impl::api::deploy_intent::DeployIntentType::Drain(node_id)) => {
+            Ok(crate::introspection::DeployIntent::Drain(uuid::Uuid::parse_str(node_id)?))
+        }
+        None => anyhow::bail!("Deploy intent without type"),
+    }
+}
+
+fn serialize_deploy_intent(crate_intent: &crate::introspection::DeployIntent) -> crate::grpc_impl::api::DeployIntent {
+    crate::grpc_impl::api::DeployIntent {
+        deploy_intent_type: Some(match crate_intent {
+            crate::introspection::DeployIntent::Migrate(component_id, targets) => {
+                crate::grpc_impl::api::deploy_intent::DeployIntentType::Migrate(crate::grpc_impl::api::MigrateIntent {
+                    component_id: component_id.to_string(),
+                    targets: targets.iter().map(|x| x.to_string()).collect(),
+                })
+            }
+            crate::introspection::DeployIntent::Cordon(node_id) => {
+                crate::grpc_impl::api::deploy_intent::DeployIntentType::Cordon(node_id.to_string())
+            }
+            crate::introspection::DeployIntent::Uncordon(node_id) => {
+                crate::grpc_impl::api::deploy_intent::DeployIntentType::Uncordon(node_id.to_string())
+            }
+            crate::introspection::DeployIntent::Drain(node_id) => crate::grpc_impl::api::deploy_intent::DeployIntentType::Drain(node_id.to_string()),
+        }),
+    }
+}
+
+pub fn parse_deploy_intents(api_intents: &crate::grpc_impl::api::DeployIntents) -> anyhow::Result<Vec<crate::introspection::DeployIntent>> {
+    api_intents.intents.iter().map(parse_deploy_intent).collect()
+}
+
+pub fn serialize_deploy_intents(crate_intents: &[crate::introspection::DeployIntent]) -> crate::grpc_impl::api::DeployIntents {
+    crate::grpc_impl::api::DeployIntents {
+        intents: crate_intents.iter().map(serialize_deploy_intent).collect(),
+    }
+}
+
+pub fn parse_function_logs_request(
+    api_request: &crate::grpc_impl::api::FunctionLogsRequest,
+) -> anyhow::Result<crate::introspection::FunctionLogsRequest> {
+    Ok(crate::introspection::FunctionLogsRequest {
+        component_ids: api_request
+            .component_ids
+            .iter()
+            .map(|x| uuid::Uuid::parse_str(x))
+            .collect::<Result<Vec<uuid::Uuid>, uuid::Error>>()?,
+        since_sequence: api_request.since_sequence,
+    })
+}
+
+pub fn serialize_function_logs_request(crate_request: &crate::introspection::FunctionLogsRequest) -> crate::grpc_impl::api::FunctionLogsRequest {
+    crate::grpc_impl::api::FunctionLogsRequest {
+        component_ids: crate_request.component_ids.iter().map(|x| x.to_string()).collect(),
+        since_sequence: crate_request.since_sequence,
+    }
+}
+
+fn parse_function_log_record(api_record: &crate::grpc_impl::api::FunctionLogRecord) -> anyhow::Result<crate::introspection::FunctionLogRecord> {
+    Ok(crate::introspection::FunctionLogRecord {
+        component_id: uuid::Uuid::parse_str(&api_record.component_id)?,
+        node_id: uuid::Uuid::parse_str(&api_record.node_id)?,
+        sequence: api_record.sequence,
+        entry: match &api_record.entry {
+            Some(entry) => super::node_registration::parse_function_log_entry(entry),
+            None => anyhow::bail!("Log record of {} does not contain an entry", api_record.component_id),
+        },
+    })
+}
+
+fn serialize_function_log_record(crate_record: &crate::introspection::FunctionLogRecord) -> crate::grpc_impl::api::FunctionLogRecord {
+    crate::grpc_impl::api::FunctionLogRecord {
+        component_id: crate_record.component_id.to_string(),
+        node_id: crate_record.node_id.to_string(),
+        entry: Some(super::node_registration::serialize_function_log_entry(&crate_record.entry)),
+        sequence: crate_record.sequence,
+    }
+}
+
+pub fn parse_function_log_records(
+    api_records: &crate::grpc_impl::api::FunctionLogRecords,
+) -> anyhow::Result<Vec<crate::introspection::FunctionLogRecord>> {
+    api_records.records.iter().map(parse_function_log_record).collect()
+}
+
+pub fn serialize_function_log_records(crate_records: &[crate::introspection::FunctionLogRecord]) -> crate::grpc_impl::api::FunctionLogRecords {
+    crate::grpc_impl::api::FunctionLogRecords {
+        records: crate_records.iter().map(serialize_function_log_record).collect(),
+    }
+}
+
+fn parse_metrics_aggregate(api_aggregate: i32) -> anyhow::Result<crate::introspection::MetricsAggregate> {
+    match api_aggregate {
+        x if x == crate::grpc_impl::api::MetricsAggregate::AggregateAvg as i32 => Ok(crate::introspection::MetricsAggregate::Avg),
+        x if x == crate::grpc_impl::api::MetricsAggregate::AggregateMin as i32 => Ok(crate::introspection::MetricsAggregate::Min),
+        x if x == crate::grpc_impl::api::MetricsAggregate::AggregateMax as i32 => Ok(crate::introspection::MetricsAggregate::Max),
+        x if x == crate::grpc_impl::api::MetricsAggregate::AggregateSum as i32 => Ok(crate::introspection::MetricsAggregate::Sum),
+        x if x == crate::grpc_impl::api::MetricsAggregate::AggregateCount as i32 => Ok(crate::introspection::MetricsAggregate::Count),
+        x => anyhow::bail!("Invalid metrics aggregate: {}", x),
+    }
+}
+
+fn serialize_metrics_aggregate(crate_aggregate: &crate::introspection::MetricsAggregate) -> crate::grpc_impl::api::MetricsAggregate {
+    match crate_aggregate {
+        crate::introspection::MetricsAggregate::Avg => crate::grpc_impl::api::MetricsAggregate::AggregateAvg,
+        crate::introspection::MetricsAggregate::Min => crate::grpc_impl::api::MetricsAggregate::AggregateMin,
+        crate::introspection::MetricsAggregate::Max => crate::grpc_impl::api::MetricsAggregate::AggregateMax,
+        crate::introspection::MetricsAggregate::Sum => crate::grpc_impl::api::MetricsAggregate::AggregateSum,
+        crate::introspection::MetricsAggregate::Count => crate::grpc_impl::api::MetricsAggregate::AggregateCount,
+    }
+}
+
+fn parse_metrics_group_by(api_group_by: i32) -> anyhow::Result<crate::introspection::MetricsGroupBy> {
+    match api_group_by {
+        x if x == crate::grpc_impl::api::MetricsGroupBy::GroupByNone as i32 => Ok(crate::introspection::MetricsGroupBy::None),
+        x if x == crate::grpc_impl::api::MetricsGroupBy::GroupByNode as i32 => Ok(crate::introspection::MetricsGroupBy::Node),
+        x if x == crate::grpc_impl::api::MetricsGroupBy::GroupByWorkflow as i32 => Ok(crate::introspection::MetricsGroupBy::Workflow),
+        x if x == crate::grpc_impl::api::MetricsGroupBy::GroupByComponent as i32 => Ok(crate::introspection::MetricsGroupBy::Component),
+        x => anyhow::bail!("Invalid metrics grouping: {}", x),
+    }
+}
+
+fn serialize_metrics_group_by(crate_group_by: &crate::introspection::MetricsGroupBy) -> crate::grpc_impl::api::MetricsGroupBy {
+    match crate_group_by {
+        crate::introspection::MetricsGroupBy::None => crate::grpc_impl::api::MetricsGroupBy::GroupByNone,
+        crate::introspection::MetricsGroupBy::Node => crate::grpc_impl::api::MetricsGroupBy::GroupByNode,
+        crate::introspection::MetricsGroupBy::Workflow => crate::grpc_impl::api::MetricsGroupBy::GroupByWorkflow,
+        crate::introspection::MetricsGroupBy::Component => crate::grpc_impl::api::MetricsGroupBy::GroupByComponent,
+    }
+}
+
+pub fn parse_metrics_query(api_query: &crate::grpc_impl::api::MetricsQuery) -> anyhow::Result<crate::introspection::MetricsQuery> {
+    Ok(crate::introspection::MetricsQuery {
+        metric: api_query.metric.clone(),
+        from_sec: api_query.from_sec,
+        to_sec: api_query.to_sec,
+        step_sec: api_query.step_sec,
+        aggregate: parse_metrics_aggregate(api_query.aggregate)?,
+        group_by: parse_metrics_group_by(api_query.group_by)?,
+        node_ids: api_query
+            .node_ids
+            .iter()
+            .map(|x| uuid::Uuid::parse_str(x))
+            .collect::<Result<Vec<uuid::Uuid>, uuid::Error>>()?,
+        workflow_ids: api_query.workflow_ids.clone(),
+    })
+}
+
+pub fn serialize_metrics_query(crate_query: &crate::introspection::MetricsQuery) -> crate::grpc_impl::api::MetricsQuery {
+    crate::grpc_impl::api::MetricsQuery {
+        metric: crate_query.metric.clone(),
+        from_sec: crate_query.from_sec,
+        to_sec: crate_query.to_sec,
+        step_sec: crate_query.step_sec,
+        aggregate: serialize_metrics_aggregate(&crate_query.aggregate) as i32,
+        group_by: serialize_metrics_group_by(&crate_query.group_by) as i32,
+        node_ids: crate_query.node_ids.iter().map(|x| x.to_string()).collect(),
+        workflow_ids: crate_query.workflow_ids.clone(),
+    }
+}
+
+pub fn parse_metrics_series_list(api_series: &crate::grpc_impl::api::MetricsSeriesList) -> anyhow::Result<Vec<crate::introspection::MetricsSeries>> {
+    Ok(api_series
+        .series
+        .iter()
+        .map(|series| crate::introspection::MetricsSeries {
+            group: series.group.clone(),
+            points: series
+                .points
+                .iter()
+                .map(|point| crate::introspection::MetricsPoint {
+                    timestamp_sec: point.timestamp_sec,
+                    value: point.value,
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+pub fn serialize_metrics_series_list(crate_series: &[crate::introspection::MetricsSeries]) -> crate::grpc_impl::api::MetricsSeriesList {
+    crate::grpc_impl::api::MetricsSeriesList {
+        series: crate_series
+            .iter()
+            .map(|series| crate::grpc_impl::api::MetricsSeries {
+                group: series.group.clone(),
+                points: series
+                    .points
+                    .iter()
+                    .map(|point| crate::grpc_impl::api::MetricsPoint {
+                        timestamp_sec: point.timestamp_sec,
+                        value: point.value,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::introspection::ActiveInstanceInfo;
+    use crate::introspection::ActiveInstanceSpec;
+    use crate::introspection::DeployIntent;
+    use crate::introspection::DomainSnapshot;
+    use crate::introspection::FunctionLogRecord;
+    use crate::introspection::FunctionLogsRequest;
+    use crate::introspection::MetricsAggregate;
+    use crate::introspection::MetricsGroupBy;
+    use crate::introspection::MetricsPoint;
+    use crate::introspection::MetricsQuery;
+    use crate::introspection::MetricsSeries;
+    use crate::introspection::NodeInfo;
+    use crate::introspection::ResourceProviderInfo;
+
+    #[test]
+    fn serialize_deserialize_domain_snapshot() {
+        let node_id = uuid::Uuid::new_v4();
+        let messages = vec![
+            DomainSnapshot::default(),
+            DomainSnapshot {
+                nodes: vec![
+                    NodeInfo {
+                        node_id,
+                        agent_url: "http://127.0.0.1:7121".to_string(),
+                        invocation_url: "http://127.0.0.1:7102".to_string(),
+                        capabilities: crate::node_registration::NodeCapabilities::minimum(),
+                        cordoned: false,
+                        health: Some(crate::node_registration::NodeHealthStatus::default()),
+                        drain: None,
+                    },
+                    NodeInfo {
+                        node_id: uuid::Uuid::new_v4(),
+                        agent_url: "http://127.0.0.1:7131".to_string(),
+                        invocation_url: "http://127.0.0.1:7112".to_string(),
+                        capabilities: crate::node_registration::NodeCapabilities::minimum(),
+                        cordoned: true,
+                        health: None,
+                        drain: Some(crate::introspection::NodeDrainStatus {
+                            migrated: 2,
+                            remaining: vec![uuid::Uuid::new_v4()],
+                            safe_to_remove: false,
+                        }),
+                    },
+                ],
+                resource_providers: vec![ResourceProviderInfo {
+                    provider_id: "file-log-1".to_string(),
+                    class_type: "file-log".to_string(),
+                    node_id,
+                    outputs: vec![],
+                }],
+                instances: vec![
+                    ActiveInstanceInfo {
+                        component_id: uuid::Uuid::new_v4(),
+                        spec: ActiveInstanceSpec::Function(crate::function_instance::SpawnFunctionRequest {
+                            spec: crate::function_instance::FunctionClassSpecification {
+                                id: "fc-1".to_string(),
+                                function_type: "RUST_WASM".to_string(),
+                                version: "0.1".to_string(),
+                                binary: None,
+                                code: None,
+                                outputs: vec!["out".to_string()],
+                            },
+                            annotations: std::collections::HashMap::from([("max_instances".to_string(), "1".to_string())]),
+                            state_specification: crate::function_instance::StateSpecification {
+                                state_id: uuid::Uuid::new_v4(),
+                                state_policy: crate::function_instance::StatePolicy::NodeLocal,
+                            },
+                            workflow_id: "workflow-1".to_string(),
+                        }),
+                        instances: vec![crate::function_instance::InstanceId::new(node_id)],
+                        output_mapping: std::collections::HashMap::from([("out".to_string(), uuid::Uuid::new_v4())]),
+                    },
+                    ActiveInstanceInfo {
+                        component_id: uuid::Uuid::new_v4(),
+                        spec: ActiveInstanceSpec::Resource(crate::resource_configuration::ResourceInstanceSpecification {
+                            class_type: "file-log".to_string(),
+                            configuration: std::collections::HashMap::from([("filename".to_string(), "out.log".to_string())]),
+                            workflow_id: "workflow-1".to_string(),
+                        }),
+                        instances: vec![],
+                        output_mapping: std::collections::HashMap::new(),
+                    },
+                ],
+            },
+        ];
+
+        for msg in messages {
+            match parse_domain_snapshot(&serialize_domain_snapshot(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_deserialize_deploy_intents() {
+        let messages = vec![
+            vec![],
+            vec![
+                DeployIntent::Migrate(uuid::Uuid::new_v4(), vec![uuid::Uuid::new_v4(), uuid::Uuid::new_v4()]),
+                DeployIntent::Migrate(uuid::Uuid::new_v4(), vec![]),
+                DeployIntent::Cordon(uuid::Uuid::new_v4()),
+                DeployIntent::Uncordon(uuid::Uuid::new_v4()),
+                DeployIntent::Drain(uuid::Uuid::new_v4()),
+            ],
+        ];
+
+        for msg in messages {
+            match parse_deploy_intents(&serialize_deploy_intents(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_deserialize_function_logs() {
+        let requests = vec![
+            FunctionLogsRequest::default(),
+            FunctionLogsRequest {
+                component_ids: vec![uuid::Uuid::new_v4(), uuid::Uuid::new_v4()],
+                since_sequence: 1750244172293448,
+            },
+        ];
+        for msg in requests {
+            match parse_function_logs_request(&serialize_function_logs_request(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        let records = vec![
+            vec![],
+            vec![FunctionLogRecord {
+                component_id: uuid::Uuid::new_v4(),
+                node_id: uuid::Uuid::new_v4(),
+                sequence: 1750244172293449,
+                entry: crate::node_registration::FunctionLogEntry {
+                    timestamp_sec: 1750244172,
+                    timestamp_ns: 293448700,
+                    target: "sensor".to_string(),
+                    message: "frame processed".to_string(),
+                    level: "Info".to_string(),
+                    workflow_id: uuid::Uuid::new_v4().to_string(),
+                    fields: std::collections::BTreeMap::from([("frame".to_string(), "42".to_string())]),
+                },
+            }],
+        ];
+        for msg in records {
+            match parse_function_log_records(&serialize_function_log_records(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_deserialize_metrics() {
+        let queries = vec![
+            MetricsQuery::default(),
+            MetricsQuery {
+                metric: "function_execution_time".to_string(),
+                from_sec: 1750244172,
+                to_sec: 1750247772,
+                step_sec: 60,
+                aggregate: MetricsAggregate::Max,
+                group_by: MetricsGroupBy::Workflow,
+                node_ids: vec![uuid::Uuid::new_v4()],
+                workflow_ids: vec![uuid::Uuid::new_v4().to_string(), uuid::Uuid::new_v4().to_string()],
+            },
+        ];
+        for msg in queries {
+            match parse_metrics_query(&serialize_metrics_query(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        let series_lists = vec![
+            vec![],
+            vec![
+                MetricsSeries {
+                    group: uuid::Uuid::new_v4().to_string(),
+                    points: vec![
+                        MetricsPoint {
+                            timestamp_sec: 1750244160,
+                            value: 0.5,
+                        },
+                        MetricsPoint {
+                            timestamp_sec: 1750244220,
+                            value: 1.5,
+                        },
+                    ],
+                },
+                MetricsSeries {
+                    group: String::default(),
+                    points: vec![],
+                },
+            ],
+        ];
+        for msg in series_lists {
+            match parse_metrics_series_list(&serialize_metrics_series_list(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+}
@@ -4,7 +4,9 @@ pub(crate) mod domain_registration;
 pub(crate) mod function_instance;
 pub(crate) mod guest_api_function;
 pub(crate) mod guest_api_host;
+pub(crate) mod introspection;
 pub(crate) mod node_management;
 pub(crate) mod node_registration;
+pub(crate) mod placement;
 pub(crate) mod resource_configuration;
 pub(crate) mod workflow_instance;
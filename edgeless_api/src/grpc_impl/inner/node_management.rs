@@ -93,6 +93,32 @@ impl crate::node_management::NodeManagementAPI for NodeManagementClient {
             }
         }
     }
+    async fn check_function_health(
+        &mut self,
+        request: crate::node_management::FunctionHealthRequest,
+    ) -> anyhow::Result<crate::node_management::FunctionHealthResponse> {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client
+                        .check_function_health(tonic::Request::new(serialize_function_health_request(&request)))
+                        .await
+                    {
+                        Ok(res) => parse_function_health_response(&res.into_inner()),
+                        Err(err) => {
+                            self.disconnect();
+                            anyhow::bail!("Error when checking the health of a function at {}: {}", self.server_addr, err);
+                        }
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -119,6 +145,25 @@ impl crate::grpc_impl::api::node_management_server::NodeManagement for NodeManag
             Err(err) => Err(tonic::Status::internal(format!("Error when resetting: {}", err))),
         }
     }
+    async fn check_function_health(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::FunctionHealthRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::FunctionHealthResponse>, tonic::Status> {
+        let parsed_request = match parse_function_health_request(&request.into_inner()) {
+            Ok(parsed_request) => parsed_request,
+            Err(err) => {
+                log::error!("Parse FunctionHealthRequest Failed: {}", err);
+                return Err(tonic::Status::invalid_argument(format!(
+                    "Error when parsing a FunctionHealthRequest message: {}",
+                    err
+                )));
+            }
+        };
+        match self.node_management_api.lock().await.check_function_health(parsed_request).await {
+            Ok(res) => Ok(tonic::Response::new(serialize_function_health_response(&res))),
+            Err(err) => Err(tonic::Status::internal(format!("Error when checking the health of a function: {}", err))),
+        }
+    }
 }
 
 fn parse_update_peers_request(
@@ -176,9 +221,64 @@ fn serialize_update_peers_request(req: &crate::node_management::UpdatePeersReque
     }
 }
 
+fn parse_function_health_request(
+    api_instance: &crate::grpc_impl::api::FunctionHealthRequest,
+) -> anyhow::Result<crate::node_management::FunctionHealthRequest> {
+    let function_id = uuid::Uuid::from_str(api_instance.function_id.as_str())
+        .map_err(|_| anyhow::anyhow!("Ill-formed FunctionHealthRequest: invalid UUID as function_id"))?;
+    Ok(crate::node_management::FunctionHealthRequest {
+        function_id,
+        probes: api_instance
+            .probes
+            .iter()
+            .map(|probe| crate::node_management::FunctionProbe {
+                payload: probe.payload.clone(),
+                expected_reply: probe.expected_reply.clone(),
+                timeout: std::time::Duration::from_millis(probe.timeout_ms),
+            })
+            .collect(),
+    })
+}
+
+fn serialize_function_health_request(req: &crate::node_management::FunctionHealthRequest) -> crate::grpc_impl::api::FunctionHealthRequest {
+    crate::grpc_impl::api::FunctionHealthRequest {
+        function_id: req.function_id.to_string(),
+        probes: req
+            .probes
+            .iter()
+            .map(|probe| crate::grpc_impl::api::FunctionProbe {
+                payload: probe.payload.clone(),
+                expected_reply: probe.expected_reply.clone(),
+                timeout_ms: probe.timeout.as_millis() as u64,
+            })
+            .collect(),
+    }
+}
+
+fn parse_function_health_response(
+    api_instance: &crate::grpc_impl::api::FunctionHealthResponse,
+) -> anyhow::Result<crate::node_management::FunctionHealthResponse> {
+    Ok(crate::node_management::FunctionHealthResponse {
+        running: api_instance.running,
+        restarts: api_instance.restarts,
+        probe_results: api_instance.probe_results.clone(),
+    })
+}
+
+fn serialize_function_health_response(res: &crate::node_management::FunctionHealthResponse) -> crate::grpc_impl::api::FunctionHealthResponse {
+    crate::grpc_impl::api::FunctionHealthResponse {
+        running: res.running,
+        restarts: res.restarts,
+        probe_results: res.probe_results.clone(),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::node_management::FunctionHealthRequest;
+    use crate::node_management::FunctionHealthResponse;
+    use crate::node_management::FunctionProbe;
     use crate::node_management::UpdatePeersRequest;
 
     #[test]
@@ -195,4 +295,45 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn serialize_deserialize_function_health() {
+        let messages = vec![
+            FunctionHealthRequest {
+                function_id: uuid::Uuid::new_v4(),
+                probes: vec![],
+            },
+            FunctionHealthRequest {
+                function_id: uuid::Uuid::new_v4(),
+                probes: vec![
+                    FunctionProbe {
+                        payload: "ping".to_string(),
+                        expected_reply: Some("pong".to_string()),
+                        timeout: std::time::Duration::from_millis(500),
+                    },
+                    FunctionProbe {
+                        payload: "ready?".to_string(),
+                        expected_reply: None,
+                        timeout: std::time::Duration::from_millis(1000),
+                    },
+                ],
+            },
+        ];
+        for msg in messages {
+            match parse_function_health_request(&serialize_function_health_request(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        let msg = FunctionHealthResponse {
+            running: true,
+            restarts: 42,
+            probe_results: vec![true, false],
+        };
+        match parse_function_health_response(&serialize_function_health_response(&msg)) {
+            Ok(val) => assert_eq!(msg, val),
+            Err(err) => panic!("{}", err),
+        }
+    }
 }
@@ -101,7 +101,7 @@ impl crate::grpc_impl::api::node_registration_server::NodeRegistration for NodeR
     }
 }
 
-fn parse_node_capabilities(api_instance: &crate::grpc_impl::api::NodeCapabilities) -> crate::node_registration::NodeCapabilities {
+pub fn parse_node_capabilities(api_instance: &crate::grpc_impl::api::NodeCapabilities) -> crate::node_registration::NodeCapabilities {
     crate::node_registration::NodeCapabilities {
         num_cpus: api_instance.num_cpus,
         model_name_cpu: api_instance.model_name_cpu.clone(),
@@ -119,7 +119,7 @@ fn parse_node_capabilities(api_instance: &crate::grpc_impl::api::NodeCapabilitie
     }
 }
 
-fn serialize_node_capabilities(req: &crate::node_registration::NodeCapabilities) -> crate::grpc_impl::api::NodeCapabilities {
+pub fn serialize_node_capabilities(req: &crate::node_registration::NodeCapabilities) -> crate::grpc_impl::api::NodeCapabilities {
     crate::grpc_impl::api::NodeCapabilities {
         num_cpus: req.num_cpus,
         model_name_cpu: req.model_name_cpu.clone(),
@@ -241,7 +241,7 @@ fn serialize_resource_provider_specification(
     }
 }
 
-fn parse_node_health_status(api_instance: &crate::grpc_impl::api::NodeHealthStatus) -> crate::node_registration::NodeHealthStatus {
+pub fn parse_node_health_status(api_instance: &crate::grpc_impl::api::NodeHealthStatus) -> crate::node_registration::NodeHealthStatus {
     crate::node_registration::NodeHealthStatus {
         mem_free: api_instance.mem_free,
         mem_used: api_instance.mem_used,
@@ -267,7 +267,7 @@ fn parse_node_health_status(api_instance: &crate::grpc_impl::api::NodeHealthStat
     }
 }
 
-fn serialize_node_health_status(req: &crate::node_registration::NodeHealthStatus) -> crate::grpc_impl::api::NodeHealthStatus {
+pub fn serialize_node_health_status(req: &crate::node_registration::NodeHealthStatus) -> crate::grpc_impl::api::NodeHealthStatus {
     crate::grpc_impl::api::NodeHealthStatus {
         mem_free: req.mem_free,
         mem_used: req.mem_used,
@@ -301,15 +301,51 @@ fn parse_sample(api_instance: &crate::grpc_impl::api::Sample) -> crate::node_reg
     }
 }
 
-fn parse_function_log_entry(api_instance: &crate::grpc_impl::api::FunctionLogEntry) -> crate::node_registration::FunctionLogEntry {
+pub fn parse_function_log_entry(api_instance: &crate::grpc_impl::api::FunctionLogEntry) -> crate::node_registration::FunctionLogEntry {
     crate::node_registration::FunctionLogEntry {
         timestamp_sec: api_instance.timestamp_sec,
         timestamp_ns: api_instance.timestamp_ns,
         target: api_instance.target.clone(),
         message: api_instance.msg.clone(),
+        level: api_instance.level.clone(),
+        workflow_id: api_instance.workflow_id.clone(),
+        fields: api_instance.fields.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
     }
 }
 
+fn parse_channel_metrics(api_instance: &crate::grpc_impl::api::ChannelMetrics) -> anyhow::Result<crate::node_registration::ChannelMetrics> {
+    Ok(crate::node_registration::ChannelMetrics {
+        timestamp_sec: api_instance.timestamp_sec,
+        timestamp_ns: api_instance.timestamp_ns,
+        component_id: uuid::Uuid::from_str(&api_instance.component_id)?,
+        channel: api_instance.channel.clone(),
+        peer_id: uuid::Uuid::from_str(&api_instance.peer_id)?,
+        sent: api_instance.sent,
+        sent_bytes: api_instance.sent_bytes,
+        received: api_instance.received,
+        received_bytes: api_instance.received_bytes,
+        dropped: api_instance.dropped,
+        queueing_delay_sum: api_instance.queueing_delay_sum,
+        queueing_delay_max: api_instance.queueing_delay_max,
+    })
+}
+
+fn parse_function_metric(api_instance: &crate::grpc_impl::api::FunctionMetric) -> anyhow::Result<crate::node_registration::FunctionMetric> {
+    Ok(crate::node_registration::FunctionMetric {
+        timestamp_sec: api_instance.timestamp_sec,
+        timestamp_ns: api_instance.timestamp_ns,
+        component_id: uuid::Uuid::from_str(&api_instance.component_id)?,
+        kind: crate::grpc_impl::common::CommonConverters::parse_function_metric_kind(api_instance.kind)?,
+        name: api_instance.name.clone(),
+        labels: api_instance.labels.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+        count: api_instance.count,
+        sum: api_instance.sum,
+        min: api_instance.min,
+        max: api_instance.max,
+        last: api_instance.last,
+    })
+}
+
 fn parse_node_performance_samples(api_instance: &crate::grpc_impl::api::NodePerformanceSamples) -> crate::node_registration::NodePerformanceSamples {
     crate::node_registration::NodePerformanceSamples {
         function_instantiate_times: api_instance
@@ -360,6 +396,32 @@ fn parse_node_performance_samples(api_instance: &crate::grpc_impl::api::NodePerf
                 _ => None,
             })
             .collect(),
+        resource_throttled: api_instance
+            .resource_throttled
+            .iter()
+            .filter_map(|x| match uuid::Uuid::from_str(&x.id) {
+                Ok(val) => Some((val, x.samples.iter().map(parse_sample).collect())),
+                _ => None,
+            })
+            .collect(),
+        resource_failures: api_instance
+            .resource_failures
+            .iter()
+            .filter_map(|x| match uuid::Uuid::from_str(&x.id) {
+                Ok(val) => Some((val, x.samples.iter().map(parse_sample).collect())),
+                _ => None,
+            })
+            .collect(),
+        dataplane_channels: api_instance
+            .dataplane_channels
+            .iter()
+            .filter_map(|x| parse_channel_metrics(x).ok())
+            .collect(),
+        function_metrics: api_instance
+            .function_metrics
+            .iter()
+            .filter_map(|x| parse_function_metric(x).ok())
+            .collect(),
     }
 }
 
@@ -371,12 +433,48 @@ fn serialize_sample(req: &crate::node_registration::Sample) -> crate::grpc_impl:
     }
 }
 
-fn serialize_function_log_entry(req: &crate::node_registration::FunctionLogEntry) -> crate::grpc_impl::api::FunctionLogEntry {
+pub fn serialize_function_log_entry(req: &crate::node_registration::FunctionLogEntry) -> crate::grpc_impl::api::FunctionLogEntry {
     crate::grpc_impl::api::FunctionLogEntry {
         timestamp_sec: req.timestamp_sec,
         timestamp_ns: req.timestamp_ns,
         target: req.target.clone(),
         msg: req.message.clone(),
+        level: req.level.clone(),
+        workflow_id: req.workflow_id.clone(),
+        fields: req.fields.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+    }
+}
+
+fn serialize_channel_metrics(req: &crate::node_registration::ChannelMetrics) -> crate::grpc_impl::api::ChannelMetrics {
+    crate::grpc_impl::api::ChannelMetrics {
+        timestamp_sec: req.timestamp_sec,
+        timestamp_ns: req.timestamp_ns,
+        component_id: req.component_id.to_string(),
+        channel: req.channel.clone(),
+        peer_id: req.peer_id.to_string(),
+        sent: req.sent,
+        sent_bytes: req.sent_bytes,
+        received: req.received,
+        received_bytes: req.received_bytes,
+        dropped: req.dropped,
+        queueing_delay_sum: req.queueing_delay_sum,
+        queueing_delay_max: req.queueing_delay_max,
+    }
+}
+
+fn serialize_function_metric(req: &crate::node_registration::FunctionMetric) -> crate::grpc_impl::api::FunctionMetric {
+    crate::grpc_impl::api::FunctionMetric {
+        timestamp_sec: req.timestamp_sec,
+        timestamp_ns: req.timestamp_ns,
+        component_id: req.component_id.to_string(),
+        kind: crate::grpc_impl::common::CommonConverters::serialize_function_metric_kind(&req.kind),
+        name: req.name.clone(),
+        labels: req.labels.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+        count: req.count,
+        sum: req.sum,
+        min: req.min,
+        max: req.max,
+        last: req.last,
     }
 }
 
@@ -430,13 +528,34 @@ fn serialize_node_performance_samples(req: &crate::node_registration::NodePerfor
                 entries: entries.iter().map(serialize_function_log_entry).collect(),
             })
             .collect(),
+        resource_throttled: req
+            .resource_throttled
+            .iter()
+            .map(|(id, samples)| crate::grpc_impl::api::Samples {
+                id: id.to_string(),
+                samples: samples.iter().map(serialize_sample).collect(),
+            })
+            .collect(),
+        resource_failures: req
+            .resource_failures
+            .iter()
+            .map(|(id, samples)| crate::grpc_impl::api::Samples {
+                id: id.to_string(),
+                samples: samples.iter().map(serialize_sample).collect(),
+            })
+            .collect(),
+        dataplane_channels: req.dataplane_channels.iter().map(serialize_channel_metrics).collect(),
+        function_metrics: req.function_metrics.iter().map(serialize_function_metric).collect(),
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::node_registration::ChannelMetrics;
     use crate::node_registration::FunctionLogEntry;
+    use crate::node_registration::FunctionMetric;
+    use crate::node_registration::FunctionMetricKind;
     use crate::node_registration::NodeCapabilities;
     use crate::node_registration::NodeHealthStatus;
     use crate::node_registration::NodePerformanceSamples;
@@ -465,6 +584,9 @@ mod test {
                 timestamp_ns: (log_cnt + 1) as u32,
                 target: String::from("target"),
                 message: format!("value={}", value),
+                level: String::from("Info"),
+                workflow_id: uuid::Uuid::new_v4().to_string(),
+                fields: std::collections::BTreeMap::from([(String::from("value"), format!("{}", value))]),
             }
         };
 
@@ -544,6 +666,69 @@ mod test {
                     (uuid::Uuid::new_v4(), vec![new_sample(0.1), new_sample(0.2), new_sample(999.0)]),
                 ]),
                 function_log_entries: std::collections::HashMap::from([(uuid::Uuid::new_v4(), vec![new_log(100.0), new_log(200.1)])]),
+                resource_throttled: std::collections::HashMap::from([(uuid::Uuid::new_v4(), vec![new_sample(42.0)])]),
+                resource_failures: std::collections::HashMap::from([(uuid::Uuid::new_v4(), vec![new_sample(7.0)])]),
+                dataplane_channels: vec![
+                    ChannelMetrics {
+                        timestamp_sec: 1000,
+                        timestamp_ns: 42,
+                        component_id: uuid::Uuid::new_v4(),
+                        channel: "out".to_string(),
+                        peer_id: uuid::Uuid::new_v4(),
+                        sent: 10,
+                        sent_bytes: 1000,
+                        received: 0,
+                        received_bytes: 0,
+                        dropped: 2,
+                        queueing_delay_sum: 0.0,
+                        queueing_delay_max: 0.0,
+                    },
+                    ChannelMetrics {
+                        timestamp_sec: 1001,
+                        timestamp_ns: 43,
+                        component_id: uuid::Uuid::new_v4(),
+                        channel: "".to_string(),
+                        peer_id: uuid::Uuid::new_v4(),
+                        sent: 0,
+                        sent_bytes: 0,
+                        received: 5,
+                        received_bytes: 500,
+                        dropped: 0,
+                        queueing_delay_sum: 0.25,
+                        queueing_delay_max: 0.1,
+                    },
+                ],
+                function_metrics: vec![
+                    FunctionMetric {
+                        timestamp_sec: 1000,
+                        timestamp_ns: 42,
+                        component_id: uuid::Uuid::new_v4(),
+                        kind: FunctionMetricKind::Counter,
+                        name: "frames_processed".to_string(),
+                        labels: std::collections::BTreeMap::new(),
+                        count: 3,
+                        sum: 30.0,
+                        min: 5.0,
+                        max: 15.0,
+                        last: 10.0,
+                    },
+                    FunctionMetric {
+                        timestamp_sec: 1001,
+                        timestamp_ns: 43,
+                        component_id: uuid::Uuid::new_v4(),
+                        kind: FunctionMetricKind::Histogram,
+                        name: "anomaly_score".to_string(),
+                        labels: std::collections::BTreeMap::from([
+                            ("camera".to_string(), "front".to_string()),
+                            ("model".to_string(), "v2".to_string()),
+                        ]),
+                        count: 2,
+                        sum: 1.5,
+                        min: 0.5,
+                        max: 1.0,
+                        last: 1.0,
+                    },
+                ],
             },
         }];
         for msg in messages {
@@ -0,0 +1,169 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+#[derive(Clone)]
+pub struct PlacementAPIClient {
+    client: Option<crate::grpc_impl::api::placement_client::PlacementClient<tonic::transport::Channel>>,
+    server_addr: String,
+    tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>,
+}
+
+impl PlacementAPIClient {
+    pub fn new(server_addr: String, tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>) -> Self {
+        Self {
+            client: None,
+            server_addr,
+            tls_config,
+        }
+    }
+
+    /// Try connecting, if not already connected.
+    ///
+    /// If an error is returned, then the client is set to None (disconnected).
+    /// Otherwise, the client is set to some value (connected).
+    async fn try_connect(&mut self) -> anyhow::Result<()> {
+        if self.client.is_none() {
+            let tls_config = if let Some(config) = &self.tls_config {
+                config.clone()
+            } else {
+                crate::grpc_impl::tls_config::TlsConfig::global_client().clone()
+            };
+
+            let channel = tls_config.create_client_channel(&self.server_addr).await?;
+
+            self.client = Some(crate::grpc_impl::api::placement_client::PlacementClient::new(channel).max_decoding_message_size(usize::MAX));
+        }
+        Ok(())
+    }
+
+    /// Disconnect the client.
+    fn disconnect(&mut self) {
+        self.client = None;
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::placement::PlacementAPI for PlacementAPIClient {
+    async fn plan(&mut self, request: crate::workflow_instance::SpawnWorkflowRequest) -> anyhow::Result<crate::workflow_instance::WorkflowPlan> {
+        match self.try_connect().await {
+            Ok(_) => {
+                if let Some(client) = &mut self.client {
+                    match client
+                        .plan(tonic::Request::new(super::workflow_instance::serialize_workflow_spawn_request(&request)))
+                        .await
+                    {
+                        Ok(res) => parse_workflow_plan(&res.into_inner()),
+                        Err(err) => {
+                            self.disconnect();
+                            Err(anyhow::anyhow!("Error when planning a workflow at {}: {}", self.server_addr, err))
+                        }
+                    }
+                } else {
+                    panic!("The impossible happened");
+                }
+            }
+            Err(err) => {
+                anyhow::bail!("Error when connecting to {}: {}", self.server_addr, err);
+            }
+        }
+    }
+}
+
+pub struct PlacementAPIServer {
+    pub root_api: tokio::sync::Mutex<Box<dyn crate::placement::PlacementAPI>>,
+}
+
+#[async_trait::async_trait]
+impl crate::grpc_impl::api::placement_server::Placement for PlacementAPIServer {
+    async fn plan(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::SpawnWorkflowRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowPlan>, tonic::Status> {
+        let req = match super::workflow_instance::parse_workflow_spawn_request(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => return Err(tonic::Status::invalid_argument(format!("Error when planning a workflow: {}", err))),
+        };
+        match self.root_api.lock().await.plan(req).await {
+            Ok(plan) => Ok(tonic::Response::new(serialize_workflow_plan(&plan))),
+            Err(err) => Err(tonic::Status::internal(format!("Error when planning a workflow: {}", err))),
+        }
+    }
+}
+
+fn parse_component_placement(
+    api_placement: &crate::grpc_impl::api::ComponentPlacement,
+) -> anyhow::Result<crate::workflow_instance::ComponentPlacement> {
+    Ok(crate::workflow_instance::ComponentPlacement {
+        name: api_placement.name.clone(),
+        domain_id: api_placement.domain_id.clone(),
+        node_id: match &api_placement.node_id {
+            Some(node_id) => Some(uuid::Uuid::parse_str(node_id)?),
+            None => None,
+        },
+        feasible_nodes: api_placement
+            .feasible_nodes
+            .iter()
+            .map(|x| uuid::Uuid::parse_str(x))
+            .collect::<Result<Vec<uuid::Uuid>, uuid::Error>>()?,
+        reasons: api_placement.reasons.clone(),
+    })
+}
+
+pub fn parse_workflow_plan(api_plan: &crate::grpc_impl::api::WorkflowPlan) -> anyhow::Result<crate::workflow_instance::WorkflowPlan> {
+    Ok(crate::workflow_instance::WorkflowPlan {
+        components: api_plan
+            .components
+            .iter()
+            .map(parse_component_placement)
+            .collect::<anyhow::Result<Vec<crate::workflow_instance::ComponentPlacement>>>()?,
+    })
+}
+
+fn serialize_component_placement(crate_placement: &crate::workflow_instance::ComponentPlacement) -> crate::grpc_impl::api::ComponentPlacement {
+    crate::grpc_impl::api::ComponentPlacement {
+        name: crate_placement.name.clone(),
+        domain_id: crate_placement.domain_id.clone(),
+        node_id: crate_placement.node_id.map(|x| x.to_string()),
+        feasible_nodes: crate_placement.feasible_nodes.iter().map(|x| x.to_string()).collect(),
+        reasons: crate_placement.reasons.clone(),
+    }
+}
+
+pub fn serialize_workflow_plan(crate_plan: &crate::workflow_instance::WorkflowPlan) -> crate::grpc_impl::api::WorkflowPlan {
+    crate::grpc_impl::api::WorkflowPlan {
+        components: crate_plan.components.iter().map(serialize_component_placement).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow_instance::ComponentPlacement;
+    use crate::workflow_instance::WorkflowPlan;
+
+    #[test]
+    fn serialize_deserialize_workflow_plan() {
+        let messages = vec![
+            WorkflowPlan { components: vec![] },
+            WorkflowPlan {
+                components: vec![
+                    ComponentPlacement {
+                        name: "f1".to_string(),
+                        domain_id: "domain-1".to_string(),
+                        node_id: Some(uuid::Uuid::new_v4()),
+                        feasible_nodes: vec![uuid::Uuid::new_v4(), uuid::Uuid::new_v4()],
+                        reasons: vec![],
+                    },
+                    ComponentPlacement::unsatisfiable("r1", "", vec!["no domain with resource class 'file-log'".to_string()]),
+                ],
+            },
+        ];
+
+        for msg in messages {
+            match parse_workflow_plan(&serialize_workflow_plan(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+}
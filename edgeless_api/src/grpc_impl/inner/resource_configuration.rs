@@ -197,7 +197,7 @@ where
     }
 }
 
-fn parse_resource_instance_specification(
+pub fn parse_resource_instance_specification(
     api_spec: &crate::grpc_impl::api::ResourceInstanceSpecification,
 ) -> anyhow::Result<crate::resource_configuration::ResourceInstanceSpecification> {
     Ok(crate::resource_configuration::ResourceInstanceSpecification {
@@ -207,7 +207,7 @@ fn parse_resource_instance_specification(
     })
 }
 
-fn serialize_resource_instance_specification(
+pub fn serialize_resource_instance_specification(
     crate_spec: &crate::resource_configuration::ResourceInstanceSpecification,
 ) -> crate::grpc_impl::api::ResourceInstanceSpecification {
     crate::grpc_impl::api::ResourceInstanceSpecification {
@@ -5,13 +5,15 @@
 
 use std::str::FromStr;
 
+type InterceptedChannel = tonic::service::interceptor::InterceptedService<tonic::transport::Channel, crate::grpc_impl::auth::BearerTokenInterceptor>;
+
 #[derive(Clone)]
 pub struct WorkflowInstanceAPIClient {
-    client: crate::grpc_impl::api::workflow_instance_client::WorkflowInstanceClient<tonic::transport::Channel>,
+    client: crate::grpc_impl::api::workflow_instance_client::WorkflowInstanceClient<InterceptedChannel>,
 }
 
 impl WorkflowInstanceAPIClient {
-    pub async fn new(server_addr: &str) -> Self {
+    pub async fn new(server_addr: &str, interceptor: crate::grpc_impl::auth::BearerTokenInterceptor) -> Self {
         let server_addr = server_addr.to_string();
         let tls_config = crate::grpc_impl::tls_config::TlsConfig::global_client();
 
@@ -19,7 +21,8 @@ impl WorkflowInstanceAPIClient {
             match tls_config.create_client_channel(&server_addr).await {
                 Ok(channel) => {
                     let client =
-                        crate::grpc_impl::api::workflow_instance_client::WorkflowInstanceClient::new(channel).max_decoding_message_size(usize::MAX);
+                        crate::grpc_impl::api::workflow_instance_client::WorkflowInstanceClient::with_interceptor(channel, interceptor.clone())
+                            .max_decoding_message_size(usize::MAX);
                     return Self { client };
                 }
                 Err(err) => {
@@ -88,8 +91,13 @@ impl crate::workflow_instance::WorkflowInstanceAPI for WorkflowInstanceAPIClient
                     Some(status) => super::workflow_instance::parse_workflow_instance(status)?,
                     None => anyhow::bail!("Workflow status not present"),
                 };
+                let health = ret
+                    .health
+                    .iter()
+                    .map(crate::grpc_impl::inner::domain_registration::parse_component_health)
+                    .collect::<anyhow::Result<Vec<_>>>()?;
 
-                return Ok(crate::workflow_instance::WorkflowInfo { request, status });
+                return Ok(crate::workflow_instance::WorkflowInfo { request, status, health });
             }
             Err(err) => Err(anyhow::anyhow!("Communication error while listing workflows: {}", err)),
         }
@@ -122,10 +130,45 @@ impl crate::workflow_instance::WorkflowInstanceAPI for WorkflowInstanceAPIClient
             Err(err) => Err(anyhow::anyhow!("Communication error while migrating a workflow: {}", err)),
         }
     }
+    async fn dry_run(&mut self, request: crate::workflow_instance::SpawnWorkflowRequest) -> anyhow::Result<crate::workflow_instance::WorkflowPlan> {
+        let ret = self
+            .client
+            .dry_run(tonic::Request::new(super::workflow_instance::serialize_workflow_spawn_request(&request)))
+            .await;
+        match ret {
+            Ok(ret) => super::placement::parse_workflow_plan(&ret.into_inner()),
+            Err(err) => Err(anyhow::anyhow!("Communication error while planning a workflow: {}", err)),
+        }
+    }
+    async fn logs(
+        &mut self,
+        request: crate::workflow_instance::WorkflowLogsRequest,
+    ) -> anyhow::Result<Vec<crate::workflow_instance::WorkflowLogEntry>> {
+        let ret = self
+            .client
+            .logs(tonic::Request::new(super::workflow_instance::serialize_workflow_logs_request(&request)))
+            .await;
+        match ret {
+            Ok(ret) => super::workflow_instance::parse_workflow_logs(&ret.into_inner()),
+            Err(err) => Err(anyhow::anyhow!("Communication error while retrieving the logs of a workflow: {}", err)),
+        }
+    }
 }
 
 pub struct WorkflowInstanceAPIServer {
     pub root_api: tokio::sync::Mutex<Box<dyn crate::workflow_instance::WorkflowInstanceAPI>>,
+    /// If set, all the requests must be authorized.
+    pub authorizer: Option<std::sync::Arc<crate::grpc_impl::auth::Authorizer>>,
+}
+
+impl WorkflowInstanceAPIServer {
+    #[allow(clippy::result_large_err)]
+    fn authorize<T>(&self, request: &tonic::Request<T>, rpc: &str) -> Result<(), tonic::Status> {
+        match &self.authorizer {
+            Some(authorizer) => authorizer.authorize(request, rpc),
+            None => Ok(()),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -134,6 +177,7 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
         &self,
         request: tonic::Request<crate::grpc_impl::api::SpawnWorkflowRequest>,
     ) -> Result<tonic::Response<crate::grpc_impl::api::SpawnWorkflowResponse>, tonic::Status> {
+        self.authorize(&request, "start")?;
         let req = match super::workflow_instance::parse_workflow_spawn_request(&request.into_inner()) {
             Ok(val) => val,
             Err(err) => {
@@ -162,6 +206,7 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
     }
 
     async fn stop(&self, request_id: tonic::Request<crate::grpc_impl::api::WorkflowId>) -> Result<tonic::Response<()>, tonic::Status> {
+        self.authorize(&request_id, "stop")?;
         let req = match super::workflow_instance::parse_workflow_id(&request_id.into_inner()) {
             Ok(val) => val,
             Err(err) => return Err(tonic::Status::internal(format!("Internal error when stopping a workflow: {}", err))),
@@ -173,7 +218,8 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
         }
     }
 
-    async fn list(&self, _request: tonic::Request<()>) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowIdList>, tonic::Status> {
+    async fn list(&self, request: tonic::Request<()>) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowIdList>, tonic::Status> {
+        self.authorize(&request, "list")?;
         let ret = self.root_api.lock().await.list().await;
         match ret {
             Ok(identifiers) => Ok(tonic::Response::new(crate::grpc_impl::api::WorkflowIdList {
@@ -187,6 +233,7 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
         &self,
         request_id: tonic::Request<crate::grpc_impl::api::WorkflowId>,
     ) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowInstanceInfo>, tonic::Status> {
+        self.authorize(&request_id, "inspect")?;
         let req = match super::workflow_instance::parse_workflow_id(&request_id.into_inner()) {
             Ok(val) => val,
             Err(err) => return Err(tonic::Status::internal(format!("Internal error when inspecting a workflow: {}", err))),
@@ -196,6 +243,11 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
             Ok(info) => Ok(tonic::Response::new(crate::grpc_impl::api::WorkflowInstanceInfo {
                 request: Some(super::workflow_instance::serialize_workflow_spawn_request(&info.request)),
                 status: Some(super::workflow_instance::serialize_workflow_instance(&info.status)),
+                health: info
+                    .health
+                    .iter()
+                    .map(crate::grpc_impl::inner::domain_registration::serialize_component_health)
+                    .collect(),
             })),
             Err(err) => Err(tonic::Status::internal(format!("Internal error when inspecting a workflow: {}", err))),
         }
@@ -205,6 +257,7 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
         &self,
         domain_id: tonic::Request<crate::grpc_impl::api::DomainId>,
     ) -> Result<tonic::Response<crate::grpc_impl::api::DomainCapabilitiesList>, tonic::Status> {
+        self.authorize(&domain_id, "domains")?;
         match self.root_api.lock().await.domains(domain_id.into_inner().domain_id).await {
             Ok(instances) => Ok(tonic::Response::new(super::workflow_instance::serialize_domain_capabilities_list(
                 &instances,
@@ -220,6 +273,7 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
         &self,
         request: tonic::Request<crate::grpc_impl::api::MigrateWorkflowRequest>,
     ) -> Result<tonic::Response<crate::grpc_impl::api::SpawnWorkflowResponse>, tonic::Status> {
+        self.authorize(&request, "migrate")?;
         let request = match super::workflow_instance::parse_migrate_workflow_request(&request.into_inner()) {
             Ok(val) => val,
             Err(err) => return Err(tonic::Status::internal(format!("Internal error when migrating a workflow: {}", err))),
@@ -238,6 +292,41 @@ impl crate::grpc_impl::api::workflow_instance_server::WorkflowInstance for Workf
             })),
         }
     }
+
+    async fn dry_run(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::SpawnWorkflowRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowPlan>, tonic::Status> {
+        self.authorize(&request, "dry_run")?;
+        let req = match super::workflow_instance::parse_workflow_spawn_request(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => {
+                return Err(tonic::Status::invalid_argument(format!(
+                    "Invalid request when planning a workflow: {}",
+                    err
+                )));
+            }
+        };
+        match self.root_api.lock().await.dry_run(req).await {
+            Ok(plan) => Ok(tonic::Response::new(super::placement::serialize_workflow_plan(&plan))),
+            Err(err) => Err(tonic::Status::internal(format!("Internal error when planning a workflow: {}", err))),
+        }
+    }
+
+    async fn logs(
+        &self,
+        request: tonic::Request<crate::grpc_impl::api::WorkflowLogsRequest>,
+    ) -> Result<tonic::Response<crate::grpc_impl::api::WorkflowLogs>, tonic::Status> {
+        self.authorize(&request, "logs")?;
+        let req = match super::workflow_instance::parse_workflow_logs_request(&request.into_inner()) {
+            Ok(val) => val,
+            Err(err) => return Err(tonic::Status::invalid_argument(format!("Invalid request for workflow logs: {}", err))),
+        };
+        match self.root_api.lock().await.logs(req).await {
+            Ok(entries) => Ok(tonic::Response::new(super::workflow_instance::serialize_workflow_logs(&entries))),
+            Err(err) => Err(tonic::Status::internal(format!("Internal error when retrieving workflow logs: {}", err))),
+        }
+    }
 }
 
 fn parse_workflow_id(api_id: &crate::grpc_impl::api::WorkflowId) -> anyhow::Result<crate::workflow_instance::WorkflowId> {
@@ -267,7 +356,7 @@ fn parse_workflow_resource(api_workflow: &crate::grpc_impl::api::WorkflowResourc
     })
 }
 
-fn parse_workflow_spawn_request(
+pub fn parse_workflow_spawn_request(
     api_request: &crate::grpc_impl::api::SpawnWorkflowRequest,
 ) -> anyhow::Result<crate::workflow_instance::SpawnWorkflowRequest> {
     Ok(crate::workflow_instance::SpawnWorkflowRequest {
@@ -390,7 +479,9 @@ fn serialize_workflow_resource(crate_resource: &crate::workflow_instance::Workfl
     }
 }
 
-fn serialize_workflow_spawn_request(crate_request: &crate::workflow_instance::SpawnWorkflowRequest) -> crate::grpc_impl::api::SpawnWorkflowRequest {
+pub fn serialize_workflow_spawn_request(
+    crate_request: &crate::workflow_instance::SpawnWorkflowRequest,
+) -> crate::grpc_impl::api::SpawnWorkflowRequest {
     crate::grpc_impl::api::SpawnWorkflowRequest {
         workflow_functions: crate_request.functions.iter().map(serialize_workflow_function).collect(),
         workflow_resources: crate_request.resources.iter().map(serialize_workflow_resource).collect(),
@@ -456,6 +547,59 @@ fn serialize_migrate_workflow_request(
     }
 }
 
+fn parse_workflow_logs_request(
+    api_request: &crate::grpc_impl::api::WorkflowLogsRequest,
+) -> anyhow::Result<crate::workflow_instance::WorkflowLogsRequest> {
+    match &api_request.workflow_id {
+        Some(workflow_id) => Ok(crate::workflow_instance::WorkflowLogsRequest {
+            workflow_id: parse_workflow_id(workflow_id)?,
+            component: api_request.component.clone(),
+            since_sequences: api_request.since_sequences.clone(),
+        }),
+        None => anyhow::bail!("missing workflow_id in WorkflowLogsRequest"),
+    }
+}
+
+fn parse_workflow_logs(api_logs: &crate::grpc_impl::api::WorkflowLogs) -> anyhow::Result<Vec<crate::workflow_instance::WorkflowLogEntry>> {
+    let mut ret = vec![];
+    for entry in &api_logs.entries {
+        match &entry.entry {
+            Some(log_entry) => ret.push(crate::workflow_instance::WorkflowLogEntry {
+                component: entry.component.clone(),
+                domain_id: entry.domain_id.clone(),
+                node_id: uuid::Uuid::from_str(&entry.node_id)?,
+                sequence: entry.sequence,
+                entry: super::node_registration::parse_function_log_entry(log_entry),
+            }),
+            None => anyhow::bail!("missing entry in WorkflowLogEntry"),
+        }
+    }
+    Ok(ret)
+}
+
+fn serialize_workflow_logs_request(crate_request: &crate::workflow_instance::WorkflowLogsRequest) -> crate::grpc_impl::api::WorkflowLogsRequest {
+    crate::grpc_impl::api::WorkflowLogsRequest {
+        workflow_id: Some(serialize_workflow_id(&crate_request.workflow_id)),
+        component: crate_request.component.clone(),
+        since_sequences: crate_request.since_sequences.clone(),
+    }
+}
+
+fn serialize_workflow_logs(crate_logs: &[crate::workflow_instance::WorkflowLogEntry]) -> crate::grpc_impl::api::WorkflowLogs {
+    crate::grpc_impl::api::WorkflowLogs {
+        entries: crate_logs
+            .iter()
+            .map(|entry| crate::grpc_impl::api::WorkflowLogEntry {
+                component: entry.component.clone(),
+                domain_id: entry.domain_id.clone(),
+                node_id: entry.node_id.to_string(),
+                entry: Some(super::node_registration::serialize_function_log_entry(&entry.entry)),
+                sequence: entry.sequence,
+            })
+            .collect(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -558,6 +702,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn serialize_deserialize_workflow_logs_request() {
+        let messages = vec![
+            crate::workflow_instance::WorkflowLogsRequest {
+                workflow_id: WorkflowId {
+                    workflow_id: uuid::Uuid::new_v4(),
+                },
+                component: "".to_string(),
+                since_sequences: HashMap::new(),
+            },
+            crate::workflow_instance::WorkflowLogsRequest {
+                workflow_id: WorkflowId {
+                    workflow_id: uuid::Uuid::new_v4(),
+                },
+                component: "f1".to_string(),
+                since_sequences: HashMap::from([("domain1".to_string(), 1700000000000042), ("domain2".to_string(), 0)]),
+            },
+        ];
+
+        for msg in messages {
+            match parse_workflow_logs_request(&serialize_workflow_logs_request(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_deserialize_workflow_logs() {
+        let messages = vec![
+            vec![],
+            vec![crate::workflow_instance::WorkflowLogEntry {
+                component: "f1".to_string(),
+                domain_id: "domain1".to_string(),
+                node_id: uuid::Uuid::new_v4(),
+                sequence: 1700000000000043,
+                entry: crate::node_registration::FunctionLogEntry {
+                    timestamp_sec: 1700000000,
+                    timestamp_ns: 42,
+                    target: "my_target".to_string(),
+                    message: "hello".to_string(),
+                    level: "Info".to_string(),
+                    workflow_id: uuid::Uuid::new_v4().to_string(),
+                    fields: std::collections::BTreeMap::from([("key".to_string(), "value".to_string())]),
+                },
+            }],
+        ];
+
+        for msg in messages {
+            match parse_workflow_logs(&serialize_workflow_logs(&msg)) {
+                Ok(val) => assert_eq!(msg, val),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
     #[test]
     fn serialize_deserialize_workflow_function_mapping() {
         let messages = vec![WorkflowFunctionMapping {
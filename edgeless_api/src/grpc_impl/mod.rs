@@ -6,6 +6,7 @@
 pub mod api {
     tonic::include_proto!("edgeless_api");
 }
+pub mod auth;
 mod common;
 mod inner;
 pub mod outer;
@@ -9,9 +9,28 @@ pub struct ControllerAPIClient {
 impl ControllerAPIClient {
     pub async fn new(api_addr: &str) -> Self {
         Self {
-            workflow_instance_client: Box::new(crate::grpc_impl::inner::workflow_instance::WorkflowInstanceAPIClient::new(api_addr).await),
+            workflow_instance_client: Box::new(
+                crate::grpc_impl::inner::workflow_instance::WorkflowInstanceAPIClient::new(
+                    api_addr,
+                    crate::grpc_impl::auth::BearerTokenInterceptor::default(),
+                )
+                .await,
+            ),
         }
     }
+
+    /// Create a client that authenticates with the given bearer token, if any.
+    pub async fn new_with_token(api_addr: &str, token: Option<String>) -> anyhow::Result<Self> {
+        Ok(Self {
+            workflow_instance_client: Box::new(
+                crate::grpc_impl::inner::workflow_instance::WorkflowInstanceAPIClient::new(
+                    api_addr,
+                    crate::grpc_impl::auth::BearerTokenInterceptor::new(token)?,
+                )
+                .await,
+            ),
+        })
+    }
 }
 
 impl crate::outer::controller::ControllerAPI for ControllerAPIClient {
@@ -27,13 +46,24 @@ impl WorkflowInstanceAPIServer {
         controller_api: Box<dyn crate::outer::controller::ControllerAPI + Send>,
         controller_url: String,
         tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>,
-    ) -> futures::future::BoxFuture<'static, ()> {
+        auth_config: Option<crate::grpc_impl::auth::AuthConfig>,
+    ) -> anyhow::Result<futures::future::BoxFuture<'static, ()>> {
         let mut controller_api = controller_api;
-        let workflow_api = crate::grpc_impl::inner::workflow_instance::WorkflowInstanceAPIServer {
-            root_api: tokio::sync::Mutex::new(controller_api.workflow_instance_api()),
+        let root_api = tokio::sync::Mutex::new(controller_api.workflow_instance_api());
+        let authorizer = match auth_config {
+            Some(auth_config) => {
+                let authorizer = crate::grpc_impl::auth::Authorizer::new(&auth_config)
+                    .map_err(|err| anyhow::anyhow!("failed to create the authorizer of the ControllerAPI GRPC server: {}", err))?;
+                log::info!("Authentication enabled for ControllerAPI GRPC server");
+                Some(std::sync::Arc::new(authorizer))
+            }
+            None => None,
         };
-        Box::pin(async move {
-            let workflow_api = workflow_api;
+        Ok(Box::pin(async move {
+            let workflow_api = crate::grpc_impl::inner::workflow_instance::WorkflowInstanceAPIServer {
+                root_api,
+                authorizer: authorizer.clone(),
+            };
             if let Ok((_proto, host, port)) = crate::util::parse_http_host(&controller_url)
                 && let Ok(host) = format!("{}:{}", host, port).parse()
             {
@@ -63,14 +93,21 @@ impl WorkflowInstanceAPIServer {
                     }
                 }
 
-                match server_builder
-                    .add_service(
-                        crate::grpc_impl::api::workflow_instance_server::WorkflowInstanceServer::new(workflow_api)
-                            .max_decoding_message_size(usize::MAX),
-                    )
-                    .serve(host)
-                    .await
-                {
+                let workflow_service =
+                    crate::grpc_impl::api::workflow_instance_server::WorkflowInstanceServer::new(workflow_api).max_decoding_message_size(usize::MAX);
+                let res = match authorizer {
+                    Some(authorizer) => {
+                        server_builder
+                            .add_service(tonic::service::interceptor::InterceptedService::new(
+                                workflow_service,
+                                crate::grpc_impl::auth::AuthInterceptor::new(authorizer),
+                            ))
+                            .serve(host)
+                            .await
+                    }
+                    None => server_builder.add_service(workflow_service).serve(host).await,
+                };
+                match res {
                     Ok(_) => {
                         log::debug!("Clean Exit");
                     }
@@ -81,6 +118,6 @@ impl WorkflowInstanceAPIServer {
             }
 
             log::info!("Stop ControllerAPI GRPC Server");
-        })
+        }))
     }
 }
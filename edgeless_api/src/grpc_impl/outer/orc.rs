@@ -7,10 +7,22 @@ pub struct OrchestratorAPIClient {
     function_instance_client: Box<dyn crate::function_instance::FunctionInstanceAPI<crate::function_instance::DomainManagedInstanceId>>,
     resource_configuration_client:
         Box<dyn crate::resource_configuration::ResourceConfigurationAPI<crate::function_instance::DomainManagedInstanceId>>,
+    placement_client: Box<dyn crate::placement::PlacementAPI>,
+    introspection_client: Box<dyn crate::introspection::IntrospectionAPI>,
 }
 
 impl OrchestratorAPIClient {
     pub async fn new(api_addr: &str, tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>) -> anyhow::Result<Self> {
+        Self::new_with_token(api_addr, tls_config, None).await
+    }
+
+    /// Create a client that authenticates with the given bearer token, if any,
+    /// on the Introspection API, which is the only one requiring credentials.
+    pub async fn new_with_token(
+        api_addr: &str,
+        tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>,
+        token: Option<String>,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
             function_instance_client: Box::new(crate::grpc_impl::inner::function_instance::FunctionInstanceAPIClient::new(
                 api_addr.to_string(),
@@ -20,6 +32,15 @@ impl OrchestratorAPIClient {
                 api_addr.to_string(),
                 tls_config.clone(),
             )),
+            placement_client: Box::new(crate::grpc_impl::inner::placement::PlacementAPIClient::new(
+                api_addr.to_string(),
+                tls_config.clone(),
+            )),
+            introspection_client: Box::new(crate::grpc_impl::inner::introspection::IntrospectionAPIClient::new(
+                api_addr.to_string(),
+                tls_config.clone(),
+                crate::grpc_impl::auth::BearerTokenInterceptor::new(token)?,
+            )),
         })
     }
 }
@@ -34,6 +55,14 @@ impl crate::outer::orc::OrchestratorAPI for OrchestratorAPIClient {
     ) -> Box<dyn crate::resource_configuration::ResourceConfigurationAPI<crate::function_instance::DomainManagedInstanceId>> {
         self.resource_configuration_client.clone()
     }
+
+    fn placement_api(&mut self) -> Box<dyn crate::placement::PlacementAPI> {
+        self.placement_client.clone()
+    }
+
+    fn introspection_api(&mut self) -> Box<dyn crate::introspection::IntrospectionAPI> {
+        self.introspection_client.clone()
+    }
 }
 
 pub struct OrchestratorAPIServer {}
@@ -43,7 +72,8 @@ impl OrchestratorAPIServer {
         agent_api: Box<dyn crate::outer::orc::OrchestratorAPI + Send>,
         orchestrator_url: String,
         tls_config: Option<crate::grpc_impl::tls_config::TlsConfig>,
-    ) -> futures::future::BoxFuture<'static, ()> {
+        auth_config: Option<crate::grpc_impl::auth::AuthConfig>,
+    ) -> anyhow::Result<futures::future::BoxFuture<'static, ()>> {
         let mut agent_api = agent_api;
         let function_api = crate::grpc_impl::inner::function_instance::FunctionInstanceAPIServer::<crate::function_instance::DomainManagedInstanceId> {
             root_api: tokio::sync::Mutex::new(agent_api.function_instance_api()),
@@ -53,8 +83,27 @@ impl OrchestratorAPIServer {
         > {
             root_api: tokio::sync::Mutex::new(agent_api.resource_configuration_api()),
         };
-        Box::pin(async move {
+        let placement_api = crate::grpc_impl::inner::placement::PlacementAPIServer {
+            root_api: tokio::sync::Mutex::new(agent_api.placement_api()),
+        };
+        let introspection_root_api = tokio::sync::Mutex::new(agent_api.introspection_api());
+        // Only the Introspection API is exposed to the users of the
+        // orchestration domain, the others are used by the controller.
+        let authorizer = match auth_config {
+            Some(auth_config) => {
+                let authorizer = crate::grpc_impl::auth::Authorizer::new(&auth_config)
+                    .map_err(|err| anyhow::anyhow!("failed to create the authorizer of the OrchestratorAPI GRPC server: {}", err))?;
+                log::info!("Authentication enabled for the Introspection API of the OrchestratorAPI GRPC server");
+                Some(std::sync::Arc::new(authorizer))
+            }
+            None => None,
+        };
+        Ok(Box::pin(async move {
             let function_api = function_api;
+            let introspection_api = crate::grpc_impl::inner::introspection::IntrospectionAPIServer {
+                root_api: introspection_root_api,
+                authorizer: authorizer.clone(),
+            };
             if let Ok((_proto, host, port)) = crate::util::parse_http_host(&orchestrator_url)
                 && let Ok(host) = format!("{}:{}", host, port).parse()
             {
@@ -84,7 +133,7 @@ impl OrchestratorAPIServer {
                     }
                 }
 
-                match server_builder
+                let router = server_builder
                     .add_service(
                         crate::grpc_impl::api::function_instance_server::FunctionInstanceServer::new(function_api)
                             .max_decoding_message_size(usize::MAX),
@@ -93,9 +142,22 @@ impl OrchestratorAPIServer {
                         crate::grpc_impl::api::resource_configuration_server::ResourceConfigurationServer::new(resource_configuration_api)
                             .max_decoding_message_size(usize::MAX),
                     )
-                    .serve(host)
-                    .await
-                {
+                    .add_service(crate::grpc_impl::api::placement_server::PlacementServer::new(placement_api).max_decoding_message_size(usize::MAX));
+                let introspection_service =
+                    crate::grpc_impl::api::introspection_server::IntrospectionServer::new(introspection_api).max_decoding_message_size(usize::MAX);
+                let res = match authorizer {
+                    Some(authorizer) => {
+                        router
+                            .add_service(tonic::service::interceptor::InterceptedService::new(
+                                introspection_service,
+                                crate::grpc_impl::auth::AuthInterceptor::new(authorizer),
+                            ))
+                            .serve(host)
+                            .await
+                    }
+                    None => router.add_service(introspection_service).serve(host).await,
+                };
+                match res {
                     Ok(_) => {
                         log::debug!("Clean Exit");
                     }
@@ -106,6 +168,6 @@ impl OrchestratorAPIServer {
             }
 
             log::info!("Stop OrchestratorAPI GRPC Server");
-        })
+        }))
     }
 }
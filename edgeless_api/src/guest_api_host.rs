@@ -30,6 +30,16 @@ pub struct TelemetryLogEvent {
     pub log_level: TelemetryLogLevel,
     pub target: String,
     pub msg: String,
+    pub fields: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryMetricEvent {
+    pub originator: edgeless_api_core::instance_id::InstanceId,
+    pub kind: crate::node_registration::FunctionMetricKind,
+    pub name: String,
+    pub labels: std::collections::BTreeMap<String, String>,
+    pub value: f64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -54,6 +64,7 @@ pub trait GuestAPIHost: GuestAPIHostClone + Sync + Send {
     async fn call(&mut self, event: OutputEventData) -> anyhow::Result<crate::guest_api_function::CallReturn>;
     async fn call_raw(&mut self, event: OutputEventDataRaw) -> anyhow::Result<crate::guest_api_function::CallReturn>;
     async fn telemetry_log(&mut self, event: TelemetryLogEvent) -> anyhow::Result<()>;
+    async fn telemetry_metric(&mut self, event: TelemetryMetricEvent) -> anyhow::Result<()>;
     async fn slf(&mut self) -> anyhow::Result<edgeless_api_core::instance_id::InstanceId>;
     async fn delayed_cast(&mut self, event: DelayedEventData) -> anyhow::Result<()>;
     async fn sync(&mut self, event: SyncData) -> anyhow::Result<()>;
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Node of an orchestration domain, as known by its orchestrator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeInfo {
+    pub node_id: crate::function_instance::NodeId,
+    pub agent_url: String,
+    pub invocation_url: String,
+    pub capabilities: crate::node_registration::NodeCapabilities,
+    /// True if no new functions/resources can be assigned to the node.
+    pub cordoned: bool,
+    /// Last health status received from the node, if any.
+    pub health: Option<crate::node_registration::NodeHealthStatus>,
+    /// Progress of the drain, if the node is being drained.
+    pub drain: Option<NodeDrainStatus>,
+}
+
+/// Progress of the drain of a node.
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct NodeDrainStatus {
+    /// Number of functions/resources migrated off the node so far.
+    pub migrated: u32,
+    /// Logical identifiers of the functions/resources still assigned to the
+    /// node, e.g., because there is no other feasible node for them.
+    pub remaining: Vec<crate::function_instance::ComponentId>,
+    /// True if no function/resource is assigned to the node anymore.
+    pub safe_to_remove: bool,
+}
+
+impl std::fmt::Display for NodeDrainStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "migrated {}, remaining [{}], safe to remove {}",
+            self.migrated,
+            self.remaining.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(","),
+            if self.safe_to_remove { "yes" } else { "no" }
+        )
+    }
+}
+
+/// Resource provider advertised by a node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceProviderInfo {
+    pub provider_id: String,
+    pub class_type: String,
+    pub node_id: crate::function_instance::NodeId,
+    pub outputs: Vec<String>,
+}
+
+/// Specification of an active function/resource.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActiveInstanceSpec {
+    /// Spawn request of a function, without the function's code.
+    Function(crate::function_instance::SpawnFunctionRequest),
+    Resource(crate::resource_configuration::ResourceInstanceSpecification),
+}
+
+/// Function/resource that the orchestrator keeps active.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveInstanceInfo {
+    /// Logical identifier of the function/resource.
+    pub component_id: crate::function_instance::ComponentId,
+    pub spec: ActiveInstanceSpec,
+    /// Physical identifiers of the instances, empty if none is assigned.
+    pub instances: Vec<crate::function_instance::InstanceId>,
+    /// Mapping of the outputs to the logical identifiers of the target
+    /// functions/resources.
+    pub output_mapping: std::collections::HashMap<String, crate::function_instance::ComponentId>,
+}
+
+/// Snapshot of the state of an orchestration domain.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DomainSnapshot {
+    pub nodes: Vec<NodeInfo>,
+    pub resource_providers: Vec<ResourceProviderInfo>,
+    pub instances: Vec<ActiveInstanceInfo>,
+}
+
+/// Intent to change the deployment in an orchestration domain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeployIntent {
+    /// Migrate the function/resource with given logical identifier to the
+    /// given target nodes, if possible.
+    Migrate(crate::function_instance::ComponentId, Vec<crate::function_instance::NodeId>),
+    /// Do not assign new functions/resources to the node.
+    Cordon(crate::function_instance::NodeId),
+    /// Allow again new functions/resources to be assigned to the node.
+    Uncordon(crate::function_instance::NodeId),
+    /// Cordon the node and migrate all its functions/resources elsewhere.
+    Drain(crate::function_instance::NodeId),
+}
+
+/// Request of the most recent log entries of functions.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FunctionLogsRequest {
+    /// Logical identifiers of the functions, all if empty.
+    pub component_ids: Vec<crate::function_instance::ComponentId>,
+    /// Only the entries with a greater sequence number are returned,
+    /// all of them if 0.
+    pub since_sequence: u64,
+}
+
+/// Log entry of a function, as collected by the orchestrator from the
+/// performance samples of the nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionLogRecord {
+    /// Logical identifier of the function.
+    pub component_id: crate::function_instance::ComponentId,
+    /// Node hosting the function instance that emitted the log.
+    pub node_id: crate::function_instance::NodeId,
+    /// Sequence number assigned by the orchestrator upon reception,
+    /// increasing in order of arrival.
+    pub sequence: u64,
+    pub entry: crate::node_registration::FunctionLogEntry,
+}
+
+/// Aggregation of the samples of a metric within an interval.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MetricsAggregate {
+    #[default]
+    Avg,
+    Min,
+    Max,
+    Sum,
+    Count,
+}
+
+/// Grouping of the series of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MetricsGroupBy {
+    /// All the series are aggregated together.
+    #[default]
+    None,
+    Node,
+    Workflow,
+    /// Function/resource, by logical identifier.
+    Component,
+}
+
+/// Query of the historical metrics collected by the orchestrator.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MetricsQuery {
+    /// Name of the metric, e.g., `mem_available` or `function_execution_time`.
+    pub metric: String,
+    /// Start of the time range: number of s since Unix epoch.
+    pub from_sec: i64,
+    /// End of the time range: number of s since Unix epoch, 0 means now.
+    pub to_sec: i64,
+    /// Duration of the intervals over which the samples are aggregated, in s.
+    /// 0 means the finest resolution retained for the whole time range.
+    pub step_sec: u64,
+    pub aggregate: MetricsAggregate,
+    pub group_by: MetricsGroupBy,
+    /// Only the series of these nodes are considered, all if empty.
+    pub node_ids: Vec<crate::function_instance::NodeId>,
+    /// Only the series of these workflows are considered, all if empty.
+    pub workflow_ids: Vec<String>,
+}
+
+/// Aggregated value of a metric within an interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsPoint {
+    /// Start of the interval: number of s since Unix epoch.
+    pub timestamp_sec: i64,
+    pub value: f64,
+}
+
+/// Series of the aggregated values of a metric for a group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsSeries {
+    /// Identifier of the node, workflow, or component, depending on the
+    /// grouping, empty if the series are not grouped.
+    pub group: String,
+    /// Points in chronological order.
+    pub points: Vec<MetricsPoint>,
+}
+
+/// API exposed by the orchestrator to inspect the state of its orchestration
+/// domain and to steer the deployment, without going through a proxy.
+#[async_trait::async_trait]
+pub trait IntrospectionAPI: IntrospectionAPIClone + Sync + Send {
+    async fn snapshot(&mut self) -> anyhow::Result<DomainSnapshot>;
+    async fn add_deploy_intents(&mut self, intents: Vec<DeployIntent>) -> anyhow::Result<()>;
+    async fn function_logs(&mut self, request: FunctionLogsRequest) -> anyhow::Result<Vec<FunctionLogRecord>>;
+    async fn query_metrics(&mut self, query: MetricsQuery) -> anyhow::Result<Vec<MetricsSeries>>;
+}
+
+// https://stackoverflow.com/a/30353928
+pub trait IntrospectionAPIClone {
+    fn clone_box(&self) -> Box<dyn IntrospectionAPI>;
+}
+impl<T> IntrospectionAPIClone for T
+where
+    T: 'static + IntrospectionAPI + Clone,
+{
+    fn clone_box(&self) -> Box<dyn IntrospectionAPI> {
+        Box::new(self.clone())
+    }
+}
+impl Clone for Box<dyn IntrospectionAPI> {
+    fn clone(&self) -> Box<dyn IntrospectionAPI> {
+        self.clone_box()
+    }
+}
@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Service Level Objectives (SLOs) on the latency of the chains of
+//! functions/resources, as specified by annotations.
+//!
+//! An SLO in the annotations of a function bounds the latency of the chain
+//! starting from that function, while an SLO in the annotations of a workflow
+//! bounds the end-to-end latency of the whole workflow. The ε-CON forwards
+//! the latter to all the functions of the workflow with the prefix
+//! [`WORKFLOW_PREFIX`].
+
+/// Prefix of the annotations of a function that carry the SLO of its workflow.
+pub const WORKFLOW_PREFIX: &str = "workflow_";
+
+/// Latency SLO.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencySlo {
+    /// Maximum latency allowed.
+    pub target: std::time::Duration,
+    /// Percentile of the latency that must not exceed the target, in (0, 100].
+    pub percentile: f64,
+    /// Time window over which the latency percentiles are computed, which is
+    /// also the time for which the SLO must be violated before the
+    /// orchestrator reacts.
+    pub window: std::time::Duration,
+}
+
+impl LatencySlo {
+    /// Return true if the given annotation is an SLO annotation.
+    pub fn is_slo_annotation(name: &str) -> bool {
+        name.starts_with("slo_")
+    }
+
+    /// SLO from the annotations, if present, with the given prefix, which
+    /// is empty for the SLO of a function and [`WORKFLOW_PREFIX`] for the
+    /// SLO of its workflow.
+    ///
+    /// The annotations are:
+    ///
+    /// - `slo_latency_ms`: the maximum latency allowed (mandatory)
+    /// - `slo_percentile`: the percentile of the latency, by default 95
+    /// - `slo_window_sec`: the time window, by default 30 s
+    ///
+    /// Invalid values are ignored and the defaults are used instead.
+    pub fn from_annotations(annotations: &std::collections::HashMap<String, String>, prefix: &str) -> Option<Self> {
+        let get = |name: &str| annotations.get(format!("{}{}", prefix, name).as_str());
+        let target = get("slo_latency_ms").and_then(|x| x.parse::<u64>().ok())?;
+        Some(Self {
+            target: std::time::Duration::from_millis(target),
+            percentile: get("slo_percentile")
+                .and_then(|x| x.parse::<f64>().ok())
+                .filter(|x| *x > 0.0 && *x <= 100.0)
+                .unwrap_or(95.0),
+            window: std::time::Duration::from_secs(get("slo_window_sec").and_then(|x| x.parse::<u64>().ok()).unwrap_or(30).max(1)),
+        })
+    }
+
+    /// Annotations of a function carrying the SLO of its workflow, if any.
+    pub fn workflow_annotations(workflow_annotations: &std::collections::HashMap<String, String>) -> std::collections::HashMap<String, String> {
+        workflow_annotations
+            .iter()
+            .filter(|(name, _val)| Self::is_slo_annotation(name))
+            .map(|(name, val)| (format!("{}{}", WORKFLOW_PREFIX, name), val.clone()))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for LatencySlo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "p{} latency <= {} ms over {} s",
+            self.percentile,
+            self.target.as_millis(),
+            self.window.as_secs()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_slo_from_annotations() {
+        assert!(LatencySlo::from_annotations(&std::collections::HashMap::new(), "").is_none());
+
+        let annotations = std::collections::HashMap::from([
+            ("slo_latency_ms".to_string(), "50".to_string()),
+            ("slo_percentile".to_string(), "200".to_string()),
+            ("slo_window_sec".to_string(), "0".to_string()),
+        ]);
+        assert_eq!(
+            Some(LatencySlo {
+                target: std::time::Duration::from_millis(50),
+                percentile: 95.0,
+                window: std::time::Duration::from_secs(1),
+            }),
+            LatencySlo::from_annotations(&annotations, "")
+        );
+        assert!(LatencySlo::from_annotations(&annotations, WORKFLOW_PREFIX).is_none());
+
+        let workflow_annotations = LatencySlo::workflow_annotations(&std::collections::HashMap::from([
+            ("slo_latency_ms".to_string(), "100".to_string()),
+            ("slo_percentile".to_string(), "99.9".to_string()),
+            ("other".to_string(), "value".to_string()),
+        ]));
+        assert_eq!(2, workflow_annotations.len());
+        assert_eq!(
+            Some(LatencySlo {
+                target: std::time::Duration::from_millis(100),
+                percentile: 99.9,
+                window: std::time::Duration::from_secs(30),
+            }),
+            LatencySlo::from_annotations(&workflow_annotations, WORKFLOW_PREFIX)
+        );
+    }
+}
@@ -7,14 +7,18 @@ pub mod coap_impl;
 pub mod common;
 pub mod domain_registration;
 pub mod function_instance;
+pub mod function_lifecycle;
 #[cfg(feature = "grpc_impl")]
 pub mod grpc_impl;
 pub mod guest_api_function;
 pub mod guest_api_host;
+pub mod introspection;
 pub mod invocation;
+pub mod latency_slo;
 pub mod node_management;
 pub mod node_registration;
 pub mod outer;
+pub mod placement;
 pub mod resource_configuration;
 pub mod util;
 pub mod workflow_instance;
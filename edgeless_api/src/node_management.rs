@@ -10,10 +10,38 @@ pub enum UpdatePeersRequest {
     Clear,
 }
 
+/// Probe of a function instance executed by a node on behalf of the
+/// orchestrator, see [`crate::function_lifecycle::ProbeSpec`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionProbe {
+    pub payload: String,
+    pub expected_reply: Option<String>,
+    pub timeout: std::time::Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionHealthRequest {
+    // Function instance identifier (in the node).
+    pub function_id: crate::function_instance::ComponentId,
+    // Probes to be executed on the function instance.
+    pub probes: Vec<FunctionProbe>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionHealthResponse {
+    // True if the function instance is running (or being restarted).
+    pub running: bool,
+    // Number of times the function instance has been restarted by the node.
+    pub restarts: u32,
+    // Outcome of the probes, in the same order as in the request.
+    pub probe_results: Vec<bool>,
+}
+
 #[async_trait::async_trait]
 pub trait NodeManagementAPI: NodeManagementAPIClone + Sync + Send {
     async fn update_peers(&mut self, request: UpdatePeersRequest) -> anyhow::Result<()>;
     async fn reset(&mut self) -> anyhow::Result<()>;
+    async fn check_function_health(&mut self, request: FunctionHealthRequest) -> anyhow::Result<FunctionHealthResponse>;
 }
 
 // https://stackoverflow.com/a/30353928
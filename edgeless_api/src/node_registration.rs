@@ -206,6 +206,12 @@ pub struct FunctionLogEntry {
     pub target: String,
     /// Message specified in the log.
     pub message: String,
+    /// Level of the log, e.g., Info, empty if unknown.
+    pub level: String,
+    /// Workflow of the function instance, empty if unknown.
+    pub workflow_id: String,
+    /// Structured fields specified in the log.
+    pub fields: std::collections::BTreeMap<String, String>,
 }
 
 impl FunctionLogEntry {
@@ -220,6 +226,146 @@ impl std::fmt::Display for FunctionLogEntry {
     }
 }
 
+/// Dataplane metrics of the events exchanged by a component with a peer
+/// along a channel since the previous update.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChannelMetrics {
+    /// Number of s since Unix epoch,
+    pub timestamp_sec: i64,
+    /// Number of ns since the last second boundary from Unix Epoch.
+    pub timestamp_ns: u32,
+    /// Component that sent or received the events.
+    pub component_id: crate::function_instance::ComponentId,
+    /// Output channel on which the events were sent.
+    /// Empty for the events received, since the channel is only known to
+    /// the sender.
+    pub channel: String,
+    /// Target of the events sent or dropped, or source of the events received.
+    pub peer_id: crate::function_instance::ComponentId,
+    /// Number of events sent.
+    pub sent: u64,
+    /// Total size of the payloads of the events sent, in bytes.
+    pub sent_bytes: u64,
+    /// Number of events received.
+    pub received: u64,
+    /// Total size of the payloads of the events received, in bytes.
+    pub received_bytes: u64,
+    /// Number of events dropped, e.g., because the channel is not mapped.
+    pub dropped: u64,
+    /// Sum of the queueing delays of the events received, in fractional s.
+    pub queueing_delay_sum: f64,
+    /// Maximum queueing delay of the events received, in fractional s.
+    pub queueing_delay_max: f64,
+}
+
+impl ChannelMetrics {
+    pub fn score(&self) -> f64 {
+        self.timestamp_sec as f64 + (self.timestamp_ns as f64) / 1e9
+    }
+}
+
+impl std::fmt::Display for ChannelMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{},{},{},{},{},{},{},{},{}",
+            self.score(),
+            self.channel,
+            self.peer_id,
+            self.sent,
+            self.sent_bytes,
+            self.received,
+            self.received_bytes,
+            self.dropped,
+            self.queueing_delay_sum,
+            self.queueing_delay_max
+        )
+    }
+}
+
+/// Kind of an application-defined metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FunctionMetricKind {
+    /// Monotonically increasing value.
+    #[default]
+    Counter,
+    /// Value that can go up and down.
+    Gauge,
+    /// Distribution of values.
+    Histogram,
+}
+
+impl std::fmt::Display for FunctionMetricKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                FunctionMetricKind::Counter => "counter",
+                FunctionMetricKind::Gauge => "gauge",
+                FunctionMetricKind::Histogram => "histogram",
+            }
+        )
+    }
+}
+
+/// Application-defined metric of a function instance, with the values
+/// observed since the previous update.
+/// For counters, the values are the increments.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FunctionMetric {
+    /// Number of s since Unix epoch,
+    pub timestamp_sec: i64,
+    /// Number of ns since the last second boundary from Unix Epoch.
+    pub timestamp_ns: u32,
+    /// Function instance that emitted the metric.
+    pub component_id: crate::function_instance::ComponentId,
+    /// Kind of the metric.
+    pub kind: FunctionMetricKind,
+    /// Name of the metric.
+    pub name: String,
+    /// Labels of the metric.
+    pub labels: std::collections::BTreeMap<String, String>,
+    /// Number of values observed.
+    pub count: u64,
+    /// Sum of the values observed.
+    pub sum: f64,
+    /// Minimum value observed.
+    pub min: f64,
+    /// Maximum value observed.
+    pub max: f64,
+    /// Last value observed.
+    pub last: f64,
+}
+
+impl FunctionMetric {
+    pub fn score(&self) -> f64 {
+        self.timestamp_sec as f64 + (self.timestamp_ns as f64) / 1e9
+    }
+}
+
+impl std::fmt::Display for FunctionMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{},{},{},{},{},{},{},{}",
+            self.score(),
+            self.kind,
+            self.name,
+            self.labels
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<String>>()
+                .join(";"),
+            self.count,
+            self.sum,
+            self.min,
+            self.max,
+            self.last
+        )
+    }
+}
+
 type FunctionTimes = std::collections::HashMap<crate::function_instance::ComponentId, Vec<Sample>>;
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -230,6 +376,16 @@ pub struct NodePerformanceSamples {
     pub function_stop_times: FunctionTimes,
     pub function_transfer_times: FunctionTimes,
     pub function_log_entries: std::collections::HashMap<crate::function_instance::ComponentId, Vec<FunctionLogEntry>>,
+    /// Number of requests throttled by each resource instance since the
+    /// previous update.
+    pub resource_throttled: FunctionTimes,
+    /// Number of requests failed by each resource instance since the
+    /// previous update.
+    pub resource_failures: FunctionTimes,
+    /// Dataplane metrics, per component, channel, and peer.
+    pub dataplane_channels: Vec<ChannelMetrics>,
+    /// Application-defined metrics, per function instance, name, and labels.
+    pub function_metrics: Vec<FunctionMetric>,
 }
 
 impl std::fmt::Display for NodeHealthStatus {
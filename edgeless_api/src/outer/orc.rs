@@ -8,4 +8,6 @@ pub trait OrchestratorAPI: Send {
     fn resource_configuration_api(
         &mut self,
     ) -> Box<dyn crate::resource_configuration::ResourceConfigurationAPI<crate::function_instance::DomainManagedInstanceId>>;
+    fn placement_api(&mut self) -> Box<dyn crate::placement::PlacementAPI>;
+    fn introspection_api(&mut self) -> Box<dyn crate::introspection::IntrospectionAPI>;
 }
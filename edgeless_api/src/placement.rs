@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// API to compute the placement of workflow components within an
+/// orchestration domain, without actually starting them.
+#[async_trait::async_trait]
+pub trait PlacementAPI: PlacementAPIClone + Sync + Send {
+    async fn plan(&mut self, request: crate::workflow_instance::SpawnWorkflowRequest) -> anyhow::Result<crate::workflow_instance::WorkflowPlan>;
+}
+
+// https://stackoverflow.com/a/30353928
+pub trait PlacementAPIClone {
+    fn clone_box(&self) -> Box<dyn PlacementAPI>;
+}
+impl<T> PlacementAPIClone for T
+where
+    T: 'static + PlacementAPI + Clone,
+{
+    fn clone_box(&self) -> Box<dyn PlacementAPI> {
+        Box::new(self.clone())
+    }
+}
+impl Clone for Box<dyn PlacementAPI> {
+    fn clone(&self) -> Box<dyn PlacementAPI> {
+        self.clone_box()
+    }
+}
@@ -186,6 +186,9 @@ impl SpawnWorkflowRequest {
 pub struct WorkflowInfo {
     pub request: SpawnWorkflowRequest,
     pub status: WorkflowInstance,
+    /// Health of the functions/resources with restarts or probes, where
+    /// `component_id` is the identifier in `status.domain_mapping`.
+    pub health: Vec<crate::function_lifecycle::ComponentHealth>,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize)]
@@ -201,6 +204,98 @@ pub struct MigrateWorkflowRequest {
     pub component: String,
 }
 
+/// Proposed placement of a function/resource, as computed by a dry run.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct ComponentPlacement {
+    pub name: String,
+    /// Orchestration domain selected, empty if none.
+    pub domain_id: String,
+    /// Node selected within the orchestration domain, if any.
+    pub node_id: Option<crate::function_instance::NodeId>,
+    /// All the nodes in the orchestration domain that could host the component.
+    pub feasible_nodes: Vec<crate::function_instance::NodeId>,
+    /// Reasons why the component cannot be placed, empty if satisfiable.
+    pub reasons: Vec<String>,
+}
+
+impl ComponentPlacement {
+    /// Return a placement for a component that cannot be satisfied.
+    pub fn unsatisfiable(name: &str, domain_id: &str, reasons: Vec<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            domain_id: domain_id.to_string(),
+            node_id: None,
+            feasible_nodes: vec![],
+            reasons,
+        }
+    }
+
+    /// Return true if a node has been found for this component.
+    pub fn is_satisfiable(&self) -> bool {
+        self.reasons.is_empty() && self.node_id.is_some()
+    }
+}
+
+/// Result of a workflow dry run.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct WorkflowPlan {
+    pub components: Vec<ComponentPlacement>,
+}
+
+impl WorkflowPlan {
+    /// Return true if all the components of the workflow can be placed.
+    pub fn is_feasible(&self) -> bool {
+        !self.components.is_empty() && self.components.iter().all(|x| x.is_satisfiable())
+    }
+}
+
+/// Request of the log entries of the functions of a workflow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowLogsRequest {
+    pub workflow_id: WorkflowId,
+    /// Name of the function, all the functions of the workflow if empty.
+    pub component: String,
+    /// Sequence number of the last entry already received, by domain
+    /// identifier: only the entries with a greater one are returned, all
+    /// of them for the domains that are not present.
+    pub since_sequences: std::collections::HashMap<String, u64>,
+}
+
+/// Log entry of a function of a workflow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowLogEntry {
+    /// Name of the function within the workflow.
+    pub component: String,
+    /// Domain hosting the function.
+    pub domain_id: String,
+    /// Node hosting the function instance that emitted the log.
+    pub node_id: crate::function_instance::NodeId,
+    /// Sequence number assigned by the orchestrator of the domain.
+    pub sequence: u64,
+    pub entry: crate::node_registration::FunctionLogEntry,
+}
+
+impl std::fmt::Display for WorkflowLogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{:09} {:5} {} ({}@{}) {}: {}",
+            self.entry.timestamp_sec,
+            self.entry.timestamp_ns,
+            self.entry.level,
+            self.component,
+            self.node_id,
+            self.domain_id,
+            self.entry.target,
+            self.entry.message
+        )?;
+        for (key, value) in &self.entry.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
 #[async_trait::async_trait]
 pub trait WorkflowInstanceAPI: WorkflowInstanceAPIClone + Send + Sync {
     async fn start(&mut self, request: SpawnWorkflowRequest) -> anyhow::Result<SpawnWorkflowResponse>;
@@ -212,6 +307,8 @@ pub trait WorkflowInstanceAPI: WorkflowInstanceAPIClone + Send + Sync {
         domain_id: String,
     ) -> anyhow::Result<std::collections::HashMap<String, crate::domain_registration::DomainCapabilities>>;
     async fn migrate(&mut self, request: MigrateWorkflowRequest) -> anyhow::Result<SpawnWorkflowResponse>;
+    async fn dry_run(&mut self, request: SpawnWorkflowRequest) -> anyhow::Result<WorkflowPlan>;
+    async fn logs(&mut self, request: WorkflowLogsRequest) -> anyhow::Result<Vec<WorkflowLogEntry>>;
 }
 
 // https://stackoverflow.com/a/30353928
@@ -50,6 +50,31 @@ impl EventMetadata {
     pub fn empty_dangling_root(offset: u64) -> Self {
         Self::from(TraceId::INVALID, SpanId::from(offset))
     }
+
+    /// Create the metadata of the root span of a new trace.
+    pub fn new_root() -> Self {
+        Self::from(TraceId::from(uuid::Uuid::new_v4().as_u128()), Self::new_span_id())
+    }
+
+    /// Create the metadata of a new span in the same trace, or the root span
+    /// of a new trace if this metadata does not belong to a valid trace.
+    pub fn new_child(&self) -> Self {
+        if self.has_trace() {
+            Self::from(self.trace_id(), Self::new_span_id())
+        } else {
+            Self::new_root()
+        }
+    }
+
+    /// True if the metadata belongs to a valid trace.
+    pub fn has_trace(&self) -> bool {
+        self.trace_id() != TraceId::INVALID
+    }
+
+    fn new_span_id() -> SpanId {
+        // The first half of a UUID v4 is never zero, because of the version.
+        SpanId::from(uuid::Uuid::new_v4().as_u64_pair().0)
+    }
 }
 
 impl<'b, C> minicbor::Decode<'b, C> for EventMetadata {
@@ -110,4 +135,24 @@ mod test {
         let em_2 = EventMetadata::from_bytes(x, y);
         assert_eq!(em_1, em_2)
     }
+
+    #[test]
+    fn test_event_metadata_new_child() {
+        let root = EventMetadata::new_root();
+        assert!(root.has_trace());
+        assert!(root.span_context().is_valid());
+        assert_ne!(root.trace_id(), EventMetadata::new_root().trace_id());
+
+        let child = root.new_child();
+        assert_eq!(root.trace_id(), child.trace_id());
+        assert_ne!(root.span_id(), child.span_id());
+        assert!(child.span_context().is_valid());
+
+        for empty in [EventMetadata::empty_new_root(), EventMetadata::empty_dangling_root(0x42a42bdecaf00050u64)] {
+            assert!(!empty.has_trace());
+            let child = empty.new_child();
+            assert!(child.has_trace());
+            assert!(child.span_context().is_valid());
+        }
+    }
 }
@@ -58,6 +58,8 @@ pub async fn edgeless_bal_main(settings: EdgelessBalSettings) {
         } else {
             None
         },
+        settings.telemetry.traces_url.clone(),
+        settings.telemetry.log_sink(),
     )
     .await
     {
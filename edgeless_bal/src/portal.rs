@@ -201,22 +201,34 @@ impl PortalResource {
                 } = dataplane_in_handle.receive_next().await;
                 let started = edgeless_node::resources::observe_transfer(created, &mut telemetry_handle);
 
+                // The events crossing the portal remain in the same trace.
+                let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start(
+                    telemetry_handle.as_ref(),
+                    "portal",
+                    edgeless_telemetry::telemetry_events::SpanKind::Internal,
+                    &metadata,
+                );
+                span.set_attribute("edgeless.portal_role", role);
+                span.set_attribute("edgeless.domain", &domain_name);
+
                 let need_reply = match message {
                     Message::Call(msg) => {
-                        let reply = dataplane_out_handle
-                            .call(target_id, msg, &edgeless_api::function_instance::EventMetadata::empty_new_root())
-                            .await;
+                        let reply = dataplane_out_handle.call(target_id, msg, &span.metadata).await;
+                        if reply == edgeless_dataplane::core::CallRet::Err {
+                            span.set_error("call failed");
+                        }
                         dataplane_in_handle.reply(source_id, channel_id, reply, &metadata).await;
                         true
                     }
                     Message::Cast(msg) => {
-                        dataplane_out_handle.send(target_id, msg, &metadata).await;
+                        dataplane_out_handle.send(target_id, msg, &span.metadata).await;
                         false
                     }
                     _ => {
                         continue;
                     }
                 };
+                span.end(telemetry_handle.as_mut());
 
                 edgeless_node::resources::observe_execution(started, &mut telemetry_handle, need_reply);
             }
@@ -21,11 +21,24 @@ use tokio_util::codec::{BytesCodec, FramedRead};
 #[derive(Debug, clap::Subcommand)]
 enum WorkflowCommands {
     Start { spec_file: String },
+    Plan { spec_file: String },
     Stop { id: String },
     Migrate { id: String, domain: String },
     MigrateComponent { id: String, domain: String, component: String },
     List {},
     Inspect { id: String },
+    Logs(WorkflowLogsArgs),
+}
+
+#[derive(Debug, clap::Args)]
+struct WorkflowLogsArgs {
+    id: String,
+    /// Keep polling for new log entries until interrupted.
+    #[arg(long, default_value_t = false)]
+    follow: bool,
+    /// Only show the log entries of the function with this name.
+    #[arg(long, default_value_t = String::from(""))]
+    component: String,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -88,9 +101,15 @@ struct Args {
     version: bool,
 }
 
+/// Environment variable that, if set, overrides the API token of the configuration file.
+const API_TOKEN_ENV: &str = "EDGELESS_API_TOKEN";
+
 #[derive(serde::Deserialize, serde::Serialize)]
 struct CLiConfig {
     controller_url: String,
+    /// Bearer token (static or JWT) used to authenticate with the ε-CON.
+    #[serde(default)]
+    api_token: Option<String>,
     function_repository: Option<FunctionRepositoryConfig>,
 }
 
@@ -98,6 +117,7 @@ impl Default for CLiConfig {
     fn default() -> Self {
         Self {
             controller_url: String::from("http://127.0.0.1:7001"),
+            api_token: None,
             function_repository: Some(FunctionRepositoryConfig::default()),
         }
     }
@@ -122,7 +142,8 @@ async fn wf_client(config_file: &str) -> anyhow::Result<Box<dyn edgeless_api::wo
     );
 
     let conf: CLiConfig = toml::from_str(&std::fs::read_to_string(config_file).unwrap()).unwrap();
-    let mut con_client = edgeless_api::grpc_impl::outer::controller::ControllerAPIClient::new(&conf.controller_url).await;
+    let api_token = std::env::var(API_TOKEN_ENV).ok().or(conf.api_token);
+    let mut con_client = edgeless_api::grpc_impl::outer::controller::ControllerAPIClient::new_with_token(&conf.controller_url, api_token).await?;
     Ok(con_client.workflow_instance_api())
 }
 
@@ -174,12 +195,70 @@ async fn workflow_inspect(wf_client: &mut Box<dyn edgeless_api::workflow_instanc
     for (name, annotation) in info.request.annotations {
         println!("W_ANN {} -> {}", name, annotation);
     }
-    for mapping in info.status.domain_mapping {
+    for mapping in &info.status.domain_mapping {
         println!("MAP {} -> {} [logical ID {}]", mapping.name, mapping.domain_id, mapping.function_id);
     }
+    for health in info.health {
+        if let Some(mapping) = info.status.domain_mapping.iter().find(|x| x.function_id == health.component_id) {
+            println!("HEALTH {}: {}", mapping.name, health);
+        }
+    }
     Ok(())
 }
 
+/// Interval between two consecutive polls of the log entries in follow mode.
+const LOGS_FOLLOW_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+async fn workflow_logs(
+    wf_client: &mut Box<dyn edgeless_api::workflow_instance::WorkflowInstanceAPI>,
+    id: &str,
+    component: &str,
+    follow: bool,
+) -> anyhow::Result<()> {
+    let mut request = edgeless_api::workflow_instance::WorkflowLogsRequest {
+        workflow_id: edgeless_api::workflow_instance::WorkflowId::new(id)?,
+        component: component.to_string(),
+        since_sequences: std::collections::HashMap::new(),
+    };
+    loop {
+        for entry in wf_client.logs(request.clone()).await? {
+            println!("{}", entry);
+            let since_sequence = request.since_sequences.entry(entry.domain_id.clone()).or_default();
+            *since_sequence = (*since_sequence).max(entry.sequence);
+        }
+        if !follow {
+            return Ok(());
+        }
+        tokio::time::sleep(LOGS_FOLLOW_INTERVAL).await;
+    }
+}
+
+fn workflow_plan_print(plan: &edgeless_api::workflow_instance::WorkflowPlan) {
+    for component in &plan.components {
+        if component.is_satisfiable() {
+            println!(
+                "PLACE {} -> {} [node {}, {} feasible]",
+                component.name,
+                component.domain_id,
+                component.node_id.unwrap_or_default(),
+                component.feasible_nodes.len()
+            );
+        } else {
+            println!(
+                "UNSATISFIABLE {}{}: {}",
+                component.name,
+                if component.domain_id.is_empty() {
+                    String::default()
+                } else {
+                    format!(" in {}", component.domain_id)
+                },
+                component.reasons.join("; ")
+            );
+        }
+    }
+    println!("workflow {}", if plan.is_feasible() { "feasible" } else { "not feasible" });
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     env_logger::init();
@@ -232,6 +311,17 @@ async fn main() -> anyhow::Result<()> {
                             Err(err) => println!("{}", err),
                         }
                     }
+                    WorkflowCommands::Plan { spec_file } => {
+                        log::debug!("Plan Workflow");
+
+                        let workflow_spec: edgeless_api::workflow_instance::SpawnWorkflowRequest =
+                            serde_json::from_str(&std::fs::read_to_string(spec_file.clone())?)?;
+                        let parent_path = std::path::Path::new(&spec_file)
+                            .parent()
+                            .expect("cannot find the workflow spec's parent path");
+                        let workflow = edgeless_cli::workflow_spec_to_request(workflow_spec, parent_path)?;
+                        workflow_plan_print(&wf_client.dry_run(workflow).await?);
+                    }
                     WorkflowCommands::Stop { id } => {
                         if id.to_lowercase() == "all" {
                             for wf_id in wf_client.list().await? {
@@ -294,6 +384,7 @@ async fn main() -> anyhow::Result<()> {
                             workflow_inspect(&mut wf_client, &id).await?
                         }
                     }
+                    WorkflowCommands::Logs(args) => workflow_logs(&mut wf_client, &args.id, &args.component, args.follow).await?,
                 }
             }
             Commands::Function { function_command } => match function_command {
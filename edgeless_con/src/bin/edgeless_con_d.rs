@@ -41,7 +41,9 @@ fn main() -> anyhow::Result<()> {
     let async_runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(8).enable_all().build()?;
     let async_tasks = vec![async_runtime.spawn(edgeless_con::edgeless_con_main(conf.clone()))];
 
-    async_runtime.block_on(async { futures::future::join_all(async_tasks).await });
+    for res in async_runtime.block_on(async { futures::future::join_all(async_tasks).await }) {
+        res??;
+    }
 
     Ok(())
 }
@@ -43,6 +43,16 @@ pub(crate) enum ControllerRequest {
         // Reply Channel
         tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse>>,
     ),
+    DryRun(
+        edgeless_api::workflow_instance::SpawnWorkflowRequest,
+        // Reply Channel
+        tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan>>,
+    ),
+    Logs(
+        edgeless_api::workflow_instance::WorkflowLogsRequest,
+        // Reply Channel
+        tokio::sync::oneshot::Sender<anyhow::Result<Vec<edgeless_api::workflow_instance::WorkflowLogEntry>>>,
+    ),
 }
 
 pub(crate) enum DomainRegisterRequest {
@@ -69,7 +79,7 @@ enum ComponentType {
 type Task = std::pin::Pin<Box<dyn futures::Future<Output = ()> + Send>>;
 
 impl Controller {
-    pub fn new(persistence_filename: String) -> (Self, Task, Task) {
+    pub fn new(persistence_filename: String, orchestrator_token: Option<String>) -> (Self, Task, Task) {
         let (workflow_instance_sender, workflow_instance_receiver) = futures::channel::mpsc::unbounded();
         let (domain_register_sender, domain_register_receiver) = futures::channel::mpsc::unbounded();
         let (internal_sender, internal_receiver) = futures::channel::mpsc::unbounded();
@@ -77,6 +87,7 @@ impl Controller {
         let main_task = Box::pin(async move {
             let mut controller_task = controller_task::ControllerTask::new(
                 persistence_filename,
+                orchestrator_token,
                 workflow_instance_receiver,
                 domain_register_receiver,
                 internal_receiver,
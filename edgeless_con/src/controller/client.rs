@@ -101,4 +101,31 @@ impl edgeless_api::workflow_instance::WorkflowInstanceAPI for ControllerWorkflow
             Err(err) => Err(anyhow::anyhow!("Controller Channel Error: {}", err)),
         }
     }
+    async fn dry_run(
+        &mut self,
+        request: edgeless_api::workflow_instance::SpawnWorkflowRequest,
+    ) -> anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan> {
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan>>();
+        if let Err(err) = self.sender.send(super::ControllerRequest::DryRun(request, reply_sender)).await {
+            anyhow::bail!("Controller Channel Error: {}", err);
+        }
+        match reply_receiver.await {
+            Ok(ret) => ret,
+            Err(err) => Err(anyhow::anyhow!("Controller Channel Error: {}", err)),
+        }
+    }
+    async fn logs(
+        &mut self,
+        request: edgeless_api::workflow_instance::WorkflowLogsRequest,
+    ) -> anyhow::Result<Vec<edgeless_api::workflow_instance::WorkflowLogEntry>> {
+        let (reply_sender, reply_receiver) =
+            tokio::sync::oneshot::channel::<anyhow::Result<Vec<edgeless_api::workflow_instance::WorkflowLogEntry>>>();
+        if let Err(err) = self.sender.send(super::ControllerRequest::Logs(request, reply_sender)).await {
+            anyhow::bail!("Controller Channel Error: {}", err);
+        }
+        match reply_receiver.await {
+            Ok(ret) => ret,
+            Err(err) => Err(anyhow::anyhow!("Controller Channel Error: {}", err)),
+        }
+    }
 }
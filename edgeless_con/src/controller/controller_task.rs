@@ -16,6 +16,9 @@ pub struct OrchestratorDesc {
     pub refresh_deadline: std::time::SystemTime,
     pub counter: u64,
     pub nonce: u64,
+    /// Health of the functions with restarts or probes in the domain,
+    /// refreshed at every domain update.
+    pub component_health: std::collections::HashMap<edgeless_api::function_instance::ComponentId, edgeless_api::function_lifecycle::ComponentHealth>,
 }
 
 #[derive(Default, Debug)]
@@ -28,6 +31,7 @@ pub struct PortalDesc {
 
 pub struct ControllerTask {
     persistence_filename: String,
+    orchestrator_token: Option<String>,
     workflow_instance_receiver: futures::channel::mpsc::UnboundedReceiver<super::ControllerRequest>,
     domain_registration_receiver: futures::channel::mpsc::UnboundedReceiver<super::DomainRegisterRequest>,
     internal_receiver: futures::channel::mpsc::UnboundedReceiver<super::InternalRequest>,
@@ -49,6 +53,7 @@ struct PersistedState {
 impl ControllerTask {
     pub fn new(
         persistence_filename: String,
+        orchestrator_token: Option<String>,
         workflow_instance_receiver: futures::channel::mpsc::UnboundedReceiver<super::ControllerRequest>,
         domain_registration_receiver: futures::channel::mpsc::UnboundedReceiver<super::DomainRegisterRequest>,
         internal_receiver: futures::channel::mpsc::UnboundedReceiver<super::InternalRequest>,
@@ -56,6 +61,7 @@ impl ControllerTask {
         let orphan_workflows = ControllerTask::load_persistence(&persistence_filename);
         Self {
             persistence_filename,
+            orchestrator_token,
             workflow_instance_receiver,
             domain_registration_receiver,
             internal_receiver,
@@ -77,6 +83,7 @@ impl ControllerTask {
     ) -> Self {
         Self {
             persistence_filename: String::default(),
+            orchestrator_token: None,
             workflow_instance_receiver,
             domain_registration_receiver,
             internal_receiver,
@@ -234,6 +241,18 @@ impl ControllerTask {
                                 log::error!("Unhandled: {:?}", err);
                             }
                         }
+                        super::ControllerRequest::DryRun(request, reply_sender) => {
+                            let reply = self.dry_run(&request).await;
+                            if let Err(err) = reply_sender.send(reply) {
+                                log::error!("Unhandled: {:?}", err);
+                            }
+                        }
+                        super::ControllerRequest::Logs(request, reply_sender) => {
+                            let reply = self.logs(&request).await;
+                            if let Err(err) = reply_sender.send(reply) {
+                                log::error!("Unhandled: {:?}", err);
+                            }
+                        }
                     }
                 },
                 Some(req) = self.internal_receiver.next() => {
@@ -252,11 +271,13 @@ impl ControllerTask {
         &mut self,
         spawn_workflow_request: edgeless_api::workflow_instance::SpawnWorkflowRequest,
     ) -> anyhow::Result<edgeless_api::workflow_instance::SpawnWorkflowResponse, edgeless_api::workflow_instance::SpawnWorkflowRequest> {
-        if !spawn_workflow_request.annotations.is_empty() {
-            log::warn!(
-                "Workflow annotations ({}) are currently ignored",
-                spawn_workflow_request.annotations.len()
-            );
+        let num_ignored = spawn_workflow_request
+            .annotations
+            .keys()
+            .filter(|x| !edgeless_api::latency_slo::LatencySlo::is_slo_annotation(x))
+            .count();
+        if num_ignored > 0 {
+            log::warn!("Workflow annotations ({}) other than the latency SLO are currently ignored", num_ignored);
         }
 
         // Optimistically identify a new identifier for the workflow that
@@ -277,7 +298,8 @@ impl ControllerTask {
             None => {
                 // No single domain was able to host the workflow.
                 // Try again with multiple domains attached to the portal, if any.
-                let domain_assignments = self.domain_assignments_portal(&spawn_workflow_request);
+                let domain_assignments =
+                    Self::domain_assignments_portal(&self.orchestrators, &self.portal_desc, &spawn_workflow_request, &mut self.rng);
 
                 if domain_assignments.is_empty() {
                     Ok(edgeless_api::workflow_instance::SpawnWorkflowResponse::ResponseError(
@@ -653,6 +675,15 @@ impl ControllerTask {
                         })
                         .collect(),
                 },
+                health: workflow
+                    .domain_mapping
+                    .values()
+                    .filter_map(|elem| {
+                        self.orchestrators
+                            .get(&elem.domain_id)
+                            .and_then(|desc| desc.component_health.get(&elem.lid).cloned())
+                    })
+                    .collect(),
             })
         } else if let Some(request) = self.orphan_workflows.get(&wf_id) {
             Ok(edgeless_api::workflow_instance::WorkflowInfo {
@@ -661,6 +692,7 @@ impl ControllerTask {
                     workflow_id: wf_id.clone(),
                     domain_mapping: vec![],
                 },
+                health: vec![],
             })
         } else {
             anyhow::bail!("Unknown workflow identifier '{}", wf_id);
@@ -679,6 +711,70 @@ impl ControllerTask {
         Ok(ret)
     }
 
+    /// Return the log entries of the functions of a workflow, optionally
+    /// only those of a given function, collected from all the domains
+    /// hosting them and sorted by timestamp.
+    async fn logs(
+        &mut self,
+        request: &edgeless_api::workflow_instance::WorkflowLogsRequest,
+    ) -> anyhow::Result<Vec<edgeless_api::workflow_instance::WorkflowLogEntry>> {
+        let workflow = match self.active_workflows.get(&request.workflow_id) {
+            Some(workflow) => workflow,
+            None if self.orphan_workflows.contains_key(&request.workflow_id) => return Ok(vec![]),
+            None => anyhow::bail!("Unknown workflow identifier '{}'", request.workflow_id),
+        };
+        if !request.component.is_empty() && !workflow.domain_mapping.contains_key(&request.component) {
+            anyhow::bail!("Unknown component '{}' in workflow '{}'", request.component, request.workflow_id);
+        }
+
+        // Group the LIDs of the functions by domain, also keeping track of
+        // their names in the workflow.
+        let mut lids_by_domain = std::collections::HashMap::<String, Vec<edgeless_api::function_instance::ComponentId>>::new();
+        let mut names = std::collections::HashMap::new();
+        for component in workflow.domain_mapping.values() {
+            if matches!(component.component_type, super::ComponentType::Function)
+                && !component.domain_id.is_empty()
+                && (request.component.is_empty() || request.component == component.name)
+            {
+                lids_by_domain.entry(component.domain_id.clone()).or_default().push(component.lid);
+                names.insert(component.lid, component.name.clone());
+            }
+        }
+
+        let mut entries = vec![];
+        for (domain_id, component_ids) in lids_by_domain {
+            let desc = match self.orchestrators.get_mut(&domain_id) {
+                Some(desc) => desc,
+                None => continue,
+            };
+            let records = desc
+                .client
+                .introspection_api()
+                .function_logs(edgeless_api::introspection::FunctionLogsRequest {
+                    component_ids,
+                    since_sequence: request.since_sequences.get(&domain_id).copied().unwrap_or(0),
+                })
+                .await;
+            match records {
+                Ok(records) => entries.extend(records.into_iter().map(|record| edgeless_api::workflow_instance::WorkflowLogEntry {
+                    component: names.get(&record.component_id).cloned().unwrap_or_default(),
+                    domain_id: domain_id.clone(),
+                    node_id: record.node_id,
+                    sequence: record.sequence,
+                    entry: record.entry,
+                })),
+                Err(err) => log::warn!(
+                    "could not retrieve the logs of workflow '{}' from domain '{}': {}",
+                    request.workflow_id,
+                    domain_id,
+                    err
+                ),
+            }
+        }
+        entries.sort_by_key(|entry| (entry.entry.timestamp_sec, entry.entry.timestamp_ns));
+        Ok(entries)
+    }
+
     /// Update domain information.
     ///
     /// Also update portal domain.
@@ -708,13 +804,19 @@ impl ControllerTask {
                     update_domain_request.domain_id.clone(),
                     OrchestratorDesc {
                         client: Box::new(
-                            edgeless_api::grpc_impl::outer::orc::OrchestratorAPIClient::new(&update_domain_request.orchestrator_url, None).await?,
+                            edgeless_api::grpc_impl::outer::orc::OrchestratorAPIClient::new_with_token(
+                                &update_domain_request.orchestrator_url,
+                                None,
+                                self.orchestrator_token.clone(),
+                            )
+                            .await?,
                         ),
                         orchestrator_url: update_domain_request.orchestrator_url.clone(),
                         capabilities: update_domain_request.capabilities.clone(),
                         refresh_deadline: update_domain_request.refresh_deadline,
                         counter: update_domain_request.counter,
                         nonce: update_domain_request.nonce,
+                        component_health: std::collections::HashMap::new(),
                     },
                 );
 
@@ -747,7 +849,12 @@ impl ControllerTask {
                     if desc.orchestrator_url != update_domain_request.orchestrator_url {
                         desc.orchestrator_url = update_domain_request.orchestrator_url.clone();
                         desc.client = Box::new(
-                            edgeless_api::grpc_impl::outer::orc::OrchestratorAPIClient::new(&update_domain_request.orchestrator_url, None).await?,
+                            edgeless_api::grpc_impl::outer::orc::OrchestratorAPIClient::new_with_token(
+                                &update_domain_request.orchestrator_url,
+                                None,
+                                self.orchestrator_token.clone(),
+                            )
+                            .await?,
                         );
                     }
 
@@ -760,6 +867,11 @@ impl ControllerTask {
                     }
                 };
                 desc.refresh_deadline = update_domain_request.refresh_deadline;
+                desc.component_health = update_domain_request
+                    .component_health
+                    .iter()
+                    .map(|health| (health.component_id, health.clone()))
+                    .collect();
                 (Ok(response), update_portal_domain)
             }
         };
@@ -926,6 +1038,111 @@ impl ControllerTask {
         }
     }
 
+    /// Compute where the functions and resources of a workflow would be
+    /// deployed, without starting anything.
+    ///
+    /// The domains are assigned as in `start_workflow()`, then the
+    /// orchestrator of each domain selected is asked to plan the placement
+    /// of the components assigned to it on its nodes.
+    ///
+    /// The random-number generator is cloned so that the decisions made here
+    /// do not affect those of actual deployments.
+    async fn dry_run(
+        &mut self,
+        request: &edgeless_api::workflow_instance::SpawnWorkflowRequest,
+    ) -> anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan> {
+        request.is_valid()?;
+
+        let mut rng = self.rng.clone();
+        let domain_assignments = match Self::workflow_compatible_domains(&self.orchestrators, request).choose(&mut rng) {
+            Some(target_domain) => Self::fill_domains(request, target_domain),
+            None => Self::domain_assignments_portal(&self.orchestrators, &self.portal_desc, request, &mut rng),
+        };
+
+        let mut plan = edgeless_api::workflow_instance::WorkflowPlan::default();
+
+        if domain_assignments.is_empty() {
+            // No domain assignment is possible: explain for every component
+            // whether it is compatible with at least one domain.
+            let split_reason = if self.orchestrators.is_empty() {
+                String::from("no domains available")
+            } else if self.portal_desc.is_some() {
+                String::from("no single domain, nor the domains attached to the portal, can host the whole workflow")
+            } else {
+                String::from("no single domain can host the whole workflow and there is no portal domain")
+            };
+            for function in &request.functions {
+                let reason = if self.orchestrators.values().any(|desc| Self::is_function_compatible(desc, function)) {
+                    split_reason.clone()
+                } else {
+                    format!("no domain with run-time '{}'", function.class_specification.function_type)
+                };
+                plan.components.push(edgeless_api::workflow_instance::ComponentPlacement::unsatisfiable(
+                    &function.name,
+                    "",
+                    vec![reason],
+                ));
+            }
+            for resource in &request.resources {
+                let reason = if self.orchestrators.values().any(|desc| Self::is_resource_compatible(desc, resource)) {
+                    split_reason.clone()
+                } else {
+                    format!("no domain with resource class '{}'", resource.class_type)
+                };
+                plan.components.push(edgeless_api::workflow_instance::ComponentPlacement::unsatisfiable(
+                    &resource.name,
+                    "",
+                    vec![reason],
+                ));
+            }
+            return Ok(plan);
+        }
+
+        let domains = domain_assignments.values().cloned().collect::<std::collections::BTreeSet<String>>();
+        for domain in domains {
+            let domain_request = edgeless_api::workflow_instance::SpawnWorkflowRequest {
+                functions: request
+                    .functions
+                    .iter()
+                    .filter(|function| domain_assignments.get(&function.name) == Some(&domain))
+                    .cloned()
+                    .collect(),
+                resources: request
+                    .resources
+                    .iter()
+                    .filter(|resource| domain_assignments.get(&resource.name) == Some(&domain))
+                    .cloned()
+                    .collect(),
+                annotations: request.annotations.clone(),
+            };
+            let res = match self.orchestrators.get_mut(&domain) {
+                Some(desc) => desc.client.placement_api().plan(domain_request.clone()).await,
+                None => Err(anyhow::anyhow!("unknown domain")),
+            };
+            match res {
+                Ok(domain_plan) => {
+                    plan.components.extend(domain_plan.components.into_iter().map(|mut component| {
+                        component.domain_id = domain.clone();
+                        component
+                    }));
+                }
+                Err(err) => {
+                    log::warn!("could not plan workflow components in domain '{}': {}", domain, err);
+                    for name in domain_request.source_components() {
+                        plan.components.push(edgeless_api::workflow_instance::ComponentPlacement::unsatisfiable(
+                            &name,
+                            &domain,
+                            vec![format!("orchestrator error: {}", err)],
+                        ));
+                    }
+                }
+            }
+        }
+        plan.components.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(plan)
+    }
+
     async fn refresh(&mut self) {
         log::debug!("Checking domains");
 
@@ -1000,12 +1217,14 @@ impl ControllerTask {
     /// The map returned the function/resource name as key and the domain
     /// selected as value.
     fn domain_assignments_portal(
-        &mut self,
+        orchestrators: &std::collections::HashMap<String, OrchestratorDesc>,
+        portal_desc: &Option<PortalDesc>,
         workflow: &edgeless_api::workflow_instance::SpawnWorkflowRequest,
+        rng: &mut rand::rngs::StdRng,
     ) -> std::collections::HashMap<String, String> {
         let mut ret = std::collections::HashMap::new();
 
-        if let Some(portal_desc) = &self.portal_desc {
+        if let Some(portal_desc) = portal_desc {
             assert!(
                 portal_desc.domains.len() > 1,
                 "too few domains for a portal: {}",
@@ -1013,26 +1232,24 @@ impl ControllerTask {
             );
 
             for function in &workflow.functions {
-                let compatible_domains = self
-                    .orchestrators
+                let compatible_domains = orchestrators
                     .iter()
                     .filter(|(domain_id, desc)| portal_desc.domains.contains(*domain_id) && Self::is_function_compatible(desc, function))
                     .map(|(domain_id, _desc)| domain_id.clone())
                     .collect::<Vec<String>>();
-                if let Some(domain) = compatible_domains.choose(&mut self.rng) {
+                if let Some(domain) = compatible_domains.choose(rng) {
                     ret.insert(function.name.clone(), domain.clone());
                 } else {
                     return std::collections::HashMap::new();
                 }
             }
             for resource in &workflow.resources {
-                let compatible_domains = self
-                    .orchestrators
+                let compatible_domains = orchestrators
                     .iter()
                     .filter(|(domain_id, desc)| portal_desc.domains.contains(*domain_id) && Self::is_resource_compatible(desc, resource))
                     .map(|(domain_id, _desc)| domain_id.clone())
                     .collect::<Vec<String>>();
-                if let Some(domain) = compatible_domains.choose(&mut self.rng) {
+                if let Some(domain) = compatible_domains.choose(rng) {
                     ret.insert(resource.name.clone(), domain.clone());
                 } else {
                     return std::collections::HashMap::new();
@@ -1145,7 +1362,8 @@ impl ControllerTask {
             match Self::workflow_compatible_domains(&self.orchestrators, &workflow_request).choose(&mut self.rng) {
                 None => {
                     // Try again with multiple domains attached to the portal, if any.
-                    let domain_assignments = self.domain_assignments_portal(&workflow_request);
+                    let domain_assignments =
+                        Self::domain_assignments_portal(&self.orchestrators, &self.portal_desc, &workflow_request, &mut self.rng);
                     if domain_assignments.is_empty() {
                         // The workflow cannot be relocated.
                         workflow_requests_unfixable.insert(wf_id, workflow_request);
@@ -1206,12 +1424,20 @@ impl ControllerTask {
         // The state_specification configuration should be
         // read from the function annotations.
         log::debug!("state specifications currently forced to NodeLocal");
+
+        // The latency SLO of the workflow, if any, is enforced by the
+        // orchestrators through the annotations of its functions.
+        let mut annotations = function.annotations.clone();
+        annotations.extend(edgeless_api::latency_slo::LatencySlo::workflow_annotations(
+            &workflow.desired_state.annotations,
+        ));
+
         let response = self
             .fn_client(domain)
             .ok_or(format!("No function client for domain: {}", domain))?
             .start(edgeless_api::function_instance::SpawnFunctionRequest {
                 spec: function.class_specification.clone(),
-                annotations: function.annotations.clone(),
+                annotations,
                 state_specification: edgeless_api::function_instance::StateSpecification {
                     state_id: uuid::Uuid::new_v4(),
                     state_policy: edgeless_api::function_instance::StatePolicy::NodeLocal,
@@ -45,6 +45,14 @@ impl edgeless_api::outer::orc::OrchestratorAPI for MockOrchestrator {
     ) -> Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::DomainManagedInstanceId>> {
         Box::new(MockResourceConfigurationAPI { sender: self.sender.clone() })
     }
+
+    fn placement_api(&mut self) -> Box<dyn edgeless_api::placement::PlacementAPI> {
+        Box::new(MockPlacementAPI {})
+    }
+
+    fn introspection_api(&mut self) -> Box<dyn edgeless_api::introspection::IntrospectionAPI> {
+        Box::new(MockIntrospectionAPI {})
+    }
 }
 
 #[derive(Clone)]
@@ -57,6 +65,73 @@ struct MockResourceConfigurationAPI {
     sender: futures::channel::mpsc::UnboundedSender<MockFunctionInstanceEvent>,
 }
 
+#[derive(Clone)]
+struct MockPlacementAPI {}
+
+#[derive(Clone)]
+struct MockIntrospectionAPI {}
+
+#[async_trait::async_trait]
+impl edgeless_api::introspection::IntrospectionAPI for MockIntrospectionAPI {
+    async fn snapshot(&mut self) -> anyhow::Result<edgeless_api::introspection::DomainSnapshot> {
+        Ok(edgeless_api::introspection::DomainSnapshot::default())
+    }
+    async fn add_deploy_intents(&mut self, _intents: Vec<edgeless_api::introspection::DeployIntent>) -> anyhow::Result<()> {
+        Ok(())
+    }
+    async fn function_logs(
+        &mut self,
+        request: edgeless_api::introspection::FunctionLogsRequest,
+    ) -> anyhow::Result<Vec<edgeless_api::introspection::FunctionLogRecord>> {
+        // Every function has a single log entry, just after the given sequence number.
+        Ok(request
+            .component_ids
+            .into_iter()
+            .map(|component_id| edgeless_api::introspection::FunctionLogRecord {
+                component_id,
+                node_id: uuid::Uuid::nil(),
+                sequence: request.since_sequence + 1,
+                entry: edgeless_api::node_registration::FunctionLogEntry {
+                    timestamp_sec: 101,
+                    target: String::from("target"),
+                    message: format!("log of {}", component_id),
+                    level: String::from("Info"),
+                    ..Default::default()
+                },
+            })
+            .collect())
+    }
+    async fn query_metrics(
+        &mut self,
+        _query: edgeless_api::introspection::MetricsQuery,
+    ) -> anyhow::Result<Vec<edgeless_api::introspection::MetricsSeries>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::placement::PlacementAPI for MockPlacementAPI {
+    async fn plan(
+        &mut self,
+        request: edgeless_api::workflow_instance::SpawnWorkflowRequest,
+    ) -> anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan> {
+        // Every component is placed on a node with nil UUID.
+        Ok(edgeless_api::workflow_instance::WorkflowPlan {
+            components: request
+                .source_components()
+                .into_iter()
+                .map(|name| edgeless_api::workflow_instance::ComponentPlacement {
+                    name,
+                    domain_id: String::default(),
+                    node_id: Some(uuid::Uuid::nil()),
+                    feasible_nodes: vec![uuid::Uuid::nil()],
+                    reasons: vec![],
+                })
+                .collect(),
+        })
+    }
+}
+
 #[async_trait::async_trait]
 impl edgeless_api::function_instance::FunctionInstanceAPI<edgeless_api::function_instance::DomainManagedInstanceId> for MockFunctionInstanceAPI {
     async fn start(
@@ -128,6 +203,7 @@ async fn test_setup() -> (
             refresh_deadline: std::time::SystemTime::now(),
             counter: 0,
             nonce: 42,
+            component_health: std::collections::HashMap::new(),
         },
     )]);
 
@@ -209,6 +285,50 @@ async fn single_function_start_stop() {
     assert!(mock_orc_receiver.try_next().is_err());
 }
 
+#[tokio::test]
+async fn workflow_latency_slo_forwarded() {
+    let (mut wf_client, mut mock_orc_receiver) = test_setup().await;
+
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            functions: vec![edgeless_api::workflow_instance::WorkflowFunction {
+                name: "f1".to_string(),
+                class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+                    id: "fc1".to_string(),
+                    function_type: "RUST_WASM".to_string(),
+                    version: "0.1".to_string(),
+                    binary: None,
+                    code: None,
+                    outputs: vec![],
+                },
+                output_mapping: std::collections::HashMap::new(),
+                annotations: std::collections::HashMap::from([("slo_latency_ms".to_string(), "10".to_string())]),
+            }],
+            resources: vec![],
+            annotations: std::collections::HashMap::from([
+                ("slo_latency_ms".to_string(), "50".to_string()),
+                ("other".to_string(), "value".to_string()),
+            ]),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(response, SpawnWorkflowResponse::WorkflowInstance(_)));
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    if let MockFunctionInstanceEvent::StartFunction((_id, spawn_req)) = mock_orc_receiver.try_next().unwrap().unwrap() {
+        assert_eq!(
+            std::collections::HashMap::from([
+                ("slo_latency_ms".to_string(), "10".to_string()),
+                ("workflow_slo_latency_ms".to_string(), "50".to_string()),
+            ]),
+            spawn_req.annotations
+        );
+    } else {
+        panic!();
+    }
+}
+
 #[tokio::test]
 async fn resource_to_function_start_stop() {
     let (mut wf_client, mut mock_orc_receiver) = test_setup().await;
@@ -433,3 +553,141 @@ async fn function_link_loop_start_stop() {
     }
     assert!(fids.is_empty());
 }
+
+#[tokio::test]
+async fn workflow_dry_run() {
+    let (mut wf_client, mut mock_orc_receiver) = test_setup().await;
+
+    let make_function = |name: &str, function_type: &str| edgeless_api::workflow_instance::WorkflowFunction {
+        name: name.to_string(),
+        class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+            id: "fc1".to_string(),
+            function_type: function_type.to_string(),
+            version: "0.1".to_string(),
+            binary: None,
+            code: None,
+            outputs: vec![],
+        },
+        output_mapping: std::collections::HashMap::new(),
+        annotations: std::collections::HashMap::new(),
+    };
+    let make_resource = |name: &str, class_type: &str| edgeless_api::workflow_instance::WorkflowResource {
+        name: name.to_string(),
+        class_type: class_type.to_string(),
+        output_mapping: std::collections::HashMap::new(),
+        configurations: std::collections::HashMap::new(),
+    };
+
+    // Feasible workflow.
+    let plan = wf_client
+        .dry_run(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            functions: vec![make_function("f1", "RUST_WASM")],
+            resources: vec![make_resource("r1", "test-res")],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    assert!(plan.is_feasible());
+    assert_eq!(
+        vec!["f1".to_string(), "r1".to_string()],
+        plan.components.iter().map(|x| x.name.clone()).collect::<Vec<String>>()
+    );
+    for component in &plan.components {
+        assert_eq!("domain-1", component.domain_id);
+        assert_eq!(Some(uuid::Uuid::nil()), component.node_id);
+    }
+
+    // Unfeasible workflow.
+    let plan = wf_client
+        .dry_run(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            functions: vec![make_function("f1", "RUST_WASM"), make_function("f2", "CONTAINER")],
+            resources: vec![make_resource("r1", "no-res")],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    assert!(!plan.is_feasible());
+    assert_eq!(3, plan.components.len());
+    for component in &plan.components {
+        assert!(component.domain_id.is_empty());
+        assert!(component.node_id.is_none());
+        assert_eq!(1, component.reasons.len());
+    }
+    assert_eq!(
+        vec!["no domain with run-time 'CONTAINER'".to_string()],
+        plan.components.iter().find(|x| x.name == "f2").unwrap().reasons
+    );
+    assert_eq!(
+        vec!["no domain with resource class 'no-res'".to_string()],
+        plan.components.iter().find(|x| x.name == "r1").unwrap().reasons
+    );
+
+    // Nothing is started in a dry run.
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    assert!(mock_orc_receiver.try_next().is_err());
+}
+
+#[tokio::test]
+async fn workflow_logs() {
+    let (mut wf_client, _mock_orc_receiver) = test_setup().await;
+
+    let make_function = |name: &str| edgeless_api::workflow_instance::WorkflowFunction {
+        name: name.to_string(),
+        class_specification: edgeless_api::function_instance::FunctionClassSpecification {
+            id: "fc1".to_string(),
+            function_type: "RUST_WASM".to_string(),
+            version: "0.1".to_string(),
+            binary: None,
+            code: None,
+            outputs: vec![],
+        },
+        output_mapping: std::collections::HashMap::new(),
+        annotations: std::collections::HashMap::new(),
+    };
+    let response = wf_client
+        .start(edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            functions: vec![make_function("f1"), make_function("f2")],
+            resources: vec![edgeless_api::workflow_instance::WorkflowResource {
+                name: "r1".to_string(),
+                class_type: "test-res".to_string(),
+                output_mapping: std::collections::HashMap::new(),
+                configurations: std::collections::HashMap::new(),
+            }],
+            annotations: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    let instance = match &response {
+        SpawnWorkflowResponse::ResponseError(err) => panic!("{}", err),
+        SpawnWorkflowResponse::WorkflowInstance(val) => val,
+    };
+    let lid = |name: &str| instance.domain_mapping.iter().find(|x| x.name == name).unwrap().function_id;
+
+    let make_request = |component: &str| edgeless_api::workflow_instance::WorkflowLogsRequest {
+        workflow_id: instance.workflow_id.clone(),
+        component: component.to_string(),
+        since_sequences: std::collections::HashMap::from([("domain-1".to_string(), 100), ("domain-2".to_string(), 200)]),
+    };
+
+    // Only the functions have log entries, enriched with their names and domains.
+    let mut entries = wf_client.logs(make_request("")).await.unwrap();
+    entries.sort_by(|a, b| a.component.cmp(&b.component));
+    assert_eq!(vec!["f1", "f2"], entries.iter().map(|x| x.component.as_str()).collect::<Vec<&str>>());
+    for entry in &entries {
+        assert_eq!("domain-1", entry.domain_id);
+        assert_eq!(101, entry.sequence);
+        assert_eq!(format!("log of {}", lid(&entry.component)), entry.entry.message);
+    }
+
+    let entries = wf_client.logs(make_request("f2")).await.unwrap();
+    assert_eq!(vec!["f2"], entries.iter().map(|x| x.component.as_str()).collect::<Vec<&str>>());
+
+    assert!(wf_client.logs(make_request("r1")).await.unwrap().is_empty());
+    assert!(wf_client.logs(make_request("f3")).await.is_err());
+
+    let mut request = make_request("");
+    request.workflow_id = edgeless_api::workflow_instance::WorkflowId {
+        workflow_id: uuid::Uuid::new_v4(),
+    };
+    assert!(wf_client.logs(request).await.is_err());
+}
@@ -10,9 +10,17 @@ pub struct EdgelessConSettings {
     pub controller_url: String,
     pub domain_register_url: String,
     pub persistence_filename: String,
+    /// Authentication and authorization of the clients of the
+    /// WorkflowInstance API. If not present, all the requests are accepted.
+    #[serde(default)]
+    pub auth: Option<edgeless_api::grpc_impl::auth::AuthConfig>,
+    /// Bearer token used to authenticate with the Introspection API of the
+    /// orchestrators, which is needed if these require authentication.
+    #[serde(default)]
+    pub orchestrator_token: Option<String>,
 }
 
-pub async fn edgeless_con_main(settings: EdgelessConSettings) {
+pub async fn edgeless_con_main(settings: EdgelessConSettings) -> anyhow::Result<()> {
     log::info!(
         "Starting Edgeless Controller at {}, persistence at {}",
         settings.controller_url,
@@ -20,13 +28,14 @@ pub async fn edgeless_con_main(settings: EdgelessConSettings) {
     );
     log::debug!("Settings: {:?}", settings);
 
-    let (mut controller, controller_task, refresh_task) = controller::Controller::new(settings.persistence_filename);
+    let (mut controller, controller_task, refresh_task) = controller::Controller::new(settings.persistence_filename, settings.orchestrator_token);
 
     let workflow_instance_server_task = edgeless_api::grpc_impl::outer::controller::WorkflowInstanceAPIServer::run(
         controller.get_workflow_instance_client(),
         settings.controller_url,
         Some(edgeless_api::grpc_impl::tls_config::TlsConfig::global_server().clone()),
-    );
+        settings.auth,
+    )?;
     let domain_register_server_task = edgeless_api::grpc_impl::outer::domain_register::DomainRegistrationAPIServer::run(
         controller.get_domain_register_client(),
         settings.domain_register_url,
@@ -34,6 +43,8 @@ pub async fn edgeless_con_main(settings: EdgelessConSettings) {
     );
 
     futures::join!(controller_task, refresh_task, workflow_instance_server_task, domain_register_server_task);
+
+    Ok(())
 }
 
 pub fn edgeless_con_default_conf() -> String {
@@ -41,6 +52,8 @@ pub fn edgeless_con_default_conf() -> String {
         controller_url: String::from("http://127.0.0.1:7001"),
         domain_register_url: String::from("http://127.0.0.1:7002"),
         persistence_filename: String::from("controller.save"),
+        auth: None,
+        orchestrator_token: None,
     };
 
     toml::to_string(&con_conf).expect("Wrong")
@@ -23,8 +23,9 @@ pub struct DataplaneHandle {
     slf: edgeless_api::function_instance::InstanceId,
     receiver: std::sync::Arc<tokio::sync::Mutex<futures::channel::mpsc::UnboundedReceiver<DataplaneEvent>>>,
     output_chain: std::sync::Arc<tokio::sync::Mutex<Vec<Box<dyn DataPlaneLink>>>>,
-    receiver_overwrites: std::sync::Arc<tokio::sync::Mutex<TemporaryReceivers>>,
-    next_id: u64,
+    receiver_overwrites: std::sync::Arc<std::sync::Mutex<TemporaryReceivers>>,
+    // Shared by all the clones of the handle, since they share the temporary receivers.
+    next_id: std::sync::Arc<std::sync::atomic::AtomicU64>,
 }
 
 impl DataplaneHandle {
@@ -34,7 +35,7 @@ impl DataplaneHandle {
         receiver: futures::channel::mpsc::UnboundedReceiver<DataplaneEvent>,
     ) -> Self {
         let (main_sender, main_receiver) = futures::channel::mpsc::unbounded::<DataplaneEvent>();
-        let receiver_overwrites = std::sync::Arc::new(tokio::sync::Mutex::new(TemporaryReceivers {
+        let receiver_overwrites = std::sync::Arc::new(std::sync::Mutex::new(TemporaryReceivers {
             temporary_receivers: std::collections::HashMap::new(),
         }));
 
@@ -52,7 +53,8 @@ impl DataplaneHandle {
                     metadata,
                 }) = receiver.next().await
                 {
-                    if let Some(sender) = clone_overwrites.lock().await.temporary_receivers.remove(&channel_id) {
+                    let sender = clone_overwrites.lock().unwrap().temporary_receivers.remove(&channel_id);
+                    if let Some(sender) = sender {
                         match sender.send((source_id, message.clone())) {
                             Ok(_) => {
                                 continue;
@@ -86,7 +88,7 @@ impl DataplaneHandle {
             receiver: std::sync::Arc::new(tokio::sync::Mutex::new(main_receiver)),
             output_chain: std::sync::Arc::new(tokio::sync::Mutex::new(output_chain)),
             receiver_overwrites,
-            next_id: 1,
+            next_id: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(1)),
         }
     }
 
@@ -119,13 +121,14 @@ impl DataplaneHandle {
     }
 
     /// Send a `cast` event.
+    /// Return false if no link could route the event to the target.
     pub async fn send(
         &mut self,
         target: edgeless_api::function_instance::InstanceId,
         msg: String,
         metadata: &edgeless_api::function_instance::EventMetadata,
-    ) {
-        self.send_inner(target, Message::Cast(msg), timestamp_utc(), 0, metadata).await;
+    ) -> bool {
+        self.send_inner(target, Message::Cast(msg), timestamp_utc(), 0, metadata).await
     }
 
     // Send a `call` event and wait for the return event.
@@ -137,11 +140,17 @@ impl DataplaneHandle {
         metadata: &edgeless_api::function_instance::EventMetadata,
     ) -> CallRet {
         let (sender, receiver) = futures::channel::oneshot::channel::<(edgeless_api::function_instance::InstanceId, Message)>();
-        let channel_id = self.next_id;
-        self.next_id += 1;
-        // Potential Leak: This is only received if a message is received (or the handle is dropped)
-        self.receiver_overwrites.lock().await.temporary_receivers.insert(channel_id, sender);
-        self.send_inner(target, Message::Call(msg), timestamp_utc(), channel_id, metadata).await;
+        let channel_id = self.next_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.receiver_overwrites.lock().unwrap().temporary_receivers.insert(channel_id, sender);
+        // The temporary receiver is removed when the reply is received or,
+        // otherwise, when this future is dropped, e.g., upon a timeout.
+        let _guard = TemporaryReceiverGuard {
+            receiver_overwrites: self.receiver_overwrites.clone(),
+            channel_id,
+        };
+        if !self.send_inner(target, Message::Call(msg), timestamp_utc(), channel_id, metadata).await {
+            return CallRet::Err;
+        }
         match receiver.await {
             Ok((_src, msg)) => match msg {
                 Message::CallRet(ret) => CallRet::Reply(ret),
@@ -181,14 +190,15 @@ impl DataplaneHandle {
         created: edgeless_api::function_instance::EventTimestamp,
         channel_id: u64,
         metadata: &edgeless_api::function_instance::EventMetadata,
-    ) {
+    ) -> bool {
         let mut lck = self.output_chain.lock().await;
         for link in &mut lck.iter_mut() {
             if link.handle_send(&target, msg.clone(), &self.slf, &created, channel_id, metadata).await == LinkProcessingResult::FINAL {
-                return;
+                return true;
             }
         }
         log::info!("Unprocessed Message: {:?}->{:?}", self.slf, target);
+        false
     }
 }
 
@@ -196,6 +206,20 @@ struct TemporaryReceivers {
     temporary_receivers: std::collections::HashMap<u64, futures::channel::oneshot::Sender<(edgeless_api::function_instance::InstanceId, Message)>>,
 }
 
+/// Remove the temporary receiver of a call when dropped.
+struct TemporaryReceiverGuard {
+    receiver_overwrites: std::sync::Arc<std::sync::Mutex<TemporaryReceivers>>,
+    channel_id: u64,
+}
+
+impl Drop for TemporaryReceiverGuard {
+    fn drop(&mut self) {
+        if let Ok(mut receiver_overwrites) = self.receiver_overwrites.lock() {
+            receiver_overwrites.temporary_receivers.remove(&self.channel_id);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DataplaneProvider {
     local_provider: std::sync::Arc<tokio::sync::Mutex<NodeLocalLinkProvider>>,
@@ -281,7 +305,7 @@ mod test {
         let mut handle_1 = provider.get_handle_for(fid_1).await;
         let mut handle_2 = provider.get_handle_for(fid_2).await;
 
-        handle_1.send(fid_2, "Test".to_string(), &metad_1).await;
+        assert!(handle_1.send(fid_2, "Test".to_string(), &metad_1).await);
 
         let res = handle_2.receive_next().await;
         assert_eq!(
@@ -295,6 +319,21 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn send_without_route() {
+        let node_id = uuid::Uuid::new_v4();
+        let fid_1 = edgeless_api::function_instance::InstanceId::new(node_id);
+        let fid_unknown = edgeless_api::function_instance::InstanceId::new(uuid::Uuid::new_v4());
+        let metad_1 = edgeless_api::function_instance::EventMetadata::from_uints(0x42a42bdecaf00020u128, 0x42a42bdecaf00021u64);
+
+        let mut provider = DataplaneProvider::new(node_id, "http://127.0.0.1:7095".to_string(), None).await;
+
+        let mut handle_1 = provider.get_handle_for(fid_1).await;
+
+        // Neither the local link nor a remote peer can deliver the event.
+        assert!(!handle_1.send(fid_unknown, "Test".to_string(), &metad_1).await);
+    }
+
     #[tokio::test]
     async fn local_call_with_return() {
         let node_id = uuid::Uuid::new_v4();
@@ -328,6 +367,65 @@ mod test {
         assert_eq!(std::mem::discriminant(&CallRet::NoReply), std::mem::discriminant(&repl));
     }
 
+    #[tokio::test]
+    async fn local_call_cancelled() {
+        let node_id = uuid::Uuid::new_v4();
+        let fid_1 = edgeless_api::function_instance::InstanceId::new(node_id);
+        let fid_2 = edgeless_api::function_instance::InstanceId::new(node_id);
+        let fid_unknown = edgeless_api::function_instance::InstanceId::new(uuid::Uuid::new_v4());
+        let metad_1 = edgeless_api::function_instance::EventMetadata::from_uints(0x42a42bdecaf00022u128, 0x42a42bdecaf00023u64);
+
+        let mut provider = DataplaneProvider::new(node_id, "http://127.0.0.1:7106".to_string(), None).await;
+
+        let mut handle_1 = provider.get_handle_for(fid_1).await;
+        let mut handle_2 = provider.get_handle_for(fid_2).await;
+
+        // A call that cannot be delivered fails immediately.
+        assert_eq!(
+            std::mem::discriminant(&CallRet::Err),
+            std::mem::discriminant(&handle_1.call(fid_unknown, "Test".to_string(), &metad_1).await)
+        );
+        assert!(handle_1.receiver_overwrites.lock().unwrap().temporary_receivers.is_empty());
+
+        // A call that times out does not leave its temporary receiver behind.
+        assert!(
+            tokio::time::timeout(
+                tokio::time::Duration::from_millis(100),
+                handle_1.call(fid_2, "Test".to_string(), &metad_1)
+            )
+            .await
+            .is_err()
+        );
+        assert!(handle_1.receiver_overwrites.lock().unwrap().temporary_receivers.is_empty());
+
+        // The late reply does not interfere with the next call.
+        let late_req = handle_2.receive_next().await;
+        handle_2
+            .reply(
+                late_req.source_id,
+                late_req.channel_id,
+                CallRet::Reply("late".to_string()),
+                &late_req.metadata,
+            )
+            .await;
+
+        let return_handle = {
+            let mut handle_1 = handle_1.clone();
+            let metad_1_cp = metad_1.clone();
+            tokio::spawn(async move { handle_1.call(fid_2, "Test".to_string(), &metad_1_cp).await })
+        };
+        let req = handle_2.receive_next().await;
+        assert_ne!(late_req.channel_id, req.channel_id);
+        handle_2
+            .reply(req.source_id, req.channel_id, CallRet::Reply("on time".to_string()), &req.metadata)
+            .await;
+        match return_handle.await.unwrap() {
+            CallRet::Reply(reply) => assert_eq!("on time", reply),
+            _ => panic!("wrong reply received"),
+        }
+        assert!(handle_1.receiver_overwrites.lock().unwrap().temporary_receivers.is_empty());
+    }
+
     #[tokio::test]
     async fn grpc_impl_e2e() {
         edgeless_api::grpc_impl::init_crypto();
@@ -22,6 +22,16 @@ unsafe extern "C" {
         out_len_ptr: *mut usize,
     ) -> i32;
     pub(crate) fn telemetry_log_asm(level: usize, target_ptr: *const u8, target_len: usize, msg_ptr: *const u8, msg_len: usize);
+    pub(crate) fn telemetry_log_fields_asm(
+        level: usize,
+        target_ptr: *const u8,
+        target_len: usize,
+        msg_ptr: *const u8,
+        msg_len: usize,
+        fields_ptr: *const u8,
+        fields_len: usize,
+    );
+    pub(crate) fn telemetry_metric_asm(kind: usize, name_ptr: *const u8, name_len: usize, labels_ptr: *const u8, labels_len: usize, value: f64);
     pub(crate) fn slf_asm(out_node_id_ptr: *mut u8, out_component_id_ptr: *mut u8);
     pub(crate) fn delayed_cast_asm(delay_ms: u64, target_ptr: *const u8, target_len: usize, payload_ptr: *const u8, payload_len: usize);
     pub(crate) fn sync_asm(data_ptr: *const u8, data_len: u32);
@@ -68,6 +68,53 @@ pub fn telemetry_log(level: usize, target: &str, msg: &str) {
     }
 }
 
+/// Log a message with structured fields, which are shipped together with it.
+/// `fields` is a comma-separated list of `key=value` pairs, which may be empty.
+pub fn telemetry_log_with_fields(level: usize, target: &str, msg: &str, fields: &str) {
+    unsafe {
+        crate::imports::telemetry_log_fields_asm(
+            level,
+            target.as_bytes().as_ptr(),
+            target.len(),
+            msg.as_bytes().as_ptr(),
+            msg.len(),
+            fields.as_bytes().as_ptr(),
+            fields.len(),
+        );
+    }
+}
+
+/// Increase an application-defined counter by a non-negative value.
+/// `labels` is a comma-separated list of `key=value` pairs, which may be empty.
+pub fn telemetry_counter(name: &str, labels: &str, value: f64) {
+    telemetry_metric(0, name, labels, value);
+}
+
+/// Set an application-defined gauge to the given value.
+/// `labels` is a comma-separated list of `key=value` pairs, which may be empty.
+pub fn telemetry_gauge(name: &str, labels: &str, value: f64) {
+    telemetry_metric(1, name, labels, value);
+}
+
+/// Add a sample to an application-defined histogram.
+/// `labels` is a comma-separated list of `key=value` pairs, which may be empty.
+pub fn telemetry_histogram(name: &str, labels: &str, value: f64) {
+    telemetry_metric(2, name, labels, value);
+}
+
+fn telemetry_metric(kind: usize, name: &str, labels: &str, value: f64) {
+    unsafe {
+        crate::imports::telemetry_metric_asm(
+            kind,
+            name.as_bytes().as_ptr(),
+            name.len(),
+            labels.as_bytes().as_ptr(),
+            labels.len(),
+            value,
+        );
+    }
+}
+
 pub fn slf() -> crate::InstanceId {
     unsafe {
         let mut id = crate::InstanceId {
@@ -92,6 +92,9 @@ pub struct EdgelessHTTPRequest {
     pub protocol: EdgelessHTTPProtocol,
     pub host: String,
     pub path: String,
+    /// Parameters captured from the path, if any.
+    #[serde(default)]
+    pub path_params: std::collections::HashMap<String, String>,
     pub body: Option<Vec<u8>>,
     pub headers: std::collections::HashMap<String, String>,
 }
@@ -102,3 +105,39 @@ pub struct EdgelessHTTPResponse {
     pub status: u16,
     pub headers: std::collections::HashMap<String, String>,
 }
+
+pub fn websocket_message_to_string(message: &EdgelessWebSocketMessage) -> String {
+    serde_json::to_string(message).unwrap()
+}
+
+pub fn websocket_message_from_string(message_str: &str) -> anyhow::Result<EdgelessWebSocketMessage> {
+    Ok(serde_json::from_str(message_str)?)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum EdgelessWebSocketEvent {
+    /// A client has connected.
+    Open,
+    /// Message received from, or to be sent to, a client.
+    #[default]
+    Message,
+    /// A client has disconnected, or has to be disconnected.
+    Close,
+}
+
+/// Message exchanged between functions and the clients connected to a
+/// websocket-ingress resource.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct EdgelessWebSocketMessage {
+    /// Identifier of the connection. If empty in a message sent to the
+    /// resource, then the message is sent to all the clients connected.
+    #[serde(default)]
+    pub connection_id: String,
+    #[serde(default)]
+    pub event: EdgelessWebSocketEvent,
+    /// Content of the message, base64-encoded if binary.
+    #[serde(default)]
+    pub data: String,
+    #[serde(default)]
+    pub binary: bool,
+}
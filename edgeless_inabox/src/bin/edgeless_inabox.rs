@@ -110,9 +110,13 @@ fn generate_configs(config_path: String, number_of_nodes: u32, initial_port: u16
             orchestrator_url_announced: announced_url(orchestrator_url),
             node_register_url: next_url(false),
             node_register_coap_url: None,
+            auth: None,
         },
         baseline: edgeless_orc::EdgelessOrcBaselineSettings {
             orchestration_strategy: edgeless_orc::OrchestrationStrategy::Random,
+            energy: edgeless_orc::EdgelessOrcEnergySettings::default(),
+            alerts: edgeless_orc::EdgelessOrcAlertSettings::default(),
+            metrics_store: edgeless_orc::EdgelessOrcMetricsStoreSettings::default(),
         },
         proxy: edgeless_orc::EdgelessOrcProxySettings {
             proxy_type: "None".to_string(),
@@ -127,6 +131,8 @@ fn generate_configs(config_path: String, number_of_nodes: u32, initial_port: u16
         controller_url,
         domain_register_url,
         persistence_filename: "controller.save".to_string(),
+        auth: None,
+        orchestrator_token: None,
     };
 
     // Nodes
@@ -150,6 +156,8 @@ fn generate_configs(config_path: String, number_of_nodes: u32, initial_port: u16
             telemetry: EdgelessNodeTelemetrySettings {
                 metrics_url: next_url(false),
                 performance_samples: false,
+                traces_url: String::new(),
+                ..Default::default()
             },
             wasm_runtime: Some(edgeless_node::EdgelessNodeWasmRuntimeSettings { enabled: true }),
             container_runtime: Some(EdgelessNodeContainerRuntimeSettings::default()),
@@ -157,6 +165,9 @@ fn generate_configs(config_path: String, number_of_nodes: u32, initial_port: u16
                 prepend_hostname: true,
                 http_ingress_url: Some(next_url(false)),
                 http_ingress_provider: Some(format!("http-ingress-{}", counter + 1)),
+                http_ingress_tls: None,
+                websocket_ingress_url: Some(next_url(false)),
+                websocket_ingress_provider: Some(format!("websocket-ingress-{}", counter + 1)),
                 http_egress_provider: Some(format!("http-egress-{}", counter + 1)),
                 http_poster_provider: Some(format!("http-poster-{}", counter + 1)),
                 file_pusher_provider: Some(FilePusherSettings::default()),
@@ -166,7 +177,11 @@ fn generate_configs(config_path: String, number_of_nodes: u32, initial_port: u16
                 ollama_provider: Some(OllamaProviderSettings::default()),
                 serverless_provider: Some(vec![ServerlessProviderSettings::default()]),
                 kafka_egress_provider: Some(String::default()),
+                kafka_ingress_provider: Some(String::default()),
                 sqlx_provider: Some(format!("sqlx-{}", counter + 1)),
+                mqtt_provider: Some(format!("mqtt-{}", counter + 1)),
+                object_store_provider: Some(format!("object-store-{}", counter + 1)),
+                object_store_root: Some(String::default()),
             }),
             user_node_capabilities: Some(edgeless_node::NodeCapabilitiesUser::default()),
             power_info: None,
@@ -41,10 +41,18 @@ pub fn edgeless_inabox_main(async_runtime: &tokio::runtime::Runtime, async_tasks
         async_tasks.push(async_runtime.spawn(edgeless_bal::edgeless_bal_main(bal_conf)));
     }
     if let Some(orc_conf) = orc_conf {
-        async_tasks.push(async_runtime.spawn(edgeless_orc::edgeless_orc_main(orc_conf)));
+        async_tasks.push(async_runtime.spawn(async move {
+            if let Err(err) = edgeless_orc::edgeless_orc_main(orc_conf).await {
+                log::error!("Could not start the orchestrator: {}", err);
+            }
+        }));
     }
     if let Some(con_conf) = con_conf {
-        async_tasks.push(async_runtime.spawn(edgeless_con::edgeless_con_main(con_conf)));
+        async_tasks.push(async_runtime.spawn(async move {
+            if let Err(err) = edgeless_con::edgeless_con_main(con_conf).await {
+                log::error!("Could not start the controller: {}", err);
+            }
+        }));
     }
     std::thread::sleep(std::time::Duration::from_millis(500));
     for node_conf in node_confs {
@@ -31,6 +31,10 @@ enum AgentRequest {
     // Node management API.
     UpdatePeers(edgeless_api::node_management::UpdatePeersRequest),
     Reset(),
+    CheckFunctionHealth(
+        edgeless_api::node_management::FunctionHealthRequest,
+        futures::channel::oneshot::Sender<edgeless_api::node_management::FunctionHealthResponse>,
+    ),
 }
 
 pub struct Agent {
@@ -91,6 +95,13 @@ impl Agent {
         // value: provider_id
         let mut resource_instances = std::collections::HashMap::<edgeless_api::function_instance::ComponentId, String>::new();
 
+        // Dataplane handle used to call() function instances for probes.
+        // Its clones share the channel IDs and the pending calls, hence
+        // probes can be executed concurrently.
+        let probe_handle = data_plane_provider
+            .get_handle_for(edgeless_api::function_instance::InstanceId::new(node_id))
+            .await;
+
         log::info!("Starting EDGELESS node agent");
         while let Some(req) = receiver.next().await {
             match req {
@@ -268,10 +279,80 @@ impl Agent {
                     }
                     resource_instances.clear();
                 }
+                AgentRequest::CheckFunctionHealth(request, responder) => {
+                    log::debug!("Agent CheckFunctionHealth {:?}", request);
+
+                    let instance_id = edgeless_api::function_instance::InstanceId {
+                        node_id,
+                        function_id: request.function_id,
+                    };
+                    let status = match function_instances
+                        .get(&request.function_id)
+                        .and_then(|function_class| function_runtimes.get_mut(function_class))
+                    {
+                        Some(runner) => runner.status(instance_id).await.unwrap_or_else(|err| {
+                            log::warn!("Could not get the status of function instance {}: {}", instance_id, err);
+                            None
+                        }),
+                        None => None,
+                    };
+
+                    // Probes may take long: do not block the agent.
+                    let probe_handle = probe_handle.clone();
+                    tokio::spawn(async move {
+                        let res = Self::check_function_health(probe_handle, instance_id, request.probes, status).await;
+                        responder
+                            .send(res)
+                            .unwrap_or_else(|_| log::warn!("Agent CheckFunctionHealth: responder send error"));
+                    });
+                }
             }
         }
     }
 
+    async fn check_function_health(
+        mut probe_handle: edgeless_dataplane::handle::DataplaneHandle,
+        instance_id: edgeless_api::function_instance::InstanceId,
+        probes: Vec<edgeless_api::node_management::FunctionProbe>,
+        status: Option<crate::base_runtime::FunctionInstanceStatus>,
+    ) -> edgeless_api::node_management::FunctionHealthResponse {
+        let (running, restarts) = match status {
+            Some(status) => (status.running, status.restarts),
+            None => (false, 0),
+        };
+
+        // Probes always fail if the function instance is not running.
+        let mut probe_results = vec![];
+        for probe in probes {
+            let passed = running
+                && match tokio::time::timeout(
+                    probe.timeout,
+                    probe_handle.call(
+                        instance_id,
+                        probe.payload.clone(),
+                        &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+                    ),
+                )
+                .await
+                {
+                    Ok(edgeless_dataplane::core::CallRet::Reply(reply)) => probe.expected_reply.is_none_or(|expected| expected == reply),
+                    Ok(edgeless_dataplane::core::CallRet::NoReply) => probe.expected_reply.is_none(),
+                    Ok(edgeless_dataplane::core::CallRet::Err) => false,
+                    Err(_) => {
+                        log::debug!("Probe timed out for function instance {}", instance_id);
+                        false
+                    }
+                };
+            probe_results.push(passed);
+        }
+
+        edgeless_api::node_management::FunctionHealthResponse {
+            running,
+            restarts,
+            probe_results,
+        }
+    }
+
     async fn stop_function(
         function_runtimes: &mut std::collections::HashMap<std::string::String, Box<dyn crate::base_runtime::RuntimeAPI + std::marker::Send>>,
         function_instances: &mut std::collections::HashMap<edgeless_api::function_instance::ComponentId, String>,
@@ -408,6 +489,19 @@ impl edgeless_api::node_management::NodeManagementAPI for NodeManagementClient {
             .await
             .map_err(|err| anyhow::anyhow!("Agent channel error when resetting a node: {}", err))
     }
+    async fn check_function_health(
+        &mut self,
+        request: edgeless_api::node_management::FunctionHealthRequest,
+    ) -> anyhow::Result<edgeless_api::node_management::FunctionHealthResponse> {
+        let (rsp_sender, rsp_receiver) = futures::channel::oneshot::channel::<edgeless_api::node_management::FunctionHealthResponse>();
+        self.sender
+            .send(AgentRequest::CheckFunctionHealth(request, rsp_sender))
+            .await
+            .map_err(|err| anyhow::anyhow!("Agent channel error when checking the health of a function instance: {}", err))?;
+        rsp_receiver
+            .await
+            .map_err(|err| anyhow::anyhow!("Agent channel error when checking the health of a function instance: {}", err))
+    }
 }
 
 #[async_trait::async_trait]
@@ -196,11 +196,27 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
     async fn init(&mut self) -> Result<(), super::FunctionInstanceError> {
         let start = tokio::time::Instant::now();
 
-        self.function_instance
-            .as_mut()
-            .ok_or(super::FunctionInstanceError::InternalError)?
-            .init(self.init_payload.as_deref(), self.serialized_state.as_deref())
-            .await?;
+        // The events generated during the initialization are the root of a new trace.
+        let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+            self.telemetry_handle.as_ref(),
+            "init",
+            edgeless_telemetry::telemetry_events::SpanKind::Internal,
+        );
+        if span.metadata.has_trace() {
+            let mut locked_shared_metadata = self.event_metadata.lock().await;
+            *locked_shared_metadata = Some(span.metadata.clone())
+        }
+
+        let res = match self.function_instance.as_mut() {
+            Some(function_instance) => {
+                function_instance
+                    .init(self.init_payload.as_deref(), self.serialized_state.as_deref())
+                    .await
+            }
+            None => Err(super::FunctionInstanceError::InternalError),
+        };
+        Self::end_span(span, &res, self.telemetry_handle.as_mut());
+        res?;
 
         self.telemetry_handle.observe(
             edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionInit(start.elapsed()),
@@ -247,6 +263,12 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
             edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionTransfer(elapsed),
             std::collections::BTreeMap::new(),
         );
+        if let edgeless_dataplane::core::Message::Cast(payload) | edgeless_dataplane::core::Message::Call(payload) = &message {
+            self.telemetry_handle.observe(
+                edgeless_telemetry::telemetry_events::TelemetryEvent::DataplaneReceived(source_id.function_id.to_string(), payload.len(), elapsed),
+                std::collections::BTreeMap::new(),
+            );
+        }
 
         match message {
             edgeless_dataplane::core::Message::Cast(payload) => self.process_cast_message(source_id, payload, metadata).await,
@@ -266,16 +288,25 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
     ) -> Result<(), super::FunctionInstanceError> {
         let start = tokio::time::Instant::now();
 
+        let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start(
+            self.telemetry_handle.as_ref(),
+            "cast",
+            edgeless_telemetry::telemetry_events::SpanKind::Consumer,
+            metadata,
+        );
+        span.set_attribute("edgeless.source_id", source_id.function_id);
+
         {
             let mut locked_shared_metadata = self.event_metadata.lock().await;
-            *locked_shared_metadata = Some(metadata.clone())
+            *locked_shared_metadata = Some(span.metadata.clone())
         }
 
-        self.function_instance
-            .as_mut()
-            .ok_or(super::FunctionInstanceError::InternalError)?
-            .cast(&source_id, &payload)
-            .await?;
+        let res = match self.function_instance.as_mut() {
+            Some(function_instance) => function_instance.cast(&source_id, &payload).await,
+            None => Err(super::FunctionInstanceError::InternalError),
+        };
+        Self::end_span(span, &res, self.telemetry_handle.as_mut());
+        res?;
 
         self.telemetry_handle.observe(
             edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionInvocationCompleted(start.elapsed()),
@@ -293,17 +324,25 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
     ) -> Result<(), super::FunctionInstanceError> {
         let start = tokio::time::Instant::now();
 
+        let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start(
+            self.telemetry_handle.as_ref(),
+            "call",
+            edgeless_telemetry::telemetry_events::SpanKind::Server,
+            metadata,
+        );
+        span.set_attribute("edgeless.source_id", source_id.function_id);
+
         {
             let mut locked_shared_metadata = self.event_metadata.lock().await;
-            *locked_shared_metadata = Some(metadata.clone())
+            *locked_shared_metadata = Some(span.metadata.clone())
         }
 
-        let res = self
-            .function_instance
-            .as_mut()
-            .ok_or(super::FunctionInstanceError::InternalError)?
-            .call(&source_id, &payload)
-            .await?;
+        let res = match self.function_instance.as_mut() {
+            Some(function_instance) => function_instance.call(&source_id, &payload).await,
+            None => Err(super::FunctionInstanceError::InternalError),
+        };
+        Self::end_span(span, &res, self.telemetry_handle.as_mut());
+        let res = res?;
 
         self.telemetry_handle.observe(
             edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionInvocationCompleted(start.elapsed()),
@@ -315,6 +354,19 @@ impl<FunctionInstanceType: FunctionInstance> FunctionInstanceTask<FunctionInstan
         Ok(())
     }
 
+    /// End the span of an invocation of the function instance, marking it
+    /// as failed if the invocation returned an error.
+    fn end_span<T>(
+        mut span: edgeless_telemetry::telemetry_events::TelemetrySpan,
+        res: &Result<T, super::FunctionInstanceError>,
+        telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+    ) {
+        if let Err(err) = res {
+            span.set_error(format!("{:?}", err));
+        }
+        span.end(telemetry_handle);
+    }
+
     async fn stop(&mut self) -> Result<(), super::FunctionInstanceError> {
         let start = tokio::time::Instant::now();
 
@@ -29,37 +29,78 @@ pub enum GuestAPIError {
 }
 
 impl GuestAPIHost {
-    pub async fn cast_alias(&mut self, alias: &str, msg: &str) -> Result<(), GuestAPIError> {
+    /// Start a span child of the event being processed by the function
+    /// instance, if any, or the root of a new trace otherwise.
+    async fn start_span(
+        &mut self,
+        name: &str,
+        kind: edgeless_telemetry::telemetry_events::SpanKind,
+        dangling_root_offset: u64,
+    ) -> edgeless_telemetry::telemetry_events::TelemetrySpan {
         let shared_metadata = { self.event_metadata.lock().await.clone() };
-        let metadata = shared_metadata.unwrap_or(edgeless_api::function_instance::EventMetadata::empty_dangling_root(0x42a42bdecaf00022u64));
-        if alias == "self" {
-            self.data_plane.send(self.instance_id, msg.to_string(), &metadata).await;
-            Ok(())
-        } else if let Some(target) = self.callback_table.get_mapping(alias).await {
-            self.data_plane.send(target, msg.to_string(), &metadata).await;
+        let parent = shared_metadata.unwrap_or(edgeless_api::function_instance::EventMetadata::empty_dangling_root(dangling_root_offset));
+        edgeless_telemetry::telemetry_events::TelemetrySpan::start(self.telemetry_handle.as_ref(), name, kind, &parent)
+    }
+
+    pub async fn cast_alias(&mut self, alias: &str, msg: &str) -> Result<(), GuestAPIError> {
+        let mut span = self
+            .start_span("cast", edgeless_telemetry::telemetry_events::SpanKind::Producer, 0x42a42bdecaf00022u64)
+            .await;
+        span.set_attribute("edgeless.alias", alias);
+        let target = if alias == "self" {
+            Some(self.instance_id)
+        } else {
+            self.callback_table.get_mapping(alias).await
+        };
+        let res = if let Some(target) = target {
+            crate::dataplane_metrics::send(
+                &mut self.data_plane,
+                self.telemetry_handle.as_mut(),
+                alias,
+                target,
+                msg.to_string(),
+                &span.metadata,
+            )
+            .await;
             Ok(())
         } else {
             log::warn!("Unknown alias at {} for cast: {}", self.instance_id, alias);
+            crate::dataplane_metrics::observe_send(self.telemetry_handle.as_mut(), alias, None, msg.len(), false);
+            span.set_error("unknown alias");
             Err(GuestAPIError::UnknownAlias)
-        }
+        };
+        span.end(self.telemetry_handle.as_mut());
+        res
     }
 
     pub async fn cast_raw(&mut self, target: edgeless_api::function_instance::InstanceId, msg: &str) -> Result<(), GuestAPIError> {
-        let shared_metadata = { self.event_metadata.lock().await.clone() };
-        let metadata = shared_metadata.unwrap_or(edgeless_api::function_instance::EventMetadata::empty_dangling_root(0x42a42bdecaf00023u64));
-        self.data_plane.send(target, msg.to_string(), &metadata).await;
+        let mut span = self
+            .start_span("cast", edgeless_telemetry::telemetry_events::SpanKind::Producer, 0x42a42bdecaf00023u64)
+            .await;
+        span.set_attribute("edgeless.target_id", target.function_id);
+        crate::dataplane_metrics::send(
+            &mut self.data_plane,
+            self.telemetry_handle.as_mut(),
+            "",
+            target,
+            msg.to_string(),
+            &span.metadata,
+        )
+        .await;
+        span.end(self.telemetry_handle.as_mut());
         Ok(())
     }
 
     pub async fn call_alias(&mut self, alias: &str, msg: &str) -> Result<edgeless_dataplane::core::CallRet, GuestAPIError> {
         if alias == "self" {
-            self.call_raw(self.instance_id, msg).await
+            self.call_channel(alias, self.instance_id, msg).await
             // return Ok(self.data_plane.call(self.instance_id.clone(), msg.to_string()).await);
         } else if let Some(target) = self.callback_table.get_mapping(alias).await {
-            return self.call_raw(target, msg).await;
+            return self.call_channel(alias, target, msg).await;
             // return Ok(self.data_plane.call(target.clone(), msg.to_string()).await);
         } else {
             log::warn!("Unknown alias at {} for call: {}", self.instance_id, alias);
+            crate::dataplane_metrics::observe_send(self.telemetry_handle.as_mut(), alias, None, msg.len(), false);
             Err(GuestAPIError::UnknownAlias)
         }
     }
@@ -69,22 +110,72 @@ impl GuestAPIHost {
         target: edgeless_api::function_instance::InstanceId,
         msg: &str,
     ) -> Result<edgeless_dataplane::core::CallRet, GuestAPIError> {
-        let shared_metadata = { self.event_metadata.lock().await.clone() };
-        let metadata = shared_metadata.unwrap_or(edgeless_api::function_instance::EventMetadata::empty_dangling_root(0x42a42bdecaf00024u64));
+        self.call_channel("", target, msg).await
+    }
+
+    /// Call a target through a channel, i.e., an alias, which is empty
+    /// if the target has been given explicitly by the function instance.
+    async fn call_channel(
+        &mut self,
+        channel: &str,
+        target: edgeless_api::function_instance::InstanceId,
+        msg: &str,
+    ) -> Result<edgeless_dataplane::core::CallRet, GuestAPIError> {
+        let mut span = self
+            .start_span("call", edgeless_telemetry::telemetry_events::SpanKind::Client, 0x42a42bdecaf00024u64)
+            .await;
+        span.set_attribute("edgeless.target_id", target.function_id);
 
-        futures::select! {
+        let call_res = futures::select! {
             _ = Box::pin(self.poison_pill_receiver.recv()).fuse() => {
-                Ok(edgeless_dataplane::core::CallRet::Err)
+                edgeless_dataplane::core::CallRet::Err
             },
-            call_res = Box::pin(self.data_plane.call(target, msg.to_string(), &metadata)).fuse() => {
-                Ok(call_res)
+            call_res = Box::pin(self.data_plane.call(target, msg.to_string(), &span.metadata)).fuse() => {
+                call_res
             }
+        };
+        crate::dataplane_metrics::observe_call(self.telemetry_handle.as_mut(), channel, target, msg.len(), &call_res);
+        if call_res == edgeless_dataplane::core::CallRet::Err {
+            span.set_error("call failed");
         }
+        span.end(self.telemetry_handle.as_mut());
+        Ok(call_res)
+    }
+
+    pub async fn telemetry_log(
+        &mut self,
+        lvl: edgeless_telemetry::telemetry_events::TelemetryLogLevel,
+        target: &str,
+        msg: &str,
+        fields: edgeless_telemetry::telemetry_events::LogFields,
+    ) {
+        self.telemetry_handle.observe(
+            edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionLogEntry(lvl, target.to_string(), msg.to_string(), fields),
+            std::collections::BTreeMap::new(),
+        );
     }
 
-    pub async fn telemetry_log(&mut self, lvl: edgeless_telemetry::telemetry_events::TelemetryLogLevel, target: &str, msg: &str) {
+    pub async fn telemetry_metric(
+        &mut self,
+        kind: edgeless_api::node_registration::FunctionMetricKind,
+        name: &str,
+        labels: std::collections::BTreeMap<String, String>,
+        value: f64,
+    ) {
+        if name.is_empty() {
+            log::warn!("Unnamed {} metric from {}, ignored", kind, self.instance_id);
+            return;
+        }
+        if !value.is_finite() || (kind == edgeless_api::node_registration::FunctionMetricKind::Counter && value < 0.0) {
+            log::warn!("Invalid value {} of {} metric {} from {}, ignored", value, kind, name, self.instance_id);
+            return;
+        }
+        if let Err(err) = edgeless_telemetry::telemetry_events::validate_metric_labels(&labels) {
+            log::warn!("Invalid labels of {} metric {} from {}, ignored: {}", kind, name, self.instance_id, err);
+            return;
+        }
         self.telemetry_handle.observe(
-            edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionLogEntry(lvl, target.to_string(), msg.to_string()),
+            edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionMetric(kind, name.to_string(), labels, value),
             std::collections::BTreeMap::new(),
         );
     }
@@ -97,8 +188,15 @@ impl GuestAPIHost {
         let mut cloned_plane = self.data_plane.clone();
         let cloned_msg = payload.to_string();
 
-        let shared_metadata = { self.event_metadata.lock().await.clone() };
-        let metadata = shared_metadata.unwrap_or(edgeless_api::function_instance::EventMetadata::empty_dangling_root(0x42a42bdecaf00025u64));
+        let mut span = self
+            .start_span(
+                "delayed-cast",
+                edgeless_telemetry::telemetry_events::SpanKind::Producer,
+                0x42a42bdecaf00025u64,
+            )
+            .await;
+        span.set_attribute("edgeless.alias", target_alias);
+        span.set_attribute("edgeless.delay_ms", delay);
 
         let target_instance_id = if target_alias == "self" {
             self.instance_id
@@ -106,12 +204,27 @@ impl GuestAPIHost {
             targted_id
         } else {
             log::warn!("Unknown alias at {} for delayed cast ({} ms): {}", self.instance_id, delay, target_alias);
+            crate::dataplane_metrics::observe_send(self.telemetry_handle.as_mut(), target_alias, None, payload.len(), false);
+            span.set_error("unknown alias");
+            span.end(self.telemetry_handle.as_mut());
             return Err(GuestAPIError::UnknownAlias);
         };
 
+        // The span ends when the event is actually sent.
+        let mut cloned_telemetry_handle = self.telemetry_handle.fork(std::collections::BTreeMap::new());
+        let cloned_alias = target_alias.to_string();
         tokio::spawn(async move {
             tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
-            cloned_plane.send(target_instance_id, cloned_msg, &metadata).await;
+            crate::dataplane_metrics::send(
+                &mut cloned_plane,
+                cloned_telemetry_handle.as_mut(),
+                &cloned_alias,
+                target_instance_id,
+                cloned_msg,
+                &span.metadata,
+            )
+            .await;
+            span.end(cloned_telemetry_handle.as_mut());
         });
 
         Ok(())
@@ -123,3 +236,139 @@ impl GuestAPIHost {
         Ok(())
     }
 }
+
+/// Parse the labels of an application-defined metric, passed by the
+/// function instance as a comma-separated list of `key=value` pairs, which
+/// is empty if there are no labels.
+/// Return an error if a pair is malformed or a key is repeated.
+pub fn parse_metric_labels(labels: &str) -> Result<std::collections::BTreeMap<String, String>, String> {
+    let mut ret = std::collections::BTreeMap::new();
+    if labels.trim().is_empty() {
+        return Ok(ret);
+    }
+    for pair in labels.split(',') {
+        let Some((key, value)) = pair.split_once('=').filter(|(key, _)| !key.trim().is_empty()) else {
+            return Err(format!("malformed label '{}'", pair));
+        };
+        if ret.insert(key.trim().to_string(), value.trim().to_string()).is_some() {
+            return Err(format!("duplicate label '{}'", key.trim()));
+        }
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use edgeless_api::node_registration::FunctionMetricKind;
+    use edgeless_telemetry::telemetry_events::TelemetryEvent;
+
+    struct MockTelemetryHandle {
+        events: std::sync::Arc<std::sync::Mutex<Vec<TelemetryEvent>>>,
+    }
+
+    impl edgeless_telemetry::telemetry_events::TelemetryHandleAPI for MockTelemetryHandle {
+        fn observe(&mut self, event: TelemetryEvent, _event_tags: std::collections::BTreeMap<String, String>) {
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn fork(
+            &mut self,
+            _child_tags: std::collections::BTreeMap<String, String>,
+        ) -> Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> {
+            Box::new(MockTelemetryHandle { events: self.events.clone() })
+        }
+    }
+
+    struct MockStateHandle {}
+
+    #[async_trait::async_trait]
+    impl crate::state_management::StateHandleAPI for MockStateHandle {
+        async fn get(&mut self) -> Option<String> {
+            None
+        }
+
+        async fn set(&mut self, _serialized_state: String) {}
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> std::collections::BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_metric_labels() {
+        assert_eq!(Ok(labels(&[])), parse_metric_labels(""));
+        assert_eq!(Ok(labels(&[])), parse_metric_labels("  "));
+        assert_eq!(Ok(labels(&[("camera", "front")])), parse_metric_labels("camera=front"));
+        assert_eq!(
+            Ok(labels(&[("camera", "front"), ("lane", ""), ("zone", "a=b")])),
+            parse_metric_labels(" camera = front,lane=,zone=a=b")
+        );
+
+        // Malformed.
+        for malformed in ["camera", "=front", " =front", "camera=front,", ",camera=front", "camera=front,,lane=2"] {
+            assert!(parse_metric_labels(malformed).is_err(), "{}", malformed);
+        }
+
+        // Duplicate.
+        assert!(parse_metric_labels("camera=front,camera=rear").is_err());
+        assert!(parse_metric_labels("camera=front, camera =front").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_metric() {
+        let node_id = uuid::Uuid::new_v4();
+        let instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:7124".to_string(), None).await;
+        let events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let (_poison_pill_sender, poison_pill_receiver) = tokio::sync::broadcast::channel(1);
+        let mut host = GuestAPIHost {
+            instance_id,
+            data_plane: dataplane_provider.get_handle_for(instance_id).await,
+            callback_table: crate::base_runtime::alias_mapping::AliasMapping::new(),
+            state_handle: Box::new(MockStateHandle {}),
+            telemetry_handle: Box::new(MockTelemetryHandle { events: events.clone() }),
+            poison_pill_receiver,
+            event_metadata: Arc::new(Mutex::new(None)),
+        };
+
+        host.telemetry_metric(FunctionMetricKind::Counter, "frames", labels(&[("camera", "front")]), 2.0)
+            .await;
+        host.telemetry_metric(FunctionMetricKind::Gauge, "score", labels(&[]), -1.5).await;
+        host.telemetry_metric(FunctionMetricKind::Histogram, "latency", labels(&[]), 0.0).await;
+        assert_eq!(
+            vec![
+                TelemetryEvent::FunctionMetric(FunctionMetricKind::Counter, "frames".to_string(), labels(&[("camera", "front")]), 2.0),
+                TelemetryEvent::FunctionMetric(FunctionMetricKind::Gauge, "score".to_string(), labels(&[]), -1.5),
+                TelemetryEvent::FunctionMetric(FunctionMetricKind::Histogram, "latency".to_string(), labels(&[]), 0.0),
+            ],
+            std::mem::take(&mut *events.lock().unwrap())
+        );
+
+        // Unnamed metric.
+        host.telemetry_metric(FunctionMetricKind::Gauge, "", labels(&[]), 1.0).await;
+
+        // Non-finite values and negative counter increments.
+        for kind in [FunctionMetricKind::Counter, FunctionMetricKind::Gauge, FunctionMetricKind::Histogram] {
+            for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+                host.telemetry_metric(kind, "frames", labels(&[]), value).await;
+            }
+        }
+        host.telemetry_metric(FunctionMetricKind::Counter, "frames", labels(&[]), -1.0).await;
+
+        // Invalid, reserved, and too many labels.
+        host.telemetry_metric(FunctionMetricKind::Gauge, "score", labels(&[("lane-2", "x")]), 1.0)
+            .await;
+        host.telemetry_metric(FunctionMetricKind::Gauge, "score", labels(&[("function_id", "x")]), 1.0)
+            .await;
+        host.telemetry_metric(FunctionMetricKind::Gauge, "score", labels(&[("__name__", "x")]), 1.0)
+            .await;
+        let names = (0..=edgeless_telemetry::telemetry_events::MAX_METRIC_LABELS)
+            .map(|i| format!("l{}", i))
+            .collect::<Vec<_>>();
+        let too_many = names.iter().map(|name| (name.as_str(), "x")).collect::<Vec<_>>();
+        host.telemetry_metric(FunctionMetricKind::Gauge, "score", labels(&too_many), 1.0).await;
+
+        assert!(events.lock().unwrap().is_empty());
+    }
+}
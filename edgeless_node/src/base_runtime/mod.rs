@@ -18,6 +18,17 @@ pub trait RuntimeAPI {
     ) -> anyhow::Result<()>;
     async fn stop(&mut self, instance_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()>;
     async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()>;
+    /// Return the status of a function instance, or None if not known.
+    async fn status(&mut self, instance_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<Option<FunctionInstanceStatus>>;
+}
+
+/// Status of a function instance hosted by a runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionInstanceStatus {
+    /// True if the function instance is running or waiting to be restarted.
+    pub running: bool,
+    /// Number of times the function instance has been restarted.
+    pub restarts: u32,
 }
 
 /// This must be implemented for each virtualization technology.
@@ -38,6 +38,20 @@ pub struct RuntimeTask<FunctionInstanceType: super::FunctionInstance> {
     guest_api_host_register: std::sync::Arc<tokio::sync::Mutex<Box<dyn GuestAPIHostRegister + Send>>>,
     slf_channel: futures::channel::mpsc::UnboundedSender<RuntimeRequest>,
     functions: std::collections::HashMap<uuid::Uuid, super::function_instance_runner::FunctionInstanceRunner<FunctionInstanceType>>,
+    lifecycles: std::collections::HashMap<uuid::Uuid, FunctionLifecycle>,
+}
+
+/// Lifecycle of a function instance, kept until the function instance is
+/// stopped explicitly, which allows to restart it when it exits.
+struct FunctionLifecycle {
+    /// The original spawn request, only saved if the function instance
+    /// may have to be restarted.
+    spawn_request: Option<edgeless_api::function_instance::SpawnFunctionRequest>,
+    restart_spec: edgeless_api::function_lifecycle::RestartSpec,
+    restarts: u32,
+    running: bool,
+    /// The last patch received, which is applied again upon restart.
+    last_patch: Option<edgeless_api::common::PatchRequest>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -49,6 +63,11 @@ pub enum RuntimeRequest {
     Stop(edgeless_api::function_instance::InstanceId),
     Patch(edgeless_api::common::PatchRequest),
     FunctionExit(edgeless_api::function_instance::InstanceId, Result<(), super::FunctionInstanceError>),
+    Restart(edgeless_api::function_instance::InstanceId),
+    Status(
+        edgeless_api::function_instance::InstanceId,
+        futures::channel::oneshot::Sender<Option<super::FunctionInstanceStatus>>,
+    ),
 }
 
 /// Entrypoint for all runtimes based on the base_runtime.
@@ -90,6 +109,7 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
             guest_api_host_register,
             slf_channel,
             functions: std::collections::HashMap::new(),
+            lifecycles: std::collections::HashMap::new(),
         }
     }
 
@@ -98,6 +118,20 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
         while let Some(req) = self.receiver.next().await {
             match req {
                 RuntimeRequest::Start(instance_id, spawn_request) => {
+                    let restart_spec = edgeless_api::function_lifecycle::RestartSpec::from_annotations(&spawn_request.annotations);
+                    self.lifecycles.insert(
+                        instance_id.function_id,
+                        FunctionLifecycle {
+                            spawn_request: match restart_spec.policy {
+                                edgeless_api::function_lifecycle::RestartPolicy::Never => None,
+                                _ => Some(spawn_request.clone()),
+                            },
+                            restart_spec,
+                            restarts: 0,
+                            running: true,
+                            last_patch: None,
+                        },
+                    );
                     self.start_function(instance_id, spawn_request).await;
                 }
                 RuntimeRequest::Stop(instance_id) => {
@@ -109,6 +143,19 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
                 RuntimeRequest::FunctionExit(id, status) => {
                     self.function_exit(id, status).await;
                 }
+                RuntimeRequest::Restart(instance_id) => {
+                    self.restart_function(instance_id).await;
+                }
+                RuntimeRequest::Status(instance_id, reply_sender) => {
+                    let status = self
+                        .lifecycles
+                        .get(&instance_id.function_id)
+                        .map(|lifecycle| super::FunctionInstanceStatus {
+                            running: lifecycle.running,
+                            restarts: lifecycle.restarts,
+                        });
+                    let _ = reply_sender.send(status);
+                }
             }
         }
     }
@@ -129,10 +176,10 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
             self.state_manager
                 .get_handle(spawn_request.state_specification.state_policy, spawn_request.state_specification.state_id)
                 .await,
-            self.telemetry_handle.fork(std::collections::BTreeMap::from([(
-                "FUNCTION_ID".to_string(),
-                instance_id.function_id.to_string(),
-            )])),
+            self.telemetry_handle.fork(std::collections::BTreeMap::from([
+                ("FUNCTION_ID".to_string(), instance_id.function_id.to_string()),
+                ("WORKFLOW_ID".to_string(), spawn_request.workflow_id.clone()),
+            ])),
             self.guest_api_host_register.clone(),
         )
         .await;
@@ -141,6 +188,9 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
 
     async fn stop_function(&mut self, instance_id: edgeless_api::function_instance::InstanceId) {
         log::info!("Stop Function {:?}", instance_id);
+        // Forget the lifecycle first, so that the function instance is not
+        // restarted when it exits.
+        self.lifecycles.remove(&instance_id.function_id);
         if let Some(instance) = self.functions.get_mut(&instance_id.function_id) {
             instance.stop().await;
         }
@@ -148,6 +198,9 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
 
     async fn patch_function_links(&mut self, update_request: edgeless_api::common::PatchRequest) {
         log::info!("Patch Function {:?}", update_request.function_id);
+        if let Some(lifecycle) = self.lifecycles.get_mut(&update_request.function_id) {
+            lifecycle.last_patch = Some(update_request.clone());
+        }
         if let Some(instance) = self.functions.get_mut(&update_request.function_id) {
             instance.patch(update_request).await;
         }
@@ -156,6 +209,48 @@ impl<FunctionInstanceType: super::FunctionInstance> RuntimeTask<FunctionInstance
     async fn function_exit(&mut self, instance_id: edgeless_api::function_instance::InstanceId, status: Result<(), super::FunctionInstanceError>) {
         log::info!("Function Exit Event: {:?} {:?}", instance_id, status);
         self.functions.remove(&instance_id.function_id);
+
+        if let Some(lifecycle) = self.lifecycles.get_mut(&instance_id.function_id) {
+            if lifecycle.spawn_request.is_some() && lifecycle.restart_spec.should_restart(status.is_err(), lifecycle.restarts) {
+                let backoff = lifecycle.restart_spec.next_backoff(lifecycle.restarts);
+                log::info!(
+                    "Function {:?} will be restarted in {} ms (policy {}, restarts so far {})",
+                    instance_id,
+                    backoff.as_millis(),
+                    lifecycle.restart_spec.policy,
+                    lifecycle.restarts
+                );
+                let mut slf_channel = self.slf_channel.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(backoff).await;
+                    let _ = slf_channel.send(RuntimeRequest::Restart(instance_id)).await;
+                });
+            } else {
+                lifecycle.running = false;
+            }
+        }
+    }
+
+    async fn restart_function(&mut self, instance_id: edgeless_api::function_instance::InstanceId) {
+        // The function instance may have been stopped in the meanwhile.
+        if self.functions.contains_key(&instance_id.function_id) {
+            return;
+        }
+        let (spawn_request, last_patch) = match self.lifecycles.get_mut(&instance_id.function_id) {
+            Some(lifecycle) => match &lifecycle.spawn_request {
+                Some(spawn_request) => {
+                    lifecycle.restarts += 1;
+                    (spawn_request.clone(), lifecycle.last_patch.clone())
+                }
+                None => return,
+            },
+            None => return,
+        };
+        log::info!("Restart Function {:?}", instance_id);
+        self.start_function(instance_id, spawn_request).await;
+        if let Some(last_patch) = last_patch {
+            self.patch_function_links(last_patch).await;
+        }
     }
 }
 
@@ -193,4 +288,243 @@ impl super::RuntimeAPI for RuntimeClient {
             Err(_) => Err(anyhow::anyhow!("Runner Channel Error")),
         }
     }
+
+    async fn status(&mut self, instance_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<Option<super::FunctionInstanceStatus>> {
+        let (reply_sender, reply_receiver) = futures::channel::oneshot::channel();
+        match self.sender.send(RuntimeRequest::Status(instance_id, reply_sender)).await {
+            Ok(_) => reply_receiver.await.map_err(|_| anyhow::anyhow!("Runner Channel Error")),
+            Err(_) => Err(anyhow::anyhow!("Runner Channel Error")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base_runtime::RuntimeAPI;
+    use crate::test_utils::NoTelemetryHandle;
+
+    /// Time of the instantiations of the mock function instances.
+    /// key:   function_id
+    /// value: instants of the instantiations
+    static INSTANTIATIONS: std::sync::LazyLock<std::sync::Mutex<std::collections::HashMap<uuid::Uuid, Vec<tokio::time::Instant>>>> =
+        std::sync::LazyLock::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+    /// Function instance whose initialization fails if its code is "fail",
+    /// otherwise it runs until stopped.
+    struct MockFunctionInstance {
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::base_runtime::FunctionInstance for MockFunctionInstance {
+        async fn instantiate(
+            instance_id: &edgeless_api::function_instance::InstanceId,
+            _runtime_configuration: std::collections::HashMap<String, String>,
+            _guest_api_host: &mut Option<crate::base_runtime::guest_api::GuestAPIHost>,
+            _binary: &[u8],
+            code: &str,
+        ) -> Result<Box<Self>, crate::base_runtime::FunctionInstanceError> {
+            INSTANTIATIONS
+                .lock()
+                .unwrap()
+                .entry(instance_id.function_id)
+                .or_default()
+                .push(tokio::time::Instant::now());
+            Ok(Box::new(Self { fail: code == "fail" }))
+        }
+
+        async fn init(
+            &mut self,
+            _init_payload: Option<&str>,
+            _serialized_state: Option<&str>,
+        ) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+            if self.fail {
+                Err(crate::base_runtime::FunctionInstanceError::BadCode("init failed".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+
+        async fn cast(
+            &mut self,
+            _src: &edgeless_api::function_instance::InstanceId,
+            _msg: &str,
+        ) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+            Ok(())
+        }
+
+        async fn call(
+            &mut self,
+            _src: &edgeless_api::function_instance::InstanceId,
+            _msg: &str,
+        ) -> Result<edgeless_dataplane::core::CallRet, crate::base_runtime::FunctionInstanceError> {
+            Ok(edgeless_dataplane::core::CallRet::NoReply)
+        }
+
+        async fn stop(&mut self) -> Result<(), crate::base_runtime::FunctionInstanceError> {
+            Ok(())
+        }
+    }
+
+    struct NoGuestAPIHostRegister {}
+
+    impl GuestAPIHostRegister for NoGuestAPIHostRegister {
+        fn needs_to_register(&mut self) -> bool {
+            false
+        }
+
+        fn register_guest_api_host(
+            &mut self,
+            _instance_id: &edgeless_api::function_instance::InstanceId,
+            _guest_api_host: crate::base_runtime::guest_api::GuestAPIHost,
+        ) {
+        }
+
+        fn deregister_guest_api_host(&mut self, _instance_id: &edgeless_api::function_instance::InstanceId) {}
+
+        fn guest_api_host(
+            &mut self,
+            _instance_id: &edgeless_api::function_instance::InstanceId,
+        ) -> Option<&mut crate::base_runtime::guest_api::GuestAPIHost> {
+            None
+        }
+
+        fn configuration(&mut self) -> std::collections::HashMap<String, String> {
+            std::collections::HashMap::new()
+        }
+    }
+
+    async fn start_runtime(node_id: uuid::Uuid, invocation_url: &str) -> RuntimeClient {
+        let (client, mut task) = create::<MockFunctionInstance>(
+            edgeless_dataplane::handle::DataplaneProvider::new(node_id, invocation_url.to_string(), None).await,
+            Box::new(crate::state_management::StateManager::new().await),
+            Box::new(NoTelemetryHandle {}),
+            std::sync::Arc::new(tokio::sync::Mutex::new(Box::new(NoGuestAPIHostRegister {}))),
+        );
+        tokio::spawn(async move { task.run().await });
+        client
+    }
+
+    fn spawn_request(code: &str, annotations: &[(&str, &str)]) -> edgeless_api::function_instance::SpawnFunctionRequest {
+        edgeless_api::function_instance::SpawnFunctionRequest {
+            spec: edgeless_api::function_instance::FunctionClassSpecification {
+                id: "mock".to_string(),
+                function_type: "MOCK".to_string(),
+                version: "0.1".to_string(),
+                binary: None,
+                code: Some(code.to_string()),
+                outputs: vec![],
+            },
+            annotations: annotations.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            state_specification: edgeless_api::function_instance::StateSpecification {
+                state_id: uuid::Uuid::new_v4(),
+                state_policy: edgeless_api::function_instance::StatePolicy::Transient,
+            },
+            workflow_id: "workflow_1".to_string(),
+        }
+    }
+
+    fn instantiations(instance_id: &edgeless_api::function_instance::InstanceId) -> Vec<tokio::time::Instant> {
+        INSTANTIATIONS.lock().unwrap().get(&instance_id.function_id).cloned().unwrap_or_default()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_restart_backoff() {
+        let node_id = uuid::Uuid::new_v4();
+        let mut client = start_runtime(node_id, "http://127.0.0.1:7104").await;
+
+        let instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        client
+            .start(
+                instance_id,
+                spawn_request(
+                    "fail",
+                    &[
+                        ("restart_policy", "on-failure"),
+                        ("restart_backoff_ms", "100"),
+                        ("restart_max_backoff_ms", "400"),
+                        ("restart_max_count", "4"),
+                    ],
+                ),
+            )
+            .await
+            .unwrap();
+
+        // Wait until the function instance gives up.
+        for _ in 0..1000 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            if client.status(instance_id).await.unwrap().is_some_and(|status| !status.running) {
+                break;
+            }
+        }
+        assert_eq!(
+            Some(crate::base_runtime::FunctionInstanceStatus { running: false, restarts: 4 }),
+            client.status(instance_id).await.unwrap()
+        );
+
+        // The backoff doubles at every restart, up to its maximum.
+        let starts = instantiations(&instance_id);
+        assert_eq!(5, starts.len());
+        let delays = starts.windows(2).map(|x| (x[1] - x[0]).as_millis()).collect::<Vec<u128>>();
+        assert_eq!(vec![100, 200, 400, 400], delays);
+
+        // No more restarts happen afterwards.
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+        assert_eq!(5, instantiations(&instance_id).len());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_restart_policy() {
+        let node_id = uuid::Uuid::new_v4();
+        let mut client = start_runtime(node_id, "http://127.0.0.1:7105").await;
+
+        // A function instance that exits with an error is not restarted
+        // with the default policy.
+        let never_instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        client.start(never_instance_id, spawn_request("fail", &[])).await.unwrap();
+
+        // A running function instance is not restarted when stopped
+        // explicitly, even if its policy is to restart always.
+        let running_instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        client
+            .start(running_instance_id, spawn_request("ok", &[("restart_policy", "always")]))
+            .await
+            .unwrap();
+
+        // A failed function instance waiting for its restart is not restarted
+        // if stopped explicitly in the meanwhile.
+        let failed_instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        client
+            .start(
+                failed_instance_id,
+                spawn_request("fail", &[("restart_policy", "on-failure"), ("restart_backoff_ms", "1000")]),
+            )
+            .await
+            .unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert_eq!(
+            Some(crate::base_runtime::FunctionInstanceStatus { running: false, restarts: 0 }),
+            client.status(never_instance_id).await.unwrap()
+        );
+        assert_eq!(
+            Some(crate::base_runtime::FunctionInstanceStatus { running: true, restarts: 0 }),
+            client.status(running_instance_id).await.unwrap()
+        );
+        assert_eq!(
+            Some(crate::base_runtime::FunctionInstanceStatus { running: true, restarts: 0 }),
+            client.status(failed_instance_id).await.unwrap()
+        );
+
+        client.stop(running_instance_id).await.unwrap();
+        client.stop(failed_instance_id).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+
+        assert_eq!(None, client.status(running_instance_id).await.unwrap());
+        assert_eq!(None, client.status(failed_instance_id).await.unwrap());
+        for instance_id in [never_instance_id, running_instance_id, failed_instance_id] {
+            assert_eq!(1, instantiations(&instance_id).len());
+        }
+    }
 }
@@ -11,12 +11,17 @@ use edgeless_node::resources::http_ingress::HttpIngressResourceSpec;
 use edgeless_node::resources::http_poster::HttpPosterResourceSpec;
 #[cfg(feature = "rdkafka")]
 use edgeless_node::resources::kafka_egress::KafkaEgressResourceSpec;
+#[cfg(feature = "rdkafka")]
+use edgeless_node::resources::kafka_ingress::KafkaIngressResourceSpec;
+use edgeless_node::resources::mqtt::MqttResourceSpec;
+use edgeless_node::resources::object_store::ObjectStoreResourceSpec;
 use edgeless_node::resources::ollama::OllamaResourceSpec;
 use edgeless_node::resources::redis::RedisResourceSpec;
 use edgeless_node::resources::resource_provider_specs::ResourceProviderSpecOutput;
 use edgeless_node::resources::resource_provider_specs::ResourceProviderSpecs;
 use edgeless_node::resources::serverless::ServerlessResourceProviderSpec;
 use edgeless_node::resources::sqlx::SqlxResourceSpec;
+use edgeless_node::resources::websocket_ingress::WebSocketIngressResourceSpec;
 
 #[derive(Debug, clap::Parser)]
 #[command(long_about = None)]
@@ -77,12 +82,17 @@ fn main() -> anyhow::Result<()> {
             Box::new(HttpIngressResourceSpec {}),
             Box::new(HttpPosterResourceSpec {}),
             Box::new(FilePusherResourceSpec {}),
+            Box::new(MqttResourceSpec {}),
+            Box::new(ObjectStoreResourceSpec {}),
             Box::new(OllamaResourceSpec {}),
             Box::new(RedisResourceSpec {}),
             Box::new(SqlxResourceSpec {}),
+            Box::new(WebSocketIngressResourceSpec {}),
         ];
         #[cfg(feature = "rdkafka")]
         specs.push(Box::new(KafkaEgressResourceSpec {}));
+        #[cfg(feature = "rdkafka")]
+        specs.push(Box::new(KafkaIngressResourceSpec {}));
         if let Ok(conf) = &conf
             && let Some(resources) = &conf.resources
             && let Some(serverless_providers) = &resources.serverless_provider
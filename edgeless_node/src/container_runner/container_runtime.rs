@@ -20,6 +20,7 @@ enum ContainerRuntimeRequest {
         tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::guest_api_function::CallReturn>>,
     ),
     TelemetryLog(edgeless_api::guest_api_host::TelemetryLogEvent),
+    TelemetryMetric(edgeless_api::guest_api_host::TelemetryMetricEvent),
     Slf(tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::function_instance::InstanceId>>),
     DelayedCast(edgeless_api::guest_api_host::DelayedEventData),
     Sync(edgeless_api::guest_api_host::SyncData),
@@ -223,6 +224,7 @@ impl ContainerRuntime {
                                 },
                                 &event.target,
                                 &event.msg,
+                                event.fields,
                             )
                             .await;
                     } else {
@@ -233,6 +235,18 @@ impl ContainerRuntime {
                         );
                     }
                 }
+                ContainerRuntimeRequest::TelemetryMetric(event) => {
+                    log::debug!("telemetry-metric, kind {}, name {}, value {}", event.kind, event.name, event.value);
+                    if let Some(runtime) = container_runtime.lock().await.guest_api_host(&event.originator) {
+                        runtime.telemetry_metric(event.kind, &event.name, event.labels, event.value).await;
+                    } else {
+                        log::warn!(
+                            "no function instance with matching ID {} when issuing a telemetry_metric with name {}: ignored",
+                            event.originator,
+                            event.name
+                        );
+                    }
+                }
                 ContainerRuntimeRequest::Slf(reply_sender) => {
                     log::debug!("slf");
 
@@ -354,6 +368,12 @@ impl edgeless_api::guest_api_host::GuestAPIHost for GuestAPIRuntimeClient {
             Err(err) => return Err(anyhow::anyhow!("GuestAPIRuntime::telemetry_log channel error: {}", err)),
         }
     }
+    async fn telemetry_metric(&mut self, event: edgeless_api::guest_api_host::TelemetryMetricEvent) -> anyhow::Result<()> {
+        match self.sender.send(ContainerRuntimeRequest::TelemetryMetric(event)).await {
+            Ok(_) => Ok(()),
+            Err(err) => return Err(anyhow::anyhow!("GuestAPIRuntime::telemetry_metric channel error: {}", err)),
+        }
+    }
     async fn slf(&mut self) -> anyhow::Result<edgeless_api::function_instance::InstanceId> {
         let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<anyhow::Result<edgeless_api::function_instance::InstanceId>>();
         match self.sender.send(ContainerRuntimeRequest::Slf(reply_sender)).await {
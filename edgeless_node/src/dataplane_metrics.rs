@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Send events through the dataplane on behalf of function and resource
+//! instances, observing the outcome per channel via telemetry.
+
+/// Cast an event to a target through a channel and observe the outcome.
+/// Return false if no link could route the event to the target.
+pub async fn send(
+    dataplane_handle: &mut edgeless_dataplane::handle::DataplaneHandle,
+    telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+    channel: &str,
+    target: edgeless_api::function_instance::InstanceId,
+    msg: String,
+    metadata: &edgeless_api::function_instance::EventMetadata,
+) -> bool {
+    let size = msg.len();
+    let delivered = dataplane_handle.send(target, msg, metadata).await;
+    observe_send(telemetry_handle, channel, Some(target), size, delivered);
+    delivered
+}
+
+/// Call a target through a channel and observe the outcome once the call
+/// returns.
+pub async fn call(
+    dataplane_handle: &mut edgeless_dataplane::handle::DataplaneHandle,
+    telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+    channel: &str,
+    target: edgeless_api::function_instance::InstanceId,
+    msg: String,
+    metadata: &edgeless_api::function_instance::EventMetadata,
+) -> edgeless_dataplane::core::CallRet {
+    let size = msg.len();
+    let res = dataplane_handle.call(target, msg, metadata).await;
+    observe_call(telemetry_handle, channel, target, size, &res);
+    res
+}
+
+/// Observe the outcome of sending an event through a channel:
+/// the target is None if the alias is unknown.
+pub fn observe_send(
+    telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+    channel: &str,
+    target: Option<edgeless_api::function_instance::InstanceId>,
+    size: usize,
+    delivered: bool,
+) {
+    let event = match target {
+        Some(target) if delivered => {
+            edgeless_telemetry::telemetry_events::TelemetryEvent::DataplaneSent(channel.to_string(), target.function_id.to_string(), size)
+        }
+        Some(target) => edgeless_telemetry::telemetry_events::TelemetryEvent::DataplaneDropped(
+            channel.to_string(),
+            target.function_id.to_string(),
+            String::from("no-route"),
+        ),
+        None => {
+            edgeless_telemetry::telemetry_events::TelemetryEvent::DataplaneDropped(channel.to_string(), String::new(), String::from("unknown-alias"))
+        }
+    };
+    telemetry_handle.observe(event, std::collections::BTreeMap::new());
+}
+
+/// Observe the outcome of a call through a channel, after it returned:
+/// a failed call, e.g., because there is no route to the target or the
+/// reply has not been received, is counted as dropped.
+pub fn observe_call(
+    telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+    channel: &str,
+    target: edgeless_api::function_instance::InstanceId,
+    size: usize,
+    res: &edgeless_dataplane::core::CallRet,
+) {
+    let event = match res {
+        edgeless_dataplane::core::CallRet::Err => edgeless_telemetry::telemetry_events::TelemetryEvent::DataplaneDropped(
+            channel.to_string(),
+            target.function_id.to_string(),
+            String::from("call-failed"),
+        ),
+        _ => edgeless_telemetry::telemetry_events::TelemetryEvent::DataplaneSent(channel.to_string(), target.function_id.to_string(), size),
+    };
+    telemetry_handle.observe(event, std::collections::BTreeMap::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use edgeless_telemetry::telemetry_events::TelemetryEvent;
+
+    struct MockTelemetryHandle {
+        events: std::sync::Arc<std::sync::Mutex<Vec<TelemetryEvent>>>,
+    }
+
+    impl edgeless_telemetry::telemetry_events::TelemetryHandleAPI for MockTelemetryHandle {
+        fn observe(&mut self, event: TelemetryEvent, _event_tags: std::collections::BTreeMap<String, String>) {
+            self.events.lock().unwrap().push(event);
+        }
+
+        fn fork(
+            &mut self,
+            _child_tags: std::collections::BTreeMap<String, String>,
+        ) -> Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> {
+            Box::new(MockTelemetryHandle { events: self.events.clone() })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dataplane_metrics_send_and_call() {
+        let node_id = uuid::Uuid::new_v4();
+        let mut provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, String::from("http://127.0.0.1:7122"), None).await;
+        let source_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let unknown_id = edgeless_api::function_instance::InstanceId::new(uuid::Uuid::new_v4());
+        let mut source = provider.get_handle_for(source_id).await;
+        let mut target = provider.get_handle_for(target_id).await;
+        let events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let mut telemetry_handle = MockTelemetryHandle { events: events.clone() };
+        let metadata = edgeless_api::function_instance::EventMetadata::empty_new_root();
+
+        // Cast to a reachable and an unreachable target.
+        assert!(send(&mut source, &mut telemetry_handle, "out", target_id, String::from("hello"), &metadata).await);
+        assert_eq!(
+            edgeless_dataplane::core::Message::Cast(String::from("hello")),
+            target.receive_next().await.message
+        );
+        assert!(!send(&mut source, &mut telemetry_handle, "out", unknown_id, String::from("hello"), &metadata).await);
+        assert_eq!(
+            vec![
+                TelemetryEvent::DataplaneSent(String::from("out"), target_id.function_id.to_string(), 5),
+                TelemetryEvent::DataplaneDropped(String::from("out"), unknown_id.function_id.to_string(), String::from("no-route")),
+            ],
+            std::mem::take(&mut *events.lock().unwrap())
+        );
+
+        // The outcome of a call is only observed once the call returns.
+        let replier = tokio::spawn(async move {
+            let event = target.receive_next().await;
+            target
+                .reply(
+                    event.source_id,
+                    event.channel_id,
+                    edgeless_dataplane::core::CallRet::Reply(String::from("world")),
+                    &event.metadata,
+                )
+                .await;
+        });
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Reply(String::from("world")),
+            call(&mut source, &mut telemetry_handle, "req", target_id, String::from("hello"), &metadata).await
+        );
+        replier.await.unwrap();
+        assert_eq!(
+            edgeless_dataplane::core::CallRet::Err,
+            call(&mut source, &mut telemetry_handle, "req", unknown_id, String::from("hello"), &metadata).await
+        );
+        assert_eq!(
+            vec![
+                TelemetryEvent::DataplaneSent(String::from("req"), target_id.function_id.to_string(), 5),
+                TelemetryEvent::DataplaneDropped(String::from("req"), unknown_id.function_id.to_string(), String::from("call-failed")),
+            ],
+            std::mem::take(&mut *events.lock().unwrap())
+        );
+    }
+}
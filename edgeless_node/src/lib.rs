@@ -8,11 +8,14 @@ use resources::resource_provider_specs::ResourceProviderSpecs;
 pub mod agent;
 pub mod base_runtime;
 pub mod container_runner;
+pub mod dataplane_metrics;
 pub mod gpu_info;
 pub mod node_subscriber;
 pub mod power_info;
 pub mod resources;
 pub mod state_management;
+#[cfg(test)]
+pub(crate) mod test_utils;
 #[cfg(feature = "wasmtime")]
 pub mod wasm_runner;
 #[cfg(feature = "wasmi")]
@@ -42,6 +45,36 @@ pub struct EdgelessNodeTelemetrySettings {
     pub metrics_url: String,
     /// True if performance samples are sent to the orchestrator as part of health status responses to keep-alive polls.
     pub performance_samples: bool,
+    /// The URL to which the spans of distributed traces are exported:
+    /// http://host:port for an OTLP/gRPC collector or file://path to append
+    /// one JSON-encoded OTLP request per line to a local file.
+    /// Tracing is disabled if empty.
+    #[serde(default)]
+    pub traces_url: String,
+    /// The URL to which the log entries of the functions are shipped:
+    /// file://path to append one JSON-encoded entry per line to local files,
+    /// rotated when they exceed `logs_max_file_size`, or http://host:port/path
+    /// for an end-point compatible with the Loki push API.
+    /// Log shipping is disabled if empty.
+    #[serde(default)]
+    pub logs_url: String,
+    /// Maximum size of a local log file, in bytes, before it is rotated.
+    #[serde(default = "default_logs_max_file_size")]
+    pub logs_max_file_size: u64,
+    /// Maximum number of rotated local log files that are kept.
+    #[serde(default = "default_logs_max_files")]
+    pub logs_max_files: u32,
+}
+
+impl EdgelessNodeTelemetrySettings {
+    /// Settings of the sink of the function log entries.
+    pub fn log_sink(&self) -> edgeless_telemetry::log_target::LogSinkSettings {
+        edgeless_telemetry::log_target::LogSinkSettings {
+            url: self.logs_url.clone(),
+            max_file_size: self.logs_max_file_size,
+            max_files: self.logs_max_files,
+        }
+    }
 }
 
 impl Default for EdgelessNodeTelemetrySettings {
@@ -49,10 +82,22 @@ impl Default for EdgelessNodeTelemetrySettings {
         Self {
             metrics_url: String::from("http://127.0.0.1:7007"),
             performance_samples: false,
+            traces_url: String::new(),
+            logs_url: String::new(),
+            logs_max_file_size: default_logs_max_file_size(),
+            logs_max_files: default_logs_max_files(),
         }
     }
 }
 
+fn default_logs_max_file_size() -> u64 {
+    edgeless_telemetry::log_target::LogSinkSettings::default().max_file_size
+}
+
+fn default_logs_max_files() -> u32 {
+    edgeless_telemetry::log_target::LogSinkSettings::default().max_files
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct EdgelessNodeWasmRuntimeSettings {
     /// True if WASM is enabled.
@@ -127,6 +172,14 @@ pub struct EdgelessNodeResourceSettings {
     pub http_ingress_url: Option<String>,
     /// If not empty, a http-ingress resource provider with that name is created.
     pub http_ingress_provider: Option<String>,
+    /// The TLS configuration of the HTTP web server exposed by the
+    /// http-ingress resource: if present, HTTPS is served instead of HTTP.
+    pub http_ingress_tls: Option<HttpIngressTlsSettings>,
+    /// If `websocket_ingress_provider` is not empty, this is the URL of the
+    /// web server exposed by the websocket-ingress resource for this node.
+    pub websocket_ingress_url: Option<String>,
+    /// If not empty, a websocket-ingress resource provider with that name is created.
+    pub websocket_ingress_provider: Option<String>,
     /// If not empty, a http-egress resource provider with that name is created.
     pub http_egress_provider: Option<String>,
     /// If not empty, a http-poster resource provider with that name is created.
@@ -151,8 +204,50 @@ pub struct EdgelessNodeResourceSettings {
     /// The resource will connect to a remote Kafka server to stream the
     /// messages received on a given topic.
     pub kafka_egress_provider: Option<String>,
+    /// If not empty, a kafka-ingress resource provider with that name is created.
+    /// The resource will connect to a remote Kafka server to consume the
+    /// records of given topics as part of a consumer group.
+    pub kafka_ingress_provider: Option<String>,
     /// The sqlx resource provider.
     pub sqlx_provider: Option<String>,
+    /// If not empty, an mqtt resource provider with that name is created.
+    /// The resource will connect to a remote MQTT broker to subscribe to
+    /// and publish on the topics given in the resource configuration.
+    pub mqtt_provider: Option<String>,
+    /// If not empty, an object-store resource provider with that name is
+    /// created. The resource will store objects on an S3-compatible service
+    /// or on the local filesystem, depending on the resource configuration.
+    pub object_store_provider: Option<String>,
+    /// If `object_store_provider` is not empty, this is the directory of the
+    /// local filesystem under which the object-store resource instances can
+    /// store objects. If empty, the local filesystem cannot be used.
+    pub object_store_root: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct HttpIngressTlsSettings {
+    /// The PEM file with the server certificate chain.
+    /// If empty, TLS is disabled.
+    pub cert_path: String,
+    /// The PEM file with the server private key.
+    pub key_path: String,
+    /// If not empty, the PEM file with the CA certificates used to verify
+    /// the certificates of the clients, which are then required.
+    pub client_ca_path: String,
+    /// The interval at which the certificate and key files are checked for
+    /// changes and reloaded, in s. If 0, they are never reloaded.
+    pub reload_interval_sec: u64,
+}
+
+impl Default for HttpIngressTlsSettings {
+    fn default() -> Self {
+        Self {
+            cert_path: String::default(),
+            key_path: String::default(),
+            client_ca_path: String::default(),
+            reload_interval_sec: 10,
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -351,23 +446,72 @@ async fn fill_resources(
             let class_type = resources::http_ingress::HttpIngressResourceSpec {}.class_type();
             let provider_id = make_provider_id(provider_id);
             log::info!("Creating {} resource provider '{}' at {}", class_type, provider_id, http_ingress_url);
-            ret.insert(
-                provider_id.clone(),
-                agent::ResourceDesc {
-                    class_type: class_type.clone(),
-                    client: resources::http_ingress::ingress_task(
-                        data_plane.clone(),
-                        edgeless_api::function_instance::InstanceId::new(node_id),
-                        http_ingress_url.clone(),
-                    )
-                    .await,
-                },
-            );
-            provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
-                provider_id,
-                class_type,
-                outputs: resources::http_ingress::HttpIngressResourceSpec {}.outputs(),
-            });
+            match resources::http_ingress::ingress_task(
+                data_plane.clone(),
+                edgeless_api::function_instance::InstanceId::new(node_id),
+                http_ingress_url.clone(),
+                settings.http_ingress_tls.clone(),
+                Box::new(telemetry_provider.get_handle(std::collections::BTreeMap::from([
+                    ("RESOURCE_CLASS_TYPE".to_string(), class_type.clone()),
+                    ("RESOURCE_PROVIDER_ID".to_string(), provider_id.clone()),
+                    ("NODE_ID".to_string(), node_id.to_string()),
+                ]))),
+            )
+            .await
+            {
+                Ok(client) => {
+                    ret.insert(
+                        provider_id.clone(),
+                        agent::ResourceDesc {
+                            class_type: class_type.clone(),
+                            client,
+                        },
+                    );
+                    provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                        provider_id,
+                        class_type,
+                        outputs: resources::http_ingress::HttpIngressResourceSpec {}.outputs(),
+                    });
+                }
+                Err(err) => log::error!("Could not create {} resource provider '{}': {}", class_type, provider_id, err),
+            }
+        }
+
+        if let (Some(websocket_ingress_url), Some(provider_id)) = (&settings.websocket_ingress_url, &settings.websocket_ingress_provider)
+            && !websocket_ingress_url.is_empty()
+            && !provider_id.is_empty()
+        {
+            let class_type = resources::websocket_ingress::WebSocketIngressResourceSpec {}.class_type();
+            let provider_id = make_provider_id(provider_id);
+            log::info!("Creating {} resource provider '{}' at {}", class_type, provider_id, websocket_ingress_url);
+            match resources::websocket_ingress::WebSocketIngressResourceProvider::new(
+                data_plane.clone(),
+                Box::new(telemetry_provider.get_handle(std::collections::BTreeMap::from([
+                    ("RESOURCE_CLASS_TYPE".to_string(), class_type.clone()),
+                    ("RESOURCE_PROVIDER_ID".to_string(), provider_id.clone()),
+                    ("NODE_ID".to_string(), node_id.to_string()),
+                ]))),
+                node_id,
+                websocket_ingress_url,
+            )
+            .await
+            {
+                Ok(provider) => {
+                    ret.insert(
+                        provider_id.clone(),
+                        agent::ResourceDesc {
+                            class_type: class_type.clone(),
+                            client: Box::new(provider),
+                        },
+                    );
+                    provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                        provider_id,
+                        class_type,
+                        outputs: resources::websocket_ingress::WebSocketIngressResourceSpec {}.outputs(),
+                    });
+                }
+                Err(err) => log::error!("Could not create {} resource provider '{}': {}", class_type, provider_id, err),
+            }
         }
 
         if let Some(provider_id) = &settings.http_egress_provider
@@ -496,6 +640,69 @@ async fn fill_resources(
             });
         }
 
+        if let Some(provider_id) = &settings.mqtt_provider
+            && !provider_id.is_empty()
+        {
+            let class_type = resources::mqtt::MqttResourceSpec {}.class_type();
+            let provider_id = make_provider_id(provider_id);
+            log::info!("Creating {} resource provider '{}'", class_type, provider_id);
+            ret.insert(
+                provider_id.clone(),
+                agent::ResourceDesc {
+                    class_type: class_type.clone(),
+                    client: Box::new(
+                        resources::mqtt::MqttResourceProvider::new(
+                            data_plane.clone(),
+                            Box::new(telemetry_provider.get_handle(std::collections::BTreeMap::from([
+                                ("RESOURCE_CLASS_TYPE".to_string(), class_type.clone()),
+                                ("RESOURCE_PROVIDER_ID".to_string(), provider_id.clone()),
+                                ("NODE_ID".to_string(), node_id.to_string()),
+                            ]))),
+                            node_id,
+                        )
+                        .await,
+                    ),
+                },
+            );
+            provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                provider_id,
+                class_type,
+                outputs: resources::mqtt::MqttResourceSpec {}.outputs(),
+            });
+        }
+
+        if let Some(provider_id) = &settings.object_store_provider
+            && !provider_id.is_empty()
+        {
+            let class_type = resources::object_store::ObjectStoreResourceSpec {}.class_type();
+            let provider_id = make_provider_id(provider_id);
+            log::info!("Creating {} resource provider '{}'", class_type, provider_id);
+            ret.insert(
+                provider_id.clone(),
+                agent::ResourceDesc {
+                    class_type: class_type.clone(),
+                    client: Box::new(
+                        resources::object_store::ObjectStoreResourceProvider::new(
+                            data_plane.clone(),
+                            settings.object_store_root.clone().filter(|x| !x.is_empty()),
+                            Box::new(telemetry_provider.get_handle(std::collections::BTreeMap::from([
+                                ("RESOURCE_CLASS_TYPE".to_string(), class_type.clone()),
+                                ("RESOURCE_PROVIDER_ID".to_string(), provider_id.clone()),
+                                ("NODE_ID".to_string(), node_id.to_string()),
+                            ]))),
+                            node_id,
+                        )
+                        .await,
+                    ),
+                },
+            );
+            provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                provider_id,
+                class_type,
+                outputs: resources::object_store::ObjectStoreResourceSpec {}.outputs(),
+            });
+        }
+
         if let Some(provider_id) = &settings.redis_provider
             && !provider_id.is_empty()
         {
@@ -688,6 +895,45 @@ async fn fill_resources(
             );
         }
 
+        if let Some(provider_id) = &settings.kafka_ingress_provider
+            && !provider_id.is_empty()
+        {
+            #[cfg(feature = "rdkafka")]
+            {
+                let class_type = resources::kafka_ingress::KafkaIngressResourceSpec {}.class_type();
+                let provider_id = make_provider_id(provider_id);
+                log::info!("Creating {} resource provider '{}'", class_type, provider_id);
+                ret.insert(
+                    provider_id.clone(),
+                    agent::ResourceDesc {
+                        class_type: class_type.clone(),
+                        client: Box::new(
+                            resources::kafka_ingress::KafkaIngressResourceProvider::new(
+                                data_plane.clone(),
+                                Box::new(telemetry_provider.get_handle(std::collections::BTreeMap::from([
+                                    ("RESOURCE_CLASS_TYPE".to_string(), class_type.clone()),
+                                    ("RESOURCE_PROVIDER_ID".to_string(), provider_id.clone()),
+                                    ("NODE_ID".to_string(), node_id.to_string()),
+                                ]))),
+                                node_id,
+                            )
+                            .await,
+                        ),
+                    },
+                );
+                provider_specifications.push(edgeless_api::node_registration::ResourceProviderSpecification {
+                    provider_id,
+                    class_type,
+                    outputs: resources::kafka_ingress::KafkaIngressResourceSpec {}.outputs(),
+                });
+            }
+            #[cfg(not(feature = "rdkafka"))]
+            log::error!(
+                "Could not create resource provider '{}' because rdkafka was disabled at compile time",
+                provider_id
+            );
+        }
+
         if let Some(provider_id) = &settings.sqlx_provider
             && !provider_id.is_empty()
         {
@@ -749,6 +995,8 @@ pub async fn edgeless_node_main(settings: EdgelessNodeSettings) {
         } else {
             None
         },
+        settings.telemetry.traces_url.clone(),
+        settings.telemetry.log_sink(),
     )
     .await
     {
@@ -897,6 +1145,9 @@ pub fn edgeless_node_default_conf() -> String {
             prepend_hostname: true,
             http_ingress_url: Some(String::from("http://127.0.0.1:7008")),
             http_ingress_provider: Some("http-ingress-1".to_string()),
+            http_ingress_tls: Some(HttpIngressTlsSettings::default()),
+            websocket_ingress_url: Some(String::from("http://127.0.0.1:7009")),
+            websocket_ingress_provider: Some("websocket-ingress-1".to_string()),
             http_egress_provider: Some("http-egress-1".to_string()),
             http_poster_provider: Some("http-poster-1".to_string()),
             file_pusher_provider: Some(FilePusherSettings::default()),
@@ -906,7 +1157,11 @@ pub fn edgeless_node_default_conf() -> String {
             ollama_provider: Some(OllamaProviderSettings::default()),
             serverless_provider: Some(vec![ServerlessProviderSettings::default()]),
             kafka_egress_provider: Some(String::default()),
+            kafka_ingress_provider: Some(String::default()),
             sqlx_provider: Some("sqlx-1".to_string()),
+            mqtt_provider: Some("mqtt-1".to_string()),
+            object_store_provider: Some("object-store-1".to_string()),
+            object_store_root: Some(String::default()),
         }),
         user_node_capabilities: Some(NodeCapabilitiesUser::default()),
         power_info: None,
@@ -163,6 +163,38 @@ impl NodeSubscriber {
                             function_stop_times,
                             function_transfer_times,
                             function_log_entries: metrics.function_log_entries,
+                            resource_throttled: metrics
+                                .resource_throttled
+                                .into_iter()
+                                .map(|(id, count)| {
+                                    let now = chrono::Utc::now();
+                                    (
+                                        id,
+                                        vec![edgeless_api::node_registration::Sample {
+                                            timestamp_sec: now.timestamp(),
+                                            timestamp_ns: now.timestamp_subsec_nanos(),
+                                            sample: count as f64,
+                                        }],
+                                    )
+                                })
+                                .collect(),
+                            resource_failures: metrics
+                                .resource_failures
+                                .into_iter()
+                                .map(|(id, count)| {
+                                    let now = chrono::Utc::now();
+                                    (
+                                        id,
+                                        vec![edgeless_api::node_registration::Sample {
+                                            timestamp_sec: now.timestamp(),
+                                            timestamp_ns: now.timestamp_subsec_nanos(),
+                                            sample: count as f64,
+                                        }],
+                                    )
+                                })
+                                .collect(),
+                            dataplane_channels: metrics.dataplane_channels.into_values().collect(),
+                            function_metrics: metrics.function_metrics.into_values().collect(),
                         },
                     };
                     match node_registration_api.update_node(update_node_request).await {
@@ -294,6 +294,7 @@ impl DDAResource {
         let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<(dda::DDA, DDAComSubscription)>();
         let mut receiver = receiver;
         let mut handle = dataplane_handle.clone();
+        let mut passer_telemetry_handle = telemetry_handle.fork(std::collections::BTreeMap::new());
         let passer_task = tokio::spawn(async move {
             while let Some((event, dda_sub)) = receiver.recv().await {
                 // if receiver.len() > 10 {
@@ -315,13 +316,22 @@ impl DDAResource {
                 match target_function_id {
                     Some(target_function_id) => match dataplane_event_type.as_str() {
                         "cast" => {
-                            let _ = handle
-                                .send(
-                                    target_function_id,
-                                    encoded_event,
-                                    &edgeless_api::function_instance::EventMetadata::empty_new_root(),
-                                )
-                                .await;
+                            let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+                                passer_telemetry_handle.as_ref(),
+                                "dda-receive",
+                                edgeless_telemetry::telemetry_events::SpanKind::Consumer,
+                            );
+                            span.set_attribute("edgeless.dda_target", &dda_sub.target);
+                            crate::dataplane_metrics::send(
+                                &mut handle,
+                                passer_telemetry_handle.as_mut(),
+                                &dda_sub.target,
+                                target_function_id,
+                                encoded_event,
+                                &span.metadata,
+                            )
+                            .await;
+                            span.end(passer_telemetry_handle.as_mut());
                         }
                         "call" => {
                             panic!("do not use calls - they will probably be removed later on");
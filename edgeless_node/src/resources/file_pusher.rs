@@ -85,9 +85,14 @@ impl FilePusherResource {
                 if let Some(instance) = inner.instances.get(&self_function_id)
                     && let Some(instance_id) = instance.target
                 {
-                    dataplane_handle
-                        .send(instance_id, msg, &edgeless_api::function_instance::EventMetadata::empty_new_root())
-                        .await;
+                    let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+                        telemetry_handle.as_ref(),
+                        "file-pusher",
+                        edgeless_telemetry::telemetry_events::SpanKind::Producer,
+                    );
+                    span.set_attribute("edgeless.file_index", cur);
+                    crate::dataplane_metrics::send(&mut dataplane_handle, telemetry_handle.as_mut(), "out", instance_id, msg, &span.metadata).await;
+                    span.end(telemetry_handle.as_mut());
                 }
 
                 // Move to the next file. Wrap-around, if needed.
@@ -20,14 +20,63 @@ impl super::resource_provider_specs::ResourceProviderSpecs for HttpEgressResourc
     }
 
     fn configurations(&self) -> std::collections::HashMap<String, String> {
-        std::collections::HashMap::new()
+        std::collections::HashMap::from([
+            (
+                String::from("base-url"),
+                String::from("URL prepended to the path of the requests that do not specify a host, e.g., https://api.example.com/v1"),
+            ),
+            (
+                String::from("allowlist"),
+                String::from(
+                    "Comma-separated list of hosts (e.g., example.com, example.com:8080, *.example.com) or URL prefixes (e.g., https://example.com/api) that can be reached, the base-url is always allowed. Default: any URL",
+                ),
+            ),
+            (
+                String::from("headers"),
+                String::from("JSON object with the headers added to the requests that do not specify them, e.g., {\"User-Agent\": \"edgeless\"}"),
+            ),
+            (
+                String::from("timeout-ms"),
+                String::from("Timeout of every request attempt, in ms, 0 means no timeout. Default: 30000"),
+            ),
+            (
+                String::from("max-retries"),
+                String::from(
+                    "Maximum number of retries on connection errors, timeouts, and 5xx responses, only for idempotent methods unless retry-non-idempotent is true. Default: 0",
+                ),
+            ),
+            (
+                String::from("retry-non-idempotent"),
+                String::from("If true, the requests with non-idempotent methods, e.g., POST, are retried too. Default: false"),
+            ),
+            (
+                String::from("retry-backoff-ms"),
+                String::from("Delay before the first retry, in ms, doubled at every subsequent retry. Default: 100"),
+            ),
+            (
+                String::from("circuit-breaker-threshold"),
+                String::from(
+                    "Number of consecutive failed requests after which further requests are rejected immediately, 0 means no circuit breaking. Default: 0",
+                ),
+            ),
+            (
+                String::from("circuit-breaker-reset-ms"),
+                String::from("Time after which a request is let through again when the circuit is open, in ms. Default: 30000"),
+            ),
+        ])
     }
 
     fn version(&self) -> String {
-        String::from("1.1")
+        String::from("1.2")
     }
 }
 
+/// Maximum delay between two consecutive attempts of the same request.
+const MAX_RETRY_BACKOFF: tokio::time::Duration = tokio::time::Duration::from_secs(30);
+
+/// Maximum number of redirections followed by a request.
+const MAX_REDIRECTS: usize = 10;
+
 #[derive(Clone)]
 pub struct EgressResourceProvider {
     inner: std::sync::Arc<tokio::sync::Mutex<EgressResourceProviderInner>>,
@@ -40,6 +89,366 @@ struct EgressResourceProviderInner {
     egress_instances: std::collections::HashMap<edgeless_api::function_instance::InstanceId, EgressResource>,
 }
 
+/// Entry of the allowlist of an `http-egress` resource instance.
+#[derive(Debug, PartialEq)]
+enum AllowedTarget {
+    /// Host, optionally with port; a leading `*.` matches any subdomain.
+    Host { host: String, port: Option<u16> },
+    /// URL prefix, which must end at a path segment boundary.
+    Prefix(String),
+}
+
+impl AllowedTarget {
+    fn parse(value: &str) -> anyhow::Result<Self> {
+        if value.contains("://") {
+            let url = reqwest::Url::parse(value).map_err(|err| anyhow::anyhow!("invalid URL '{}' in allowlist: {}", value, err))?;
+            return Ok(AllowedTarget::Prefix(url.as_str().to_string()));
+        }
+        let (host, port) = match value.rsplit_once(':').filter(|_| !value.ends_with(']')) {
+            Some((host, port)) => (
+                host,
+                Some(port.parse::<u16>().map_err(|_| anyhow::anyhow!("invalid port in allowlist: {}", value))?),
+            ),
+            None => (value, None),
+        };
+        if host.is_empty() || host == "*." {
+            anyhow::bail!("invalid host in allowlist: {}", value);
+        }
+        Ok(AllowedTarget::Host {
+            host: host.to_lowercase(),
+            port,
+        })
+    }
+
+    fn matches(&self, url: &reqwest::Url) -> bool {
+        match self {
+            AllowedTarget::Host { host, port } => {
+                let Some(url_host) = url.host_str() else {
+                    return false;
+                };
+                let host_matches = match host.strip_prefix("*.") {
+                    Some(domain) => url_host.strip_suffix(domain).is_some_and(|x| x.ends_with('.')),
+                    None => url_host == host,
+                };
+                host_matches && port.is_none_or(|port| url.port_or_known_default() == Some(port))
+            }
+            AllowedTarget::Prefix(prefix) => match url.as_str().strip_prefix(prefix.as_str()) {
+                Some(rest) => prefix.ends_with('/') || rest.is_empty() || rest.starts_with(['/', '?', '#']),
+                None => false,
+            },
+        }
+    }
+}
+
+/// Configuration of an `http-egress` resource instance.
+#[derive(Debug)]
+struct HttpEgressConfiguration {
+    base_url: Option<String>,
+    allowlist: Vec<AllowedTarget>,
+    headers: reqwest::header::HeaderMap,
+    timeout: Option<tokio::time::Duration>,
+    max_retries: u32,
+    retry_non_idempotent: bool,
+    retry_backoff: tokio::time::Duration,
+    circuit_breaker_threshold: u32,
+    circuit_breaker_reset: tokio::time::Duration,
+}
+
+/// Parse a numeric field of the configuration, if present and not empty.
+fn parse_number<T: std::str::FromStr>(configuration: &std::collections::HashMap<String, String>, name: &str, default: T) -> anyhow::Result<T> {
+    match configuration.get(name).filter(|x| !x.is_empty()) {
+        Some(value) => value.parse::<T>().map_err(|_| anyhow::anyhow!("invalid {} '{}'", name, value)),
+        None => Ok(default),
+    }
+}
+
+impl HttpEgressConfiguration {
+    fn from_configuration(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let number = |name: &str, default: u64| parse_number::<u64>(configuration, name, default);
+
+        let base_url = match configuration.get("base-url").filter(|x| !x.is_empty()) {
+            Some(base_url) => {
+                let url = reqwest::Url::parse(base_url).map_err(|err| anyhow::anyhow!("invalid base-url '{}': {}", base_url, err))?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    anyhow::bail!("unsupported scheme in base-url: {}", base_url);
+                }
+                Some(base_url.trim_end_matches('/').to_string())
+            }
+            None => None,
+        };
+
+        let mut allowlist = configuration
+            .get("allowlist")
+            .map(|x| {
+                x.split(',')
+                    .map(|x| x.trim())
+                    .filter(|x| !x.is_empty())
+                    .map(AllowedTarget::parse)
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        if !allowlist.is_empty()
+            && let Some(base_url) = &base_url
+        {
+            allowlist.push(AllowedTarget::parse(base_url)?);
+        }
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(value) = configuration.get("headers").filter(|x| !x.is_empty()) {
+            let values: std::collections::HashMap<String, String> =
+                serde_json::from_str(value).map_err(|err| anyhow::anyhow!("invalid headers '{}': {}", value, err))?;
+            for (name, value) in values {
+                headers.insert(
+                    reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                        .map_err(|err| anyhow::anyhow!("invalid header name '{}': {}", name, err))?,
+                    reqwest::header::HeaderValue::from_str(&value).map_err(|err| anyhow::anyhow!("invalid value of header '{}': {}", name, err))?,
+                );
+            }
+        }
+
+        let timeout = number("timeout-ms", 30000)?;
+        Ok(Self {
+            base_url,
+            allowlist,
+            headers,
+            timeout: if timeout > 0 {
+                Some(tokio::time::Duration::from_millis(timeout))
+            } else {
+                None
+            },
+            max_retries: parse_number::<u32>(configuration, "max-retries", 0)?,
+            retry_non_idempotent: parse_number::<bool>(configuration, "retry-non-idempotent", false)?,
+            retry_backoff: tokio::time::Duration::from_millis(number("retry-backoff-ms", 100)?),
+            circuit_breaker_threshold: parse_number::<u32>(configuration, "circuit-breaker-threshold", 0)?,
+            circuit_breaker_reset: tokio::time::Duration::from_millis(number("circuit-breaker-reset-ms", 30000)?),
+        })
+    }
+
+    /// Return the URL of the request, which must be in the allowlist.
+    fn url(&self, req: &edgeless_http::EdgelessHTTPRequest) -> anyhow::Result<reqwest::Url> {
+        let separator = if req.path.is_empty() || req.path.starts_with('/') { "" } else { "/" };
+        let url = if req.host.is_empty() {
+            match &self.base_url {
+                Some(base_url) => format!("{}{}{}", base_url, separator, req.path),
+                None => anyhow::bail!("missing host in the request and no base-url configured"),
+            }
+        } else {
+            let protocol_string = match req.protocol {
+                edgeless_http::EdgelessHTTPProtocol::HTTPS => "https",
+                _ => "http",
+            };
+            format!("{}://{}{}{}", protocol_string, req.host, separator, req.path)
+        };
+        let url = reqwest::Url::parse(&url).map_err(|err| anyhow::anyhow!("invalid URL '{}': {}", url, err))?;
+        Ok(url)
+    }
+
+    fn is_allowed(&self, url: &reqwest::Url) -> bool {
+        self.allowlist.is_empty() || self.allowlist.iter().any(|x| x.matches(url))
+    }
+
+    /// Delay before the given retry, starting from 0.
+    fn backoff(&self, retry: u32) -> tokio::time::Duration {
+        self.retry_backoff
+            .saturating_mul(1_u32.checked_shl(retry).unwrap_or(u32::MAX))
+            .min(MAX_RETRY_BACKOFF)
+    }
+}
+
+/// Circuit breaker rejecting requests after a given number of consecutive
+/// failures. When the reset time expires a single trial request is let
+/// through: if it succeeds the circuit is closed, otherwise it is open again.
+#[derive(Debug)]
+struct CircuitBreaker {
+    threshold: u32,
+    reset: tokio::time::Duration,
+    failures: u32,
+    open_until: Option<tokio::time::Instant>,
+    trial: bool,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, reset: tokio::time::Duration) -> Self {
+        Self {
+            threshold,
+            reset,
+            failures: 0,
+            open_until: None,
+            trial: false,
+        }
+    }
+
+    /// Return true if a new request can be performed.
+    fn acquire(&mut self, now: tokio::time::Instant) -> bool {
+        match self.open_until {
+            Some(open_until) if now < open_until || self.trial => false,
+            Some(_) => {
+                self.trial = true;
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Record the outcome of a request.
+    fn record(&mut self, success: bool, now: tokio::time::Instant) {
+        if self.threshold == 0 {
+            return;
+        }
+        if success {
+            self.failures = 0;
+            self.open_until = None;
+        } else {
+            self.failures = self.failures.saturating_add(1);
+            if self.trial || self.failures >= self.threshold {
+                self.open_until = Some(now + self.reset);
+            }
+        }
+        self.trial = false;
+    }
+}
+
+/// Client shared by the requests of an `http-egress` resource instance.
+struct EgressClient {
+    configuration: std::sync::Arc<HttpEgressConfiguration>,
+    client: reqwest::Client,
+    circuit_breaker: std::sync::Mutex<CircuitBreaker>,
+}
+
+impl EgressClient {
+    fn new(configuration: HttpEgressConfiguration) -> anyhow::Result<Self> {
+        let mut builder = reqwest::Client::builder().default_headers(configuration.headers.clone());
+        if let Some(timeout) = configuration.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        // Check the target of every redirection against the allowlist,
+        // otherwise an allowed server could redirect to any URL.
+        let configuration = std::sync::Arc::new(configuration);
+        let cloned_configuration = configuration.clone();
+        builder = builder.redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if !cloned_configuration.is_allowed(attempt.url()) {
+                let err = format!("redirect not allowed: {}", attempt.url());
+                attempt.error(err)
+            } else {
+                attempt.follow()
+            }
+        }));
+
+        Ok(Self {
+            client: builder.build()?,
+            circuit_breaker: std::sync::Mutex::new(CircuitBreaker::new(
+                configuration.circuit_breaker_threshold,
+                configuration.circuit_breaker_reset,
+            )),
+            configuration,
+        })
+    }
+
+    /// Perform a request, with retries, and report to telemetry the failure
+    /// of the request, if any, with the reason of its last attempt.
+    ///
+    /// Only requests with idempotent methods are retried, unless configured
+    /// otherwise. Responses with status 5xx are returned if no retries are
+    /// left.
+    async fn perform_request(
+        &self,
+        req: edgeless_http::EdgelessHTTPRequest,
+        telemetry_handle: &mut Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+    ) -> anyhow::Result<edgeless_http::EdgelessHTTPResponse> {
+        let mut observe_failure = |reason: &str| {
+            telemetry_handle.observe(
+                edgeless_telemetry::telemetry_events::TelemetryEvent::ResourceFailure(reason.to_string()),
+                std::collections::BTreeMap::new(),
+            );
+        };
+
+        let method = reqwest::Method::from_bytes(edgeless_http::edgeless_method_to_string(req.method.clone()).as_bytes())?;
+        let url = self.configuration.url(&req)?;
+        if !self.configuration.is_allowed(&url) {
+            observe_failure("not-allowed");
+            anyhow::bail!("URL not allowed: {}", url);
+        }
+        if !self.circuit_breaker.lock().unwrap().acquire(tokio::time::Instant::now()) {
+            observe_failure("circuit-open");
+            anyhow::bail!("circuit open for URL: {}", url);
+        }
+
+        let max_retries = if method.is_idempotent() || self.configuration.retry_non_idempotent {
+            self.configuration.max_retries
+        } else {
+            0
+        };
+        let mut retry = 0;
+        loop {
+            let res = self.send(method.clone(), url.clone(), &req).await;
+            let failure = match &res {
+                Ok(resp) if resp.status >= 500 => Some("server-error"),
+                Ok(_) => None,
+                Err(err) if err.is_timeout() => Some("timeout"),
+                Err(err) if err.is_connect() => Some("connect"),
+                Err(err) if err.is_redirect() => Some("redirect"),
+                Err(_) => Some("error"),
+            };
+            let Some(reason) = failure else {
+                self.circuit_breaker.lock().unwrap().record(true, tokio::time::Instant::now());
+                return Ok(res?);
+            };
+            if !matches!(reason, "error" | "redirect") && retry < max_retries {
+                tokio::time::sleep(self.configuration.backoff(retry)).await;
+                retry += 1;
+                continue;
+            }
+            observe_failure(reason);
+            self.circuit_breaker.lock().unwrap().record(false, tokio::time::Instant::now());
+            return Ok(res?);
+        }
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        url: reqwest::Url,
+        req: &edgeless_http::EdgelessHTTPRequest,
+    ) -> reqwest::Result<edgeless_http::EdgelessHTTPResponse> {
+        let mut client_r = self.client.request(method, url);
+
+        if let Some(b) = &req.body {
+            client_r = client_r.body(b.clone());
+        }
+
+        for (header_key, header_val) in &req.headers {
+            client_r = client_r.header(header_key, header_val);
+        }
+
+        let ret = client_r.send().await?;
+
+        let headers = ret
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| match v.to_str() {
+                Ok(value) => Some((k.to_string(), value.to_string())),
+                _ => {
+                    log::warn!("Could not parse received header value");
+                    None
+                }
+            })
+            .collect();
+
+        Ok(edgeless_http::EdgelessHTTPResponse {
+            status: ret.status().as_u16(),
+            headers,
+            body: match ret.bytes().await {
+                Ok(btes) => Some(btes.to_vec()),
+                _ => None,
+            },
+        })
+    }
+}
+
 pub struct EgressResource {
     join_handle: tokio::task::JoinHandle<()>,
 }
@@ -52,11 +461,13 @@ impl Drop for EgressResource {
 
 impl EgressResource {
     async fn new(
+        client: EgressClient,
         dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
         telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
     ) -> Self {
         let mut dataplane_handle = dataplane_handle;
         let mut telemetry_handle = telemetry_handle;
+        let client = std::sync::Arc::new(client);
 
         let handle = tokio::spawn(async move {
             loop {
@@ -85,8 +496,10 @@ impl EgressResource {
                     }
                 };
                 let mut cloned_dataplane = dataplane_handle.clone();
+                let mut cloned_telemetry = telemetry_handle.fork(std::collections::BTreeMap::new());
+                let client = client.clone();
                 tokio::spawn(async move {
-                    match Self::perform_request(req).await {
+                    match client.perform_request(req, &mut cloned_telemetry).await {
                         Ok(resp) => {
                             let serialized_resp = edgeless_http::response_to_string(&resp);
                             cloned_dataplane
@@ -98,7 +511,8 @@ impl EgressResource {
                                 )
                                 .await;
                         }
-                        Err(_) => {
+                        Err(err) => {
+                            log::warn!("http-egress request failed: {}", err);
                             cloned_dataplane
                                 .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Err, &metadata)
                                 .await;
@@ -111,52 +525,6 @@ impl EgressResource {
 
         Self { join_handle: handle }
     }
-
-    async fn perform_request(req: edgeless_http::EdgelessHTTPRequest) -> anyhow::Result<edgeless_http::EdgelessHTTPResponse> {
-        let method = reqwest::Method::from_bytes(edgeless_http::edgeless_method_to_string(req.method).as_bytes())?;
-
-        let protocol_string = match req.protocol {
-            edgeless_http::EdgelessHTTPProtocol::HTTPS => "HTTPS",
-            _ => "HTTP",
-        };
-
-        let url = format!("{}://{}{}", protocol_string, req.host, req.path);
-
-        let client = reqwest::Client::new();
-
-        let mut client_r = client.request(method, url);
-
-        if let Some(b) = req.body {
-            client_r = client_r.body(b);
-        }
-
-        for (header_key, header_val) in req.headers {
-            client_r = client_r.header(header_key, header_val);
-        }
-
-        let ret = client_r.send().await?;
-
-        let headers = ret
-            .headers()
-            .iter()
-            .filter_map(|(k, v)| match v.to_str() {
-                Ok(value) => Some((k.to_string(), value.to_string())),
-                _ => {
-                    log::warn!("Could not parse received header value");
-                    None
-                }
-            })
-            .collect();
-
-        Ok(edgeless_http::EdgelessHTTPResponse {
-            status: ret.status().as_u16(),
-            headers,
-            body: match ret.bytes().await {
-                Ok(btes) => Some(btes.to_vec()),
-                _ => None,
-            },
-        })
-    }
 }
 
 impl EgressResourceProvider {
@@ -180,8 +548,20 @@ impl EgressResourceProvider {
 impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for EgressResourceProvider {
     async fn start(
         &mut self,
-        _instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let client = match HttpEgressConfiguration::from_configuration(&instance_specification.configuration).and_then(EgressClient::new) {
+            Ok(client) => client,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ));
+            }
+        };
+
         let mut lck = self.inner.lock().await;
 
         let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
@@ -192,7 +572,7 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
             new_id.function_id.to_string(),
         )]));
         lck.egress_instances
-            .insert(new_id, EgressResource::new(dataplane_handle, telemetry_handle).await);
+            .insert(new_id, EgressResource::new(client, dataplane_handle, telemetry_handle).await);
 
         Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
     }
@@ -207,3 +587,410 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockTelemetryHandle {
+        failures: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl edgeless_telemetry::telemetry_events::TelemetryHandleAPI for MockTelemetryHandle {
+        fn observe(&mut self, event: edgeless_telemetry::telemetry_events::TelemetryEvent, _event_tags: std::collections::BTreeMap<String, String>) {
+            if let edgeless_telemetry::telemetry_events::TelemetryEvent::ResourceFailure(reason) = event {
+                self.failures.lock().unwrap().push(reason);
+            }
+        }
+
+        fn fork(
+            &mut self,
+            _child_tags: std::collections::BTreeMap<String, String>,
+        ) -> Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> {
+            Box::new(MockTelemetryHandle {
+                failures: self.failures.clone(),
+            })
+        }
+    }
+
+    fn configuration(values: &[(&str, &str)]) -> anyhow::Result<HttpEgressConfiguration> {
+        HttpEgressConfiguration::from_configuration(&values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+    }
+
+    fn request(host: &str, path: &str) -> edgeless_http::EdgelessHTTPRequest {
+        edgeless_http::EdgelessHTTPRequest {
+            method: edgeless_http::EdgelessHTTPMethod::Get,
+            protocol: edgeless_http::EdgelessHTTPProtocol::HTTP,
+            host: host.to_string(),
+            path: path.to_string(),
+            path_params: std::collections::HashMap::new(),
+            body: None,
+            headers: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_http_egress_configuration() {
+        let conf = configuration(&[]).unwrap();
+        assert!(conf.base_url.is_none());
+        assert!(conf.allowlist.is_empty());
+        assert!(conf.headers.is_empty());
+        assert_eq!(Some(tokio::time::Duration::from_secs(30)), conf.timeout);
+        assert_eq!(0, conf.max_retries);
+        assert!(!conf.retry_non_idempotent);
+        assert_eq!(0, conf.circuit_breaker_threshold);
+
+        let conf = configuration(&[
+            ("base-url", "https://api.example.com/v1/"),
+            ("allowlist", "example.com, *.example.org:8080"),
+            ("headers", r#"{"User-Agent": "edgeless", "Accept": "application/json"}"#),
+            ("timeout-ms", "0"),
+            ("max-retries", "3"),
+            ("retry-non-idempotent", "true"),
+            ("retry-backoff-ms", "200"),
+            ("circuit-breaker-threshold", "5"),
+            ("circuit-breaker-reset-ms", "1000"),
+        ])
+        .unwrap();
+        assert_eq!(Some("https://api.example.com/v1"), conf.base_url.as_deref());
+        assert_eq!(
+            vec![
+                AllowedTarget::Host {
+                    host: "example.com".to_string(),
+                    port: None
+                },
+                AllowedTarget::Host {
+                    host: "*.example.org".to_string(),
+                    port: Some(8080)
+                },
+                AllowedTarget::Prefix("https://api.example.com/v1".to_string()),
+            ],
+            conf.allowlist
+        );
+        assert_eq!("edgeless", conf.headers.get("user-agent").unwrap());
+        assert_eq!("application/json", conf.headers.get("accept").unwrap());
+        assert!(conf.timeout.is_none());
+        assert_eq!(3, conf.max_retries);
+        assert!(conf.retry_non_idempotent);
+        assert_eq!(5, conf.circuit_breaker_threshold);
+        assert_eq!(tokio::time::Duration::from_secs(1), conf.circuit_breaker_reset);
+
+        for values in [
+            [("base-url", "ftp://example.com")],
+            [("base-url", "example.com")],
+            [("allowlist", "example.com:http")],
+            [("allowlist", "*.")],
+            [("headers", "User-Agent: edgeless")],
+            [("headers", r#"{"User Agent": "edgeless"}"#)],
+            [("timeout-ms", "-1")],
+            [("max-retries", "many")],
+            [("max-retries", "-1")],
+            [("max-retries", "4294967296")],
+            [("circuit-breaker-threshold", "4294967296")],
+            [("retry-non-idempotent", "yes")],
+        ] {
+            assert!(configuration(&values).is_err(), "{:?}", values);
+        }
+    }
+
+    #[test]
+    fn test_http_egress_url() {
+        let conf = configuration(&[("base-url", "https://api.example.com/v1")]).unwrap();
+        assert_eq!("https://api.example.com/v1/users", conf.url(&request("", "/users")).unwrap().as_str());
+        assert_eq!("https://api.example.com/v1/users", conf.url(&request("", "users")).unwrap().as_str());
+        assert_eq!(
+            "http://example.com:8080/x?y=1",
+            conf.url(&request("example.com:8080", "/x?y=1")).unwrap().as_str()
+        );
+
+        let conf = configuration(&[]).unwrap();
+        assert!(conf.url(&request("", "/users")).is_err());
+    }
+
+    #[test]
+    fn test_http_egress_allowlist() {
+        let conf = configuration(&[
+            ("base-url", "https://api.example.com/v1"),
+            (
+                "allowlist",
+                "example.com,*.example.org,localhost:8080,[::1],http://files.example.net/public",
+            ),
+        ])
+        .unwrap();
+        for (url, expected) in [
+            ("https://api.example.com/v1", true),
+            ("https://api.example.com/v1/users", true),
+            ("https://api.example.com/v10", false),
+            ("https://api.example.com/v2", false),
+            ("http://example.com/any", true),
+            ("https://example.com:8443/any", true),
+            ("http://sub.example.com", false),
+            ("http://a.example.org", true),
+            ("http://a.b.example.org", true),
+            ("http://example.org", false),
+            ("http://badexample.org", false),
+            ("http://localhost:8080/", true),
+            ("http://localhost/", false),
+            ("http://[::1]:9000/", true),
+            ("http://files.example.net/public/a.txt", true),
+            ("http://files.example.net/public?x=1", true),
+            ("http://files.example.net/publicity", false),
+            ("http://files.example.net.evil.com/public", false),
+        ] {
+            assert_eq!(expected, conf.is_allowed(&reqwest::Url::parse(url).unwrap()), "{}", url);
+        }
+
+        let conf = configuration(&[]).unwrap();
+        assert!(conf.is_allowed(&reqwest::Url::parse("http://any.host/").unwrap()));
+    }
+
+    #[test]
+    fn test_http_egress_backoff() {
+        let conf = configuration(&[("retry-backoff-ms", "100")]).unwrap();
+        assert_eq!(tokio::time::Duration::from_millis(100), conf.backoff(0));
+        assert_eq!(tokio::time::Duration::from_millis(200), conf.backoff(1));
+        assert_eq!(tokio::time::Duration::from_millis(800), conf.backoff(3));
+        assert_eq!(MAX_RETRY_BACKOFF, conf.backoff(20));
+        assert_eq!(MAX_RETRY_BACKOFF, conf.backoff(100));
+    }
+
+    #[test]
+    fn test_http_egress_circuit_breaker() {
+        let now = tokio::time::Instant::now();
+        let reset = tokio::time::Duration::from_secs(10);
+
+        // Disabled.
+        let mut breaker = CircuitBreaker::new(0, reset);
+        for _ in 0..100 {
+            assert!(breaker.acquire(now));
+            breaker.record(false, now);
+        }
+
+        let mut breaker = CircuitBreaker::new(3, reset);
+        for _ in 0..2 {
+            assert!(breaker.acquire(now));
+            breaker.record(false, now);
+        }
+        assert!(breaker.acquire(now));
+        breaker.record(true, now);
+
+        // Open after three consecutive failures.
+        for _ in 0..3 {
+            assert!(breaker.acquire(now));
+            breaker.record(false, now);
+        }
+        assert!(!breaker.acquire(now));
+        assert!(!breaker.acquire(now + reset / 2));
+
+        // Only one trial request, which fails.
+        let later = now + reset;
+        assert!(breaker.acquire(later));
+        assert!(!breaker.acquire(later));
+        breaker.record(false, later);
+        assert!(!breaker.acquire(later));
+
+        // The next trial request succeeds.
+        let later = later + reset;
+        assert!(breaker.acquire(later));
+        breaker.record(true, later);
+        assert!(breaker.acquire(later));
+        assert!(breaker.acquire(later));
+        breaker.record(false, later);
+        assert!(breaker.acquire(later));
+    }
+
+    async fn mock_server(statuses: Vec<u16>) -> (String, std::sync::Arc<std::sync::Mutex<Vec<hyper::HeaderMap>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let cloned_requests = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let requests = cloned_requests.clone();
+                let statuses = statuses.clone();
+                let service = hyper::service::service_fn(move |req: hyper::Request<hyper::body::Incoming>| {
+                    let requests = requests.clone();
+                    let statuses = statuses.clone();
+                    async move {
+                        let mut requests = requests.lock().unwrap();
+                        requests.push(req.headers().clone());
+                        let status = statuses.get(requests.len() - 1).cloned().unwrap_or(200);
+                        let mut res = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from("hello")));
+                        *res.status_mut() = hyper::StatusCode::from_u16(status).unwrap();
+                        Ok::<_, std::convert::Infallible>(res)
+                    }
+                });
+                tokio::spawn(async move {
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        (host, requests)
+    }
+
+    /// Start a server redirecting all the requests to the given URL.
+    async fn redirect_server(location: String) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let location = location.clone();
+                let service = hyper::service::service_fn(move |_req: hyper::Request<hyper::body::Incoming>| {
+                    let location = location.clone();
+                    async move {
+                        let mut res = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::new()));
+                        *res.status_mut() = hyper::StatusCode::FOUND;
+                        res.headers_mut()
+                            .insert(hyper::header::LOCATION, hyper::header::HeaderValue::from_str(&location).unwrap());
+                        Ok::<_, std::convert::Infallible>(res)
+                    }
+                });
+                tokio::spawn(async move {
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        host
+    }
+
+    #[tokio::test]
+    async fn test_http_egress_perform_request() {
+        let failures = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let mut telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> =
+            Box::new(MockTelemetryHandle { failures: failures.clone() });
+        let take_failures = || std::mem::take(&mut *failures.lock().unwrap());
+
+        // Retry on 5xx, with default headers overridden by the request.
+        let (host, requests) = mock_server(vec![503, 500, 200]).await;
+        let base_url = format!("http://{}", host);
+        let client = EgressClient::new(
+            configuration(&[
+                ("base-url", base_url.as_str()),
+                ("allowlist", "example.com"),
+                ("headers", r#"{"User-Agent": "edgeless", "Accept": "text/plain"}"#),
+                ("max-retries", "2"),
+                ("retry-backoff-ms", "1"),
+            ])
+            .unwrap(),
+        )
+        .unwrap();
+        let mut req = request("", "/hello");
+        req.headers.insert("Accept".to_string(), "application/json".to_string());
+        let resp = client.perform_request(req, &mut telemetry_handle).await.unwrap();
+        assert_eq!(200, resp.status);
+        assert_eq!(Some(b"hello".to_vec()), resp.body);
+        assert!(take_failures().is_empty());
+        let requests = std::mem::take(&mut *requests.lock().unwrap());
+        assert_eq!(3, requests.len());
+        for headers in requests {
+            assert_eq!("edgeless", headers.get("user-agent").unwrap());
+            assert_eq!("application/json", headers.get("accept").unwrap());
+        }
+
+        // Host not in the allowlist.
+        assert!(
+            client
+                .perform_request(request("example.org", "/hello"), &mut telemetry_handle)
+                .await
+                .is_err()
+        );
+        assert_eq!(vec!["not-allowed"], take_failures());
+
+        // The last 5xx response is returned when no retries are left.
+        let (host, _requests) = mock_server(vec![500, 502, 503, 504]).await;
+        let client = EgressClient::new(
+            configuration(&[
+                ("max-retries", "1"),
+                ("retry-backoff-ms", "1"),
+                ("circuit-breaker-threshold", "2"),
+                ("circuit-breaker-reset-ms", "60000"),
+            ])
+            .unwrap(),
+        )
+        .unwrap();
+        let resp = client.perform_request(request(&host, "/"), &mut telemetry_handle).await.unwrap();
+        assert_eq!(502, resp.status);
+        let resp = client.perform_request(request(&host, "/"), &mut telemetry_handle).await.unwrap();
+        assert_eq!(504, resp.status);
+        assert_eq!(vec!["server-error"; 2], take_failures());
+
+        // The circuit is now open.
+        assert!(client.perform_request(request(&host, "/"), &mut telemetry_handle).await.is_err());
+        assert_eq!(vec!["circuit-open"], take_failures());
+
+        // Connection refused.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        let client = EgressClient::new(configuration(&[("max-retries", "1"), ("retry-backoff-ms", "1")]).unwrap()).unwrap();
+        assert!(client.perform_request(request(&host, "/"), &mut telemetry_handle).await.is_err());
+        assert_eq!(vec!["connect"], take_failures());
+
+        // Non-idempotent methods are only retried if enabled.
+        for (retry_non_idempotent, expected_status, expected_requests) in [("false", 503, 1), ("true", 200, 2)] {
+            let (host, requests) = mock_server(vec![503, 200]).await;
+            let client = EgressClient::new(
+                configuration(&[
+                    ("max-retries", "1"),
+                    ("retry-backoff-ms", "1"),
+                    ("retry-non-idempotent", retry_non_idempotent),
+                ])
+                .unwrap(),
+            )
+            .unwrap();
+            let mut post = request(&host, "/");
+            post.method = edgeless_http::EdgelessHTTPMethod::Post;
+            let resp = client.perform_request(post, &mut telemetry_handle).await.unwrap();
+            assert_eq!(expected_status, resp.status);
+            assert_eq!(expected_requests, requests.lock().unwrap().len());
+        }
+        assert_eq!(vec!["server-error"], take_failures());
+    }
+    #[tokio::test]
+    async fn test_http_egress_redirect() {
+        let failures = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let mut telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> =
+            Box::new(MockTelemetryHandle { failures: failures.clone() });
+
+        // The final server is reachable only via localhost, while the
+        // redirecting servers are allowed via their IP address and port.
+        let (target_host, requests) = mock_server(vec![]).await;
+        let target_port = target_host.rsplit_once(':').unwrap().1;
+        let allowed_host = redirect_server(format!("http://localhost:{}/", target_port)).await;
+        let not_allowed_host = redirect_server(format!("http://{}/", target_host)).await;
+        let client = EgressClient::new(
+            configuration(&[(
+                "allowlist",
+                format!("{},{},localhost:{}", allowed_host, not_allowed_host, target_port).as_str(),
+            )])
+            .unwrap(),
+        )
+        .unwrap();
+
+        // Redirection to an allowed host.
+        let resp = client.perform_request(request(&allowed_host, "/"), &mut telemetry_handle).await.unwrap();
+        assert_eq!(200, resp.status);
+        assert_eq!(1, requests.lock().unwrap().len());
+
+        // Redirection to a host that is not allowed: the request is refused
+        // without reaching it.
+        assert!(
+            client
+                .perform_request(request(&not_allowed_host, "/"), &mut telemetry_handle)
+                .await
+                .is_err()
+        );
+        assert_eq!(1, requests.lock().unwrap().len());
+        assert_eq!(vec!["redirect"], std::mem::take(&mut *failures.lock().unwrap()));
+    }
+}
@@ -6,7 +6,6 @@
 use base64::Engine;
 use edgeless_api::function_instance::ComponentId;
 use http_body_util::BodyExt;
-use rand::{SeedableRng, seq::SliceRandom};
 use std::str::FromStr;
 
 pub struct HttpIngressResourceSpec {}
@@ -36,6 +35,18 @@ impl super::resource_provider_specs::ResourceProviderSpecs for HttpIngressResour
                 String::from("method"),
                 String::from("Comma-separated list of HTTP methods allowed. Default: accept any method"),
             ),
+            (
+                String::from("path"),
+                String::from(
+                    "If not empty, requires the path of the URL to be exactly the one given, where segments like {name} match any value and are captured as path parameters. Default: accept any path",
+                ),
+            ),
+            (
+                String::from("path_prefix"),
+                String::from(
+                    "If not empty, requires the path of the URL to start with the segments given, where segments like {name} match any value and are captured as path parameters. Cannot be used with path. Default: accept any path",
+                ),
+            ),
             (
                 String::from("wf_id"),
                 String::from(
@@ -52,34 +63,666 @@ impl super::resource_provider_specs::ResourceProviderSpecs for HttpIngressResour
                 String::from("encode_base64"),
                 String::from("Encode the input with base64 (only with async=true)."),
             ),
+            (
+                String::from("max_concurrency"),
+                String::from(
+                    "Maximum number of HTTP requests served concurrently, further requests are rejected with 429 Too Many Requests. Default: 0, i.e., unlimited",
+                ),
+            ),
+            (
+                String::from("rate_limit"),
+                String::from(
+                    "Maximum rate of HTTP requests, in requests/s, further requests are rejected with 429 Too Many Requests. Default: 0, i.e., unlimited",
+                ),
+            ),
+            (
+                String::from("rate_burst"),
+                String::from("Maximum number of HTTP requests accepted in a burst with rate_limit. Default: rate_limit"),
+            ),
+            (
+                String::from("client_rate_limit"),
+                String::from("Maximum rate of HTTP requests of each client, in requests/s. Default: 0, i.e., unlimited"),
+            ),
+            (
+                String::from("client_rate_burst"),
+                String::from("Maximum number of HTTP requests of each client accepted in a burst with client_rate_limit. Default: client_rate_limit"),
+            ),
+            (
+                String::from("client_max_concurrency"),
+                String::from("Maximum number of HTTP requests of each client served concurrently. Default: 0, i.e., unlimited"),
+            ),
+            (
+                String::from("client_key"),
+                String::from(
+                    "How clients are identified: ip, i.e., by their IP address, or api_key, i.e., by the value of the api_key_header. Default: ip",
+                ),
+            ),
+            (
+                String::from("timeout_ms"),
+                String::from(
+                    "Maximum time to wait for the reply of the target, in ms, after which 504 Gateway Timeout is returned (only with async=false). Default: 0, i.e., no timeout",
+                ),
+            ),
+            (
+                String::from("api_keys"),
+                String::from("Comma-separated list of API keys, one of which must be in the api_key_header of the HTTP requests"),
+            ),
+            (
+                String::from("api_key_header"),
+                String::from("Name of the header containing the API key. Default: x-api-key"),
+            ),
+            (
+                String::from("basic_auth"),
+                String::from("Comma-separated list of user:password credentials, one of which must be used with HTTP basic authentication"),
+            ),
+            (
+                String::from("hmac_secret"),
+                String::from("Secret used to verify the hex-encoded HMAC-SHA256 of the body of the HTTP requests, in the hmac_header"),
+            ),
+            (
+                String::from("hmac_header"),
+                String::from("Name of the header containing the HMAC-SHA256 of the body, optionally prefixed by sha256=. Default: x-signature"),
+            ),
+            (
+                String::from("max_body_size"),
+                String::from(
+                    "Maximum size of the body of HTTP requests, in bytes, larger requests are rejected with 413 Payload Too Large. Default: 0, i.e., unlimited",
+                ),
+            ),
         ])
     }
 
     fn version(&self) -> String {
-        String::from("2.1")
+        String::from("2.5")
     }
 }
 
+/// Segment of a path pattern.
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Literal(String),
+    /// Matches any value, captured as a path parameter with the given name.
+    Param(String),
+}
+
+/// Paths matched by an http-ingress resource.
+#[derive(Debug, Clone, PartialEq)]
+enum PathPattern {
+    Any,
+    Exact(Vec<PathSegment>),
+    Prefix(Vec<PathSegment>),
+}
+
+impl PathPattern {
+    fn new(path: Option<&String>, path_prefix: Option<&String>) -> anyhow::Result<Self> {
+        match (path.filter(|x| !x.is_empty()), path_prefix.filter(|x| !x.is_empty())) {
+            (None, None) => Ok(Self::Any),
+            (Some(path), None) => Ok(Self::Exact(Self::parse_segments(path)?)),
+            (None, Some(path_prefix)) => Ok(Self::Prefix(Self::parse_segments(path_prefix)?)),
+            (Some(_), Some(_)) => anyhow::bail!("path and path_prefix cannot be both specified"),
+        }
+    }
+
+    fn parse_segments(path: &str) -> anyhow::Result<Vec<PathSegment>> {
+        anyhow::ensure!(path.starts_with('/'), "path '{}' does not start with '/'", path);
+        let mut segments = vec![];
+        let mut names = std::collections::HashSet::new();
+        for segment in path.split('/').filter(|x| !x.is_empty()) {
+            if let Some(name) = segment.strip_prefix('{').and_then(|x| x.strip_suffix('}')) {
+                anyhow::ensure!(
+                    !name.is_empty() && !name.contains(['{', '}']),
+                    "invalid path parameter '{}' in path '{}'",
+                    segment,
+                    path
+                );
+                anyhow::ensure!(names.insert(name), "duplicate path parameter '{}' in path '{}'", name, path);
+                segments.push(PathSegment::Param(name.to_string()));
+            } else {
+                anyhow::ensure!(!segment.contains(['{', '}']), "invalid segment '{}' in path '{}'", segment, path);
+                segments.push(PathSegment::Literal(segment.to_string()));
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Return the path parameters if the given path matches, otherwise None.
+    fn matches(&self, path: &str) -> Option<std::collections::HashMap<String, String>> {
+        let (segments, exact) = match self {
+            Self::Any => return Some(std::collections::HashMap::new()),
+            Self::Exact(segments) => (segments, true),
+            Self::Prefix(segments) => (segments, false),
+        };
+        let path_segments = path.split('/').filter(|x| !x.is_empty()).collect::<Vec<&str>>();
+        if path_segments.len() < segments.len() || (exact && path_segments.len() != segments.len()) {
+            return None;
+        }
+        let mut params = std::collections::HashMap::new();
+        for (segment, path_segment) in segments.iter().zip(path_segments) {
+            match segment {
+                PathSegment::Literal(literal) => {
+                    if literal != path_segment {
+                        return None;
+                    }
+                }
+                PathSegment::Param(name) => {
+                    params.insert(name.clone(), path_segment.to_string());
+                }
+            }
+        }
+        Some(params)
+    }
+
+    /// Return the shape of the pattern, i.e., the pattern without the names
+    /// of the path parameters: two patterns with the same shape match
+    /// exactly the same paths.
+    fn shape(&self) -> (u8, Vec<Option<&str>>) {
+        let (kind, segments) = match self {
+            Self::Any => return (0, vec![]),
+            Self::Prefix(segments) => (1, segments),
+            Self::Exact(segments) => (2, segments),
+        };
+        (
+            kind,
+            segments
+                .iter()
+                .map(|x| match x {
+                    PathSegment::Literal(literal) => Some(literal.as_str()),
+                    PathSegment::Param(_) => None,
+                })
+                .collect(),
+        )
+    }
+
+    /// Return the precedence of the pattern, where greater values are more
+    /// specific: exact paths come first, then prefixes (longest first),
+    /// then any path. With the same length, literal segments win over
+    /// path parameters, from left to right.
+    fn precedence(&self) -> (u8, usize, Vec<bool>) {
+        let (kind, segments) = self.shape();
+        (kind, segments.len(), segments.iter().map(|x| x.is_some()).collect())
+    }
+}
+
+impl std::fmt::Display for PathPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (segments, suffix) = match self {
+            Self::Any => return write!(f, "any"),
+            Self::Exact(segments) => (segments, ""),
+            Self::Prefix(segments) => (segments, "*"),
+        };
+        for segment in segments {
+            match segment {
+                PathSegment::Literal(literal) => write!(f, "/{}", literal)?,
+                PathSegment::Param(name) => write!(f, "/{{{}}}", name)?,
+            }
+        }
+        write!(f, "{}{}", if segments.is_empty() { "/" } else { "" }, suffix)
+    }
+}
+
+#[derive(Clone)]
 struct ResourceDesc {
     host: Option<String>,
     allow: std::collections::HashSet<edgeless_http::EdgelessHTTPMethod>,
+    path: PathPattern,
     wf_id: Option<String>,
     async_out: bool,
     encode_base64: bool,
+    /// Limits the number of requests served concurrently, if set.
+    concurrency: Option<std::sync::Arc<tokio::sync::Semaphore>>,
+    /// Maximum time to wait for the reply of the target, if set.
+    timeout: Option<tokio::time::Duration>,
+    /// Maximum size of the body of the requests, in bytes, if set.
+    max_body_size: Option<usize>,
+    /// Authentication methods that must all be satisfied by the requests.
+    auth: Vec<Auth>,
+    /// Rate limits and per-client concurrency caps, if any.
+    throttle: Option<std::sync::Arc<Throttle>>,
     target: Option<edgeless_api::function_instance::InstanceId>,
 }
 
+impl ResourceDesc {
+    /// Return true if both resources may be selected for the same HTTP
+    /// request, i.e., they match the same host, workflow identifier, and
+    /// paths, and there is at least one method allowed by both.
+    fn conflicts_with(&self, other: &ResourceDesc) -> bool {
+        self.host == other.host
+            && self.wf_id == other.wf_id
+            && self.path.shape() == other.path.shape()
+            && (self.allow.is_empty() || other.allow.is_empty() || !self.allow.is_disjoint(&other.allow))
+    }
+}
+
+/// Authentication method of HTTP requests, verified before the target is
+/// invoked.
+#[derive(Clone, PartialEq)]
+enum Auth {
+    /// One of the keys must be the value of the given header.
+    ApiKey { header: hyper::header::HeaderName, keys: Vec<String> },
+    /// One of the user:password credentials must be in the Authorization
+    /// header, with the Basic scheme.
+    Basic { credentials: Vec<String> },
+    /// The given header must contain the hex-encoded HMAC-SHA256 of the body,
+    /// optionally prefixed by `sha256=`.
+    Hmac { header: hyper::header::HeaderName, secret: Vec<u8> },
+}
+
+impl Auth {
+    /// Parse the authentication methods from the resource configuration.
+    fn from_configuration(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Vec<Auth>> {
+        let list = |name: &str| -> Vec<String> {
+            configuration
+                .get(name)
+                .map(|x| x.split(',').filter(|x| !x.is_empty()).map(|x| x.to_string()).collect())
+                .unwrap_or_default()
+        };
+        let header = |name: &str, default: &str| -> anyhow::Result<hyper::header::HeaderName> {
+            let value = configuration.get(name).filter(|x| !x.is_empty()).map(|x| x.as_str()).unwrap_or(default);
+            hyper::header::HeaderName::from_str(value).map_err(|err| anyhow::anyhow!("invalid {} '{}': {}", name, value, err))
+        };
+
+        let mut ret = vec![];
+        let keys = list("api_keys");
+        if !keys.is_empty() {
+            ret.push(Auth::ApiKey {
+                header: header("api_key_header", "x-api-key")?,
+                keys,
+            });
+        }
+        let credentials = list("basic_auth");
+        if let Some(invalid) = credentials.iter().find(|x| !x.contains(':')) {
+            anyhow::bail!("invalid basic_auth credentials '{}', expected user:password", invalid);
+        }
+        if !credentials.is_empty() {
+            ret.push(Auth::Basic { credentials });
+        }
+        if let Some(secret) = configuration.get("hmac_secret").filter(|x| !x.is_empty()) {
+            ret.push(Auth::Hmac {
+                header: header("hmac_header", "x-signature")?,
+                secret: secret.as_bytes().to_vec(),
+            });
+        }
+        Ok(ret)
+    }
+
+    /// Return true if the method needs the body of the request.
+    fn needs_body(&self) -> bool {
+        matches!(self, Auth::Hmac { .. })
+    }
+
+    /// Return true if the request is authenticated by this method.
+    fn verify(&self, headers: &hyper::HeaderMap, body: &[u8]) -> bool {
+        match self {
+            Auth::ApiKey { header, keys } => headers
+                .get(header)
+                .is_some_and(|value| keys.iter().any(|key| constant_time_eq(key.as_bytes(), value.as_bytes()))),
+            Auth::Basic { credentials } => headers
+                .get(hyper::header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Basic "))
+                .and_then(|value| base64::engine::general_purpose::STANDARD.decode(value.trim()).ok())
+                .is_some_and(|value| credentials.iter().any(|x| constant_time_eq(x.as_bytes(), &value))),
+            Auth::Hmac { header, secret } => {
+                let signature = match headers.get(header).and_then(|value| value.to_str().ok()) {
+                    Some(value) => decode_hex(value.strip_prefix("sha256=").unwrap_or(value)),
+                    None => None,
+                };
+                let Some(signature) = signature else {
+                    return false;
+                };
+                let mut mac = <hmac::Hmac<sha2::Sha256> as hmac::Mac>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+                hmac::Mac::update(&mut mac, body);
+                hmac::Mac::verify_slice(mac, &signature).is_ok()
+            }
+        }
+    }
+}
+
+/// Compare two byte strings in a time that does not depend on their content.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| value.get(i..i + 2).and_then(|x| u8::from_str_radix(x, 16).ok()))
+        .collect()
+}
+
+fn unauthorized(auth: &[Auth]) -> hyper::Response<http_body_util::Full<hyper::body::Bytes>> {
+    let mut res = response(hyper::StatusCode::UNAUTHORIZED, "Unauthorized");
+    if auth.iter().any(|x| matches!(x, Auth::Basic { .. })) {
+        res.headers_mut().insert(
+            hyper::header::WWW_AUTHENTICATE,
+            hyper::header::HeaderValue::from_static("Basic realm=\"edgeless\""),
+        );
+    }
+    res
+}
+
+/// Token bucket limiting the rate of requests.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TokenBucket {
+    /// Tokens added per second.
+    rate: f64,
+    /// Maximum number of tokens.
+    burst: f64,
+    tokens: f64,
+    last: tokio::time::Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: f64, now: tokio::time::Instant) -> Self {
+        Self {
+            rate,
+            burst,
+            tokens: burst,
+            last: now,
+        }
+    }
+
+    fn refill(&mut self, now: tokio::time::Instant) {
+        self.tokens = (self.tokens + now.saturating_duration_since(self.last).as_secs_f64() * self.rate).min(self.burst);
+        self.last = now;
+    }
+
+    /// Return the time until a token is available, if there is none now.
+    fn wait(&self) -> Option<tokio::time::Duration> {
+        if self.tokens >= 1.0 {
+            None
+        } else {
+            Some(tokio::time::Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// How clients are identified for per-client limits.
+#[derive(Clone, Debug, PartialEq)]
+enum ClientKey {
+    /// IP address of the client.
+    Ip,
+    /// Value of the header with the API key.
+    ApiKey(hyper::header::HeaderName),
+}
+
+struct ClientState {
+    bucket: Option<TokenBucket>,
+    active: usize,
+}
+
+impl ClientState {
+    fn is_idle(&self, now: tokio::time::Instant) -> bool {
+        self.active == 0
+            && self.bucket.is_none_or(|mut bucket| {
+                bucket.refill(now);
+                bucket.tokens >= bucket.burst
+            })
+    }
+}
+
+#[derive(Default)]
+struct ThrottleState {
+    bucket: Option<TokenBucket>,
+    clients: std::collections::HashMap<String, ClientState>,
+}
+
+/// Beyond this number of clients tracked, the idle ones are forgotten.
+const MAX_THROTTLE_CLIENTS: usize = 1024;
+
+/// Rate limits of a resource, global and per client, and per-client
+/// concurrency caps.
+struct Throttle {
+    /// Rate (requests/s) and burst of all the requests.
+    rate: Option<(f64, f64)>,
+    /// Rate (requests/s) and burst of the requests of every client.
+    client_rate: Option<(f64, f64)>,
+    /// Maximum number of requests of every client served concurrently.
+    client_concurrency: Option<usize>,
+    client_key: ClientKey,
+    state: std::sync::Mutex<ThrottleState>,
+}
+
+/// Request rejected by a `Throttle`.
+#[derive(Debug, PartialEq)]
+struct Throttled {
+    reason: &'static str,
+    retry_after: tokio::time::Duration,
+}
+
+/// Held while serving a request subject to per-client concurrency caps.
+struct ThrottlePermit {
+    throttle: std::sync::Arc<Throttle>,
+    client: String,
+}
+
+impl Drop for ThrottlePermit {
+    fn drop(&mut self) {
+        if let Some(client) = self.throttle.state.lock().unwrap().clients.get_mut(&self.client) {
+            client.active -= 1;
+        }
+    }
+}
+
+impl Throttle {
+    /// Parse the limits from the resource configuration, if any.
+    fn from_configuration(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Option<Throttle>> {
+        let number = |name: &str| -> anyhow::Result<f64> {
+            match configuration.get(name).filter(|x| !x.is_empty()) {
+                Some(value) => match value.parse::<f64>() {
+                    Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+                    _ => anyhow::bail!("invalid {} '{}'", name, value),
+                },
+                None => Ok(0.0),
+            }
+        };
+        let rate = |rate_name: &str, burst_name: &str| -> anyhow::Result<Option<(f64, f64)>> {
+            let rate = number(rate_name)?;
+            let burst = number(burst_name)?;
+            Ok(if rate > 0.0 {
+                Some((rate, if burst > 0.0 { burst.max(1.0) } else { rate.max(1.0) }))
+            } else {
+                None
+            })
+        };
+
+        let rate_limit = rate("rate_limit", "rate_burst")?;
+        let client_rate = rate("client_rate_limit", "client_rate_burst")?;
+        let client_concurrency = number("client_max_concurrency")? as usize;
+        let client_key = match configuration.get("client_key").map(|x| x.as_str()).unwrap_or("ip") {
+            "ip" | "" => ClientKey::Ip,
+            "api_key" => ClientKey::ApiKey(
+                hyper::header::HeaderName::from_str(
+                    configuration
+                        .get("api_key_header")
+                        .filter(|x| !x.is_empty())
+                        .map_or("x-api-key", |x| x.as_str()),
+                )
+                .map_err(|err| anyhow::anyhow!("invalid api_key_header: {}", err))?,
+            ),
+            value => anyhow::bail!("invalid client_key '{}', expected ip or api_key", value),
+        };
+
+        if rate_limit.is_none() && client_rate.is_none() && client_concurrency == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Throttle {
+            rate: rate_limit,
+            client_rate,
+            client_concurrency: if client_concurrency > 0 { Some(client_concurrency) } else { None },
+            client_key,
+            state: std::sync::Mutex::new(ThrottleState::default()),
+        }))
+    }
+
+    /// Admit a request if within all the limits. The permit returned, if any,
+    /// must be held until the request has been served.
+    fn admit(
+        self: &std::sync::Arc<Self>,
+        headers: &hyper::HeaderMap,
+        remote_addr: &std::net::IpAddr,
+        now: tokio::time::Instant,
+    ) -> Result<Option<ThrottlePermit>, Throttled> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        if let Some((rate, burst)) = self.rate {
+            let bucket = state.bucket.get_or_insert_with(|| TokenBucket::new(rate, burst, now));
+            bucket.refill(now);
+            if let Some(retry_after) = bucket.wait() {
+                return Err(Throttled { reason: "rate", retry_after });
+            }
+        }
+
+        let client = if self.client_rate.is_some() || self.client_concurrency.is_some() {
+            Some(match &self.client_key {
+                ClientKey::Ip => remote_addr.to_string(),
+                ClientKey::ApiKey(header) => headers
+                    .get(header)
+                    .map(|x| String::from_utf8_lossy(x.as_bytes()).to_string())
+                    .unwrap_or_default(),
+            })
+        } else {
+            None
+        };
+        if let Some(client) = &client {
+            if state.clients.len() >= MAX_THROTTLE_CLIENTS {
+                state.clients.retain(|_, x| !x.is_idle(now));
+            }
+            let entry = state.clients.entry(client.clone()).or_insert_with(|| ClientState {
+                bucket: self.client_rate.map(|(rate, burst)| TokenBucket::new(rate, burst, now)),
+                active: 0,
+            });
+            if let Some(bucket) = &mut entry.bucket {
+                bucket.refill(now);
+                if let Some(retry_after) = bucket.wait() {
+                    return Err(Throttled { reason: "rate", retry_after });
+                }
+            }
+            if let Some(client_concurrency) = self.client_concurrency
+                && entry.active >= client_concurrency
+            {
+                return Err(Throttled {
+                    reason: "concurrency",
+                    retry_after: tokio::time::Duration::from_secs(1),
+                });
+            }
+            if let Some(bucket) = &mut entry.bucket {
+                bucket.tokens -= 1.0;
+            }
+            entry.active += 1;
+        }
+        if let Some(bucket) = &mut state.bucket {
+            bucket.tokens -= 1.0;
+        }
+
+        Ok(client.map(|client| ThrottlePermit {
+            throttle: self.clone(),
+            client,
+        }))
+    }
+}
+
+fn too_many_requests(retry_after: tokio::time::Duration) -> hyper::Response<http_body_util::Full<hyper::body::Bytes>> {
+    let mut res = response(hyper::StatusCode::TOO_MANY_REQUESTS, "Too Many Requests");
+    res.headers_mut().insert(
+        hyper::header::RETRY_AFTER,
+        hyper::header::HeaderValue::from(retry_after.as_secs_f64().ceil().max(1.0) as u64),
+    );
+    res
+}
+
+type ActiveResources = std::collections::HashMap<ComponentId, std::sync::Arc<ResourceDesc>>;
+
 struct IngressState {
-    active_resources: std::collections::HashMap<ComponentId, ResourceDesc>,
+    /// Copy-on-write map of the active resources: a snapshot is taken for
+    /// every HTTP request, so that the lock is not held while serving it.
+    active_resources: std::sync::Arc<ActiveResources>,
     dataplane: edgeless_dataplane::handle::DataplaneHandle,
+    telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+}
+
+impl IngressState {
+    fn observe_throttled(&mut self, resource_id: &ComponentId, reason: &str) {
+        self.telemetry_handle.observe(
+            edgeless_telemetry::telemetry_events::TelemetryEvent::ResourceThrottled(reason.to_string()),
+            std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), resource_id.to_string())]),
+        );
+    }
+
+    /// Observe the outcome of a request cast to the target of a resource instance.
+    fn observe_send(&mut self, resource_id: &ComponentId, target: edgeless_api::function_instance::InstanceId, size: usize, delivered: bool) {
+        let mut telemetry_handle = self.resource_telemetry_handle(resource_id);
+        crate::dataplane_metrics::observe_send(telemetry_handle.as_mut(), "new_request", Some(target), size, delivered);
+    }
+
+    /// Observe the outcome of a request called on the target of a resource
+    /// instance, after the call returned or timed out.
+    fn observe_call(
+        &mut self,
+        resource_id: &ComponentId,
+        target: edgeless_api::function_instance::InstanceId,
+        size: usize,
+        res: &edgeless_dataplane::core::CallRet,
+    ) {
+        let mut telemetry_handle = self.resource_telemetry_handle(resource_id);
+        crate::dataplane_metrics::observe_call(telemetry_handle.as_mut(), "new_request", target, size, res);
+    }
+
+    fn resource_telemetry_handle(&mut self, resource_id: &ComponentId) -> Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> {
+        self.telemetry_handle
+            .fork(std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), resource_id.to_string())]))
+    }
+
+    /// Start the span of an HTTP request, which continues the trace of the
+    /// client if the request carries a W3C `traceparent` header.
+    fn start_span(&self, headers: &hyper::HeaderMap) -> edgeless_telemetry::telemetry_events::TelemetrySpan {
+        let parent = parse_traceparent(headers).unwrap_or(edgeless_api::function_instance::EventMetadata::empty_new_root());
+        edgeless_telemetry::telemetry_events::TelemetrySpan::start(
+            self.telemetry_handle.as_ref(),
+            "http-ingress",
+            edgeless_telemetry::telemetry_events::SpanKind::Server,
+            &parent,
+        )
+    }
+
+    fn end_span(&mut self, span: edgeless_telemetry::telemetry_events::TelemetrySpan) {
+        span.end(self.telemetry_handle.as_mut());
+    }
+}
+
+/// Parse the trace context of a W3C `traceparent` header, if present and
+/// valid, i.e., `00-<32 hex digits trace id>-<16 hex digits parent id>-<flags>`.
+fn parse_traceparent(headers: &hyper::HeaderMap) -> Option<edgeless_api::function_instance::EventMetadata> {
+    let value = headers.get("traceparent")?.to_str().ok()?;
+    let mut fields = value.trim().split('-');
+    let (version, trace_id, parent_id, _flags) = (fields.next()?, fields.next()?, fields.next()?, fields.next()?);
+    if version != "00" || fields.next().is_some() || trace_id.len() != 32 || parent_id.len() != 16 {
+        return None;
+    }
+    let metadata = edgeless_api::function_instance::EventMetadata::from_uints(
+        u128::from_str_radix(trace_id, 16).ok()?,
+        u64::from_str_radix(parent_id, 16).ok()?,
+    );
+    metadata.has_trace().then_some(metadata)
 }
 
 #[derive(Clone)]
 struct IngressService {
     listen_addr: String,
+    remote_addr: std::net::IpAddr,
     interests: std::sync::Arc<tokio::sync::Mutex<IngressState>>,
 }
 
+fn response(status: hyper::StatusCode, body: &str) -> hyper::Response<http_body_util::Full<hyper::body::Bytes>> {
+    let mut res = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(body.to_string())));
+    *res.status_mut() = status;
+    res
+}
+
 impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for IngressService {
     type Response = hyper::Response<http_body_util::Full<hyper::body::Bytes>>;
 
@@ -90,11 +733,15 @@ impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for IngressS
     fn call(&self, req: hyper::Request<hyper::body::Incoming>) -> Self::Future {
         let cloned = self.interests.clone();
         let cloned_addr = self.listen_addr.clone();
-        let mut rng = rand::rngs::StdRng::from_entropy();
+        let remote_addr = self.remote_addr;
         Box::pin(async move {
-            let mut lck = cloned.lock().await;
+            let (active_resources, mut dataplane) = {
+                let lck = cloned.lock().await;
+                (lck.active_resources.clone(), lck.dataplane.clone())
+            };
 
             let query = req.uri().query().unwrap_or_default().to_string();
+            let path = req.uri().path().to_string();
             let (parts, body) = req.into_parts();
 
             let host = match parts.headers.get(hyper::header::HOST) {
@@ -113,105 +760,302 @@ impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for IngressS
                 }
             }
 
-            let data = body.collect().await?.to_bytes();
-
-            // Find the set of matching resources for this HTTP request.
+            // Find the set of resources matching the host, workflow
+            // identifier, and path of this HTTP request.
             let mut matching = vec![];
-            for desc in lck.active_resources.values() {
+            for (resource_id, desc) in active_resources.iter() {
                 if (desc.host.is_none() || desc.host == Some(host.to_string()))
-                    && (desc.allow.is_empty() || desc.allow.contains(&method))
                     && (desc.wf_id.is_none() || desc.wf_id == wf_id)
                     && desc.target.is_some()
+                    && let Some(path_params) = desc.path.matches(&path)
                 {
-                    matching.push(desc);
+                    matching.push((resource_id, desc, path_params));
                 }
             }
 
-            // Choone one resource at random, if any.
-            if let Some(desc) = matching.choose(&mut rng) {
-                let target = desc.target.unwrap();
-
-                if desc.async_out {
-                    // Invoke the next component via cast().
-                    let msg = if desc.encode_base64 {
-                        base64::engine::general_purpose::STANDARD.encode(&data)
-                    } else {
-                        String::from_utf8(data.to_vec())?
-                    };
-                    lck.dataplane
-                        .send(target, msg, &edgeless_api::function_instance::EventMetadata::empty_new_root())
-                        .await;
-                    let mut ok_res = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from("OK")));
-                    *ok_res.status_mut() = hyper::StatusCode::OK;
-                    return Ok(ok_res);
+            // Keep only the most specific ones.
+            let precedence = |desc: &ResourceDesc| (desc.path.precedence(), desc.host.is_some(), desc.wf_id.is_some());
+            if let Some(best) = matching.iter().map(|(_, desc, _)| precedence(desc)).max() {
+                matching.retain(|(_, desc, _)| precedence(desc) == best);
+            }
+
+            // Select the one allowing the method, which is unique because
+            // conflicting resources are rejected when started.
+            let (resource_id, desc, path_params) = match matching.iter().find(|(_, desc, _)| desc.allow.is_empty() || desc.allow.contains(&method)) {
+                Some((resource_id, desc, path_params)) => (resource_id, desc, path_params),
+                None if matching.is_empty() => return Ok(response(hyper::StatusCode::NOT_FOUND, "Not Found")),
+                None => {
+                    let mut allow = matching
+                        .iter()
+                        .flat_map(|(_, desc, _)| desc.allow.iter().map(|x| edgeless_http::edgeless_method_to_string(x.clone())))
+                        .collect::<Vec<String>>();
+                    allow.sort();
+                    allow.dedup();
+                    let mut not_allowed = response(hyper::StatusCode::METHOD_NOT_ALLOWED, "Method Not Allowed");
+                    if let Ok(allow) = hyper::header::HeaderValue::from_str(&allow.join(", ")) {
+                        not_allowed.headers_mut().insert(hyper::header::ALLOW, allow);
+                    }
+                    return Ok(not_allowed);
+                }
+            };
+            let target = desc.target.unwrap();
+
+            // Reject unauthenticated requests, those authenticated with the
+            // body are verified once it has been read.
+            if !desc.auth.iter().filter(|x| !x.needs_body()).all(|x| x.verify(&parts.headers, &[])) {
+                return Ok(unauthorized(&desc.auth));
+            }
+
+            // Reject the request if the resource is already serving the
+            // maximum number of requests allowed or the rate limits are
+            // exceeded, otherwise hold the permits until the request has
+            // been served.
+            let _permit = match &desc.concurrency {
+                Some(semaphore) => match semaphore.clone().try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    Err(_) => {
+                        cloned.lock().await.observe_throttled(resource_id, "concurrency");
+                        return Ok(too_many_requests(tokio::time::Duration::from_secs(1)));
+                    }
+                },
+                None => None,
+            };
+            let _throttle_permit = match &desc.throttle {
+                Some(throttle) => match throttle.admit(&parts.headers, &remote_addr, tokio::time::Instant::now()) {
+                    Ok(permit) => permit,
+                    Err(throttled) => {
+                        cloned.lock().await.observe_throttled(resource_id, throttled.reason);
+                        return Ok(too_many_requests(throttled.retry_after));
+                    }
+                },
+                None => None,
+            };
+
+            let data = match desc.max_body_size {
+                Some(max_body_size) => match http_body_util::Limited::new(body, max_body_size).collect().await {
+                    Ok(collected) => collected.to_bytes(),
+                    Err(err) if err.downcast_ref::<http_body_util::LengthLimitError>().is_some() => {
+                        return Ok(response(hyper::StatusCode::PAYLOAD_TOO_LARGE, "Payload Too Large"));
+                    }
+                    Err(err) => return Err(anyhow::anyhow!(err)),
+                },
+                None => body.collect().await?.to_bytes(),
+            };
+            if !desc.auth.iter().filter(|x| x.needs_body()).all(|x| x.verify(&parts.headers, &data)) {
+                return Ok(unauthorized(&desc.auth));
+            }
+
+            let mut span = cloned.lock().await.start_span(&parts.headers);
+            span.set_attribute("edgeless.resource_id", resource_id);
+            span.set_attribute("http.request.method", &parts.method);
+            span.set_attribute("url.path", &path);
+
+            if desc.async_out {
+                // Invoke the next component via cast().
+                let msg = if desc.encode_base64 {
+                    base64::engine::general_purpose::STANDARD.encode(&data)
                 } else {
-                    // Invoke the next component via call().
-                    let msg = edgeless_http::EdgelessHTTPRequest {
-                        host: host.to_string(),
-                        protocol: edgeless_http::EdgelessHTTPProtocol::Unknown,
-                        method: method.clone(),
-                        path: parts.uri.to_string(),
-                        body: Some(Vec::from(data)),
-                        headers: parts
-                            .headers
-                            .iter()
-                            .filter_map(|(k, v)| match v.to_str() {
-                                Ok(header_value) => Some((k.to_string(), header_value.to_string())),
-                                Err(_) => {
-                                    log::warn!("Bad Header Value.");
-                                    None
-                                }
-                            })
-                            .collect(),
-                    };
-                    let serialized_msg = serde_json::to_string(&msg)?;
-                    let res = lck
-                        .dataplane
-                        .call(target, serialized_msg, &edgeless_api::function_instance::EventMetadata::empty_new_root())
-                        .await;
-                    if let edgeless_dataplane::core::CallRet::Reply(data) = res {
-                        let processor_response: edgeless_http::EdgelessHTTPResponse = serde_json::from_str(&data)?;
-                        let mut response_builder = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(
-                            processor_response.body.unwrap_or_default(),
-                        )));
-                        *response_builder.status_mut() = hyper::StatusCode::from_u16(processor_response.status)?;
-                        {
-                            let headers = response_builder.headers_mut();
-                            for (header_key, header_val) in processor_response.headers {
-                                if let (Ok(key), Ok(value)) = (
-                                    hyper::header::HeaderName::from_bytes(header_key.as_bytes()),
-                                    hyper::header::HeaderValue::from_str(&header_val),
-                                ) {
-                                    headers.append(key, value);
-                                }
-                            }
+                    String::from_utf8(data.to_vec())?
+                };
+                let size = msg.len();
+                let delivered = dataplane.send(target, msg, &span.metadata).await;
+                let mut lck = cloned.lock().await;
+                lck.observe_send(resource_id, target, size, delivered);
+                lck.end_span(span);
+                return Ok(response(hyper::StatusCode::OK, "OK"));
+            }
+
+            // Invoke the next component via call().
+            let msg = edgeless_http::EdgelessHTTPRequest {
+                host: host.to_string(),
+                protocol: edgeless_http::EdgelessHTTPProtocol::Unknown,
+                method: method.clone(),
+                path: parts.uri.to_string(),
+                path_params: path_params.clone(),
+                body: Some(Vec::from(data)),
+                headers: parts
+                    .headers
+                    .iter()
+                    .filter_map(|(k, v)| match v.to_str() {
+                        Ok(header_value) => Some((k.to_string(), header_value.to_string())),
+                        Err(_) => {
+                            log::warn!("Bad Header Value.");
+                            None
+                        }
+                    })
+                    .collect(),
+            };
+            let serialized_msg = serde_json::to_string(&msg)?;
+            let size = serialized_msg.len();
+            let metadata = span.metadata.clone();
+            let res = match desc.timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, dataplane.call(target, serialized_msg, &metadata)).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        span.set_error("timeout");
+                        let mut lck = cloned.lock().await;
+                        lck.observe_call(resource_id, target, size, &edgeless_dataplane::core::CallRet::Err);
+                        lck.end_span(span);
+                        return Ok(response(hyper::StatusCode::GATEWAY_TIMEOUT, "Gateway Timeout"));
+                    }
+                },
+                None => dataplane.call(target, serialized_msg, &metadata).await,
+            };
+            if !matches!(res, edgeless_dataplane::core::CallRet::Reply(_)) {
+                span.set_error("no reply");
+            }
+            {
+                let mut lck = cloned.lock().await;
+                lck.observe_call(resource_id, target, size, &res);
+                lck.end_span(span);
+            }
+            if let edgeless_dataplane::core::CallRet::Reply(data) = res {
+                let processor_response: edgeless_http::EdgelessHTTPResponse = serde_json::from_str(&data)?;
+                let mut response_builder = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(
+                    processor_response.body.unwrap_or_default(),
+                )));
+                *response_builder.status_mut() = hyper::StatusCode::from_u16(processor_response.status)?;
+                {
+                    let headers = response_builder.headers_mut();
+                    for (header_key, header_val) in processor_response.headers {
+                        if let (Ok(key), Ok(value)) = (
+                            hyper::header::HeaderName::from_bytes(header_key.as_bytes()),
+                            hyper::header::HeaderValue::from_str(&header_val),
+                        ) {
+                            headers.append(key, value);
                         }
-                        return Ok(response_builder);
                     }
                 }
+                return Ok(response_builder);
             }
 
-            let mut not_found = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from("Not Found")));
-            *not_found.status_mut() = hyper::StatusCode::NOT_FOUND;
-            Ok(not_found)
+            Ok(response(hyper::StatusCode::NOT_FOUND, "Not Found"))
+        })
+    }
+}
+
+/// Server certificate of the HTTPS server, reloaded when the certificate or
+/// key files are modified.
+#[derive(Debug)]
+struct CertResolver {
+    cert_path: String,
+    key_path: String,
+    current: std::sync::RwLock<(Option<std::time::SystemTime>, std::sync::Arc<rustls::sign::CertifiedKey>)>,
+}
+
+impl CertResolver {
+    fn new(cert_path: &str, key_path: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            cert_path: cert_path.to_string(),
+            key_path: key_path.to_string(),
+            current: std::sync::RwLock::new((Self::modified(cert_path, key_path), std::sync::Arc::new(Self::load(cert_path, key_path)?))),
         })
     }
+
+    /// Return the most recent modification time of the two files.
+    fn modified(cert_path: &str, key_path: &str) -> Option<std::time::SystemTime> {
+        [cert_path, key_path]
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).and_then(|x| x.modified()).ok())
+            .max()
+    }
+
+    fn load(cert_path: &str, key_path: &str) -> anyhow::Result<rustls::sign::CertifiedKey> {
+        use rustls::pki_types::pem::PemObject;
+        let certs = rustls::pki_types::CertificateDer::pem_file_iter(cert_path)?.collect::<Result<Vec<_>, _>>()?;
+        anyhow::ensure!(!certs.is_empty(), "no certificate found in {}", cert_path);
+        let key = rustls::pki_types::PrivateKeyDer::from_pem_file(key_path)?;
+        Ok(rustls::sign::CertifiedKey::new(
+            certs,
+            rustls::crypto::ring::sign::any_supported_type(&key)?,
+        ))
+    }
+
+    /// Reload the certificate and key if the files have been modified since
+    /// the last time they were loaded. The current ones are kept on error.
+    fn reload_if_modified(&self) {
+        let modified = Self::modified(&self.cert_path, &self.key_path);
+        if modified == self.current.read().unwrap().0 {
+            return;
+        }
+        match Self::load(&self.cert_path, &self.key_path) {
+            Ok(certified_key) => {
+                log::info!("reloaded the http-ingress certificate from {}", self.cert_path);
+                *self.current.write().unwrap() = (modified, std::sync::Arc::new(certified_key));
+            }
+            Err(err) => log::error!("could not reload the http-ingress certificate from {}: {}", self.cert_path, err),
+        }
+    }
+}
+
+impl rustls::server::ResolvesServerCert for CertResolver {
+    fn resolve(&self, _client_hello: rustls::server::ClientHello<'_>) -> Option<std::sync::Arc<rustls::sign::CertifiedKey>> {
+        Some(self.current.read().unwrap().1.clone())
+    }
+}
+
+/// Create the TLS acceptor of the HTTPS server and start the task that
+/// reloads the server certificate when it changes.
+fn tls_acceptor(settings: &crate::HttpIngressTlsSettings) -> anyhow::Result<tokio_rustls::TlsAcceptor> {
+    let provider = std::sync::Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone()).with_safe_default_protocol_versions()?;
+    let builder = if settings.client_ca_path.is_empty() {
+        builder.with_no_client_auth()
+    } else {
+        use rustls::pki_types::pem::PemObject;
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls::pki_types::CertificateDer::pem_file_iter(&settings.client_ca_path)? {
+            roots.add(cert?)?;
+        }
+        builder.with_client_cert_verifier(rustls::server::WebPkiClientVerifier::builder_with_provider(std::sync::Arc::new(roots), provider).build()?)
+    };
+
+    let resolver = std::sync::Arc::new(CertResolver::new(&settings.cert_path, &settings.key_path)?);
+    let mut config = builder.with_cert_resolver(resolver.clone());
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    if settings.reload_interval_sec > 0 {
+        let interval = tokio::time::Duration::from_secs(settings.reload_interval_sec);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                resolver.reload_if_modified();
+            }
+        });
+    }
+
+    Ok(tokio_rustls::TlsAcceptor::from(std::sync::Arc::new(config)))
 }
 
 pub async fn ingress_task(
     dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
     ingress_id: edgeless_api::function_instance::InstanceId,
     ingress_url: String,
-) -> Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId>> {
+    tls: Option<crate::HttpIngressTlsSettings>,
+    telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+) -> anyhow::Result<Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId>>> {
     let mut provider = dataplane_provider;
-    let (_, host, port) = edgeless_api::util::parse_http_host(&ingress_url).unwrap();
-    let addr = std::net::SocketAddr::from((std::net::IpAddr::from_str(&host).unwrap(), port));
+    let (proto, host, port) = edgeless_api::util::parse_http_host(&ingress_url)?;
+    let addr = std::net::SocketAddr::from((std::net::IpAddr::from_str(&host)?, port));
+
+    let tls_acceptor = match tls.filter(|tls| !tls.cert_path.is_empty()) {
+        Some(tls) => Some(tls_acceptor(&tls)?),
+        None => None,
+    };
+    if tls_acceptor.is_some() != (proto == edgeless_api::util::Proto::HTTPS) {
+        log::warn!(
+            "the http-ingress URL is {} but TLS is {}",
+            ingress_url,
+            if tls_acceptor.is_some() { "enabled" } else { "disabled" }
+        );
+    }
 
     let dataplane = provider.get_handle_for(ingress_id).await;
 
     let ingress_state = std::sync::Arc::new(tokio::sync::Mutex::new(IngressState {
-        active_resources: std::collections::HashMap::new(),
+        active_resources: std::sync::Arc::new(std::collections::HashMap::new()),
         dataplane,
+        telemetry_handle,
     }));
 
     let cloned_interests = ingress_state.clone();
@@ -219,38 +1063,45 @@ pub async fn ingress_task(
     let _web_task: tokio::task::JoinHandle<anyhow::Result<()>> = tokio::spawn(async move {
         let listener = tokio::net::TcpListener::bind(addr).await?;
         loop {
-            let (stream, _) = match listener.accept().await {
+            let (stream, peer) = match listener.accept().await {
                 Ok(val) => val,
                 Err(_) => {
                     log::error!("Accept Error");
                     continue;
                 }
             };
-            let io = hyper_util::rt::TokioIo::new(stream);
             let cloned_interests = cloned_interests.clone();
             let cloned_host = host.clone();
             let cloned_port = port;
+            let cloned_tls_acceptor = tls_acceptor.clone();
             tokio::task::spawn(async move {
-                if let Err(err) = hyper::server::conn::http1::Builder::new()
-                    .serve_connection(
-                        io,
-                        IngressService {
-                            interests: cloned_interests,
-                            listen_addr: format!("{}:{}", cloned_host, cloned_port).to_string(),
-                        },
-                    )
-                    .await
-                {
+                let service = IngressService {
+                    interests: cloned_interests,
+                    listen_addr: format!("{}:{}", cloned_host, cloned_port).to_string(),
+                    remote_addr: peer.ip(),
+                };
+                let builder = hyper::server::conn::http1::Builder::new();
+                let res = match cloned_tls_acceptor {
+                    Some(acceptor) => match acceptor.accept(stream).await {
+                        Ok(stream) => builder.serve_connection(hyper_util::rt::TokioIo::new(stream), service).await,
+                        Err(err) => {
+                            log::warn!("TLS handshake with {} failed: {}", peer, err);
+                            return;
+                        }
+                    },
+                    None => builder.serve_connection(hyper_util::rt::TokioIo::new(stream), service).await,
+                };
+                if let Err(err) = res {
                     println!("Error serving connection: {:?}", err);
                 }
             });
         }
     });
 
-    Box::new(IngressResource {
+    Ok(Box::new(IngressResource {
         own_node_id: ingress_id.node_id,
         configuration_state: ingress_state,
-    })
+    }))
 }
 
 #[derive(Clone)]
@@ -288,6 +1139,20 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
                 }
             };
         }
+        let path = match PathPattern::new(
+            instance_specification.configuration.get("path"),
+            instance_specification.configuration.get("path_prefix"),
+        ) {
+            Ok(path) => path,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Error when creating a resource".to_string(),
+                        detail: Some(format!("Invalid path specified in http-ingress: {err}")),
+                    },
+                ));
+            }
+        };
         let wf_id = if instance_specification
             .configuration
             .get("wf_id")
@@ -308,31 +1173,106 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
             .get("encode_base64")
             .unwrap_or(&String::from("false"))
             .eq_ignore_ascii_case("true");
+        let mut limits = [0_u64; 3];
+        for (limit, name) in limits.iter_mut().zip(["max_concurrency", "timeout_ms", "max_body_size"]) {
+            if let Some(value) = instance_specification.configuration.get(name) {
+                match value.parse::<u64>() {
+                    Ok(value) => *limit = value,
+                    Err(err) => {
+                        return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                            edgeless_api::common::ResponseError {
+                                summary: "Error when creating a resource".to_string(),
+                                detail: Some(format!("Invalid {name} '{value}' specified in http-ingress: {err}")),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        let [max_concurrency, timeout_ms, max_body_size] = limits;
+        let throttle = match Throttle::from_configuration(&instance_specification.configuration) {
+            Ok(throttle) => throttle.map(std::sync::Arc::new),
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Error when creating a resource".to_string(),
+                        detail: Some(format!("Invalid rate limits specified in http-ingress: {err}")),
+                    },
+                ));
+            }
+        };
+        let auth = match Auth::from_configuration(&instance_specification.configuration) {
+            Ok(auth) => auth,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Error when creating a resource".to_string(),
+                        detail: Some(format!("Invalid authentication specified in http-ingress: {err}")),
+                    },
+                ));
+            }
+        };
 
-        // Assign a new component identifier to the newly-created  resource.
-        log::info!(
-            "created a new http-ingress resource: host {:?}, methods allowed {:?}, wf_id {:?}, {}",
+        let desc = ResourceDesc {
             host,
             allow,
+            path,
             wf_id,
-            if async_out { "cast" } else { "call" }
-        );
-        let resource_id = edgeless_api::function_instance::InstanceId::new(self.own_node_id);
-        lck.active_resources.insert(
-            resource_id.function_id,
-            ResourceDesc {
-                host,
-                allow,
-                wf_id,
-                async_out,
-                encode_base64,
-                target: None, // will be set by patch()
+            async_out,
+            encode_base64,
+            concurrency: if max_concurrency > 0 {
+                Some(std::sync::Arc::new(tokio::sync::Semaphore::new(max_concurrency as usize)))
+            } else {
+                None
+            },
+            timeout: if timeout_ms > 0 {
+                Some(tokio::time::Duration::from_millis(timeout_ms))
+            } else {
+                None
             },
+            max_body_size: if max_body_size > 0 { Some(max_body_size as usize) } else { None },
+            auth,
+            throttle,
+            target: None, // will be set by patch()
+        };
+
+        // Reject the resource if it would match the same requests as an
+        // existing one.
+        if let Some(existing_id) = lck
+            .active_resources
+            .iter()
+            .find_map(|(id, existing)| if existing.conflicts_with(&desc) { Some(id) } else { None })
+        {
+            return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Error when creating a resource".to_string(),
+                    detail: Some(format!(
+                        "http-ingress with host {:?}, path {}, wf_id {:?} conflicts with existing resource '{}'",
+                        desc.host, desc.path, desc.wf_id, existing_id
+                    )),
+                },
+            ));
+        }
+
+        // Assign a new component identifier to the newly-created  resource.
+        log::info!(
+            "created a new http-ingress resource: host {:?}, methods allowed {:?}, path {}, wf_id {:?}, {}, max concurrency {}, timeout {} ms, max body size {}",
+            desc.host,
+            desc.allow,
+            desc.path,
+            desc.wf_id,
+            if desc.async_out { "cast" } else { "call" },
+            max_concurrency,
+            timeout_ms,
+            max_body_size
         );
+        let resource_id = edgeless_api::function_instance::InstanceId::new(self.own_node_id);
+        std::sync::Arc::make_mut(&mut lck.active_resources).insert(resource_id.function_id, std::sync::Arc::new(desc));
         Ok(edgeless_api::common::StartComponentResponse::InstanceId(resource_id))
     }
     async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
-        self.configuration_state.lock().await.active_resources.remove(&resource_id.function_id);
+        let mut lck = self.configuration_state.lock().await;
+        std::sync::Arc::make_mut(&mut lck.active_resources).remove(&resource_id.function_id);
         Ok(())
     }
 
@@ -342,12 +1282,619 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
             update.function_id
         ))?;
         let mut lck = self.configuration_state.lock().await;
-        let desc = lck.active_resources.get_mut(&update.function_id).ok_or(anyhow::anyhow!(
-            "Trying to patch a non-existing resource with PID '{}'",
-            update.function_id
-        ))?;
-        desc.target = Some(*target);
+        let desc = std::sync::Arc::make_mut(&mut lck.active_resources)
+            .get_mut(&update.function_id)
+            .ok_or(anyhow::anyhow!(
+                "Trying to patch a non-existing resource with PID '{}'",
+                update.function_id
+            ))?;
+        std::sync::Arc::make_mut(desc).target = Some(*target);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::NoTelemetryHandle;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    /// Start an http-ingress on a free port and return its URL, the API to
+    /// configure its resources, and the dataplane provider of the node.
+    async fn start_ingress(
+        invocation_port: u16,
+        tls: Option<crate::HttpIngressTlsSettings>,
+    ) -> (
+        String,
+        Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId>>,
+        edgeless_dataplane::handle::DataplaneProvider,
+    ) {
+        let node_id = uuid::Uuid::new_v4();
+        let provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, format!("http://127.0.0.1:{}", invocation_port), None).await;
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let url = format!("{}://{}", if tls.is_some() { "https" } else { "http" }, addr);
+        let ingress = ingress_task(
+            provider.clone(),
+            edgeless_api::function_instance::InstanceId::new(node_id),
+            url.clone(),
+            tls,
+            Box::new(NoTelemetryHandle {}),
+        )
+        .await
+        .unwrap();
+
+        // Wait for the HTTP server to accept connections.
+        for _ in 0..100 {
+            if tokio::net::TcpStream::connect(addr).await.is_ok() {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        }
+        (url, ingress, provider)
+    }
+
+    /// Start an http-ingress resource and return the dataplane handle of
+    /// the target of its requests.
+    async fn start_resource(
+        ingress: &mut Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId>>,
+        provider: &mut edgeless_dataplane::handle::DataplaneProvider,
+        configuration: &[(&str, &str)],
+    ) -> edgeless_dataplane::handle::DataplaneHandle {
+        let resource_id = match ingress
+            .start(edgeless_api::resource_configuration::ResourceInstanceSpecification {
+                class_type: "http-ingress".to_string(),
+                configuration: configuration.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                workflow_id: "workflow_1".to_string(),
+            })
+            .await
+            .unwrap()
+        {
+            edgeless_api::common::StartComponentResponse::InstanceId(resource_id) => resource_id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        };
+        let target_id = edgeless_api::function_instance::InstanceId::new(resource_id.node_id);
+        let target = provider.get_handle_for(target_id).await;
+        ingress
+            .patch(edgeless_api::common::PatchRequest {
+                function_id: resource_id.function_id,
+                output_mapping: std::collections::HashMap::from([("new_request".to_string(), target_id)]),
+            })
+            .await
+            .unwrap();
+        target
+    }
+
+    /// Reply to a request received by a target with the given body.
+    async fn reply(target: &mut edgeless_dataplane::handle::DataplaneHandle, request: edgeless_dataplane::core::DataplaneEvent, body: &str) {
+        let response = edgeless_http::EdgelessHTTPResponse {
+            body: Some(body.as_bytes().to_vec()),
+            status: 200,
+            headers: std::collections::HashMap::new(),
+        };
+        target
+            .reply(
+                request.source_id,
+                request.channel_id,
+                edgeless_dataplane::core::CallRet::Reply(serde_json::to_string(&response).unwrap()),
+                &request.metadata,
+            )
+            .await;
+    }
+
+    /// Path of a test certificate or key.
+    fn test_cert(name: &str) -> String {
+        format!("{}/src/resources/tls_certs/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    /// Send a GET request over HTTPS to the given address, trusting only the
+    /// test CA and presenting the given client certificate, if any.
+    /// Return the status of the response and the certificate of the server.
+    async fn https_get(
+        addr: String,
+        path: &'static str,
+        client_cert: Option<&'static str>,
+    ) -> anyhow::Result<(hyper::StatusCode, rustls::pki_types::CertificateDer<'static>)> {
+        use rustls::pki_types::pem::PemObject;
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(rustls::pki_types::CertificateDer::from_pem_file(test_cert("ca.pem"))?)?;
+        let builder = rustls::ClientConfig::builder_with_provider(std::sync::Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots);
+        let config = match client_cert {
+            Some(name) => builder.with_client_auth_cert(
+                vec![rustls::pki_types::CertificateDer::from_pem_file(test_cert(&format!("{}.pem", name)))?],
+                rustls::pki_types::PrivateKeyDer::from_pem_file(test_cert(&format!("{}.key", name)))?,
+            )?,
+            None => builder.with_no_client_auth(),
+        };
+
+        let stream = tokio::net::TcpStream::connect(&addr).await?;
+        let stream = tokio_rustls::TlsConnector::from(std::sync::Arc::new(config))
+            .connect(rustls::pki_types::ServerName::try_from("127.0.0.1")?.to_owned(), stream)
+            .await?;
+        let server_cert = match stream.get_ref().1.peer_certificates().and_then(|certs| certs.first()) {
+            Some(cert) => cert.clone().into_owned(),
+            None => anyhow::bail!("no server certificate"),
+        };
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(hyper_util::rt::TokioIo::new(stream)).await?;
+        tokio::spawn(connection);
+        let res = sender
+            .send_request(
+                hyper::Request::get(path)
+                    .header(hyper::header::HOST, addr)
+                    .body(http_body_util::Empty::<hyper::body::Bytes>::new())?,
+            )
+            .await?;
+        Ok((res.status(), server_cert))
+    }
+
+    fn desc(host: Option<&str>, methods: &[edgeless_http::EdgelessHTTPMethod], path: PathPattern) -> ResourceDesc {
+        ResourceDesc {
+            host: host.map(|x| x.to_string()),
+            allow: methods.iter().cloned().collect(),
+            path,
+            wf_id: None,
+            async_out: false,
+            encode_base64: false,
+            concurrency: None,
+            timeout: None,
+            max_body_size: None,
+            auth: vec![],
+            throttle: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn test_http_ingress_path_pattern() {
+        let any = PathPattern::new(None, Some(&String::new())).unwrap();
+        let exact = PathPattern::new(Some(&"/api/orders/{id}".to_string()), None).unwrap();
+        let prefix = PathPattern::new(None, Some(&"/api".to_string())).unwrap();
+        let root = PathPattern::new(None, Some(&"/".to_string())).unwrap();
+        assert_eq!(PathPattern::Any, any);
+        assert_eq!("/api/orders/{id}", exact.to_string());
+        assert_eq!("/api*", prefix.to_string());
+        assert_eq!("/*", root.to_string());
+
+        assert!(PathPattern::new(Some(&"/a".to_string()), Some(&"/b".to_string())).is_err());
+        assert!(PathPattern::new(Some(&"a".to_string()), None).is_err());
+        assert!(PathPattern::new(Some(&"/a/{}".to_string()), None).is_err());
+        assert!(PathPattern::new(Some(&"/a/{x}/{x}".to_string()), None).is_err());
+        assert!(PathPattern::new(Some(&"/a/x{y}".to_string()), None).is_err());
+
+        assert_eq!(
+            Some(std::collections::HashMap::from([("id".to_string(), "42".to_string())])),
+            exact.matches("/api/orders/42")
+        );
+        assert!(exact.matches("/api/orders/42/").is_some());
+        assert!(exact.matches("/api/orders").is_none());
+        assert!(exact.matches("/api/orders/42/items").is_none());
+        assert!(exact.matches("/api/users/42").is_none());
+
+        assert!(prefix.matches("/api").is_some());
+        assert!(prefix.matches("/api/users").is_some());
+        assert!(prefix.matches("/apiary").is_none());
+        assert!(prefix.matches("/").is_none());
+        assert!(root.matches("/").is_some());
+        assert!(root.matches("/anything").is_some());
+        assert!(any.matches("/anything").is_some());
+
+        // Precedence: exact, then longest prefix, then literals over params.
+        let prefix_long = PathPattern::new(None, Some(&"/api/orders".to_string())).unwrap();
+        let prefix_param = PathPattern::new(None, Some(&"/api/{resource}".to_string())).unwrap();
+        assert!(exact.precedence() > prefix_long.precedence());
+        assert!(prefix_long.precedence() > prefix_param.precedence());
+        assert!(prefix_param.precedence() > prefix.precedence());
+        assert!(prefix.precedence() > root.precedence());
+        assert!(root.precedence() > any.precedence());
+    }
+
+    #[test]
+    fn test_http_ingress_conflicts() {
+        use edgeless_http::EdgelessHTTPMethod::*;
+        let orders = || PathPattern::new(Some(&"/api/orders/{id}".to_string()), None).unwrap();
+        let orders_other_name = || PathPattern::new(Some(&"/api/orders/{order}".to_string()), None).unwrap();
+        let users = || PathPattern::new(Some(&"/api/users/{id}".to_string()), None).unwrap();
+
+        assert!(desc(None, &[], PathPattern::Any).conflicts_with(&desc(None, &[], PathPattern::Any)));
+        assert!(!desc(None, &[], PathPattern::Any).conflicts_with(&desc(Some("a.com"), &[], PathPattern::Any)));
+        assert!(desc(None, &[], orders()).conflicts_with(&desc(None, &[Get], orders_other_name())));
+        assert!(!desc(None, &[], orders()).conflicts_with(&desc(None, &[], users())));
+        assert!(!desc(None, &[Get], orders()).conflicts_with(&desc(None, &[Post, Put], orders())));
+        assert!(desc(None, &[Get, Post], orders()).conflicts_with(&desc(None, &[Post], orders())));
+        assert!(!desc(None, &[], orders()).conflicts_with(&desc(None, &[], PathPattern::Any)));
+    }
+
+    #[test]
+    fn test_http_ingress_auth() {
+        let configuration = |entries: &[(&str, &str)]| -> std::collections::HashMap<String, String> {
+            entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let headers = |entries: &[(&'static str, &'static str)]| -> hyper::HeaderMap {
+            entries
+                .iter()
+                .map(|(k, v)| (hyper::header::HeaderName::from_static(k), hyper::header::HeaderValue::from_static(v)))
+                .collect()
+        };
+
+        assert!(Auth::from_configuration(&configuration(&[])).unwrap().is_empty());
+        assert!(Auth::from_configuration(&configuration(&[("basic_auth", "alice")])).is_err());
+        assert!(Auth::from_configuration(&configuration(&[("api_keys", "k1"), ("api_key_header", "bad header")])).is_err());
+
+        let auth = Auth::from_configuration(&configuration(&[
+            ("api_keys", "k1,k2"),
+            ("api_key_header", "x-token"),
+            ("basic_auth", "alice:pwd,bob:pwd"),
+            ("hmac_secret", "secret"),
+        ]))
+        .unwrap();
+        assert_eq!(3, auth.len());
+        let (api_key, basic, hmac) = (&auth[0], &auth[1], &auth[2]);
+        assert!(!api_key.needs_body() && !basic.needs_body() && hmac.needs_body());
+
+        assert!(api_key.verify(&headers(&[("x-token", "k2")]), &[]));
+        assert!(!api_key.verify(&headers(&[("x-token", "k3")]), &[]));
+        assert!(!api_key.verify(&headers(&[("x-api-key", "k1")]), &[]));
+
+        assert!(basic.verify(&headers(&[("authorization", "Basic YWxpY2U6cHdk")]), &[]));
+        assert!(!basic.verify(&headers(&[("authorization", "Basic YWxpY2U6cHdl")]), &[]));
+        assert!(!basic.verify(&headers(&[("authorization", "Bearer YWxpY2U6cHdk")]), &[]));
+        assert!(!basic.verify(&headers(&[]), &[]));
+
+        let signature = "88aab3ede8d3adf94d26ab90d3bafd4a2083070c3bcce9c014ee04a443847c0b";
+        assert!(hmac.verify(&headers(&[("x-signature", signature)]), b"hello"));
+        assert!(hmac.verify(
+            &headers(&[("x-signature", "sha256=88aab3ede8d3adf94d26ab90d3bafd4a2083070c3bcce9c014ee04a443847c0b")]),
+            b"hello"
+        ));
+        assert!(!hmac.verify(&headers(&[("x-signature", signature)]), b"hello!"));
+        assert!(!hmac.verify(&headers(&[("x-signature", "not-hex")]), b"hello"));
+        assert!(!hmac.verify(&headers(&[]), b"hello"));
+    }
+
+    #[test]
+    fn test_http_ingress_throttle() {
+        let configuration = |entries: &[(&str, &str)]| -> std::collections::HashMap<String, String> {
+            entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let throttle = |entries: &[(&str, &str)]| std::sync::Arc::new(Throttle::from_configuration(&configuration(entries)).unwrap().unwrap());
+        let ip = |x: &str| std::net::IpAddr::from_str(x).unwrap();
+        let no_headers = hyper::HeaderMap::new();
+        let now = tokio::time::Instant::now();
+
+        assert!(Throttle::from_configuration(&configuration(&[])).unwrap().is_none());
+        assert!(Throttle::from_configuration(&configuration(&[("rate_limit", "-1")])).is_err());
+        assert!(Throttle::from_configuration(&configuration(&[("rate_limit", "x")])).is_err());
+        assert!(Throttle::from_configuration(&configuration(&[("client_rate_limit", "1"), ("client_key", "cookie")])).is_err());
+
+        // Global rate limit and per-client concurrency cap.
+        let limits = throttle(&[("rate_limit", "10"), ("rate_burst", "2"), ("client_max_concurrency", "1")]);
+        let permit_1 = limits.admit(&no_headers, &ip("10.0.0.1"), now).unwrap();
+        assert!(permit_1.is_some());
+        assert_eq!(
+            Err(Throttled {
+                reason: "concurrency",
+                retry_after: tokio::time::Duration::from_secs(1)
+            }),
+            limits.admit(&no_headers, &ip("10.0.0.1"), now).map(|_| ())
+        );
+        let _permit_2 = limits.admit(&no_headers, &ip("10.0.0.2"), now).unwrap();
+        assert_eq!(
+            Err(Throttled {
+                reason: "rate",
+                retry_after: tokio::time::Duration::from_millis(100)
+            }),
+            limits.admit(&no_headers, &ip("10.0.0.3"), now).map(|_| ())
+        );
+        drop(permit_1);
+        assert!(
+            limits
+                .admit(&no_headers, &ip("10.0.0.1"), now + tokio::time::Duration::from_millis(100))
+                .is_ok()
+        );
+
+        // Per-client rate limit, with clients identified by API key.
+        let limits = throttle(&[("client_rate_limit", "1"), ("client_key", "api_key"), ("api_key_header", "x-token")]);
+        let key = |value: &'static str| -> hyper::HeaderMap {
+            [(
+                hyper::header::HeaderName::from_static("x-token"),
+                hyper::header::HeaderValue::from_static(value),
+            )]
+            .into_iter()
+            .collect()
+        };
+        assert!(limits.admit(&key("k1"), &ip("10.0.0.1"), now).is_ok());
+        assert!(limits.admit(&key("k1"), &ip("10.0.0.2"), now).is_err());
+        assert!(limits.admit(&key("k2"), &ip("10.0.0.1"), now).is_ok());
+        assert!(
+            limits
+                .admit(&key("k1"), &ip("10.0.0.1"), now + tokio::time::Duration::from_secs(1))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_http_ingress_traceparent() {
+        let headers = |value: &'static str| -> hyper::HeaderMap {
+            [(
+                hyper::header::HeaderName::from_static("traceparent"),
+                hyper::header::HeaderValue::from_static(value),
+            )]
+            .into_iter()
+            .collect()
+        };
+
+        let metadata = parse_traceparent(&headers("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")).unwrap();
+        assert_eq!(
+            edgeless_api::function_instance::EventMetadata::from_uints(0x4bf92f3577b34da6a3ce929d0e0e4736, 0x00f067aa0ba902b7),
+            metadata
+        );
+
+        assert!(parse_traceparent(&hyper::HeaderMap::new()).is_none());
+        for invalid in [
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473z-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-00",
+        ] {
+            assert!(parse_traceparent(&headers(invalid)).is_none(), "{}", invalid);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_service_routing() {
+        let (url, mut ingress, mut provider) = start_ingress(7108, None).await;
+        let mut post_target = start_resource(&mut ingress, &mut provider, &[("path", "/orders/{id}"), ("method", "POST")]).await;
+        let _put_target = start_resource(&mut ingress, &mut provider, &[("path", "/orders/{id}"), ("method", "PUT,DELETE")]).await;
+        let client = reqwest::Client::new();
+
+        // Unknown path.
+        let res = client.post(format!("{}/customers/42", url)).send().await.unwrap();
+        assert_eq!(reqwest::StatusCode::NOT_FOUND, res.status());
+
+        // Known path, but no resource allows the method.
+        let res = client.get(format!("{}/orders/42", url)).send().await.unwrap();
+        assert_eq!(reqwest::StatusCode::METHOD_NOT_ALLOWED, res.status());
+        assert_eq!("DELETE, POST, PUT", res.headers().get(reqwest::header::ALLOW).unwrap().to_str().unwrap());
+
+        // Known path and method.
+        let post = tokio::spawn(client.post(format!("{}/orders/42", url)).send());
+        let request = post_target.receive_next().await;
+        let path_params = match &request.message {
+            edgeless_dataplane::core::Message::Call(msg) => serde_json::from_str::<edgeless_http::EdgelessHTTPRequest>(msg).unwrap().path_params,
+            _ => panic!("wrong message received"),
+        };
+        assert_eq!(Some(&"42".to_string()), path_params.get("id"));
+        reply(&mut post_target, request, "created").await;
+        let res = post.await.unwrap().unwrap();
+        assert_eq!(reqwest::StatusCode::OK, res.status());
+        assert_eq!("created", res.text().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_service_limits() {
+        let (url, mut ingress, mut provider) = start_ingress(7107, None).await;
+        let mut slow_target = start_resource(&mut ingress, &mut provider, &[("path", "/slow"), ("timeout_ms", "1000")]).await;
+        let mut fast_target = start_resource(&mut ingress, &mut provider, &[("path", "/fast")]).await;
+        let _small_target = start_resource(&mut ingress, &mut provider, &[("path", "/small"), ("max_body_size", "4")]).await;
+        let mut limited_target = start_resource(&mut ingress, &mut provider, &[("path", "/limited"), ("max_concurrency", "1")]).await;
+        let client = reqwest::Client::new();
+
+        // A target that does not reply does not block the requests to
+        // another target, and it times out eventually.
+        let slow = tokio::spawn(client.get(format!("{}/slow", url)).send());
+        let _slow_request = slow_target.receive_next().await;
+        let fast = tokio::spawn(client.get(format!("{}/fast", url)).send());
+        let fast_request = fast_target.receive_next().await;
+        reply(&mut fast_target, fast_request, "fast").await;
+        let res = fast.await.unwrap().unwrap();
+        assert_eq!(reqwest::StatusCode::OK, res.status());
+        assert_eq!("fast", res.text().await.unwrap());
+        assert!(!slow.is_finished());
+        let res = slow.await.unwrap().unwrap();
+        assert_eq!(reqwest::StatusCode::GATEWAY_TIMEOUT, res.status());
+
+        // The body is too large.
+        let res = client.post(format!("{}/small", url)).body("too large").send().await.unwrap();
+        assert_eq!(reqwest::StatusCode::PAYLOAD_TOO_LARGE, res.status());
+
+        // The concurrency limit is exceeded while the first request is
+        // being served, and not anymore once it has been served.
+        let first = tokio::spawn(client.get(format!("{}/limited", url)).send());
+        let first_request = limited_target.receive_next().await;
+        let res = client.get(format!("{}/limited", url)).send().await.unwrap();
+        assert_eq!(reqwest::StatusCode::TOO_MANY_REQUESTS, res.status());
+        assert!(res.headers().contains_key(reqwest::header::RETRY_AFTER));
+        reply(&mut limited_target, first_request, "first").await;
+        assert_eq!(reqwest::StatusCode::OK, first.await.unwrap().unwrap().status());
+        let second = tokio::spawn(client.get(format!("{}/limited", url)).send());
+        let second_request = limited_target.receive_next().await;
+        reply(&mut limited_target, second_request, "second").await;
+        assert_eq!("second", second.await.unwrap().unwrap().text().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_service_throttle() {
+        let (url, mut ingress, mut provider) = start_ingress(7113, None).await;
+        let _rated_target = start_resource(
+            &mut ingress,
+            &mut provider,
+            &[("path", "/rated"), ("async", "true"), ("rate_limit", "0.1"), ("rate_burst", "2")],
+        )
+        .await;
+        let _client_rated_target = start_resource(
+            &mut ingress,
+            &mut provider,
+            &[
+                ("path", "/client_rated"),
+                ("async", "true"),
+                ("client_rate_limit", "0.1"),
+                ("client_rate_burst", "1"),
+                ("client_key", "api_key"),
+            ],
+        )
+        .await;
+        let mut capped_target = start_resource(
+            &mut ingress,
+            &mut provider,
+            &[("path", "/capped"), ("client_max_concurrency", "1"), ("client_key", "api_key")],
+        )
+        .await;
+        let client = reqwest::Client::new();
+
+        // The burst is accepted, then requests are rejected until the next
+        // token is available, i.e., after 1 / 0.1 = 10 s.
+        for _ in 0..2 {
+            let res = client.post(format!("{}/rated", url)).body("x").send().await.unwrap();
+            assert_eq!(reqwest::StatusCode::OK, res.status());
+        }
+        let res = client.post(format!("{}/rated", url)).body("x").send().await.unwrap();
+        assert_eq!(reqwest::StatusCode::TOO_MANY_REQUESTS, res.status());
+        assert_eq!("10", res.headers().get(reqwest::header::RETRY_AFTER).unwrap().to_str().unwrap());
+
+        // A client exceeding its rate does not affect the others.
+        let post = |api_key: &'static str| client.post(format!("{}/client_rated", url)).header("x-api-key", api_key).body("x").send();
+        assert_eq!(reqwest::StatusCode::OK, post("a").await.unwrap().status());
+        let res = post("a").await.unwrap();
+        assert_eq!(reqwest::StatusCode::TOO_MANY_REQUESTS, res.status());
+        assert!(res.headers().contains_key(reqwest::header::RETRY_AFTER));
+        assert_eq!(reqwest::StatusCode::OK, post("b").await.unwrap().status());
+
+        // A client at its concurrency cap does not affect the others.
+        let get = |api_key: &'static str| client.get(format!("{}/capped", url)).header("x-api-key", api_key).send();
+        let first = tokio::spawn(get("a"));
+        let first_request = capped_target.receive_next().await;
+        let res = get("a").await.unwrap();
+        assert_eq!(reqwest::StatusCode::TOO_MANY_REQUESTS, res.status());
+        assert!(res.headers().contains_key(reqwest::header::RETRY_AFTER));
+        let other = tokio::spawn(get("b"));
+        let other_request = capped_target.receive_next().await;
+        reply(&mut capped_target, other_request, "b").await;
+        assert_eq!("b", other.await.unwrap().unwrap().text().await.unwrap());
+        reply(&mut capped_target, first_request, "a").await;
+        assert_eq!("a", first.await.unwrap().unwrap().text().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_service_https() {
+        let tls = crate::HttpIngressTlsSettings {
+            cert_path: test_cert("server-1.pem"),
+            key_path: test_cert("server-1.key"),
+            client_ca_path: String::default(),
+            reload_interval_sec: 0,
+        };
+        let (url, mut ingress, mut provider) = start_ingress(7109, Some(tls)).await;
+        let addr = url.trim_start_matches("https://").to_string();
+        let mut target = start_resource(&mut ingress, &mut provider, &[("path", "/hello")]).await;
+
+        let get = tokio::spawn(https_get(addr.clone(), "/hello", None));
+        let request = target.receive_next().await;
+        reply(&mut target, request, "world").await;
+        let (status, server_cert) = get.await.unwrap().unwrap();
+        assert_eq!(hyper::StatusCode::OK, status);
+        assert_eq!(
+            CertResolver::load(&test_cert("server-1.pem"), &test_cert("server-1.key")).unwrap().cert[0],
+            server_cert
+        );
+
+        // Plain HTTP requests are not served.
+        assert!(reqwest::get(format!("http://{}/hello", addr)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_service_https_reload() {
+        let dir = std::env::temp_dir().join(format!("edgeless-http-ingress-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+        let install = |name: &str| {
+            std::fs::copy(test_cert(&format!("{}.key", name)), &key_path).unwrap();
+            std::fs::copy(test_cert(&format!("{}.pem", name)), &cert_path).unwrap();
+        };
+        let expected_cert = |name: &str| {
+            CertResolver::load(&test_cert(&format!("{}.pem", name)), &test_cert(&format!("{}.key", name)))
+                .unwrap()
+                .cert[0]
+                .clone()
+        };
+
+        install("server-1");
+        let tls = crate::HttpIngressTlsSettings {
+            cert_path: cert_path.to_string_lossy().to_string(),
+            key_path: key_path.to_string_lossy().to_string(),
+            client_ca_path: String::default(),
+            reload_interval_sec: 1,
+        };
+        let (url, _ingress, _provider) = start_ingress(7110, Some(tls)).await;
+        let addr = url.trim_start_matches("https://").to_string();
+
+        let (status, server_cert) = https_get(addr.clone(), "/", None).await.unwrap();
+        assert_eq!(hyper::StatusCode::NOT_FOUND, status);
+        assert_eq!(expected_cert("server-1"), server_cert);
+
+        // The new certificate is served after the next check of the files,
+        // without restarting the server.
+        install("server-2");
+        let mut reloaded = false;
+        for _ in 0..50 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            if let Ok((_, server_cert)) = https_get(addr.clone(), "/", None).await
+                && server_cert == expected_cert("server-2")
+            {
+                reloaded = true;
+                break;
+            }
+        }
+        assert!(reloaded);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_service_client_cert() {
+        let tls = crate::HttpIngressTlsSettings {
+            cert_path: test_cert("server-1.pem"),
+            key_path: test_cert("server-1.key"),
+            client_ca_path: test_cert("ca.pem"),
+            reload_interval_sec: 0,
+        };
+        let (url, _ingress, _provider) = start_ingress(7111, Some(tls)).await;
+        let addr = url.trim_start_matches("https://").to_string();
+
+        // Client certificate signed by the CA.
+        let (status, _) = https_get(addr.clone(), "/", Some("client")).await.unwrap();
+        assert_eq!(hyper::StatusCode::NOT_FOUND, status);
+
+        // Client certificate signed by another CA, or no certificate at all.
+        assert!(https_get(addr.clone(), "/", Some("other-client")).await.is_err());
+        assert!(https_get(addr.clone(), "/", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_ingress_service_auth() {
+        let (url, mut ingress, mut provider) = start_ingress(7112, None).await;
+        let mut target = start_resource(&mut ingress, &mut provider, &[("path", "/private"), ("api_keys", "k1")]).await;
+        let client = reqwest::Client::new();
+
+        // Unauthenticated requests are rejected without reaching the target.
+        let res = client.get(format!("{}/private", url)).send().await.unwrap();
+        assert_eq!(reqwest::StatusCode::UNAUTHORIZED, res.status());
+        let res = client.get(format!("{}/private", url)).header("x-api-key", "k2").send().await.unwrap();
+        assert_eq!(reqwest::StatusCode::UNAUTHORIZED, res.status());
+        assert!(
+            tokio::time::timeout(tokio::time::Duration::from_millis(200), target.receive_next())
+                .await
+                .is_err()
+        );
+
+        // Authenticated request.
+        let get = tokio::spawn(client.get(format!("{}/private", url)).header("x-api-key", "k1").send());
+        let request = target.receive_next().await;
+        reply(&mut target, request, "secret").await;
+        let res = get.await.unwrap().unwrap();
+        assert_eq!(reqwest::StatusCode::OK, res.status());
+        assert_eq!("secret", res.text().await.unwrap());
+    }
+}
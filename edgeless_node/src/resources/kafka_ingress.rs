@@ -0,0 +1,559 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use rdkafka::Message;
+use rdkafka::consumer::Consumer;
+
+pub struct KafkaIngressResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for KafkaIngressResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("kafka-ingress")
+    }
+
+    fn description(&self) -> String {
+        r"Consume records from an external Apache Kafka server -- see https://kafka.apache.org/
+
+Each record is sent via call() on the output channel 'out' and its offset is committed only after the call succeeds.
+The partitions are shared among all the resource instances with the same consumer group, which by default is
+derived from the workflow identifier and the topics, so that the committed offsets survive restarts and migrations."
+            .to_string()
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("out")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("brokers"),
+                String::from("Comma-separated list of initial brokers to access the cluster"),
+            ),
+            (
+                String::from("topic"),
+                String::from("Comma-separated list of topics from which records are consumed"),
+            ),
+            (
+                String::from("group-id"),
+                String::from("Consumer group. Default: edgeless- followed by the workflow identifier and the comma-separated topics"),
+            ),
+            (
+                String::from("auto-offset-reset"),
+                String::from("Where to start when there is no committed offset: earliest or latest. Default: latest"),
+            ),
+            (
+                String::from("retry-ms"),
+                String::from("Interval between two attempts to deliver a record, in ms, must be positive. Default: 1000"),
+            ),
+            (
+                String::from("max-retries"),
+                String::from("Number of failed attempts after which a record is skipped, 0 means never. Default: 0"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+#[derive(Clone)]
+pub struct KafkaIngressResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<KafkaIngressResourceProviderInner>>,
+}
+
+pub struct KafkaIngressResourceProviderInner {
+    node_id: edgeless_api::function_instance::NodeId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, KafkaIngressResource>,
+}
+
+pub struct KafkaIngressResource {
+    join_handle: tokio::task::JoinHandle<()>,
+    target: Option<edgeless_api::function_instance::InstanceId>,
+}
+
+impl Drop for KafkaIngressResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+/// Configuration of a kafka-ingress resource instance.
+#[derive(Debug, Clone, PartialEq)]
+struct KafkaIngressConfiguration {
+    brokers: String,
+    topics: Vec<String>,
+    group_id: String,
+    auto_offset_reset: String,
+    retry_interval: std::time::Duration,
+    max_retries: u32,
+}
+
+impl KafkaIngressConfiguration {
+    fn from_configuration(configuration: &std::collections::HashMap<String, String>, workflow_id: &str) -> anyhow::Result<Self> {
+        let (brokers, topic) = match (configuration.get("brokers"), configuration.get("topic")) {
+            (Some(brokers), Some(topic)) => (brokers, topic),
+            _ => anyhow::bail!("One of the fields 'brokers' or 'topic' is missing"),
+        };
+        let topics: Vec<String> = topic.split(',').map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect();
+        if topics.is_empty() {
+            anyhow::bail!("No topic specified");
+        }
+
+        let auto_offset_reset = configuration.get("auto-offset-reset").cloned().unwrap_or(String::from("latest"));
+        if auto_offset_reset != "earliest" && auto_offset_reset != "latest" {
+            anyhow::bail!("Invalid auto-offset-reset value: {}", auto_offset_reset);
+        }
+
+        let retry_ms = match configuration.get("retry-ms") {
+            Some(retry_ms) => match retry_ms.parse::<u64>() {
+                Ok(retry_ms) if retry_ms > 0 => retry_ms,
+                _ => anyhow::bail!("Invalid retry-ms value: {}", retry_ms),
+            },
+            None => 1000,
+        };
+        let max_retries = match configuration.get("max-retries") {
+            Some(max_retries) => max_retries
+                .parse::<u32>()
+                .map_err(|_| anyhow::anyhow!("Invalid max-retries value: {}", max_retries))?,
+            None => 0,
+        };
+
+        let group_id = match configuration.get("group-id") {
+            Some(group_id) => group_id.clone(),
+            None => format!("edgeless-{}-{}", workflow_id, topics.join(",")),
+        };
+
+        Ok(Self {
+            brokers: brokers.clone(),
+            topics,
+            group_id,
+            auto_offset_reset,
+            retry_interval: std::time::Duration::from_millis(retry_ms),
+            max_retries,
+        })
+    }
+}
+
+/// Record consumed, detached from the consumer.
+#[derive(Debug, Clone, PartialEq)]
+struct KafkaRecord {
+    topic: String,
+    partition: i32,
+    offset: i64,
+    payload: String,
+}
+
+/// Source of the records delivered by a kafka-ingress resource instance,
+/// which can be replaced to test the delivery without a Kafka server.
+#[async_trait::async_trait]
+trait RecordConsumer: Send + Sync + 'static {
+    /// Wait for the next record.
+    async fn next_record(&self) -> anyhow::Result<KafkaRecord>;
+
+    /// Commit the offset of the record following the given one.
+    fn commit_record(&self, record: &KafkaRecord) -> anyhow::Result<()>;
+}
+
+#[async_trait::async_trait]
+impl RecordConsumer for rdkafka::consumer::StreamConsumer {
+    async fn next_record(&self) -> anyhow::Result<KafkaRecord> {
+        let msg = self.recv().await?;
+        Ok(KafkaRecord {
+            topic: msg.topic().to_string(),
+            partition: msg.partition(),
+            offset: msg.offset(),
+            payload: String::from_utf8_lossy(msg.payload().unwrap_or_default()).to_string(),
+        })
+    }
+
+    fn commit_record(&self, record: &KafkaRecord) -> anyhow::Result<()> {
+        let mut offsets = rdkafka::TopicPartitionList::new();
+        offsets.add_partition_offset(&record.topic, record.partition, rdkafka::Offset::Offset(record.offset + 1))?;
+        self.commit(&offsets, rdkafka::consumer::CommitMode::Async)?;
+        Ok(())
+    }
+}
+
+/// The kafka-ingress resource joins a consumer group and delivers every
+/// record consumed to the target, one at a time, via a call().
+///
+/// The offset of a record is committed only after the call() has succeeded,
+/// otherwise the delivery is retried: a record may thus be delivered more
+/// than once (at-least-once semantics).
+///
+/// All the instances of the resource in the same consumer group share the
+/// partitions of the topics. By default, the consumer group is derived from
+/// the workflow identifier and the topics, which do not change when the
+/// resource instance is restarted or migrated, so that consumption resumes
+/// from the last offset committed.
+impl KafkaIngressResource {
+    async fn new(
+        self_function_id: edgeless_api::function_instance::ComponentId,
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        inner: std::sync::Arc<tokio::sync::Mutex<KafkaIngressResourceProviderInner>>,
+        configuration: &KafkaIngressConfiguration,
+    ) -> anyhow::Result<Self> {
+        let consumer: rdkafka::consumer::StreamConsumer = rdkafka::config::ClientConfig::new()
+            .set("bootstrap.servers", &configuration.brokers)
+            .set("group.id", &configuration.group_id)
+            .set("enable.auto.commit", "false")
+            .set("auto.offset.reset", &configuration.auto_offset_reset)
+            .create()?;
+        let topics: Vec<&str> = configuration.topics.iter().map(|x| x.as_str()).collect();
+        consumer.subscribe(&topics)?;
+
+        log::info!(
+            "KafkaIngressResource created, brokers: {}, topics: {:?}, group: {}",
+            configuration.brokers,
+            topics,
+            configuration.group_id
+        );
+
+        Ok(Self {
+            join_handle: tokio::spawn(consume(
+                consumer,
+                self_function_id,
+                dataplane_handle,
+                telemetry_handle,
+                inner,
+                configuration.retry_interval,
+                configuration.max_retries,
+            )),
+            target: None,
+        })
+    }
+}
+
+/// Deliver the records of the consumer to the target of the resource
+/// instance, committing their offsets after the delivery, until aborted.
+async fn consume(
+    consumer: impl RecordConsumer,
+    self_function_id: edgeless_api::function_instance::ComponentId,
+    mut dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+    mut telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+    inner: std::sync::Arc<tokio::sync::Mutex<KafkaIngressResourceProviderInner>>,
+    retry_interval: std::time::Duration,
+    max_retries: u32,
+) {
+    loop {
+        let record = match consumer.next_record().await {
+            Ok(record) => record,
+            Err(err) => {
+                log::warn!("Could not consume from Kafka: {}", err);
+                tokio::time::sleep(retry_interval).await;
+                continue;
+            }
+        };
+
+        // Deliver the record until successful or the maximum number
+        // of retries is reached.
+        let started = chrono::Utc::now();
+        let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+            telemetry_handle.as_ref(),
+            "kafka-ingress",
+            edgeless_telemetry::telemetry_events::SpanKind::Consumer,
+        );
+        span.set_attribute("messaging.destination.name", &record.topic);
+        span.set_attribute("messaging.kafka.offset", record.offset);
+        let mut attempts = 0_u32;
+        loop {
+            let target = match inner.lock().await.instances.get(&self_function_id) {
+                Some(instance) => instance.target,
+                None => None,
+            };
+            let Some(target) = target else {
+                // Not patched yet: wait without counting an attempt.
+                tokio::time::sleep(retry_interval).await;
+                continue;
+            };
+            let res = crate::dataplane_metrics::call(
+                &mut dataplane_handle,
+                telemetry_handle.as_mut(),
+                "out",
+                target,
+                record.payload.clone(),
+                &span.metadata,
+            )
+            .await;
+            if res != edgeless_dataplane::core::CallRet::Err {
+                break;
+            }
+            attempts += 1;
+            if max_retries > 0 && attempts >= max_retries {
+                log::error!(
+                    "Skipping Kafka record {}/{}/{} after {} failed attempts: {}",
+                    record.topic,
+                    record.partition,
+                    record.offset,
+                    attempts,
+                    record.payload
+                );
+                span.set_error("too many attempts");
+                break;
+            }
+            tokio::time::sleep(retry_interval).await;
+        }
+        span.set_attribute("edgeless.attempts", attempts);
+        span.end(telemetry_handle.as_mut());
+
+        // Commit the offset of the next record to be consumed.
+        if let Err(err) = consumer.commit_record(&record) {
+            log::error!(
+                "Could not commit Kafka record {}/{}/{}: {}",
+                record.topic,
+                record.partition,
+                record.offset,
+                err
+            );
+        }
+
+        crate::resources::observe_execution(started, &mut telemetry_handle, true);
+    }
+}
+
+impl KafkaIngressResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        node_id: edgeless_api::function_instance::NodeId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(KafkaIngressResourceProviderInner {
+                node_id,
+                dataplane_provider,
+                telemetry_handle,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for KafkaIngressResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.node_id);
+
+        let configuration =
+            match KafkaIngressConfiguration::from_configuration(&instance_specification.configuration, &instance_specification.workflow_id) {
+                Ok(configuration) => configuration,
+                Err(err) => {
+                    return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                        edgeless_api::common::ResponseError {
+                            summary: "Invalid resource configuration".to_string(),
+                            detail: Some(err.to_string()),
+                        },
+                    ));
+                }
+            };
+
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+        let telemetry_handle = lck.telemetry_handle.fork(std::collections::BTreeMap::from([(
+            "FUNCTION_ID".to_string(),
+            new_id.function_id.to_string(),
+        )]));
+
+        match KafkaIngressResource::new(new_id.function_id, dataplane_handle, telemetry_handle, self.inner.clone(), &configuration).await {
+            Ok(resource) => {
+                lck.instances.insert(new_id.function_id, resource);
+                Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+            }
+            Err(err) => Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Invalid resource configuration".to_string(),
+                    detail: Some(err.to_string()),
+                },
+            )),
+        }
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        let mut lck = self.inner.lock().await;
+        if let Some(instance) = lck.instances.get_mut(&update.function_id) {
+            instance.target = update.output_mapping.get("out").cloned();
+        } else {
+            anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::NoTelemetryHandle;
+    use crate::test_utils::configuration;
+
+    /// Consumer returning the records sent on a channel, which keeps track
+    /// of the records committed.
+    struct MockConsumer {
+        records: tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<KafkaRecord>>,
+        commits: std::sync::Arc<std::sync::Mutex<Vec<KafkaRecord>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl RecordConsumer for MockConsumer {
+        async fn next_record(&self) -> anyhow::Result<KafkaRecord> {
+            self.records.lock().await.recv().await.ok_or(anyhow::anyhow!("no more records"))
+        }
+
+        fn commit_record(&self, record: &KafkaRecord) -> anyhow::Result<()> {
+            self.commits.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    fn record(offset: i64, payload: &str) -> KafkaRecord {
+        KafkaRecord {
+            topic: String::from("topic"),
+            partition: 0,
+            offset,
+            payload: payload.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_kafka_ingress_configuration() {
+        let from_configuration = |entries: &[(&str, &str)]| KafkaIngressConfiguration::from_configuration(&configuration(entries), "wf");
+        let required = [("brokers", "localhost:9092"), ("topic", "t1, t2")];
+        let with = |entries: &[(&str, &str)]| from_configuration(&[&required[..], entries].concat());
+
+        assert!(from_configuration(&[]).is_err());
+        assert!(from_configuration(&[("brokers", "localhost:9092")]).is_err());
+        assert!(from_configuration(&[("topic", "t1")]).is_err());
+        assert!(with(&[("topic", " , ")]).is_err());
+        assert!(with(&[("auto-offset-reset", "beginning")]).is_err());
+        assert!(with(&[("retry-ms", "x")]).is_err());
+        assert!(with(&[("retry-ms", "-1")]).is_err());
+        assert!(with(&[("retry-ms", "0")]).is_err());
+        assert!(with(&[("max-retries", "x")]).is_err());
+        assert!(with(&[("max-retries", "-1")]).is_err());
+
+        assert_eq!(
+            KafkaIngressConfiguration {
+                brokers: String::from("localhost:9092"),
+                topics: vec![String::from("t1"), String::from("t2")],
+                group_id: String::from("edgeless-wf-t1,t2"),
+                auto_offset_reset: String::from("latest"),
+                retry_interval: std::time::Duration::from_millis(1000),
+                max_retries: 0,
+            },
+            with(&[]).unwrap()
+        );
+
+        assert_eq!(
+            KafkaIngressConfiguration {
+                brokers: String::from("localhost:9092"),
+                topics: vec![String::from("t1"), String::from("t2")],
+                group_id: String::from("my-group"),
+                auto_offset_reset: String::from("earliest"),
+                retry_interval: std::time::Duration::from_millis(50),
+                max_retries: 3,
+            },
+            with(&[
+                ("group-id", "my-group"),
+                ("auto-offset-reset", "earliest"),
+                ("retry-ms", "50"),
+                ("max-retries", "3")
+            ])
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_kafka_ingress_delivery() {
+        let node_id = uuid::Uuid::new_v4();
+        let mut provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, String::from("http://127.0.0.1:7109"), None).await;
+        let resource_provider = KafkaIngressResourceProvider::new(provider.clone(), Box::new(NoTelemetryHandle {}), node_id).await;
+
+        let resource_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut target = provider.get_handle_for(target_id).await;
+
+        let (record_sender, record_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let commits = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let join_handle = tokio::spawn(consume(
+            MockConsumer {
+                records: tokio::sync::Mutex::new(record_receiver),
+                commits: commits.clone(),
+            },
+            resource_id.function_id,
+            provider.get_handle_for(resource_id).await,
+            Box::new(NoTelemetryHandle {}),
+            resource_provider.inner.clone(),
+            std::time::Duration::from_millis(10),
+            2,
+        ));
+        resource_provider.inner.lock().await.instances.insert(
+            resource_id.function_id,
+            KafkaIngressResource {
+                join_handle,
+                target: Some(target_id),
+            },
+        );
+
+        let wait_commits = |num: usize| {
+            let commits = commits.clone();
+            async move {
+                for _ in 0..100 {
+                    if commits.lock().unwrap().len() >= num {
+                        break;
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                }
+                commits.lock().unwrap().clone()
+            }
+        };
+
+        // The delivery of a record fails twice: the offset is not committed
+        // until the record is skipped after max-retries attempts.
+        record_sender.send(record(10, "first")).unwrap();
+        for _ in 0..2 {
+            let event = target.receive_next().await;
+            assert_eq!(edgeless_dataplane::core::Message::Call(String::from("first")), event.message);
+            assert!(commits.lock().unwrap().is_empty());
+            target
+                .reply(event.source_id, event.channel_id, edgeless_dataplane::core::CallRet::Err, &event.metadata)
+                .await;
+        }
+        assert_eq!(vec![record(10, "first")], wait_commits(1).await);
+
+        // The next record is delivered at the first attempt, and its offset
+        // is committed only after the target has replied.
+        record_sender.send(record(11, "second")).unwrap();
+        let event = target.receive_next().await;
+        assert_eq!(edgeless_dataplane::core::Message::Call(String::from("second")), event.message);
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(1, commits.lock().unwrap().len());
+        target
+            .reply(
+                event.source_id,
+                event.channel_id,
+                edgeless_dataplane::core::CallRet::Reply(String::from("ok")),
+                &event.metadata,
+            )
+            .await;
+        assert_eq!(vec![record(10, "first"), record(11, "second")], wait_commits(2).await);
+
+        // No more calls are received by the target.
+        assert!(
+            tokio::time::timeout(tokio::time::Duration::from_millis(100), target.receive_next())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -9,11 +9,16 @@ pub mod http_ingress;
 pub mod http_poster;
 #[cfg(feature = "rdkafka")]
 pub mod kafka_egress;
+#[cfg(feature = "rdkafka")]
+pub mod kafka_ingress;
+pub mod mqtt;
+pub mod object_store;
 pub mod ollama;
 pub mod redis;
 pub mod resource_provider_specs;
 pub mod serverless;
 pub mod sqlx;
+pub mod websocket_ingress;
 
 pub fn observe_transfer(
     created: edgeless_api::function_instance::EventTimestamp,
@@ -0,0 +1,609 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+pub struct MqttResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for MqttResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("mqtt")
+    }
+
+    fn description(&self) -> String {
+        r"Exchange messages with an external MQTT broker -- see https://mqtt.org/
+
+The messages published on the topics matching the filters in 'subscribe' are cast to the output channel 'out'.
+The messages received via cast() or call() are published on the topic specified in 'topic'."
+            .to_string()
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("out")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (String::from("url"), String::from("URL of the MQTT broker, e.g., mqtt://127.0.0.1:1883")),
+            (
+                String::from("subscribe"),
+                String::from("Comma-separated list of topic filters to which the resource subscribes (optional)"),
+            ),
+            (
+                String::from("topic"),
+                String::from("Topic on which the messages received are published (optional)"),
+            ),
+            (String::from("qos"), String::from("Quality of service: 0, 1, or 2. Default: 0")),
+            (
+                String::from("retain"),
+                String::from("If true, the messages published are retained by the broker. Default: false"),
+            ),
+            (
+                String::from("client-id"),
+                String::from("Client identifier. Default: edgeless- followed by the resource instance identifier"),
+            ),
+            (
+                String::from("username"),
+                String::from("Username to authenticate with the broker (optional)"),
+            ),
+            (
+                String::from("password"),
+                String::from("Password to authenticate with the broker (optional)"),
+            ),
+            (
+                String::from("add-topic"),
+                String::from("If present, cast a JSON object with fields topic and payload, instead of the payload only"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+#[derive(Clone)]
+pub struct MqttResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<MqttResourceProviderInner>>,
+}
+
+struct MqttResourceProviderInner {
+    node_id: edgeless_api::function_instance::NodeId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, MqttResource>,
+}
+
+/// Configuration of an mqtt resource instance.
+#[derive(Debug, Clone, PartialEq)]
+struct MqttConfiguration {
+    host: String,
+    port: u16,
+    subscribe: Vec<String>,
+    topic: Option<String>,
+    qos: rumqttc::QoS,
+    retain: bool,
+    client_id: Option<String>,
+    credentials: Option<(String, String)>,
+    add_topic: bool,
+}
+
+impl MqttConfiguration {
+    fn from_configuration(configuration: &std::collections::HashMap<String, String>) -> anyhow::Result<Self> {
+        let url = match configuration.get("url") {
+            Some(url) => url,
+            None => anyhow::bail!("Missing MQTT broker URL"),
+        };
+        let address = match url.split_once("://") {
+            Some(("mqtt", address)) | Some(("tcp", address)) => address,
+            Some((scheme, _)) => anyhow::bail!("Unsupported scheme '{}' in MQTT broker URL", scheme),
+            None => url.as_str(),
+        };
+        let address = address.trim_end_matches('/');
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse::<u16>()
+                    .map_err(|_| anyhow::anyhow!("Invalid port in MQTT broker URL: {}", url))?,
+            ),
+            None => (address, 1883),
+        };
+        if host.is_empty() {
+            anyhow::bail!("Missing host in MQTT broker URL: {}", url);
+        }
+
+        let subscribe = configuration
+            .get("subscribe")
+            .map(|x| x.split(',').map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect())
+            .unwrap_or_default();
+        let topic = configuration.get("topic").filter(|x| !x.is_empty()).cloned();
+        if let Some(topic) = &topic
+            && (topic.contains('#') || topic.contains('+'))
+        {
+            anyhow::bail!("Wildcards are not allowed in the MQTT topic: {}", topic);
+        }
+
+        let qos = match configuration.get("qos").map(|x| x.as_str()).unwrap_or("0") {
+            "0" => rumqttc::QoS::AtMostOnce,
+            "1" => rumqttc::QoS::AtLeastOnce,
+            "2" => rumqttc::QoS::ExactlyOnce,
+            qos => anyhow::bail!("Invalid MQTT QoS: {}", qos),
+        };
+        let retain = configuration.get("retain").is_some_and(|x| x.eq_ignore_ascii_case("true"));
+
+        let credentials = match (configuration.get("username"), configuration.get("password")) {
+            (Some(username), password) => Some((username.clone(), password.cloned().unwrap_or_default())),
+            (None, Some(_)) => anyhow::bail!("MQTT password specified without a username"),
+            (None, None) => None,
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            subscribe,
+            topic,
+            qos,
+            retain,
+            client_id: configuration.get("client-id").filter(|x| !x.is_empty()).cloned(),
+            credentials,
+            add_topic: configuration.contains_key("add-topic"),
+        })
+    }
+}
+
+/// Message cast to the output channel, if `add-topic` is specified.
+#[derive(serde::Serialize)]
+struct MqttMessage<'a> {
+    topic: &'a str,
+    payload: &'a str,
+}
+
+pub struct MqttResource {
+    join_handles: Vec<tokio::task::JoinHandle<()>>,
+    target: Option<edgeless_api::function_instance::InstanceId>,
+}
+
+impl Drop for MqttResource {
+    fn drop(&mut self) {
+        for join_handle in &self.join_handles {
+            join_handle.abort();
+        }
+    }
+}
+
+/// The mqtt resource connects to an MQTT broker with its own client.
+///
+/// The messages received from the broker on the topics to which the resource
+/// subscribes are cast to the output channel `out`, while the messages
+/// received from the other components are published on the configured topic.
+///
+/// The subscriptions are renewed every time the client (re-)connects.
+impl MqttResource {
+    fn new(
+        configuration: MqttConfiguration,
+        self_function_id: edgeless_api::function_instance::ComponentId,
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        inner: std::sync::Arc<tokio::sync::Mutex<MqttResourceProviderInner>>,
+    ) -> Self {
+        let client_id = configuration
+            .client_id
+            .clone()
+            .unwrap_or_else(|| format!("edgeless-{}", self_function_id));
+        let mut options = rumqttc::MqttOptions::new(client_id, configuration.host.clone(), configuration.port);
+        options.set_keep_alive(std::time::Duration::from_secs(30));
+        if let Some((username, password)) = &configuration.credentials {
+            options.set_credentials(username, password);
+        }
+        let (client, mut event_loop) = rumqttc::AsyncClient::new(options, 100);
+
+        log::info!(
+            "MqttResource created, broker {}:{}, subscribe {:?}, topic {:?}",
+            configuration.host,
+            configuration.port,
+            configuration.subscribe,
+            configuration.topic
+        );
+
+        // Poll the connection with the broker and cast the messages
+        // received to the target, if any.
+        let mut telemetry_handle = telemetry_handle;
+        let mut out_dataplane_handle = dataplane_handle.clone();
+        let mut out_telemetry_handle = telemetry_handle.fork(std::collections::BTreeMap::new());
+        let out_configuration = configuration.clone();
+        let out_client = client.clone();
+        let event_loop_handle = tokio::spawn(async move {
+            loop {
+                match event_loop.poll().await {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => {
+                        for filter in &out_configuration.subscribe {
+                            if let Err(err) = out_client.try_subscribe(filter, out_configuration.qos) {
+                                log::error!("Could not subscribe to MQTT topic filter '{}': {}", filter, err);
+                            }
+                        }
+                    }
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) => {
+                        let target = match inner.lock().await.instances.get(&self_function_id) {
+                            Some(instance) => instance.target,
+                            None => None,
+                        };
+                        let Some(target) = target else {
+                            log::debug!("Discarding MQTT message on topic '{}': no target", publish.topic);
+                            continue;
+                        };
+                        let payload = String::from_utf8_lossy(&publish.payload);
+                        let msg = if out_configuration.add_topic {
+                            serde_json::to_string(&MqttMessage {
+                                topic: &publish.topic,
+                                payload: &payload,
+                            })
+                            .unwrap_or_default()
+                        } else {
+                            payload.to_string()
+                        };
+                        let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+                            out_telemetry_handle.as_ref(),
+                            "mqtt-receive",
+                            edgeless_telemetry::telemetry_events::SpanKind::Consumer,
+                        );
+                        span.set_attribute("messaging.destination.name", &publish.topic);
+                        crate::dataplane_metrics::send(
+                            &mut out_dataplane_handle,
+                            out_telemetry_handle.as_mut(),
+                            "out",
+                            target,
+                            msg,
+                            &span.metadata,
+                        )
+                        .await;
+                        span.end(out_telemetry_handle.as_mut());
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        log::warn!(
+                            "Error on the connection with the MQTT broker {}:{}: {}",
+                            out_configuration.host,
+                            out_configuration.port,
+                            err
+                        );
+                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        // Publish the messages received on the configured topic.
+        let mut dataplane_handle = dataplane_handle;
+        let receive_handle = tokio::spawn(async move {
+            loop {
+                let edgeless_dataplane::core::DataplaneEvent {
+                    source_id,
+                    channel_id,
+                    message,
+                    created,
+                    metadata,
+                } = dataplane_handle.receive_next().await;
+                let started = crate::resources::observe_transfer(created, &mut telemetry_handle);
+
+                let (need_reply, message_data) = match message {
+                    edgeless_dataplane::core::Message::Call(data) => (true, data),
+                    edgeless_dataplane::core::Message::Cast(data) => (false, data),
+                    _ => {
+                        continue;
+                    }
+                };
+
+                let res = match &configuration.topic {
+                    Some(topic) => client
+                        .publish(topic, configuration.qos, configuration.retain, message_data.into_bytes())
+                        .await
+                        .map_err(|err| anyhow::anyhow!("could not publish on topic '{}': {}", topic, err)),
+                    None => Err(anyhow::anyhow!("no topic specified in the configuration")),
+                };
+                if let Err(err) = &res {
+                    log::error!("Invalid MQTT publish operation: {}", err);
+                }
+
+                if need_reply {
+                    dataplane_handle
+                        .reply(
+                            source_id,
+                            channel_id,
+                            match res {
+                                Ok(_) => edgeless_dataplane::core::CallRet::Reply("".to_string()),
+                                Err(_) => edgeless_dataplane::core::CallRet::Err,
+                            },
+                            &metadata,
+                        )
+                        .await;
+                }
+
+                crate::resources::observe_execution(started, &mut telemetry_handle, need_reply);
+            }
+        });
+
+        Self {
+            join_handles: vec![event_loop_handle, receive_handle],
+            target: None,
+        }
+    }
+}
+
+impl MqttResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        node_id: edgeless_api::function_instance::NodeId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(MqttResourceProviderInner {
+                node_id,
+                dataplane_provider,
+                telemetry_handle,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for MqttResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let configuration = match MqttConfiguration::from_configuration(&instance_specification.configuration) {
+            Ok(configuration) => configuration,
+            Err(err) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ));
+            }
+        };
+
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+        let telemetry_handle = lck.telemetry_handle.fork(std::collections::BTreeMap::from([(
+            "FUNCTION_ID".to_string(),
+            new_id.function_id.to_string(),
+        )]));
+        lck.instances.insert(
+            new_id.function_id,
+            MqttResource::new(configuration, new_id.function_id, dataplane_handle, telemetry_handle, self.inner.clone()),
+        );
+
+        Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        let mut lck = self.inner.lock().await;
+        if let Some(instance) = lck.instances.get_mut(&update.function_id) {
+            instance.target = update.output_mapping.get("out").cloned();
+        } else {
+            anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::NoTelemetryHandle;
+    use crate::test_utils::configuration;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    /// Start a minimal in-process MQTT 3.1.1 broker listening on the given
+    /// port, which supports just what the resource needs: connect, subscribe,
+    /// publish (QoS 0 and 1) and keep-alive. Messages are forwarded to the
+    /// matching subscribers with QoS 0.
+    async fn start_broker(port: u16) {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+        let subscribers = std::sync::Arc::new(std::sync::Mutex::new(
+            Vec::<(String, tokio::sync::mpsc::UnboundedSender<bytes::BytesMut>)>::new(),
+        ));
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let subscribers = subscribers.clone();
+                tokio::spawn(async move {
+                    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+                    let (mut reader, mut writer) = stream.into_split();
+                    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<bytes::BytesMut>();
+                    tokio::spawn(async move {
+                        while let Some(frame) = receiver.recv().await {
+                            if writer.write_all(&frame).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+
+                    let mut buffer = bytes::BytesMut::new();
+                    loop {
+                        let packet = match rumqttc::mqttbytes::v4::read(&mut buffer, 1 << 20) {
+                            Ok(packet) => packet,
+                            Err(rumqttc::mqttbytes::Error::InsufficientBytes(_)) => match reader.read_buf(&mut buffer).await {
+                                Ok(0) | Err(_) => break,
+                                Ok(_) => continue,
+                            },
+                            Err(_) => break,
+                        };
+                        let mut frame = bytes::BytesMut::new();
+                        match packet {
+                            rumqttc::Packet::Connect(_) => {
+                                rumqttc::ConnAck::new(rumqttc::ConnectReturnCode::Success, false)
+                                    .write(&mut frame)
+                                    .unwrap();
+                            }
+                            rumqttc::Packet::Subscribe(subscribe) => {
+                                let mut codes = vec![];
+                                for filter in subscribe.filters {
+                                    codes.push(rumqttc::SubscribeReasonCode::Success(filter.qos));
+                                    subscribers.lock().unwrap().push((filter.path, sender.clone()));
+                                }
+                                rumqttc::SubAck::new(subscribe.pkid, codes).write(&mut frame).unwrap();
+                            }
+                            rumqttc::Packet::Publish(publish) => {
+                                if publish.qos == rumqttc::QoS::AtLeastOnce {
+                                    rumqttc::PubAck::new(publish.pkid).write(&mut frame).unwrap();
+                                }
+                                let forwarded = rumqttc::Publish::from_bytes(publish.topic.clone(), rumqttc::QoS::AtMostOnce, publish.payload);
+                                for (filter, subscriber) in subscribers.lock().unwrap().iter() {
+                                    if rumqttc::matches(&forwarded.topic, filter) {
+                                        let mut forwarded_frame = bytes::BytesMut::new();
+                                        forwarded.write(&mut forwarded_frame).unwrap();
+                                        let _ = subscriber.send(forwarded_frame);
+                                    }
+                                }
+                            }
+                            rumqttc::Packet::PingReq => {
+                                rumqttc::PingResp.write(&mut frame).unwrap();
+                            }
+                            rumqttc::Packet::Disconnect => break,
+                            _ => {}
+                        }
+                        if !frame.is_empty() && sender.send(frame).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_mqtt_configuration() {
+        assert!(MqttConfiguration::from_configuration(&configuration(&[])).is_err());
+        assert!(MqttConfiguration::from_configuration(&configuration(&[("url", "mqtts://127.0.0.1:8883")])).is_err());
+        assert!(MqttConfiguration::from_configuration(&configuration(&[("url", "mqtt://127.0.0.1:x")])).is_err());
+        assert!(MqttConfiguration::from_configuration(&configuration(&[("url", "mqtt://:1883")])).is_err());
+        assert!(MqttConfiguration::from_configuration(&configuration(&[("url", "mqtt://host"), ("qos", "3")])).is_err());
+        assert!(MqttConfiguration::from_configuration(&configuration(&[("url", "mqtt://host"), ("topic", "a/#")])).is_err());
+        assert!(MqttConfiguration::from_configuration(&configuration(&[("url", "mqtt://host"), ("password", "pwd")])).is_err());
+
+        let default = MqttConfiguration::from_configuration(&configuration(&[("url", "mqtt://broker.local")])).unwrap();
+        assert_eq!(
+            MqttConfiguration {
+                host: "broker.local".to_string(),
+                port: 1883,
+                subscribe: vec![],
+                topic: None,
+                qos: rumqttc::QoS::AtMostOnce,
+                retain: false,
+                client_id: None,
+                credentials: None,
+                add_topic: false,
+            },
+            default
+        );
+
+        let full = MqttConfiguration::from_configuration(&configuration(&[
+            ("url", "tcp://10.0.0.1:1884/"),
+            ("subscribe", "sensors/+/temperature, alarms/#,"),
+            ("topic", "actuators/valve"),
+            ("qos", "2"),
+            ("retain", "true"),
+            ("client-id", "my-client"),
+            ("username", "alice"),
+            ("password", "secret"),
+            ("add-topic", ""),
+        ]))
+        .unwrap();
+        assert_eq!(
+            MqttConfiguration {
+                host: "10.0.0.1".to_string(),
+                port: 1884,
+                subscribe: vec!["sensors/+/temperature".to_string(), "alarms/#".to_string()],
+                topic: Some("actuators/valve".to_string()),
+                qos: rumqttc::QoS::ExactlyOnce,
+                retain: true,
+                client_id: Some("my-client".to_string()),
+                credentials: Some(("alice".to_string(), "secret".to_string())),
+                add_topic: true,
+            },
+            full
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mqtt_publish_subscribe() {
+        let port = 18830;
+        start_broker(port).await;
+
+        let node_id = uuid::Uuid::new_v4();
+        let mut dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:7103".to_string(), None).await;
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut target_handle = dataplane_provider.get_handle_for(target_id).await;
+
+        let mut provider = MqttResourceProvider::new(dataplane_provider, Box::new(NoTelemetryHandle {}), node_id).await;
+
+        // The resource publishes on a topic to which it is also subscribed,
+        // hence every message sent to it comes back to the target.
+        let resource_id = match provider
+            .start(edgeless_api::resource_configuration::ResourceInstanceSpecification {
+                class_type: "mqtt".to_string(),
+                configuration: configuration(&[
+                    ("url", &format!("mqtt://127.0.0.1:{}", port)),
+                    ("subscribe", "edgeless/#"),
+                    ("topic", "edgeless/test"),
+                    ("qos", "1"),
+                    ("add-topic", ""),
+                ]),
+                workflow_id: String::default(),
+            })
+            .await
+            .unwrap()
+        {
+            edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        };
+        provider
+            .patch(edgeless_api::common::PatchRequest {
+                function_id: resource_id.function_id,
+                output_mapping: std::collections::HashMap::from([("out".to_string(), target_id)]),
+            })
+            .await
+            .unwrap();
+
+        // Retry until the broker is up and the subscription is active.
+        let mut received = None;
+        for _ in 0..50 {
+            target_handle
+                .send(
+                    resource_id,
+                    "hello".to_string(),
+                    &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+                )
+                .await;
+            if let Ok(event) = tokio::time::timeout(std::time::Duration::from_millis(200), target_handle.receive_next()).await {
+                received = Some(event);
+                break;
+            }
+        }
+        let event = received.expect("no message received from the MQTT broker");
+        assert_eq!(resource_id, event.source_id);
+        assert_eq!(
+            edgeless_dataplane::core::Message::Cast(r#"{"topic":"edgeless/test","payload":"hello"}"#.to_string()),
+            event.message
+        );
+
+        // A call() is answered once the message is published.
+        let res = target_handle
+            .call(
+                resource_id,
+                "world".to_string(),
+                &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+            )
+            .await;
+        assert_eq!(edgeless_dataplane::core::CallRet::Reply("".to_string()), res);
+
+        provider.stop(resource_id).await.unwrap();
+    }
+}
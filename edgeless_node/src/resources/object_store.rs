@@ -0,0 +1,711 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use base64::Engine;
+use futures::TryStreamExt;
+
+pub struct ObjectStoreResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for ObjectStoreResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("object-store")
+    }
+
+    fn description(&self) -> String {
+        r"Store objects on an S3-compatible service or on the local filesystem
+
+Requests are JSON objects with an 'op' field: put, get, list, delete.
+If 'notify-prefix' is specified, an event is cast to the output channel 'out' when an object appears, or is modified, under that prefix."
+            .to_string()
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("out")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("url"),
+                String::from("Location of the objects, e.g., s3://bucket/prefix or file://dir, relative to the object_store_root of the node"),
+            ),
+            (
+                String::from("endpoint"),
+                String::from("Endpoint of the S3-compatible service, e.g., http://127.0.0.1:9000. Default: AWS"),
+            ),
+            (String::from("region"), String::from("Region of the S3 bucket (optional)")),
+            (
+                String::from("access-key-id"),
+                String::from("Access key identifier of the S3 service. Default: anonymous access"),
+            ),
+            (
+                String::from("secret-access-key"),
+                String::from("Secret access key of the S3 service. Default: anonymous access"),
+            ),
+            (
+                String::from("notify-prefix"),
+                String::from("Prefix of the objects whose creation is notified (optional)"),
+            ),
+            (
+                String::from("notify-interval-ms"),
+                String::from("Interval between two checks for new objects, in ms. Default: 1000"),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.0")
+    }
+}
+
+/// Request received by the object-store resource, encoded in JSON.
+///
+/// The content of the objects is exchanged as a string, which is
+/// base64-encoded if the `base64` field is true.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ObjectStoreRequest {
+    /// Create or overwrite an object.
+    Put {
+        key: String,
+        data: String,
+        #[serde(default)]
+        base64: bool,
+    },
+    /// Read an object.
+    Get {
+        key: String,
+        #[serde(default)]
+        base64: bool,
+    },
+    /// List the objects, optionally only those under a prefix.
+    List {
+        #[serde(default)]
+        prefix: Option<String>,
+    },
+    /// Delete an object.
+    Delete { key: String },
+}
+
+#[derive(Clone)]
+pub struct ObjectStoreResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<ObjectStoreResourceProviderInner>>,
+}
+
+struct ObjectStoreResourceProviderInner {
+    node_id: edgeless_api::function_instance::NodeId,
+    local_root: Option<String>,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, ObjectStoreResource>,
+}
+
+pub struct ObjectStoreResource {
+    join_handles: Vec<tokio::task::JoinHandle<()>>,
+    target: Option<edgeless_api::function_instance::InstanceId>,
+}
+
+impl Drop for ObjectStoreResource {
+    fn drop(&mut self) {
+        for join_handle in &self.join_handles {
+            join_handle.abort();
+        }
+    }
+}
+
+/// Create the backend from the resource configuration.
+///
+/// With S3, the path in the URL, if any, is a prefix added to all the keys.
+/// The credentials are only taken from the resource configuration, never
+/// from the environment of the node: without them, the access is anonymous.
+///
+/// With the local filesystem, the path in the URL must be relative to the
+/// root directory configured at the node, if any, without `..` components.
+fn build_store(
+    configuration: &std::collections::HashMap<String, String>,
+    local_root: Option<&str>,
+) -> anyhow::Result<std::sync::Arc<dyn object_store::ObjectStore>> {
+    let url = configuration.get("url").ok_or_else(|| anyhow::anyhow!("Missing field 'url'"))?;
+
+    if let Some(location) = url.strip_prefix("s3://") {
+        let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+        if bucket.is_empty() {
+            anyhow::bail!("Missing bucket in the URL: {}", url);
+        }
+        let mut builder = object_store::aws::AmazonS3Builder::new().with_bucket_name(bucket);
+        if let Some(endpoint) = configuration.get("endpoint") {
+            builder = builder.with_endpoint(endpoint).with_allow_http(endpoint.starts_with("http://"));
+        }
+        if let Some(region) = configuration.get("region") {
+            builder = builder.with_region(region);
+        }
+        builder = match (configuration.get("access-key-id"), configuration.get("secret-access-key")) {
+            (Some(access_key_id), Some(secret_access_key)) => builder.with_access_key_id(access_key_id).with_secret_access_key(secret_access_key),
+            (None, None) => builder.with_skip_signature(true),
+            _ => anyhow::bail!("Both access-key-id and secret-access-key must be specified, or none"),
+        };
+        let store = builder.build()?;
+        let prefix = prefix.trim_matches('/');
+        if prefix.is_empty() {
+            Ok(std::sync::Arc::new(store))
+        } else {
+            Ok(std::sync::Arc::new(object_store::prefix::PrefixStore::new(
+                store,
+                object_store::path::Path::parse(prefix)?,
+            )))
+        }
+    } else if let Some(path) = url.strip_prefix("file://") {
+        let Some(local_root) = local_root else {
+            anyhow::bail!("The local filesystem is not allowed on this node: {}", url);
+        };
+        let path = std::path::Path::new(path);
+        if !path
+            .components()
+            .all(|x| matches!(x, std::path::Component::Normal(_) | std::path::Component::CurDir))
+        {
+            anyhow::bail!("The path must be relative and cannot contain '..': {}", url);
+        }
+        let path = std::path::Path::new(local_root).join(path);
+        std::fs::create_dir_all(&path)?;
+        Ok(std::sync::Arc::new(object_store::local::LocalFileSystem::new_with_prefix(path)?))
+    } else {
+        anyhow::bail!("Unsupported URL, only s3:// and file:// are allowed: {}", url)
+    }
+}
+
+fn meta_to_json(meta: &object_store::ObjectMeta) -> serde_json::Value {
+    serde_json::json!({
+        "key": meta.location.to_string(),
+        "size": meta.size,
+        "last_modified": meta.last_modified.to_rfc3339(),
+        "e_tag": meta.e_tag,
+    })
+}
+
+/// Handle a request, returning the JSON-encoded result.
+async fn handle_request(store: &dyn object_store::ObjectStore, request: ObjectStoreRequest) -> anyhow::Result<serde_json::Value> {
+    match request {
+        ObjectStoreRequest::Put { key, data, base64: encoded } => {
+            let data = if encoded {
+                base64::engine::general_purpose::STANDARD.decode(data)?
+            } else {
+                data.into_bytes()
+            };
+            let size = data.len();
+            let res = store.put(&object_store::path::Path::parse(&key)?, data.into()).await?;
+            Ok(serde_json::json!({ "key": key, "size": size, "e_tag": res.e_tag }))
+        }
+        ObjectStoreRequest::Get { key, base64: encoded } => {
+            let res = store.get(&object_store::path::Path::parse(&key)?).await?;
+            let meta = res.meta.clone();
+            let data = res.bytes().await?;
+            let mut ret = meta_to_json(&meta);
+            ret["data"] = serde_json::Value::String(if encoded {
+                base64::engine::general_purpose::STANDARD.encode(data)
+            } else {
+                String::from_utf8_lossy(&data).to_string()
+            });
+            Ok(ret)
+        }
+        ObjectStoreRequest::List { prefix } => {
+            let prefix = prefix.map(|prefix| object_store::path::Path::parse(&prefix)).transpose()?;
+            let objects: Vec<object_store::ObjectMeta> = store.list(prefix.as_ref()).try_collect().await?;
+            Ok(serde_json::json!({ "objects": objects.iter().map(meta_to_json).collect::<Vec<_>>() }))
+        }
+        ObjectStoreRequest::Delete { key } => {
+            store.delete(&object_store::path::Path::parse(&key)?).await?;
+            Ok(serde_json::json!({}))
+        }
+    }
+}
+
+/// Detect the objects created or modified under a prefix by comparing
+/// the listings of consecutive polls.
+///
+/// An object is returned by every poll until it is marked as seen, e.g.,
+/// because it could not be notified yet.
+struct ObjectWatcher {
+    prefix: object_store::path::Path,
+    seen: Option<std::collections::HashMap<object_store::path::Path, (chrono::DateTime<chrono::Utc>, Option<String>)>>,
+}
+
+impl ObjectWatcher {
+    fn new(prefix: object_store::path::Path) -> Self {
+        Self { prefix, seen: None }
+    }
+
+    /// Return the objects that are new or modified since they were last
+    /// marked as seen.
+    ///
+    /// The first poll only marks as seen the objects already present.
+    async fn poll(&mut self, store: &dyn object_store::ObjectStore) -> anyhow::Result<Vec<object_store::ObjectMeta>> {
+        let objects: Vec<object_store::ObjectMeta> = store.list(Some(&self.prefix)).try_collect().await?;
+        let mut new_objects = vec![];
+        let mut seen = std::collections::HashMap::new();
+        for meta in objects {
+            let version = (meta.last_modified, meta.e_tag.clone());
+            match &self.seen {
+                Some(prev) if prev.get(&meta.location) != Some(&version) => new_objects.push(meta),
+                _ => {
+                    seen.insert(meta.location, version);
+                }
+            }
+        }
+        self.seen = Some(seen);
+        Ok(new_objects)
+    }
+
+    /// Mark an object returned by `poll()` as seen.
+    fn mark_seen(&mut self, meta: &object_store::ObjectMeta) {
+        self.seen
+            .get_or_insert_with(std::collections::HashMap::new)
+            .insert(meta.location.clone(), (meta.last_modified, meta.e_tag.clone()));
+    }
+}
+
+/// The object-store resource reads and writes objects on the configured
+/// backend on behalf of the other components, which can then exchange
+/// references to the objects (i.e., their keys) instead of their content.
+///
+/// If a notification prefix is configured, the backend is polled
+/// periodically and the metadata of new or modified objects is cast to the
+/// output channel `out`.
+impl ObjectStoreResource {
+    fn new(
+        store: std::sync::Arc<dyn object_store::ObjectStore>,
+        notify: Option<(object_store::path::Path, std::time::Duration)>,
+        self_function_id: edgeless_api::function_instance::ComponentId,
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        inner: std::sync::Arc<tokio::sync::Mutex<ObjectStoreResourceProviderInner>>,
+    ) -> Self {
+        let mut join_handles = vec![];
+        let mut telemetry_handle = telemetry_handle;
+
+        // Notify the new objects to the target, if any.
+        if let Some((prefix, interval)) = notify {
+            let mut out_dataplane_handle = dataplane_handle.clone();
+            let mut out_telemetry_handle = telemetry_handle.fork(std::collections::BTreeMap::new());
+            let out_store = store.clone();
+            join_handles.push(tokio::spawn(async move {
+                let mut watcher = ObjectWatcher::new(prefix);
+                let mut interval = tokio::time::interval(interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    interval.tick().await;
+                    let new_objects = match watcher.poll(out_store.as_ref()).await {
+                        Ok(new_objects) => new_objects,
+                        Err(err) => {
+                            log::warn!("Could not list the objects under '{}': {}", watcher.prefix, err);
+                            continue;
+                        }
+                    };
+                    if new_objects.is_empty() {
+                        continue;
+                    }
+                    let target = match inner.lock().await.instances.get(&self_function_id) {
+                        Some(instance) => instance.target,
+                        None => None,
+                    };
+                    let Some(target) = target else {
+                        log::debug!("Delaying {} object notifications: no target", new_objects.len());
+                        continue;
+                    };
+                    for meta in new_objects {
+                        let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+                            out_telemetry_handle.as_ref(),
+                            "object-store-notify",
+                            edgeless_telemetry::telemetry_events::SpanKind::Producer,
+                        );
+                        span.set_attribute("edgeless.object", &meta.location);
+                        crate::dataplane_metrics::send(
+                            &mut out_dataplane_handle,
+                            out_telemetry_handle.as_mut(),
+                            "out",
+                            target,
+                            meta_to_json(&meta).to_string(),
+                            &span.metadata,
+                        )
+                        .await;
+                        span.end(out_telemetry_handle.as_mut());
+                        watcher.mark_seen(&meta);
+                    }
+                }
+            }));
+        }
+
+        // Serve the requests received.
+        let mut dataplane_handle = dataplane_handle;
+        join_handles.push(tokio::spawn(async move {
+            loop {
+                let edgeless_dataplane::core::DataplaneEvent {
+                    source_id,
+                    channel_id,
+                    message,
+                    created,
+                    metadata,
+                } = dataplane_handle.receive_next().await;
+                let started = crate::resources::observe_transfer(created, &mut telemetry_handle);
+
+                let (need_reply, message_data) = match message {
+                    edgeless_dataplane::core::Message::Call(data) => (true, data),
+                    edgeless_dataplane::core::Message::Cast(data) => (false, data),
+                    _ => {
+                        continue;
+                    }
+                };
+
+                let res = match serde_json::from_str::<ObjectStoreRequest>(&message_data) {
+                    Ok(request) => handle_request(store.as_ref(), request).await,
+                    Err(err) => Err(anyhow::anyhow!("invalid request: {}", err)),
+                };
+                let res = match res {
+                    Ok(res) => res,
+                    Err(err) => {
+                        log::warn!("Invalid object-store operation: {}", err);
+                        serde_json::json!({ "error": err.to_string() })
+                    }
+                };
+
+                if need_reply {
+                    dataplane_handle
+                        .reply(
+                            source_id,
+                            channel_id,
+                            edgeless_dataplane::core::CallRet::Reply(res.to_string()),
+                            &metadata,
+                        )
+                        .await;
+                }
+
+                crate::resources::observe_execution(started, &mut telemetry_handle, need_reply);
+            }
+        }));
+
+        Self { join_handles, target: None }
+    }
+}
+
+impl ObjectStoreResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        local_root: Option<String>,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        node_id: edgeless_api::function_instance::NodeId,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(tokio::sync::Mutex::new(ObjectStoreResourceProviderInner {
+                node_id,
+                local_root,
+                dataplane_provider,
+                telemetry_handle,
+                instances: std::collections::HashMap::new(),
+            })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId> for ObjectStoreResourceProvider {
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let notify = instance_specification
+            .configuration
+            .get("notify-prefix")
+            .map(|prefix| object_store::path::Path::parse(prefix.trim_matches('/')))
+            .transpose();
+        let local_root = self.inner.lock().await.local_root.clone();
+        let store = build_store(&instance_specification.configuration, local_root.as_deref());
+        let (store, notify) = match (store, notify) {
+            (Ok(store), Ok(notify)) => (store, notify),
+            (Err(err), _) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(err.to_string()),
+                    },
+                ));
+            }
+            (_, Err(err)) => {
+                return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                    edgeless_api::common::ResponseError {
+                        summary: "Invalid resource configuration".to_string(),
+                        detail: Some(format!("Invalid notify-prefix: {}", err)),
+                    },
+                ));
+            }
+        };
+        let notify_interval_ms = instance_specification
+            .configuration
+            .get("notify-interval-ms")
+            .unwrap_or(&String::from("1000"))
+            .parse::<u64>()
+            .unwrap_or(1000)
+            .max(1);
+
+        let mut lck = self.inner.lock().await;
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+        let telemetry_handle = lck.telemetry_handle.fork(std::collections::BTreeMap::from([(
+            "FUNCTION_ID".to_string(),
+            new_id.function_id.to_string(),
+        )]));
+
+        lck.instances.insert(
+            new_id.function_id,
+            ObjectStoreResource::new(
+                store,
+                notify.map(|prefix| (prefix, std::time::Duration::from_millis(notify_interval_ms))),
+                new_id.function_id,
+                dataplane_handle,
+                telemetry_handle,
+                self.inner.clone(),
+            ),
+        );
+
+        Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        let mut lck = self.inner.lock().await;
+        if let Some(instance) = lck.instances.get_mut(&update.function_id) {
+            instance.target = update.output_mapping.get("out").cloned();
+        } else {
+            anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_store() -> (std::path::PathBuf, std::sync::Arc<dyn object_store::ObjectStore>) {
+        let dir = std::env::temp_dir().join(format!("edgeless-object-store-{}", uuid::Uuid::new_v4()));
+        let store = build_store(
+            &std::collections::HashMap::from([("url".to_string(), "file://objects".to_string())]),
+            dir.to_str(),
+        )
+        .unwrap();
+        (dir, store)
+    }
+
+    #[test]
+    fn test_object_store_request_deserialize() {
+        assert_eq!(
+            ObjectStoreRequest::Put {
+                key: "a/b".to_string(),
+                data: "hello".to_string(),
+                base64: false
+            },
+            serde_json::from_str(r#"{"op":"put","key":"a/b","data":"hello"}"#).unwrap()
+        );
+        assert_eq!(
+            ObjectStoreRequest::Get {
+                key: "a/b".to_string(),
+                base64: true
+            },
+            serde_json::from_str(r#"{"op":"get","key":"a/b","base64":true}"#).unwrap()
+        );
+        assert_eq!(
+            ObjectStoreRequest::List { prefix: None },
+            serde_json::from_str(r#"{"op":"list"}"#).unwrap()
+        );
+        assert!(serde_json::from_str::<ObjectStoreRequest>(r#"{"op":"put","key":"a/b"}"#).is_err());
+    }
+
+    #[test]
+    fn test_object_store_build() {
+        let conf = |entries: &[(&str, &str)]| entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        assert!(build_store(&std::collections::HashMap::new(), None).is_err());
+        assert!(build_store(&conf(&[("url", "ftp://host/dir")]), None).is_err());
+        assert!(build_store(&conf(&[("url", "s3://")]), None).is_err());
+
+        // S3 with credentials, without, or with partial ones.
+        let s3 = [
+            ("url", "s3://bucket/some/prefix"),
+            ("endpoint", "http://127.0.0.1:9000"),
+            ("region", "us-east-1"),
+        ];
+        assert!(build_store(&conf(&s3), None).is_ok());
+        assert!(
+            build_store(
+                &conf(&[&s3[..], &[("access-key-id", "id"), ("secret-access-key", "secret")]].concat()),
+                None
+            )
+            .is_ok()
+        );
+        assert!(build_store(&conf(&[&s3[..], &[("access-key-id", "id")]].concat()), None).is_err());
+
+        // Local filesystem only under the root of the node.
+        let dir = std::env::temp_dir().join(format!("edgeless-object-store-{}", uuid::Uuid::new_v4()));
+        assert!(build_store(&conf(&[("url", "file://objects")]), None).is_err());
+        assert!(build_store(&conf(&[("url", "file://objects")]), dir.to_str()).is_ok());
+        assert!(dir.join("objects").is_dir());
+        assert!(build_store(&conf(&[("url", "file://")]), dir.to_str()).is_ok());
+        assert!(build_store(&conf(&[("url", "file:///etc")]), dir.to_str()).is_err());
+        assert!(build_store(&conf(&[("url", "file://../objects")]), dir.to_str()).is_err());
+        assert!(build_store(&conf(&[("url", "file://objects/../../etc")]), dir.to_str()).is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_object_store_local() {
+        let (dir, store) = local_store();
+
+        let res = handle_request(
+            store.as_ref(),
+            ObjectStoreRequest::Put {
+                key: "data/a.txt".to_string(),
+                data: "hello".to_string(),
+                base64: false,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(5, res["size"]);
+        handle_request(
+            store.as_ref(),
+            ObjectStoreRequest::Put {
+                key: "data/b.bin".to_string(),
+                data: base64::engine::general_purpose::STANDARD.encode([0_u8, 1, 2]),
+                base64: true,
+            },
+        )
+        .await
+        .unwrap();
+        handle_request(
+            store.as_ref(),
+            ObjectStoreRequest::Put {
+                key: "other/c.txt".to_string(),
+                data: "world".to_string(),
+                base64: false,
+            },
+        )
+        .await
+        .unwrap();
+
+        let res = handle_request(
+            store.as_ref(),
+            ObjectStoreRequest::Get {
+                key: "data/a.txt".to_string(),
+                base64: false,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!("hello", res["data"]);
+        assert_eq!("data/a.txt", res["key"]);
+        let res = handle_request(
+            store.as_ref(),
+            ObjectStoreRequest::Get {
+                key: "data/b.bin".to_string(),
+                base64: true,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!("AAEC", res["data"]);
+
+        let res = handle_request(
+            store.as_ref(),
+            ObjectStoreRequest::List {
+                prefix: Some("data".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        let mut keys: Vec<_> = res["objects"].as_array().unwrap().iter().map(|x| x["key"].as_str().unwrap()).collect();
+        keys.sort();
+        assert_eq!(vec!["data/a.txt", "data/b.bin"], keys);
+        let res = handle_request(store.as_ref(), ObjectStoreRequest::List { prefix: None }).await.unwrap();
+        assert_eq!(3, res["objects"].as_array().unwrap().len());
+
+        handle_request(
+            store.as_ref(),
+            ObjectStoreRequest::Delete {
+                key: "data/a.txt".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(
+            handle_request(
+                store.as_ref(),
+                ObjectStoreRequest::Get {
+                    key: "data/a.txt".to_string(),
+                    base64: false,
+                },
+            )
+            .await
+            .is_err()
+        );
+
+        // Invalid keys are rejected.
+        assert!(
+            handle_request(
+                store.as_ref(),
+                ObjectStoreRequest::Get {
+                    key: "../a.txt".to_string(),
+                    base64: false,
+                },
+            )
+            .await
+            .is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_object_store_watcher() {
+        let (dir, store) = local_store();
+        let put = |key: &str| {
+            let store = store.clone();
+            let key = key.to_string();
+            async move {
+                store
+                    .put(&object_store::path::Path::parse(&key).unwrap(), "content".into())
+                    .await
+                    .unwrap();
+            }
+        };
+
+        put("incoming/existing").await;
+        let mut watcher = ObjectWatcher::new(object_store::path::Path::parse("incoming").unwrap());
+
+        // The objects already present are not notified.
+        assert!(watcher.poll(store.as_ref()).await.unwrap().is_empty());
+
+        put("incoming/new").await;
+        put("outgoing/ignored").await;
+        let new_objects = watcher.poll(store.as_ref()).await.unwrap();
+        assert_eq!(1, new_objects.len());
+        assert_eq!("incoming/new", new_objects[0].location.to_string());
+
+        // The object is returned again until marked as seen.
+        let new_objects = watcher.poll(store.as_ref()).await.unwrap();
+        assert_eq!(1, new_objects.len());
+        watcher.mark_seen(&new_objects[0]);
+        assert!(watcher.poll(store.as_ref()).await.unwrap().is_empty());
+
+        // Deleted objects are not notified.
+        store
+            .delete(&object_store::path::Path::parse("incoming/existing").unwrap())
+            .await
+            .unwrap();
+        assert!(watcher.poll(store.as_ref()).await.unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
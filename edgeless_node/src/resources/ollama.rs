@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: © 2024 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
 // SPDX-License-Identifier: MIT
 
-use futures::{SinkExt, StreamExt};
+use futures::{StreamExt, TryStreamExt};
 
 pub struct OllamaResourceSpec {}
 
@@ -11,7 +11,12 @@ impl super::resource_provider_specs::ResourceProviderSpecs for OllamaResourceSpe
     }
 
     fn description(&self) -> String {
-        r"Interact via an LLM ChatBot deployed on an external Ollama server -- see https://ollama.com/".to_string()
+        r"Interact via an LLM ChatBot deployed on an external Ollama server -- see https://ollama.com/
+
+An OpenAI-compatible chat-completions server can be used instead by setting 'backend' to 'openai'.
+The prompt is received via cast(), either as plain text or as a JSON object with fields 'prompt' and 'conversation'.
+The answer is cast to the output channel 'out', as a whole or chunk by chunk followed by an end-of-stream marker."
+            .to_string()
     }
 
     fn outputs(&self) -> Vec<String> {
@@ -19,47 +24,361 @@ impl super::resource_provider_specs::ResourceProviderSpecs for OllamaResourceSpe
     }
 
     fn configurations(&self) -> std::collections::HashMap<String, String> {
-        std::collections::HashMap::from([(String::from("model"), String::from("Model to be used for chatting"))])
+        std::collections::HashMap::from([
+            (String::from("model"), String::from("Model to be used for chatting")),
+            (
+                String::from("backend"),
+                String::from("Type of the LLM server: ollama or openai. Default: ollama"),
+            ),
+            (
+                String::from("url"),
+                String::from("Base URL of the LLM server, e.g., https://api.openai.com/v1. Default: the ollama server in the node configuration"),
+            ),
+            (
+                String::from("api-key"),
+                String::from("Key used to authenticate with the LLM server as a bearer token (optional)"),
+            ),
+            (
+                String::from("system-prompt"),
+                String::from("System prompt added at the beginning of every conversation (optional)"),
+            ),
+            (String::from("temperature"), String::from("Sampling temperature of the model (optional)")),
+            (
+                String::from("max-tokens"),
+                String::from("Maximum number of tokens generated for every answer (optional)"),
+            ),
+            (
+                String::from("max-conversations"),
+                String::from("Maximum number of conversations whose history is kept, the least recently used is forgotten beyond. Default: 100"),
+            ),
+            (
+                String::from("stream"),
+                String::from("If true, cast every chunk of the answer as soon as it is generated. Default: false"),
+            ),
+            (
+                String::from("end-of-stream"),
+                String::from("Message cast after the last chunk of a plain text answer when streaming. Default: [DONE]"),
+            ),
+        ])
     }
 
     fn version(&self) -> String {
-        String::from("1.1")
+        String::from("2.0")
     }
 }
 
-#[derive(Clone)]
-pub struct OllamaResourceProvider {
-    inner: std::sync::Arc<tokio::sync::Mutex<OllamaResourceProviderInner>>,
+/// Prompt received by the ollama resource as a JSON object.
+///
+/// If the conversation is not specified, the resource instance identifier
+/// is used, i.e., all the prompts belong to the same conversation.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct OllamaRequest {
+    pub prompt: String,
+    #[serde(default)]
+    pub conversation: Option<String>,
 }
 
-struct ChatCommand {
-    model_name: String,
-    history_id: String,
-    prompt: String,
-    resource_id: edgeless_api::function_instance::ComponentId,
-    reply_sender: tokio::sync::oneshot::Sender<anyhow::Result<(edgeless_api::function_instance::InstanceId, String)>>,
+/// Answer, or chunk of an answer, cast by the ollama resource if the prompt
+/// was received as a JSON object.
+///
+/// When streaming, the last message has an empty content and `done` is true.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct OllamaResponse {
+    pub conversation: String,
+    pub content: String,
+    pub done: bool,
 }
 
-enum OllamaCommand {
-    Chat(ChatCommand),
-    // resource_id, target
-    Patch(edgeless_api::function_instance::ComponentId, edgeless_api::function_instance::InstanceId),
+/// Protocol spoken by the LLM server.
+#[derive(Clone, Debug, PartialEq)]
+enum ChatBackend {
+    /// Ollama chat API, i.e., /api/chat.
+    Ollama,
+    /// OpenAI-compatible chat-completions API, i.e., /chat/completions.
+    OpenAi,
 }
 
-impl std::fmt::Display for OllamaCommand {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            OllamaCommand::Chat(cmd) => write!(
-                f,
-                "model {}, history_id {}, prompt length {}, resource_id {})",
-                cmd.model_name,
-                cmd.history_id,
-                cmd.prompt.len(),
-                cmd.resource_id
-            ),
-            OllamaCommand::Patch(resource_id, target) => write!(f, "resource_id {}, target {}", resource_id, target),
+/// Configuration of an ollama resource instance.
+#[derive(Clone, Debug, PartialEq)]
+struct ChatConfiguration {
+    backend: ChatBackend,
+    url: String,
+    api_key: Option<String>,
+    model: String,
+    system_prompt: Option<String>,
+    temperature: Option<f64>,
+    max_tokens: Option<u32>,
+    max_conversations: usize,
+    stream: bool,
+    end_of_stream: String,
+}
+
+impl ChatConfiguration {
+    fn from_configuration(configuration: &std::collections::HashMap<String, String>, ollama_url: &str) -> anyhow::Result<Self> {
+        let model = configuration
+            .get("model")
+            .ok_or_else(|| anyhow::anyhow!("Missing model name"))?
+            .to_string();
+        let backend = match configuration.get("backend").map(|x| x.as_str()).unwrap_or("ollama") {
+            "ollama" => ChatBackend::Ollama,
+            "openai" => ChatBackend::OpenAi,
+            backend => anyhow::bail!("Invalid backend: {}", backend),
+        };
+        let url = match (configuration.get("url"), &backend) {
+            (Some(url), _) => url.trim_end_matches('/').to_string(),
+            (None, ChatBackend::Ollama) => ollama_url.to_string(),
+            (None, ChatBackend::OpenAi) => anyhow::bail!("Missing url of the OpenAI-compatible server"),
+        };
+        let temperature = match configuration.get("temperature") {
+            Some(val) => Some(val.parse::<f64>().map_err(|_| anyhow::anyhow!("Invalid temperature: {}", val))?),
+            None => None,
+        };
+        let max_tokens = match configuration.get("max-tokens") {
+            Some(val) => Some(val.parse::<u32>().map_err(|_| anyhow::anyhow!("Invalid max-tokens: {}", val))?),
+            None => None,
+        };
+        let max_conversations = match configuration.get("max-conversations") {
+            Some(val) => match val.parse::<usize>() {
+                Ok(val) if val > 0 => val,
+                _ => anyhow::bail!("Invalid max-conversations: {}", val),
+            },
+            None => 100,
+        };
+
+        Ok(Self {
+            backend,
+            url,
+            api_key: configuration.get("api-key").cloned(),
+            model,
+            system_prompt: configuration.get("system-prompt").cloned(),
+            temperature,
+            max_tokens,
+            max_conversations,
+            stream: configuration.get("stream").is_some_and(|x| x.to_lowercase() == "true"),
+            end_of_stream: configuration.get("end-of-stream").cloned().unwrap_or(String::from("[DONE]")),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+impl ChatMessage {
+    fn new(role: &str, content: &str) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+}
+
+/// Histories of the conversations of a resource instance.
+///
+/// Beyond the maximum number of conversations, the history of the least
+/// recently used one is removed.
+struct Conversations {
+    messages_number_limit: usize,
+    max_conversations: usize,
+    /// Counter incremented at every push, to find the least recently used
+    /// conversation.
+    last_used: u64,
+    histories: std::collections::HashMap<String, (u64, Vec<ChatMessage>)>,
+}
+
+impl Conversations {
+    fn new(messages_number_limit: usize, max_conversations: usize) -> Self {
+        Self {
+            messages_number_limit,
+            max_conversations,
+            last_used: 0,
+            histories: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Return the messages to be sent to the LLM server for a new prompt.
+    fn messages(&self, conversation: &str, system_prompt: &Option<String>, prompt: &str) -> Vec<ChatMessage> {
+        let mut ret = vec![];
+        if let Some(system_prompt) = system_prompt {
+            ret.push(ChatMessage::new("system", system_prompt));
+        }
+        if let Some((_, history)) = self.histories.get(conversation) {
+            ret.extend(history.iter().cloned());
+        }
+        ret.push(ChatMessage::new("user", prompt));
+        ret
+    }
+
+    /// Add a prompt and its answer to a conversation, removing the oldest
+    /// messages beyond the limit.
+    fn push(&mut self, conversation: &str, prompt: &str, answer: &str) {
+        if !self.histories.contains_key(conversation)
+            && self.histories.len() >= self.max_conversations
+            && let Some(lru) = self.histories.iter().min_by_key(|(_, (last_used, _))| *last_used).map(|(k, _)| k.clone())
+        {
+            self.histories.remove(&lru);
+        }
+        self.last_used += 1;
+        let (last_used, history) = self.histories.entry(conversation.to_string()).or_default();
+        *last_used = self.last_used;
+        history.push(ChatMessage::new("user", prompt));
+        history.push(ChatMessage::new("assistant", answer));
+        if history.len() > self.messages_number_limit {
+            history.drain(..history.len() - self.messages_number_limit);
+        }
+    }
+}
+
+/// Client of an LLM server.
+struct ChatClient {
+    client: reqwest::Client,
+    configuration: ChatConfiguration,
+}
+
+impl ChatClient {
+    fn new(configuration: ChatConfiguration) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            configuration,
         }
     }
+
+    /// Send the messages to the LLM server and return the chunks of the
+    /// answer, which consist of a single item if streaming is disabled.
+    async fn chat(&self, messages: Vec<ChatMessage>) -> anyhow::Result<futures::stream::BoxStream<'static, anyhow::Result<String>>> {
+        let conf = &self.configuration;
+        let (url, body) = match conf.backend {
+            ChatBackend::Ollama => {
+                let mut options = serde_json::Map::new();
+                if let Some(temperature) = conf.temperature {
+                    options.insert("temperature".to_string(), serde_json::json!(temperature));
+                }
+                if let Some(max_tokens) = conf.max_tokens {
+                    options.insert("num_predict".to_string(), serde_json::json!(max_tokens));
+                }
+                (
+                    format!("{}/api/chat", conf.url),
+                    serde_json::json!({ "model": conf.model, "messages": messages, "stream": conf.stream, "options": options }),
+                )
+            }
+            ChatBackend::OpenAi => {
+                let mut body = serde_json::json!({ "model": conf.model, "messages": messages, "stream": conf.stream });
+                if let Some(temperature) = conf.temperature {
+                    body["temperature"] = serde_json::json!(temperature);
+                }
+                if let Some(max_tokens) = conf.max_tokens {
+                    body["max_tokens"] = serde_json::json!(max_tokens);
+                }
+                (format!("{}/chat/completions", conf.url), body)
+            }
+        };
+
+        let mut request = self.client.post(url).json(&body);
+        if let Some(api_key) = &conf.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            anyhow::bail!("{} from the LLM server: {}", status, response.text().await.unwrap_or_default());
+        }
+
+        if !conf.stream {
+            let answer: serde_json::Value = response.json().await?;
+            let content = match conf.backend {
+                ChatBackend::Ollama => answer["message"]["content"].as_str(),
+                ChatBackend::OpenAi => answer["choices"][0]["message"]["content"].as_str(),
+            }
+            .ok_or_else(|| anyhow::anyhow!("Invalid answer from the LLM server: {}", answer))?
+            .to_string();
+            return Ok(futures::stream::once(async move { Ok(content) }).boxed());
+        }
+
+        let backend = conf.backend.clone();
+        Ok(body_lines(response.bytes_stream())
+            .try_filter_map(move |line| {
+                let chunk = match backend {
+                    ChatBackend::Ollama => parse_ollama_chunk(&line),
+                    ChatBackend::OpenAi => parse_openai_chunk(&line),
+                };
+                async move { chunk }
+            })
+            .boxed())
+    }
+}
+
+/// Split a body into lines, without the trailing newline.
+fn body_lines<S, B, E>(body: S) -> futures::stream::BoxStream<'static, anyhow::Result<String>>
+where
+    S: futures::Stream<Item = Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    futures::stream::unfold((body.boxed(), Vec::<u8>::new(), false), |(mut body, mut buffer, mut eof)| async move {
+        loop {
+            if let Some(pos) = buffer.iter().position(|x| *x == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                return Some((Ok(String::from_utf8_lossy(&line).trim_end().to_string()), (body, buffer, eof)));
+            }
+            if eof {
+                if buffer.is_empty() {
+                    return None;
+                }
+                let line = String::from_utf8_lossy(&buffer).trim_end().to_string();
+                buffer.clear();
+                return Some((Ok(line), (body, buffer, eof)));
+            }
+            match body.next().await {
+                Some(Ok(bytes)) => buffer.extend_from_slice(bytes.as_ref()),
+                Some(Err(err)) => {
+                    buffer.clear();
+                    return Some((Err(err.into()), (body, buffer, true)));
+                }
+                None => eof = true,
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Parse a line of a streamed answer of an Ollama server, which contains
+/// a JSON object.
+fn parse_ollama_chunk(line: &str) -> anyhow::Result<Option<String>> {
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    let chunk: serde_json::Value = serde_json::from_str(line)?;
+    if let Some(err) = chunk.get("error") {
+        anyhow::bail!("Error from the LLM server: {}", err);
+    }
+    Ok(chunk["message"]["content"].as_str().filter(|x| !x.is_empty()).map(|x| x.to_string()))
+}
+
+/// Parse a line of a streamed answer of an OpenAI-compatible server,
+/// which is made of server-sent events.
+fn parse_openai_chunk(line: &str) -> anyhow::Result<Option<String>> {
+    let Some(data) = line.strip_prefix("data:") else {
+        return Ok(None);
+    };
+    let data = data.trim();
+    if data == "[DONE]" {
+        return Ok(None);
+    }
+    let chunk: serde_json::Value = serde_json::from_str(data)?;
+    if let Some(err) = chunk.get("error") {
+        anyhow::bail!("Error from the LLM server: {}", err);
+    }
+    Ok(chunk["choices"][0]["delta"]["content"]
+        .as_str()
+        .filter(|x| !x.is_empty())
+        .map(|x| x.to_string()))
+}
+
+#[derive(Clone)]
+pub struct OllamaResourceProvider {
+    inner: std::sync::Arc<tokio::sync::Mutex<OllamaResourceProviderInner>>,
 }
 
 pub struct OllamaResourceProviderInner {
@@ -67,12 +386,13 @@ pub struct OllamaResourceProviderInner {
     dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
     telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
     instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, OllamaResource>,
-    sender: futures::channel::mpsc::UnboundedSender<OllamaCommand>,
-    _handle: tokio::task::JoinHandle<()>,
+    ollama_url: String,
+    ollama_messages_number_limit: u16,
 }
 
 pub struct OllamaResource {
     join_handle: tokio::task::JoinHandle<()>,
+    target: Option<edgeless_api::function_instance::InstanceId>,
 }
 
 impl Drop for OllamaResource {
@@ -86,22 +406,25 @@ impl OllamaResource {
     ///
     /// - `dataplane_provider`: handle to the EDGELESS data plane
     /// - `telemetry_hangle`: handle to the node's telemetry sub-system
-    /// - `model_name`: name of the AI model to use
+    /// - `configuration`: configuration of the chat with the LLM server
+    /// - `messages_number_limit`: maximum number of messages per conversation
     /// - `instance_id`: identifier of this resource instance
-    /// - `sender`: channel to send commands to the resource task
+    /// - `inner`: state of the resource provider, to find the target
     async fn new(
         dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
         telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
-        model_name: String,
+        configuration: ChatConfiguration,
+        messages_number_limit: usize,
         instance_id: edgeless_api::function_instance::InstanceId,
-        sender: futures::channel::mpsc::UnboundedSender<OllamaCommand>,
+        inner: std::sync::Arc<tokio::sync::Mutex<OllamaResourceProviderInner>>,
     ) -> anyhow::Result<Self> {
         let mut dataplane_handle = dataplane_handle;
         let mut telemetry_handle = telemetry_handle;
-        let mut sender = sender;
 
-        let history_id = instance_id.function_id.to_string();
+        let default_conversation = instance_id.function_id.to_string();
         let handle = tokio::spawn(async move {
+            let client = ChatClient::new(configuration);
+            let mut conversations = Conversations::new(messages_number_limit, client.configuration.max_conversations);
             loop {
                 let edgeless_dataplane::core::DataplaneEvent {
                     source_id: _,
@@ -113,44 +436,119 @@ impl OllamaResource {
                 let started = crate::resources::observe_transfer(created, &mut telemetry_handle);
 
                 // Ignore any non-cast messages.
-                let prompt = match message {
+                let data = match message {
                     edgeless_dataplane::core::Message::Cast(data) => data,
                     _ => {
                         continue;
                     }
                 };
 
-                let (reply_sender, reply_receiver) =
-                    tokio::sync::oneshot::channel::<anyhow::Result<(edgeless_api::function_instance::InstanceId, String)>>();
-                let _ = sender
-                    .send(OllamaCommand::Chat(ChatCommand {
-                        model_name: model_name.clone(),
-                        history_id: history_id.clone(),
-                        prompt,
-                        resource_id: instance_id.function_id,
-                        reply_sender,
-                    }))
-                    .await;
-
-                match reply_receiver.await {
-                    Ok(response) => match response {
-                        Ok((target, response)) => {
-                            let _ = dataplane_handle.send(target, response, &metadata).await;
-                        }
-                        Err(err) => {
-                            log::warn!("Error from ollama: {}", err)
+                // The prompt is in plain text, unless it is a JSON object, in
+                // which case the output is also a JSON object.
+                let (request, json_output) = match serde_json::from_str::<OllamaRequest>(&data) {
+                    Ok(request) => (request, true),
+                    Err(_) => (
+                        OllamaRequest {
+                            prompt: data,
+                            conversation: None,
+                        },
+                        false,
+                    ),
+                };
+                let conversation = request.conversation.clone().unwrap_or(default_conversation.clone());
+                let output = |content: &str, done: bool| {
+                    if json_output {
+                        serde_json::to_string(&OllamaResponse {
+                            conversation: conversation.clone(),
+                            content: content.to_string(),
+                            done,
+                        })
+                        .unwrap_or_default()
+                    } else {
+                        content.to_string()
+                    }
+                };
+
+                let target = match inner.lock().await.instances.get(&instance_id.function_id) {
+                    Some(instance) => instance.target,
+                    None => None,
+                };
+                let Some(target) = target else {
+                    log::warn!("Discarding prompt for conversation {}: no target", conversation);
+                    continue;
+                };
+
+                let stream = client.configuration.stream;
+                let mut answer = String::new();
+                let res = match client
+                    .chat(conversations.messages(&conversation, &client.configuration.system_prompt, &request.prompt))
+                    .await
+                {
+                    Ok(mut chunks) => loop {
+                        match chunks.next().await {
+                            Some(Ok(chunk)) => {
+                                if stream {
+                                    crate::dataplane_metrics::send(
+                                        &mut dataplane_handle,
+                                        telemetry_handle.as_mut(),
+                                        "out",
+                                        target,
+                                        output(&chunk, false),
+                                        &metadata,
+                                    )
+                                    .await;
+                                }
+                                answer.push_str(&chunk);
+                            }
+                            Some(Err(err)) => break Err(err),
+                            None => break Ok(()),
                         }
                     },
-                    Err(err) => {
-                        log::warn!("Communication error with ollama resource provider: {}", err)
+                    Err(err) => Err(err),
+                };
+
+                match res {
+                    Ok(_) => {
+                        conversations.push(&conversation, &request.prompt, &answer);
+                        if !stream {
+                            crate::dataplane_metrics::send(
+                                &mut dataplane_handle,
+                                telemetry_handle.as_mut(),
+                                "out",
+                                target,
+                                output(&answer, true),
+                                &metadata,
+                            )
+                            .await;
+                        }
                     }
+                    Err(err) => log::warn!(
+                        "Error from LLM server with model {}, conversation {}: {}",
+                        client.configuration.model,
+                        conversation,
+                        err
+                    ),
+                }
+
+                // Always terminate the stream, so that the target does not
+                // wait forever for the rest of the answer.
+                if stream {
+                    let end_of_stream = if json_output {
+                        output("", true)
+                    } else {
+                        client.configuration.end_of_stream.clone()
+                    };
+                    crate::dataplane_metrics::send(&mut dataplane_handle, telemetry_handle.as_mut(), "out", target, end_of_stream, &metadata).await;
                 }
 
                 crate::resources::observe_execution(started, &mut telemetry_handle, false);
             }
         });
 
-        Ok(Self { join_handle: handle })
+        Ok(Self {
+            join_handle: handle,
+            target: None,
+        })
     }
 }
 
@@ -173,57 +571,14 @@ impl OllamaResourceProvider {
         ollama_port: u16,
         ollama_messages_number_limit: u16,
     ) -> Self {
-        // Create a channel for:
-        // - single receiver: the loop in the task below
-        // - multiple senders: the resource instances that will be created
-        //   at run-time
-        let (sender, receiver) = futures::channel::mpsc::unbounded();
-        let mut receiver: futures::channel::mpsc::UnboundedReceiver<OllamaCommand> = receiver;
-
-        // Create a new instance of the ollama connector.
-        let mut ollama = ollama_rs::Ollama::new_with_history(format!("http://{}", ollama_host), ollama_port, ollama_messages_number_limit);
-
-        let _handle = tokio::spawn(async move {
-            let mut targets = std::collections::HashMap::new();
-            while let Some(command) = receiver.next().await {
-                match command {
-                    OllamaCommand::Chat(cmd) => {
-                        if let Some(target) = targets.get(&cmd.resource_id) {
-                            let result = ollama
-                                .send_chat_messages_with_history(
-                                    ollama_rs::generation::chat::request::ChatMessageRequest::new(
-                                        cmd.model_name.clone(),
-                                        vec![ollama_rs::generation::chat::ChatMessage::user(cmd.prompt)],
-                                    ),
-                                    cmd.history_id.clone(),
-                                )
-                                .await;
-                            let response = match result {
-                                Ok(res) => Ok((*target, res.message.unwrap().content)),
-                                Err(err) => anyhow::Result::Err(anyhow::anyhow!(
-                                    "Ollama error with model {}, history_id {}: {}",
-                                    cmd.model_name,
-                                    cmd.history_id,
-                                    err
-                                )),
-                            };
-                            let _ = cmd.reply_sender.send(response);
-                        }
-                    }
-                    OllamaCommand::Patch(resource_id, target) => {
-                        targets.insert(resource_id, target);
-                    }
-                };
-            }
-        });
         Self {
             inner: std::sync::Arc::new(tokio::sync::Mutex::new(OllamaResourceProviderInner {
                 resource_provider_id,
                 dataplane_provider,
                 telemetry_handle,
                 instances: std::collections::HashMap::new(),
-                sender,
-                _handle,
+                ollama_url: format!("http://{}:{}", ollama_host, ollama_port),
+                ollama_messages_number_limit,
             })),
         }
     }
@@ -236,27 +591,37 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
         let mut lck = self.inner.lock().await;
-        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
-        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
-        let telemetry_handle = lck.telemetry_handle.fork(std::collections::BTreeMap::from([(
-            "FUNCTION_ID".to_string(),
-            new_id.function_id.to_string(),
-        )]));
 
         // Read configuration
-        let model = match instance_specification.configuration.get("model") {
-            Some(model) => model,
-            None => {
+        let configuration = match ChatConfiguration::from_configuration(&instance_specification.configuration, &lck.ollama_url) {
+            Ok(configuration) => configuration,
+            Err(err) => {
                 return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
                     edgeless_api::common::ResponseError {
                         summary: "Invalid resource configuration".to_string(),
-                        detail: Some("Missing model name".to_string()),
+                        detail: Some(err.to_string()),
                     },
                 ));
             }
         };
 
-        match OllamaResource::new(dataplane_handle, telemetry_handle, model.to_string(), new_id, lck.sender.clone()).await {
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+        let telemetry_handle = lck.telemetry_handle.fork(std::collections::BTreeMap::from([(
+            "FUNCTION_ID".to_string(),
+            new_id.function_id.to_string(),
+        )]));
+
+        match OllamaResource::new(
+            dataplane_handle,
+            telemetry_handle,
+            configuration,
+            lck.ollama_messages_number_limit as usize,
+            new_id,
+            self.inner.clone(),
+        )
+        .await
+        {
             Ok(resource) => {
                 lck.instances.insert(new_id.function_id, resource);
                 return Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id));
@@ -287,22 +652,251 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
             }
         };
 
-        // Check that the resource to be patched is active.
+        // Add/update the mapping of the resource instance to the target.
         let mut lck = self.inner.lock().await;
-        if !lck.instances.contains_key(&update.function_id) {
+        if let Some(instance) = lck.instances.get_mut(&update.function_id) {
+            instance.target = Some(target);
+        } else {
             anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
         }
 
-        // Add/update the mapping of the resource provider to the target.
-        let _ = lck.sender.send(OllamaCommand::Patch(update.function_id, target)).await;
-
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    // use super::*;
+    use super::*;
+
+    /// Start a mock LLM server answering "Hello, world!" in two chunks,
+    /// which returns the bodies of the requests received.
+    async fn mock_llm_server() -> (String, std::sync::Arc<std::sync::Mutex<Vec<serde_json::Value>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let cloned_requests = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let requests = cloned_requests.clone();
+                let service = hyper::service::service_fn(move |req: hyper::Request<hyper::body::Incoming>| {
+                    let requests = requests.clone();
+                    async move {
+                        use http_body_util::BodyExt;
+                        let path = req.uri().path().to_string();
+                        let body: serde_json::Value = serde_json::from_slice(&req.into_body().collect().await.unwrap().to_bytes()).unwrap();
+                        let stream = body["stream"].as_bool().unwrap_or_default();
+                        requests.lock().unwrap().push(body);
+
+                        let chunks = ["Hello, ", "world!"];
+                        let body = match (path.as_str(), stream) {
+                            ("/api/chat", false) => {
+                                serde_json::json!({"message": {"role": "assistant", "content": chunks.concat()}, "done": true}).to_string()
+                            }
+                            ("/api/chat", true) => {
+                                let mut body = String::new();
+                                for chunk in chunks {
+                                    body.push_str(&format!(
+                                        "{}\n",
+                                        serde_json::json!({"message": {"role": "assistant", "content": chunk}, "done": false})
+                                    ));
+                                }
+                                body.push_str(&format!(
+                                    "{}\n",
+                                    serde_json::json!({"message": {"role": "assistant", "content": ""}, "done": true})
+                                ));
+                                body
+                            }
+                            ("/v1/chat/completions", false) => {
+                                serde_json::json!({"choices": [{"index": 0, "message": {"role": "assistant", "content": chunks.concat()}}]})
+                                    .to_string()
+                            }
+                            ("/v1/chat/completions", true) => {
+                                let mut body = String::new();
+                                for chunk in chunks {
+                                    body.push_str(&format!(
+                                        "data: {}\n\n",
+                                        serde_json::json!({"choices": [{"index": 0, "delta": {"content": chunk}}]})
+                                    ));
+                                }
+                                body.push_str("data: [DONE]\n\n");
+                                body
+                            }
+                            _ => {
+                                let mut res = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::new()));
+                                *res.status_mut() = hyper::StatusCode::NOT_FOUND;
+                                return Ok::<_, std::convert::Infallible>(res);
+                            }
+                        };
+                        Ok::<_, std::convert::Infallible>(hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(body))))
+                    }
+                });
+                tokio::spawn(async move {
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn configuration(backend: &str, url: &str, stream: bool) -> ChatConfiguration {
+        ChatConfiguration::from_configuration(
+            &std::collections::HashMap::from([
+                ("model".to_string(), "my-model".to_string()),
+                ("backend".to_string(), backend.to_string()),
+                ("url".to_string(), url.to_string()),
+                ("system-prompt".to_string(), "Be brief.".to_string()),
+                ("temperature".to_string(), "0.5".to_string()),
+                ("max-tokens".to_string(), "100".to_string()),
+                ("stream".to_string(), stream.to_string()),
+            ]),
+            "http://localhost:11434",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ollama_configuration() {
+        let conf = |pairs: &[(&str, &str)]| {
+            ChatConfiguration::from_configuration(
+                &pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                "http://localhost:11434",
+            )
+        };
+
+        let default = conf(&[("model", "llama3")]).unwrap();
+        assert_eq!(ChatBackend::Ollama, default.backend);
+        assert_eq!("http://localhost:11434", default.url);
+        assert!(!default.stream);
+        assert_eq!("[DONE]", default.end_of_stream);
+        assert_eq!(None, default.temperature);
+        assert_eq!(100, default.max_conversations);
+
+        let openai = conf(&[
+            ("model", "gpt-4o-mini"),
+            ("backend", "openai"),
+            ("url", "https://api.openai.com/v1/"),
+            ("api-key", "secret"),
+            ("stream", "true"),
+            ("end-of-stream", "<EOS>"),
+        ])
+        .unwrap();
+        assert_eq!(ChatBackend::OpenAi, openai.backend);
+        assert_eq!("https://api.openai.com/v1", openai.url);
+        assert_eq!(Some("secret".to_string()), openai.api_key);
+        assert!(openai.stream);
+        assert_eq!("<EOS>", openai.end_of_stream);
+
+        assert!(conf(&[]).is_err());
+        assert!(conf(&[("model", "gpt-4o-mini"), ("backend", "openai")]).is_err());
+        assert!(conf(&[("model", "llama3"), ("backend", "unknown")]).is_err());
+        assert!(conf(&[("model", "llama3"), ("temperature", "hot")]).is_err());
+        assert!(conf(&[("model", "llama3"), ("max-tokens", "-1")]).is_err());
+        assert!(conf(&[("model", "llama3"), ("max-conversations", "0")]).is_err());
+    }
+
+    #[test]
+    fn test_ollama_conversations() {
+        let mut conversations = Conversations::new(4, 2);
+        let system_prompt = Some("Be brief.".to_string());
+
+        assert_eq!(
+            vec![ChatMessage::new("system", "Be brief."), ChatMessage::new("user", "q1")],
+            conversations.messages("a", &system_prompt, "q1")
+        );
+        conversations.push("a", "q1", "a1");
+        conversations.push("a", "q2", "a2");
+        conversations.push("b", "q3", "a3");
+        assert_eq!(
+            vec![
+                ChatMessage::new("user", "q1"),
+                ChatMessage::new("assistant", "a1"),
+                ChatMessage::new("user", "q2"),
+                ChatMessage::new("assistant", "a2"),
+                ChatMessage::new("user", "q4"),
+            ],
+            conversations.messages("a", &None, "q4")
+        );
+
+        // The oldest messages are removed beyond the limit.
+        conversations.push("a", "q4", "a4");
+        assert_eq!(
+            vec![
+                ChatMessage::new("user", "q2"),
+                ChatMessage::new("assistant", "a2"),
+                ChatMessage::new("user", "q4"),
+                ChatMessage::new("assistant", "a4"),
+                ChatMessage::new("user", "q5"),
+            ],
+            conversations.messages("a", &None, "q5")
+        );
+        assert_eq!(3, conversations.messages("b", &None, "q6").len());
+
+        // The least recently used conversation is removed beyond the limit.
+        conversations.push("a", "q5", "a5");
+        conversations.push("c", "q7", "a7");
+        assert_eq!(1, conversations.messages("b", &None, "q8").len());
+        assert_eq!(5, conversations.messages("a", &None, "q6").len());
+        assert_eq!(3, conversations.messages("c", &None, "q8").len());
+        assert_eq!(2, conversations.histories.len());
+    }
+
+    #[tokio::test]
+    async fn test_ollama_body_lines() {
+        let body = futures::stream::iter(vec![
+            Ok::<_, std::io::Error>(b"first\nsec".to_vec()),
+            Ok(b"ond\r\n\nthi".to_vec()),
+            Ok(b"rd".to_vec()),
+        ]);
+        let lines: Vec<String> = body_lines(body).try_collect().await.unwrap();
+        assert_eq!(vec!["first", "second", "", "third"], lines);
+    }
+
+    #[tokio::test]
+    async fn test_ollama_chat() {
+        let (url, requests) = mock_llm_server().await;
+
+        for (backend, url) in [("ollama", url.clone()), ("openai", format!("{}/v1", url))] {
+            for stream in [false, true] {
+                let client = ChatClient::new(configuration(backend, &url, stream));
+                let chunks: Vec<String> = client
+                    .chat(vec![ChatMessage::new("user", "Hi")])
+                    .await
+                    .unwrap()
+                    .try_collect()
+                    .await
+                    .unwrap();
+                if stream {
+                    assert_eq!(vec!["Hello, ", "world!"], chunks, "backend {}", backend);
+                } else {
+                    assert_eq!(vec!["Hello, world!"], chunks, "backend {}", backend);
+                }
+
+                let request = requests.lock().unwrap().pop().unwrap();
+                assert_eq!("my-model", request["model"]);
+                assert_eq!(stream, request["stream"]);
+                assert_eq!("Hi", request["messages"][0]["content"]);
+                match backend {
+                    "ollama" => {
+                        assert_eq!(0.5, request["options"]["temperature"]);
+                        assert_eq!(100, request["options"]["num_predict"]);
+                    }
+                    _ => {
+                        assert_eq!(0.5, request["temperature"]);
+                        assert_eq!(100, request["max_tokens"]);
+                    }
+                }
+            }
+        }
+
+        // Wrong URL.
+        let client = ChatClient::new(configuration("openai", &url, false));
+        assert!(client.chat(vec![ChatMessage::new("user", "Hi")]).await.is_err());
+    }
 
     #[ignore]
     #[tokio::test]
@@ -4,6 +4,8 @@
 // SPDX-License-Identifier: MIT
 use edgeless_dataplane::core::Message;
 extern crate redis;
+use futures::StreamExt;
+use redis::AsyncCommands;
 use redis::Commands;
 
 pub struct RedisResourceSpec {}
@@ -14,30 +16,64 @@ impl super::resource_provider_specs::ResourceProviderSpecs for RedisResourceSpec
     }
 
     fn description(&self) -> String {
-        r"Perform SET and GET operations on a Redis server -- https://redis.io/
+        r"Perform operations on a Redis server -- https://redis.io/
 
+In key-value mode (default):
 A SET operation is performed with a cast() on the key specified in the 'key' configuration parameter of the resource.
-A GET operation is performed with a call(), with the key specified in the message body."
+A GET operation is performed with a call(), with the key specified in the message body.
+
+In command mode, the message body is a JSON object with the command to be executed, e.g., {'cmd':'HSET','key':'k','field':'f','value':'v'}.
+The result of a call() is JSON-encoded.
+
+In subscriber mode, the messages published on pub/sub channels or the entries added to a stream are cast to the output channel 'out'."
             .to_string()
     }
 
     fn outputs(&self) -> Vec<String> {
-        vec![]
+        vec![String::from("out")]
     }
 
     fn configurations(&self) -> std::collections::HashMap<String, String> {
         std::collections::HashMap::from([
             (String::from("url"), String::from("URL of the Redis server to use")),
+            (
+                String::from("mode"),
+                String::from("One of: key-value, command, subscriber. Default: key-value"),
+            ),
             (String::from("key"), String::from("Key for SET operations (optional)")),
             (
                 String::from("add-workflow-id"),
                 String::from("If present, add the workflow identifier to the key"),
             ),
+            (
+                String::from("channels"),
+                String::from("Subscriber mode: comma-separated list of pub/sub channels or patterns (optional)"),
+            ),
+            (
+                String::from("add-channel"),
+                String::from("Subscriber mode: if present, cast a JSON object with fields channel and payload, instead of the payload only"),
+            ),
+            (
+                String::from("stream"),
+                String::from("Subscriber mode: key of the stream to read (optional)"),
+            ),
+            (
+                String::from("group"),
+                String::from("Subscriber mode: consumer group of the stream. Default: edgeless- followed by the workflow identifier"),
+            ),
+            (
+                String::from("consumer"),
+                String::from("Subscriber mode: consumer name in the group. Default: edgeless- followed by the workflow identifier"),
+            ),
+            (
+                String::from("start-id"),
+                String::from("Subscriber mode: ID from which the group is created, if it does not exist. Default: $"),
+            ),
         ])
     }
 
     fn version(&self) -> String {
-        String::from("1.2")
+        String::from("1.3")
     }
 }
 
@@ -50,23 +86,207 @@ pub struct RedisResourceProviderInner {
     resource_provider_id: edgeless_api::function_instance::InstanceId,
     dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
     telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
-    instances: std::collections::HashMap<edgeless_api::function_instance::InstanceId, RedisResource>,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, RedisResource>,
 }
 
 pub struct RedisResource {
-    join_handle: tokio::task::JoinHandle<()>,
+    join_handles: Vec<tokio::task::JoinHandle<()>>,
+    target: Option<edgeless_api::function_instance::InstanceId>,
 }
 
 impl Drop for RedisResource {
     fn drop(&mut self) {
-        self.join_handle.abort();
+        for join_handle in &self.join_handles {
+            join_handle.abort();
+        }
     }
 }
 
+/// Structured command executed by a redis resource in command mode.
+///
+/// All the keys are prepended by the workflow identifier, if
+/// `add-workflow-id` is specified in the resource configuration.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "cmd", rename_all = "UPPERCASE")]
+pub enum RedisCommand {
+    Get {
+        key: String,
+    },
+    Set {
+        key: String,
+        value: String,
+        /// Time to live, in s.
+        #[serde(default)]
+        ttl: Option<u64>,
+    },
+    Del {
+        key: String,
+    },
+    Incr {
+        key: String,
+        #[serde(default = "RedisCommand::default_increment")]
+        by: i64,
+    },
+    Expire {
+        key: String,
+        seconds: i64,
+    },
+    Ttl {
+        key: String,
+    },
+    Hset {
+        key: String,
+        field: String,
+        value: String,
+    },
+    Hget {
+        key: String,
+        field: String,
+    },
+    Hdel {
+        key: String,
+        field: String,
+    },
+    Hgetall {
+        key: String,
+    },
+    Lpush {
+        key: String,
+        value: String,
+    },
+    Rpush {
+        key: String,
+        value: String,
+    },
+    Lpop {
+        key: String,
+    },
+    Rpop {
+        key: String,
+    },
+    Publish {
+        channel: String,
+        message: String,
+    },
+    Xadd {
+        key: String,
+        fields: std::collections::BTreeMap<String, String>,
+    },
+}
+
+impl RedisCommand {
+    fn default_increment() -> i64 {
+        1
+    }
+
+    /// Execute the command, with keys prepended by `key_header`.
+    /// Return the JSON-encoded result.
+    fn execute(&self, connection: &mut redis::Connection, key_header: &str) -> redis::RedisResult<serde_json::Value> {
+        let k = |key: &String| format!("{}{}", key_header, key);
+        Ok(match self {
+            RedisCommand::Get { key } => serde_json::json!(connection.get::<_, Option<String>>(k(key))?),
+            RedisCommand::Set { key, value, ttl } => {
+                match ttl {
+                    Some(ttl) => connection.set_ex::<_, _, ()>(k(key), value, *ttl)?,
+                    None => connection.set::<_, _, ()>(k(key), value)?,
+                }
+                serde_json::json!("OK")
+            }
+            RedisCommand::Del { key } => serde_json::json!(connection.del::<_, i64>(k(key))?),
+            RedisCommand::Incr { key, by } => serde_json::json!(connection.incr::<_, _, i64>(k(key), *by)?),
+            RedisCommand::Expire { key, seconds } => serde_json::json!(connection.expire::<_, i64>(k(key), *seconds)?),
+            RedisCommand::Ttl { key } => serde_json::json!(connection.ttl::<_, i64>(k(key))?),
+            RedisCommand::Hset { key, field, value } => serde_json::json!(connection.hset::<_, _, _, i64>(k(key), field, value)?),
+            RedisCommand::Hget { key, field } => serde_json::json!(connection.hget::<_, _, Option<String>>(k(key), field)?),
+            RedisCommand::Hdel { key, field } => serde_json::json!(connection.hdel::<_, _, i64>(k(key), field)?),
+            RedisCommand::Hgetall { key } => serde_json::json!(connection.hgetall::<_, std::collections::BTreeMap<String, String>>(k(key))?),
+            RedisCommand::Lpush { key, value } => serde_json::json!(connection.lpush::<_, _, i64>(k(key), value)?),
+            RedisCommand::Rpush { key, value } => serde_json::json!(connection.rpush::<_, _, i64>(k(key), value)?),
+            RedisCommand::Lpop { key } => serde_json::json!(connection.lpop::<_, Option<String>>(k(key), None)?),
+            RedisCommand::Rpop { key } => serde_json::json!(connection.rpop::<_, Option<String>>(k(key), None)?),
+            RedisCommand::Publish { channel, message } => serde_json::json!(connection.publish::<_, _, i64>(channel, message)?),
+            RedisCommand::Xadd { key, fields } => serde_json::json!(connection.xadd_map::<_, _, _, String>(k(key), "*", fields)?),
+        })
+    }
+}
+
+/// Stream read by a redis resource in subscriber mode.
+#[derive(Debug, Clone, PartialEq)]
+struct RedisStream {
+    key: String,
+    group: String,
+    consumer: String,
+    start_id: String,
+}
+
+/// Operation mode of a redis resource instance.
+#[derive(Debug, Clone, PartialEq)]
+enum RedisMode {
+    /// SET via cast() on a fixed key, GET via call().
+    KeyValue { key: Option<String> },
+    /// Execute the structured commands received.
+    Command,
+    /// Cast the messages published on channels and the stream entries.
+    Subscriber {
+        channels: Vec<String>,
+        add_channel: bool,
+        stream: Option<RedisStream>,
+    },
+}
+
+impl RedisMode {
+    fn from_configuration(configuration: &std::collections::HashMap<String, String>, key_header: &str, workflow_id: &str) -> anyhow::Result<Self> {
+        match configuration.get("mode").map(|x| x.as_str()).unwrap_or("key-value") {
+            "key-value" => Ok(RedisMode::KeyValue {
+                key: configuration.get("key").map(|k| format!("{}{}", key_header, k)),
+            }),
+            "command" => Ok(RedisMode::Command),
+            "subscriber" => {
+                let channels: Vec<String> = configuration
+                    .get("channels")
+                    .map(|x| x.split(',').map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect())
+                    .unwrap_or_default();
+                let stream = configuration.get("stream").filter(|x| !x.is_empty()).map(|key| RedisStream {
+                    key: format!("{}{}", key_header, key),
+                    group: configuration.get("group").cloned().unwrap_or(format!("edgeless-{}", workflow_id)),
+                    consumer: configuration.get("consumer").cloned().unwrap_or(format!("edgeless-{}", workflow_id)),
+                    start_id: configuration.get("start-id").cloned().unwrap_or(String::from("$")),
+                });
+                if channels.is_empty() && stream.is_none() {
+                    anyhow::bail!("No channels or stream specified in subscriber mode");
+                }
+                Ok(RedisMode::Subscriber {
+                    channels,
+                    add_channel: configuration.contains_key("add-channel"),
+                    stream,
+                })
+            }
+            mode => anyhow::bail!("Invalid mode: {}", mode),
+        }
+    }
+}
+
+/// Message cast to the output channel, if `add-channel` is specified.
+#[derive(serde::Serialize)]
+struct RedisChannelMessage<'a> {
+    channel: &'a str,
+    payload: &'a str,
+}
+
+/// Stream entry cast to the output channel.
+#[derive(serde::Serialize)]
+struct RedisStreamEntry<'a> {
+    stream: &'a str,
+    id: &'a str,
+    fields: std::collections::BTreeMap<String, String>,
+}
+
 /// The redis resource can be used to access a Redis KVS.
 /// Each resource instance has its own connection at the Redis URL specified
 /// in the resource configuration.
-/// The same resource can be used to GET or SET keys.
+///
+/// In key-value mode (default), the same resource can be used to GET or SET
+/// keys.
 ///
 /// The GET operation is done on an arbitrary key that is specified as the
 /// message of the call() operation.
@@ -74,36 +294,105 @@ impl Drop for RedisResource {
 /// The SET operation is done via a cast() on the key, if specified in the
 /// resource configuration.
 ///
+/// In command mode, the message is a JSON-encoded `RedisCommand`.
+///
+/// In subscriber mode, the resource casts to its output channel the messages
+/// published on the pub/sub channels and the entries of a stream, read as
+/// part of a consumer group and acknowledged once delivered. The entries
+/// delivered to the consumer but not acknowledged, e.g., because the
+/// resource was stopped, are read again when the resource is started.
+///
 /// Optionally, the key can be prepended by the workflow identifier and a
 /// semicolon.
 impl RedisResource {
     async fn new(
+        self_function_id: edgeless_api::function_instance::ComponentId,
         dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
         telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        inner: std::sync::Arc<tokio::sync::Mutex<RedisResourceProviderInner>>,
         redis_url: &str,
-        redis_key: Option<&String>,
-        workflow_id: Option<String>,
+        configuration: &std::collections::HashMap<String, String>,
+        workflow_id: String,
     ) -> anyhow::Result<Self> {
         let mut dataplane_handle = dataplane_handle;
         let mut telemetry_handle = telemetry_handle;
 
-        log::info!(
-            "RedisResource created, url {}, key {:?}, workflow_id {:?}",
-            redis_url,
-            redis_key,
-            workflow_id
-        );
-
-        let workflow_id_header = if let Some(workflow_id) = workflow_id {
+        let workflow_id_header = if configuration.contains_key("add-workflow-id") {
             format!("{}:", workflow_id)
         } else {
             String::default()
         };
-        let redis_key = redis_key.cloned().map(|k| format!("{}{}", workflow_id_header, k));
+        let mode = RedisMode::from_configuration(configuration, &workflow_id_header, &workflow_id)?;
+
+        log::info!("RedisResource created, url {}, mode {:?}, workflow_id {}", redis_url, mode, workflow_id);
+
+        let client = redis::Client::open(redis_url)?;
+        let mut join_handles = vec![];
 
-        let mut connection = redis::Client::open(redis_url)?.get_connection()?;
+        let redis_key = match &mode {
+            RedisMode::KeyValue { key } => key.clone(),
+            _ => None,
+        };
+        let command_mode = mode == RedisMode::Command;
+        let mut connection = match mode {
+            RedisMode::Subscriber {
+                channels,
+                add_channel,
+                stream,
+            } => {
+                if !channels.is_empty() {
+                    let client = client.clone();
+                    let dataplane_handle = dataplane_handle.clone();
+                    let mut telemetry_handle = telemetry_handle.fork(std::collections::BTreeMap::new());
+                    let inner = inner.clone();
+                    join_handles.push(tokio::spawn(async move {
+                        loop {
+                            if let Err(err) = Self::subscribe_channels(
+                                &client,
+                                &channels,
+                                add_channel,
+                                self_function_id,
+                                dataplane_handle.clone(),
+                                telemetry_handle.as_mut(),
+                                inner.clone(),
+                            )
+                            .await
+                            {
+                                log::warn!("Error when subscribing to Redis channels {:?}: {}", channels, err);
+                            }
+                            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                        }
+                    }));
+                }
+                if let Some(stream) = stream {
+                    let client = client.clone();
+                    let dataplane_handle = dataplane_handle.clone();
+                    let mut telemetry_handle = telemetry_handle.fork(std::collections::BTreeMap::new());
+                    let inner = inner.clone();
+                    join_handles.push(tokio::spawn(async move {
+                        loop {
+                            if let Err(err) = Self::read_stream(
+                                &client,
+                                &stream,
+                                self_function_id,
+                                dataplane_handle.clone(),
+                                telemetry_handle.as_mut(),
+                                inner.clone(),
+                            )
+                            .await
+                            {
+                                log::warn!("Error when reading Redis stream '{}': {}", stream.key, err);
+                            }
+                            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                        }
+                    }));
+                }
+                None
+            }
+            _ => Some(client.get_connection()?),
+        };
 
-        let handle = tokio::spawn(async move {
+        join_handles.push(tokio::spawn(async move {
             loop {
                 let edgeless_dataplane::core::DataplaneEvent {
                     source_id,
@@ -122,7 +411,40 @@ impl RedisResource {
                     }
                 };
 
-                if get_operation {
+                let Some(connection) = &mut connection else {
+                    log::warn!("Invalid operation requested on a redis resource in subscriber mode");
+                    if get_operation {
+                        dataplane_handle
+                            .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Err, &metadata)
+                            .await;
+                    }
+                    continue;
+                };
+
+                if command_mode {
+                    let res = match serde_json::from_str::<RedisCommand>(&message_data) {
+                        Ok(command) => command
+                            .execute(connection, &workflow_id_header)
+                            .map_err(|err| format!("could not execute {:?}: {}", command, err)),
+                        Err(err) => Err(format!("invalid command '{}': {}", message_data, err)),
+                    };
+                    if let Err(err) = &res {
+                        log::error!("Redis command failed: {}", err);
+                    }
+                    if get_operation {
+                        dataplane_handle
+                            .reply(
+                                source_id,
+                                channel_id,
+                                match res {
+                                    Ok(value) => edgeless_dataplane::core::CallRet::Reply(value.to_string()),
+                                    Err(_) => edgeless_dataplane::core::CallRet::Err,
+                                },
+                                &metadata,
+                            )
+                            .await;
+                    }
+                } else if get_operation {
                     // GET
                     let redis_key = format!("{}{}", workflow_id_header, message_data);
                     match connection.get::<&str, std::string::String>(&redis_key) {
@@ -156,9 +478,151 @@ impl RedisResource {
 
                 crate::resources::observe_execution(started, &mut telemetry_handle, get_operation);
             }
-        });
+        }));
 
-        Ok(Self { join_handle: handle })
+        Ok(Self { join_handles, target: None })
+    }
+
+    /// Cast a message to the target of the resource instance, if any.
+    /// Return true if the message has been delivered.
+    async fn cast(
+        inner: &std::sync::Arc<tokio::sync::Mutex<RedisResourceProviderInner>>,
+        self_function_id: &edgeless_api::function_instance::ComponentId,
+        dataplane_handle: &mut edgeless_dataplane::handle::DataplaneHandle,
+        telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+        msg: String,
+    ) -> bool {
+        let target = match inner.lock().await.instances.get(self_function_id) {
+            Some(instance) => instance.target,
+            None => None,
+        };
+        match target {
+            Some(target) => {
+                let mut span = edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+                    telemetry_handle,
+                    "redis-receive",
+                    edgeless_telemetry::telemetry_events::SpanKind::Consumer,
+                );
+                span.set_attribute("edgeless.resource_id", self_function_id);
+                let delivered = crate::dataplane_metrics::send(dataplane_handle, telemetry_handle, "out", target, msg, &span.metadata).await;
+                span.end(telemetry_handle);
+                delivered
+            }
+            None => false,
+        }
+    }
+
+    /// Subscribe to pub/sub channels and cast the messages received, until
+    /// the connection is closed.
+    async fn subscribe_channels(
+        client: &redis::Client,
+        channels: &[String],
+        add_channel: bool,
+        self_function_id: edgeless_api::function_instance::ComponentId,
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+        inner: std::sync::Arc<tokio::sync::Mutex<RedisResourceProviderInner>>,
+    ) -> redis::RedisResult<()> {
+        let mut dataplane_handle = dataplane_handle;
+        let mut pubsub = client.get_async_pubsub().await?;
+        for channel in channels {
+            if channel.contains(['*', '?', '[']) {
+                pubsub.psubscribe(channel).await?;
+            } else {
+                pubsub.subscribe(channel).await?;
+            }
+        }
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            let payload = String::from_utf8_lossy(msg.get_payload_bytes());
+            let data = if add_channel {
+                serde_json::to_string(&RedisChannelMessage {
+                    channel: msg.get_channel_name(),
+                    payload: &payload,
+                })
+                .unwrap_or_default()
+            } else {
+                payload.to_string()
+            };
+            if !Self::cast(&inner, &self_function_id, &mut dataplane_handle, telemetry_handle, data).await {
+                log::debug!("Discarding Redis message on channel '{}': not delivered", msg.get_channel_name());
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the entries of a stream as part of a consumer group, cast them,
+    /// and acknowledge them once delivered, until an error occurs.
+    /// The entries pending for the consumer are read first.
+    async fn read_stream(
+        client: &redis::Client,
+        stream: &RedisStream,
+        self_function_id: edgeless_api::function_instance::ComponentId,
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        telemetry_handle: &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+        inner: std::sync::Arc<tokio::sync::Mutex<RedisResourceProviderInner>>,
+    ) -> redis::RedisResult<()> {
+        let mut dataplane_handle = dataplane_handle;
+        let mut connection = client.get_multiplexed_async_connection().await?;
+        if let Err(err) = connection
+            .xgroup_create_mkstream::<_, _, _, ()>(&stream.key, &stream.group, &stream.start_id)
+            .await
+            && err.code() != Some("BUSYGROUP")
+        {
+            return Err(err);
+        }
+
+        let options = redis::streams::StreamReadOptions::default()
+            .group(&stream.group, &stream.consumer)
+            .count(10)
+            .block(1000);
+        // ID from which the entries are read: the pending entries, i.e.,
+        // delivered to this consumer but not acknowledged, are read from "0"
+        // onwards until there are none left, then the new ones with ">".
+        let mut read_id = String::from("0");
+        loop {
+            // Do not consume entries until there is a target.
+            if inner.lock().await.instances.get(&self_function_id).is_none_or(|x| x.target.is_none()) {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                continue;
+            }
+
+            let reply: Option<redis::streams::StreamReadReply> = connection.xread_options(&[&stream.key], &[&read_id], &options).await?;
+            let entries: Vec<_> = reply
+                .map(|x| x.keys)
+                .unwrap_or_default()
+                .into_iter()
+                .flat_map(|stream_key| stream_key.ids)
+                .collect();
+            if entries.is_empty() && read_id != ">" {
+                read_id = String::from(">");
+                continue;
+            }
+            for entry in entries {
+                let fields = entry
+                    .map
+                    .iter()
+                    .map(|(k, v)| (k.clone(), redis::from_redis_value::<String>(v).unwrap_or_default()))
+                    .collect();
+                let data = serde_json::to_string(&RedisStreamEntry {
+                    stream: &stream.key,
+                    id: &entry.id,
+                    fields,
+                })
+                .unwrap_or_default();
+                if !Self::cast(&inner, &self_function_id, &mut dataplane_handle, telemetry_handle, data).await {
+                    // Leave the entry pending and read it again, in order.
+                    log::warn!("Could not deliver the entry '{}' of Redis stream '{}', retrying", entry.id, stream.key);
+                    read_id = String::from("0");
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    break;
+                }
+                connection.xack::<_, _, _, ()>(&stream.key, &stream.group, &[&entry.id]).await?;
+                if read_id != ">" {
+                    read_id = entry.id;
+                }
+            }
+        }
     }
 }
 
@@ -173,7 +637,7 @@ impl RedisResourceProvider {
                 resource_provider_id,
                 dataplane_provider,
                 telemetry_handle,
-                instances: std::collections::HashMap::<edgeless_api::function_instance::InstanceId, RedisResource>::new(),
+                instances: std::collections::HashMap::<edgeless_api::function_instance::ComponentId, RedisResource>::new(),
             })),
         }
     }
@@ -195,19 +659,18 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
             )]));
 
             match RedisResource::new(
+                new_id.function_id,
                 dataplane_handle,
                 telemetry_handle,
+                self.inner.clone(),
                 url,
-                instance_specification.configuration.get("key"),
-                instance_specification
-                    .configuration
-                    .contains_key("add-workflow-id")
-                    .then_some(instance_specification.workflow_id),
+                &instance_specification.configuration,
+                instance_specification.workflow_id,
             )
             .await
             {
                 Ok(resource) => {
-                    lck.instances.insert(new_id, resource);
+                    lck.instances.insert(new_id.function_id, resource);
                     return Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id));
                 }
                 Err(err) => {
@@ -230,12 +693,223 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
     }
 
     async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
-        self.inner.lock().await.instances.remove(&resource_id);
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
         Ok(())
     }
 
-    async fn patch(&mut self, _update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
-        // the resource has no channels: nothing to be patched
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        let mut lck = self.inner.lock().await;
+        if let Some(instance) = lck.instances.get_mut(&update.function_id) {
+            instance.target = update.output_mapping.get("out").cloned();
+        } else {
+            anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::NoTelemetryHandle;
+    use crate::test_utils::configuration;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    /// Receive the next message cast by the resource to its target.
+    async fn receive(target: &mut edgeless_dataplane::handle::DataplaneHandle) -> serde_json::Value {
+        let event = tokio::time::timeout(tokio::time::Duration::from_secs(10), target.receive_next())
+            .await
+            .unwrap();
+        match event.message {
+            Message::Cast(data) => serde_json::from_str(&data).unwrap(),
+            message => panic!("unexpected message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_redis_command_deserialize() {
+        assert_eq!(
+            RedisCommand::Hset {
+                key: "k".to_string(),
+                field: "f".to_string(),
+                value: "v".to_string()
+            },
+            serde_json::from_str(r#"{"cmd":"HSET","key":"k","field":"f","value":"v"}"#).unwrap()
+        );
+        assert_eq!(
+            RedisCommand::Incr {
+                key: "counter".to_string(),
+                by: 1
+            },
+            serde_json::from_str(r#"{"cmd":"INCR","key":"counter"}"#).unwrap()
+        );
+        assert_eq!(
+            RedisCommand::Set {
+                key: "k".to_string(),
+                value: "v".to_string(),
+                ttl: Some(60)
+            },
+            serde_json::from_str(r#"{"cmd":"SET","key":"k","value":"v","ttl":60}"#).unwrap()
+        );
+        assert_eq!(
+            RedisCommand::Xadd {
+                key: "events".to_string(),
+                fields: std::collections::BTreeMap::from([("temperature".to_string(), "21".to_string())])
+            },
+            serde_json::from_str(r#"{"cmd":"XADD","key":"events","fields":{"temperature":"21"}}"#).unwrap()
+        );
+        assert!(serde_json::from_str::<RedisCommand>(r#"{"cmd":"FLUSHALL"}"#).is_err());
+        assert!(serde_json::from_str::<RedisCommand>(r#"{"cmd":"HGET","key":"k"}"#).is_err());
+    }
+
+    #[test]
+    fn test_redis_mode() {
+        assert_eq!(
+            RedisMode::KeyValue {
+                key: Some("wf:counter".to_string())
+            },
+            RedisMode::from_configuration(&configuration(&[("key", "counter")]), "wf:", "wf").unwrap()
+        );
+        assert_eq!(
+            RedisMode::Command,
+            RedisMode::from_configuration(&configuration(&[("mode", "command")]), "", "wf").unwrap()
+        );
+        assert!(RedisMode::from_configuration(&configuration(&[("mode", "other")]), "", "wf").is_err());
+        assert!(RedisMode::from_configuration(&configuration(&[("mode", "subscriber")]), "", "wf").is_err());
+        assert_eq!(
+            RedisMode::Subscriber {
+                channels: vec!["alarms".to_string(), "sensors.*".to_string()],
+                add_channel: true,
+                stream: Some(RedisStream {
+                    key: "events".to_string(),
+                    group: "edgeless-wf".to_string(),
+                    consumer: "edgeless-wf".to_string(),
+                    start_id: "$".to_string(),
+                }),
+            },
+            RedisMode::from_configuration(
+                &configuration(&[
+                    ("mode", "subscriber"),
+                    ("channels", "alarms, sensors.*"),
+                    ("add-channel", ""),
+                    ("stream", "events")
+                ]),
+                "",
+                "wf"
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redis_subscriber() {
+        let redis_url = "redis://localhost:6379";
+        // Skip the test if there is no local Redis listening on default port.
+        let mut connection = match redis::Client::open(redis_url).and_then(|client| client.get_connection()) {
+            Ok(connection) => connection,
+            Err(_) => {
+                println!("the test cannot be run because there is no Redis reachable on localhost at port 6379");
+                return;
+            }
+        };
+
+        let node_id = uuid::Uuid::new_v4();
+        let mut provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, String::from("http://127.0.0.1:7111"), None).await;
+        let mut redis_provider = RedisResourceProvider::new(
+            provider.clone(),
+            Box::new(NoTelemetryHandle {}),
+            edgeless_api::function_instance::InstanceId::new(node_id),
+        )
+        .await;
+
+        let channel = format!("edgeless-test-{}", uuid::Uuid::new_v4());
+        let stream = format!("edgeless-test-stream-{}", uuid::Uuid::new_v4());
+
+        // Add an entry to the stream which is read, but not acknowledged, by
+        // the consumer that the resource uses by default, as if a previous
+        // instance had been stopped before delivering it.
+        connection
+            .xgroup_create_mkstream::<_, _, _, ()>(&stream, "edgeless-workflow_1", "0")
+            .unwrap();
+        let pending_id = connection.xadd::<_, _, _, _, String>(&stream, "*", &[("temperature", "20")]).unwrap();
+        connection
+            .xread_options::<_, _, redis::streams::StreamReadReply>(
+                &[&stream],
+                &[">"],
+                &redis::streams::StreamReadOptions::default().group("edgeless-workflow_1", "edgeless-workflow_1"),
+            )
+            .unwrap();
+        let resource_id = match redis_provider
+            .start(edgeless_api::resource_configuration::ResourceInstanceSpecification {
+                class_type: "redis".to_string(),
+                configuration: std::collections::HashMap::from([
+                    ("url".to_string(), redis_url.to_string()),
+                    ("mode".to_string(), "subscriber".to_string()),
+                    ("channels".to_string(), channel.clone()),
+                    ("add-channel".to_string(), String::default()),
+                    ("stream".to_string(), stream.clone()),
+                    ("start-id".to_string(), "0".to_string()),
+                ]),
+                workflow_id: "workflow_1".to_string(),
+            })
+            .await
+            .unwrap()
+        {
+            edgeless_api::common::StartComponentResponse::InstanceId(resource_id) => resource_id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        };
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut target = provider.get_handle_for(target_id).await;
+        redis_provider
+            .patch(edgeless_api::common::PatchRequest {
+                function_id: resource_id.function_id,
+                output_mapping: std::collections::HashMap::from([("out".to_string(), target_id)]),
+            })
+            .await
+            .unwrap();
+
+        // The pending entry is cast first.
+        assert_eq!(
+            serde_json::json!({"stream": stream, "id": pending_id, "fields": {"temperature": "20"}}),
+            receive(&mut target).await
+        );
+
+        // Publish a message on the channel, once the resource has subscribed.
+        for _ in 0..100 {
+            let (_, subscribers) = redis::cmd("PUBSUB")
+                .arg("NUMSUB")
+                .arg(&channel)
+                .query::<(String, i64)>(&mut connection)
+                .unwrap();
+            if subscribers > 0 {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+        connection.publish::<_, _, i64>(&channel, "hello").unwrap();
+        assert_eq!(serde_json::json!({"channel": channel, "payload": "hello"}), receive(&mut target).await);
+
+        // Add an entry to the stream, which is acknowledged once cast.
+        let id = connection.xadd::<_, _, _, _, String>(&stream, "*", &[("temperature", "21")]).unwrap();
+        assert_eq!(
+            serde_json::json!({"stream": stream, "id": id, "fields": {"temperature": "21"}}),
+            receive(&mut target).await
+        );
+        let mut pending = usize::MAX;
+        for _ in 0..100 {
+            pending = connection
+                .xpending::<_, _, redis::streams::StreamPendingReply>(&stream, "edgeless-workflow_1")
+                .unwrap()
+                .count();
+            if pending == 0 {
+                break;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!(0, pending);
+
+        redis_provider.stop(resource_id).await.unwrap();
+        connection.del::<_, ()>(&stream).unwrap();
+    }
+}
@@ -48,7 +48,7 @@ pub struct ServerlessResourceProvider {
 struct CallCommand {
     msg: String,
     resource_id: edgeless_api::function_instance::ComponentId,
-    reply_sender: tokio::sync::oneshot::Sender<anyhow::Result<(Option<edgeless_api::function_instance::InstanceId>, String)>>,
+    reply_sender: tokio::sync::oneshot::Sender<anyhow::Result<(Option<edgeless_api::function_instance::InstanceId>, &'static str, String)>>,
 }
 
 enum ServerlessCommand {
@@ -114,7 +114,7 @@ impl ServerlessResource {
                 };
 
                 let (reply_sender, reply_receiver) =
-                    tokio::sync::oneshot::channel::<anyhow::Result<(Option<edgeless_api::function_instance::InstanceId>, String)>>();
+                    tokio::sync::oneshot::channel::<anyhow::Result<(Option<edgeless_api::function_instance::InstanceId>, &'static str, String)>>();
                 let _ = sender
                     .send(ServerlessCommand::Call(CallCommand {
                         msg,
@@ -125,9 +125,17 @@ impl ServerlessResource {
 
                 match reply_receiver.await {
                     Ok(response) => match response {
-                        Ok((target, response)) => {
+                        Ok((target, channel, response)) => {
                             if let Some(target) = target {
-                                let _ = dataplane_handle.send(target, response, &metadata).await;
+                                crate::dataplane_metrics::send(
+                                    &mut dataplane_handle,
+                                    telemetry_handle.as_mut(),
+                                    channel,
+                                    target,
+                                    response,
+                                    &metadata,
+                                )
+                                .await;
                             }
                         }
                         Err(err) => {
@@ -182,9 +190,10 @@ impl ServerlessResourceProvider {
                             Ok(ret) => {
                                 if ret.status() == reqwest::StatusCode::OK {
                                     match ret.text().await {
-                                        Ok(body) => Ok((target_out, body)),
+                                        Ok(body) => Ok((target_out, "out", body)),
                                         Err(err) => Ok((
                                             target_err,
+                                            "err",
                                             format!(
                                                 "error when calling serverless function at {} for resource {}: {}",
                                                 function_url, cmd.resource_id, err,
@@ -194,6 +203,7 @@ impl ServerlessResourceProvider {
                                 } else {
                                     Ok((
                                         target_err,
+                                        "err",
                                         format!(
                                             "error when calling serverless function at {} for resource {}: status {} returned",
                                             function_url,
@@ -205,6 +215,7 @@ impl ServerlessResourceProvider {
                             }
                             Err(err) => Ok((
                                 target_err,
+                                "err",
                                 format!(
                                     "error when calling serverless function at {} for resource {}: {}",
                                     function_url, cmd.resource_id, err,
@@ -1,8 +1,10 @@
 // SPDX-FileCopyrightText: © 2024 University of Cambridge, System Research Group
 // SPDX-FileCopyrightText: © 2024 Chen Chen <cc2181@cam.ac.uk>
 // SPDX-License-Identifier: MIT
+use base64::Engine;
 use edgeless_dataplane::core::Message;
-use sqlx::{FromRow, Sqlite, SqlitePool, migrate::MigrateDatabase};
+use futures::TryStreamExt;
+use sqlx::{Column, FromRow, Row, Sqlite, SqlitePool, ValueRef, migrate::MigrateDatabase};
 use tokio;
 
 pub struct SqlxResourceSpec {}
@@ -13,22 +15,79 @@ impl super::resource_provider_specs::ResourceProviderSpecs for SqlxResourceSpec
     }
 
     fn description(&self) -> String {
-        r"Perform operations on an SQLite database".to_string()
+        r"Perform operations on an SQL database (SQLite, and PostgreSQL/MySQL if enabled at build time)
+
+Requests are JSON objects with an 'op' field: begin, execute, query, commit, rollback.
+Statements are prepared and the parameters in 'params' are bound to them.
+Rows are returned as JSON objects, or cast one by one to the output channel 'out' if 'stream' is true.
+Invalid requests are rejected, unless the legacy mode is enabled."
+            .to_string()
     }
 
     fn outputs(&self) -> Vec<String> {
-        vec![]
+        vec![String::from("out")]
     }
 
     fn configurations(&self) -> std::collections::HashMap<String, String> {
-        std::collections::HashMap::from([(String::from("url"), String::from("URL of the SQLite database"))])
+        std::collections::HashMap::from([
+            (String::from("url"), String::from("URL of the database")),
+            (
+                String::from("migrations"),
+                String::from("Directory containing the migrations applied when the resource starts"),
+            ),
+            (
+                String::from("transaction-timeout-ms"),
+                String::from("Idle time after which an open transaction is rolled back, in ms. Default: 30000"),
+            ),
+            (
+                String::from("legacy"),
+                String::from(
+                    "If true, the messages that are not JSON requests are executed as raw SQL statements on the WorkflowState table. Default: false",
+                ),
+            ),
+        ])
     }
 
     fn version(&self) -> String {
-        String::from("1.1")
+        String::from("2.0")
     }
 }
 
+/// Request received by the sqlx resource, encoded in JSON.
+///
+/// Statements are prepared and the parameters are bound in order to the
+/// placeholders (`$1`, `$2`, ... or `?`, depending on the database).
+/// A statement is executed within a transaction, previously opened via
+/// `begin`, if its identifier is specified.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum SqlxRequest {
+    /// Open a transaction, which spans over multiple calls.
+    Begin,
+    /// Execute a statement, returning the number of rows affected.
+    Execute {
+        sql: String,
+        #[serde(default)]
+        params: Vec<serde_json::Value>,
+        #[serde(default)]
+        transaction: Option<String>,
+    },
+    /// Execute a query, returning the rows or casting them to the output.
+    Query {
+        sql: String,
+        #[serde(default)]
+        params: Vec<serde_json::Value>,
+        #[serde(default)]
+        transaction: Option<String>,
+        #[serde(default)]
+        stream: bool,
+    },
+    /// Commit a transaction.
+    Commit { transaction: String },
+    /// Roll back a transaction.
+    Rollback { transaction: String },
+}
+
 #[derive(Clone)]
 pub struct SqlxResourceProvider {
     inner: std::sync::Arc<tokio::sync::Mutex<SqlxResourceProviderInner>>,
@@ -38,11 +97,12 @@ pub struct SqlxResourceProviderInner {
     resource_provider_id: edgeless_api::function_instance::InstanceId,
     dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
     telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
-    instances: std::collections::HashMap<edgeless_api::function_instance::InstanceId, SqlxResource>,
+    instances: std::collections::HashMap<edgeless_api::function_instance::ComponentId, SqlxResource>,
 }
 
 pub struct SqlxResource {
     join_handle: tokio::task::JoinHandle<()>,
+    target: Option<edgeless_api::function_instance::InstanceId>,
 }
 
 impl Drop for SqlxResource {
@@ -51,29 +111,241 @@ impl Drop for SqlxResource {
     }
 }
 
+/// Connection pool of a resource instance with its open transactions.
+struct SqlxSession {
+    pool: sqlx::AnyPool,
+    transactions: std::collections::HashMap<String, (sqlx::Transaction<'static, sqlx::Any>, std::time::Instant)>,
+    transaction_timeout: std::time::Duration,
+}
+
+impl SqlxSession {
+    async fn new(url: &str, migrations: Option<&str>, transaction_timeout: std::time::Duration) -> anyhow::Result<Self> {
+        sqlx::any::install_default_drivers();
+
+        if !sqlx::Any::database_exists(url).await.unwrap_or(false) {
+            log::info!("Creating database {}", url);
+            sqlx::Any::create_database(url).await?;
+        }
+        let pool = sqlx::AnyPool::connect(url).await?;
+
+        if let Some(migrations) = migrations {
+            let migrator = sqlx::migrate::Migrator::new(std::path::Path::new(migrations)).await?;
+            migrator.run(&pool).await?;
+            log::info!("Applied {} migrations from {}", migrator.iter().count(), migrations);
+        }
+
+        Ok(Self {
+            pool,
+            transactions: std::collections::HashMap::new(),
+            transaction_timeout,
+        })
+    }
+
+    /// Handle a request, returning the JSON-encoded result.
+    ///
+    /// If the request is a query with streaming enabled, the rows are cast
+    /// to the target via the given dataplane handle, in the same trace as
+    /// the request, and observed via the given telemetry handle.
+    async fn handle(
+        &mut self,
+        request: SqlxRequest,
+        out: Option<(
+            &mut edgeless_dataplane::handle::DataplaneHandle,
+            &mut dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI,
+            edgeless_api::function_instance::InstanceId,
+            &edgeless_api::function_instance::EventMetadata,
+        )>,
+    ) -> anyhow::Result<serde_json::Value> {
+        self.expire_transactions().await;
+
+        match request {
+            SqlxRequest::Begin => {
+                let tx = self.pool.begin().await?;
+                let id = uuid::Uuid::new_v4().to_string();
+                self.transactions.insert(id.clone(), (tx, std::time::Instant::now()));
+                Ok(serde_json::json!({ "transaction": id }))
+            }
+            SqlxRequest::Execute { sql, params, transaction } => {
+                let query = bind(sqlx::query::<sqlx::Any>(&sql), &params);
+                let res = match transaction {
+                    Some(id) => query.execute(&mut **self.transaction(&id)?).await?,
+                    None => query.execute(&self.pool).await?,
+                };
+                Ok(serde_json::json!({ "rows_affected": res.rows_affected(), "last_insert_id": res.last_insert_id() }))
+            }
+            SqlxRequest::Query {
+                sql,
+                params,
+                transaction,
+                stream,
+            } => {
+                let query = bind(sqlx::query::<sqlx::Any>(&sql), &params);
+                if stream {
+                    let Some((dataplane_handle, telemetry_handle, target, metadata)) = out else {
+                        anyhow::bail!("Cannot stream rows: the output channel 'out' is not mapped");
+                    };
+                    let mut rows = match transaction {
+                        Some(id) => query.fetch(&mut **self.transaction(&id)?),
+                        None => query.fetch(&self.pool),
+                    };
+                    let mut rows_streamed = 0_u64;
+                    while let Some(row) = rows.try_next().await? {
+                        crate::dataplane_metrics::send(
+                            dataplane_handle,
+                            telemetry_handle,
+                            "out",
+                            target,
+                            row_to_json(&row)?.to_string(),
+                            metadata,
+                        )
+                        .await;
+                        rows_streamed += 1;
+                    }
+                    Ok(serde_json::json!({ "rows_streamed": rows_streamed }))
+                } else {
+                    let rows = match transaction {
+                        Some(id) => query.fetch_all(&mut **self.transaction(&id)?).await?,
+                        None => query.fetch_all(&self.pool).await?,
+                    };
+                    let rows = rows.iter().map(row_to_json).collect::<anyhow::Result<Vec<_>>>()?;
+                    Ok(serde_json::json!({ "rows": rows }))
+                }
+            }
+            SqlxRequest::Commit { transaction } => {
+                let (tx, _) = self
+                    .transactions
+                    .remove(&transaction)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown transaction: {}", transaction))?;
+                tx.commit().await?;
+                Ok(serde_json::json!({}))
+            }
+            SqlxRequest::Rollback { transaction } => {
+                let (tx, _) = self
+                    .transactions
+                    .remove(&transaction)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown transaction: {}", transaction))?;
+                tx.rollback().await?;
+                Ok(serde_json::json!({}))
+            }
+        }
+    }
+
+    /// Return an open transaction, refreshing its last use time.
+    fn transaction(&mut self, id: &str) -> anyhow::Result<&mut sqlx::Transaction<'static, sqlx::Any>> {
+        let (tx, last_used) = self
+            .transactions
+            .get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown transaction: {}", id))?;
+        *last_used = std::time::Instant::now();
+        Ok(tx)
+    }
+
+    /// Roll back the transactions that have been idle for too long.
+    /// Called before handling a request and periodically by the resource.
+    async fn expire_transactions(&mut self) {
+        let expired: Vec<String> = self
+            .transactions
+            .iter()
+            .filter(|(_, (_, last_used))| last_used.elapsed() > self.transaction_timeout)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in expired {
+            if let Some((tx, _)) = self.transactions.remove(&id) {
+                log::warn!("Rolling back transaction {} after {} ms idle", id, self.transaction_timeout.as_millis());
+                if let Err(err) = tx.rollback().await {
+                    log::error!("Could not roll back transaction {}: {}", id, err);
+                }
+            }
+        }
+    }
+}
+
+/// Bind the JSON parameters to a query, in order.
+///
+/// Arrays and objects are bound as their JSON text.
+fn bind<'q>(
+    query: sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>,
+    params: &'q [serde_json::Value],
+) -> sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>> {
+    params.iter().fold(query, |query, param| match param {
+        serde_json::Value::Null => query.bind(None::<String>),
+        serde_json::Value::Bool(val) => query.bind(*val),
+        serde_json::Value::Number(val) => match val.as_i64() {
+            Some(val) => query.bind(val),
+            None => query.bind(val.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(val) => query.bind(val.as_str()),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => query.bind(param.to_string()),
+    })
+}
+
+/// Convert a row to a JSON object with the column names as keys.
+///
+/// Blobs are encoded in base64.
+fn row_to_json(row: &sqlx::any::AnyRow) -> anyhow::Result<serde_json::Value> {
+    let mut ret = serde_json::Map::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let value = row.try_get_raw(i)?;
+        let value = if value.is_null() {
+            serde_json::Value::Null
+        } else {
+            match value.type_info().kind() {
+                sqlx::any::AnyTypeInfoKind::Null => serde_json::Value::Null,
+                sqlx::any::AnyTypeInfoKind::Bool => serde_json::json!(row.try_get::<bool, _>(i)?),
+                sqlx::any::AnyTypeInfoKind::SmallInt => serde_json::json!(row.try_get::<i16, _>(i)?),
+                sqlx::any::AnyTypeInfoKind::Integer => serde_json::json!(row.try_get::<i32, _>(i)?),
+                sqlx::any::AnyTypeInfoKind::BigInt => serde_json::json!(row.try_get::<i64, _>(i)?),
+                sqlx::any::AnyTypeInfoKind::Real => serde_json::json!(row.try_get::<f32, _>(i)?),
+                sqlx::any::AnyTypeInfoKind::Double => serde_json::json!(row.try_get::<f64, _>(i)?),
+                sqlx::any::AnyTypeInfoKind::Text => serde_json::json!(row.try_get::<String, _>(i)?),
+                sqlx::any::AnyTypeInfoKind::Blob => {
+                    serde_json::json!(base64::engine::general_purpose::STANDARD.encode(row.try_get::<Vec<u8>, _>(i)?))
+                }
+            }
+        };
+        ret.insert(column.name().to_string(), value);
+    }
+    Ok(serde_json::Value::Object(ret))
+}
+
 impl SqlxResource {
+    #[allow(clippy::too_many_arguments)]
     async fn new(
+        self_function_id: edgeless_api::function_instance::ComponentId,
         dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
         telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        inner: std::sync::Arc<tokio::sync::Mutex<SqlxResourceProviderInner>>,
         sqlx_url: &str,
+        migrations: Option<&str>,
+        transaction_timeout: std::time::Duration,
+        legacy: bool,
         workflow_id: &str,
     ) -> anyhow::Result<Self> {
         let mut dataplane_handle = dataplane_handle;
         let mut telemetry_handle = telemetry_handle;
+        let mut session = SqlxSession::new(sqlx_url, migrations, transaction_timeout).await?;
         let sqlx_url = sqlx_url.to_string();
 
         let workflow_id = workflow_id.to_string();
         let handle = tokio::spawn(async move {
+            // Check periodically for idle transactions, so that they are
+            // rolled back even if no more requests are received.
+            let mut expiry_interval = tokio::time::interval(transaction_timeout.min(std::time::Duration::from_secs(1)));
             loop {
-                let workflow_id = workflow_id.clone();
-
+                let event = tokio::select! {
+                    event = dataplane_handle.receive_next() => event,
+                    _ = expiry_interval.tick() => {
+                        session.expire_transactions().await;
+                        continue;
+                    }
+                };
                 let edgeless_dataplane::core::DataplaneEvent {
                     source_id,
                     channel_id,
                     message,
                     created,
                     metadata,
-                } = dataplane_handle.receive_next().await;
+                } = event;
                 let started = crate::resources::observe_transfer(created, &mut telemetry_handle);
 
                 let mut need_reply = false;
@@ -88,97 +360,114 @@ impl SqlxResource {
                     }
                 };
 
-                if !Sqlite::database_exists(&sqlx_url).await.unwrap_or(false) {
-                    println!("Creating database {}", sqlx_url);
-                    match Sqlite::create_database(&sqlx_url).await {
-                        Ok(_) => log::info!("Create sqlx db success"),
-                        Err(error) => panic!("error: {}", error),
-                    }
-                } else {
-                    log::info!("sqlx Database exists");
-                }
-
-                let db = SqlitePool::connect(&sqlx_url).await.unwrap();
-
-                let response = sqlx::query(
-                    "CREATE TABLE IF NOT EXISTS WorkflowState (
-                    id VARCHAR(255) PRIMARY KEY,
-                    metadata JSONB NOT NULL);",
-                )
-                .execute(&db)
-                .await
-                .unwrap();
-
-                log::info!("create table in sql {:?}", response);
-
-                if message_data.to_string().contains("SELECT") {
-                    let result: sqlx::Result<WorkflowState, sqlx::Error> =
-                        sqlx::query_as(message_data.as_str()).bind(workflow_id).fetch_one(&db).await;
-
-                    match result {
-                        Ok(response) => {
-                            log::info!("Response from database: {response:?}");
-                            if need_reply {
-                                dataplane_handle
-                                    .reply(
-                                        source_id,
-                                        channel_id,
-                                        edgeless_dataplane::core::CallRet::Reply(serde_json::to_string(&response).unwrap_or_default()),
-                                        &metadata,
-                                    )
-                                    .await;
+                let reply = match serde_json::from_str::<SqlxRequest>(&message_data) {
+                    Ok(request) => {
+                        let target = match inner.lock().await.instances.get(&self_function_id) {
+                            Some(instance) => instance.target,
+                            None => None,
+                        };
+                        let res = match session
+                            .handle(
+                                request,
+                                target.map(|target| (&mut dataplane_handle, telemetry_handle.as_mut(), target, &metadata)),
+                            )
+                            .await
+                        {
+                            Ok(res) => res,
+                            Err(err) => {
+                                log::info!("Error from database: {}", err);
+                                serde_json::json!({ "error": err.to_string() })
                             }
-                        }
-                        Err(e) => {
-                            log::info!("Response from database: {:?}", e.to_string());
-                            dataplane_handle
-                                .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Reply(e.to_string()), &metadata)
-                                .await;
-                        }
+                        };
+                        edgeless_dataplane::core::CallRet::Reply(res.to_string())
                     }
-                } else if message_data.to_string().contains("INSERT")
-                    || message_data.to_string().contains("UPDATE")
-                    || message_data.to_string().contains("DELETE")
-                {
-                    let result = sqlx::query(message_data.as_str()).bind(workflow_id).execute(&db).await;
-                    match result {
-                        Ok(response) => {
-                            log::info!("Response from database: {:?}", response);
-                            if need_reply {
-                                let res = format!(
-                                    "rows_affected: {}, last_insert_rowid: {}.",
-                                    response.rows_affected(),
-                                    response.last_insert_rowid()
-                                );
-                                dataplane_handle
-                                    .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Reply(res), &metadata)
-                                    .await;
-                            }
-                        }
-
-                        Err(e) => {
-                            log::info!("Error from state management: {:?}", e);
-                            dataplane_handle
-                                .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Reply(e.to_string()), &metadata)
-                                .await;
-                        }
+                    Err(_) if legacy => legacy_request(&sqlx_url, &workflow_id, &message_data).await,
+                    Err(err) => {
+                        log::info!("Invalid request to the sqlx resource: {}", err);
+                        edgeless_dataplane::core::CallRet::Reply(serde_json::json!({ "error": format!("invalid request: {}", err) }).to_string())
                     }
-                } else {
-                    log::info!("Unknow operation in state management");
-                    dataplane_handle
-                        .reply(source_id, channel_id, edgeless_dataplane::core::CallRet::Err, &metadata)
-                        .await;
                 };
 
-                db.close().await;
+                if need_reply {
+                    dataplane_handle.reply(source_id, channel_id, reply, &metadata).await;
+                }
                 crate::resources::observe_execution(started, &mut telemetry_handle, need_reply);
             }
         });
 
-        Ok(Self { join_handle: handle })
+        Ok(Self {
+            join_handle: handle,
+            target: None,
+        })
     }
 }
 
+/// Handle a raw SQL statement on the WorkflowState table of an SQLite
+/// database, with the workflow identifier bound to `$1`.
+///
+/// Kept for backward compatibility with the resource version 1.x, only if
+/// enabled via the `legacy` configuration.
+async fn legacy_request(sqlx_url: &str, workflow_id: &str, message_data: &str) -> edgeless_dataplane::core::CallRet {
+    if !Sqlite::database_exists(sqlx_url).await.unwrap_or(false) {
+        log::info!("Creating database {}", sqlx_url);
+        if let Err(err) = Sqlite::create_database(sqlx_url).await {
+            return edgeless_dataplane::core::CallRet::Reply(err.to_string());
+        }
+    }
+
+    let db = match SqlitePool::connect(sqlx_url).await {
+        Ok(db) => db,
+        Err(err) => return edgeless_dataplane::core::CallRet::Reply(err.to_string()),
+    };
+
+    let response = sqlx::query(
+        "CREATE TABLE IF NOT EXISTS WorkflowState (
+        id VARCHAR(255) PRIMARY KEY,
+        metadata JSONB NOT NULL);",
+    )
+    .execute(&db)
+    .await;
+
+    log::info!("create table in sql {:?}", response);
+
+    let reply = if message_data.contains("SELECT") {
+        let result: sqlx::Result<WorkflowState, sqlx::Error> = sqlx::query_as(message_data).bind(workflow_id).fetch_one(&db).await;
+
+        match result {
+            Ok(response) => {
+                log::info!("Response from database: {response:?}");
+                edgeless_dataplane::core::CallRet::Reply(serde_json::to_string(&response).unwrap_or_default())
+            }
+            Err(e) => {
+                log::info!("Response from database: {:?}", e.to_string());
+                edgeless_dataplane::core::CallRet::Reply(e.to_string())
+            }
+        }
+    } else if message_data.contains("INSERT") || message_data.contains("UPDATE") || message_data.contains("DELETE") {
+        let result = sqlx::query(message_data).bind(workflow_id).execute(&db).await;
+        match result {
+            Ok(response) => {
+                log::info!("Response from database: {:?}", response);
+                edgeless_dataplane::core::CallRet::Reply(format!(
+                    "rows_affected: {}, last_insert_rowid: {}.",
+                    response.rows_affected(),
+                    response.last_insert_rowid()
+                ))
+            }
+            Err(e) => {
+                log::info!("Error from state management: {:?}", e);
+                edgeless_dataplane::core::CallRet::Reply(e.to_string())
+            }
+        }
+    } else {
+        log::info!("Unknow operation in state management");
+        edgeless_dataplane::core::CallRet::Err
+    };
+
+    db.close().await;
+    reply
+}
+
 impl SqlxResourceProvider {
     pub async fn new(
         dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
@@ -190,7 +479,7 @@ impl SqlxResourceProvider {
                 resource_provider_id,
                 dataplane_provider,
                 telemetry_handle,
-                instances: std::collections::HashMap::<edgeless_api::function_instance::InstanceId, SqlxResource>::new(),
+                instances: std::collections::HashMap::<edgeless_api::function_instance::ComponentId, SqlxResource>::new(),
             })),
         }
     }
@@ -203,6 +492,34 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
     ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
         if let (Some(url), workflow_id) = (instance_specification.configuration.get("url"), instance_specification.workflow_id) {
+            let migrations = instance_specification.configuration.get("migrations");
+            let transaction_timeout_ms = match instance_specification.configuration.get("transaction-timeout-ms") {
+                Some(value) => match value.parse::<u64>() {
+                    Ok(value) if value > 0 => value,
+                    _ => {
+                        return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                            edgeless_api::common::ResponseError {
+                                summary: "Invalid resource configuration".to_string(),
+                                detail: Some(format!("Invalid transaction-timeout-ms value: {}", value)),
+                            },
+                        ));
+                    }
+                },
+                None => 30000,
+            };
+            let legacy = match instance_specification.configuration.get("legacy").map(|x| x.as_str()) {
+                Some("true") => true,
+                Some("false") | None => false,
+                Some(value) => {
+                    return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                        edgeless_api::common::ResponseError {
+                            summary: "Invalid resource configuration".to_string(),
+                            detail: Some(format!("Invalid legacy value: {}", value)),
+                        },
+                    ));
+                }
+            };
+
             let mut lck = self.inner.lock().await;
             let new_id = edgeless_api::function_instance::InstanceId::new(lck.resource_provider_id.node_id);
             let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
@@ -211,9 +528,21 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
                 new_id.function_id.to_string(),
             )]));
 
-            match SqlxResource::new(dataplane_handle, telemetry_handle, url, &workflow_id).await {
+            match SqlxResource::new(
+                new_id.function_id,
+                dataplane_handle,
+                telemetry_handle,
+                self.inner.clone(),
+                url,
+                migrations.map(|x| x.as_str()),
+                std::time::Duration::from_millis(transaction_timeout_ms),
+                legacy,
+                &workflow_id,
+            )
+            .await
+            {
                 Ok(resource) => {
-                    lck.instances.insert(new_id, resource);
+                    lck.instances.insert(new_id.function_id, resource);
                     return Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id));
                 }
                 Err(err) => {
@@ -236,12 +565,18 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
     }
 
     async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
-        self.inner.lock().await.instances.remove(&resource_id);
+        self.inner.lock().await.instances.remove(&resource_id.function_id);
         Ok(())
     }
 
-    async fn patch(&mut self, _update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
-        // the resource has no channels: nothing to be patched
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        let mut lck = self.inner.lock().await;
+        if let Some(instance) = lck.instances.get_mut(&update.function_id) {
+            instance.target = update.output_mapping.get("out").cloned();
+        } else {
+            anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+        }
+
         Ok(())
     }
 }
@@ -257,3 +592,248 @@ impl std::fmt::Display for WorkflowState {
         write!(f, "id: {}, metadata: {}", self.id, self.metadata)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::NoTelemetryHandle;
+    use crate::test_utils::configuration;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    /// Call the resource with a request, returning the JSON reply.
+    async fn call(
+        caller: &mut edgeless_dataplane::handle::DataplaneHandle,
+        resource_id: edgeless_api::function_instance::InstanceId,
+        request: SqlxRequest,
+    ) -> serde_json::Value {
+        match caller
+            .call(
+                resource_id,
+                serde_json::to_string(&request).unwrap(),
+                &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+            )
+            .await
+        {
+            edgeless_dataplane::core::CallRet::Reply(reply) => serde_json::from_str(&reply).unwrap(),
+            ret => panic!("unexpected reply: {:?}", ret),
+        }
+    }
+
+    #[test]
+    fn test_sqlx_request_deserialize() {
+        assert_eq!(SqlxRequest::Begin, serde_json::from_str(r#"{"op":"begin"}"#).unwrap());
+        assert_eq!(
+            SqlxRequest::Execute {
+                sql: "INSERT INTO t VALUES ($1, $2)".to_string(),
+                params: vec![serde_json::json!(1), serde_json::json!("a")],
+                transaction: Some("tx".to_string()),
+            },
+            serde_json::from_str(r#"{"op":"execute","sql":"INSERT INTO t VALUES ($1, $2)","params":[1,"a"],"transaction":"tx"}"#).unwrap()
+        );
+        assert_eq!(
+            SqlxRequest::Query {
+                sql: "SELECT * FROM t".to_string(),
+                params: vec![],
+                transaction: None,
+                stream: false,
+            },
+            serde_json::from_str(r#"{"op":"query","sql":"SELECT * FROM t"}"#).unwrap()
+        );
+        assert_eq!(
+            SqlxRequest::Commit {
+                transaction: "tx".to_string()
+            },
+            serde_json::from_str(r#"{"op":"commit","transaction":"tx"}"#).unwrap()
+        );
+
+        // Raw SQL statements are rejected, unless in legacy mode.
+        assert!(serde_json::from_str::<SqlxRequest>("SELECT id, metadata FROM WorkflowState WHERE id=$1").is_err());
+        assert!(serde_json::from_str::<SqlxRequest>(r#"{"op":"truncate"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_session() {
+        let dir = std::env::temp_dir().join(format!("edgeless-sqlx-{}", uuid::Uuid::new_v4()));
+        let migrations = dir.join("migrations");
+        std::fs::create_dir_all(&migrations).unwrap();
+        std::fs::write(
+            migrations.join("1_init.sql"),
+            "CREATE TABLE readings (id INTEGER PRIMARY KEY, sensor TEXT NOT NULL, value DOUBLE, raw BLOB);",
+        )
+        .unwrap();
+        let url = format!("sqlite://{}", dir.join("test.db").display());
+
+        let mut session = SqlxSession::new(&url, migrations.to_str(), std::time::Duration::from_secs(30))
+            .await
+            .unwrap();
+
+        let insert = |sensor: &str, value: f64, transaction: Option<String>| SqlxRequest::Execute {
+            sql: "INSERT INTO readings (sensor, value, raw) VALUES ($1, $2, $3)".to_string(),
+            params: vec![serde_json::json!(sensor), serde_json::json!(value), serde_json::Value::Null],
+            transaction,
+        };
+        let count = SqlxRequest::Query {
+            sql: "SELECT COUNT(*) AS n FROM readings".to_string(),
+            params: vec![],
+            transaction: None,
+            stream: false,
+        };
+
+        // Statement outside a transaction.
+        let res = session.handle(insert("a", 1.5, None), None).await.unwrap();
+        assert_eq!(1, res["rows_affected"]);
+        assert_eq!(1, res["last_insert_id"]);
+
+        // Committed transaction.
+        let tx = session.handle(SqlxRequest::Begin, None).await.unwrap()["transaction"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        session.handle(insert("b", 2.5, Some(tx.clone())), None).await.unwrap();
+        session.handle(insert("c", 3.5, Some(tx.clone())), None).await.unwrap();
+        session.handle(SqlxRequest::Commit { transaction: tx.clone() }, None).await.unwrap();
+        assert_eq!(serde_json::json!([{"n": 3}]), session.handle(count.clone(), None).await.unwrap()["rows"]);
+
+        // The transaction cannot be used anymore.
+        assert!(session.handle(insert("d", 4.5, Some(tx)), None).await.is_err());
+
+        // Rolled back transaction.
+        let tx = session.handle(SqlxRequest::Begin, None).await.unwrap()["transaction"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        session.handle(insert("e", 5.5, Some(tx.clone())), None).await.unwrap();
+        session.handle(SqlxRequest::Rollback { transaction: tx }, None).await.unwrap();
+        assert_eq!(serde_json::json!([{"n": 3}]), session.handle(count, None).await.unwrap()["rows"]);
+
+        // Query with bound parameters.
+        let res = session
+            .handle(
+                SqlxRequest::Query {
+                    sql: "SELECT sensor, value, raw FROM readings WHERE value > $1 ORDER BY id".to_string(),
+                    params: vec![serde_json::json!(2)],
+                    transaction: None,
+                    stream: false,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::json!([{"sensor": "b", "value": 2.5, "raw": null}, {"sensor": "c", "value": 3.5, "raw": null}]),
+            res["rows"]
+        );
+
+        // Streaming requires the output channel to be mapped.
+        assert!(
+            session
+                .handle(
+                    SqlxRequest::Query {
+                        sql: "SELECT * FROM readings".to_string(),
+                        params: vec![],
+                        transaction: None,
+                        stream: true,
+                    },
+                    None,
+                )
+                .await
+                .is_err()
+        );
+
+        // Unknown transaction.
+        assert!(session.handle(insert("f", 6.5, Some("unknown".to_string())), None).await.is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_session_transaction_timeout() {
+        let dir = std::env::temp_dir().join(format!("edgeless-sqlx-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = format!("sqlite://{}", dir.join("test.db").display());
+
+        let mut session = SqlxSession::new(&url, None, std::time::Duration::from_millis(100)).await.unwrap();
+        let tx = session.handle(SqlxRequest::Begin, None).await.unwrap()["transaction"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(session.handle(SqlxRequest::Commit { transaction: tx }, None).await.is_err());
+        assert!(session.transactions.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_resource_transaction_timeout() {
+        let dir = std::env::temp_dir().join(format!("edgeless-sqlx-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = format!("sqlite://{}", dir.join("test.db").display());
+        let mut session = SqlxSession::new(&url, None, std::time::Duration::from_secs(30)).await.unwrap();
+        let execute = |sql: &str| SqlxRequest::Execute {
+            sql: sql.to_string(),
+            params: vec![],
+            transaction: None,
+        };
+        session.handle(execute("CREATE TABLE readings (value INTEGER)"), None).await.unwrap();
+
+        let node_id = uuid::Uuid::new_v4();
+        let mut provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, String::from("http://127.0.0.1:7113"), None).await;
+        let mut sqlx_provider = SqlxResourceProvider::new(
+            provider.clone(),
+            Box::new(NoTelemetryHandle {}),
+            edgeless_api::function_instance::InstanceId::new(node_id),
+        )
+        .await;
+        let resource_id = match sqlx_provider
+            .start(edgeless_api::resource_configuration::ResourceInstanceSpecification {
+                class_type: "sqlx".to_string(),
+                configuration: configuration(&[("url", url.as_str()), ("transaction-timeout-ms", "100")]),
+                workflow_id: "workflow_1".to_string(),
+            })
+            .await
+            .unwrap()
+        {
+            edgeless_api::common::StartComponentResponse::InstanceId(resource_id) => resource_id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        };
+        let mut caller = provider.get_handle_for(edgeless_api::function_instance::InstanceId::new(node_id)).await;
+
+        // Write within a transaction, then leave it idle.
+        let tx = call(&mut caller, resource_id, SqlxRequest::Begin).await["transaction"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        call(
+            &mut caller,
+            resource_id,
+            SqlxRequest::Execute {
+                sql: "INSERT INTO readings (value) VALUES (1)".to_string(),
+                params: vec![],
+                transaction: Some(tx),
+            },
+        )
+        .await;
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+        // The transaction has been rolled back without further requests to
+        // the resource: its row is gone and the database is not locked.
+        session.handle(execute("INSERT INTO readings (value) VALUES (2)"), None).await.unwrap();
+        let rows = session
+            .handle(
+                SqlxRequest::Query {
+                    sql: "SELECT value FROM readings".to_string(),
+                    params: vec![],
+                    transaction: None,
+                    stream: false,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(serde_json::json!({"rows": [{"value": 2}]}), rows);
+
+        sqlx_provider.stop(resource_id).await.unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
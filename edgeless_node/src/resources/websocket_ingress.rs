@@ -0,0 +1,959 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use base64::Engine;
+use edgeless_api::function_instance::ComponentId;
+use futures::SinkExt;
+use futures::StreamExt;
+use http_body_util::BodyExt;
+use std::str::FromStr;
+
+/// Default maximum size of a message received from a client, in bytes.
+const DEFAULT_MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+/// Default maximum number of clients connected to a resource instance.
+const DEFAULT_MAX_CONNECTIONS: u64 = 1024;
+
+pub struct WebSocketIngressResourceSpec {}
+
+impl super::resource_provider_specs::ResourceProviderSpecs for WebSocketIngressResourceSpec {
+    fn class_type(&self) -> String {
+        String::from("websocket-ingress")
+    }
+
+    fn description(&self) -> String {
+        r"Exchange messages with external clients connected via WebSocket, or Server-Sent Events as a fallback".to_string()
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        vec![String::from("new_message")]
+    }
+
+    fn configurations(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            (
+                String::from("path"),
+                String::from("Path of the URL to which the clients connect. Default: /"),
+            ),
+            (
+                String::from("sse"),
+                String::from("Also accept clients using Server-Sent Events, which send messages via POST. Default: true"),
+            ),
+            (
+                String::from("max_body_size"),
+                String::from(
+                    "Maximum size of a message received from a client, in bytes, larger POST requests are rejected with 413 Payload Too Large. Default: 1048576, 0 means unlimited",
+                ),
+            ),
+            (
+                String::from("max_connections"),
+                String::from(
+                    "Maximum number of clients connected at the same time, further ones are rejected with 503 Service Unavailable. Default: 1024, 0 means unlimited",
+                ),
+            ),
+        ])
+    }
+
+    fn version(&self) -> String {
+        String::from("1.1")
+    }
+}
+
+type Body = http_body_util::combinators::UnsyncBoxBody<hyper::body::Bytes, std::convert::Infallible>;
+
+struct WebSocketIngressResource {
+    path: String,
+    sse: bool,
+    /// Maximum size of a message received from a client, if limited.
+    max_body_size: Option<usize>,
+    /// Maximum number of clients connected at the same time, if limited.
+    max_connections: Option<usize>,
+    target: Option<edgeless_api::function_instance::InstanceId>,
+    /// Channels to send messages to the connected clients, by connection identifier.
+    connections: std::collections::HashMap<String, tokio::sync::mpsc::UnboundedSender<edgeless_http::EdgelessWebSocketMessage>>,
+    dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+    /// Telemetry handle of the resource instance, to observe the messages sent.
+    telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for WebSocketIngressResource {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+impl WebSocketIngressResource {
+    /// Return true if no more clients can connect.
+    fn is_full(&self) -> bool {
+        self.max_connections
+            .is_some_and(|max_connections| self.connections.len() >= max_connections)
+    }
+
+    /// Send a message to the client with the given connection identifier,
+    /// or to all the clients if empty.
+    /// Return false if the connection does not exist.
+    fn deliver(&mut self, message: edgeless_http::EdgelessWebSocketMessage) -> bool {
+        if message.connection_id.is_empty() {
+            self.connections.retain(|_, sender| sender.send(message.clone()).is_ok());
+            true
+        } else {
+            match self.connections.get(&message.connection_id) {
+                Some(sender) => sender.send(message).is_ok(),
+                None => false,
+            }
+        }
+    }
+}
+
+struct WebSocketIngressState {
+    node_id: edgeless_api::function_instance::NodeId,
+    dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+    telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+    instances: std::collections::HashMap<ComponentId, WebSocketIngressResource>,
+}
+
+type SharedState = std::sync::Arc<tokio::sync::Mutex<WebSocketIngressState>>;
+
+/// Cast a message to the target of a resource instance.
+/// Return false if the resource instance does not exist or it has no target.
+async fn forward(state: &SharedState, resource_id: &ComponentId, message: edgeless_http::EdgelessWebSocketMessage) -> bool {
+    let (target, mut dataplane_handle, mut span) = {
+        let lck = state.lock().await;
+        match lck.instances.get(resource_id) {
+            Some(instance) => match instance.target {
+                Some(target) => (
+                    target,
+                    instance.dataplane_handle.clone(),
+                    edgeless_telemetry::telemetry_events::TelemetrySpan::start_root(
+                        lck.telemetry_handle.as_ref(),
+                        "websocket-ingress",
+                        edgeless_telemetry::telemetry_events::SpanKind::Consumer,
+                    ),
+                ),
+                None => return false,
+            },
+            None => return false,
+        }
+    };
+    span.set_attribute("edgeless.resource_id", resource_id);
+    let msg = edgeless_http::websocket_message_to_string(&message);
+    let size = msg.len();
+    let delivered = dataplane_handle.send(target, msg, &span.metadata).await;
+    let mut lck = state.lock().await;
+    if let Some(instance) = lck.instances.get_mut(resource_id) {
+        crate::dataplane_metrics::observe_send(instance.telemetry_handle.as_mut(), "new_message", Some(target), size, delivered);
+    }
+    span.end(lck.telemetry_handle.as_mut());
+    true
+}
+
+/// Connection of a client, which is closed when dropped.
+struct Connection {
+    state: SharedState,
+    resource_id: ComponentId,
+    connection_id: String,
+}
+
+impl Connection {
+    /// Register a new connection to a resource instance, notifying its target.
+    /// Return None if the resource instance does not exist or it has reached
+    /// the maximum number of connections.
+    async fn open(
+        state: &SharedState,
+        resource_id: &ComponentId,
+    ) -> Option<(Self, tokio::sync::mpsc::UnboundedReceiver<edgeless_http::EdgelessWebSocketMessage>)> {
+        let connection_id = uuid::Uuid::new_v4().to_string();
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        {
+            let mut lck = state.lock().await;
+            let instance = lck.instances.get_mut(resource_id)?;
+            if instance.is_full() {
+                return None;
+            }
+            instance.connections.insert(connection_id.clone(), sender);
+        }
+        forward(
+            state,
+            resource_id,
+            edgeless_http::EdgelessWebSocketMessage {
+                connection_id: connection_id.clone(),
+                event: edgeless_http::EdgelessWebSocketEvent::Open,
+                ..Default::default()
+            },
+        )
+        .await;
+        Some((
+            Self {
+                state: state.clone(),
+                resource_id: *resource_id,
+                connection_id,
+            },
+            receiver,
+        ))
+    }
+
+    /// Cast a message received from the client to the target.
+    async fn received(&self, data: String, binary: bool) {
+        forward(
+            &self.state,
+            &self.resource_id,
+            edgeless_http::EdgelessWebSocketMessage {
+                connection_id: self.connection_id.clone(),
+                event: edgeless_http::EdgelessWebSocketEvent::Message,
+                data,
+                binary,
+            },
+        )
+        .await;
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let state = self.state.clone();
+        let resource_id = self.resource_id;
+        let connection_id = std::mem::take(&mut self.connection_id);
+        tokio::spawn(async move {
+            let removed = match state.lock().await.instances.get_mut(&resource_id) {
+                Some(instance) => instance.connections.remove(&connection_id).is_some(),
+                None => false,
+            };
+            if removed {
+                forward(
+                    &state,
+                    &resource_id,
+                    edgeless_http::EdgelessWebSocketMessage {
+                        connection_id,
+                        event: edgeless_http::EdgelessWebSocketEvent::Close,
+                        ..Default::default()
+                    },
+                )
+                .await;
+            }
+        });
+    }
+}
+
+/// Exchange messages with a client connected via WebSocket until closed.
+async fn serve_websocket(
+    state: SharedState,
+    resource_id: ComponentId,
+    websocket: tokio_tungstenite::WebSocketStream<hyper_util::rt::TokioIo<hyper::upgrade::Upgraded>>,
+) {
+    use tokio_tungstenite::tungstenite::Message;
+
+    let Some((connection, mut receiver)) = Connection::open(&state, &resource_id).await else {
+        return;
+    };
+    let (mut sink, mut stream) = websocket.split();
+    loop {
+        tokio::select! {
+            frame = stream.next() => match frame {
+                Some(Ok(Message::Text(data))) => connection.received(data, false).await,
+                Some(Ok(Message::Binary(data))) => connection.received(base64::engine::general_purpose::STANDARD.encode(data), true).await,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            message = receiver.recv() => match message {
+                Some(message) if message.event != edgeless_http::EdgelessWebSocketEvent::Close => {
+                    let frame = if message.binary {
+                        match base64::engine::general_purpose::STANDARD.decode(&message.data) {
+                            Ok(data) => Message::Binary(data),
+                            Err(err) => {
+                                log::warn!("invalid binary message for connection {}: {}", connection.connection_id, err);
+                                continue;
+                            }
+                        }
+                    } else {
+                        Message::Text(message.data)
+                    };
+                    if sink.send(frame).await.is_err() {
+                        break;
+                    }
+                }
+                _ => {
+                    let _ = sink.send(Message::Close(None)).await;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Format a Server-Sent Event.
+fn sse_event(event: &str, data: &str) -> String {
+    let mut ret = format!("event: {}\n", event);
+    for line in data.split('\n') {
+        ret.push_str("data: ");
+        ret.push_str(line.trim_end_matches('\r'));
+        ret.push('\n');
+    }
+    ret.push('\n');
+    ret
+}
+
+fn response(status: hyper::StatusCode, body: &str) -> hyper::Response<Body> {
+    let mut res = hyper::Response::new(http_body_util::Full::new(hyper::body::Bytes::from(body.to_string())).boxed_unsync());
+    *res.status_mut() = status;
+    res
+}
+
+fn is_websocket_upgrade(headers: &hyper::HeaderMap) -> bool {
+    let header_contains = |name: hyper::header::HeaderName, value: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|x| x.to_str().ok())
+            .flat_map(|x| x.split(','))
+            .any(|x| x.trim().eq_ignore_ascii_case(value))
+    };
+    header_contains(hyper::header::CONNECTION, "upgrade") && header_contains(hyper::header::UPGRADE, "websocket")
+}
+
+#[derive(Clone)]
+struct WebSocketIngressService {
+    state: SharedState,
+}
+
+impl hyper::service::Service<hyper::Request<hyper::body::Incoming>> for WebSocketIngressService {
+    type Response = hyper::Response<Body>;
+
+    type Error = anyhow::Error;
+
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn call(&self, req: hyper::Request<hyper::body::Incoming>) -> Self::Future {
+        let state = self.state.clone();
+        Box::pin(async move {
+            let mut req = req;
+
+            // Find the resource instance with the path requested.
+            let (resource_id, sse, max_body_size, full) = {
+                let lck = state.lock().await;
+                match lck.instances.iter().find(|(_, instance)| instance.path == req.uri().path()) {
+                    Some((resource_id, instance)) => (*resource_id, instance.sse, instance.max_body_size, instance.is_full()),
+                    None => return Ok(response(hyper::StatusCode::NOT_FOUND, "Not Found")),
+                }
+            };
+
+            // Accept the WebSocket handshake, then serve the connection in
+            // a separate task once upgraded.
+            if is_websocket_upgrade(req.headers()) {
+                if full {
+                    return Ok(response(hyper::StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable"));
+                }
+                let accept = match req.headers().get(hyper::header::SEC_WEBSOCKET_KEY) {
+                    Some(key) => tokio_tungstenite::tungstenite::handshake::derive_accept_key(key.as_bytes()),
+                    None => return Ok(response(hyper::StatusCode::BAD_REQUEST, "Bad Request")),
+                };
+                let upgrade = hyper::upgrade::on(&mut req);
+                tokio::spawn(async move {
+                    match upgrade.await {
+                        Ok(upgraded) => {
+                            let config = max_body_size.map(|max_body_size| tokio_tungstenite::tungstenite::protocol::WebSocketConfig {
+                                max_message_size: Some(max_body_size),
+                                max_frame_size: Some(max_body_size),
+                                ..Default::default()
+                            });
+                            let websocket = tokio_tungstenite::WebSocketStream::from_raw_socket(
+                                hyper_util::rt::TokioIo::new(upgraded),
+                                tokio_tungstenite::tungstenite::protocol::Role::Server,
+                                config,
+                            )
+                            .await;
+                            serve_websocket(state, resource_id, websocket).await;
+                        }
+                        Err(err) => log::warn!("WebSocket upgrade failed: {}", err),
+                    }
+                });
+                let mut res = response(hyper::StatusCode::SWITCHING_PROTOCOLS, "");
+                let headers = res.headers_mut();
+                headers.insert(hyper::header::CONNECTION, hyper::header::HeaderValue::from_static("upgrade"));
+                headers.insert(hyper::header::UPGRADE, hyper::header::HeaderValue::from_static("websocket"));
+                headers.insert(hyper::header::SEC_WEBSOCKET_ACCEPT, hyper::header::HeaderValue::from_str(&accept)?);
+                return Ok(res);
+            }
+
+            if !sse {
+                return Ok(response(hyper::StatusCode::BAD_REQUEST, "Bad Request"));
+            }
+
+            // Open a stream of Server-Sent Events, the first one of which
+            // contains the connection identifier.
+            let accepts_sse = req
+                .headers()
+                .get(hyper::header::ACCEPT)
+                .and_then(|x| x.to_str().ok())
+                .is_some_and(|x| x.contains("text/event-stream"));
+            if req.method() == hyper::Method::GET && accepts_sse {
+                if full {
+                    return Ok(response(hyper::StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable"));
+                }
+                let Some((connection, receiver)) = Connection::open(&state, &resource_id).await else {
+                    return Ok(response(hyper::StatusCode::NOT_FOUND, "Not Found"));
+                };
+                let open = sse_event("open", &connection.connection_id);
+                let events = futures::stream::unfold((connection, receiver), |(connection, mut receiver)| async move {
+                    match receiver.recv().await {
+                        Some(message) if message.event != edgeless_http::EdgelessWebSocketEvent::Close => {
+                            let event = sse_event(if message.binary { "binary" } else { "message" }, &message.data);
+                            Some((event, (connection, receiver)))
+                        }
+                        _ => None,
+                    }
+                });
+                let body = futures::stream::once(async move { open })
+                    .chain(events)
+                    .map(|x| Ok::<_, std::convert::Infallible>(hyper::body::Frame::data(hyper::body::Bytes::from(x))));
+                let mut res = hyper::Response::new(http_body_util::StreamBody::new(body).boxed_unsync());
+                let headers = res.headers_mut();
+                headers.insert(hyper::header::CONTENT_TYPE, hyper::header::HeaderValue::from_static("text/event-stream"));
+                headers.insert(hyper::header::CACHE_CONTROL, hyper::header::HeaderValue::from_static("no-cache"));
+                return Ok(res);
+            }
+
+            // Messages from clients using Server-Sent Events are sent via
+            // POST, with the connection identifier in the query.
+            if req.method() == hyper::Method::POST {
+                let connection_id = req
+                    .uri()
+                    .query()
+                    .unwrap_or_default()
+                    .split('&')
+                    .find_map(|x| x.strip_prefix("connection_id="))
+                    .unwrap_or_default()
+                    .to_string();
+                let connected = match state.lock().await.instances.get(&resource_id) {
+                    Some(instance) => instance.connections.contains_key(&connection_id),
+                    None => false,
+                };
+                if !connected {
+                    return Ok(response(hyper::StatusCode::NOT_FOUND, "Not Found"));
+                }
+                let body = req.into_body();
+                let data = match max_body_size {
+                    Some(max_body_size) => match http_body_util::Limited::new(body, max_body_size).collect().await {
+                        Ok(collected) => collected.to_bytes(),
+                        Err(err) if err.downcast_ref::<http_body_util::LengthLimitError>().is_some() => {
+                            return Ok(response(hyper::StatusCode::PAYLOAD_TOO_LARGE, "Payload Too Large"));
+                        }
+                        Err(err) => return Err(anyhow::anyhow!(err)),
+                    },
+                    None => body.collect().await?.to_bytes(),
+                }
+                .to_vec();
+                let (data, binary) = match String::from_utf8(data) {
+                    Ok(data) => (data, false),
+                    Err(err) => (base64::engine::general_purpose::STANDARD.encode(err.into_bytes()), true),
+                };
+                forward(
+                    &state,
+                    &resource_id,
+                    edgeless_http::EdgelessWebSocketMessage {
+                        connection_id,
+                        event: edgeless_http::EdgelessWebSocketEvent::Message,
+                        data,
+                        binary,
+                    },
+                )
+                .await;
+                return Ok(response(hyper::StatusCode::OK, "OK"));
+            }
+
+            Ok(response(hyper::StatusCode::BAD_REQUEST, "Bad Request"))
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct WebSocketIngressResourceProvider {
+    state: SharedState,
+}
+
+impl WebSocketIngressResourceProvider {
+    pub async fn new(
+        dataplane_provider: edgeless_dataplane::handle::DataplaneProvider,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        node_id: edgeless_api::function_instance::NodeId,
+        url: &str,
+    ) -> anyhow::Result<Self> {
+        let (_, host, port) = edgeless_api::util::parse_http_host(url)?;
+        let addr = std::net::SocketAddr::from((std::net::IpAddr::from_str(&host)?, port));
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+
+        let state = std::sync::Arc::new(tokio::sync::Mutex::new(WebSocketIngressState {
+            node_id,
+            dataplane_provider,
+            telemetry_handle,
+            instances: std::collections::HashMap::new(),
+        }));
+
+        let cloned_state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = match listener.accept().await {
+                    Ok(val) => val,
+                    Err(_) => {
+                        log::error!("Accept Error");
+                        continue;
+                    }
+                };
+                let service = WebSocketIngressService { state: cloned_state.clone() };
+                tokio::task::spawn(async move {
+                    if let Err(err) = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                        .with_upgrades()
+                        .await
+                    {
+                        log::warn!("Error serving connection: {:?}", err);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { state })
+    }
+
+    /// Deliver the messages received by a resource instance to its clients.
+    fn receive_task(
+        resource_id: ComponentId,
+        dataplane_handle: edgeless_dataplane::handle::DataplaneHandle,
+        telemetry_handle: Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI>,
+        state: SharedState,
+    ) -> tokio::task::JoinHandle<()> {
+        let mut dataplane_handle = dataplane_handle;
+        let mut telemetry_handle = telemetry_handle;
+        tokio::spawn(async move {
+            loop {
+                let edgeless_dataplane::core::DataplaneEvent {
+                    source_id,
+                    channel_id,
+                    message,
+                    created,
+                    metadata,
+                } = dataplane_handle.receive_next().await;
+                let started = crate::resources::observe_transfer(created, &mut telemetry_handle);
+                let (data, need_reply) = match message {
+                    edgeless_dataplane::core::Message::Cast(data) => (data, false),
+                    edgeless_dataplane::core::Message::Call(data) => (data, true),
+                    _ => continue,
+                };
+
+                let delivered = match edgeless_http::websocket_message_from_string(&data) {
+                    Ok(message) => match state.lock().await.instances.get_mut(&resource_id) {
+                        Some(instance) => instance.deliver(message),
+                        None => false,
+                    },
+                    Err(err) => {
+                        log::warn!("Invalid message received by websocket-ingress: {}", err);
+                        false
+                    }
+                };
+                if need_reply {
+                    dataplane_handle
+                        .reply(
+                            source_id,
+                            channel_id,
+                            if delivered {
+                                edgeless_dataplane::core::CallRet::NoReply
+                            } else {
+                                edgeless_dataplane::core::CallRet::Err
+                            },
+                            &metadata,
+                        )
+                        .await;
+                }
+                crate::resources::observe_execution(started, &mut telemetry_handle, need_reply);
+            }
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::InstanceId>
+    for WebSocketIngressResourceProvider
+{
+    async fn start(
+        &mut self,
+        instance_specification: edgeless_api::resource_configuration::ResourceInstanceSpecification,
+    ) -> anyhow::Result<edgeless_api::common::StartComponentResponse<edgeless_api::function_instance::InstanceId>> {
+        let path = instance_specification.configuration.get("path").cloned().unwrap_or(String::from("/"));
+        let sse = !instance_specification
+            .configuration
+            .get("sse")
+            .is_some_and(|x| x.eq_ignore_ascii_case("false"));
+        let mut limits = [DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_CONNECTIONS];
+        for (limit, name) in limits.iter_mut().zip(["max_body_size", "max_connections"]) {
+            if let Some(value) = instance_specification.configuration.get(name) {
+                match value.parse::<u64>() {
+                    Ok(value) => *limit = value,
+                    Err(err) => {
+                        return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                            edgeless_api::common::ResponseError {
+                                summary: "Error when creating a resource".to_string(),
+                                detail: Some(format!("Invalid {name} '{value}' specified in websocket-ingress: {err}")),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        let [max_body_size, max_connections] = limits;
+
+        let mut lck = self.state.lock().await;
+        if !path.starts_with('/') || lck.instances.values().any(|instance| instance.path == path) {
+            return Ok(edgeless_api::common::StartComponentResponse::ResponseError(
+                edgeless_api::common::ResponseError {
+                    summary: "Error when creating a resource".to_string(),
+                    detail: Some(format!("Invalid or already used path '{path}' specified in websocket-ingress")),
+                },
+            ));
+        }
+
+        let new_id = edgeless_api::function_instance::InstanceId::new(lck.node_id);
+        let dataplane_handle = lck.dataplane_provider.get_handle_for(new_id).await;
+        let mut telemetry_handle = lck.telemetry_handle.fork(std::collections::BTreeMap::from([(
+            "FUNCTION_ID".to_string(),
+            new_id.function_id.to_string(),
+        )]));
+        let join_handle = Self::receive_task(
+            new_id.function_id,
+            dataplane_handle.clone(),
+            telemetry_handle.fork(std::collections::BTreeMap::new()),
+            self.state.clone(),
+        );
+
+        log::info!("created a new websocket-ingress resource: path {}, sse {}", path, sse);
+        lck.instances.insert(
+            new_id.function_id,
+            WebSocketIngressResource {
+                path,
+                sse,
+                max_body_size: if max_body_size > 0 { Some(max_body_size as usize) } else { None },
+                max_connections: if max_connections > 0 { Some(max_connections as usize) } else { None },
+                target: None, // will be set by patch()
+                connections: std::collections::HashMap::new(),
+                dataplane_handle,
+                telemetry_handle,
+                join_handle,
+            },
+        );
+
+        Ok(edgeless_api::common::StartComponentResponse::InstanceId(new_id))
+    }
+
+    async fn stop(&mut self, resource_id: edgeless_api::function_instance::InstanceId) -> anyhow::Result<()> {
+        self.state.lock().await.instances.remove(&resource_id.function_id);
+        Ok(())
+    }
+
+    async fn patch(&mut self, update: edgeless_api::common::PatchRequest) -> anyhow::Result<()> {
+        let mut lck = self.state.lock().await;
+        if let Some(instance) = lck.instances.get_mut(&update.function_id) {
+            instance.target = update.output_mapping.get("new_message").cloned();
+        } else {
+            anyhow::bail!("Patching a non-existing resource: {}", update.function_id);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::NoTelemetryHandle;
+    use crate::test_utils::configuration;
+    use edgeless_api::resource_configuration::ResourceConfigurationAPI;
+
+    /// Receive the next message cast by the resource to its target.
+    async fn receive(target: &mut edgeless_dataplane::handle::DataplaneHandle) -> edgeless_http::EdgelessWebSocketMessage {
+        let event = tokio::time::timeout(tokio::time::Duration::from_secs(5), target.receive_next())
+            .await
+            .unwrap();
+        match event.message {
+            edgeless_dataplane::core::Message::Cast(data) => edgeless_http::websocket_message_from_string(&data).unwrap(),
+            message => panic!("unexpected message: {:?}", message),
+        }
+    }
+
+    /// Cast a message from the target to the resource.
+    async fn send(
+        target: &mut edgeless_dataplane::handle::DataplaneHandle,
+        resource_id: edgeless_api::function_instance::InstanceId,
+        connection_id: &str,
+        data: &str,
+    ) {
+        let message = edgeless_http::EdgelessWebSocketMessage {
+            connection_id: connection_id.to_string(),
+            event: edgeless_http::EdgelessWebSocketEvent::Message,
+            data: data.to_string(),
+            binary: false,
+        };
+        assert!(
+            target
+                .send(
+                    resource_id,
+                    edgeless_http::websocket_message_to_string(&message),
+                    &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+                )
+                .await
+        );
+    }
+
+    /// Read the next Server-Sent Event of a stream, returning its name and data.
+    async fn next_sse(res: &mut reqwest::Response, buffer: &mut String) -> (String, String) {
+        loop {
+            if let Some(pos) = buffer.find("\n\n") {
+                let event: String = buffer.drain(..pos + 2).collect();
+                let mut name = String::new();
+                let mut data = vec![];
+                for line in event.lines() {
+                    if let Some(value) = line.strip_prefix("event: ") {
+                        name = value.to_string();
+                    } else if let Some(value) = line.strip_prefix("data: ") {
+                        data.push(value);
+                    }
+                }
+                return (name, data.join("\n"));
+            }
+            let chunk = tokio::time::timeout(tokio::time::Duration::from_secs(5), res.chunk())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    }
+
+    #[test]
+    fn test_websocket_ingress_sse_event() {
+        assert_eq!("event: open\ndata: \n\n", sse_event("open", ""));
+        assert_eq!("event: message\ndata: hello\n\n", sse_event("message", "hello"));
+        assert_eq!("event: message\ndata: a\ndata: b\ndata: \n\n", sse_event("message", "a\r\nb\n"));
+    }
+
+    #[test]
+    fn test_websocket_ingress_upgrade() {
+        let headers = |entries: &[(&'static str, &'static str)]| -> hyper::HeaderMap {
+            entries
+                .iter()
+                .map(|(k, v)| (hyper::header::HeaderName::from_static(k), hyper::header::HeaderValue::from_static(v)))
+                .collect()
+        };
+        assert!(is_websocket_upgrade(&headers(&[("connection", "Upgrade"), ("upgrade", "websocket")])));
+        assert!(is_websocket_upgrade(&headers(&[
+            ("connection", "keep-alive, Upgrade"),
+            ("upgrade", "WebSocket")
+        ])));
+        assert!(!is_websocket_upgrade(&headers(&[("connection", "keep-alive"), ("upgrade", "websocket")])));
+        assert!(!is_websocket_upgrade(&headers(&[("connection", "upgrade"), ("upgrade", "h2c")])));
+        assert!(!is_websocket_upgrade(&headers(&[])));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_ingress_end_to_end() {
+        use tokio_tungstenite::tungstenite::Message;
+
+        let node_id = uuid::Uuid::new_v4();
+        let mut provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, String::from("http://127.0.0.1:7110"), None).await;
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let mut ingress =
+            WebSocketIngressResourceProvider::new(provider.clone(), Box::new(NoTelemetryHandle {}), node_id, &format!("http://{}", addr))
+                .await
+                .unwrap();
+
+        let resource_id = match ingress
+            .start(edgeless_api::resource_configuration::ResourceInstanceSpecification {
+                class_type: "websocket-ingress".to_string(),
+                configuration: std::collections::HashMap::from([("path".to_string(), "/ws".to_string())]),
+                workflow_id: "workflow_1".to_string(),
+            })
+            .await
+            .unwrap()
+        {
+            edgeless_api::common::StartComponentResponse::InstanceId(resource_id) => resource_id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        };
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut target = provider.get_handle_for(target_id).await;
+        ingress
+            .patch(edgeless_api::common::PatchRequest {
+                function_id: resource_id.function_id,
+                output_mapping: std::collections::HashMap::from([("new_message".to_string(), target_id)]),
+            })
+            .await
+            .unwrap();
+
+        // Connect a client via WebSocket, which sends a message.
+        let (mut websocket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+        let open = receive(&mut target).await;
+        assert_eq!(edgeless_http::EdgelessWebSocketEvent::Open, open.event);
+        let websocket_id = open.connection_id;
+        websocket.send(Message::Text(String::from("hello"))).await.unwrap();
+        assert_eq!(
+            edgeless_http::EdgelessWebSocketMessage {
+                connection_id: websocket_id.clone(),
+                event: edgeless_http::EdgelessWebSocketEvent::Message,
+                data: String::from("hello"),
+                binary: false,
+            },
+            receive(&mut target).await
+        );
+
+        // Connect a client via Server-Sent Events, which sends a message via
+        // POST with its connection identifier.
+        let client = reqwest::Client::new();
+        let mut sse = client
+            .get(format!("http://{}/ws", addr))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(reqwest::StatusCode::OK, sse.status());
+        let mut buffer = String::new();
+        let (event, sse_id) = next_sse(&mut sse, &mut buffer).await;
+        assert_eq!("open", event);
+        let open = receive(&mut target).await;
+        assert_eq!(edgeless_http::EdgelessWebSocketEvent::Open, open.event);
+        assert_eq!(sse_id, open.connection_id);
+
+        let res = client
+            .post(format!("http://{}/ws?connection_id={}", addr, sse_id))
+            .body("from-sse")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(reqwest::StatusCode::OK, res.status());
+        assert_eq!(
+            edgeless_http::EdgelessWebSocketMessage {
+                connection_id: sse_id.clone(),
+                event: edgeless_http::EdgelessWebSocketEvent::Message,
+                data: String::from("from-sse"),
+                binary: false,
+            },
+            receive(&mut target).await
+        );
+        let res = client
+            .post(format!("http://{}/ws?connection_id=unknown", addr))
+            .body("from-sse")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(reqwest::StatusCode::NOT_FOUND, res.status());
+
+        // The casts of the function are delivered to the clients addressed
+        // individually, or to all of them if no connection is specified.
+        send(&mut target, resource_id, &sse_id, "to-sse").await;
+        send(&mut target, resource_id, &websocket_id, "to-websocket").await;
+        send(&mut target, resource_id, "", "to-all").await;
+        for expected in ["to-websocket", "to-all"] {
+            let frame = tokio::time::timeout(tokio::time::Duration::from_secs(5), websocket.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(Message::Text(String::from(expected)), frame);
+        }
+        for expected in ["to-sse", "to-all"] {
+            assert_eq!((String::from("message"), String::from(expected)), next_sse(&mut sse, &mut buffer).await);
+        }
+
+        // The target is notified when a client disconnects.
+        websocket.close(None).await.unwrap();
+        let close = receive(&mut target).await;
+        assert_eq!(edgeless_http::EdgelessWebSocketEvent::Close, close.event);
+        assert_eq!(websocket_id, close.connection_id);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_ingress_limits() {
+        let node_id = uuid::Uuid::new_v4();
+        let mut provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, String::from("http://127.0.0.1:7112"), None).await;
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let mut ingress =
+            WebSocketIngressResourceProvider::new(provider.clone(), Box::new(NoTelemetryHandle {}), node_id, &format!("http://{}", addr))
+                .await
+                .unwrap();
+
+        let specification = |entries: &[(&str, &str)]| edgeless_api::resource_configuration::ResourceInstanceSpecification {
+            class_type: "websocket-ingress".to_string(),
+            configuration: configuration(entries),
+            workflow_id: "workflow_1".to_string(),
+        };
+        match ingress
+            .start(specification(&[("path", "/invalid"), ("max_body_size", "1MB")]))
+            .await
+            .unwrap()
+        {
+            edgeless_api::common::StartComponentResponse::InstanceId(_) => panic!("invalid max_body_size accepted"),
+            edgeless_api::common::StartComponentResponse::ResponseError(_) => {}
+        };
+        let resource_id = match ingress
+            .start(specification(&[("path", "/ws"), ("max_body_size", "4"), ("max_connections", "1")]))
+            .await
+            .unwrap()
+        {
+            edgeless_api::common::StartComponentResponse::InstanceId(resource_id) => resource_id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        };
+        let target_id = edgeless_api::function_instance::InstanceId::new(node_id);
+        let mut target = provider.get_handle_for(target_id).await;
+        ingress
+            .patch(edgeless_api::common::PatchRequest {
+                function_id: resource_id.function_id,
+                output_mapping: std::collections::HashMap::from([("new_message".to_string(), target_id)]),
+            })
+            .await
+            .unwrap();
+
+        let client = reqwest::Client::new();
+        let mut sse = client
+            .get(format!("http://{}/ws", addr))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(reqwest::StatusCode::OK, sse.status());
+        let mut buffer = String::new();
+        let (_, sse_id) = next_sse(&mut sse, &mut buffer).await;
+        assert_eq!(edgeless_http::EdgelessWebSocketEvent::Open, receive(&mut target).await.event);
+
+        // Messages larger than max_body_size are rejected.
+        let post = |body: &'static str| client.post(format!("http://{}/ws?connection_id={}", addr, sse_id)).body(body).send();
+        assert_eq!(reqwest::StatusCode::PAYLOAD_TOO_LARGE, post("hello").await.unwrap().status());
+        assert_eq!(reqwest::StatusCode::OK, post("hell").await.unwrap().status());
+        assert_eq!(String::from("hell"), receive(&mut target).await.data);
+
+        // No more clients can connect, via SSE or WebSocket, until the
+        // current one is closed.
+        let res = client
+            .get(format!("http://{}/ws", addr))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(reqwest::StatusCode::SERVICE_UNAVAILABLE, res.status());
+        match tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await {
+            Err(tokio_tungstenite::tungstenite::Error::Http(res)) => assert_eq!(hyper::StatusCode::SERVICE_UNAVAILABLE, res.status()),
+            res => panic!("unexpected result: {:?}", res.map(|(_, res)| res)),
+        }
+        let close = edgeless_http::EdgelessWebSocketMessage {
+            connection_id: sse_id.clone(),
+            event: edgeless_http::EdgelessWebSocketEvent::Close,
+            ..Default::default()
+        };
+        assert!(
+            target
+                .send(
+                    resource_id,
+                    edgeless_http::websocket_message_to_string(&close),
+                    &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+                )
+                .await
+        );
+        assert_eq!(edgeless_http::EdgelessWebSocketEvent::Close, receive(&mut target).await.event);
+        tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+    }
+}
@@ -0,0 +1,20 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Utilities shared by the unit tests of the node.
+
+/// Telemetry handle discarding all the events.
+pub(crate) struct NoTelemetryHandle {}
+
+impl edgeless_telemetry::telemetry_events::TelemetryHandleAPI for NoTelemetryHandle {
+    fn observe(&mut self, _event: edgeless_telemetry::telemetry_events::TelemetryEvent, _event_tags: std::collections::BTreeMap<String, String>) {}
+
+    fn fork(&mut self, _child_tags: std::collections::BTreeMap<String, String>) -> Box<dyn edgeless_telemetry::telemetry_events::TelemetryHandleAPI> {
+        Box::new(NoTelemetryHandle {})
+    }
+}
+
+/// Build the configuration of a resource instance from key-value pairs.
+pub(crate) fn configuration(entries: &[(&str, &str)]) -> std::collections::HashMap<String, String> {
+    entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
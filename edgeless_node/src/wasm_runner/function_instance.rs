@@ -130,6 +130,28 @@ impl crate::base_runtime::FunctionInstance for WASMFunctionInstance {
                 ))
             })
             .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+        linker
+            .func_wrap7_async(
+                "env",
+                "telemetry_log_fields_asm",
+                |store, level, target_ptr, target_len, msg_ptr, msg_len, fields_ptr, fields_len| {
+                    Box::new(super::guest_api_binding::telemetry_log_fields(
+                        store, level, target_ptr, target_len, msg_ptr, msg_len, fields_ptr, fields_len,
+                    ))
+                },
+            )
+            .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+        linker
+            .func_wrap6_async(
+                "env",
+                "telemetry_metric_asm",
+                |store, kind, name_ptr, name_len, labels_ptr, labels_len, value| {
+                    Box::new(super::guest_api_binding::telemetry_metric(
+                        store, kind, name_ptr, name_len, labels_ptr, labels_len, value,
+                    ))
+                },
+            )
+            .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
         linker
             .func_wrap2_async("env", "slf_asm", |store, out_node_id_ptr, out_component_id_ptr| {
                 Box::new(super::guest_api_binding::slf(store, out_node_id_ptr, out_component_id_ptr))
@@ -25,11 +25,62 @@ pub async fn telemetry_log(
     caller
         .data_mut()
         .host
-        .telemetry_log(super::helpers::level_from_i32(level), &target, &msg)
+        .telemetry_log(super::helpers::level_from_i32(level), &target, &msg, std::collections::BTreeMap::new())
         .await;
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+pub async fn telemetry_log_fields(
+    mut caller: wasmtime::Caller<'_, GuestAPI>,
+    level: i32,
+    target_ptr: i32,
+    target_len: i32,
+    msg_ptr: i32,
+    msg_len: i32,
+    fields_ptr: i32,
+    fields_len: i32,
+) -> wasmtime::Result<()> {
+    let mem = get_memory(&mut caller)?;
+    let target = super::helpers::load_string_from_vm(&mut caller.as_context_mut(), &mem, target_ptr, target_len)?;
+    let msg = super::helpers::load_string_from_vm(&mut caller.as_context_mut(), &mem, msg_ptr, msg_len)?;
+    let fields = super::helpers::load_string_from_vm(&mut caller.as_context_mut(), &mem, fields_ptr, fields_len)?;
+
+    caller
+        .data_mut()
+        .host
+        .telemetry_log(
+            super::helpers::level_from_i32(level),
+            &target,
+            &msg,
+            crate::base_runtime::guest_api::parse_metric_labels(&fields),
+        )
+        .await;
+    Ok(())
+}
+
+pub async fn telemetry_metric(
+    mut caller: wasmtime::Caller<'_, GuestAPI>,
+    kind: i32,
+    name_ptr: i32,
+    name_len: i32,
+    labels_ptr: i32,
+    labels_len: i32,
+    value: f64,
+) -> wasmtime::Result<()> {
+    let mem = get_memory(&mut caller)?;
+    let name = super::helpers::load_string_from_vm(&mut caller.as_context_mut(), &mem, name_ptr, name_len)?;
+    let labels = super::helpers::load_string_from_vm(&mut caller.as_context_mut(), &mem, labels_ptr, labels_len)?;
+
+    if let Some(kind) = super::helpers::metric_kind_from_i32(kind) {
+        match crate::base_runtime::guest_api::parse_metric_labels(&labels) {
+            Ok(labels) => caller.data_mut().host.telemetry_metric(kind, &name, labels, value).await,
+            Err(err) => log::warn!("Invalid labels of {} metric {}, ignored: {}", kind, name, err),
+        }
+    }
+    Ok(())
+}
+
 pub async fn cast_raw(
     mut caller: wasmtime::Caller<'_, GuestAPI>,
     instance_node_id_ptr: i32,
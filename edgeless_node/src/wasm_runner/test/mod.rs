@@ -136,7 +136,8 @@ async fn basic_lifecycle() {
         std::mem::discriminant(&edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionLogEntry(
             edgeless_telemetry::telemetry_events::TelemetryLogLevel::Info,
             "".to_string(),
-            "".to_string()
+            "".to_string(),
+            std::collections::BTreeMap::new()
         ))
     );
 
@@ -166,7 +167,8 @@ async fn basic_lifecycle() {
         std::mem::discriminant(&edgeless_telemetry::telemetry_events::TelemetryEvent::FunctionLogEntry(
             edgeless_telemetry::telemetry_events::TelemetryLogLevel::Info,
             "".to_string(),
-            "".to_string()
+            "".to_string(),
+            std::collections::BTreeMap::new()
         ))
     );
 
@@ -318,6 +320,21 @@ async fn is_telemetry_event_invocation_complete(receiver: &mut TelemetryReceiver
     std::mem::discriminant(&telemetry_event) == std::mem::discriminant(&TelemetryEvent::FunctionInvocationCompleted(tokio::time::Duration::ZERO))
 }
 
+async fn is_telemetry_event_dataplane_received(receiver: &mut TelemetryReceiver) -> bool {
+    let telemetry_event = receiver.try_recv();
+    assert!(telemetry_event.is_ok());
+    let (telemetry_event, _tags) = telemetry_event.unwrap();
+    std::mem::discriminant(&telemetry_event)
+        == std::mem::discriminant(&TelemetryEvent::DataplaneReceived(String::new(), 0, tokio::time::Duration::ZERO))
+}
+
+async fn is_telemetry_event_dataplane_sent(receiver: &mut TelemetryReceiver) -> bool {
+    let telemetry_event = receiver.try_recv();
+    assert!(telemetry_event.is_ok());
+    let (telemetry_event, _tags) = telemetry_event.unwrap();
+    std::mem::discriminant(&telemetry_event) == std::mem::discriminant(&TelemetryEvent::DataplaneSent(String::new(), String::new(), 0))
+}
+
 // test input (host-> function): cast
 // We assume this works after this test and trigger the different outputs using casts.
 #[tokio::test]
@@ -329,6 +346,7 @@ async fn messaging_cast_raw_input() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
 
     assert!(telemetry_mock_receiver.try_recv().is_ok());
     assert!(telemetry_mock_receiver.try_recv().is_ok());
@@ -345,6 +363,8 @@ async fn messaging_cast_raw_output() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_sent(&mut telemetry_mock_receiver).await);
     assert!(is_telemetry_event_invocation_complete(&mut telemetry_mock_receiver).await);
     assert!(telemetry_mock_receiver.try_recv().is_err());
 
@@ -367,6 +387,8 @@ async fn messaging_call_raw_output() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_sent(&mut telemetry_mock_receiver).await);
 
     // This won't have completed here.
     assert!(telemetry_mock_receiver.try_recv().is_err());
@@ -411,7 +433,9 @@ async fn messaging_delayed_cast_output() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
     assert!(is_telemetry_event_invocation_complete(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_sent(&mut telemetry_mock_receiver).await);
     assert!(telemetry_mock_receiver.try_recv().is_err());
 }
 
@@ -426,6 +450,8 @@ async fn messaging_cast_output() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_sent(&mut telemetry_mock_receiver).await);
     assert!(is_telemetry_event_invocation_complete(&mut telemetry_mock_receiver).await);
     assert!(telemetry_mock_receiver.try_recv().is_err());
 
@@ -446,6 +472,8 @@ async fn messaging_call_output() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_sent(&mut telemetry_mock_receiver).await);
 
     // This won't have completed here.
     assert!(telemetry_mock_receiver.try_recv().is_err());
@@ -475,6 +503,8 @@ async fn function_in_call_can_be_stopped() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_sent(&mut telemetry_mock_receiver).await);
 
     // This won't have completed here.
     assert!(telemetry_mock_receiver.try_recv().is_err());
@@ -502,6 +532,7 @@ async fn messaging_call_raw_input_noreply() {
     assert_eq!(ret, CallRet::NoReply);
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
     assert!(is_telemetry_event_invocation_complete(&mut telemetry_mock_receiver).await);
     assert!(telemetry_mock_receiver.try_recv().is_err());
 }
@@ -516,6 +547,7 @@ async fn messaging_call_raw_input_reply() {
     assert_eq!(ret, CallRet::Reply("test_reply".to_string()));
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
     assert!(is_telemetry_event_invocation_complete(&mut telemetry_mock_receiver).await);
     assert!(telemetry_mock_receiver.try_recv().is_err());
 }
@@ -530,6 +562,7 @@ async fn messaging_call_raw_input_err() {
     assert_eq!(ret, CallRet::Err);
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
     assert!(is_telemetry_event_invocation_complete(&mut telemetry_mock_receiver).await);
     assert!(telemetry_mock_receiver.try_recv().is_err());
 }
@@ -600,7 +633,8 @@ async fn state_management() {
         TelemetryEvent::FunctionLogEntry(
             edgeless_telemetry::telemetry_events::TelemetryLogLevel::Info,
             "state_test".to_string(),
-            "no_state".to_string()
+            "no_state".to_string(),
+            std::collections::BTreeMap::new()
         )
     );
 
@@ -616,6 +650,7 @@ async fn state_management() {
     assert_eq!(state_set_value, "new_state".to_string());
 
     assert!(is_telemetry_event_transfer(&mut telemetry_mock_receiver).await);
+    assert!(is_telemetry_event_dataplane_received(&mut telemetry_mock_receiver).await);
     wait_for_oks(1, true, &telemetry_mock_receiver).await;
 
     let res = client.stop(instance_id).await;
@@ -639,9 +674,89 @@ async fn state_management() {
         TelemetryEvent::FunctionLogEntry(
             edgeless_telemetry::telemetry_events::TelemetryLogLevel::Info,
             "edgeless_test_state".to_string(),
-            "existing_state".to_string()
+            "existing_state".to_string(),
+            std::collections::BTreeMap::new()
         )
     );
 
     wait_for_oks(1, true, &telemetry_mock_receiver).await;
 }
+
+// test output: application-defined metrics, with the invalid ones being ignored
+#[tokio::test]
+async fn telemetry_metric_output() {
+    let node_id = uuid::Uuid::new_v4();
+    let instance_id = edgeless_api::function_instance::InstanceId::new(node_id);
+
+    let state_manager = Box::new(crate::state_management::StateManager::new().await);
+    let mut dataplane_provider = edgeless_dataplane::handle::DataplaneProvider::new(node_id, "http://127.0.0.1:7002".to_string(), None).await;
+
+    let test_peer_fid = edgeless_api::function_instance::InstanceId::new(node_id);
+    let mut test_peer_handle = dataplane_provider.get_handle_for(test_peer_fid).await;
+
+    let (telemetry_mock_sender, telemetry_mock_receiver) = std::sync::mpsc::channel::<(
+        edgeless_telemetry::telemetry_events::TelemetryEvent,
+        std::collections::BTreeMap<String, String>,
+    )>();
+    let telemetry_handle = Box::new(MockTelemetryHandle {
+        sender: telemetry_mock_sender,
+    });
+
+    let (mut client, mut rt_task) = crate::base_runtime::runtime::create::<super::function_instance::WASMFunctionInstance>(
+        dataplane_provider,
+        state_manager,
+        telemetry_handle,
+        mock_runtime(),
+    );
+
+    tokio::spawn(async move { rt_task.run().await });
+
+    let spawn_req = edgeless_api::function_instance::SpawnFunctionRequest {
+        spec: edgeless_api::function_instance::FunctionClassSpecification {
+            id: "EXAMPLE_1".to_string(),
+            function_type: "RUST_WASM".to_string(),
+            version: "0.1".to_string(),
+            binary: Some(wat::parse_str(include_str!("../../../../functions/metric_test/metric_test.wat")).unwrap()),
+            code: None,
+            outputs: vec![],
+        },
+        annotations: std::collections::HashMap::new(),
+        state_specification: edgeless_api::function_instance::StateSpecification {
+            state_id: instance_id.function_id,
+            state_policy: edgeless_api::function_instance::StatePolicy::Transient,
+        },
+        workflow_id: "workflow_1".to_string(),
+    };
+
+    assert!(client.start(instance_id, spawn_req).await.is_ok());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Instantiate and init.
+    assert_eq!(2, telemetry_mock_receiver.try_iter().count());
+
+    test_peer_handle
+        .send(
+            instance_id,
+            "metrics".to_string(),
+            &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+        )
+        .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let metrics = telemetry_mock_receiver
+        .try_iter()
+        .filter(|(event, _tags)| matches!(event, TelemetryEvent::FunctionMetric(..)))
+        .map(|(event, _tags)| event)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![TelemetryEvent::FunctionMetric(
+            edgeless_api::node_registration::FunctionMetricKind::Counter,
+            "frames".to_string(),
+            std::collections::BTreeMap::from([("camera".to_string(), "front".to_string())]),
+            2.0
+        )],
+        metrics
+    );
+
+    assert!(client.stop(instance_id).await.is_ok());
+}
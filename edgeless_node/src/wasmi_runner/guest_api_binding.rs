@@ -20,7 +20,59 @@ pub fn telemetry_log(
     let target = load_string_from_vm(&mut caller.as_context_mut(), &mem, target_ptr, target_len)?;
     let msg = load_string_from_vm(&mut caller.as_context_mut(), &mem, msg_ptr, msg_len)?;
 
-    tokio::runtime::Handle::current().block_on(caller.data_mut().host.telemetry_log(level_from_i32(level), &target, &msg));
+    tokio::runtime::Handle::current().block_on(caller.data_mut().host.telemetry_log(
+        level_from_i32(level),
+        &target,
+        &msg,
+        std::collections::BTreeMap::new(),
+    ));
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn telemetry_log_fields(
+    mut caller: wasmi::Caller<'_, GuestAPI>,
+    level: i32,
+    target_ptr: i32,
+    target_len: i32,
+    msg_ptr: i32,
+    msg_len: i32,
+    fields_ptr: i32,
+    fields_len: i32,
+) -> Result<(), wasmi::core::Trap> {
+    let mem = get_memory(&mut caller)?;
+    let target = load_string_from_vm(&mut caller.as_context_mut(), &mem, target_ptr, target_len)?;
+    let msg = load_string_from_vm(&mut caller.as_context_mut(), &mem, msg_ptr, msg_len)?;
+    let fields = load_string_from_vm(&mut caller.as_context_mut(), &mem, fields_ptr, fields_len)?;
+
+    tokio::runtime::Handle::current().block_on(caller.data_mut().host.telemetry_log(
+        level_from_i32(level),
+        &target,
+        &msg,
+        crate::base_runtime::guest_api::parse_metric_labels(&fields),
+    ));
+    Ok(())
+}
+
+pub fn telemetry_metric(
+    mut caller: wasmi::Caller<'_, GuestAPI>,
+    kind: i32,
+    name_ptr: i32,
+    name_len: i32,
+    labels_ptr: i32,
+    labels_len: i32,
+    value: f64,
+) -> Result<(), wasmi::core::Trap> {
+    let mem = get_memory(&mut caller)?;
+    let name = load_string_from_vm(&mut caller.as_context_mut(), &mem, name_ptr, name_len)?;
+    let labels = load_string_from_vm(&mut caller.as_context_mut(), &mem, labels_ptr, labels_len)?;
+
+    if let Some(kind) = metric_kind_from_i32(kind) {
+        match crate::base_runtime::guest_api::parse_metric_labels(&labels) {
+            Ok(labels) => tokio::runtime::Handle::current().block_on(caller.data_mut().host.telemetry_metric(kind, &name, labels, value)),
+            Err(err) => log::warn!("Invalid labels of {} metric {}, ignored: {}", kind, name, err),
+        }
+    }
     Ok(())
 }
 
@@ -50,3 +50,15 @@ pub(crate) fn level_from_i32(lvl: i32) -> edgeless_telemetry::telemetry_events::
         }
     }
 }
+
+pub(crate) fn metric_kind_from_i32(kind: i32) -> Option<edgeless_api::node_registration::FunctionMetricKind> {
+    match kind {
+        0 => Some(edgeless_api::node_registration::FunctionMetricKind::Counter),
+        1 => Some(edgeless_api::node_registration::FunctionMetricKind::Gauge),
+        2 => Some(edgeless_api::node_registration::FunctionMetricKind::Histogram),
+        _ => {
+            log::warn!("Function used unknown metric kind");
+            None
+        }
+    }
+}
@@ -89,6 +89,20 @@ impl crate::base_runtime::FunctionInstance for WASMIFunctionInstance {
                 wasmi::Func::wrap(&mut store, guest_api_binding::telemetry_log),
             )
             .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+        linker
+            .define(
+                "env",
+                "telemetry_log_fields_asm",
+                wasmi::Func::wrap(&mut store, guest_api_binding::telemetry_log_fields),
+            )
+            .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
+        linker
+            .define(
+                "env",
+                "telemetry_metric_asm",
+                wasmi::Func::wrap(&mut store, guest_api_binding::telemetry_metric),
+            )
+            .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
         linker
             .define("env", "slf_asm", wasmi::Func::wrap(&mut store, guest_api_binding::slf))
             .map_err(|_| crate::base_runtime::FunctionInstanceError::InternalError)?;
@@ -0,0 +1,451 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Alert rules evaluated on the health status and performance samples
+//! periodically received from the nodes.
+
+/// Timeout of the notification of an alert via a webhook or a cast.
+const NOTIFY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Condition of an alert rule on the value of a metric.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertCondition {
+    /// The value is greater than the threshold.
+    Above,
+    /// The value is smaller than the threshold.
+    Below,
+}
+
+/// Sink to which an alert is notified.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSink {
+    /// Orchestrator log.
+    Log,
+    /// HTTP POST of the alert in JSON to the webhook URL.
+    Webhook,
+    /// Cast of the alert in JSON to the alert component.
+    Cast,
+}
+
+fn default_notify() -> Vec<AlertSink> {
+    vec![AlertSink::Log]
+}
+
+/// Alert rule on a metric of the nodes, see [`node_metrics`].
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct AlertRule {
+    /// Name of the rule.
+    pub name: String,
+    /// Name of the metric.
+    pub metric: String,
+    /// If true, the condition applies to the rate of change of the metric,
+    /// per second, rather than to its value.
+    #[serde(default)]
+    pub rate: bool,
+    /// Condition on the value (or rate) of the metric.
+    pub condition: AlertCondition,
+    /// Threshold of the condition.
+    pub threshold: f64,
+    /// Time for which the condition must hold before the alert fires, in s.
+    #[serde(default)]
+    pub for_sec: u64,
+    /// Sinks to which the alert is notified when fired and resolved.
+    #[serde(default = "default_notify")]
+    pub notify: Vec<AlertSink>,
+    /// If true, the node is cordoned when the alert fires.
+    #[serde(default)]
+    pub cordon: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+    /// The metric of a firing alert is not reported anymore by the node,
+    /// hence it is unknown whether the condition still holds.
+    Stale,
+}
+
+/// Alert fired or resolved on a node.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub status: AlertStatus,
+    pub node_id: edgeless_api::function_instance::NodeId,
+    pub metric: String,
+    pub rate: bool,
+    /// Value (or rate) of the metric that triggered the status change,
+    /// or the last one received if the alert is stale.
+    pub value: f64,
+    pub condition: AlertCondition,
+    pub threshold: f64,
+    /// Number of s since Unix epoch.
+    pub timestamp: f64,
+}
+
+impl std::fmt::Display for Alert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "alert '{}' {} on node {}: {}{} = {} ({} {})",
+            self.rule,
+            match self.status {
+                AlertStatus::Firing => "firing",
+                AlertStatus::Resolved => "resolved",
+                AlertStatus::Stale => "stale",
+            },
+            self.node_id,
+            self.metric,
+            if self.rate { "/s" } else { "" },
+            self.value,
+            match self.condition {
+                AlertCondition::Above => "above",
+                AlertCondition::Below => "below",
+            },
+            self.threshold
+        )
+    }
+}
+
+/// Return the metrics of a node that can be used in the alert rules:
+///
+/// - all the fields of the health status, with the same names, except
+///   those with negative values, which are not available at the node;
+/// - `function_execution_time` and `function_transfer_time`: the average
+///   execution and transfer times of the function instances, in s, among
+///   the samples received;
+/// - `resource_failures` and `resource_throttled`: the total number of
+///   requests failed and throttled by the resource instances.
+///
+/// The performance metrics are present only if the node reported samples.
+pub fn node_metrics(
+    health: &edgeless_api::node_registration::NodeHealthStatus,
+    samples: &edgeless_api::node_registration::NodePerformanceSamples,
+) -> std::collections::HashMap<String, f64> {
+    let mut metrics = std::collections::HashMap::new();
+    for (name, value) in [
+        ("mem_free", health.mem_free as f64),
+        ("mem_used", health.mem_used as f64),
+        ("mem_available", health.mem_available as f64),
+        ("proc_cpu_usage", health.proc_cpu_usage as f64),
+        ("proc_memory", health.proc_memory as f64),
+        ("proc_vmemory", health.proc_vmemory as f64),
+        ("load_avg_1", health.load_avg_1 as f64),
+        ("load_avg_5", health.load_avg_5 as f64),
+        ("load_avg_15", health.load_avg_15 as f64),
+        ("tot_rx_bytes", health.tot_rx_bytes as f64),
+        ("tot_rx_pkts", health.tot_rx_pkts as f64),
+        ("tot_rx_errs", health.tot_rx_errs as f64),
+        ("tot_tx_bytes", health.tot_tx_bytes as f64),
+        ("tot_tx_pkts", health.tot_tx_pkts as f64),
+        ("tot_tx_errs", health.tot_tx_errs as f64),
+        ("disk_free_space", health.disk_free_space as f64),
+        ("disk_tot_reads", health.disk_tot_reads as f64),
+        ("disk_tot_writes", health.disk_tot_writes as f64),
+        ("gpu_load_perc", health.gpu_load_perc as f64),
+        ("gpu_temp_cels", health.gpu_temp_cels as f64),
+        ("active_power", health.active_power as f64),
+    ] {
+        if value >= 0.0 {
+            metrics.insert(name.to_string(), value);
+        }
+    }
+
+    let all_samples = |times: &std::collections::HashMap<_, Vec<edgeless_api::node_registration::Sample>>| {
+        times.values().flatten().map(|x| x.sample).collect::<Vec<f64>>()
+    };
+    for (name, times) in [
+        ("function_execution_time", &samples.function_execution_times),
+        ("function_transfer_time", &samples.function_transfer_times),
+    ] {
+        let values = all_samples(times);
+        if !values.is_empty() {
+            metrics.insert(name.to_string(), values.iter().sum::<f64>() / values.len() as f64);
+        }
+    }
+    for (name, counts) in [
+        ("resource_failures", &samples.resource_failures),
+        ("resource_throttled", &samples.resource_throttled),
+    ] {
+        let values = all_samples(counts);
+        if !values.is_empty() {
+            metrics.insert(name.to_string(), values.iter().sum::<f64>());
+        }
+    }
+
+    metrics
+}
+
+/// State of a rule on a node.
+#[derive(Debug, Default)]
+struct RuleState {
+    /// Time since when the condition holds, if it does.
+    pending_since: Option<f64>,
+    /// True if the alert has fired and has not been resolved yet.
+    firing: bool,
+    /// Last value (or rate) of the metric received.
+    last_value: f64,
+}
+
+/// Engine evaluating the alert rules on the metrics of the nodes.
+///
+/// An alert fires when the condition of a rule holds for at least `for_sec`
+/// on a node and it is resolved as soon as the condition does not hold
+/// anymore. If the metric of a rule is not reported by a node, the rule is
+/// not evaluated and its alert, if firing, becomes stale.
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    // key: (rule index, node_id)
+    states: std::collections::HashMap<(usize, edgeless_api::function_instance::NodeId), RuleState>,
+    // last value of the metrics, used to compute their rates
+    // key: node_id
+    // value: (time, metrics)
+    last_metrics: std::collections::HashMap<edgeless_api::function_instance::NodeId, (f64, std::collections::HashMap<String, f64>)>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self { rules, ..Default::default() }
+    }
+
+    /// Evaluate the rules on the metrics received from a node at time `now`,
+    /// in s, and return the alerts that fired or were resolved, with the
+    /// rules that triggered them.
+    pub fn evaluate(
+        &mut self,
+        node_id: edgeless_api::function_instance::NodeId,
+        now: f64,
+        metrics: std::collections::HashMap<String, f64>,
+    ) -> Vec<(AlertRule, Alert)> {
+        let rates = match self.last_metrics.get(&node_id) {
+            Some((last_time, last_metrics)) if now > *last_time => metrics
+                .iter()
+                .filter_map(|(name, value)| {
+                    last_metrics
+                        .get(name)
+                        .map(|last_value| (name.clone(), (value - last_value) / (now - last_time)))
+                })
+                .collect(),
+            _ => std::collections::HashMap::new(),
+        };
+
+        let mut alerts = vec![];
+        for (index, rule) in self.rules.iter().enumerate() {
+            let state = self.states.entry((index, node_id)).or_default();
+            let value = match if rule.rate { rates.get(&rule.metric) } else { metrics.get(&rule.metric) } {
+                Some(value) => *value,
+                None => {
+                    // The condition must hold again for `for_sec` once the
+                    // metric is reported again.
+                    state.pending_since = None;
+                    if !state.firing {
+                        continue;
+                    }
+                    state.firing = false;
+                    alerts.push((rule.clone(), Self::alert(rule, AlertStatus::Stale, node_id, state.last_value, now)));
+                    continue;
+                }
+            };
+            state.last_value = value;
+            let holds = match rule.condition {
+                AlertCondition::Above => value > rule.threshold,
+                AlertCondition::Below => value < rule.threshold,
+            };
+            let status = if holds {
+                let since = *state.pending_since.get_or_insert(now);
+                if state.firing || (now - since) < rule.for_sec as f64 {
+                    continue;
+                }
+                state.firing = true;
+                AlertStatus::Firing
+            } else {
+                state.pending_since = None;
+                if !state.firing {
+                    continue;
+                }
+                state.firing = false;
+                AlertStatus::Resolved
+            };
+            alerts.push((rule.clone(), Self::alert(rule, status, node_id, value, now)));
+        }
+
+        self.last_metrics.insert(node_id, (now, metrics));
+        alerts
+    }
+
+    fn alert(rule: &AlertRule, status: AlertStatus, node_id: edgeless_api::function_instance::NodeId, value: f64, now: f64) -> Alert {
+        Alert {
+            rule: rule.name.clone(),
+            status,
+            node_id,
+            metric: rule.metric.clone(),
+            rate: rule.rate,
+            value,
+            condition: rule.condition.clone(),
+            threshold: rule.threshold,
+            timestamp: now,
+        }
+    }
+
+    /// Remove the state of a node.
+    pub fn remove_node(&mut self, node_id: &edgeless_api::function_instance::NodeId) {
+        self.states.retain(|(_index, cur_node_id), _state| cur_node_id != node_id);
+        self.last_metrics.remove(node_id);
+    }
+}
+
+/// Notify an alert via an HTTP POST of its JSON representation.
+pub async fn notify_webhook(url: String, alert: Alert) {
+    match reqwest::Client::new().post(&url).timeout(NOTIFY_TIMEOUT).json(&alert).send().await {
+        Ok(response) => {
+            if !response.status().is_success() {
+                log::warn!("Webhook '{}' replied with status {} to {}", url, response.status(), alert);
+            }
+        }
+        Err(err) => log::warn!("Could not notify {} to webhook '{}': {}", alert, url, err),
+    }
+}
+
+/// Notify an alert via a cast of its JSON representation to a component
+/// instance, reachable at the given invocation URL.
+pub async fn notify_cast(invocation_url: String, target: edgeless_api::function_instance::InstanceId, alert: Alert) {
+    let payload = match serde_json::to_string(&alert) {
+        Ok(payload) => payload,
+        Err(err) => {
+            log::error!("Could not serialize {}: {}", alert, err);
+            return;
+        }
+    };
+    let cast = async {
+        let mut client = edgeless_api::grpc_impl::outer::invocation::InvocationAPIClient::new(&invocation_url).await;
+        let event = edgeless_api::invocation::Event {
+            target,
+            source: edgeless_api::function_instance::InstanceId::none(),
+            stream_id: 0,
+            data: edgeless_api::invocation::EventData::Cast(payload),
+            created: edgeless_api::function_instance::EventTimestamp::default(),
+            metadata: edgeless_api::function_instance::EventMetadata::empty_new_root(),
+        };
+        edgeless_api::invocation::InvocationAPI::handle(&mut client, event).await
+    };
+    match tokio::time::timeout(NOTIFY_TIMEOUT, cast).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(err)) => log::warn!("Could not cast {} to {}: {}", alert, target, err),
+        Err(_) => log::warn!("Timeout when casting {} to {}", alert, target),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_engine() {
+        let rule = |name: &str, metric: &str, rate: bool, condition: AlertCondition, threshold: f64, for_sec: u64| AlertRule {
+            name: name.to_string(),
+            metric: metric.to_string(),
+            rate,
+            condition,
+            threshold,
+            for_sec,
+            notify: default_notify(),
+            cordon: false,
+        };
+        let mut engine = AlertEngine::new(vec![
+            rule("memory-pressure", "mem_available", false, AlertCondition::Below, 1000.0, 10),
+            rule("rx-errors", "tot_rx_errs", true, AlertCondition::Above, 5.0, 0),
+        ]);
+        let node_id = uuid::Uuid::new_v4();
+        let metrics = |mem_available: f64, tot_rx_errs: f64| {
+            std::collections::HashMap::from([("mem_available".to_string(), mem_available), ("tot_rx_errs".to_string(), tot_rx_errs)])
+        };
+        let fired = |alerts: Vec<(AlertRule, Alert)>| alerts.into_iter().map(|(rule, alert)| (rule.name, alert.status)).collect::<Vec<_>>();
+
+        // No rates at the first update.
+        assert!(engine.evaluate(node_id, 100.0, metrics(500.0, 0.0)).is_empty());
+
+        // The memory pressure is not sustained for long enough.
+        assert!(engine.evaluate(node_id, 105.0, metrics(500.0, 10.0)).is_empty());
+
+        // The rate of errors is 10 / 2 > 5 and the memory pressure lasts 10 s.
+        assert_eq!(
+            vec![
+                ("memory-pressure".to_string(), AlertStatus::Firing),
+                ("rx-errors".to_string(), AlertStatus::Firing)
+            ],
+            fired(engine.evaluate(node_id, 110.0, metrics(500.0, 30.0)))
+        );
+
+        // The alerts are not fired again while the conditions hold.
+        assert!(engine.evaluate(node_id, 111.0, metrics(500.0, 40.0)).is_empty());
+
+        // The alerts are resolved when the conditions do not hold anymore.
+        assert_eq!(
+            vec![("memory-pressure".to_string(), AlertStatus::Resolved)],
+            fired(engine.evaluate(node_id, 112.0, metrics(2000.0, 41.0)))
+        );
+        assert_eq!(
+            vec![("rx-errors".to_string(), AlertStatus::Resolved)],
+            fired(engine.evaluate(node_id, 113.0, metrics(2000.0, 41.0)))
+        );
+
+        // Missing metrics are ignored.
+        assert!(engine.evaluate(node_id, 114.0, std::collections::HashMap::new()).is_empty());
+
+        // The firing alerts become stale when their metrics are missing and
+        // they fire again only after the condition holds for `for_sec`.
+        assert!(engine.evaluate(node_id, 200.0, metrics(500.0, 41.0)).is_empty());
+        assert_eq!(
+            vec![("memory-pressure".to_string(), AlertStatus::Firing)],
+            fired(engine.evaluate(node_id, 210.0, metrics(400.0, 41.0)))
+        );
+        let alerts = engine.evaluate(node_id, 211.0, std::collections::HashMap::from([("tot_rx_errs".to_string(), 41.0)]));
+        assert_eq!(1, alerts.len());
+        assert_eq!(AlertStatus::Stale, alerts[0].1.status);
+        assert_eq!(400.0, alerts[0].1.value);
+        assert_eq!(211.0, alerts[0].1.timestamp);
+        assert!(engine.evaluate(node_id, 212.0, std::collections::HashMap::new()).is_empty());
+        assert!(engine.evaluate(node_id, 213.0, metrics(500.0, 41.0)).is_empty());
+        assert_eq!(
+            vec![("memory-pressure".to_string(), AlertStatus::Firing)],
+            fired(engine.evaluate(node_id, 223.0, metrics(500.0, 41.0)))
+        );
+
+        // The state is lost when the node is removed.
+        engine.evaluate(node_id, 230.0, metrics(500.0, 41.0));
+        engine.remove_node(&node_id);
+        assert!(engine.evaluate(node_id, 240.0, metrics(500.0, 100.0)).is_empty());
+    }
+
+    #[test]
+    fn test_node_metrics() {
+        let health = edgeless_api::node_registration::NodeHealthStatus {
+            mem_available: 1024,
+            ..edgeless_api::node_registration::NodeHealthStatus::invalid()
+        };
+        let sample = |sample: f64| edgeless_api::node_registration::Sample {
+            timestamp_sec: 0,
+            timestamp_ns: 0,
+            sample,
+        };
+        let samples = edgeless_api::node_registration::NodePerformanceSamples {
+            function_execution_times: std::collections::HashMap::from([
+                (uuid::Uuid::new_v4(), vec![sample(0.1), sample(0.2)]),
+                (uuid::Uuid::new_v4(), vec![sample(0.3)]),
+            ]),
+            resource_failures: std::collections::HashMap::from([(uuid::Uuid::new_v4(), vec![sample(2.0), sample(3.0)])]),
+            ..Default::default()
+        };
+
+        let metrics = node_metrics(&health, &samples);
+        assert_eq!(Some(&1024.0), metrics.get("mem_available"));
+        assert!(!metrics.contains_key("mem_free"));
+        assert!((metrics.get("function_execution_time").unwrap() - 0.2).abs() < 1e-9);
+        assert!(!metrics.contains_key("function_transfer_time"));
+        assert_eq!(Some(&5.0), metrics.get("resource_failures"));
+    }
+}
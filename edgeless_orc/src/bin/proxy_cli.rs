@@ -9,17 +9,27 @@ use std::{io::Write, str::FromStr};
 use clap::Parser;
 use edgeless_orc::proxy::Proxy;
 
+const API_TOKEN_ENV: &str = "EDGELESS_API_TOKEN";
+
 #[derive(Debug, clap::Parser)]
 #[command(long_about = None)]
 struct Args {
     #[command(subcommand)]
     command: Commands,
-    /// Orchestrator proxy type. One of: Redis.
+    /// Orchestrator proxy type. One of: Redis, Orchestrator.
     #[arg(short, long, default_value_t = String::from("Redis"))]
     proxy_type: String,
     /// URL of the Redis server used as orchestrator's proxy.
     #[arg(short, long, default_value_t = String::from("redis://localhost:6379"))]
     redis_url: String,
+    /// URL of the orchestrator, used only with the Orchestrator proxy type.
+    #[arg(long, default_value_t = String::from("http://127.0.0.1:7003"))]
+    orchestrator_url: String,
+    /// Bearer token used to authenticate with the orchestrator's
+    /// Introspection API. If not set, it is read from the environment
+    /// variable EDGELESS_API_TOKEN.
+    #[arg(long)]
+    api_token: Option<String>,
     /// URL of the EDGELESS controller, used only with some commands.
     #[arg(short, long, default_value_t = String::from("http://127.0.0.1:7001"))]
     controller_url: String,
@@ -64,6 +74,7 @@ enum NodeCommands {
     ResourceProviders {},
     Health {},
     Instances {},
+    Drain {},
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -89,6 +100,7 @@ enum IntentCommands {
     Migrate { instance: String, node_id: String },
     Cordon { node_id: String },
     Uncordon { node_id: String },
+    Drain { node_id: String },
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -109,6 +121,29 @@ enum Commands {
         #[command(subcommand)]
         dump_command: DumpCommands,
     },
+    /// Query the historical metrics from the orchestrator at orchestrator_url.
+    Metrics {
+        /// Name of the metric, e.g., mem_available or function_execution_time.
+        metric: String,
+        /// Time range, in seconds before now.
+        #[arg(long, default_value_t = 3600)]
+        last_sec: u64,
+        /// Duration of the aggregation intervals, in seconds, 0 means the finest resolution available.
+        #[arg(long, default_value_t = 0)]
+        step_sec: u64,
+        /// Aggregation of the samples. One of: avg, min, max, sum, count.
+        #[arg(long, default_value_t = String::from("avg"))]
+        aggregate: String,
+        /// Grouping of the series. One of: none, node, workflow, component.
+        #[arg(long, default_value_t = String::from("none"))]
+        group_by: String,
+        /// Only the series of this node, by identifier or name.
+        #[arg(long)]
+        node: Option<String>,
+        /// Only the series of this workflow.
+        #[arg(long)]
+        workflow: Option<String>,
+    },
 }
 
 fn open_file(filename: &str) -> anyhow::Result<std::fs::File> {
@@ -121,11 +156,7 @@ fn open_file(filename: &str) -> anyhow::Result<std::fs::File> {
         .open(filename)?)
 }
 
-fn top_nodes(
-    proxy: &mut edgeless_orc::proxy_redis::ProxyRedis,
-    stdout: &mut std::io::Stdout,
-    map_node: &dyn Fn(&uuid::Uuid) -> String,
-) -> anyhow::Result<()> {
+fn top_nodes(proxy: &mut dyn Proxy, stdout: &mut std::io::Stdout, map_node: &dyn Fn(&uuid::Uuid) -> String) -> anyhow::Result<()> {
     let mut ascii_table = ascii_table::AsciiTable::default();
     ascii_table.set_max_width(termion::terminal_size()?.0 as usize);
     ascii_table.column(0).set_header("Node").set_align(ascii_table::Align::Left);
@@ -171,7 +202,7 @@ fn top_nodes(
 }
 
 async fn top_workflow(
-    proxy: &mut edgeless_orc::proxy_redis::ProxyRedis,
+    proxy: &mut dyn Proxy,
     edgeless_cli: &mut Box<dyn edgeless_api::workflow_instance::WorkflowInstanceAPI>,
     wf_id: &edgeless_api::workflow_instance::WorkflowId,
     stdout: &mut std::io::Stdout,
@@ -284,11 +315,17 @@ async fn main() -> anyhow::Result<()> {
         );
         return Ok(());
     }
-    anyhow::ensure!(args.proxy_type.to_lowercase() == "redis", "unknown proxy type: {}", args.proxy_type);
-
-    let mut proxy = match edgeless_orc::proxy_redis::ProxyRedis::new_client(&args.redis_url) {
-        Ok(proxy) => proxy,
-        Err(err) => anyhow::bail!("could not connect to a Redis at {}: {}", args.redis_url, err),
+    let api_token = args.api_token.clone().or(std::env::var(API_TOKEN_ENV).ok());
+    let mut proxy: Box<dyn Proxy> = match args.proxy_type.to_lowercase().as_str() {
+        "redis" => match edgeless_orc::proxy_redis::ProxyRedis::new_client(&args.redis_url) {
+            Ok(proxy) => Box::new(proxy),
+            Err(err) => anyhow::bail!("could not connect to a Redis at {}: {}", args.redis_url, err),
+        },
+        "orchestrator" => match edgeless_orc::proxy_introspection::ProxyIntrospection::new_client(&args.orchestrator_url, api_token.clone()).await {
+            Ok(proxy) => Box::new(proxy),
+            Err(err) => anyhow::bail!("could not connect to an orchestrator at {}: {}", args.orchestrator_url, err),
+        },
+        _ => anyhow::bail!("unknown proxy type: {}", args.proxy_type),
     };
 
     let node_print_format = NodePrintFormat::from(&args.node_print_format)?;
@@ -359,6 +396,11 @@ async fn main() -> anyhow::Result<()> {
                         println!("{} -> {}", map_node(node), health);
                     }
                 }
+                NodeCommands::Drain {} => {
+                    for (node, drain) in proxy.fetch_node_drains().iter().sorted_by_key(|x| x.0.to_string()) {
+                        println!("{} -> {}", map_node(node), drain);
+                    }
+                }
                 NodeCommands::Instances {} => {
                     for (node, instances) in proxy.fetch_nodes_to_instances().iter().sorted_by_key(|x| x.0.to_string()) {
                         println!("{}", map_node(node));
@@ -392,9 +434,9 @@ async fn main() -> anyhow::Result<()> {
                 write!(stdout, "{}", termion::clear::All).unwrap();
 
                 if let Some(edgeless_cli) = &mut edgeless_cli {
-                    top_workflow(&mut proxy, edgeless_cli, &wf_id, &mut stdout, &map_node).await?;
+                    top_workflow(proxy.as_mut(), edgeless_cli, &wf_id, &mut stdout, &map_node).await?;
                 } else {
-                    top_nodes(&mut proxy, &mut stdout, &map_node)?;
+                    top_nodes(proxy.as_mut(), &mut stdout, &map_node)?;
                 }
 
                 stdout.flush()?;
@@ -434,6 +476,13 @@ async fn main() -> anyhow::Result<()> {
                 };
                 proxy.add_deploy_intents(vec![edgeless_orc::deploy_intent::DeployIntent::Uncordon(node_id)]);
             }
+            IntentCommands::Drain { node_id } => {
+                let node_id = match uuid::Uuid::from_str(&node_id) {
+                    Ok(node_id) => node_id,
+                    Err(err) => anyhow::bail!("invalid node id {}: {}", node_id, err),
+                };
+                proxy.add_deploy_intents(vec![edgeless_orc::deploy_intent::DeployIntent::Drain(node_id)]);
+            }
         },
         Commands::Dump { dump_command } => match dump_command {
             DumpCommands::Performance {} => {
@@ -498,6 +547,74 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         },
+        Commands::Metrics {
+            metric,
+            last_sec,
+            step_sec,
+            aggregate,
+            group_by,
+            node,
+            workflow,
+        } => {
+            use edgeless_api::introspection::IntrospectionAPI;
+            use edgeless_api::outer::orc::OrchestratorAPI;
+            let aggregate = match aggregate.to_lowercase().as_str() {
+                "avg" => edgeless_api::introspection::MetricsAggregate::Avg,
+                "min" => edgeless_api::introspection::MetricsAggregate::Min,
+                "max" => edgeless_api::introspection::MetricsAggregate::Max,
+                "sum" => edgeless_api::introspection::MetricsAggregate::Sum,
+                "count" => edgeless_api::introspection::MetricsAggregate::Count,
+                _ => anyhow::bail!("invalid aggregate: {}", aggregate),
+            };
+            let group_by = match group_by.to_lowercase().as_str() {
+                "none" => edgeless_api::introspection::MetricsGroupBy::None,
+                "node" => edgeless_api::introspection::MetricsGroupBy::Node,
+                "workflow" => edgeless_api::introspection::MetricsGroupBy::Workflow,
+                "component" => edgeless_api::introspection::MetricsGroupBy::Component,
+                _ => anyhow::bail!("invalid grouping: {}", group_by),
+            };
+            let node_ids = match node {
+                Some(node) => match uuid::Uuid::from_str(&node) {
+                    Ok(node_id) => vec![node_id],
+                    Err(err) => {
+                        if let Some((uuid, _name)) = node_to_names.iter().find(|(_uuid, name)| **name == node) {
+                            vec![*uuid]
+                        } else {
+                            anyhow::bail!("invalid node id {}: {}", node, err);
+                        }
+                    }
+                },
+                None => vec![],
+            };
+
+            let mut client = edgeless_api::grpc_impl::outer::orc::OrchestratorAPIClient::new_with_token(&args.orchestrator_url, None, api_token)
+                .await?
+                .introspection_api();
+            let series_list = client
+                .query_metrics(edgeless_api::introspection::MetricsQuery {
+                    metric,
+                    from_sec: chrono::Utc::now().timestamp() - last_sec as i64,
+                    to_sec: 0,
+                    step_sec,
+                    aggregate,
+                    group_by,
+                    node_ids,
+                    workflow_ids: workflow.into_iter().collect(),
+                })
+                .await?;
+            for series in series_list {
+                if !series.group.is_empty() {
+                    match (group_by, uuid::Uuid::from_str(&series.group)) {
+                        (edgeless_api::introspection::MetricsGroupBy::Node, Ok(node_id)) => println!("{}", map_node(&node_id)),
+                        _ => println!("{}", series.group),
+                    }
+                }
+                for point in series.points {
+                    let timestamp = chrono::DateTime::from_timestamp(point.timestamp_sec, 0).unwrap_or_default();
+                    println!("{},{}", timestamp.to_rfc3339(), point.value);
+                }
+            }
+        }
     }
 
     Ok(())
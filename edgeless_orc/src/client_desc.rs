@@ -7,6 +7,8 @@ pub struct ClientDesc {
     pub api: Box<dyn edgeless_api::outer::agent::AgentAPI + Send>,
     pub capabilities: edgeless_api::node_registration::NodeCapabilities,
     pub cordoned: bool,
+    /// Progress of the drain, if the node is being drained.
+    pub drain: Option<edgeless_api::introspection::NodeDrainStatus>,
 }
 
 impl ClientDesc {
@@ -44,6 +46,7 @@ impl ClientDesc {
             },
             capabilities: request.capabilities.clone(),
             cordoned: false,
+            drain: None,
         })
     }
 
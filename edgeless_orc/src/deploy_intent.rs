@@ -15,6 +15,10 @@ pub enum DeployIntent {
     /// The node is not cordoned anymore, i.e., the orchestrator can assign new
     /// resources or functions to it.
     Uncordon(edgeless_api::function_instance::NodeId),
+    /// The node becomes cordoned and all the functions and resources assigned
+    /// to it are migrated to other nodes, if possible. The drain is cancelled
+    /// if the node is uncordoned.
+    Drain(edgeless_api::function_instance::NodeId),
 }
 
 impl DeployIntent {
@@ -47,6 +51,12 @@ impl DeployIntent {
                         anyhow::bail!("ill-formed cordon value for node '{}': {}", tokens[2], value)
                     }
                 }
+                "drain" => {
+                    anyhow::ensure!(tokens.len() == 3);
+                    let node_id = uuid::Uuid::from_str(tokens[2])?;
+                    anyhow::ensure!(value == "yes", "ill-formed drain value for node '{}': {}", tokens[2], value);
+                    Ok(DeployIntent::Drain(node_id))
+                }
                 _ => anyhow::bail!("unknown intent type '{}'", tokens[1]),
             }
         } else {
@@ -59,6 +69,7 @@ impl DeployIntent {
             Self::Migrate(component, _) => format!("intent:migrate:{}", component),
             Self::Cordon(node_id) => format!("intent:cordon:{}", node_id),
             Self::Uncordon(node_id) => format!("intent:cordon:{}", node_id),
+            Self::Drain(node_id) => format!("intent:drain:{}", node_id),
         }
     }
 
@@ -67,6 +78,29 @@ impl DeployIntent {
             Self::Migrate(_, targets) => targets.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(","),
             Self::Cordon(_) => "yes".to_string(),
             Self::Uncordon(_) => "no".to_string(),
+            Self::Drain(_) => "yes".to_string(),
+        }
+    }
+}
+
+impl From<edgeless_api::introspection::DeployIntent> for DeployIntent {
+    fn from(intent: edgeless_api::introspection::DeployIntent) -> Self {
+        match intent {
+            edgeless_api::introspection::DeployIntent::Migrate(component, targets) => Self::Migrate(component, targets),
+            edgeless_api::introspection::DeployIntent::Cordon(node_id) => Self::Cordon(node_id),
+            edgeless_api::introspection::DeployIntent::Uncordon(node_id) => Self::Uncordon(node_id),
+            edgeless_api::introspection::DeployIntent::Drain(node_id) => Self::Drain(node_id),
+        }
+    }
+}
+
+impl From<DeployIntent> for edgeless_api::introspection::DeployIntent {
+    fn from(intent: DeployIntent) -> Self {
+        match intent {
+            DeployIntent::Migrate(component, targets) => Self::Migrate(component, targets),
+            DeployIntent::Cordon(node_id) => Self::Cordon(node_id),
+            DeployIntent::Uncordon(node_id) => Self::Uncordon(node_id),
+            DeployIntent::Drain(node_id) => Self::Drain(node_id),
         }
     }
 }
@@ -82,6 +116,7 @@ impl std::fmt::Display for DeployIntent {
             ),
             DeployIntent::Cordon(node_id) => write!(f, "cordon node_id {}", node_id),
             DeployIntent::Uncordon(node_id) => write!(f, "uncordon node_id {}", node_id),
+            DeployIntent::Drain(node_id) => write!(f, "drain node_id {}", node_id),
         }
     }
 }
@@ -105,23 +105,37 @@ impl DeploymentRequirements {
         capabilities: &edgeless_api::node_registration::NodeCapabilities,
         resource_providers: &std::collections::HashSet<String>,
     ) -> bool {
+        self.unmet(node_id, capabilities, resource_providers).is_empty()
+    }
+
+    /// Return a human-readable description of the requirements that are not
+    /// met by the given node, or an empty vector if the node is feasible.
+    ///
+    /// Parameters: same as `is_feasible()`.
+    pub fn unmet(
+        &self,
+        node_id: &uuid::Uuid,
+        capabilities: &edgeless_api::node_registration::NodeCapabilities,
+        resource_providers: &std::collections::HashSet<String>,
+    ) -> Vec<String> {
+        let mut ret = vec![];
         if !self.node_id_match_any.is_empty() && !self.node_id_match_any.contains(node_id) {
-            return false;
+            ret.push(String::from("node not in node_id_match_any"));
         }
         for label in self.label_match_all.iter() {
             if !capabilities.labels.contains(label) {
-                return false;
+                ret.push(format!("missing label '{}'", label));
             }
         }
         for provider in self.resource_match_all.iter() {
             if !resource_providers.contains(provider) {
-                return false;
+                ret.push(format!("missing resource provider '{}'", provider));
             }
         }
         match self.tee {
             crate::affinity_level::AffinityLevel::Required => {
                 if !capabilities.is_tee_running {
-                    return false;
+                    ret.push(String::from("TEE required"));
                 }
             }
             crate::affinity_level::AffinityLevel::NotRequired => {}
@@ -129,11 +143,11 @@ impl DeploymentRequirements {
         match self.tpm {
             crate::affinity_level::AffinityLevel::Required => {
                 if !capabilities.has_tpm {
-                    return false;
+                    ret.push(String::from("TPM required"));
                 }
             }
             crate::affinity_level::AffinityLevel::NotRequired => {}
         }
-        true
+        ret
     }
 }
@@ -14,6 +14,7 @@ pub struct DomainSubscriber {
 pub enum DomainSubscriberRequest {
     Update(Box<edgeless_api::domain_registration::DomainCapabilities>),
     RegisterOrcSender(futures::channel::mpsc::UnboundedSender<crate::orchestrator::OrchestratorRequest>),
+    UpdateHealth(Vec<edgeless_api::function_lifecycle::ComponentHealth>),
     Refresh(),
 }
 
@@ -78,6 +79,7 @@ impl DomainSubscriber {
         .await
         .domain_registration_api();
         let mut last_caps = edgeless_api::domain_registration::DomainCapabilities::default();
+        let mut last_health = vec![];
         let mut counter = 0;
         let mut orc_sender = None;
 
@@ -88,6 +90,10 @@ impl DomainSubscriber {
                     counter += 1;
                     last_caps = *new_caps;
                 }
+                DomainSubscriberRequest::UpdateHealth(new_health) => {
+                    log::debug!("Subscriber UpdateHealth {:?}", new_health);
+                    last_health = new_health;
+                }
                 DomainSubscriberRequest::RegisterOrcSender(new_orc_sender) => {
                     log::info!("Orchestrator registered with domain subscriber");
                     orc_sender = Some(new_orc_sender);
@@ -104,6 +110,7 @@ impl DomainSubscriber {
                         refresh_deadline: std::time::SystemTime::now() + std::time::Duration::from_secs(subscription_refresh_interval_sec * 2),
                         counter,
                         nonce,
+                        component_health: last_health.clone(),
                     };
                     match domain_registration_api.update_domain(update_domain_request).await {
                         Ok(response) => {
@@ -0,0 +1,176 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Power model of a node, which is linear with the number of function
+/// instances assigned to it. A node without function instances is assumed
+/// to be powered down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerModel {
+    /// Power consumed with no function instances, in mW.
+    pub idle_power: f64,
+    /// Additional power consumed by each function instance, in mW.
+    pub instance_power: f64,
+}
+
+impl PowerModel {
+    /// Marginal power cost, in mW, of assigning one more function instance
+    /// to a node that currently hosts `num_instances`.
+    pub fn marginal_cost(&self, num_instances: usize) -> f64 {
+        if num_instances == 0 {
+            self.idle_power + self.instance_power
+        } else {
+            self.instance_power
+        }
+    }
+}
+
+/// Active power measurements received from a node.
+#[derive(Debug, Default)]
+struct PowerMeasurements {
+    /// Lowest active power measured.
+    min_power: Option<f64>,
+    /// Last active power measured with the number of function instances
+    /// assigned to the node at that time.
+    last: Option<(f64, usize)>,
+}
+
+/// Estimator of the power models of the nodes.
+///
+/// The parameters of the model can be advertised by a node with the labels
+/// `power_idle_mw=<value>` and `power_instance_mw=<value>`, otherwise they
+/// are estimated from the active power measured by the node:
+///
+/// - the idle power is the lowest active power measured;
+/// - the power of a function instance is the difference between the last
+///   active power measured and the idle power, divided by the number of
+///   function instances assigned to the node at that time.
+///
+/// If there are no measurements, the default values are used.
+#[derive(Debug)]
+pub struct PowerEstimator {
+    default_model: PowerModel,
+    // key: node_id
+    measurements: std::collections::HashMap<edgeless_api::function_instance::NodeId, PowerMeasurements>,
+}
+
+/// Return the value of the label in the form `<name>=<value>`, if any.
+fn label_value(labels: &[String], name: &str) -> Option<f64> {
+    labels
+        .iter()
+        .filter_map(|label| label.split_once('='))
+        .find(|(key, _value)| *key == name)
+        .and_then(|(_key, value)| value.parse::<f64>().ok())
+        .filter(|value| *value >= 0.0)
+}
+
+impl PowerEstimator {
+    pub fn new(settings: &crate::EdgelessOrcEnergySettings) -> Self {
+        Self {
+            default_model: PowerModel {
+                idle_power: settings.default_idle_power_mw,
+                instance_power: settings.default_instance_power_mw,
+            },
+            measurements: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Add the active power, in mW, measured by a node hosting a given
+    /// number of function instances. Negative values, meaning that the
+    /// power is not measured by the node, are ignored.
+    pub fn update(&mut self, node_id: edgeless_api::function_instance::NodeId, active_power: i32, num_instances: usize) {
+        if active_power < 0 {
+            return;
+        }
+        let active_power = active_power as f64;
+        let measurements = self.measurements.entry(node_id).or_default();
+        measurements.min_power = Some(measurements.min_power.map_or(active_power, |x| x.min(active_power)));
+        measurements.last = Some((active_power, num_instances));
+    }
+
+    /// Remove the measurements of a node.
+    pub fn remove(&mut self, node_id: &edgeless_api::function_instance::NodeId) {
+        self.measurements.remove(node_id);
+    }
+
+    /// Return the current power model of a node with given labels.
+    pub fn model(&self, node_id: &edgeless_api::function_instance::NodeId, labels: &[String]) -> PowerModel {
+        let measurements = self.measurements.get(node_id);
+        let idle_power = label_value(labels, "power_idle_mw")
+            .or(measurements.and_then(|x| x.min_power))
+            .unwrap_or(self.default_model.idle_power);
+        let instance_power = label_value(labels, "power_instance_mw")
+            .or(measurements.and_then(|x| match x.last {
+                Some((power, num_instances)) if num_instances > 0 => Some((power - idle_power).max(0.0) / num_instances as f64),
+                _ => None,
+            }))
+            .unwrap_or(self.default_model.instance_power);
+        PowerModel { idle_power, instance_power }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_estimator() {
+        let mut estimator = PowerEstimator::new(&crate::EdgelessOrcEnergySettings {
+            default_idle_power_mw: 10000.0,
+            default_instance_power_mw: 1000.0,
+            ..Default::default()
+        });
+        let node_id = uuid::Uuid::new_v4();
+
+        // No measurements and no labels: default values.
+        let default_model = PowerModel {
+            idle_power: 10000.0,
+            instance_power: 1000.0,
+        };
+        assert_eq!(default_model, estimator.model(&node_id, &[]));
+        assert_eq!(11000.0, default_model.marginal_cost(0));
+        assert_eq!(1000.0, default_model.marginal_cost(3));
+
+        // Measurements.
+        estimator.update(node_id, -1, 0);
+        assert_eq!(default_model, estimator.model(&node_id, &[]));
+        estimator.update(node_id, 5000, 0);
+        assert_eq!(
+            PowerModel {
+                idle_power: 5000.0,
+                instance_power: 1000.0,
+            },
+            estimator.model(&node_id, &[])
+        );
+        estimator.update(node_id, 8000, 2);
+        assert_eq!(
+            PowerModel {
+                idle_power: 5000.0,
+                instance_power: 1500.0,
+            },
+            estimator.model(&node_id, &[])
+        );
+
+        // Labels override the measurements, invalid labels are ignored.
+        let labels = vec![
+            "stable".to_string(),
+            "power_idle_mw=2000".to_string(),
+            "power_instance_mw=not-a-number".to_string(),
+        ];
+        assert_eq!(
+            PowerModel {
+                idle_power: 2000.0,
+                instance_power: 3000.0,
+            },
+            estimator.model(&node_id, &labels)
+        );
+
+        estimator.remove(&node_id);
+        assert_eq!(
+            PowerModel {
+                idle_power: 2000.0,
+                instance_power: 1000.0,
+            },
+            estimator.model(&node_id, &labels)
+        );
+    }
+}
@@ -0,0 +1,179 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use edgeless_api::function_lifecycle::ProbeKind;
+
+/// Health check of a function instance to be executed on its node.
+pub struct HealthCheck {
+    pub lid: edgeless_api::function_instance::ComponentId,
+    pub instance_id: edgeless_api::function_instance::InstanceId,
+    /// Kinds of the probes in the request, in the same order.
+    pub probes: Vec<ProbeKind>,
+    pub client: Box<dyn edgeless_api::node_management::NodeManagementAPI>,
+    pub request: edgeless_api::node_management::FunctionHealthRequest,
+}
+
+/// Outcome of a health check.
+pub struct HealthCheckResult {
+    pub lid: edgeless_api::function_instance::ComponentId,
+    pub instance_id: edgeless_api::function_instance::InstanceId,
+    pub probes: Vec<ProbeKind>,
+    pub response: anyhow::Result<edgeless_api::node_management::FunctionHealthResponse>,
+}
+
+/// Execute the health checks concurrently.
+pub async fn execute(checks: Vec<HealthCheck>) -> Vec<HealthCheckResult> {
+    futures::future::join_all(checks.into_iter().map(|check| async move {
+        let mut client = check.client;
+        HealthCheckResult {
+            lid: check.lid,
+            instance_id: check.instance_id,
+            probes: check.probes,
+            response: client.check_function_health(check.request).await,
+        }
+    }))
+    .await
+}
+
+/// Health of a function instance, as tracked by the orchestrator.
+#[derive(Debug, Default)]
+pub struct InstanceHealth {
+    pub ready: Option<bool>,
+    pub live: Option<bool>,
+    /// Number of consecutive failures, by probe kind.
+    pub failures: std::collections::HashMap<ProbeKind, u32>,
+}
+
+/// Health of a logical function, as tracked by the orchestrator.
+#[derive(Debug, Default)]
+pub struct FunctionHealth {
+    /// Time of the last health check, if any.
+    pub last_check: Option<tokio::time::Instant>,
+    /// True if a health check is in progress.
+    pub pending: bool,
+    /// Number of restarts done by the nodes, by function instance.
+    pub node_restarts: std::collections::HashMap<edgeless_api::function_instance::InstanceId, u32>,
+    /// Number of restarts done by the orchestrator following liveness failures.
+    pub orc_restarts: u32,
+    /// Health of the probes, by function instance.
+    pub instances: std::collections::HashMap<edgeless_api::function_instance::InstanceId, InstanceHealth>,
+}
+
+impl FunctionHealth {
+    /// Return the health of the logical function: a probe is reported as
+    /// failing if it is failing for any of the function instances.
+    pub fn to_component_health(&self, lid: &edgeless_api::function_instance::ComponentId) -> edgeless_api::function_lifecycle::ComponentHealth {
+        let aggregate = |flags: Vec<Option<bool>>| {
+            if flags.contains(&Some(false)) {
+                Some(false)
+            } else if flags.contains(&Some(true)) {
+                Some(true)
+            } else {
+                None
+            }
+        };
+        edgeless_api::function_lifecycle::ComponentHealth {
+            component_id: *lid,
+            restarts: self.orc_restarts + self.node_restarts.values().sum::<u32>(),
+            ready: aggregate(self.instances.values().map(|instance| instance.ready).collect()),
+            live: aggregate(self.instances.values().map(|instance| instance.live).collect()),
+        }
+    }
+
+    /// Update the state of a probe of a function instance with its latest
+    /// outcome.
+    ///
+    /// Return true if the probe is (still) failing.
+    pub fn update_probe(
+        &mut self,
+        instance_id: &edgeless_api::function_instance::InstanceId,
+        kind: ProbeKind,
+        passed: bool,
+        failure_threshold: u32,
+    ) -> bool {
+        let instance = self.instances.entry(*instance_id).or_default();
+        let flag = match kind {
+            ProbeKind::Liveness => &mut instance.live,
+            ProbeKind::Readiness => &mut instance.ready,
+        };
+        if passed {
+            instance.failures.remove(&kind);
+            *flag = Some(true);
+            false
+        } else {
+            let failures = instance.failures.entry(kind).or_insert(0);
+            *failures += 1;
+            if *failures >= failure_threshold {
+                *flag = Some(false);
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    /// Forget the probes' state of the function instances that are not
+    /// active anymore.
+    pub fn retain_instances(&mut self, active: &[edgeless_api::function_instance::InstanceId]) {
+        self.instances.retain(|instance_id, _| active.contains(instance_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_health_update_probe() {
+        let lid = uuid::Uuid::new_v4();
+        let instance_id = edgeless_api::function_instance::InstanceId::new(uuid::Uuid::new_v4());
+        let mut health = FunctionHealth::default();
+        assert_eq!(
+            edgeless_api::function_lifecycle::ComponentHealth {
+                component_id: lid,
+                ..Default::default()
+            },
+            health.to_component_health(&lid)
+        );
+
+        assert!(!health.update_probe(&instance_id, ProbeKind::Readiness, true, 2));
+        assert_eq!(Some(true), health.to_component_health(&lid).ready);
+        assert!(!health.update_probe(&instance_id, ProbeKind::Readiness, false, 2));
+        assert_eq!(Some(true), health.to_component_health(&lid).ready);
+        assert!(health.update_probe(&instance_id, ProbeKind::Readiness, false, 2));
+        assert_eq!(Some(false), health.to_component_health(&lid).ready);
+        assert!(!health.update_probe(&instance_id, ProbeKind::Readiness, true, 2));
+        assert_eq!(Some(true), health.to_component_health(&lid).ready);
+        assert_eq!(None, health.to_component_health(&lid).live);
+
+        health.orc_restarts = 1;
+        health
+            .node_restarts
+            .insert(edgeless_api::function_instance::InstanceId::new(uuid::Uuid::new_v4()), 2);
+        assert_eq!(3, health.to_component_health(&lid).restarts);
+    }
+
+    #[test]
+    fn test_function_health_multiple_instances() {
+        let lid = uuid::Uuid::new_v4();
+        let failing = edgeless_api::function_instance::InstanceId::new(uuid::Uuid::new_v4());
+        let passing = edgeless_api::function_instance::InstanceId::new(uuid::Uuid::new_v4());
+        let mut health = FunctionHealth::default();
+
+        // The passing probe of an instance does not reset the consecutive
+        // failures of the other one.
+        for _ in 0..2 {
+            assert!(!health.update_probe(&failing, ProbeKind::Liveness, false, 3));
+            assert!(!health.update_probe(&passing, ProbeKind::Liveness, true, 3));
+        }
+        assert!(health.update_probe(&failing, ProbeKind::Liveness, false, 3));
+        assert!(!health.update_probe(&passing, ProbeKind::Liveness, true, 3));
+        assert_eq!(Some(false), health.instances.get(&failing).unwrap().live);
+        assert_eq!(Some(true), health.instances.get(&passing).unwrap().live);
+        assert_eq!(Some(false), health.to_component_health(&lid).live);
+
+        health.retain_instances(&[passing]);
+        assert_eq!(1, health.instances.len());
+        assert_eq!(Some(true), health.to_component_health(&lid).live);
+    }
+}
@@ -5,20 +5,26 @@
 
 pub mod active_instance;
 pub mod affinity_level;
+pub mod alerts;
 pub mod client_desc;
 pub mod deploy_intent;
 pub mod deployment_requirements;
 pub mod domain_info;
 pub mod domain_subscriber;
+pub mod energy;
+pub mod function_health;
+pub mod metrics_store;
 pub mod node_register;
 pub mod node_register_client;
 pub mod orchestration_logic;
 pub mod orchestrator;
 pub mod orchestrator_task;
 pub mod proxy;
+pub mod proxy_introspection;
 pub mod proxy_none;
 pub mod proxy_redis;
 pub mod resource_provider;
+pub mod slo_evaluator;
 
 use futures::join;
 
@@ -46,12 +52,96 @@ pub struct EdgelessOrcGeneralSettings {
     pub node_register_url: String,
     /// The CoAP URL of the node register.
     pub node_register_coap_url: Option<String>,
+    /// Authentication and authorization of the clients of the Introspection
+    /// API. If not present, all the requests are accepted.
+    #[serde(default)]
+    pub auth: Option<edgeless_api::grpc_impl::auth::AuthConfig>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct EdgelessOrcBaselineSettings {
     /// The orchestration strategy.
     pub orchestration_strategy: OrchestrationStrategy,
+    /// Settings of the energy-aware orchestration.
+    #[serde(default)]
+    pub energy: EdgelessOrcEnergySettings,
+    /// Settings of the alerts on the health of the nodes.
+    #[serde(default)]
+    pub alerts: EdgelessOrcAlertSettings,
+    /// Settings of the store of the historical metrics.
+    #[serde(default)]
+    pub metrics_store: EdgelessOrcMetricsStoreSettings,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct EdgelessOrcEnergySettings {
+    /// Maximum number of function instances per core that can be assigned
+    /// to a node by the EnergyAware strategy, unless all the feasible nodes
+    /// are full, and by the consolidation.
+    pub max_instances_per_core: u32,
+    /// Power consumed by an idle node, in mW, if not advertised by the node
+    /// nor measured.
+    pub default_idle_power_mw: f64,
+    /// Power consumed by a function instance, in mW, if not advertised by the
+    /// node nor measured.
+    pub default_instance_power_mw: f64,
+    /// Interval between consecutive consolidation passes, in seconds, which
+    /// migrate functions so that nodes can be powered down. 0 means disabled.
+    pub consolidation_interval_sec: u64,
+}
+
+impl Default for EdgelessOrcEnergySettings {
+    fn default() -> Self {
+        Self {
+            max_instances_per_core: 4,
+            default_idle_power_mw: 10000.0,
+            default_instance_power_mw: 1000.0,
+            consolidation_interval_sec: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct EdgelessOrcAlertSettings {
+    /// URL to which the alerts are notified via HTTP POST, if not empty.
+    pub webhook_url: String,
+    /// Logical identifier of the function or resource to which the alerts
+    /// are cast, if not empty.
+    pub cast_component_id: String,
+    /// Alert rules on the metrics of the nodes.
+    pub rules: Vec<alerts::AlertRule>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct EdgelessOrcMetricsStoreSettings {
+    /// Resolutions at which the health status of the nodes and the
+    /// performance samples of the functions are retained. No tiers means
+    /// that the store is disabled.
+    pub tiers: Vec<metrics_store::MetricsStoreTier>,
+}
+
+impl Default for EdgelessOrcMetricsStoreSettings {
+    fn default() -> Self {
+        Self {
+            tiers: vec![
+                metrics_store::MetricsStoreTier {
+                    resolution_sec: 10,
+                    retention_sec: 3600,
+                },
+                metrics_store::MetricsStoreTier {
+                    resolution_sec: 300,
+                    retention_sec: 86400,
+                },
+                metrics_store::MetricsStoreTier {
+                    resolution_sec: 3600,
+                    retention_sec: 604800,
+                },
+            ],
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -98,6 +188,9 @@ pub enum OrchestrationStrategy {
     /// RoundRobin traverses the list of available worker nodes in a fixed order
     /// and places new function instances according to this fixed order.
     RoundRobin,
+    /// EnergyAware selects the worker node with the lowest marginal power
+    /// cost of a new function instance, among those that are not full.
+    EnergyAware,
 }
 
 pub fn make_proxy(settings: EdgelessOrcProxySettings) -> std::sync::Arc<tokio::sync::Mutex<dyn proxy::Proxy>> {
@@ -112,7 +205,7 @@ pub fn make_proxy(settings: EdgelessOrcProxySettings) -> std::sync::Arc<tokio::s
     std::sync::Arc::new(tokio::sync::Mutex::new(proxy_none::ProxyNone {}))
 }
 
-pub async fn edgeless_orc_main(settings: EdgelessOrcSettings) {
+pub async fn edgeless_orc_main(settings: EdgelessOrcSettings) -> anyhow::Result<()> {
     log::info!("Starting Edgeless Orchestrator");
     log::debug!("Settings: {:?}", settings);
 
@@ -152,7 +245,8 @@ pub async fn edgeless_orc_main(settings: EdgelessOrcSettings) {
         orchestrator.get_api_client(),
         settings.general.orchestrator_url,
         Some(edgeless_api::grpc_impl::tls_config::TlsConfig::global_server().clone()),
-    );
+        settings.general.auth,
+    )?;
 
     // Create the node register.
     let (mut node_register, node_register_task, node_register_refresh_task) =
@@ -199,6 +293,8 @@ pub async fn edgeless_orc_main(settings: EdgelessOrcSettings) {
         subscriber_task,
         subscriber_refresh_task
     );
+
+    Ok(())
 }
 
 pub fn edgeless_orc_default_conf() -> String {
@@ -211,9 +307,13 @@ pub fn edgeless_orc_default_conf() -> String {
             orchestrator_url_announced: String::from("http://127.0.0.1:7003"),
             node_register_url: String::from("http://127.0.0.1:7004"),
             node_register_coap_url: None,
+            auth: None,
         },
         baseline: EdgelessOrcBaselineSettings {
             orchestration_strategy: OrchestrationStrategy::Random,
+            energy: EdgelessOrcEnergySettings::default(),
+            alerts: EdgelessOrcAlertSettings::default(),
+            metrics_store: EdgelessOrcMetricsStoreSettings::default(),
         },
         proxy: EdgelessOrcProxySettings {
             proxy_type: "None".to_string(),
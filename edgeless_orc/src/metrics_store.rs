@@ -0,0 +1,483 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+//! Embedded store of the historical metrics of the nodes and functions,
+//! downsampled at multiple resolutions with configurable retention.
+
+/// Resolution at which the metrics are retained.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct MetricsStoreTier {
+    /// Duration of the intervals over which the samples are aggregated, in s.
+    pub resolution_sec: u64,
+    /// Time for which the aggregated samples are retained, in s.
+    pub retention_sec: u64,
+}
+
+/// Identifier of a series of samples.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeriesKey {
+    /// Name of the metric.
+    pub metric: String,
+    /// Node from which the samples have been received.
+    pub node_id: edgeless_api::function_instance::NodeId,
+    /// Logical identifier of the function/resource, None for the metrics
+    /// of the node.
+    pub component_id: Option<edgeless_api::function_instance::ComponentId>,
+    /// Workflow of the function/resource, empty for the metrics of the node.
+    pub workflow_id: String,
+}
+
+impl SeriesKey {
+    /// Key of a metric of a node.
+    pub fn node(metric: &str, node_id: edgeless_api::function_instance::NodeId) -> Self {
+        Self {
+            metric: metric.to_string(),
+            node_id,
+            component_id: None,
+            workflow_id: String::default(),
+        }
+    }
+
+    /// Key of a metric of a function/resource hosted on a node.
+    pub fn component(
+        metric: &str,
+        node_id: edgeless_api::function_instance::NodeId,
+        component_id: edgeless_api::function_instance::ComponentId,
+        workflow_id: &str,
+    ) -> Self {
+        Self {
+            metric: metric.to_string(),
+            node_id,
+            component_id: Some(component_id),
+            workflow_id: workflow_id.to_string(),
+        }
+    }
+}
+
+/// Aggregate of the samples within an interval.
+#[derive(Debug, Clone, PartialEq)]
+struct Bucket {
+    /// Start of the interval: number of s since Unix epoch.
+    start: i64,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Bucket {
+    fn new(start: i64) -> Self {
+        Self {
+            start,
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn merge(&mut self, other: &Bucket) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    fn value(&self, aggregate: edgeless_api::introspection::MetricsAggregate) -> f64 {
+        match aggregate {
+            edgeless_api::introspection::MetricsAggregate::Avg => self.sum / self.count as f64,
+            edgeless_api::introspection::MetricsAggregate::Min => self.min,
+            edgeless_api::introspection::MetricsAggregate::Max => self.max,
+            edgeless_api::introspection::MetricsAggregate::Sum => self.sum,
+            edgeless_api::introspection::MetricsAggregate::Count => self.count as f64,
+        }
+    }
+}
+
+/// Samples of a series aggregated at the resolution of a tier.
+#[derive(Debug)]
+struct TierSeries {
+    resolution: i64,
+    retention: i64,
+    // sorted by start time
+    buckets: std::collections::VecDeque<Bucket>,
+}
+
+impl TierSeries {
+    fn new(tier: &MetricsStoreTier) -> Self {
+        Self {
+            resolution: tier.resolution_sec as i64,
+            retention: tier.retention_sec as i64,
+            buckets: std::collections::VecDeque::new(),
+        }
+    }
+
+    fn add(&mut self, timestamp: i64, value: f64) {
+        let start = timestamp.div_euclid(self.resolution) * self.resolution;
+        let pos = self.buckets.iter().rposition(|bucket| bucket.start <= start);
+        match pos {
+            Some(pos) if self.buckets[pos].start == start => self.buckets[pos].add(value),
+            pos => {
+                let mut bucket = Bucket::new(start);
+                bucket.add(value);
+                self.buckets.insert(pos.map_or(0, |pos| pos + 1), bucket);
+            }
+        }
+        if let Some(newest) = self.buckets.back() {
+            self.prune(newest.start + self.resolution);
+        }
+    }
+
+    /// Remove the buckets that are entirely older than the retention time.
+    fn prune(&mut self, now: i64) {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|bucket| bucket.start + self.resolution <= now - self.retention)
+        {
+            self.buckets.pop_front();
+        }
+    }
+}
+
+/// Store of the historical metrics.
+///
+/// Every sample added is aggregated (count, sum, min, max) into the interval
+/// containing it at every resolution, i.e., tier, configured, which retains
+/// the aggregates for the given time.
+/// A query is served from the finest tier that retains the whole time range
+/// requested, or from the coarsest one if none does.
+#[derive(Debug, Default)]
+pub struct MetricsStore {
+    // sorted by increasing resolution
+    tiers: Vec<MetricsStoreTier>,
+    series: std::collections::HashMap<SeriesKey, Vec<TierSeries>>,
+}
+
+impl MetricsStore {
+    /// Create a store with the given tiers. Tiers with null resolution or
+    /// retention are ignored. With no tiers the samples are discarded.
+    pub fn new(settings: &crate::EdgelessOrcMetricsStoreSettings) -> Self {
+        let mut tiers = settings
+            .tiers
+            .iter()
+            .filter(|tier| tier.resolution_sec > 0 && tier.retention_sec > 0)
+            .cloned()
+            .collect::<Vec<MetricsStoreTier>>();
+        tiers.sort_by_key(|tier| tier.resolution_sec);
+        Self {
+            tiers,
+            series: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Add a sample to a series, with its timestamp in s since Unix epoch.
+    pub fn add(&mut self, key: SeriesKey, timestamp: f64, value: f64) {
+        if self.tiers.is_empty() || !value.is_finite() {
+            return;
+        }
+        let tiers = &self.tiers;
+        let series = self.series.entry(key).or_insert_with(|| tiers.iter().map(TierSeries::new).collect());
+        for tier_series in series {
+            tier_series.add(timestamp.floor() as i64, value);
+        }
+    }
+
+    /// Remove the samples older than the retention time and the series left
+    /// without samples.
+    pub fn gc(&mut self, now: f64) {
+        let now = now.floor() as i64;
+        self.series.retain(|_key, series| {
+            for tier_series in series.iter_mut() {
+                tier_series.prune(now);
+            }
+            series.iter().any(|tier_series| !tier_series.buckets.is_empty())
+        });
+    }
+
+    /// Return the series of the aggregated values of a metric, one per group,
+    /// sorted by group.
+    ///
+    /// The duration of the intervals is rounded up to a multiple of the
+    /// resolution of the tier serving the query. Series that do not belong
+    /// to any group, e.g., metrics of the nodes when grouping by workflow,
+    /// are ignored.
+    pub fn query(&self, query: &edgeless_api::introspection::MetricsQuery, now: f64) -> Vec<edgeless_api::introspection::MetricsSeries> {
+        let now = now.floor() as i64;
+        let to = if query.to_sec > 0 { query.to_sec } else { now };
+        let tier = match self.tier(query.from_sec, now) {
+            Some(tier) => tier,
+            None => return vec![],
+        };
+        let resolution = self.tiers[tier].resolution_sec;
+        let step = (query.step_sec.max(1).div_ceil(resolution) * resolution) as i64;
+
+        let mut groups = std::collections::BTreeMap::<String, std::collections::BTreeMap<i64, Bucket>>::new();
+        for (key, series) in &self.series {
+            if key.metric != query.metric
+                || (!query.node_ids.is_empty() && !query.node_ids.contains(&key.node_id))
+                || (!query.workflow_ids.is_empty() && !query.workflow_ids.contains(&key.workflow_id))
+            {
+                continue;
+            }
+            let group = match query.group_by {
+                edgeless_api::introspection::MetricsGroupBy::None => String::default(),
+                edgeless_api::introspection::MetricsGroupBy::Node => key.node_id.to_string(),
+                edgeless_api::introspection::MetricsGroupBy::Workflow => {
+                    if key.workflow_id.is_empty() {
+                        continue;
+                    }
+                    key.workflow_id.clone()
+                }
+                edgeless_api::introspection::MetricsGroupBy::Component => match key.component_id {
+                    Some(component_id) => component_id.to_string(),
+                    None => continue,
+                },
+            };
+            let intervals = groups.entry(group).or_default();
+            for bucket in series[tier]
+                .buckets
+                .iter()
+                .filter(|bucket| bucket.start >= query.from_sec && bucket.start <= to)
+            {
+                let start = bucket.start.div_euclid(step) * step;
+                intervals.entry(start).or_insert_with(|| Bucket::new(start)).merge(bucket);
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(group, intervals)| edgeless_api::introspection::MetricsSeries {
+                group,
+                points: intervals
+                    .into_values()
+                    .map(|bucket| edgeless_api::introspection::MetricsPoint {
+                        timestamp_sec: bucket.start,
+                        value: bucket.value(query.aggregate),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Return the aggregated value of a metric of the nodes since `from_sec`,
+    /// for every node that reported it, e.g., to be used by the placement
+    /// policies.
+    pub fn node_values(
+        &self,
+        metric: &str,
+        from_sec: i64,
+        aggregate: edgeless_api::introspection::MetricsAggregate,
+        now: f64,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, f64> {
+        let tier = match self.tier(from_sec, now.floor() as i64) {
+            Some(tier) => tier,
+            None => return std::collections::HashMap::new(),
+        };
+        self.series
+            .iter()
+            .filter(|(key, _series)| key.metric == metric && key.component_id.is_none())
+            .filter_map(|(key, series)| {
+                let mut total = Bucket::new(from_sec);
+                for bucket in series[tier].buckets.iter().filter(|bucket| bucket.start >= from_sec) {
+                    total.merge(bucket);
+                }
+                (total.count > 0).then(|| (key.node_id, total.value(aggregate)))
+            })
+            .collect()
+    }
+
+    /// Return the index of the finest tier that retains the samples since
+    /// `from_sec`, or of the coarsest one if none does.
+    fn tier(&self, from_sec: i64, now: i64) -> Option<usize> {
+        self.tiers
+            .iter()
+            .position(|tier| now - tier.retention_sec as i64 <= from_sec)
+            .or(self.tiers.len().checked_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_store() -> MetricsStore {
+        MetricsStore::new(&crate::EdgelessOrcMetricsStoreSettings {
+            tiers: vec![
+                MetricsStoreTier {
+                    resolution_sec: 60,
+                    retention_sec: 3600,
+                },
+                MetricsStoreTier {
+                    resolution_sec: 10,
+                    retention_sec: 100,
+                },
+                MetricsStoreTier {
+                    resolution_sec: 0,
+                    retention_sec: 100,
+                },
+            ],
+        })
+    }
+
+    #[test]
+    fn test_metrics_store_downsampling() {
+        let mut store = new_store();
+        let node_id = uuid::Uuid::new_v4();
+
+        // One sample per second, with value equal to the timestamp.
+        for t in 1000..1200 {
+            store.add(SeriesKey::node("mem_used", node_id), t as f64 + 0.5, t as f64);
+        }
+        store.add(SeriesKey::node("mem_used", node_id), 1100.0, f64::NAN);
+
+        // Recent range: served at 10 s resolution, older samples are dropped.
+        let query =
+            |from_sec: i64, step_sec: u64, aggregate: edgeless_api::introspection::MetricsAggregate| edgeless_api::introspection::MetricsQuery {
+                metric: "mem_used".to_string(),
+                from_sec,
+                step_sec,
+                aggregate,
+                ..Default::default()
+            };
+        let series = store.query(&query(1150, 0, edgeless_api::introspection::MetricsAggregate::Avg), 1200.0);
+        assert_eq!(1, series.len());
+        assert_eq!("", series[0].group);
+        assert_eq!(5, series[0].points.len());
+        assert_eq!(
+            edgeless_api::introspection::MetricsPoint {
+                timestamp_sec: 1150,
+                value: 1154.5
+            },
+            series[0].points[0]
+        );
+
+        // Steps are rounded up to a multiple of the resolution.
+        let series = store.query(&query(1150, 15, edgeless_api::introspection::MetricsAggregate::Max), 1200.0);
+        assert_eq!(
+            vec![(1140, 1159.0), (1160, 1179.0), (1180, 1199.0)],
+            series[0].points.iter().map(|x| (x.timestamp_sec, x.value)).collect::<Vec<_>>()
+        );
+
+        // Old range: served at 60 s resolution.
+        let series = store.query(&query(0, 0, edgeless_api::introspection::MetricsAggregate::Count), 1200.0);
+        assert_eq!(
+            vec![(960, 20.0), (1020, 60.0), (1080, 60.0), (1140, 60.0)],
+            series[0].points.iter().map(|x| (x.timestamp_sec, x.value)).collect::<Vec<_>>()
+        );
+
+        // Unknown metric.
+        assert!(store.query(&edgeless_api::introspection::MetricsQuery::default(), 1200.0).is_empty());
+
+        // All the samples expire.
+        store.gc(10000.0);
+        assert!(
+            store
+                .query(&query(0, 0, edgeless_api::introspection::MetricsAggregate::Count), 10000.0)
+                .is_empty()
+        );
+        assert!(store.series.is_empty());
+    }
+
+    #[test]
+    fn test_metrics_store_group_by() {
+        let mut store = new_store();
+        let node_ids = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+        let lids = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+        let workflow_ids = ["wf-1", "wf-1", "wf-2"];
+        for (i, (lid, workflow_id)) in lids.iter().zip(workflow_ids).enumerate() {
+            store.add(
+                SeriesKey::component("function_execution_time", node_ids[i % 2], *lid, workflow_id),
+                1000.0,
+                (i + 1) as f64,
+            );
+        }
+        store.add(SeriesKey::node("function_execution_time", node_ids[0]), 1000.0, 100.0);
+
+        let query = |group_by: edgeless_api::introspection::MetricsGroupBy| edgeless_api::introspection::MetricsQuery {
+            metric: "function_execution_time".to_string(),
+            from_sec: 990,
+            aggregate: edgeless_api::introspection::MetricsAggregate::Sum,
+            group_by,
+            ..Default::default()
+        };
+        let values = |series: Vec<edgeless_api::introspection::MetricsSeries>| {
+            series
+                .into_iter()
+                .map(|x| (x.group, x.points.iter().map(|x| x.value).collect::<Vec<f64>>()))
+                .collect::<std::collections::HashMap<String, Vec<f64>>>()
+        };
+
+        assert_eq!(
+            std::collections::HashMap::from([(String::default(), vec![106.0])]),
+            values(store.query(&query(edgeless_api::introspection::MetricsGroupBy::None), 1000.0))
+        );
+        assert_eq!(
+            std::collections::HashMap::from([(node_ids[0].to_string(), vec![104.0]), (node_ids[1].to_string(), vec![2.0])]),
+            values(store.query(&query(edgeless_api::introspection::MetricsGroupBy::Node), 1000.0))
+        );
+        assert_eq!(
+            std::collections::HashMap::from([("wf-1".to_string(), vec![3.0]), ("wf-2".to_string(), vec![3.0])]),
+            values(store.query(&query(edgeless_api::introspection::MetricsGroupBy::Workflow), 1000.0))
+        );
+        assert_eq!(
+            3,
+            values(store.query(&query(edgeless_api::introspection::MetricsGroupBy::Component), 1000.0)).len()
+        );
+
+        // Filters.
+        let mut filtered_query = query(edgeless_api::introspection::MetricsGroupBy::Workflow);
+        filtered_query.node_ids = vec![node_ids[0]];
+        assert_eq!(
+            std::collections::HashMap::from([("wf-1".to_string(), vec![1.0]), ("wf-2".to_string(), vec![3.0])]),
+            values(store.query(&filtered_query, 1000.0))
+        );
+        filtered_query.workflow_ids = vec!["wf-2".to_string()];
+        assert_eq!(
+            std::collections::HashMap::from([("wf-2".to_string(), vec![3.0])]),
+            values(store.query(&filtered_query, 1000.0))
+        );
+    }
+
+    #[test]
+    fn test_metrics_store_node_values() {
+        let mut store = new_store();
+        let node_ids = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+        for t in 1000..1060 {
+            store.add(SeriesKey::node("load_avg_1", node_ids[0]), t as f64, if t < 1030 { 1.0 } else { 3.0 });
+            store.add(SeriesKey::node("load_avg_1", node_ids[1]), t as f64, 5.0);
+        }
+        store.add(SeriesKey::node("mem_used", node_ids[2]), 1050.0, 100.0);
+        store.add(
+            SeriesKey::component("load_avg_1", node_ids[2], uuid::Uuid::new_v4(), "wf-1"),
+            1050.0,
+            100.0,
+        );
+
+        assert_eq!(
+            std::collections::HashMap::from([(node_ids[0], 2.0), (node_ids[1], 5.0)]),
+            store.node_values("load_avg_1", 1000, edgeless_api::introspection::MetricsAggregate::Avg, 1060.0)
+        );
+        assert_eq!(
+            std::collections::HashMap::from([(node_ids[0], 3.0), (node_ids[1], 5.0)]),
+            store.node_values("load_avg_1", 1030, edgeless_api::introspection::MetricsAggregate::Avg, 1060.0)
+        );
+        assert!(
+            store
+                .node_values("load_avg_1", 2000, edgeless_api::introspection::MetricsAggregate::Avg, 2000.0)
+                .is_empty()
+        );
+        assert!(
+            MetricsStore::default()
+                .node_values("load_avg_1", 1000, edgeless_api::introspection::MetricsAggregate::Avg, 1060.0)
+                .is_empty()
+        );
+    }
+}
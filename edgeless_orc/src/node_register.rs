@@ -170,7 +170,29 @@ impl NodeRegister {
                         }
                         }
 
-                        // Push the dynamic data to the proxy.
+                        // Push the dynamic data to the orchestrator and proxy.
+                        let _ = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::UpdateNodeHealth(
+                            request.node_id,
+                            request.health_status.clone(),
+                        )).await;
+                        if !request.performance_samples.function_log_entries.is_empty() {
+                            let _ = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::UpdateNodeLogs(
+                                request.node_id,
+                                request.performance_samples.function_log_entries.clone(),
+                            )).await;
+                        }
+                        if !request.performance_samples.function_execution_times.is_empty()
+                            || !request.performance_samples.function_transfer_times.is_empty()
+                        {
+                            let _ = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::UpdateNodeLatencies(
+                                request.performance_samples.function_execution_times.clone(),
+                                request.performance_samples.function_transfer_times.clone(),
+                            )).await;
+                        }
+                        let _ = orchestrator_sender.send(crate::orchestrator::OrchestratorRequest::UpdateNodeMetrics(
+                            request.node_id,
+                            crate::alerts::node_metrics(&request.health_status, &request.performance_samples),
+                        )).await;
                         let mut proxy = proxy.lock().await;
                         proxy.push_node_health(&request.node_id, request.health_status);
                         proxy.push_performance_samples(&request.node_id, request.performance_samples);
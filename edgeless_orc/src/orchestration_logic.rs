@@ -5,9 +5,17 @@
 use rand::SeedableRng;
 use rand::distributions::Distribution;
 
+/// Used by EnergyAware, time window over which the load of the nodes is
+/// averaged, in s.
+const LOAD_WINDOW_SEC: i64 = 60;
+
 /// Keeps all the necessary state that is needed to make simple orchestration
 /// decisions. Provides convenience methods that can be used by the
 /// orchestrator.
+///
+/// It can be cloned to simulate orchestration decisions without affecting
+/// the state of the original, e.g., in a dry run.
+#[derive(Clone)]
 pub struct OrchestrationLogic {
     /// Orchestration strategy.
     orchestration_strategy: crate::OrchestrationStrategy,
@@ -23,13 +31,20 @@ pub struct OrchestrationLogic {
     resource_providers: Vec<std::collections::HashSet<String>>,
     /// Used by Random, pair of (weight, node_id).
     weights: Vec<f32>,
+    /// Used by EnergyAware, power model of each node.
+    power_models: std::collections::HashMap<uuid::Uuid, crate::energy::PowerModel>,
+    /// Used by EnergyAware, number of function instances assigned to each node.
+    num_instances: std::collections::HashMap<uuid::Uuid, usize>,
+    /// Used by EnergyAware, maximum number of function instances per core.
+    max_instances_per_core: u32,
 }
 
 impl OrchestrationLogic {
-    pub fn new(orchestration_strategy: crate::OrchestrationStrategy) -> Self {
+    pub fn new(orchestration_strategy: crate::OrchestrationStrategy, max_instances_per_core: u32) -> Self {
         match orchestration_strategy {
             crate::OrchestrationStrategy::Random => log::info!("Orchestration logic strategy: random"),
             crate::OrchestrationStrategy::RoundRobin => log::info!("Orchestration logic strategy: round-robin"),
+            crate::OrchestrationStrategy::EnergyAware => log::info!("Orchestration logic strategy: energy-aware"),
         };
 
         Self {
@@ -40,6 +55,9 @@ impl OrchestrationLogic {
             capabilities: vec![],
             resource_providers: vec![],
             weights: vec![],
+            power_models: std::collections::HashMap::new(),
+            num_instances: std::collections::HashMap::new(),
+            max_instances_per_core,
         }
     }
 
@@ -80,6 +98,32 @@ impl OrchestrationLogic {
         assert!(self.nodes.len() <= clients.len());
     }
 
+    /// Update the power models of the nodes and the number of function
+    /// instances currently assigned to them.
+    pub fn update_energy(
+        &mut self,
+        power_models: std::collections::HashMap<uuid::Uuid, crate::energy::PowerModel>,
+        num_instances: std::collections::HashMap<uuid::Uuid, usize>,
+    ) {
+        self.power_models = power_models;
+        self.num_instances = num_instances;
+    }
+
+    /// Account for a new function instance assigned to a node, until the
+    /// next update of the number of function instances.
+    ///
+    /// Selecting a node with `next()` does not assign the instance, since
+    /// the function instance may not be started there eventually.
+    pub fn add_instance(&mut self, node_id: uuid::Uuid) {
+        *self.num_instances.entry(node_id).or_default() += 1;
+    }
+
+    /// Maximum number of function instances that should be assigned to a node
+    /// with given capabilities.
+    pub fn node_capacity(&self, capabilities: &edgeless_api::node_registration::NodeCapabilities) -> usize {
+        (std::cmp::max(capabilities.num_cores, capabilities.num_cpus).max(1) * self.max_instances_per_core) as usize
+    }
+
     /// Filter only the nodes on which the given function can be deployed.
     pub fn feasible_nodes(&self, spawn_req: &edgeless_api::function_instance::SpawnFunctionRequest, nodes: &Vec<uuid::Uuid>) -> Vec<uuid::Uuid> {
         let mut candidates = vec![];
@@ -114,11 +158,40 @@ impl OrchestrationLogic {
         capabilities.runtimes.contains(&runtime.to_string()) && reqs.is_feasible(node_id, capabilities, resource_providers)
     }
 
+    /// Return the reasons why the given function cannot be deployed on any
+    /// of the nodes, or an empty vector if at least one node is feasible.
+    ///
+    /// A reason is reported only once, even if it applies to multiple nodes.
+    pub fn unmet_requirements(&self, spawn_req: &edgeless_api::function_instance::SpawnFunctionRequest) -> Vec<String> {
+        if self.nodes.is_empty() {
+            return vec![String::from("no nodes available")];
+        }
+        let reqs = crate::deployment_requirements::DeploymentRequirements::from_annotations(&spawn_req.annotations);
+        let mut ret = std::collections::BTreeSet::new();
+        for i in 0..self.nodes.len() {
+            let mut unmet = reqs.unmet(&self.nodes[i], &self.capabilities[i], &self.resource_providers[i]);
+            if !self.capabilities[i].runtimes.contains(&spawn_req.spec.function_type) {
+                unmet.push(format!("missing runtime '{}'", spawn_req.spec.function_type));
+            }
+            if unmet.is_empty() {
+                return vec![];
+            }
+            ret.extend(unmet);
+        }
+        ret.into_iter().collect()
+    }
+
     /// Select the next node on which a function instance should be spawned,
     /// based on a general orchestration strategy as defined in the settings.
     /// Always match the deployment requirements specified with the nodes'
     /// capabilities.
-    pub fn next(&mut self, spawn_req: &edgeless_api::function_instance::SpawnFunctionRequest) -> Option<uuid::Uuid> {
+    ///
+    /// The historical metrics of the nodes are read from `metrics_store`.
+    pub fn next(
+        &mut self,
+        spawn_req: &edgeless_api::function_instance::SpawnFunctionRequest,
+        metrics_store: &crate::metrics_store::MetricsStore,
+    ) -> Option<uuid::Uuid> {
         if self.nodes.is_empty() {
             return None;
         }
@@ -176,6 +249,52 @@ impl OrchestrationLogic {
                 }
                 None
             }
+            crate::OrchestrationStrategy::EnergyAware => {
+                // Among the feasible nodes, select the one with the lowest
+                // marginal power cost, preferring those that are not full
+                // and, with the same cost, those with lower recent load
+                // and then those with higher weight.
+                let now = chrono::Utc::now().timestamp_micros() as f64 * 1e-6;
+                let loads = metrics_store.node_values(
+                    "load_avg_1",
+                    now as i64 - LOAD_WINDOW_SEC,
+                    edgeless_api::introspection::MetricsAggregate::Avg,
+                    now,
+                );
+                let load = |i: usize| loads.get(&self.nodes[i]).copied().unwrap_or_default();
+                let mut best: Option<(usize, bool, f64)> = None;
+                for i in 0..self.nodes.len() {
+                    if !Self::is_node_feasible(
+                        &spawn_req.spec.function_type,
+                        &reqs,
+                        &self.nodes[i],
+                        &self.capabilities[i],
+                        &self.resource_providers[i],
+                    ) {
+                        continue;
+                    }
+                    let num_instances = self.num_instances.get(&self.nodes[i]).copied().unwrap_or_default();
+                    let full = num_instances >= self.node_capacity(&self.capabilities[i]);
+                    let cost = self
+                        .power_models
+                        .get(&self.nodes[i])
+                        .map(|model| model.marginal_cost(num_instances))
+                        .unwrap_or_default();
+                    let better = match best {
+                        None => true,
+                        Some((best_i, best_full, best_cost)) => {
+                            (full, cost) < (best_full, best_cost)
+                                || ((full, cost) == (best_full, best_cost)
+                                    && (load(i) < load(best_i) || (load(i) == load(best_i) && self.weights[i] > self.weights[best_i])))
+                        }
+                    };
+                    if better {
+                        best = Some((i, full, cost));
+                    }
+                }
+
+                best.map(|(i, _full, _cost)| self.nodes[i])
+            }
         }
     }
 }
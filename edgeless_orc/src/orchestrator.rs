@@ -47,23 +47,53 @@ pub enum OrchestratorRequest {
     ),
     StopResource(edgeless_api::function_instance::DomainManagedInstanceId),
     Patch(edgeless_api::common::PatchRequest),
+    Plan(
+        edgeless_api::workflow_instance::SpawnWorkflowRequest,
+        tokio::sync::oneshot::Sender<anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan>>,
+    ),
     AddNode(
         uuid::Uuid,
         crate::client_desc::ClientDesc,
         Vec<edgeless_api::node_registration::ResourceProviderSpecification>,
     ),
     DelNode(uuid::Uuid),
+    UpdateNodeHealth(uuid::Uuid, edgeless_api::node_registration::NodeHealthStatus),
     Refresh(
         // Reply Channel
         tokio::sync::oneshot::Sender<()>,
     ),
     Reset(),
+    HealthChecks(tokio::sync::oneshot::Sender<Vec<crate::function_health::HealthCheck>>),
+    HealthResults(Vec<crate::function_health::HealthCheckResult>),
+    Snapshot(tokio::sync::oneshot::Sender<edgeless_api::introspection::DomainSnapshot>),
+    AddDeployIntents(Vec<crate::deploy_intent::DeployIntent>),
+    UpdateNodeLogs(
+        uuid::Uuid,
+        std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::node_registration::FunctionLogEntry>>,
+    ),
+    UpdateNodeLatencies(
+        // function execution times
+        std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::node_registration::Sample>>,
+        // function transfer times
+        std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::node_registration::Sample>>,
+    ),
+    UpdateNodeMetrics(uuid::Uuid, std::collections::HashMap<String, f64>),
+    FunctionLogs(
+        edgeless_api::introspection::FunctionLogsRequest,
+        tokio::sync::oneshot::Sender<Vec<edgeless_api::introspection::FunctionLogRecord>>,
+    ),
+    QueryMetrics(
+        edgeless_api::introspection::MetricsQuery,
+        tokio::sync::oneshot::Sender<Vec<edgeless_api::introspection::MetricsSeries>>,
+    ),
 }
 
 pub struct OrchestratorClient {
     function_instance_client: Box<dyn edgeless_api::function_instance::FunctionInstanceAPI<edgeless_api::function_instance::DomainManagedInstanceId>>,
     resource_configuration_client:
         Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::DomainManagedInstanceId>>,
+    placement_client: Box<dyn edgeless_api::placement::PlacementAPI>,
+    introspection_client: Box<dyn edgeless_api::introspection::IntrospectionAPI>,
 }
 
 impl edgeless_api::outer::orc::OrchestratorAPI for OrchestratorClient {
@@ -78,6 +108,14 @@ impl edgeless_api::outer::orc::OrchestratorAPI for OrchestratorClient {
     ) -> Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::DomainManagedInstanceId>> {
         self.resource_configuration_client.clone()
     }
+
+    fn placement_api(&mut self) -> Box<dyn edgeless_api::placement::PlacementAPI> {
+        self.placement_client.clone()
+    }
+
+    fn introspection_api(&mut self) -> Box<dyn edgeless_api::introspection::IntrospectionAPI> {
+        self.introspection_client.clone()
+    }
 }
 
 #[derive(Clone)]
@@ -90,6 +128,16 @@ pub struct ResourceConfigurationClient {
     sender: futures::channel::mpsc::UnboundedSender<OrchestratorRequest>,
 }
 
+#[derive(Clone)]
+pub struct PlacementClient {
+    sender: futures::channel::mpsc::UnboundedSender<OrchestratorRequest>,
+}
+
+#[derive(Clone)]
+pub struct IntrospectionClient {
+    sender: futures::channel::mpsc::UnboundedSender<OrchestratorRequest>,
+}
+
 impl Orchestrator {
     pub async fn new(
         settings: crate::EdgelessOrcBaselineSettings,
@@ -120,6 +168,21 @@ impl Orchestrator {
                 let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<()>();
                 let _ = refresh_sender.send(OrchestratorRequest::Refresh(reply_sender)).await;
                 let _ = reply_receiver.await;
+
+                // Run the health checks that are due, if any, without
+                // blocking the orchestrator while waiting for the nodes.
+                let (checks_sender, checks_receiver) = tokio::sync::oneshot::channel();
+                let _ = refresh_sender.send(OrchestratorRequest::HealthChecks(checks_sender)).await;
+                if let Ok(checks) = checks_receiver.await {
+                    if !checks.is_empty() {
+                        let mut results_sender = refresh_sender.clone();
+                        tokio::spawn(async move {
+                            let results = crate::function_health::execute(checks).await;
+                            let _ = results_sender.send(OrchestratorRequest::HealthResults(results)).await;
+                        });
+                    }
+                }
+
                 tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             }
         });
@@ -135,6 +198,8 @@ impl Orchestrator {
         Box::new(OrchestratorClient {
             function_instance_client: Box::new(OrchestratorFunctionInstanceOrcClient { sender: self.sender.clone() }),
             resource_configuration_client: Box::new(ResourceConfigurationClient { sender: self.sender.clone() }),
+            placement_client: Box::new(PlacementClient { sender: self.sender.clone() }),
+            introspection_client: Box::new(IntrospectionClient { sender: self.sender.clone() }),
         })
     }
 }
@@ -220,3 +285,79 @@ impl edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api
         }
     }
 }
+
+#[async_trait::async_trait]
+impl edgeless_api::placement::PlacementAPI for PlacementClient {
+    async fn plan(
+        &mut self,
+        request: edgeless_api::workflow_instance::SpawnWorkflowRequest,
+    ) -> anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan> {
+        log::debug!("PlacementAPI::plan() {:?}", request.source_components());
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<anyhow::Result<edgeless_api::workflow_instance::WorkflowPlan>>();
+        if let Err(err) = self.sender.send(OrchestratorRequest::Plan(request, reply_sender)).await {
+            return Err(anyhow::anyhow!("Orchestrator channel error when planning a workflow: {}", err));
+        }
+        match reply_receiver.await {
+            Ok(plan) => plan,
+            Err(err) => Err(anyhow::anyhow!("Orchestrator channel error when planning a workflow: {}", err)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl edgeless_api::introspection::IntrospectionAPI for IntrospectionClient {
+    async fn snapshot(&mut self) -> anyhow::Result<edgeless_api::introspection::DomainSnapshot> {
+        log::debug!("IntrospectionAPI::snapshot()");
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<edgeless_api::introspection::DomainSnapshot>();
+        if let Err(err) = self.sender.send(OrchestratorRequest::Snapshot(reply_sender)).await {
+            return Err(anyhow::anyhow!("Orchestrator channel error when retrieving a snapshot: {}", err));
+        }
+        match reply_receiver.await {
+            Ok(snapshot) => Ok(snapshot),
+            Err(err) => Err(anyhow::anyhow!("Orchestrator channel error when retrieving a snapshot: {}", err)),
+        }
+    }
+
+    async fn add_deploy_intents(&mut self, intents: Vec<edgeless_api::introspection::DeployIntent>) -> anyhow::Result<()> {
+        log::debug!("IntrospectionAPI::add_deploy_intents() {:?}", intents);
+        match self
+            .sender
+            .send(OrchestratorRequest::AddDeployIntents(
+                intents.into_iter().map(crate::deploy_intent::DeployIntent::from).collect(),
+            ))
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => Err(anyhow::anyhow!("Orchestrator channel error when adding deploy intents: {}", err)),
+        }
+    }
+
+    async fn function_logs(
+        &mut self,
+        request: edgeless_api::introspection::FunctionLogsRequest,
+    ) -> anyhow::Result<Vec<edgeless_api::introspection::FunctionLogRecord>> {
+        log::debug!("IntrospectionAPI::function_logs() {:?}", request);
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<Vec<edgeless_api::introspection::FunctionLogRecord>>();
+        if let Err(err) = self.sender.send(OrchestratorRequest::FunctionLogs(request, reply_sender)).await {
+            return Err(anyhow::anyhow!("Orchestrator channel error when retrieving function logs: {}", err));
+        }
+        match reply_receiver.await {
+            Ok(records) => Ok(records),
+            Err(err) => Err(anyhow::anyhow!("Orchestrator channel error when retrieving function logs: {}", err)),
+        }
+    }
+    async fn query_metrics(
+        &mut self,
+        query: edgeless_api::introspection::MetricsQuery,
+    ) -> anyhow::Result<Vec<edgeless_api::introspection::MetricsSeries>> {
+        log::debug!("IntrospectionAPI::query_metrics() {:?}", query);
+        let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<Vec<edgeless_api::introspection::MetricsSeries>>();
+        if let Err(err) = self.sender.send(OrchestratorRequest::QueryMetrics(query, reply_sender)).await {
+            return Err(anyhow::anyhow!("Orchestrator channel error when querying metrics: {}", err));
+        }
+        match reply_receiver.await {
+            Ok(series) => Ok(series),
+            Err(err) => Err(anyhow::anyhow!("Orchestrator channel error when querying metrics: {}", err)),
+        }
+    }
+}
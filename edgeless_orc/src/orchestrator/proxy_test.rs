@@ -41,6 +41,11 @@ impl crate::proxy::Proxy for ProxyTest {
     fn fetch_node_healths(&mut self) -> crate::proxy::NodeHealthStatuses {
         std::collections::HashMap::new()
     }
+    fn fetch_node_drains(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, edgeless_api::introspection::NodeDrainStatus> {
+        std::collections::HashMap::new()
+    }
     fn fetch_performance_samples(&mut self) -> std::collections::HashMap<String, crate::proxy::PerformanceSamples> {
         std::collections::HashMap::new()
     }
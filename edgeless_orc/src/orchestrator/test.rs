@@ -32,6 +32,7 @@ pub enum MockAgentEvent {
     StopResource(edgeless_api::function_instance::InstanceId),
     PatchResource(edgeless_api::common::PatchRequest),
     Reset(),
+    CheckFunctionHealth(edgeless_api::node_management::FunctionHealthRequest),
 }
 
 pub struct MockNode {
@@ -103,6 +104,19 @@ impl edgeless_api::node_management::NodeManagementAPI for MockAgentAPI {
         self.sender.send(MockAgentEvent::Reset()).await.unwrap();
         Ok(())
     }
+    async fn check_function_health(
+        &mut self,
+        request: edgeless_api::node_management::FunctionHealthRequest,
+    ) -> anyhow::Result<edgeless_api::node_management::FunctionHealthResponse> {
+        // Probes with payload "fail" always fail, all others pass.
+        let probe_results = request.probes.iter().map(|probe| probe.payload != "fail").collect();
+        self.sender.send(MockAgentEvent::CheckFunctionHealth(request)).await.unwrap();
+        Ok(edgeless_api::node_management::FunctionHealthResponse {
+            running: true,
+            restarts: 0,
+            probe_results,
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -171,6 +185,7 @@ fn create_clients_resources(
             }) as Box<dyn edgeless_api::outer::agent::AgentAPI + Send>,
             capabilities,
             cordoned: false,
+            drain: None,
         };
 
         let mut resources = vec![];
@@ -191,6 +206,7 @@ fn create_clients_resources(
 struct SetupResult {
     fun_client: Box<dyn edgeless_api::function_instance::FunctionInstanceAPI<edgeless_api::function_instance::DomainManagedInstanceId>>,
     res_client: Box<dyn edgeless_api::resource_configuration::ResourceConfigurationAPI<edgeless_api::function_instance::DomainManagedInstanceId>>,
+    plan_client: Box<dyn edgeless_api::placement::PlacementAPI>,
     nodes: std::collections::HashMap<uuid::Uuid, futures::channel::mpsc::UnboundedReceiver<MockAgentEvent>>,
     stable_node_id: uuid::Uuid,
     subscriber_receiver: UnboundedReceiver<DomainSubscriberRequest>,
@@ -199,18 +215,25 @@ struct SetupResult {
 }
 
 async fn setup(num_nodes: u32, num_resources_per_node: u32) -> SetupResult {
-    let (mut nodes, client_descs_resources, stable_node_id) = create_clients_resources(num_nodes, num_resources_per_node);
-    let (subscriber_sender, subscriber_receiver) = futures::channel::mpsc::unbounded();
-
-    let proxy = std::sync::Arc::new(tokio::sync::Mutex::new(proxy_test::ProxyTest::default()));
-    let (mut orchestrator, orchestrator_task, _refresh_task) = Orchestrator::new(
+    setup_with_settings(
+        num_nodes,
+        num_resources_per_node,
         crate::EdgelessOrcBaselineSettings {
             orchestration_strategy: crate::OrchestrationStrategy::Random,
+            energy: crate::EdgelessOrcEnergySettings::default(),
+            alerts: crate::EdgelessOrcAlertSettings::default(),
+            metrics_store: crate::EdgelessOrcMetricsStoreSettings::default(),
         },
-        proxy.clone(),
-        subscriber_sender,
     )
-    .await;
+    .await
+}
+
+async fn setup_with_settings(num_nodes: u32, num_resources_per_node: u32, settings: crate::EdgelessOrcBaselineSettings) -> SetupResult {
+    let (mut nodes, client_descs_resources, stable_node_id) = create_clients_resources(num_nodes, num_resources_per_node);
+    let (subscriber_sender, subscriber_receiver) = futures::channel::mpsc::unbounded();
+
+    let proxy = std::sync::Arc::new(tokio::sync::Mutex::new(proxy_test::ProxyTest::default()));
+    let (mut orchestrator, orchestrator_task, _refresh_task) = Orchestrator::new(settings, proxy.clone(), subscriber_sender).await;
     tokio::spawn(orchestrator_task);
 
     let mut orchestrator_sender = orchestrator.get_sender();
@@ -225,6 +248,7 @@ async fn setup(num_nodes: u32, num_resources_per_node: u32) -> SetupResult {
     SetupResult {
         fun_client: orchestrator.get_api_client().function_instance_api(),
         res_client: orchestrator.get_api_client().resource_configuration_api(),
+        plan_client: orchestrator.get_api_client().placement_api(),
         nodes,
         stable_node_id,
         subscriber_receiver,
@@ -244,6 +268,7 @@ fn event_to_string(event: &MockAgentEvent) -> &'static str {
         MockAgentEvent::PatchResource(_) => "patch-resource",
         MockAgentEvent::UpdatePeers(_) => "update-peers",
         MockAgentEvent::Reset() => "reset",
+        MockAgentEvent::CheckFunctionHealth(_) => "check-function-health",
     }
 }
 
@@ -332,6 +357,46 @@ async fn clear_events(receivers: &mut std::collections::HashMap<uuid::Uuid, futu
     }
 }
 
+async fn snapshot(orc_sender: &mut UnboundedSender<OrchestratorRequest>) -> edgeless_api::introspection::DomainSnapshot {
+    let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel();
+    orc_sender.send(OrchestratorRequest::Snapshot(reply_sender)).await.unwrap();
+    reply_receiver.await.unwrap()
+}
+
+async fn function_logs(
+    orc_sender: &mut UnboundedSender<OrchestratorRequest>,
+    lid: uuid::Uuid,
+    since_sequence: u64,
+) -> Vec<edgeless_api::introspection::FunctionLogRecord> {
+    let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel();
+    orc_sender
+        .send(OrchestratorRequest::FunctionLogs(
+            edgeless_api::introspection::FunctionLogsRequest {
+                component_ids: vec![lid],
+                since_sequence,
+            },
+            reply_sender,
+        ))
+        .await
+        .unwrap();
+    reply_receiver.await.unwrap()
+}
+
+async fn query_metrics(
+    orc_sender: &mut UnboundedSender<OrchestratorRequest>,
+    query: edgeless_api::introspection::MetricsQuery,
+) -> Vec<edgeless_api::introspection::MetricsSeries> {
+    let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel();
+    orc_sender.send(OrchestratorRequest::QueryMetrics(query, reply_sender)).await.unwrap();
+    reply_receiver.await.unwrap()
+}
+
+async fn refresh(orc_sender: &mut UnboundedSender<OrchestratorRequest>) {
+    let (reply_sender, reply_receiver) = tokio::sync::oneshot::channel::<()>();
+    let _ = orc_sender.send(OrchestratorRequest::Refresh(reply_sender)).await;
+    let _ = reply_receiver.await;
+}
+
 fn make_spawn_function_request(class_id: &str) -> edgeless_api::function_instance::SpawnFunctionRequest {
     edgeless_api::function_instance::SpawnFunctionRequest {
         spec: FunctionClassSpecification {
@@ -1330,6 +1395,7 @@ async fn test_orc_recreate_fun_after_disconnect() {
                 }) as Box<dyn edgeless_api::outer::agent::AgentAPI + Send>,
                 capabilities,
                 cordoned: false,
+                drain: None,
             },
             vec![],
         ))
@@ -1670,6 +1736,7 @@ async fn test_orc_invalid_migration() {
             }) as Box<dyn edgeless_api::outer::agent::AgentAPI + Send>,
             capabilities,
             cordoned: false,
+            drain: None,
         };
 
         let mut resources = vec![];
@@ -1695,6 +1762,9 @@ async fn test_orc_invalid_migration() {
     let (mut orchestrator, orchestrator_task, _refresh_task) = Orchestrator::new(
         crate::EdgelessOrcBaselineSettings {
             orchestration_strategy: crate::OrchestrationStrategy::Random,
+            energy: crate::EdgelessOrcEnergySettings::default(),
+            alerts: crate::EdgelessOrcAlertSettings::default(),
+            metrics_store: crate::EdgelessOrcMetricsStoreSettings::default(),
         },
         proxy.clone(),
         subscriber_sender,
@@ -2040,6 +2110,404 @@ async fn test_orc_reset() {
     no_function_event(&mut setup.nodes).await;
 }
 
+#[tokio::test]
+async fn test_orc_function_health() {
+    let mut setup = setup(1, 0).await;
+    let (_node_id, mock_node_receiver) = setup.nodes.iter_mut().next().unwrap();
+
+    // Start a function with a failing liveness probe and a readiness probe.
+    let mut spawn_req = make_spawn_function_request("fc-1");
+    spawn_req.annotations = std::collections::HashMap::from([
+        ("liveness_probe".to_string(), "fail".to_string()),
+        ("readiness_probe".to_string(), "ready?".to_string()),
+        ("probe_period_ms".to_string(), "1".to_string()),
+        ("probe_failure_threshold".to_string(), "2".to_string()),
+    ]);
+    match setup.fun_client.start(spawn_req.clone()).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(_) => {}
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    let int_instance_id = match wait_for_function_event(mock_node_receiver).await {
+        MockAgentEvent::StartFunction((new_instance_id, _)) => new_instance_id,
+        _ => panic!("wrong event received"),
+    };
+
+    // Run the health checks twice: the function instance is restarted only
+    // after the liveness probe has failed a number of times equal to
+    // the failure threshold.
+    for i in 0..2 {
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        let (checks_sender, checks_receiver) = tokio::sync::oneshot::channel();
+        setup.orc_sender.send(OrchestratorRequest::HealthChecks(checks_sender)).await.unwrap();
+        let checks = checks_receiver.await.unwrap();
+        assert_eq!(1, checks.len());
+        assert_eq!(int_instance_id, checks[0].instance_id);
+        assert_eq!(
+            vec![
+                edgeless_api::function_lifecycle::ProbeKind::Liveness,
+                edgeless_api::function_lifecycle::ProbeKind::Readiness
+            ],
+            checks[0].probes
+        );
+
+        let results = crate::function_health::execute(checks).await;
+        match wait_for_function_event(setup.nodes.values_mut().next().unwrap()).await {
+            MockAgentEvent::CheckFunctionHealth(request) => {
+                assert_eq!(int_instance_id.function_id, request.function_id);
+                assert_eq!(2, request.probes.len());
+            }
+            _ => panic!("wrong event received"),
+        }
+        setup.orc_sender.send(OrchestratorRequest::HealthResults(results)).await.unwrap();
+
+        if i == 0 {
+            no_function_event(&mut setup.nodes).await;
+        }
+    }
+
+    let mock_node_receiver = setup.nodes.values_mut().next().unwrap();
+    match wait_for_function_event(mock_node_receiver).await {
+        MockAgentEvent::StopFunction(instance_id) => assert_eq!(int_instance_id, instance_id),
+        _ => panic!("wrong event received"),
+    }
+    match wait_for_function_event(mock_node_receiver).await {
+        MockAgentEvent::StartFunction((new_instance_id, spawn_req_rcvd)) => {
+            assert_ne!(int_instance_id, new_instance_id);
+            assert_eq!(spawn_req, spawn_req_rcvd);
+        }
+        _ => panic!("wrong event received"),
+    }
+
+    // Check the health reported to the domain subscriber.
+    let mut last_health = vec![];
+    while let Ok(Some(event)) = setup.subscriber_receiver.try_next() {
+        if let DomainSubscriberRequest::UpdateHealth(health) = event {
+            last_health = health;
+        }
+    }
+    assert_eq!(1, last_health.len());
+    assert_eq!(1, last_health[0].restarts);
+    assert_eq!(Some(true), last_health[0].ready);
+    assert_eq!(Some(false), last_health[0].live);
+}
+
+#[tokio::test]
+async fn test_orc_function_health_multiple_instances() {
+    let mut setup = setup(2, 0).await;
+
+    let mut spawn_req = make_spawn_function_request("fc-1");
+    spawn_req.annotations = std::collections::HashMap::from([
+        ("liveness_probe".to_string(), "alive?".to_string()),
+        ("probe_failure_threshold".to_string(), "2".to_string()),
+    ]);
+    let lid = match setup.fun_client.start(spawn_req.clone()).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    let (node_id, event) = wait_for_event_multiple(&mut setup.nodes).await;
+    let failing_instance_id = match event {
+        MockAgentEvent::StartFunction((new_instance_id, _)) => new_instance_id,
+        _ => panic!("wrong event received"),
+    };
+    let other_node_id = *setup.nodes.keys().find(|x| **x != node_id).unwrap();
+    let passing_instance_id = edgeless_api::function_instance::InstanceId::new(other_node_id);
+
+    // Collect the health checks, which are not executed: their results are
+    // injected below.
+    let (checks_sender, checks_receiver) = tokio::sync::oneshot::channel();
+    setup.orc_sender.send(OrchestratorRequest::HealthChecks(checks_sender)).await.unwrap();
+    assert_eq!(1, checks_receiver.await.unwrap().len());
+
+    // The liveness probe of one instance fails in every round, while that
+    // of the other instance passes: the consecutive failures of the former
+    // must not be reset by the latter.
+    let result = |instance_id: edgeless_api::function_instance::InstanceId, passed: bool| crate::function_health::HealthCheckResult {
+        lid,
+        instance_id,
+        probes: vec![edgeless_api::function_lifecycle::ProbeKind::Liveness],
+        response: Ok(edgeless_api::node_management::FunctionHealthResponse {
+            running: true,
+            restarts: 0,
+            probe_results: vec![passed],
+        }),
+    };
+    for i in 0..2 {
+        setup
+            .orc_sender
+            .send(OrchestratorRequest::HealthResults(vec![
+                result(failing_instance_id, false),
+                result(passing_instance_id, true),
+            ]))
+            .await
+            .unwrap();
+
+        if i == 0 {
+            no_function_event(&mut setup.nodes).await;
+        }
+    }
+
+    // Only the failing instance is restarted.
+    let mock_node_receiver = setup.nodes.get_mut(&node_id).unwrap();
+    match wait_for_function_event(mock_node_receiver).await {
+        MockAgentEvent::StopFunction(instance_id) => assert_eq!(failing_instance_id, instance_id),
+        _ => panic!("wrong event received"),
+    }
+    let (_node_id, event) = wait_for_event_multiple(&mut setup.nodes).await;
+    match event {
+        MockAgentEvent::StartFunction((new_instance_id, spawn_req_rcvd)) => {
+            assert_ne!(failing_instance_id, new_instance_id);
+            assert_eq!(spawn_req, spawn_req_rcvd);
+        }
+        _ => panic!("wrong event received"),
+    }
+    no_function_event(&mut setup.nodes).await;
+}
+
+#[tokio::test]
+async fn test_orc_introspection() {
+    let mut setup = setup(2, 1).await;
+
+    let domain_snapshot = snapshot(&mut setup.orc_sender).await;
+    assert_eq!(2, domain_snapshot.nodes.len());
+    assert_eq!(2, domain_snapshot.resource_providers.len());
+    assert!(domain_snapshot.instances.is_empty());
+    assert!(domain_snapshot.nodes.iter().all(|node| !node.cordoned && node.health.is_none()));
+
+    // Start a function and check that it appears in the snapshot, without
+    // its code.
+    let spawn_req = make_spawn_function_request("fc-1");
+    let lid = match setup.fun_client.start(spawn_req.clone()).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    let (node_id, event) = wait_for_event_multiple(&mut setup.nodes).await;
+    let int_instance_id = match event {
+        MockAgentEvent::StartFunction((new_instance_id, _)) => new_instance_id,
+        _ => panic!("wrong event received"),
+    };
+    assert_eq!(node_id, int_instance_id.node_id);
+
+    let domain_snapshot = snapshot(&mut setup.orc_sender).await;
+    assert_eq!(1, domain_snapshot.instances.len());
+    assert_eq!(lid, domain_snapshot.instances[0].component_id);
+    assert_eq!(vec![int_instance_id], domain_snapshot.instances[0].instances);
+    match &domain_snapshot.instances[0].spec {
+        edgeless_api::introspection::ActiveInstanceSpec::Function(spawn_req_rcvd) => {
+            assert_eq!(spawn_req.spec.id, spawn_req_rcvd.spec.id);
+            assert!(spawn_req_rcvd.spec.binary.is_none());
+        }
+        _ => panic!("wrong instance spec"),
+    }
+
+    // Cordon the node through a deploy intent, which is applied at the
+    // next refresh.
+    setup
+        .orc_sender
+        .send(OrchestratorRequest::AddDeployIntents(vec![deploy_intent::DeployIntent::Cordon(node_id)]))
+        .await
+        .unwrap();
+    refresh(&mut setup.orc_sender).await;
+
+    let domain_snapshot = snapshot(&mut setup.orc_sender).await;
+    for node in domain_snapshot.nodes {
+        assert_eq!(node.node_id == node_id, node.cordoned);
+    }
+}
+
+#[tokio::test]
+async fn test_orc_function_logs() {
+    let mut setup = setup(1, 0).await;
+
+    let lid = match setup.fun_client.start(make_spawn_function_request("fc-1")).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    let (node_id, event) = wait_for_event_multiple(&mut setup.nodes).await;
+    let int_instance_id = match event {
+        MockAgentEvent::StartFunction((new_instance_id, _)) => new_instance_id,
+        _ => panic!("wrong event received"),
+    };
+
+    let new_entry = |timestamp_sec: i64, message: &str| edgeless_api::node_registration::FunctionLogEntry {
+        timestamp_sec,
+        timestamp_ns: 0,
+        target: "target".to_string(),
+        message: message.to_string(),
+        level: "Info".to_string(),
+        ..Default::default()
+    };
+
+    // The entries of unknown function instances are ignored.
+    setup
+        .orc_sender
+        .send(OrchestratorRequest::UpdateNodeLogs(
+            node_id,
+            std::collections::HashMap::from([
+                (int_instance_id.function_id, vec![new_entry(10, "first"), new_entry(20, "second")]),
+                (uuid::Uuid::new_v4(), vec![new_entry(15, "unknown")]),
+            ]),
+        ))
+        .await
+        .unwrap();
+
+    let records = function_logs(&mut setup.orc_sender, lid, 0).await;
+    assert_eq!(
+        vec!["first", "second"],
+        records.iter().map(|x| x.entry.message.as_str()).collect::<Vec<&str>>()
+    );
+    assert!(records.iter().all(|x| x.component_id == lid && x.node_id == node_id));
+    assert!(records[0].sequence < records[1].sequence);
+
+    let last_sequence = records[1].sequence;
+    let records = function_logs(&mut setup.orc_sender, lid, records[0].sequence).await;
+    assert_eq!(vec!["second"], records.iter().map(|x| x.entry.message.as_str()).collect::<Vec<&str>>());
+
+    // The entries received later are returned even if their timestamp is
+    // older than that of the entries already returned.
+    setup
+        .orc_sender
+        .send(OrchestratorRequest::UpdateNodeLogs(
+            node_id,
+            std::collections::HashMap::from([(int_instance_id.function_id, vec![new_entry(5, "late")])]),
+        ))
+        .await
+        .unwrap();
+    let records = function_logs(&mut setup.orc_sender, lid, last_sequence).await;
+    assert_eq!(vec!["late"], records.iter().map(|x| x.entry.message.as_str()).collect::<Vec<&str>>());
+
+    // The entries are removed when the function is stopped.
+    setup.fun_client.stop(lid).await.unwrap();
+    setup
+        .orc_sender
+        .send(OrchestratorRequest::UpdateNodeLogs(
+            node_id,
+            std::collections::HashMap::from([(int_instance_id.function_id, vec![new_entry(30, "third")])]),
+        ))
+        .await
+        .unwrap();
+    assert!(function_logs(&mut setup.orc_sender, lid, 0).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_orc_drain_node() {
+    let mut setup = setup(2, 1).await;
+    let stable_node_id = setup.stable_node_id;
+
+    // Start a function that can only run on the stable node, plus other
+    // functions and resources without deployment requirements.
+    let mut spawn_req = make_spawn_function_request("fc-pinned");
+    spawn_req.annotations.insert("label_match_all".to_string(), "stable".to_string());
+    let pinned_lid = match setup.fun_client.start(spawn_req).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    match wait_for_event_multiple(&mut setup.nodes).await {
+        (node_id, MockAgentEvent::StartFunction(_)) => assert_eq!(stable_node_id, node_id),
+        _ => panic!("wrong event received"),
+    }
+
+    let mut num_movable_on_stable = 0;
+    for i in 0..6 {
+        if i % 2 == 0 {
+            match setup.fun_client.start(make_spawn_function_request("fc-1")).await.unwrap() {
+                edgeless_api::common::StartComponentResponse::InstanceId(_) => {}
+                edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+            }
+        } else {
+            match setup.res_client.start(make_start_resource_request("rc-1")).await.unwrap() {
+                edgeless_api::common::StartComponentResponse::InstanceId(_) => {}
+                edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+            }
+        }
+        match wait_for_event_multiple(&mut setup.nodes).await {
+            (node_id, MockAgentEvent::StartFunction(_)) | (node_id, MockAgentEvent::StartResource(_)) => {
+                if node_id == stable_node_id {
+                    num_movable_on_stable += 1;
+                }
+            }
+            _ => panic!("wrong event received"),
+        }
+    }
+
+    // Drain the stable node: all the functions/resources but the pinned one
+    // are migrated to the other node.
+    setup
+        .proxy
+        .lock()
+        .await
+        .add_deploy_intents(vec![deploy_intent::DeployIntent::Drain(stable_node_id)]);
+    refresh(&mut setup.orc_sender).await;
+
+    let mut num_started = 0;
+    let mut num_stopped = 0;
+    while let Some((node_id, event)) = wait_for_events_if_any(&mut setup.nodes).await {
+        match event {
+            MockAgentEvent::StartFunction(_) | MockAgentEvent::StartResource(_) => {
+                assert_ne!(stable_node_id, node_id);
+                num_started += 1;
+            }
+            MockAgentEvent::StopFunction(_) | MockAgentEvent::StopResource(_) => {
+                assert_eq!(stable_node_id, node_id);
+                num_stopped += 1;
+            }
+            _ => panic!("unexpected event"),
+        }
+    }
+    assert_eq!(num_movable_on_stable, num_started);
+    assert_eq!(num_movable_on_stable, num_stopped);
+
+    let expected_drain = edgeless_api::introspection::NodeDrainStatus {
+        migrated: num_movable_on_stable,
+        remaining: vec![pinned_lid],
+        safe_to_remove: false,
+    };
+    let domain_snapshot = snapshot(&mut setup.orc_sender).await;
+    let stable_node = domain_snapshot.nodes.iter().find(|node| node.node_id == stable_node_id).unwrap();
+    assert!(stable_node.cordoned);
+    assert_eq!(Some(expected_drain.clone()), stable_node.drain);
+
+    // Nothing changes at the next refresh.
+    refresh(&mut setup.orc_sender).await;
+    no_function_event(&mut setup.nodes).await;
+
+    // Stop the pinned function: the node becomes safe to remove.
+    setup.fun_client.stop(pinned_lid).await.unwrap();
+    match wait_for_event_multiple(&mut setup.nodes).await {
+        (node_id, MockAgentEvent::StopFunction(_)) => assert_eq!(stable_node_id, node_id),
+        _ => panic!("wrong event received"),
+    }
+    refresh(&mut setup.orc_sender).await;
+
+    let domain_snapshot = snapshot(&mut setup.orc_sender).await;
+    let stable_node = domain_snapshot.nodes.iter().find(|node| node.node_id == stable_node_id).unwrap();
+    assert_eq!(
+        Some(edgeless_api::introspection::NodeDrainStatus {
+            migrated: num_movable_on_stable,
+            remaining: vec![],
+            safe_to_remove: true,
+        }),
+        stable_node.drain
+    );
+    assert!(
+        domain_snapshot
+            .instances
+            .iter()
+            .all(|instance| instance.instances.iter().all(|x| x.node_id != stable_node_id))
+    );
+
+    // Uncordoning the node cancels the drain.
+    setup
+        .proxy
+        .lock()
+        .await
+        .add_deploy_intents(vec![deploy_intent::DeployIntent::Uncordon(stable_node_id)]);
+    refresh(&mut setup.orc_sender).await;
+    let domain_snapshot = snapshot(&mut setup.orc_sender).await;
+    let stable_node = domain_snapshot.nodes.iter().find(|node| node.node_id == stable_node_id).unwrap();
+    assert!(!stable_node.cordoned);
+    assert!(stable_node.drain.is_none());
+}
+
 #[tokio::test]
 #[serial_test::serial]
 async fn test_orc_update_domain_capabilities() {
@@ -2074,6 +2542,7 @@ async fn test_orc_update_domain_capabilities() {
                     num_events += 1;
                 }
                 DomainSubscriberRequest::RegisterOrcSender(_) => {}
+                DomainSubscriberRequest::UpdateHealth(_) => {}
                 DomainSubscriberRequest::Refresh() => {
                     panic!("unexpected refresh event received");
                 }
@@ -2118,7 +2587,7 @@ fn test_orc_deployment_requirements() {
 
 #[test]
 fn test_orc_feasible_nodes() {
-    let mut logic = crate::orchestration_logic::OrchestrationLogic::new(crate::OrchestrationStrategy::Random);
+    let mut logic = crate::orchestration_logic::OrchestrationLogic::new(crate::OrchestrationStrategy::Random, 4);
 
     // No nodes
     let mut fun1_req = make_spawn_function_request("fun");
@@ -2155,3 +2624,504 @@ fn test_orc_feasible_nodes() {
     fun1_req.spec.function_type = "non-existing-runtime".to_string();
     assert!(logic.feasible_nodes(&fun1_req, &all_nodes).is_empty());
 }
+
+#[test]
+fn test_orc_energy_aware_strategy() {
+    let mut logic = crate::orchestration_logic::OrchestrationLogic::new(crate::OrchestrationStrategy::EnergyAware, 2);
+    let fun_req = make_spawn_function_request("fun");
+    let mut metrics_store = crate::metrics_store::MetricsStore::default();
+    assert!(logic.next(&fun_req, &metrics_store).is_none());
+
+    let (nodes, mut client_descs_resources, _stable_node_id) = create_clients_resources(3, 0);
+    let mut client_descs = std::collections::HashMap::new();
+    for node_id in nodes.keys() {
+        let client_desc = client_descs_resources.remove(&node_id).unwrap().0;
+        client_descs.insert(*node_id, client_desc);
+    }
+    logic.update_nodes(&client_descs, &std::collections::HashMap::new());
+
+    // Node A has a high idle power, node B a low idle power but a high
+    // power per function instance, node C is in between.
+    // All the nodes can host two function instances.
+    let node_ids = client_descs.keys().cloned().collect::<Vec<uuid::Uuid>>();
+    let (a, b, c) = (node_ids[0], node_ids[1], node_ids[2]);
+    let new_model = |idle_power: f64, instance_power: f64| crate::energy::PowerModel { idle_power, instance_power };
+    let power_models = std::collections::HashMap::from([
+        (a, new_model(10000.0, 1000.0)),
+        (b, new_model(2000.0, 3000.0)),
+        (c, new_model(5000.0, 500.0)),
+    ]);
+    logic.update_energy(power_models.clone(), std::collections::HashMap::new());
+
+    // Selecting a node does not assign the function instance to it.
+    assert_eq!(Some(b), logic.next(&fun_req, &metrics_store));
+    assert_eq!(Some(b), logic.next(&fun_req, &metrics_store));
+
+    let selected = (0..7)
+        .map(|_| {
+            let node_id = logic.next(&fun_req, &metrics_store).unwrap();
+            logic.add_instance(node_id);
+            node_id
+        })
+        .collect::<Vec<uuid::Uuid>>();
+    assert_eq!(vec![b, b, c, c, a, a, c], selected);
+
+    // The number of function instances is reset at every update.
+    logic.update_energy(power_models, std::collections::HashMap::from([(a, 1)]));
+    assert_eq!(Some(a), logic.next(&fun_req, &metrics_store));
+
+    // With the same power models, the node with the lowest recent load is
+    // selected, as reported in the metrics store.
+    let power_models = node_ids.iter().map(|node_id| (*node_id, new_model(5000.0, 500.0))).collect();
+    logic.update_energy(power_models, std::collections::HashMap::new());
+    metrics_store = crate::metrics_store::MetricsStore::new(&crate::EdgelessOrcMetricsStoreSettings::default());
+    let now = chrono::Utc::now().timestamp_micros() as f64 * 1e-6;
+    for (node_id, load) in [(a, 2.0), (b, 3.0), (c, 1.0)] {
+        metrics_store.add(crate::metrics_store::SeriesKey::node("load_avg_1", node_id), now - 1.0, load);
+    }
+    assert_eq!(Some(c), logic.next(&fun_req, &metrics_store));
+}
+
+#[tokio::test]
+async fn test_orc_energy_consolidation() {
+    let mut setup = setup_with_settings(
+        2,
+        0,
+        crate::EdgelessOrcBaselineSettings {
+            orchestration_strategy: crate::OrchestrationStrategy::Random,
+            energy: crate::EdgelessOrcEnergySettings {
+                consolidation_interval_sec: 1,
+                ..Default::default()
+            },
+            alerts: crate::EdgelessOrcAlertSettings::default(),
+            metrics_store: crate::EdgelessOrcMetricsStoreSettings::default(),
+        },
+    )
+    .await;
+    let node_ids = setup.nodes.keys().cloned().collect::<Vec<uuid::Uuid>>();
+
+    // Start one function on each node, by cordoning the other one.
+    for (i, node_id) in node_ids.iter().enumerate() {
+        setup.proxy.lock().await.add_deploy_intents(vec![
+            deploy_intent::DeployIntent::Uncordon(*node_id),
+            deploy_intent::DeployIntent::Cordon(node_ids[1 - i]),
+        ]);
+        refresh(&mut setup.orc_sender).await;
+        match setup.fun_client.start(make_spawn_function_request("fc")).await.unwrap() {
+            edgeless_api::common::StartComponentResponse::InstanceId(_) => {}
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        }
+        match wait_for_event_multiple(&mut setup.nodes).await {
+            (event_node_id, MockAgentEvent::StartFunction(_)) => assert_eq!(*node_id, event_node_id),
+            _ => panic!("wrong event received"),
+        }
+    }
+    setup
+        .proxy
+        .lock()
+        .await
+        .add_deploy_intents(node_ids.iter().map(|node_id| deploy_intent::DeployIntent::Uncordon(*node_id)).collect());
+    refresh(&mut setup.orc_sender).await;
+
+    // One of the two functions is migrated to the other node.
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+    refresh(&mut setup.orc_sender).await;
+    let mut start_node_id = None;
+    let mut stop_node_id = None;
+    for _ in 0..2 {
+        match wait_for_event_multiple(&mut setup.nodes).await {
+            (node_id, MockAgentEvent::StartFunction(_)) => start_node_id = Some(node_id),
+            (node_id, MockAgentEvent::StopFunction(_)) => stop_node_id = Some(node_id),
+            _ => panic!("wrong event received"),
+        }
+    }
+    assert!(start_node_id.is_some() && stop_node_id.is_some());
+    assert_ne!(start_node_id, stop_node_id);
+
+    // There is nothing else to consolidate.
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+    refresh(&mut setup.orc_sender).await;
+    no_function_event(&mut setup.nodes).await;
+
+    let snapshot = snapshot(&mut setup.orc_sender).await;
+    assert_eq!(2, snapshot.instances.len());
+    assert!(
+        snapshot
+            .instances
+            .iter()
+            .all(|x| x.instances.iter().all(|y| Some(y.node_id) == start_node_id))
+    );
+}
+
+#[tokio::test]
+async fn test_orc_plan() {
+    let mut setup = setup(3, 1).await;
+
+    let make_function = |name: &str, function_type: &str, annotations: std::collections::HashMap<String, String>| {
+        let spawn_req = make_spawn_function_request(name);
+        edgeless_api::workflow_instance::WorkflowFunction {
+            name: name.to_string(),
+            class_specification: FunctionClassSpecification {
+                function_type: function_type.to_string(),
+                ..spawn_req.spec
+            },
+            output_mapping: std::collections::HashMap::new(),
+            annotations,
+        }
+    };
+    let make_resource = |name: &str, class_type: &str| edgeless_api::workflow_instance::WorkflowResource {
+        name: name.to_string(),
+        class_type: class_type.to_string(),
+        output_mapping: std::collections::HashMap::new(),
+        configurations: std::collections::HashMap::new(),
+    };
+
+    let request = edgeless_api::workflow_instance::SpawnWorkflowRequest {
+        functions: vec![
+            make_function("f1", "RUST_WASM", std::collections::HashMap::new()),
+            make_function(
+                "f2",
+                "RUST_WASM",
+                std::collections::HashMap::from([("label_match_all".to_string(), "stable".to_string())]),
+            ),
+            make_function(
+                "f3",
+                "RUST_WASM",
+                std::collections::HashMap::from([
+                    ("label_match_all".to_string(), "gpu".to_string()),
+                    ("tee".to_string(), "required".to_string()),
+                ]),
+            ),
+            make_function("f4", "CONTAINER", std::collections::HashMap::new()),
+        ],
+        resources: vec![make_resource("r1", "rc-1"), make_resource("r2", "rc-666")],
+        annotations: std::collections::HashMap::new(),
+    };
+
+    let plan = setup.plan_client.plan(request).await.unwrap();
+    assert!(!plan.is_feasible());
+    assert_eq!(6, plan.components.len());
+
+    // Nothing is started in a dry run.
+    no_function_event(&mut setup.nodes).await;
+
+    let placement = |name: &str| plan.components.iter().find(|x| x.name == name).unwrap().clone();
+
+    let f1 = placement("f1");
+    assert!(f1.is_satisfiable());
+    assert_eq!(3, f1.feasible_nodes.len());
+    assert!(f1.feasible_nodes.contains(&f1.node_id.unwrap()));
+
+    let f2 = placement("f2");
+    assert!(f2.is_satisfiable());
+    assert_eq!(vec![setup.stable_node_id], f2.feasible_nodes);
+    assert_eq!(Some(setup.stable_node_id), f2.node_id);
+
+    let f3 = placement("f3");
+    assert!(!f3.is_satisfiable());
+    assert!(f3.feasible_nodes.is_empty());
+    assert_eq!(vec!["TEE required".to_string(), "missing label 'gpu'".to_string()], f3.reasons);
+
+    let f4 = placement("f4");
+    assert!(!f4.is_satisfiable());
+    assert_eq!(vec!["missing runtime 'CONTAINER'".to_string()], f4.reasons);
+
+    let r1 = placement("r1");
+    assert!(r1.is_satisfiable());
+    assert_eq!(3, r1.feasible_nodes.len());
+
+    let r2 = placement("r2");
+    assert!(!r2.is_satisfiable());
+    assert_eq!(vec!["no resource provider of class 'rc-666'".to_string()], r2.reasons);
+
+    // The decisions made in a dry run do not affect those of the actual
+    // deployments, which are the same if nothing changes in between.
+    for _ in 0..10 {
+        let request = edgeless_api::workflow_instance::SpawnWorkflowRequest {
+            functions: vec![],
+            resources: vec![make_resource("r1", "rc-1")],
+            annotations: std::collections::HashMap::new(),
+        };
+        let plan = setup.plan_client.plan(request).await.unwrap();
+        match setup.res_client.start(make_start_resource_request("rc-1")).await.unwrap() {
+            edgeless_api::common::StartComponentResponse::InstanceId(_) => {}
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        }
+        match wait_for_event_multiple(&mut setup.nodes).await {
+            (node_id, MockAgentEvent::StartResource(_)) => assert_eq!(Some(node_id), plan.components[0].node_id),
+            _ => panic!("wrong event received"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_orc_latency_slo() {
+    let mut setup = setup(2, 0).await;
+    let stable_node_id = setup.stable_node_id;
+
+    // Deploy the following workflow:
+    //
+    // f0 --> f1
+    //
+    // where f0 has a latency SLO and is pinned to the stable node, while f1
+    // runs on the other node, which is achieved by cordoning the stable node
+    // before starting f1.
+    let mut spawn_req = make_spawn_function_request("fc-0");
+    spawn_req.annotations.insert("label_match_all".to_string(), "stable".to_string());
+    spawn_req.annotations.insert("slo_latency_ms".to_string(), "50".to_string());
+    spawn_req.annotations.insert("slo_window_sec".to_string(), "1".to_string());
+    let mut lids = vec![];
+    let mut pids = vec![];
+    for i in 0..2 {
+        if i == 1 {
+            spawn_req = make_spawn_function_request("fc-1");
+            setup
+                .proxy
+                .lock()
+                .await
+                .add_deploy_intents(vec![deploy_intent::DeployIntent::Cordon(stable_node_id)]);
+            refresh(&mut setup.orc_sender).await;
+        }
+        lids.push(match setup.fun_client.start(spawn_req.clone()).await.unwrap() {
+            edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+            edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+        });
+        match wait_for_event_multiple(&mut setup.nodes).await {
+            (node_id, MockAgentEvent::StartFunction((new_instance_id, _))) => {
+                assert_eq!(i == 0, node_id == stable_node_id);
+                pids.push(new_instance_id.function_id);
+            }
+            _ => panic!("wrong event received"),
+        }
+    }
+    setup
+        .proxy
+        .lock()
+        .await
+        .add_deploy_intents(vec![deploy_intent::DeployIntent::Uncordon(stable_node_id)]);
+    refresh(&mut setup.orc_sender).await;
+
+    setup
+        .fun_client
+        .patch(edgeless_api::common::PatchRequest {
+            function_id: lids[0],
+            output_mapping: std::collections::HashMap::from([(
+                "out".to_string(),
+                edgeless_api::function_instance::InstanceId {
+                    node_id: uuid::Uuid::nil(),
+                    function_id: lids[1],
+                },
+            )]),
+        })
+        .await
+        .unwrap();
+    clear_events(&mut setup.nodes).await;
+
+    let new_samples = |value: f64| {
+        let now = chrono::Utc::now();
+        vec![edgeless_api::node_registration::Sample {
+            timestamp_sec: now.timestamp(),
+            timestamp_ns: now.timestamp_subsec_nanos(),
+            sample: value,
+        }]
+    };
+    let update_latencies = |transfer: f64| {
+        OrchestratorRequest::UpdateNodeLatencies(
+            std::collections::HashMap::from([(pids[0], new_samples(0.001)), (pids[1], new_samples(0.001))]),
+            std::collections::HashMap::from([(pids[0], new_samples(0.001)), (pids[1], new_samples(transfer))]),
+        )
+    };
+
+    // The SLO is satisfied: nothing happens.
+    setup.orc_sender.send(update_latencies(0.010)).await.unwrap();
+    refresh(&mut setup.orc_sender).await;
+    no_function_event(&mut setup.nodes).await;
+
+    // The SLO is violated, but not for long enough.
+    setup.orc_sender.send(update_latencies(0.100)).await.unwrap();
+    refresh(&mut setup.orc_sender).await;
+    no_function_event(&mut setup.nodes).await;
+
+    // The violation is sustained: f1 is migrated to the node of f0.
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+    setup.orc_sender.send(update_latencies(0.100)).await.unwrap();
+    refresh(&mut setup.orc_sender).await;
+
+    let mut num_patches = 0;
+    for _ in 0..3 {
+        let (node_id, event) = wait_for_event_multiple(&mut setup.nodes).await;
+        match event {
+            MockAgentEvent::StartFunction((_new_instance_id, spawn_req_rcvd)) => {
+                assert_eq!("fc-1", spawn_req_rcvd.spec.id);
+                assert_eq!(stable_node_id, node_id);
+            }
+            MockAgentEvent::StopFunction(instance_id) => {
+                assert_eq!(pids[1], instance_id.function_id);
+                assert_ne!(stable_node_id, node_id);
+            }
+            MockAgentEvent::PatchFunction(patch_request) => {
+                assert_eq!(pids[0], patch_request.function_id);
+                num_patches += 1;
+            }
+            _ => panic!("unexpected event"),
+        }
+    }
+    assert_eq!(1, num_patches);
+
+    no_function_event(&mut setup.nodes).await;
+}
+
+#[tokio::test]
+async fn test_orc_alert_cordon() {
+    let mut setup = setup_with_settings(
+        2,
+        0,
+        crate::EdgelessOrcBaselineSettings {
+            orchestration_strategy: crate::OrchestrationStrategy::Random,
+            energy: crate::EdgelessOrcEnergySettings::default(),
+            alerts: crate::EdgelessOrcAlertSettings {
+                rules: vec![crate::alerts::AlertRule {
+                    name: "memory-pressure".to_string(),
+                    metric: "mem_available".to_string(),
+                    rate: false,
+                    condition: crate::alerts::AlertCondition::Below,
+                    threshold: 1000.0,
+                    for_sec: 0,
+                    notify: vec![crate::alerts::AlertSink::Log],
+                    cordon: true,
+                }],
+                ..Default::default()
+            },
+            metrics_store: crate::EdgelessOrcMetricsStoreSettings::default(),
+        },
+    )
+    .await;
+    let stable_node_id = setup.stable_node_id;
+    let update_metrics = |node_id: uuid::Uuid, mem_available: f64| {
+        OrchestratorRequest::UpdateNodeMetrics(node_id, std::collections::HashMap::from([("mem_available".to_string(), mem_available)]))
+    };
+    let cordoned = |domain_snapshot: edgeless_api::introspection::DomainSnapshot| {
+        domain_snapshot
+            .nodes
+            .iter()
+            .filter(|node| node.cordoned)
+            .map(|node| node.node_id)
+            .collect::<Vec<uuid::Uuid>>()
+    };
+
+    // The rule does not fire: no node is cordoned.
+    setup.orc_sender.send(update_metrics(stable_node_id, 2000.0)).await.unwrap();
+    refresh(&mut setup.orc_sender).await;
+    assert!(cordoned(snapshot(&mut setup.orc_sender).await).is_empty());
+
+    // The rule fires on the stable node, which is cordoned.
+    setup.orc_sender.send(update_metrics(stable_node_id, 500.0)).await.unwrap();
+    refresh(&mut setup.orc_sender).await;
+    assert_eq!(vec![stable_node_id], cordoned(snapshot(&mut setup.orc_sender).await));
+
+    // New functions are started on the other node.
+    match setup.fun_client.start(make_spawn_function_request("fc")).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(_) => {}
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    }
+    match wait_for_event_multiple(&mut setup.nodes).await {
+        (node_id, MockAgentEvent::StartFunction(_)) => assert_ne!(stable_node_id, node_id),
+        _ => panic!("wrong event received"),
+    }
+
+    // The node remains cordoned after the alert is resolved.
+    setup.orc_sender.send(update_metrics(stable_node_id, 2000.0)).await.unwrap();
+    refresh(&mut setup.orc_sender).await;
+    assert_eq!(vec![stable_node_id], cordoned(snapshot(&mut setup.orc_sender).await));
+}
+
+#[tokio::test]
+async fn test_orc_metrics_store() {
+    let mut setup = setup(2, 0).await;
+    let node_ids = setup.nodes.keys().cloned().collect::<Vec<uuid::Uuid>>();
+    let from_sec = chrono::Utc::now().timestamp() - 60;
+
+    // Health status of the nodes.
+    for (i, node_id) in node_ids.iter().enumerate() {
+        setup
+            .orc_sender
+            .send(OrchestratorRequest::UpdateNodeHealth(
+                *node_id,
+                edgeless_api::node_registration::NodeHealthStatus {
+                    mem_used: 1000 * (i as i32 + 1),
+                    ..edgeless_api::node_registration::NodeHealthStatus::invalid()
+                },
+            ))
+            .await
+            .unwrap();
+    }
+    let series = query_metrics(
+        &mut setup.orc_sender,
+        edgeless_api::introspection::MetricsQuery {
+            metric: "mem_used".to_string(),
+            from_sec,
+            step_sec: 3600,
+            aggregate: edgeless_api::introspection::MetricsAggregate::Max,
+            group_by: edgeless_api::introspection::MetricsGroupBy::Node,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(2, series.len());
+    for (i, node_id) in node_ids.iter().enumerate() {
+        let node_series = series.iter().find(|x| x.group == node_id.to_string()).unwrap();
+        assert_eq!(
+            vec![1000.0 * (i + 1) as f64],
+            node_series.points.iter().map(|x| x.value).collect::<Vec<f64>>()
+        );
+    }
+
+    // Execution times of a function.
+    let lid = match setup.fun_client.start(make_spawn_function_request("fc")).await.unwrap() {
+        edgeless_api::common::StartComponentResponse::InstanceId(id) => id,
+        edgeless_api::common::StartComponentResponse::ResponseError(err) => panic!("{}", err),
+    };
+    let pid = match wait_for_event_multiple(&mut setup.nodes).await {
+        (_node_id, MockAgentEvent::StartFunction((new_instance_id, _))) => new_instance_id.function_id,
+        _ => panic!("wrong event received"),
+    };
+    let now = chrono::Utc::now();
+    let samples = [0.1, 0.2, 0.6]
+        .iter()
+        .map(|value| edgeless_api::node_registration::Sample {
+            timestamp_sec: now.timestamp(),
+            timestamp_ns: 0,
+            sample: *value,
+        })
+        .collect::<Vec<edgeless_api::node_registration::Sample>>();
+    setup
+        .orc_sender
+        .send(OrchestratorRequest::UpdateNodeLatencies(
+            std::collections::HashMap::from([(pid, samples)]),
+            std::collections::HashMap::new(),
+        ))
+        .await
+        .unwrap();
+
+    let query = |group_by: edgeless_api::introspection::MetricsGroupBy| edgeless_api::introspection::MetricsQuery {
+        metric: "function_execution_time".to_string(),
+        from_sec,
+        step_sec: 3600,
+        aggregate: edgeless_api::introspection::MetricsAggregate::Count,
+        group_by,
+        ..Default::default()
+    };
+    let series = query_metrics(&mut setup.orc_sender, query(edgeless_api::introspection::MetricsGroupBy::Component)).await;
+    assert_eq!(1, series.len());
+    assert_eq!(lid.to_string(), series[0].group);
+    assert_eq!(vec![3.0], series[0].points.iter().map(|x| x.value).collect::<Vec<f64>>());
+
+    let series = query_metrics(&mut setup.orc_sender, query(edgeless_api::introspection::MetricsGroupBy::Workflow)).await;
+    assert_eq!(1, series.len());
+    assert_eq!("workflow_1", series[0].group);
+
+    // No samples of unknown metrics.
+    let mut unknown_query = query(edgeless_api::introspection::MetricsGroupBy::None);
+    unknown_query.metric = "unknown".to_string();
+    assert!(query_metrics(&mut setup.orc_sender, unknown_query).await.is_empty());
+}
@@ -10,6 +10,9 @@ use rand::seq::SliceRandom;
 
 use crate::active_instance::ActiveInstance;
 
+/// Maximum number of log entries retained for each function instance.
+const MAX_LOG_ENTRIES: usize = 1000;
+
 #[derive(Debug)]
 enum Pid {
     // 0: node_id, pid
@@ -48,6 +51,38 @@ pub(crate) struct OrchestratorTask {
     //        value: lid (target function)
     dependency_graph: std::collections::HashMap<uuid::Uuid, std::collections::HashMap<String, uuid::Uuid>>,
     dependency_graph_changed: bool,
+    // health of the functions with probes or a restart policy
+    // key: lid
+    function_health: std::collections::HashMap<uuid::Uuid, crate::function_health::FunctionHealth>,
+    // last health status received from the nodes
+    // key: node_id
+    node_health: std::collections::HashMap<uuid::Uuid, edgeless_api::node_registration::NodeHealthStatus>,
+    // deploy intents received via the introspection API, consumed at
+    // the next refresh together with those retrieved from the proxy
+    pending_deploy_intents: Vec<crate::deploy_intent::DeployIntent>,
+    // most recent log entries of the active functions, as received from
+    // the nodes, in order of arrival
+    // key: lid
+    // value: (sequence number, node_id, log entry)
+    function_logs:
+        std::collections::HashMap<uuid::Uuid, std::collections::VecDeque<(u64, uuid::Uuid, edgeless_api::node_registration::FunctionLogEntry)>>,
+    // sequence number assigned to the next log entry received, starting
+    // from the current time in microseconds so that it keeps increasing
+    // across restarts of the orchestrator
+    next_log_sequence: u64,
+    // evaluator of the latency SLOs of the active functions
+    slo_evaluator: crate::slo_evaluator::SloEvaluator,
+    energy_settings: crate::EdgelessOrcEnergySettings,
+    // estimator of the power models of the nodes
+    power_estimator: crate::energy::PowerEstimator,
+    // time of the last consolidation pass, if any
+    last_consolidation: Option<tokio::time::Instant>,
+    alert_settings: crate::EdgelessOrcAlertSettings,
+    // engine evaluating the alert rules on the metrics of the nodes
+    alert_engine: crate::alerts::AlertEngine,
+    // historical health status of the nodes and performance samples of
+    // the functions
+    metrics_store: crate::metrics_store::MetricsStore,
 }
 
 impl OrchestratorTask {
@@ -63,12 +98,27 @@ impl OrchestratorTask {
             resource_providers: std::collections::HashMap::new(),
             proxy,
             subscriber_sender,
-            orchestration_logic: crate::orchestration_logic::OrchestrationLogic::new(orchestrator_settings.orchestration_strategy),
+            orchestration_logic: crate::orchestration_logic::OrchestrationLogic::new(
+                orchestrator_settings.orchestration_strategy,
+                orchestrator_settings.energy.max_instances_per_core,
+            ),
             rng: rand::rngs::StdRng::from_entropy(),
             active_instances: std::collections::HashMap::new(),
             active_instances_changed: false,
             dependency_graph: std::collections::HashMap::new(),
             dependency_graph_changed: false,
+            function_health: std::collections::HashMap::new(),
+            node_health: std::collections::HashMap::new(),
+            pending_deploy_intents: vec![],
+            function_logs: std::collections::HashMap::new(),
+            next_log_sequence: chrono::Utc::now().timestamp_micros().max(0) as u64,
+            slo_evaluator: crate::slo_evaluator::SloEvaluator::default(),
+            power_estimator: crate::energy::PowerEstimator::new(&orchestrator_settings.energy),
+            energy_settings: orchestrator_settings.energy,
+            last_consolidation: None,
+            alert_engine: crate::alerts::AlertEngine::new(orchestrator_settings.alerts.rules.clone()),
+            alert_settings: orchestrator_settings.alerts,
+            metrics_store: crate::metrics_store::MetricsStore::new(&orchestrator_settings.metrics_store),
         }
     }
 
@@ -103,6 +153,13 @@ impl OrchestratorTask {
                     log::debug!("Orchestrator Patch {:?}", update);
                     self.patch(update).await;
                 }
+                crate::orchestrator::OrchestratorRequest::Plan(request, reply_channel) => {
+                    log::debug!("Orchestrator Plan {:?}", request.source_components());
+                    let res = self.plan(&request);
+                    if let Err(err) = reply_channel.send(Ok(res)) {
+                        log::error!("Orchestrator channel error in PLAN: {:?}", err);
+                    }
+                }
                 crate::orchestrator::OrchestratorRequest::AddNode(node_id, mut client_desc, resource_providers) => {
                     log::debug!("Orchestrator AddNode {}", client_desc.to_string_short());
                     let _ = client_desc.api.node_management_api().reset().await;
@@ -116,6 +173,14 @@ impl OrchestratorTask {
                     self.update_domain().await;
                     self.refresh().await;
                 }
+                crate::orchestrator::OrchestratorRequest::UpdateNodeHealth(node_id, health) => {
+                    if self.nodes.contains_key(&node_id) {
+                        let num_instances = self.num_function_instances().get(&node_id).copied().unwrap_or_default();
+                        self.power_estimator.update(node_id, health.active_power, num_instances);
+                        self.store_node_health(node_id, &health);
+                        self.node_health.insert(node_id, health);
+                    }
+                }
                 crate::orchestrator::OrchestratorRequest::Refresh(reply_sender) => {
                     log::debug!("Orchestrator Refresh");
                     self.refresh().await;
@@ -125,6 +190,48 @@ impl OrchestratorTask {
                     log::debug!("Orchestrator Reset");
                     self.reset().await;
                 }
+                crate::orchestrator::OrchestratorRequest::HealthChecks(reply_sender) => {
+                    log::debug!("Orchestrator HealthChecks");
+                    let _ = reply_sender.send(self.health_checks());
+                }
+                crate::orchestrator::OrchestratorRequest::HealthResults(results) => {
+                    log::debug!("Orchestrator HealthResults");
+                    self.handle_health_results(results).await;
+                }
+                crate::orchestrator::OrchestratorRequest::Snapshot(reply_sender) => {
+                    log::debug!("Orchestrator Snapshot");
+                    if reply_sender.send(self.snapshot()).is_err() {
+                        log::error!("Orchestrator channel error in SNAPSHOT");
+                    }
+                }
+                crate::orchestrator::OrchestratorRequest::AddDeployIntents(intents) => {
+                    log::debug!("Orchestrator AddDeployIntents {}", intents.iter().map(|x| x.to_string()).join(", "));
+                    self.pending_deploy_intents.extend(intents);
+                }
+                crate::orchestrator::OrchestratorRequest::UpdateNodeLogs(node_id, log_entries) => {
+                    self.update_node_logs(node_id, log_entries);
+                }
+                crate::orchestrator::OrchestratorRequest::UpdateNodeLatencies(execution_times, transfer_times) => {
+                    self.update_node_latencies(execution_times, transfer_times);
+                }
+                crate::orchestrator::OrchestratorRequest::UpdateNodeMetrics(node_id, metrics) => {
+                    if self.nodes.contains_key(&node_id) {
+                        self.update_node_metrics(node_id, metrics);
+                    }
+                }
+                crate::orchestrator::OrchestratorRequest::QueryMetrics(query, reply_sender) => {
+                    log::debug!("Orchestrator QueryMetrics {:?}", query);
+                    let series = self.metrics_store.query(&query, chrono::Utc::now().timestamp_micros() as f64 * 1e-6);
+                    if reply_sender.send(series).is_err() {
+                        log::error!("Orchestrator channel error in QUERYMETRICS");
+                    }
+                }
+                crate::orchestrator::OrchestratorRequest::FunctionLogs(request, reply_sender) => {
+                    log::debug!("Orchestrator FunctionLogs");
+                    if reply_sender.send(self.function_logs(&request)).is_err() {
+                        log::error!("Orchestrator channel error in FUNCTIONLOGS");
+                    }
+                }
             }
         }
     }
@@ -437,6 +544,104 @@ impl OrchestratorTask {
             .collect::<Vec<String>>()
     }
 
+    /// Return the reasons why no resource provider is feasible for the given
+    /// resource specification, or an empty vector if there is at least one.
+    fn unmet_resource_requirements(&self, resource_req: &edgeless_api::resource_configuration::ResourceInstanceSpecification) -> Vec<String> {
+        if !self.feasible_providers(resource_req).is_empty() {
+            return vec![];
+        }
+        if resource_req.class_type == "portal" && Some(&String::from("portal")) == resource_req.configuration.get("domain") {
+            return vec![format!(
+                "no portal resource provider for domain '{}'",
+                resource_req.configuration.get("domain_name").cloned().unwrap_or_default()
+            )];
+        }
+
+        let deployment_requirements = crate::deployment_requirements::DeploymentRequirements::from_annotations(&resource_req.configuration);
+        let mut ret = std::collections::BTreeSet::new();
+        for provider in self
+            .resource_providers
+            .values()
+            .filter(|provider| provider.class_type == resource_req.class_type)
+        {
+            if let Some(desc) = self.nodes.get(&provider.node_id) {
+                if desc.cordoned {
+                    ret.insert(format!("node {} cordoned", provider.node_id));
+                } else {
+                    ret.extend(deployment_requirements.unmet(&provider.node_id, &desc.capabilities, &std::collections::HashSet::default()));
+                }
+            }
+        }
+        if ret.is_empty() {
+            ret.insert(format!("no resource provider of class '{}'", resource_req.class_type));
+        }
+        ret.into_iter().collect()
+    }
+
+    /// Compute where the functions and resources of a workflow would be
+    /// deployed in this orchestration domain, without starting anything.
+    ///
+    /// The orchestration logic and the random-number generator are cloned so
+    /// that the decisions made here do not affect those of actual deployments.
+    fn plan(&self, request: &edgeless_api::workflow_instance::SpawnWorkflowRequest) -> edgeless_api::workflow_instance::WorkflowPlan {
+        let mut orchestration_logic = self.orchestration_logic.clone();
+        orchestration_logic.update_energy(self.power_models(), self.num_function_instances());
+        let mut rng = self.rng.clone();
+        let all_nodes = self.nodes.keys().cloned().collect::<Vec<uuid::Uuid>>();
+        let mut components = vec![];
+
+        for function in &request.functions {
+            let spawn_req = edgeless_api::function_instance::SpawnFunctionRequest {
+                spec: function.class_specification.clone(),
+                annotations: function.annotations.clone(),
+                state_specification: edgeless_api::function_instance::StateSpecification::default(),
+                workflow_id: String::default(),
+            };
+            let node_id = orchestration_logic.next(&spawn_req, &self.metrics_store);
+            if let Some(node_id) = node_id {
+                // Account for the function instance in the following
+                // placements of this workflow only.
+                orchestration_logic.add_instance(node_id);
+            }
+            components.push(edgeless_api::workflow_instance::ComponentPlacement {
+                name: function.name.clone(),
+                domain_id: String::default(),
+                node_id,
+                feasible_nodes: orchestration_logic.feasible_nodes(&spawn_req, &all_nodes),
+                reasons: match node_id {
+                    Some(_) => vec![],
+                    None => orchestration_logic.unmet_requirements(&spawn_req),
+                },
+            });
+        }
+
+        for resource in &request.resources {
+            let resource_req = edgeless_api::resource_configuration::ResourceInstanceSpecification {
+                class_type: resource.class_type.clone(),
+                configuration: resource.configurations.clone(),
+                workflow_id: String::default(),
+            };
+            let matching_providers = self.feasible_providers(&resource_req);
+            let node_id = matching_providers
+                .choose(&mut rng)
+                .and_then(|provider_id| self.resource_providers.get(provider_id))
+                .map(|provider| provider.node_id);
+            components.push(edgeless_api::workflow_instance::ComponentPlacement {
+                name: resource.name.clone(),
+                domain_id: String::default(),
+                node_id,
+                feasible_nodes: matching_providers
+                    .iter()
+                    .filter_map(|provider_id| self.resource_providers.get(provider_id).map(|provider| provider.node_id))
+                    .unique()
+                    .collect(),
+                reasons: self.unmet_resource_requirements(&resource_req),
+            });
+        }
+
+        edgeless_api::workflow_instance::WorkflowPlan { components }
+    }
+
     // Return true if the given resource can be created on this node.
     fn is_node_feasible_for_resource(
         &self,
@@ -477,12 +682,40 @@ impl OrchestratorTask {
         &mut self,
         spawn_req: &edgeless_api::function_instance::SpawnFunctionRequest,
     ) -> anyhow::Result<edgeless_api::function_instance::NodeId> {
-        match self.orchestration_logic.next(spawn_req) {
+        self.update_energy();
+        match self.orchestration_logic.next(spawn_req, &self.metrics_store) {
             Some(node_id) => Ok(node_id),
             None => Err(anyhow::anyhow!("no valid node found")),
         }
     }
 
+    /// Return the number of function instances assigned to each node.
+    fn num_function_instances(&self) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, usize> {
+        let mut num_instances = std::collections::HashMap::new();
+        for active_instance in self.active_instances.values() {
+            if let crate::active_instance::ActiveInstance::Function(_spawn_req, instances) = active_instance {
+                for instance in instances {
+                    *num_instances.entry(instance.node_id).or_default() += 1;
+                }
+            }
+        }
+        num_instances
+    }
+
+    /// Return the current power model of each node.
+    fn power_models(&self) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, crate::energy::PowerModel> {
+        self.nodes
+            .iter()
+            .map(|(node_id, desc)| (*node_id, self.power_estimator.model(node_id, &desc.capabilities.labels)))
+            .collect()
+    }
+
+    /// Update the orchestration logic with the current power models of the
+    /// nodes and the function instances assigned to them.
+    fn update_energy(&mut self) {
+        self.orchestration_logic.update_energy(self.power_models(), self.num_function_instances());
+    }
+
     /// Start a new function instance on node assigned by orchestration's logic.
     async fn start_function(
         &mut self,
@@ -525,6 +758,7 @@ impl OrchestratorTask {
                 self.apply_patches(self.dependencies(&lid)).await;
                 self.dependency_graph.remove(&lid);
                 self.dependency_graph_changed = true;
+                self.function_health.remove(&lid);
             }
             None => {
                 log::error!("Request to stop a function that is not known: lid {}", lid);
@@ -605,6 +839,7 @@ impl OrchestratorTask {
                         ),
                     );
                     self.active_instances_changed = true;
+                    self.orchestration_logic.add_instance(*node_id);
                     log::info!("Spawned at node_id {}, LID {}, pid {}", node_id, &lid, id.function_id);
 
                     Ok(edgeless_api::common::StartComponentResponse::InstanceId(*lid))
@@ -874,6 +1109,10 @@ impl OrchestratorTask {
             return;
         }
 
+        self.node_health.remove(&node_id);
+        self.power_estimator.remove(&node_id);
+        self.alert_engine.remove_node(&node_id);
+
         // Remove all the resource providers associated with the node removed.
         self.resource_providers.retain(|_k, v| v.node_id != node_id);
 
@@ -1067,9 +1306,19 @@ impl OrchestratorTask {
             }
         }
 
+        // Remove the expired samples from the metrics store.
+        self.metrics_store.gc(chrono::Utc::now().timestamp_micros() as f64 * 1e-6);
+
+        // Migrate functions away from a node, if possible, so that it can
+        // be powered down.
+        let consolidation_intents = self.consolidate();
+        self.pending_deploy_intents.extend(consolidation_intents);
+
         // Check if there are intents from the proxy.
-        let deploy_intents = self.proxy.lock().await.retrieve_deploy_intents();
+        let mut deploy_intents = std::mem::take(&mut self.pending_deploy_intents);
+        deploy_intents.extend(self.proxy.lock().await.retrieve_deploy_intents());
         let mut cordoned_uncordoned_nodes = false;
+        let mut drains_changed = false;
         for intent in deploy_intents {
             match intent {
                 crate::deploy_intent::DeployIntent::Migrate(lid, targets) => {
@@ -1103,21 +1352,46 @@ impl OrchestratorTask {
                     if let Some(desc) = self.nodes.get_mut(&node_id) {
                         desc.cordoned = false;
                         cordoned_uncordoned_nodes = true;
+                        if desc.drain.take().is_some() {
+                            log::info!("drain of node '{}' cancelled", node_id);
+                            drains_changed = true;
+                        }
                     } else {
                         log::warn!("request to cordon unknown node '{}' ignored", node_id);
                     }
                 }
+                crate::deploy_intent::DeployIntent::Drain(node_id) => {
+                    if let Some(desc) = self.nodes.get_mut(&node_id) {
+                        desc.cordoned = true;
+                        cordoned_uncordoned_nodes = true;
+                        if desc.drain.is_none() {
+                            log::info!("drain of node '{}' started", node_id);
+                            desc.drain = Some(edgeless_api::introspection::NodeDrainStatus::default());
+                            drains_changed = true;
+                        }
+                    } else {
+                        log::warn!("request to drain unknown node '{}' ignored", node_id);
+                    }
+                }
             }
         }
         if cordoned_uncordoned_nodes {
             self.orchestration_logic.update_nodes(&self.nodes, &self.resource_providers);
         }
 
+        // Move the functions/resources away from the nodes being drained.
+        if self.drain_nodes(&mut to_be_repatched).await {
+            drains_changed = true;
+        }
+
         // Repatch everything that needs to be repatched.
         self.apply_patches(to_be_repatched).await;
 
         // Update the proxy.
         let mut proxy = self.proxy.lock().await;
+        if drains_changed {
+            proxy.update_nodes(&self.nodes);
+        }
         if self.active_instances_changed {
             proxy.update_active_instances(&self.active_instances);
             self.active_instances_changed = false;
@@ -1128,6 +1402,658 @@ impl OrchestratorTask {
         }
     }
 
+    /// Migrate the functions/resources assigned to the nodes being drained to
+    /// other nodes, then update the drain status of the latter.
+    ///
+    /// The functions/resources that cannot be migrated now, e.g., because
+    /// there is no other feasible node, are tried again at the next refresh.
+    /// A node is safe to remove when it has no functions/resources assigned.
+    ///
+    /// * `to_be_repatched` - The logical identifiers of the components that
+    ///   must be repatched following the migrations are added here.
+    ///
+    /// Return true if the drain status of any node has changed.
+    async fn drain_nodes(&mut self, to_be_repatched: &mut Vec<edgeless_api::function_instance::ComponentId>) -> bool {
+        let draining_nodes = self
+            .nodes
+            .iter()
+            .filter_map(|(node_id, desc)| if desc.drain.is_some() { Some(*node_id) } else { None })
+            .collect::<Vec<edgeless_api::function_instance::NodeId>>();
+
+        let mut changed = false;
+        for node_id in draining_nodes {
+            let lids = self
+                .active_instances
+                .iter()
+                .filter_map(|(lid, active_instance)| {
+                    if active_instance.instance_ids().iter().any(|x| x.node_id == node_id) {
+                        Some(*lid)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<edgeless_api::function_instance::ComponentId>>();
+
+            let mut migrated = 0;
+            let mut remaining = vec![];
+            for lid in lids {
+                // Select the target node with the same logic used to start
+                // a new function/resource, which never picks cordoned nodes.
+                let target = match self.active_instances.get(&lid) {
+                    Some(crate::active_instance::ActiveInstance::Function(spawn_req, _)) => {
+                        let spawn_req = spawn_req.clone();
+                        self.select_node(&spawn_req).ok()
+                    }
+                    Some(crate::active_instance::ActiveInstance::Resource(resource_req, _)) => {
+                        let target_node_ids = self
+                            .feasible_providers(resource_req)
+                            .iter()
+                            .filter_map(|provider_id| self.resource_providers.get(provider_id).map(|x| x.node_id))
+                            .filter(|target_node_id| *target_node_id != node_id)
+                            .collect::<Vec<edgeless_api::function_instance::NodeId>>();
+                        target_node_ids.choose(&mut self.rng).cloned()
+                    }
+                    None => None,
+                };
+                let target = match target {
+                    Some(target) if target != node_id => target,
+                    _ => {
+                        remaining.push(lid);
+                        continue;
+                    }
+                };
+                match self.migrate(&lid, &vec![target]).await {
+                    Ok(target_node_id) => {
+                        log::info!("Drain of node '{}': '{}' migrated to '{}'", node_id, lid, target_node_id);
+                        migrated += 1;
+
+                        // Repatch the component migrated and all the
+                        // components that depend on it.
+                        to_be_repatched.push(lid);
+                        for (origin_lid, output_mapping) in self.dependency_graph.iter() {
+                            if output_mapping.values().contains(&lid) {
+                                to_be_repatched.push(*origin_lid);
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        log::warn!("Drain of node '{}': could not migrate '{}': {}", node_id, lid, err);
+                        remaining.push(lid);
+                    }
+                }
+            }
+
+            if let Some(drain) = self.nodes.get_mut(&node_id).and_then(|desc| desc.drain.as_mut()) {
+                let safe_to_remove = remaining.is_empty();
+                if migrated > 0 || drain.remaining != remaining || drain.safe_to_remove != safe_to_remove {
+                    drain.migrated += migrated;
+                    drain.remaining = remaining;
+                    drain.safe_to_remove = safe_to_remove;
+                    log::info!("Drain of node '{}': {}", node_id, drain);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Return the intents to migrate all the functions assigned to a node to
+    /// other nodes that host functions or resources already, so that it can
+    /// be powered down, if this saves power and it does not exceed the
+    /// capacity of the other nodes.
+    ///
+    /// Nodes hosting resources or being drained are not consolidated, and
+    /// so are the nodes hosting functions with multiple instances or with a
+    /// latency SLO, whose placement is driven by the SLO evaluation.
+    ///
+    /// At most one node is consolidated at every pass, if enabled.
+    fn consolidate(&mut self) -> Vec<crate::deploy_intent::DeployIntent> {
+        if self.energy_settings.consolidation_interval_sec == 0 {
+            return vec![];
+        }
+        let now = tokio::time::Instant::now();
+        if let Some(last_consolidation) = self.last_consolidation
+            && now.duration_since(last_consolidation) < tokio::time::Duration::from_secs(self.energy_settings.consolidation_interval_sec)
+        {
+            return vec![];
+        }
+        self.last_consolidation = Some(now);
+
+        // key: node_id, value: lids of the functions assigned
+        let mut functions: std::collections::HashMap<edgeless_api::function_instance::NodeId, Vec<uuid::Uuid>> = std::collections::HashMap::new();
+        let mut nodes_with_resources = std::collections::HashSet::new();
+        for (lid, active_instance) in &self.active_instances {
+            match active_instance {
+                crate::active_instance::ActiveInstance::Function(_spawn_req, instances) => {
+                    for instance in instances {
+                        functions.entry(instance.node_id).or_default().push(*lid);
+                    }
+                }
+                crate::active_instance::ActiveInstance::Resource(_resource_spec, instance) => {
+                    if !instance.is_none() {
+                        nodes_with_resources.insert(instance.node_id);
+                    }
+                }
+            }
+        }
+        let power_models = self.power_models();
+
+        // Try first the nodes with fewer functions and higher idle power.
+        let candidates = functions
+            .iter()
+            .filter(|(node_id, _lids)| {
+                !nodes_with_resources.contains(*node_id)
+                    && power_models.contains_key(*node_id)
+                    && self.nodes.get(*node_id).is_some_and(|desc| desc.drain.is_none())
+            })
+            .sorted_by(|(node_a, lids_a), (node_b, lids_b)| {
+                lids_a
+                    .len()
+                    .cmp(&lids_b.len())
+                    .then(power_models[*node_b].idle_power.total_cmp(&power_models[*node_a].idle_power))
+            })
+            .map(|(node_id, _lids)| *node_id)
+            .collect::<Vec<edgeless_api::function_instance::NodeId>>();
+
+        for source in candidates {
+            let targets = self
+                .nodes
+                .keys()
+                .filter(|node_id| **node_id != source && (functions.contains_key(node_id) || nodes_with_resources.contains(node_id)))
+                .cloned()
+                .collect::<Vec<edgeless_api::function_instance::NodeId>>();
+            let mut load = targets
+                .iter()
+                .map(|node_id| (*node_id, functions.get(node_id).map_or(0, |lids| lids.len())))
+                .collect::<std::collections::HashMap<edgeless_api::function_instance::NodeId, usize>>();
+
+            let mut intents = vec![];
+            let mut new_power = 0.0;
+            for lid in &functions[&source] {
+                let spawn_req = match self.active_instances.get(lid) {
+                    Some(crate::active_instance::ActiveInstance::Function(spawn_req, instances)) if instances.len() == 1 => spawn_req,
+                    _ => break,
+                };
+                if edgeless_api::latency_slo::LatencySlo::from_annotations(&spawn_req.annotations, "").is_some()
+                    || edgeless_api::latency_slo::LatencySlo::from_annotations(&spawn_req.annotations, edgeless_api::latency_slo::WORKFLOW_PREFIX)
+                        .is_some()
+                {
+                    break;
+                }
+                let target = self
+                    .orchestration_logic
+                    .feasible_nodes(spawn_req, &targets)
+                    .into_iter()
+                    .filter(|node_id| load[node_id] < self.orchestration_logic.node_capacity(&self.nodes[node_id].capabilities))
+                    .min_by(|a, b| power_models[a].instance_power.total_cmp(&power_models[b].instance_power));
+                match target {
+                    Some(target) => {
+                        *load.get_mut(&target).unwrap() += 1;
+                        new_power += power_models[&target].instance_power;
+                        intents.push(crate::deploy_intent::DeployIntent::Migrate(*lid, vec![target]));
+                    }
+                    None => break,
+                }
+            }
+            if intents.len() < functions[&source].len() {
+                continue;
+            }
+
+            let source_model = &power_models[&source];
+            let savings = source_model.idle_power + source_model.instance_power * intents.len() as f64 - new_power;
+            if savings > 0.0 {
+                log::info!(
+                    "consolidation: migrating {} function(s) away from node '{}', which can then be powered down, saving {:.0} mW",
+                    intents.len(),
+                    source,
+                    savings
+                );
+                return intents;
+            }
+        }
+        vec![]
+    }
+
+    /// Return a snapshot of the orchestration domain.
+    fn snapshot(&self) -> edgeless_api::introspection::DomainSnapshot {
+        edgeless_api::introspection::DomainSnapshot {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(node_id, client_desc)| edgeless_api::introspection::NodeInfo {
+                    node_id: *node_id,
+                    agent_url: client_desc.agent_url.clone(),
+                    invocation_url: client_desc.invocation_url.clone(),
+                    capabilities: client_desc.capabilities.clone(),
+                    cordoned: client_desc.cordoned,
+                    health: self.node_health.get(node_id).cloned(),
+                    drain: client_desc.drain.clone(),
+                })
+                .collect(),
+            resource_providers: self
+                .resource_providers
+                .iter()
+                .map(|(provider_id, provider)| edgeless_api::introspection::ResourceProviderInfo {
+                    provider_id: provider_id.clone(),
+                    class_type: provider.class_type.clone(),
+                    node_id: provider.node_id,
+                    outputs: provider.outputs.clone(),
+                })
+                .collect(),
+            instances: self
+                .active_instances
+                .iter()
+                .map(|(lid, active_instance)| {
+                    let (spec, instances) = match active_instance {
+                        crate::active_instance::ActiveInstance::Function(spawn_req, instances) => (
+                            edgeless_api::introspection::ActiveInstanceSpec::Function(spawn_req.strip()),
+                            instances.clone(),
+                        ),
+                        crate::active_instance::ActiveInstance::Resource(resource_spec, instance_id) => (
+                            edgeless_api::introspection::ActiveInstanceSpec::Resource(resource_spec.clone()),
+                            if instance_id.is_none() { vec![] } else { vec![*instance_id] },
+                        ),
+                    };
+                    edgeless_api::introspection::ActiveInstanceInfo {
+                        component_id: *lid,
+                        spec,
+                        instances,
+                        output_mapping: self.dependency_graph.get(lid).cloned().unwrap_or_default(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Return the map from the physical identifiers of the component
+    /// instances to their logical identifiers.
+    fn pid_to_lid(&self) -> std::collections::HashMap<uuid::Uuid, uuid::Uuid> {
+        self.active_instances
+            .iter()
+            .flat_map(|(lid, active_instance)| match active_instance {
+                crate::active_instance::ActiveInstance::Function(_req, instances) => instances.iter().map(|x| (x.function_id, *lid)).collect(),
+                crate::active_instance::ActiveInstance::Resource(_req, instance) => {
+                    if instance.is_none() {
+                        vec![]
+                    } else {
+                        vec![(instance.function_id, *lid)]
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Store the log entries received from a node, indexed by the LID of the
+    /// function instance that emitted them and with increasing sequence
+    /// numbers, and remove those of the functions that are not active
+    /// anymore.
+    fn update_node_logs(
+        &mut self,
+        node_id: uuid::Uuid,
+        log_entries: std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::node_registration::FunctionLogEntry>>,
+    ) {
+        let pid_to_lid = self.pid_to_lid();
+        for (pid, entries) in log_entries {
+            if let Some(lid) = pid_to_lid.get(&pid) {
+                let buffer = self.function_logs.entry(*lid).or_default();
+                for entry in entries {
+                    buffer.push_back((self.next_log_sequence, node_id, entry));
+                    self.next_log_sequence += 1;
+                }
+                while buffer.len() > MAX_LOG_ENTRIES {
+                    buffer.pop_front();
+                }
+            }
+        }
+        self.function_logs.retain(|lid, _| self.active_instances.contains_key(lid));
+    }
+
+    /// Store the execution and transfer times received from a node, then
+    /// evaluate the latency SLOs of the active functions and schedule the
+    /// migrations needed to mitigate their sustained violations, if any,
+    /// at the next refresh.
+    fn update_node_latencies(
+        &mut self,
+        mut execution_times: std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::node_registration::Sample>>,
+        mut transfer_times: std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::node_registration::Sample>>,
+    ) {
+        for (pid, lid) in self.pid_to_lid() {
+            let execution = execution_times.remove(&pid).unwrap_or_default();
+            let transfer = transfer_times.remove(&pid).unwrap_or_default();
+            if !execution.is_empty() || !transfer.is_empty() {
+                self.store_function_samples(pid, lid, &execution, &transfer);
+                self.slo_evaluator.add_samples(lid, execution, transfer);
+            }
+        }
+
+        let intents = self.slo_evaluator.evaluate(
+            chrono::Utc::now().timestamp_micros() as f64 * 1e-6,
+            &self.slo_targets(),
+            &self.dependency_graph,
+            &self.placements(),
+        );
+        self.pending_deploy_intents.extend(intents);
+    }
+
+    /// Evaluate the alert rules on the metrics received from a node, notify
+    /// the alerts fired, resolved, or stale, and schedule the cordon of the node at
+    /// the next refresh if required by the rule of an alert fired.
+    fn update_node_metrics(&mut self, node_id: uuid::Uuid, metrics: std::collections::HashMap<String, f64>) {
+        let alerts = self
+            .alert_engine
+            .evaluate(node_id, chrono::Utc::now().timestamp_micros() as f64 * 1e-6, metrics);
+        for (rule, alert) in alerts {
+            if rule.cordon && alert.status == crate::alerts::AlertStatus::Firing && self.nodes.get(&node_id).is_some_and(|x| !x.cordoned) {
+                log::info!("Cordoning node '{}' because of {}", node_id, alert);
+                self.pending_deploy_intents.push(crate::deploy_intent::DeployIntent::Cordon(node_id));
+            }
+            for sink in &rule.notify {
+                match sink {
+                    crate::alerts::AlertSink::Log => match alert.status {
+                        crate::alerts::AlertStatus::Firing => log::warn!("{}", alert),
+                        crate::alerts::AlertStatus::Resolved => log::info!("{}", alert),
+                        crate::alerts::AlertStatus::Stale => log::warn!("{}", alert),
+                    },
+                    crate::alerts::AlertSink::Webhook => {
+                        if self.alert_settings.webhook_url.is_empty() {
+                            log::warn!("Cannot notify {} to a webhook: no webhook URL configured", alert);
+                        } else {
+                            tokio::spawn(crate::alerts::notify_webhook(self.alert_settings.webhook_url.clone(), alert.clone()));
+                        }
+                    }
+                    crate::alerts::AlertSink::Cast => {
+                        let targets = self.alert_cast_targets();
+                        if targets.is_empty() {
+                            log::warn!(
+                                "Cannot cast {} to component '{}': no active instances",
+                                alert,
+                                self.alert_settings.cast_component_id
+                            );
+                        }
+                        for (invocation_url, target) in targets {
+                            tokio::spawn(crate::alerts::notify_cast(invocation_url, target, alert.clone()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the invocation URLs and identifiers of the instances of the
+    /// component to which the alerts are cast.
+    fn alert_cast_targets(&self) -> Vec<(String, edgeless_api::function_instance::InstanceId)> {
+        let lid = match uuid::Uuid::parse_str(&self.alert_settings.cast_component_id) {
+            Ok(lid) => lid,
+            Err(_) => return vec![],
+        };
+        self.lid_to_pid(&lid)
+            .into_iter()
+            .filter_map(|pid| {
+                let instance_id = pid.instance_id();
+                self.nodes
+                    .get(&instance_id.node_id)
+                    .map(|client_desc| (client_desc.invocation_url.clone(), instance_id))
+            })
+            .collect()
+    }
+
+    /// Add the health status received from a node to the metrics store.
+    fn store_node_health(&mut self, node_id: uuid::Uuid, health: &edgeless_api::node_registration::NodeHealthStatus) {
+        let now = chrono::Utc::now().timestamp_micros() as f64 * 1e-6;
+        let metrics = crate::alerts::node_metrics(health, &edgeless_api::node_registration::NodePerformanceSamples::default());
+        for (metric, value) in metrics {
+            self.metrics_store
+                .add(crate::metrics_store::SeriesKey::node(&metric, node_id), now, value);
+        }
+    }
+
+    /// Add the execution and transfer times of a function instance, with
+    /// given physical and logical identifiers, to the metrics store.
+    fn store_function_samples(
+        &mut self,
+        pid: uuid::Uuid,
+        lid: uuid::Uuid,
+        execution_times: &[edgeless_api::node_registration::Sample],
+        transfer_times: &[edgeless_api::node_registration::Sample],
+    ) {
+        let active_instance = match self.active_instances.get(&lid) {
+            Some(active_instance) => active_instance,
+            None => return,
+        };
+        let node_id = match active_instance.instance_ids().iter().find(|x| x.function_id == pid) {
+            Some(instance_id) => instance_id.node_id,
+            None => return,
+        };
+        let workflow_id = active_instance.workflow_id();
+        for (metric, samples) in [("function_execution_time", execution_times), ("function_transfer_time", transfer_times)] {
+            for sample in samples {
+                self.metrics_store.add(
+                    crate::metrics_store::SeriesKey::component(metric, node_id, lid, &workflow_id),
+                    sample.timestamp_sec as f64 + sample.timestamp_ns as f64 * 1e-9,
+                    sample.sample,
+                );
+            }
+        }
+    }
+
+    /// Return the latency SLOs of the active functions.
+    ///
+    /// The SLO of a workflow is evaluated on the chains starting from the
+    /// functions of the workflow that do not have an upstream function in
+    /// the same workflow.
+    fn slo_targets(&self) -> Vec<crate::slo_evaluator::SloTarget> {
+        let workflow_of = |lid: &uuid::Uuid| match self.active_instances.get(lid) {
+            Some(crate::active_instance::ActiveInstance::Function(spawn_req, _instances)) => Some(spawn_req.workflow_id.as_str()),
+            _ => None,
+        };
+        let mut targets = vec![];
+        for (lid, active_instance) in &self.active_instances {
+            let spawn_req = match active_instance {
+                crate::active_instance::ActiveInstance::Function(spawn_req, _instances) => spawn_req,
+                crate::active_instance::ActiveInstance::Resource(_, _) => continue,
+            };
+            if let Some(slo) = edgeless_api::latency_slo::LatencySlo::from_annotations(&spawn_req.annotations, "") {
+                targets.push(crate::slo_evaluator::SloTarget {
+                    lid: *lid,
+                    scope: crate::slo_evaluator::SloScope::Function,
+                    slo,
+                });
+            }
+            if let Some(slo) =
+                edgeless_api::latency_slo::LatencySlo::from_annotations(&spawn_req.annotations, edgeless_api::latency_slo::WORKFLOW_PREFIX)
+            {
+                let has_upstream = self.dependency_graph.iter().any(|(origin_lid, output_mapping)| {
+                    origin_lid != lid && output_mapping.values().contains(lid) && workflow_of(origin_lid) == Some(spawn_req.workflow_id.as_str())
+                });
+                if !has_upstream {
+                    targets.push(crate::slo_evaluator::SloTarget {
+                        lid: *lid,
+                        scope: crate::slo_evaluator::SloScope::Workflow,
+                        slo,
+                    });
+                }
+            }
+        }
+        targets
+    }
+
+    /// Return the current placement of the active functions/resources.
+    fn placements(&self) -> std::collections::HashMap<uuid::Uuid, crate::slo_evaluator::Placement> {
+        self.active_instances
+            .iter()
+            .map(|(lid, active_instance)| {
+                let placement = match active_instance {
+                    crate::active_instance::ActiveInstance::Function(_req, instances) => crate::slo_evaluator::Placement {
+                        nodes: instances.iter().map(|x| x.node_id).collect(),
+                        is_function: true,
+                    },
+                    crate::active_instance::ActiveInstance::Resource(_req, instance) => crate::slo_evaluator::Placement {
+                        nodes: if instance.is_none() { vec![] } else { vec![instance.node_id] },
+                        is_function: false,
+                    },
+                };
+                (*lid, placement)
+            })
+            .collect()
+    }
+
+    /// Return the log entries of the given function instances that are
+    /// have a sequence number greater than the one in the request.
+    fn function_logs(&self, request: &edgeless_api::introspection::FunctionLogsRequest) -> Vec<edgeless_api::introspection::FunctionLogRecord> {
+        let mut records = vec![];
+        for lid in &request.component_ids {
+            if let Some(buffer) = self.function_logs.get(lid) {
+                records.extend(
+                    buffer
+                        .iter()
+                        .filter(|(sequence, _node_id, _entry)| *sequence > request.since_sequence)
+                        .map(|(sequence, node_id, entry)| edgeless_api::introspection::FunctionLogRecord {
+                            component_id: *lid,
+                            node_id: *node_id,
+                            sequence: *sequence,
+                            entry: entry.clone(),
+                        }),
+                );
+            }
+        }
+        records
+    }
+
+    /// Return the health checks that are due, i.e., for all the functions
+    /// with probes or a restart policy whose last check is older than the
+    /// shortest probe period (or a default period, if there are no probes),
+    /// unless there is already a health check in progress for them.
+    fn health_checks(&mut self) -> Vec<crate::function_health::HealthCheck> {
+        let now = tokio::time::Instant::now();
+        let mut checks = vec![];
+        for (lid, active_instance) in &self.active_instances {
+            let (spawn_req, instances) = match active_instance {
+                crate::active_instance::ActiveInstance::Function(spawn_req, instances) => (spawn_req, instances),
+                crate::active_instance::ActiveInstance::Resource(_, _) => continue,
+            };
+            let probes = edgeless_api::function_lifecycle::ProbeSpec::all_from_annotations(&spawn_req.annotations);
+            let restart_spec = edgeless_api::function_lifecycle::RestartSpec::from_annotations(&spawn_req.annotations);
+            if probes.is_empty() && restart_spec.policy == edgeless_api::function_lifecycle::RestartPolicy::Never {
+                continue;
+            }
+            let period = probes
+                .iter()
+                .map(|probe| probe.period)
+                .min()
+                .unwrap_or(std::time::Duration::from_millis(5000));
+
+            let health = self.function_health.entry(*lid).or_default();
+            if health.pending || health.last_check.is_some_and(|last_check| now.duration_since(last_check) < period) {
+                continue;
+            }
+            health.last_check = Some(now);
+            health.retain_instances(instances);
+
+            for instance_id in instances {
+                if let Some(client_desc) = self.nodes.get_mut(&instance_id.node_id) {
+                    health.pending = true;
+                    checks.push(crate::function_health::HealthCheck {
+                        lid: *lid,
+                        instance_id: *instance_id,
+                        probes: probes.iter().map(|probe| probe.kind).collect(),
+                        client: client_desc.api.node_management_api(),
+                        request: edgeless_api::node_management::FunctionHealthRequest {
+                            function_id: instance_id.function_id,
+                            probes: probes
+                                .iter()
+                                .map(|probe| edgeless_api::node_management::FunctionProbe {
+                                    payload: probe.payload.clone(),
+                                    expected_reply: probe.expected_reply.clone(),
+                                    timeout: probe.timeout,
+                                })
+                                .collect(),
+                        },
+                    });
+                }
+            }
+        }
+        checks
+    }
+
+    /// Update the health of the functions with the outcome of the health
+    /// checks, restart the function instances whose liveness probe has
+    /// failed, and notify the domain subscriber.
+    async fn handle_health_results(&mut self, results: Vec<crate::function_health::HealthCheckResult>) {
+        // Function instances to be restarted.
+        // key:   instance_id
+        // value: lid
+        let mut to_be_restarted = std::collections::HashMap::new();
+
+        for result in results {
+            let probes = match self.active_instances.get(&result.lid) {
+                Some(crate::active_instance::ActiveInstance::Function(spawn_req, _)) => {
+                    edgeless_api::function_lifecycle::ProbeSpec::all_from_annotations(&spawn_req.annotations)
+                }
+                _ => continue,
+            };
+            let health = match self.function_health.get_mut(&result.lid) {
+                Some(health) => health,
+                None => continue,
+            };
+            health.pending = false;
+
+            let response = match result.response {
+                Ok(response) => response,
+                Err(err) => {
+                    log::warn!("Health check failed for function instance_id {}: {}", result.instance_id, err);
+                    continue;
+                }
+            };
+            health.node_restarts.insert(result.instance_id, response.restarts);
+            for (kind, passed) in result.probes.iter().zip(response.probe_results.iter()) {
+                let failure_threshold = probes
+                    .iter()
+                    .find(|probe| probe.kind == *kind)
+                    .map(|probe| probe.failure_threshold)
+                    .unwrap_or(1);
+                if health.update_probe(&result.instance_id, *kind, *passed, failure_threshold)
+                    && *kind == edgeless_api::function_lifecycle::ProbeKind::Liveness
+                {
+                    to_be_restarted.insert(result.instance_id, result.lid);
+                }
+            }
+        }
+
+        // Stop the function instances that failed the liveness probe: those
+        // that are the only instance of their logical function are then
+        // created again by refresh(), otherwise the surviving instances
+        // take over the logical function.
+        let mut to_be_repatched = vec![];
+        for (instance_id, lid) in &to_be_restarted {
+            log::warn!("Liveness probe failed for function instance_id {}: restarting", instance_id);
+            self.stop_function(instance_id).await;
+            if let Some(crate::active_instance::ActiveInstance::Function(_, instances)) = self.active_instances.get_mut(lid) {
+                instances.retain(|x| x != instance_id);
+                if !instances.is_empty() {
+                    to_be_repatched.extend(self.dependencies(lid));
+                }
+                self.active_instances_changed = true;
+            }
+            if let Some(health) = self.function_health.get_mut(lid) {
+                health.orc_restarts += 1;
+                if let Some(instance) = health.instances.get_mut(instance_id) {
+                    instance.failures.remove(&edgeless_api::function_lifecycle::ProbeKind::Liveness);
+                }
+            }
+        }
+        if !to_be_restarted.is_empty() {
+            self.apply_patches(to_be_repatched).await;
+            self.refresh().await;
+        }
+
+        let _ = self
+            .subscriber_sender
+            .send(super::domain_subscriber::DomainSubscriberRequest::UpdateHealth(
+                self.function_health.iter().map(|(lid, health)| health.to_component_health(lid)).collect(),
+            ))
+            .await;
+    }
+
     async fn reset(&mut self) {
         log::info!("Resetting the orchestration domain to a clean state");
         let mut function_lids = vec![];
@@ -70,6 +70,11 @@ pub trait Proxy: Sync + Send {
     /// Fetch all the last nodes' health statuses, with timestamp.
     fn fetch_node_healths(&mut self) -> NodeHealthStatuses;
 
+    /// Fetch the drain status of the nodes being drained.
+    fn fetch_node_drains(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, edgeless_api::introspection::NodeDrainStatus>;
+
     /// Fetch the performance samples.
     fn fetch_performance_samples(&mut self) -> std::collections::HashMap<String, PerformanceSamples>;
 
@@ -0,0 +1,236 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// An orchestrator proxy client that reads the data directly from the
+/// orchestrator through its introspection API, hence it does not require
+/// the orchestrator to mirror its internal data structures elsewhere.
+///
+/// Every fetch retrieves a fresh snapshot of the orchestration domain.
+/// Performance samples and the history of the nodes' health status are not
+/// available: only the last health status received from each node is.
+/// The domain information is not available either, since it is not part of
+/// the snapshot.
+///
+/// The methods block the current thread while waiting for the orchestrator,
+/// which requires a multi-threaded tokio run-time.
+pub struct ProxyIntrospection {
+    client: Box<dyn edgeless_api::introspection::IntrospectionAPI>,
+}
+
+impl ProxyIntrospection {
+    /// Create a proxy client for the orchestrator at the given URL, which
+    /// authenticates with the given bearer token, if any.
+    pub async fn new_client(orchestrator_url: &str, token: Option<String>) -> anyhow::Result<Self> {
+        use edgeless_api::outer::orc::OrchestratorAPI;
+        let mut orc_client = edgeless_api::grpc_impl::outer::orc::OrchestratorAPIClient::new_with_token(orchestrator_url, None, token).await?;
+        Ok(Self {
+            client: orc_client.introspection_api(),
+        })
+    }
+
+    fn fetch_snapshot(&mut self) -> edgeless_api::introspection::DomainSnapshot {
+        let client = &mut self.client;
+        match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(client.snapshot())) {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                log::warn!("could not retrieve a snapshot from the orchestrator: {}", err);
+                edgeless_api::introspection::DomainSnapshot::default()
+            }
+        }
+    }
+
+    fn fetch_instances(&mut self) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, crate::active_instance::ActiveInstance> {
+        self.fetch_snapshot()
+            .instances
+            .into_iter()
+            .map(|instance| {
+                (
+                    instance.component_id,
+                    match instance.spec {
+                        edgeless_api::introspection::ActiveInstanceSpec::Function(spawn_req) => {
+                            crate::active_instance::ActiveInstance::Function(spawn_req, instance.instances)
+                        }
+                        edgeless_api::introspection::ActiveInstanceSpec::Resource(resource_spec) => crate::active_instance::ActiveInstance::Resource(
+                            resource_spec,
+                            instance
+                                .instances
+                                .first()
+                                .cloned()
+                                .unwrap_or(edgeless_api::function_instance::InstanceId::none()),
+                        ),
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+impl super::proxy::Proxy for ProxyIntrospection {
+    fn update_nodes(&mut self, _nodes: &std::collections::HashMap<uuid::Uuid, crate::client_desc::ClientDesc>) {}
+    fn update_resource_providers(&mut self, _resource_providers: &std::collections::HashMap<String, crate::resource_provider::ResourceProvider>) {}
+    fn update_active_instances(&mut self, _active_instances: &std::collections::HashMap<uuid::Uuid, crate::active_instance::ActiveInstance>) {}
+    fn update_dependency_graph(&mut self, _dependency_graph: &std::collections::HashMap<uuid::Uuid, std::collections::HashMap<String, uuid::Uuid>>) {}
+    fn update_domain_info(&mut self, _domain_info: &crate::domain_info::DomainInfo) {}
+    fn push_node_health(&mut self, _node_id: &uuid::Uuid, _node_health: edgeless_api::node_registration::NodeHealthStatus) {}
+    fn push_performance_samples(&mut self, _node_id: &uuid::Uuid, _performance_samples: edgeless_api::node_registration::NodePerformanceSamples) {}
+    fn add_deploy_intents(&mut self, intents: Vec<crate::deploy_intent::DeployIntent>) {
+        let client = &mut self.client;
+        let intents = intents.into_iter().map(edgeless_api::introspection::DeployIntent::from).collect();
+        if let Err(err) = tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(client.add_deploy_intents(intents))) {
+            log::warn!("could not add deploy intents to the orchestrator: {}", err);
+        }
+    }
+    fn retrieve_deploy_intents(&mut self) -> Vec<crate::deploy_intent::DeployIntent> {
+        // The deploy intents are consumed directly by the orchestrator.
+        vec![]
+    }
+    fn fetch_domain_info(&mut self) -> crate::domain_info::DomainInfo {
+        log::warn!("the domain information is not available through the introspection API");
+        crate::domain_info::DomainInfo::default()
+    }
+    fn fetch_node_capabilities(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, edgeless_api::node_registration::NodeCapabilities> {
+        self.fetch_snapshot()
+            .nodes
+            .into_iter()
+            .map(|node| (node.node_id, node.capabilities))
+            .collect()
+    }
+    fn fetch_resource_providers(&mut self) -> std::collections::HashMap<String, crate::resource_provider::ResourceProvider> {
+        self.fetch_snapshot()
+            .resource_providers
+            .into_iter()
+            .map(|provider| {
+                (
+                    provider.provider_id,
+                    crate::resource_provider::ResourceProvider {
+                        class_type: provider.class_type,
+                        node_id: provider.node_id,
+                        outputs: provider.outputs,
+                    },
+                )
+            })
+            .collect()
+    }
+    fn fetch_node_health(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, edgeless_api::node_registration::NodeHealthStatus> {
+        self.fetch_snapshot()
+            .nodes
+            .into_iter()
+            .filter_map(|node| node.health.map(|health| (node.node_id, health)))
+            .collect()
+    }
+    fn fetch_node_healths(&mut self) -> crate::proxy::NodeHealthStatuses {
+        let now = chrono::Utc::now();
+        self.fetch_node_health()
+            .into_iter()
+            .map(|(node_id, health)| (node_id, vec![(now, health)]))
+            .collect()
+    }
+    fn fetch_node_drains(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, edgeless_api::introspection::NodeDrainStatus> {
+        self.fetch_snapshot()
+            .nodes
+            .into_iter()
+            .filter_map(|node| node.drain.map(|drain| (node.node_id, drain)))
+            .collect()
+    }
+    fn fetch_performance_samples(&mut self) -> std::collections::HashMap<String, crate::proxy::PerformanceSamples> {
+        std::collections::HashMap::new()
+    }
+    fn fetch_performance_series(&mut self, _node: &str, _series: &str) -> crate::proxy::PerformanceSeries {
+        vec![]
+    }
+    fn fetch_function_instance_requests(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, edgeless_api::function_instance::SpawnFunctionRequest> {
+        let mut instances = std::collections::HashMap::new();
+        for (logical_id, instance) in self.fetch_instances() {
+            if let crate::active_instance::ActiveInstance::Function(spawn_function_req, _instance_ids) = instance {
+                instances.insert(logical_id, spawn_function_req);
+            }
+        }
+        instances
+    }
+    fn fetch_resource_instance_configurations(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, edgeless_api::resource_configuration::ResourceInstanceSpecification>
+    {
+        let mut instances = std::collections::HashMap::new();
+        for (logical_id, instance) in self.fetch_instances() {
+            if let crate::active_instance::ActiveInstance::Resource(resource_specification, _instance_id) = instance {
+                instances.insert(logical_id, resource_specification);
+            }
+        }
+        instances
+    }
+    fn fetch_function_instances_to_nodes(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::function_instance::NodeId>> {
+        let mut instances = std::collections::HashMap::new();
+        for (logical_id, instance) in self.fetch_instances() {
+            if let crate::active_instance::ActiveInstance::Function(_, instance_ids) = instance {
+                instances.insert(logical_id, instance_ids.iter().map(|x| x.node_id).collect());
+            }
+        }
+        instances
+    }
+    fn fetch_instances_to_physical_ids(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::function_instance::ComponentId>> {
+        self.fetch_instances()
+            .into_iter()
+            .map(|(logical_id, instance)| (logical_id, instance.instance_ids().iter().map(|x| x.function_id).collect()))
+            .collect()
+    }
+    fn fetch_resource_instances_to_nodes(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, edgeless_api::function_instance::NodeId> {
+        let mut instances = std::collections::HashMap::new();
+        for (logical_id, instance) in self.fetch_instances() {
+            if let crate::active_instance::ActiveInstance::Resource(_, instance_id) = instance {
+                instances.insert(logical_id, instance_id.node_id);
+            }
+        }
+        instances
+    }
+    fn fetch_nodes_to_instances(&mut self) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, Vec<crate::proxy::Instance>> {
+        let mut nodes_mapping = std::collections::HashMap::new();
+        for (logical_id, instance) in self.fetch_instances() {
+            match instance {
+                crate::active_instance::ActiveInstance::Function(_, instance_ids) => {
+                    for instance_id in instance_ids {
+                        let res = nodes_mapping.entry(instance_id.node_id).or_insert(vec![]);
+                        res.push(crate::proxy::Instance::Function(logical_id));
+                    }
+                }
+                crate::active_instance::ActiveInstance::Resource(_, instance_id) => {
+                    let res = nodes_mapping.entry(instance_id.node_id).or_insert(vec![]);
+                    res.push(crate::proxy::Instance::Resource(logical_id));
+                }
+            }
+        }
+        nodes_mapping
+    }
+    fn fetch_dependency_graph(&mut self) -> std::collections::HashMap<uuid::Uuid, std::collections::HashMap<String, uuid::Uuid>> {
+        self.fetch_snapshot()
+            .instances
+            .into_iter()
+            .filter(|instance| !instance.output_mapping.is_empty())
+            .map(|instance| (instance.component_id, instance.output_mapping))
+            .collect()
+    }
+    fn fetch_logical_id_to_workflow_id(&mut self) -> std::collections::HashMap<edgeless_api::function_instance::ComponentId, String> {
+        self.fetch_instances()
+            .iter()
+            .map(|(logical_id, instance)| (*logical_id, instance.workflow_id()))
+            .collect()
+    }
+    fn updated(&mut self, _category: crate::proxy::Category) -> bool {
+        true
+    }
+    fn garbage_collection(&mut self, _period: tokio::time::Duration) {}
+}
@@ -297,6 +297,18 @@ impl super::proxy::Proxy for ProxyRedis {
                 serde_json::to_string(&client_desc.capabilities).unwrap_or_default().as_str(),
             )
             .exec(&mut self.connection);
+            match &client_desc.drain {
+                Some(drain) => {
+                    let _ = redis::Cmd::set(
+                        format!("node:drain:{}", uuid).as_str(),
+                        serde_json::to_string(drain).unwrap_or_default().as_str(),
+                    )
+                    .exec(&mut self.connection);
+                }
+                None => {
+                    let _ = redis::Cmd::del(format!("node:drain:{}", uuid).as_str()).exec(&mut self.connection);
+                }
+            }
             let new_caps = client_desc.capabilities.to_csv();
             if let Some(dd) = &mut self.dataset_dumping {
                 let write: bool = if let Some(old_caps) = self.node_capabilities.get(uuid) {
@@ -319,6 +331,8 @@ impl super::proxy::Proxy for ProxyRedis {
                 .del(format!("node:capabilities:{}", uuid).as_str())
                 .ignore()
                 .del(format!("node:health:{}", uuid).as_str())
+                .ignore()
+                .del(format!("node:drain:{}", uuid).as_str())
                 .exec(&mut self.connection);
         });
 
@@ -458,6 +472,8 @@ impl super::proxy::Proxy for ProxyRedis {
             ("function_execution_time", &performance_samples.function_execution_times),
             ("function_stop_time", &performance_samples.function_stop_times),
             ("function_transfer_time", &performance_samples.function_transfer_times),
+            ("resource_throttled", &performance_samples.resource_throttled),
+            ("resource_failures", &performance_samples.resource_failures),
         ];
         for (name, series) in all_sample_series {
             for (function_id, values) in series {
@@ -481,6 +497,16 @@ impl super::proxy::Proxy for ProxyRedis {
             }
         }
 
+        for channel_metrics in &performance_samples.dataplane_channels {
+            let key = format!("performance:{}:dataplane_channel", channel_metrics.component_id);
+            let _ = redis::Cmd::zadd(&key, channel_metrics.to_string(), channel_metrics.score()).exec(&mut self.connection);
+        }
+
+        for function_metric in &performance_samples.function_metrics {
+            let key = format!("performance:{}:function_metric", function_metric.component_id);
+            let _ = redis::Cmd::zadd(&key, function_metric.to_string(), function_metric.score()).exec(&mut self.connection);
+        }
+
         for (function_id, log_entries) in &performance_samples.function_log_entries {
             for log_entry in log_entries {
                 let key = format!("performance:{}:{}", function_id, log_entry.target);
@@ -610,6 +636,25 @@ impl super::proxy::Proxy for ProxyRedis {
         health
     }
 
+    fn fetch_node_drains(
+        &mut self,
+    ) -> std::collections::HashMap<edgeless_api::function_instance::NodeId, edgeless_api::introspection::NodeDrainStatus> {
+        let mut drains = std::collections::HashMap::new();
+        for node_key in self.connection.keys::<&str, Vec<String>>("node:drain:*").unwrap_or(vec![]) {
+            let tokens: Vec<&str> = node_key.split(':').collect();
+            assert_eq!(tokens.len(), 3);
+            assert_eq!("node", tokens[0]);
+            assert_eq!("drain", tokens[1]);
+            if let Ok(node_id) = edgeless_api::function_instance::NodeId::parse_str(tokens[2])
+                && let Ok(value) = self.connection.get::<&str, String>(&node_key)
+                && let Ok(val) = serde_json::from_str::<edgeless_api::introspection::NodeDrainStatus>(&value)
+            {
+                drains.insert(node_id, val);
+            }
+        }
+        drains
+    }
+
     fn fetch_node_healths(&mut self) -> crate::proxy::NodeHealthStatuses {
         let mut healths = std::collections::HashMap::new();
         for node_key in self.connection.keys::<&str, Vec<String>>("node:health:*").unwrap_or(vec![]) {
@@ -1015,6 +1060,7 @@ mod test {
                 timestamp_ns: (log_cnt + 1) as u32,
                 target: String::from("target"),
                 message: format!("value={}", value),
+                ..Default::default()
             }
         };
 
@@ -1069,6 +1115,10 @@ mod test {
                 function_stop_times: std::collections::HashMap::from([(fid_perf_1, samples_1.clone()), (fid_perf_2, samples_2.clone())]),
                 function_transfer_times: std::collections::HashMap::from([(fid_perf_1, samples_1.clone()), (fid_perf_2, samples_2.clone())]),
                 function_log_entries: std::collections::HashMap::from([(fid_perf_1, log_1.clone()), (fid_perf_2, log_2.clone())]),
+                resource_throttled: std::collections::HashMap::new(),
+                resource_failures: std::collections::HashMap::new(),
+                dataplane_channels: vec![],
+                function_metrics: vec![],
             },
         );
 
@@ -1214,6 +1264,7 @@ mod test {
                 }) as Box<dyn edgeless_api::outer::agent::AgentAPI + Send>,
                 capabilities: edgeless_api::node_registration::NodeCapabilities::minimum(),
                 cordoned: false,
+                drain: None,
             },
         );
         assert!(!redis_proxy.updated(crate::proxy::Category::NodeCapabilities));
@@ -1225,6 +1276,20 @@ mod test {
 
         assert_eq!(nodes_expected, redis_proxy.fetch_node_capabilities());
         assert!(!redis_proxy.updated(crate::proxy::Category::NodeCapabilities));
+
+        // Check the drain status.
+        assert!(redis_proxy.fetch_node_drains().is_empty());
+        let drain = edgeless_api::introspection::NodeDrainStatus {
+            migrated: 1,
+            remaining: vec![uuid::Uuid::new_v4()],
+            safe_to_remove: false,
+        };
+        nodes.get_mut(&node_id).unwrap().drain = Some(drain.clone());
+        redis_proxy.update_nodes(&nodes);
+        assert_eq!(std::collections::HashMap::from([(node_id, drain)]), redis_proxy.fetch_node_drains());
+        nodes.get_mut(&node_id).unwrap().drain = None;
+        redis_proxy.update_nodes(&nodes);
+        assert!(redis_proxy.fetch_node_drains().is_empty());
     }
 
     #[serial_test::serial]
@@ -1286,6 +1351,7 @@ mod test {
         let component4 = uuid::Uuid::new_v4();
         let node1 = uuid::Uuid::new_v4();
         let node2 = uuid::Uuid::new_v4();
+        let node3 = uuid::Uuid::new_v4();
         let intents = vec![
             DeployIntent::Migrate(component1, vec![]),
             DeployIntent::Migrate(component2, vec![node1]),
@@ -1293,6 +1359,7 @@ mod test {
             DeployIntent::Migrate(component4, vec![node1, node2, node2]),
             DeployIntent::Cordon(node1),
             DeployIntent::Uncordon(node2),
+            DeployIntent::Drain(node3),
         ];
         redis_proxy.add_deploy_intents(intents);
 
@@ -1318,6 +1385,9 @@ mod test {
                 DeployIntent::Uncordon(node_id) => {
                     assert_eq!(node2, node_id);
                 }
+                DeployIntent::Drain(node_id) => {
+                    assert_eq!(node3, node_id);
+                }
             }
         }
     }
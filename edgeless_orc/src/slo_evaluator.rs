@@ -0,0 +1,394 @@
+// SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use edgeless_api::latency_slo::LatencySlo;
+use itertools::Itertools;
+
+/// Maximum number of samples retained for each component, per type.
+const MAX_SAMPLES: usize = 10000;
+
+/// Scope of a latency SLO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SloScope {
+    /// SLO in the annotations of the function.
+    Function,
+    /// SLO in the annotations of the function's workflow.
+    Workflow,
+}
+
+impl std::fmt::Display for SloScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SloScope::Function => "function",
+                SloScope::Workflow => "workflow",
+            }
+        )
+    }
+}
+
+/// Latency SLO to be evaluated on the chains starting from a component.
+pub struct SloTarget {
+    pub lid: edgeless_api::function_instance::ComponentId,
+    pub scope: SloScope,
+    pub slo: LatencySlo,
+}
+
+/// Current placement of a component.
+pub struct Placement {
+    /// Nodes to which the component instances are assigned.
+    pub nodes: Vec<edgeless_api::function_instance::NodeId>,
+    /// True if the component is a function, false if it is a resource.
+    pub is_function: bool,
+}
+
+/// Execution and transfer time samples of a component, in order of arrival.
+#[derive(Default)]
+struct ComponentSamples {
+    execution: std::collections::VecDeque<edgeless_api::node_registration::Sample>,
+    transfer: std::collections::VecDeque<edgeless_api::node_registration::Sample>,
+}
+
+/// Evaluator of the latency SLOs of the components in the orchestration
+/// domain.
+///
+/// The latency of a component is the sum of the percentiles of its transfer
+/// and execution times, and the latency of a chain is the sum of those of its
+/// components, which is a (usually pessimistic) approximation of the
+/// percentile of the end-to-end latency.
+/// An SLO is violated if the latency of the slowest chain starting from its
+/// component exceeds the target.
+/// If the violation lasts for the SLO time window, the evaluator issues an
+/// intent to co-locate the two consecutive components with the highest
+/// transfer time that are assigned to different nodes.
+#[derive(Default)]
+pub struct SloEvaluator {
+    // key: lid
+    samples: std::collections::HashMap<edgeless_api::function_instance::ComponentId, ComponentSamples>,
+    // time (in s since Unix epoch) since when the SLO is violated
+    // key: (lid, scope)
+    violating_since: std::collections::HashMap<(edgeless_api::function_instance::ComponentId, SloScope), f64>,
+}
+
+fn timestamp(sample: &edgeless_api::node_registration::Sample) -> f64 {
+    sample.timestamp_sec as f64 + sample.timestamp_ns as f64 * 1e-9
+}
+
+/// Nearest-rank percentile of the samples more recent than `since`, if any.
+fn percentile(samples: &std::collections::VecDeque<edgeless_api::node_registration::Sample>, since: f64, percentile: f64) -> Option<f64> {
+    let values = samples
+        .iter()
+        .filter(|x| timestamp(x) >= since)
+        .map(|x| x.sample)
+        .sorted_by(|a, b| a.total_cmp(b))
+        .collect::<Vec<f64>>();
+    if values.is_empty() {
+        return None;
+    }
+    let rank = ((percentile / 100.0) * values.len() as f64).ceil() as usize;
+    Some(values[rank.clamp(1, values.len()) - 1])
+}
+
+impl SloEvaluator {
+    /// Add the execution and transfer time samples of a component.
+    pub fn add_samples(
+        &mut self,
+        lid: edgeless_api::function_instance::ComponentId,
+        execution: Vec<edgeless_api::node_registration::Sample>,
+        transfer: Vec<edgeless_api::node_registration::Sample>,
+    ) {
+        let samples = self.samples.entry(lid).or_default();
+        for (buffer, new_samples) in [(&mut samples.execution, execution), (&mut samples.transfer, transfer)] {
+            buffer.extend(new_samples);
+            while buffer.len() > MAX_SAMPLES {
+                buffer.pop_front();
+            }
+        }
+    }
+
+    /// Evaluate the SLOs at time `now` (in s since Unix epoch).
+    ///
+    /// The samples and the state of the components that are not in
+    /// `placements` are removed, as well as the samples older than the
+    /// longest time window.
+    ///
+    /// Return the intents to mitigate the SLO violations, if any.
+    pub fn evaluate(
+        &mut self,
+        now: f64,
+        targets: &[SloTarget],
+        dependency_graph: &std::collections::HashMap<edgeless_api::function_instance::ComponentId, std::collections::HashMap<String, uuid::Uuid>>,
+        placements: &std::collections::HashMap<edgeless_api::function_instance::ComponentId, Placement>,
+    ) -> Vec<crate::deploy_intent::DeployIntent> {
+        let max_window = targets.iter().map(|x| x.slo.window.as_secs_f64()).fold(0.0, f64::max);
+        self.samples.retain(|lid, _| placements.contains_key(lid));
+        for samples in self.samples.values_mut() {
+            samples.execution.retain(|x| timestamp(x) >= now - max_window);
+            samples.transfer.retain(|x| timestamp(x) >= now - max_window);
+        }
+        self.samples
+            .retain(|_, samples| !samples.execution.is_empty() || !samples.transfer.is_empty());
+        self.violating_since
+            .retain(|(lid, scope), _| targets.iter().any(|x| x.lid == *lid && x.scope == *scope));
+
+        let mut intents = vec![];
+        let mut migrating = std::collections::HashSet::new();
+        for target in targets {
+            let since = now - target.slo.window.as_secs_f64();
+            let (latency, path) = self.slowest_chain(&target.lid, since, target.slo.percentile, dependency_graph, &mut vec![]);
+            if !path.iter().any(|lid| self.samples.contains_key(lid)) {
+                self.violating_since.remove(&(target.lid, target.scope));
+                continue;
+            }
+
+            if latency <= target.slo.target.as_secs_f64() {
+                if self.violating_since.remove(&(target.lid, target.scope)).is_some() {
+                    log::info!("{} SLO of component {} satisfied again", target.scope, target.lid);
+                }
+                continue;
+            }
+
+            let violating_since = *self.violating_since.entry((target.lid, target.scope)).or_insert_with(|| {
+                log::warn!(
+                    "{} SLO of component {} violated ({}): latency {:.3} ms along {}",
+                    target.scope,
+                    target.lid,
+                    target.slo,
+                    latency * 1000.0,
+                    path.iter().join(" -> ")
+                );
+                now
+            });
+            if now - violating_since < target.slo.window.as_secs_f64() {
+                continue;
+            }
+
+            // The violation is sustained: try to co-locate two components
+            // along the slowest chain, then wait for another time window
+            // before doing anything else.
+            self.violating_since.insert((target.lid, target.scope), now);
+            match self.mitigation(&path, since, target.slo.percentile, placements) {
+                Some((lid, node_id)) => {
+                    if migrating.insert(lid) {
+                        log::info!(
+                            "migrating component {} to node {} to mitigate the {} SLO violation of component {}",
+                            lid,
+                            node_id,
+                            target.scope,
+                            target.lid
+                        );
+                        self.samples.remove(&lid);
+                        intents.push(crate::deploy_intent::DeployIntent::Migrate(lid, vec![node_id]));
+                    }
+                }
+                None => log::warn!(
+                    "{} SLO violation of component {} cannot be mitigated by co-locating components",
+                    target.scope,
+                    target.lid
+                ),
+            }
+        }
+        intents
+    }
+
+    /// Latency of a component, i.e., the sum of the percentiles of its
+    /// transfer and execution times, or zero if there are no samples.
+    fn latency(&self, lid: &edgeless_api::function_instance::ComponentId, since: f64, pct: f64) -> f64 {
+        match self.samples.get(lid) {
+            Some(samples) => {
+                percentile(&samples.transfer, since, pct).unwrap_or_default() + percentile(&samples.execution, since, pct).unwrap_or_default()
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Return the latency of the slowest chain starting from a component,
+    /// together with the components in the chain. Loops are not followed.
+    fn slowest_chain(
+        &self,
+        lid: &edgeless_api::function_instance::ComponentId,
+        since: f64,
+        pct: f64,
+        dependency_graph: &std::collections::HashMap<edgeless_api::function_instance::ComponentId, std::collections::HashMap<String, uuid::Uuid>>,
+        visited: &mut Vec<edgeless_api::function_instance::ComponentId>,
+    ) -> (f64, Vec<edgeless_api::function_instance::ComponentId>) {
+        visited.push(*lid);
+        let mut slowest: (f64, Vec<edgeless_api::function_instance::ComponentId>) = (0.0, vec![]);
+        if let Some(output_mapping) = dependency_graph.get(lid) {
+            for next_lid in output_mapping.values().unique() {
+                if visited.contains(next_lid) {
+                    continue;
+                }
+                let chain = self.slowest_chain(next_lid, since, pct, dependency_graph, visited);
+                if slowest.1.is_empty() || chain.0 > slowest.0 {
+                    slowest = chain;
+                }
+            }
+        }
+        visited.pop();
+
+        let mut path = vec![*lid];
+        path.extend(slowest.1);
+        (self.latency(lid, since, pct) + slowest.0, path)
+    }
+
+    /// Find the two consecutive components in a chain that are assigned to
+    /// different nodes with the highest transfer time, and return the
+    /// function that should be migrated and its target node.
+    ///
+    /// The downstream component is preferred if both are functions.
+    fn mitigation(
+        &self,
+        path: &[edgeless_api::function_instance::ComponentId],
+        since: f64,
+        pct: f64,
+        placements: &std::collections::HashMap<edgeless_api::function_instance::ComponentId, Placement>,
+    ) -> Option<(edgeless_api::function_instance::ComponentId, edgeless_api::function_instance::NodeId)> {
+        let mut candidate = None;
+        let mut max_transfer = f64::MIN;
+        for (upstream, downstream) in path.iter().tuple_windows() {
+            let (Some(upstream_placement), Some(downstream_placement)) = (placements.get(upstream), placements.get(downstream)) else {
+                continue;
+            };
+            if upstream_placement.nodes.is_empty()
+                || downstream_placement.nodes.is_empty()
+                || upstream_placement.nodes.iter().any(|x| downstream_placement.nodes.contains(x))
+            {
+                continue;
+            }
+            let transfer = self
+                .samples
+                .get(downstream)
+                .and_then(|x| percentile(&x.transfer, since, pct))
+                .unwrap_or_default();
+            let migration = if downstream_placement.is_function {
+                Some((*downstream, upstream_placement.nodes[0]))
+            } else if upstream_placement.is_function {
+                Some((*upstream, downstream_placement.nodes[0]))
+            } else {
+                None
+            };
+            if migration.is_some() && transfer > max_transfer {
+                max_transfer = transfer;
+                candidate = migration;
+            }
+        }
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_samples(now: f64, values: &[f64]) -> Vec<edgeless_api::node_registration::Sample> {
+        values
+            .iter()
+            .map(|x| edgeless_api::node_registration::Sample {
+                timestamp_sec: now as i64,
+                timestamp_ns: 0,
+                sample: *x,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_slo_evaluator_percentile() {
+        let samples = std::collections::VecDeque::from(new_samples(10.0, &[5.0, 1.0, 4.0, 2.0, 3.0]));
+        assert_eq!(Some(5.0), percentile(&samples, 0.0, 95.0));
+        assert_eq!(Some(3.0), percentile(&samples, 0.0, 50.0));
+        assert_eq!(Some(1.0), percentile(&samples, 0.0, 1.0));
+        assert_eq!(None, percentile(&samples, 11.0, 50.0));
+    }
+
+    #[test]
+    fn test_slo_evaluator_sustained_violation() {
+        // Chain: f0 -> f1 -> f2, where f0 and f1 are in node0, f2 in node1.
+        let lids = (0..3).map(|_| uuid::Uuid::new_v4()).collect::<Vec<uuid::Uuid>>();
+        let node0 = uuid::Uuid::new_v4();
+        let node1 = uuid::Uuid::new_v4();
+        let dependency_graph = std::collections::HashMap::from([
+            (lids[0], std::collections::HashMap::from([("out".to_string(), lids[1])])),
+            (
+                lids[1],
+                std::collections::HashMap::from([("out".to_string(), lids[2]), ("back".to_string(), lids[0])]),
+            ),
+        ]);
+        let placements = std::collections::HashMap::from([
+            (
+                lids[0],
+                Placement {
+                    nodes: vec![node0],
+                    is_function: true,
+                },
+            ),
+            (
+                lids[1],
+                Placement {
+                    nodes: vec![node0],
+                    is_function: true,
+                },
+            ),
+            (
+                lids[2],
+                Placement {
+                    nodes: vec![node1],
+                    is_function: true,
+                },
+            ),
+        ]);
+        let targets = vec![SloTarget {
+            lid: lids[0],
+            scope: SloScope::Workflow,
+            slo: LatencySlo {
+                target: std::time::Duration::from_millis(50),
+                percentile: 95.0,
+                window: std::time::Duration::from_secs(10),
+            },
+        }];
+
+        let mut evaluator = SloEvaluator::default();
+
+        // No samples: nothing to do.
+        assert!(evaluator.evaluate(100.0, &targets, &dependency_graph, &placements).is_empty());
+
+        // The SLO is satisfied: 10 + 10 + 15 ms.
+        for lid in &lids {
+            evaluator.add_samples(*lid, new_samples(100.0, &[0.005]), new_samples(100.0, &[0.005]));
+        }
+        evaluator.add_samples(lids[2], vec![], new_samples(100.0, &[0.010]));
+        assert!(evaluator.evaluate(100.0, &targets, &dependency_graph, &placements).is_empty());
+        assert!(evaluator.violating_since.is_empty());
+
+        // The SLO is violated, but not for long enough.
+        evaluator.add_samples(lids[2], vec![], new_samples(101.0, &[0.100]));
+        assert!(evaluator.evaluate(101.0, &targets, &dependency_graph, &placements).is_empty());
+        assert!(evaluator.evaluate(110.0, &targets, &dependency_graph, &placements).is_empty());
+
+        // The violation is sustained: f2 is migrated to the node of f1.
+        evaluator.add_samples(lids[2], vec![], new_samples(111.0, &[0.100]));
+        let intents = evaluator.evaluate(111.0, &targets, &dependency_graph, &placements);
+        assert_eq!(1, intents.len());
+        match &intents[0] {
+            crate::deploy_intent::DeployIntent::Migrate(lid, nodes) => {
+                assert_eq!(lids[2], *lid);
+                assert_eq!(vec![node0], *nodes);
+            }
+            _ => panic!("wrong intent"),
+        }
+        assert!(!evaluator.samples.contains_key(&lids[2]));
+
+        // The samples of the components that are not active anymore and the
+        // state of the SLOs removed are cleared.
+        evaluator.add_samples(lids[2], vec![], new_samples(112.0, &[0.100]));
+        assert!(
+            evaluator
+                .evaluate(112.0, &[], &dependency_graph, &std::collections::HashMap::new())
+                .is_empty()
+        );
+        assert!(evaluator.samples.is_empty());
+        assert!(evaluator.violating_since.is_empty());
+    }
+}
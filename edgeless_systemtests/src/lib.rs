@@ -73,9 +73,13 @@ mod system_tests {
                     orchestrator_url_announced: orchestrator_url.to_string(),
                     node_register_url: node_register_url.clone(),
                     node_register_coap_url: None,
+                    auth: None,
                 },
                 baseline: edgeless_orc::EdgelessOrcBaselineSettings {
                     orchestration_strategy: edgeless_orc::OrchestrationStrategy::RoundRobin,
+                    energy: edgeless_orc::EdgelessOrcEnergySettings::default(),
+                    alerts: edgeless_orc::EdgelessOrcAlertSettings::default(),
+                    metrics_store: edgeless_orc::EdgelessOrcMetricsStoreSettings::default(),
                 },
                 proxy: match redis_url {
                     None => edgeless_orc::EdgelessOrcProxySettings {
@@ -120,6 +124,8 @@ mod system_tests {
                     telemetry: edgeless_node::EdgelessNodeTelemetrySettings {
                         metrics_url: format!("http://{}:{}", address, next_port()),
                         performance_samples: false,
+                        traces_url: String::new(),
+                        ..Default::default()
                     },
                     wasm_runtime: Some(edgeless_node::EdgelessNodeWasmRuntimeSettings { enabled: true }),
                     container_runtime: None,
@@ -136,6 +142,8 @@ mod system_tests {
             controller_url: controller_url.clone(),
             domain_register_url: domain_register_url.clone(),
             persistence_filename: String::default(),
+            auth: None,
+            orchestrator_token: None,
         }));
         tokio::spawn(task);
 
@@ -181,9 +189,13 @@ mod system_tests {
                 orchestrator_url_announced: orchestrator_url.to_string(),
                 node_register_url: node_register_url_portal.clone(),
                 node_register_coap_url: None,
+                auth: None,
             },
             baseline: edgeless_orc::EdgelessOrcBaselineSettings {
                 orchestration_strategy: edgeless_orc::OrchestrationStrategy::RoundRobin,
+                energy: edgeless_orc::EdgelessOrcEnergySettings::default(),
+                alerts: edgeless_orc::EdgelessOrcAlertSettings::default(),
+                metrics_store: edgeless_orc::EdgelessOrcMetricsStoreSettings::default(),
             },
             proxy: edgeless_orc::EdgelessOrcProxySettings {
                 proxy_type: "None".to_string(),
@@ -233,6 +245,8 @@ mod system_tests {
                 telemetry: edgeless_node::EdgelessNodeTelemetrySettings {
                     metrics_url: format!("http://{}:{}", address, next_port()),
                     performance_samples: false,
+                    traces_url: String::new(),
+                    ..Default::default()
                 },
             }));
             tokio::spawn(task);
@@ -1128,7 +1142,7 @@ mod system_tests {
         expected_mapping: std::collections::HashMap<&str, &String>,
         client: &mut Box<dyn WorkflowInstanceAPI>,
     ) {
-        if let Ok(edgeless_api::workflow_instance::WorkflowInfo { request: _, status }) = client.inspect(wf_id.clone()).await {
+        if let Ok(edgeless_api::workflow_instance::WorkflowInfo { request: _, status, .. }) = client.inspect(wf_id.clone()).await {
             assert_eq!(wf_id, status.workflow_id);
             for edgeless_api::workflow_instance::WorkflowFunctionMapping {
                 name,
@@ -0,0 +1,375 @@
+// SPDX-FileCopyrightText: © 2023 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+/// Maximum number of log entries shipped with a single request.
+const MAX_BATCH_SIZE: usize = 512;
+
+/// Maximum time a log entry waits before being shipped.
+const SHIP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Timeout of the requests to an HTTP end-point.
+const HTTP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Tags of the telemetry handle that are used as labels of the streams
+/// pushed to an HTTP end-point, all the others are only in the log lines.
+const STREAM_LABELS: [&str; 2] = ["NODE_ID", "WORKFLOW_ID"];
+
+/// Settings of the sink to which the function log entries are shipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSinkSettings {
+    /// URL of the sink, which can be either `file://path` for rotating
+    /// local files or `http://host:port/path` for an HTTP end-point
+    /// compatible with the Loki push API, e.g.,
+    /// `http://127.0.0.1:3100/loki/api/v1/push`; if empty then the log
+    /// entries are not shipped.
+    pub url: String,
+    /// Maximum size of a local file, in bytes, before it is rotated.
+    pub max_file_size: u64,
+    /// Maximum number of rotated local files that are kept.
+    pub max_files: u32,
+}
+
+impl Default for LogSinkSettings {
+    fn default() -> Self {
+        Self {
+            url: String::default(),
+            max_file_size: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+/// Ships the log entries of the functions, enriched with the tags of the
+/// telemetry handle (node, function, workflow), to a local file or to an
+/// HTTP end-point.
+///
+/// The log entries are shipped in batches by a dedicated task, so that the
+/// telemetry processor is never blocked by the sink.
+pub struct LogEventTarget {
+    sender: tokio::sync::mpsc::UnboundedSender<LogRecord>,
+}
+
+/// Log entry of a function, as observed by the telemetry processor.
+#[derive(Debug, Clone, PartialEq)]
+struct LogRecord {
+    timestamp_sec: i64,
+    timestamp_ns: u32,
+    level: String,
+    target: String,
+    message: String,
+    fields: crate::telemetry_events::LogFields,
+    tags: std::collections::BTreeMap<String, String>,
+}
+
+impl LogRecord {
+    /// JSON line of the log entry, with the tags in lowercase.
+    fn to_line(&self) -> String {
+        let mut obj = serde_json::Map::new();
+        obj.insert(String::from("timestamp_sec"), serde_json::json!(self.timestamp_sec));
+        obj.insert(String::from("timestamp_ns"), serde_json::json!(self.timestamp_ns));
+        obj.insert(String::from("level"), serde_json::json!(self.level));
+        for (key, value) in &self.tags {
+            obj.insert(key.to_lowercase(), serde_json::json!(value));
+        }
+        obj.insert(String::from("target"), serde_json::json!(self.target));
+        obj.insert(String::from("message"), serde_json::json!(self.message));
+        obj.insert(String::from("fields"), serde_json::json!(self.fields));
+        serde_json::Value::Object(obj).to_string()
+    }
+
+    /// Labels of the stream of the log entry.
+    fn stream_labels(&self) -> std::collections::BTreeMap<String, String> {
+        let mut labels = std::collections::BTreeMap::from([
+            (String::from("service_name"), String::from("edgeless")),
+            (String::from("level"), self.level.to_lowercase()),
+        ]);
+        for key in STREAM_LABELS {
+            if let Some(value) = self.tags.get(key) {
+                labels.insert(key.to_lowercase(), value.clone());
+            }
+        }
+        labels
+    }
+}
+
+/// Local file that is rotated when it exceeds a given size: the current file
+/// is renamed as `path.1`, the previous `path.1` as `path.2`, and so on,
+/// until `path.<max_files>`, which is overwritten.
+struct RotatingFile {
+    path: std::path::PathBuf,
+    max_file_size: u64,
+    max_files: u32,
+    /// Size of the current file, read from the file system if unknown.
+    size: Option<u64>,
+}
+
+impl RotatingFile {
+    fn rotated_path(&self, index: u32) -> std::path::PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        std::path::PathBuf::from(path)
+    }
+
+    async fn rotate(&self) -> anyhow::Result<()> {
+        if self.max_files == 0 {
+            tokio::fs::remove_file(&self.path).await?;
+            return Ok(());
+        }
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if tokio::fs::try_exists(&from).await? {
+                tokio::fs::rename(&from, self.rotated_path(index + 1)).await?;
+            }
+        }
+        tokio::fs::rename(&self.path, self.rotated_path(1)).await?;
+        Ok(())
+    }
+
+    async fn append(&self, content: &str) -> anyhow::Result<()> {
+        if !content.is_empty() {
+            let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(&self.path).await?;
+            tokio::io::AsyncWriteExt::write_all(&mut file, content.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, lines: Vec<String>) -> anyhow::Result<()> {
+        // The size is read again from the file system upon failure.
+        let mut size = match self.size.take() {
+            Some(size) => size,
+            None => tokio::fs::metadata(&self.path).await.map(|metadata| metadata.len()).unwrap_or_default(),
+        };
+        let mut content = String::new();
+        for line in lines {
+            if size > 0 && size + line.len() as u64 > self.max_file_size {
+                self.append(&content).await?;
+                content.clear();
+                self.rotate().await?;
+                size = 0;
+            }
+            size += line.len() as u64;
+            content.push_str(&line);
+        }
+        self.append(&content).await?;
+        self.size = Some(size);
+        Ok(())
+    }
+}
+
+/// Destination of the log entries.
+enum LogSink {
+    /// Local files, where each log entry is appended as a JSON line.
+    File(RotatingFile),
+    /// HTTP end-point compatible with the Loki push API.
+    Http(reqwest::Client, String),
+}
+
+impl LogSink {
+    fn new(settings: &LogSinkSettings) -> anyhow::Result<Self> {
+        if let Some(path) = settings.url.strip_prefix("file://") {
+            anyhow::ensure!(!path.is_empty(), "empty path in logs URL: {}", settings.url);
+            anyhow::ensure!(settings.max_file_size > 0, "invalid maximum size of log files: 0");
+            Ok(Self::File(RotatingFile {
+                path: std::path::PathBuf::from(path),
+                max_file_size: settings.max_file_size,
+                max_files: settings.max_files,
+                size: None,
+            }))
+        } else if settings.url.starts_with("http://") || settings.url.starts_with("https://") {
+            let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+            Ok(Self::Http(client, settings.url.clone()))
+        } else {
+            anyhow::bail!("unsupported logs URL: {}", settings.url)
+        }
+    }
+
+    async fn ship(&mut self, batch: Vec<LogRecord>) -> anyhow::Result<()> {
+        match self {
+            Self::File(file) => {
+                file.write(batch.iter().map(|record| format!("{}\n", record.to_line())).collect()).await?;
+            }
+            Self::Http(client, url) => {
+                let response = client.post(url.as_str()).json(&to_push_request(&batch)).send().await?;
+                anyhow::ensure!(response.status().is_success(), "HTTP status {}", response.status());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LogEventTarget {
+    pub fn new(settings: &LogSinkSettings) -> anyhow::Result<Self> {
+        let mut sink = LogSink::new(settings)?;
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<LogRecord>();
+
+        tokio::spawn(async move {
+            let mut batch = vec![];
+            loop {
+                // Wait for the first log entry of the next batch.
+                match receiver.recv().await {
+                    Some(record) => batch.push(record),
+                    None => break,
+                }
+
+                // Collect more log entries until the batch is full or the interval expires.
+                let deadline = tokio::time::Instant::now() + SHIP_INTERVAL;
+                let mut closed = false;
+                while batch.len() < MAX_BATCH_SIZE {
+                    match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                        Ok(Some(record)) => batch.push(record),
+                        Ok(None) => {
+                            closed = true;
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                let num_records = batch.len();
+                if let Err(err) = sink.ship(std::mem::take(&mut batch)).await {
+                    log::warn!("could not ship {} log entries: {}", num_records, err);
+                }
+                if closed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { sender })
+    }
+}
+
+impl crate::telemetry_events::EventProcessor for LogEventTarget {
+    fn handle(
+        &mut self,
+        event: &crate::telemetry_events::TelemetryEvent,
+        event_tags: &std::collections::BTreeMap<String, String>,
+    ) -> crate::telemetry_events::TelemetryProcessingResult {
+        match event {
+            crate::telemetry_events::TelemetryEvent::FunctionLogEntry(lvl, target, message, fields) => {
+                let now = chrono::Utc::now();
+                let _ = self.sender.send(LogRecord {
+                    timestamp_sec: now.timestamp(),
+                    timestamp_ns: now.timestamp_subsec_nanos(),
+                    level: crate::telemetry_events::telemetry_to_api(*lvl),
+                    target: target.clone(),
+                    message: message.clone(),
+                    fields: fields.clone(),
+                    tags: event_tags.clone(),
+                });
+                crate::telemetry_events::TelemetryProcessingResult::PROCESSED
+            }
+            _ => crate::telemetry_events::TelemetryProcessingResult::PASSED,
+        }
+    }
+}
+
+/// Build the body of a request of the Loki push API from a batch of log
+/// entries, grouped by stream labels.
+fn to_push_request(batch: &[LogRecord]) -> serde_json::Value {
+    let mut streams = std::collections::BTreeMap::<std::collections::BTreeMap<String, String>, Vec<serde_json::Value>>::new();
+    for record in batch {
+        let timestamp = record.timestamp_sec as i128 * 1_000_000_000 + record.timestamp_ns as i128;
+        streams
+            .entry(record.stream_labels())
+            .or_default()
+            .push(serde_json::json!([timestamp.to_string(), record.to_line()]));
+    }
+
+    serde_json::json!({
+        "streams": streams
+            .into_iter()
+            .map(|(labels, values)| serde_json::json!({"stream": labels, "values": values}))
+            .collect::<Vec<serde_json::Value>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_record(node_id: &str, message: &str) -> LogRecord {
+        LogRecord {
+            timestamp_sec: 1700000000,
+            timestamp_ns: 42,
+            level: String::from("Info"),
+            target: String::from("my_target"),
+            message: message.to_string(),
+            fields: crate::telemetry_events::LogFields::from([(String::from("key"), String::from("value"))]),
+            tags: std::collections::BTreeMap::from([
+                (String::from("NODE_ID"), node_id.to_string()),
+                (String::from("FUNCTION_ID"), String::from("fun-1")),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_log_target_to_push_request() {
+        let request = to_push_request(&[new_record("node-1", "a"), new_record("node-2", "b"), new_record("node-1", "c")]);
+        let streams = request["streams"].as_array().unwrap();
+        assert_eq!(2, streams.len());
+        assert_eq!("node-1", streams[0]["stream"]["node_id"]);
+        assert_eq!("info", streams[0]["stream"]["level"]);
+        assert!(streams[0]["stream"].get("function_id").is_none());
+
+        let values = streams[0]["values"].as_array().unwrap();
+        assert_eq!(2, values.len());
+        assert_eq!("1700000000000000042", values[0][0]);
+        let line: serde_json::Value = serde_json::from_str(values[0][1].as_str().unwrap()).unwrap();
+        assert_eq!("a", line["message"]);
+        assert_eq!("fun-1", line["function_id"]);
+        assert_eq!("value", line["fields"]["key"]);
+    }
+
+    #[tokio::test]
+    async fn test_log_target_rotating_file() {
+        let path = std::env::temp_dir().join(format!("edgeless-logs-{}.json", uuid::Uuid::new_v4()));
+        let url = format!("file://{}", path.display());
+        assert!(LogSink::new(&LogSinkSettings::default()).is_err());
+        assert!(
+            LogSink::new(&LogSinkSettings {
+                url: String::from("ftp://localhost"),
+                ..Default::default()
+            })
+            .is_err()
+        );
+
+        let line_len = new_record("node-1", "0").to_line().len() as u64 + 1;
+        let mut sink = LogSink::new(&LogSinkSettings {
+            url,
+            max_file_size: 2 * line_len,
+            max_files: 2,
+        })
+        .unwrap();
+        for i in 0..4 {
+            sink.ship(vec![
+                new_record("node-1", &format!("{}", 2 * i)),
+                new_record("node-1", &format!("{}", 2 * i + 1)),
+            ])
+            .await
+            .unwrap();
+        }
+
+        let messages = |path: &std::path::Path| {
+            std::fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|line| {
+                    serde_json::from_str::<serde_json::Value>(line).unwrap()["message"]
+                        .as_str()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<String>>()
+        };
+        let rotated_path = |index: u32| std::path::PathBuf::from(format!("{}.{}", path.display(), index));
+        assert_eq!(vec!["6", "7"], messages(&path));
+        assert_eq!(vec!["4", "5"], messages(&rotated_path(1)));
+        assert_eq!(vec!["2", "3"], messages(&rotated_path(2)));
+        assert!(!rotated_path(3).exists());
+
+        for path in [path.clone(), rotated_path(1), rotated_path(2)] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
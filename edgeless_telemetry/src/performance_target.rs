@@ -18,22 +18,77 @@ pub struct Metrics {
     pub function_times: [FunctionTimes; 5],
     pub function_log_entries:
         std::collections::HashMap<edgeless_api::function_instance::ComponentId, Vec<edgeless_api::node_registration::FunctionLogEntry>>,
+    /// Number of requests throttled by resources, per resource instance.
+    pub resource_throttled: std::collections::HashMap<edgeless_api::function_instance::ComponentId, u64>,
+    /// Number of requests failed by resources, per resource instance.
+    pub resource_failures: std::collections::HashMap<edgeless_api::function_instance::ComponentId, u64>,
+    /// Dataplane metrics, per (component, channel, peer).
+    pub dataplane_channels: std::collections::HashMap<ChannelKey, edgeless_api::node_registration::ChannelMetrics>,
+    /// Application-defined metrics, per (function instance, name, labels).
+    pub function_metrics: std::collections::HashMap<FunctionMetricKey, edgeless_api::node_registration::FunctionMetric>,
 }
 
+/// Key of the dataplane metrics: (component, channel, peer).
+pub type ChannelKey = (
+    edgeless_api::function_instance::ComponentId,
+    String,
+    edgeless_api::function_instance::ComponentId,
+);
+
+/// Key of the application-defined metrics: (function instance, name, labels).
+pub type FunctionMetricKey = (
+    edgeless_api::function_instance::ComponentId,
+    String,
+    crate::telemetry_events::MetricLabels,
+);
+
 /// Non thread-safe data structure holding performance-related per-node metrics.
 #[derive(Default)]
 pub struct PerformanceTarget {
     metrics: Metrics,
+    /// Number of series of application-defined metrics, per function instance.
+    function_series: std::collections::HashMap<edgeless_api::function_instance::ComponentId, usize>,
 }
 
 impl PerformanceTarget {
     /// Return the current metrics and reset them.
     pub fn get_metrics(&mut self) -> Metrics {
+        self.function_series.clear();
         Metrics {
             function_times: std::mem::take(&mut self.metrics.function_times),
             function_log_entries: std::mem::take(&mut self.metrics.function_log_entries),
+            resource_throttled: std::mem::take(&mut self.metrics.resource_throttled),
+            resource_failures: std::mem::take(&mut self.metrics.resource_failures),
+            dataplane_channels: std::mem::take(&mut self.metrics.dataplane_channels),
+            function_metrics: std::mem::take(&mut self.metrics.function_metrics),
         }
     }
+
+    /// Return the dataplane metrics of a channel, with the timestamp updated.
+    fn channel_metrics(
+        &mut self,
+        event_tags: &std::collections::BTreeMap<String, String>,
+        channel: &str,
+        peer_id: &str,
+    ) -> Option<&mut edgeless_api::node_registration::ChannelMetrics> {
+        let component_id = uuid::Uuid::from_str(event_tags.get("FUNCTION_ID")?).ok()?;
+        // The peer is unknown if the event is dropped before being routed.
+        let peer_id = uuid::Uuid::from_str(peer_id).unwrap_or(uuid::Uuid::nil());
+        let now = chrono::Utc::now();
+        let metrics = self
+            .metrics
+            .dataplane_channels
+            .entry((component_id, channel.to_string(), peer_id))
+            .or_insert_with(|| edgeless_api::node_registration::ChannelMetrics {
+                component_id,
+                channel: channel.to_string(),
+                peer_id,
+                ..Default::default()
+            });
+        metrics.timestamp_sec = now.timestamp();
+        metrics.timestamp_ns = now.timestamp_subsec_nanos();
+        Some(metrics)
+    }
 }
 
 impl crate::telemetry_events::EventProcessor for PerformanceTarget {
@@ -98,7 +153,7 @@ impl crate::telemetry_events::EventProcessor for PerformanceTarget {
                     res.push(new_sample(lat));
                 }
             }
-            crate::telemetry_events::TelemetryEvent::FunctionLogEntry(_lvl, target, message) => {
+            crate::telemetry_events::TelemetryEvent::FunctionLogEntry(lvl, target, message, fields) => {
                 if let Some(function_id) = event_tags.get("FUNCTION_ID")
                     && let Ok(function_id) = uuid::Uuid::from_str(function_id)
                 {
@@ -109,9 +164,85 @@ impl crate::telemetry_events::EventProcessor for PerformanceTarget {
                         timestamp_ns: now.timestamp_subsec_nanos(),
                         target: target.to_string(),
                         message: message.to_string(),
+                        level: crate::telemetry_events::telemetry_to_api(*lvl),
+                        workflow_id: event_tags.get("WORKFLOW_ID").cloned().unwrap_or_default(),
+                        fields: fields.clone(),
                     });
                 }
             }
+            crate::telemetry_events::TelemetryEvent::FunctionMetric(kind, name, labels, value) => {
+                if let Some(function_id) = event_tags.get("FUNCTION_ID")
+                    && let Ok(function_id) = uuid::Uuid::from_str(function_id)
+                {
+                    let key = (function_id, name.clone(), labels.clone());
+                    if !self.metrics.function_metrics.contains_key(&key) {
+                        let series = self.function_series.entry(function_id).or_default();
+                        if *series >= crate::telemetry_events::MAX_METRIC_SERIES {
+                            log::warn!("metric {} of {} ignored: too many series", name, function_id);
+                            return crate::telemetry_events::TelemetryProcessingResult::PROCESSED;
+                        }
+                        *series += 1;
+                    }
+                    let now = chrono::Utc::now();
+                    let metric = self
+                        .metrics
+                        .function_metrics
+                        .entry(key)
+                        .or_insert_with(|| edgeless_api::node_registration::FunctionMetric {
+                            component_id: function_id,
+                            kind: *kind,
+                            name: name.clone(),
+                            labels: labels.clone(),
+                            min: f64::MAX,
+                            max: f64::MIN,
+                            ..Default::default()
+                        });
+                    if metric.kind == *kind {
+                        metric.timestamp_sec = now.timestamp();
+                        metric.timestamp_ns = now.timestamp_subsec_nanos();
+                        metric.count += 1;
+                        metric.sum += value;
+                        metric.min = metric.min.min(*value);
+                        metric.max = metric.max.max(*value);
+                        metric.last = *value;
+                    } else {
+                        log::warn!("metric {} of {} is a {}, ignoring {} value", name, function_id, metric.kind, kind);
+                    }
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::ResourceThrottled(_reason) => {
+                if let Some(function_id) = event_tags.get("FUNCTION_ID")
+                    && let Ok(function_id) = uuid::Uuid::from_str(function_id)
+                {
+                    *self.metrics.resource_throttled.entry(function_id).or_default() += 1;
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::ResourceFailure(_reason) => {
+                if let Some(function_id) = event_tags.get("FUNCTION_ID")
+                    && let Ok(function_id) = uuid::Uuid::from_str(function_id)
+                {
+                    *self.metrics.resource_failures.entry(function_id).or_default() += 1;
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::DataplaneSent(channel, target, size) => {
+                if let Some(metrics) = self.channel_metrics(event_tags, channel, target) {
+                    metrics.sent += 1;
+                    metrics.sent_bytes += *size as u64;
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::DataplaneReceived(source, size, delay) => {
+                if let Some(metrics) = self.channel_metrics(event_tags, "", source) {
+                    metrics.received += 1;
+                    metrics.received_bytes += *size as u64;
+                    metrics.queueing_delay_sum += delay.as_secs_f64();
+                    metrics.queueing_delay_max = metrics.queueing_delay_max.max(delay.as_secs_f64());
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::DataplaneDropped(channel, target, _reason) => {
+                if let Some(metrics) = self.channel_metrics(event_tags, channel, target) {
+                    metrics.dropped += 1;
+                }
+            }
             _ => {
                 return crate::telemetry_events::TelemetryProcessingResult::PASSED;
             }
@@ -178,7 +309,9 @@ mod tests {
     fn test_performance_target_get_metrics() {
         let mut target = PerformanceTarget::default();
         let fid = uuid::Uuid::new_v4();
-        let event_tags = std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), fid.to_string())]);
+        let wid = uuid::Uuid::new_v4();
+        let event_tags =
+            std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), fid.to_string()), ("WORKFLOW_ID".to_string(), wid.to_string())]);
 
         let metrics = target.get_metrics();
         assert!(metrics.function_times[FunctionTime::Instantiate as usize].is_empty());
@@ -187,6 +320,10 @@ mod tests {
         assert!(metrics.function_times[FunctionTime::Stop as usize].is_empty());
         assert!(metrics.function_times[FunctionTime::Transfer as usize].is_empty());
         assert!(metrics.function_log_entries.is_empty());
+        assert!(metrics.resource_throttled.is_empty());
+        assert!(metrics.resource_failures.is_empty());
+        assert!(metrics.dataplane_channels.is_empty());
+        assert!(metrics.function_metrics.is_empty());
 
         let mut expected_instantiate = vec![];
         let mut expected_init = vec![];
@@ -231,9 +368,24 @@ mod tests {
                     crate::telemetry_events::TelemetryLogLevel::Trace,
                     log_target_message.0,
                     log_target_message.1,
+                    crate::telemetry_events::LogFields::from([(String::from("i"), i.to_string())]),
                 ),
                 &event_tags,
             );
+            for _ in 0..i {
+                target.handle(
+                    &crate::telemetry_events::TelemetryEvent::ResourceThrottled(String::from("rate")),
+                    &event_tags,
+                );
+                target.handle(
+                    &crate::telemetry_events::TelemetryEvent::ResourceFailure(String::from("connect")),
+                    &event_tags,
+                );
+                target.handle(
+                    &crate::telemetry_events::TelemetryEvent::ResourceFailure(String::from("circuit-open")),
+                    &event_tags,
+                );
+            }
         }
 
         let metrics = target.get_metrics();
@@ -261,6 +413,14 @@ mod tests {
                 .map(|x| (x.target.clone(), x.message.clone()))
                 .collect::<Vec<(String, String)>>()
         );
+        for (i, entry) in log_entries.iter().enumerate() {
+            assert_eq!("Trace", entry.level);
+            assert_eq!(wid.to_string(), entry.workflow_id);
+            assert_eq!(Some(&i.to_string()), entry.fields.get("i"));
+        }
+
+        assert_eq!(Some(&45), metrics.resource_throttled.get(&fid));
+        assert_eq!(Some(&90), metrics.resource_failures.get(&fid));
 
         let metrics = target.get_metrics();
         assert!(metrics.function_times[FunctionTime::Instantiate as usize].is_empty());
@@ -269,5 +429,172 @@ mod tests {
         assert!(metrics.function_times[FunctionTime::Stop as usize].is_empty());
         assert!(metrics.function_times[FunctionTime::Transfer as usize].is_empty());
         assert!(metrics.function_log_entries.is_empty());
+        assert!(metrics.resource_throttled.is_empty());
+        assert!(metrics.resource_failures.is_empty());
+        assert!(metrics.dataplane_channels.is_empty());
+        assert!(metrics.function_metrics.is_empty());
+    }
+
+    #[test]
+    fn test_performance_target_dataplane_channels() {
+        let mut target = PerformanceTarget::default();
+        let fid = uuid::Uuid::new_v4();
+        let peer_1 = uuid::Uuid::new_v4();
+        let peer_2 = uuid::Uuid::new_v4();
+        let event_tags = std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), fid.to_string())]);
+
+        for i in 1..=4 {
+            target.handle(
+                &crate::telemetry_events::TelemetryEvent::DataplaneSent(String::from("out"), peer_1.to_string(), 100 * i),
+                &event_tags,
+            );
+            target.handle(
+                &crate::telemetry_events::TelemetryEvent::DataplaneReceived(peer_2.to_string(), 10 * i, std::time::Duration::from_millis(i as u64)),
+                &event_tags,
+            );
+        }
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::DataplaneDropped(String::from("out"), peer_1.to_string(), String::from("no-route")),
+            &event_tags,
+        );
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::DataplaneDropped(String::from("err"), String::new(), String::from("unknown-alias")),
+            &event_tags,
+        );
+
+        // Events without a valid component identifier are ignored.
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::DataplaneSent(String::from("out"), peer_1.to_string(), 1),
+            &std::collections::BTreeMap::new(),
+        );
+
+        let metrics = target.get_metrics();
+        assert_eq!(3, metrics.dataplane_channels.len());
+
+        let sent = metrics.dataplane_channels.get(&(fid, String::from("out"), peer_1)).unwrap();
+        assert_eq!(
+            (4, 1000, 0, 0, 1),
+            (sent.sent, sent.sent_bytes, sent.received, sent.received_bytes, sent.dropped)
+        );
+
+        let received = metrics.dataplane_channels.get(&(fid, String::new(), peer_2)).unwrap();
+        assert_eq!(
+            (0, 0, 4, 100, 0),
+            (
+                received.sent,
+                received.sent_bytes,
+                received.received,
+                received.received_bytes,
+                received.dropped
+            )
+        );
+        assert!((received.queueing_delay_sum - 0.010).abs() < 1e-9);
+        assert!((received.queueing_delay_max - 0.004).abs() < 1e-9);
+
+        let dropped = metrics.dataplane_channels.get(&(fid, String::from("err"), uuid::Uuid::nil())).unwrap();
+        assert_eq!((0, 1), (dropped.sent, dropped.dropped));
+
+        assert!(target.get_metrics().dataplane_channels.is_empty());
+    }
+
+    #[test]
+    fn test_performance_target_function_metrics() {
+        let mut target = PerformanceTarget::default();
+        let fid = uuid::Uuid::new_v4();
+        let event_tags = std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), fid.to_string())]);
+        let labels = std::collections::BTreeMap::from([("camera".to_string(), "front".to_string())]);
+
+        for i in 1..=4 {
+            target.handle(
+                &crate::telemetry_events::TelemetryEvent::FunctionMetric(
+                    edgeless_api::node_registration::FunctionMetricKind::Counter,
+                    String::from("frames"),
+                    labels.clone(),
+                    i as f64,
+                ),
+                &event_tags,
+            );
+            target.handle(
+                &crate::telemetry_events::TelemetryEvent::FunctionMetric(
+                    edgeless_api::node_registration::FunctionMetricKind::Gauge,
+                    String::from("score"),
+                    std::collections::BTreeMap::new(),
+                    10.0 - i as f64,
+                ),
+                &event_tags,
+            );
+        }
+
+        // Values with a kind different from that of the first one are ignored.
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::FunctionMetric(
+                edgeless_api::node_registration::FunctionMetricKind::Histogram,
+                String::from("frames"),
+                labels.clone(),
+                100.0,
+            ),
+            &event_tags,
+        );
+
+        // The same metric with different labels is a different series.
+        target.handle(
+            &crate::telemetry_events::TelemetryEvent::FunctionMetric(
+                edgeless_api::node_registration::FunctionMetricKind::Counter,
+                String::from("frames"),
+                std::collections::BTreeMap::new(),
+                1.0,
+            ),
+            &event_tags,
+        );
+
+        let metrics = target.get_metrics();
+        assert_eq!(3, metrics.function_metrics.len());
+
+        let frames = metrics.function_metrics.get(&(fid, String::from("frames"), labels.clone())).unwrap();
+        assert_eq!(edgeless_api::node_registration::FunctionMetricKind::Counter, frames.kind);
+        assert_eq!((4, 10.0, 1.0, 4.0, 4.0), (frames.count, frames.sum, frames.min, frames.max, frames.last));
+
+        let score = metrics
+            .function_metrics
+            .get(&(fid, String::from("score"), std::collections::BTreeMap::new()))
+            .unwrap();
+        assert_eq!(edgeless_api::node_registration::FunctionMetricKind::Gauge, score.kind);
+        assert_eq!((4, 6.0, 9.0, 6.0), (score.count, score.min, score.max, score.last));
+
+        assert!(target.get_metrics().function_metrics.is_empty());
+    }
+
+    #[test]
+    fn test_performance_target_function_metrics_max_series() {
+        let mut target = PerformanceTarget::default();
+        let fid = uuid::Uuid::new_v4();
+        let event_tags = std::collections::BTreeMap::from([("FUNCTION_ID".to_string(), fid.to_string())]);
+        let metric = |name: String| {
+            crate::telemetry_events::TelemetryEvent::FunctionMetric(
+                edgeless_api::node_registration::FunctionMetricKind::Gauge,
+                name,
+                std::collections::BTreeMap::new(),
+                1.0,
+            )
+        };
+
+        for i in 0..=crate::telemetry_events::MAX_METRIC_SERIES {
+            target.handle(&metric(format!("m{}", i)), &event_tags);
+        }
+        target.handle(&metric(String::from("m0")), &event_tags);
+        let metrics = target.get_metrics();
+        assert_eq!(crate::telemetry_events::MAX_METRIC_SERIES, metrics.function_metrics.len());
+        assert_eq!(
+            2,
+            metrics
+                .function_metrics
+                .get(&(fid, String::from("m0"), std::collections::BTreeMap::new()))
+                .unwrap()
+                .count
+        );
+
+        // The series are counted again after the metrics are collected.
+        target.handle(&metric(format!("m{}", crate::telemetry_events::MAX_METRIC_SERIES)), &event_tags);
+        assert_eq!(1, target.get_metrics().function_metrics.len());
     }
 }
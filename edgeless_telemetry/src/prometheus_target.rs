@@ -10,6 +10,22 @@ pub struct PrometheusEventTarget {
     function_count: prometheus_client::metrics::family::Family<RuntimeLabels, prometheus_client::metrics::gauge::Gauge>,
     execution_times: prometheus_client::metrics::family::Family<ExecutionLabels, prometheus_client::metrics::histogram::Histogram>,
     transfer_times: prometheus_client::metrics::family::Family<TransferLabels, prometheus_client::metrics::histogram::Histogram>,
+    resource_throttled: prometheus_client::metrics::family::Family<ThrottleLabels, prometheus_client::metrics::counter::Counter>,
+    resource_failures: prometheus_client::metrics::family::Family<FailureLabels, prometheus_client::metrics::counter::Counter>,
+    dataplane_sent: prometheus_client::metrics::family::Family<ChannelLabels, prometheus_client::metrics::counter::Counter>,
+    dataplane_received: prometheus_client::metrics::family::Family<ChannelLabels, prometheus_client::metrics::counter::Counter>,
+    dataplane_dropped: prometheus_client::metrics::family::Family<DropLabels, prometheus_client::metrics::counter::Counter>,
+    dataplane_payload_sizes: prometheus_client::metrics::family::Family<PayloadLabels, prometheus_client::metrics::histogram::Histogram>,
+    dataplane_queueing_delays: prometheus_client::metrics::family::Family<ChannelLabels, prometheus_client::metrics::histogram::Histogram>,
+    function_counters: prometheus_client::metrics::family::Family<
+        FunctionMetricLabels,
+        prometheus_client::metrics::counter::Counter<f64, std::sync::atomic::AtomicU64>,
+    >,
+    function_gauges:
+        prometheus_client::metrics::family::Family<FunctionMetricLabels, prometheus_client::metrics::gauge::Gauge<f64, std::sync::atomic::AtomicU64>>,
+    function_histograms: prometheus_client::metrics::family::Family<FunctionMetricLabels, prometheus_client::metrics::histogram::Histogram>,
+    function_series: FunctionMetricSeries,
+    dataplane_peers: DataplanePeers,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelSet)]
@@ -47,6 +63,112 @@ struct TransferLabels {
     function_id: String,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelSet)]
+struct ThrottleLabels {
+    node_id: String,
+    function_id: String,
+    resource_class_type: String,
+    reason: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelSet)]
+struct FailureLabels {
+    node_id: String,
+    function_id: String,
+    resource_class_type: String,
+    reason: String,
+}
+
+/// Labels of the events sent (to a target) or received (from a source) along
+/// a channel, which is empty for the events received.
+/// The peers beyond `MAX_DATAPLANE_PEERS` are aggregated as `other`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelSet)]
+struct ChannelLabels {
+    node_id: String,
+    function_id: String,
+    channel: String,
+    peer_id: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelSet)]
+struct DropLabels {
+    node_id: String,
+    function_id: String,
+    channel: String,
+    reason: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelValue)]
+enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, prometheus_client::encoding::EncodeLabelSet)]
+struct PayloadLabels {
+    node_id: String,
+    function_id: String,
+    direction: Direction,
+}
+
+/// Labels of the application-defined metrics: node_id, function_id, and
+/// metric name, followed by the labels set by the function instance.
+/// The labels are not known in advance, hence they cannot be a struct.
+type FunctionMetricLabels = Vec<(String, String)>;
+
+/// Series of the application-defined metrics exported, per function instance.
+#[derive(Default)]
+struct FunctionMetricSeries {
+    series: std::collections::HashMap<String, std::collections::HashSet<(edgeless_api::node_registration::FunctionMetricKind, FunctionMetricLabels)>>,
+}
+
+impl FunctionMetricSeries {
+    /// Return true if the series is already exported or it can be added
+    /// without exceeding the maximum number of series of the function instance.
+    fn admit(&mut self, function_id: &str, kind: edgeless_api::node_registration::FunctionMetricKind, labels: &FunctionMetricLabels) -> bool {
+        let series = self.series.entry(function_id.to_string()).or_default();
+        let key = (kind, labels.clone());
+        if series.contains(&key) {
+            return true;
+        }
+        if series.len() >= crate::telemetry_events::MAX_METRIC_SERIES {
+            return false;
+        }
+        series.insert(key);
+        true
+    }
+}
+
+/// Maximum number of distinct peers in the dataplane metrics, per function
+/// instance.
+const MAX_DATAPLANE_PEERS: usize = 100;
+
+/// Value of the peer_id label that aggregates the peers beyond the maximum.
+const OTHER_DATAPLANE_PEERS: &str = "other";
+
+/// Peers of the dataplane metrics exported, per function instance.
+#[derive(Default)]
+struct DataplanePeers {
+    peers: std::collections::HashMap<String, std::collections::HashSet<String>>,
+}
+
+impl DataplanePeers {
+    /// Return the value of the peer_id label: the peer itself if it is already
+    /// exported or it can be added without exceeding the maximum number of
+    /// peers of the function instance, otherwise `other`.
+    fn label(&mut self, function_id: &str, peer_id: String) -> String {
+        let peers = self.peers.entry(function_id.to_string()).or_default();
+        if peers.contains(&peer_id) {
+            return peer_id;
+        }
+        if peers.len() >= MAX_DATAPLANE_PEERS {
+            return String::from(OTHER_DATAPLANE_PEERS);
+        }
+        peers.insert(peer_id.clone());
+        peer_id
+    }
+}
+
 impl PrometheusEventTarget {
     pub async fn new(endpoint: &str) -> Self {
         let registry = std::sync::Arc::new(tokio::sync::Mutex::new(<prometheus_client::registry::Registry>::default()));
@@ -71,7 +193,79 @@ impl PrometheusEventTarget {
 
         registry.lock().await.register("function_count", "", function_count.clone());
         registry.lock().await.register("execution_times", "", execution_times.clone());
+        let resource_throttled =
+            prometheus_client::metrics::family::Family::<ThrottleLabels, prometheus_client::metrics::counter::Counter>::default();
+        let resource_failures = prometheus_client::metrics::family::Family::<FailureLabels, prometheus_client::metrics::counter::Counter>::default();
+
         registry.lock().await.register("transfer_times", "", transfer_times.clone());
+        registry
+            .lock()
+            .await
+            .register("resource_throttled", "Requests throttled by resources", resource_throttled.clone());
+        registry
+            .lock()
+            .await
+            .register("resource_failures", "Requests failed by resources", resource_failures.clone());
+
+        let dataplane_sent = prometheus_client::metrics::family::Family::<ChannelLabels, prometheus_client::metrics::counter::Counter>::default();
+        let dataplane_received = prometheus_client::metrics::family::Family::<ChannelLabels, prometheus_client::metrics::counter::Counter>::default();
+        let dataplane_dropped = prometheus_client::metrics::family::Family::<DropLabels, prometheus_client::metrics::counter::Counter>::default();
+        let dataplane_payload_sizes =
+            prometheus_client::metrics::family::Family::<PayloadLabels, prometheus_client::metrics::histogram::Histogram>::new_with_constructor(
+                || {
+                    // From 64 bytes to 1 MB.
+                    prometheus_client::metrics::histogram::Histogram::new(prometheus_client::metrics::histogram::exponential_buckets(64.0, 4.0, 8))
+                },
+            );
+        let dataplane_queueing_delays =
+            prometheus_client::metrics::family::Family::<ChannelLabels, prometheus_client::metrics::histogram::Histogram>::new_with_constructor(
+                || {
+                    // From 1 ms to about 2 s.
+                    prometheus_client::metrics::histogram::Histogram::new(prometheus_client::metrics::histogram::exponential_buckets(0.001, 2.0, 12))
+                },
+            );
+        {
+            let mut registry = registry.lock().await;
+            registry.register("dataplane_sent", "Events sent along dataplane channels", dataplane_sent.clone());
+            registry.register(
+                "dataplane_received",
+                "Events received along dataplane channels",
+                dataplane_received.clone(),
+            );
+            registry.register("dataplane_dropped", "Events dropped by the dataplane", dataplane_dropped.clone());
+            registry.register(
+                "dataplane_payload_sizes",
+                "Payload sizes of the dataplane events, in bytes",
+                dataplane_payload_sizes.clone(),
+            );
+            registry.register(
+                "dataplane_queueing_delays",
+                "Queueing delays of the dataplane events received, in seconds",
+                dataplane_queueing_delays.clone(),
+            );
+        }
+
+        let function_counters = prometheus_client::metrics::family::Family::<
+            FunctionMetricLabels,
+            prometheus_client::metrics::counter::Counter<f64, std::sync::atomic::AtomicU64>,
+        >::default();
+        let function_gauges = prometheus_client::metrics::family::Family::<
+            FunctionMetricLabels,
+            prometheus_client::metrics::gauge::Gauge<f64, std::sync::atomic::AtomicU64>,
+        >::default();
+        let function_histograms =
+            prometheus_client::metrics::family::Family::<FunctionMetricLabels, prometheus_client::metrics::histogram::Histogram>::new_with_constructor(
+                || {
+                    // From 1 ms to about 4000 s, for lack of a better choice.
+                    prometheus_client::metrics::histogram::Histogram::new(prometheus_client::metrics::histogram::exponential_buckets(0.001, 4.0, 12))
+                },
+            );
+        {
+            let mut registry = registry.lock().await;
+            registry.register("function_counter", "Application-defined counters", function_counters.clone());
+            registry.register("function_gauge", "Application-defined gauges", function_gauges.clone());
+            registry.register("function_histogram", "Application-defined histograms", function_histograms.clone());
+        }
 
         let reg_clone = registry.clone();
         let socket_addr: std::net::SocketAddr = endpoint.parse().unwrap_or_else(|_| panic!("invalid endpoint: {}", &endpoint));
@@ -95,10 +289,41 @@ impl PrometheusEventTarget {
             function_count,
             execution_times,
             transfer_times,
+            resource_throttled,
+            resource_failures,
+            dataplane_sent,
+            dataplane_received,
+            dataplane_dropped,
+            dataplane_payload_sizes,
+            dataplane_queueing_delays,
+            function_counters,
+            function_gauges,
+            function_histograms,
+            function_series: FunctionMetricSeries::default(),
+            dataplane_peers: DataplanePeers::default(),
         }
     }
 }
 
+/// Return the labels of an application-defined metric, i.e., those set by the
+/// node followed by those set by the function instance, or an error if the
+/// latter are not valid.
+fn function_metric_labels(
+    node_id: &str,
+    function_id: &str,
+    name: &str,
+    labels: &crate::telemetry_events::MetricLabels,
+) -> Result<FunctionMetricLabels, String> {
+    crate::telemetry_events::validate_metric_labels(labels)?;
+    let mut ret = vec![
+        (String::from("node_id"), node_id.to_string()),
+        (String::from("function_id"), function_id.to_string()),
+        (String::from("metric"), name.to_string()),
+    ];
+    ret.extend(labels.iter().map(|(key, value)| (key.clone(), value.clone())));
+    Ok(ret)
+}
+
 impl crate::telemetry_events::EventProcessor for PrometheusEventTarget {
     fn handle(
         &mut self,
@@ -160,6 +385,104 @@ impl crate::telemetry_events::EventProcessor for PrometheusEventTarget {
                         .observe(lat.as_secs_f64())
                 }
             }
+            crate::telemetry_events::TelemetryEvent::FunctionMetric(kind, name, labels, value) => {
+                if let (Some(node_id), Some(function_id)) = (event_tags.get("NODE_ID"), event_tags.get("FUNCTION_ID")) {
+                    match function_metric_labels(node_id, function_id, name, labels) {
+                        Err(err) => log::warn!("Metric {} of {} ignored: {}", name, function_id, err),
+                        Ok(labels) if !self.function_series.admit(function_id, *kind, &labels) => log::warn!(
+                            "Metric {} of {} ignored: too many series (max {})",
+                            name,
+                            function_id,
+                            crate::telemetry_events::MAX_METRIC_SERIES
+                        ),
+                        Ok(labels) => match kind {
+                            edgeless_api::node_registration::FunctionMetricKind::Counter => {
+                                self.function_counters.get_or_create(&labels).inc_by(*value);
+                            }
+                            edgeless_api::node_registration::FunctionMetricKind::Gauge => {
+                                self.function_gauges.get_or_create(&labels).set(*value);
+                            }
+                            edgeless_api::node_registration::FunctionMetricKind::Histogram => {
+                                self.function_histograms.get_or_create(&labels).observe(*value);
+                            }
+                        },
+                    }
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::ResourceThrottled(reason) => {
+                if let (Some(node_id), Some(function_id)) = (event_tags.get("NODE_ID"), event_tags.get("FUNCTION_ID")) {
+                    self.resource_throttled
+                        .get_or_create(&ThrottleLabels {
+                            node_id: node_id.to_string(),
+                            function_id: function_id.to_string(),
+                            resource_class_type: event_tags.get("RESOURCE_CLASS_TYPE").cloned().unwrap_or_default(),
+                            reason: reason.to_string(),
+                        })
+                        .inc();
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::ResourceFailure(reason) => {
+                if let (Some(node_id), Some(function_id)) = (event_tags.get("NODE_ID"), event_tags.get("FUNCTION_ID")) {
+                    self.resource_failures
+                        .get_or_create(&FailureLabels {
+                            node_id: node_id.to_string(),
+                            function_id: function_id.to_string(),
+                            resource_class_type: event_tags.get("RESOURCE_CLASS_TYPE").cloned().unwrap_or_default(),
+                            reason: reason.to_string(),
+                        })
+                        .inc();
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::DataplaneSent(channel, target, size) => {
+                if let (Some(node_id), Some(function_id)) = (event_tags.get("NODE_ID"), event_tags.get("FUNCTION_ID")) {
+                    self.dataplane_sent
+                        .get_or_create(&ChannelLabels {
+                            node_id: node_id.to_string(),
+                            function_id: function_id.to_string(),
+                            channel: channel.to_string(),
+                            peer_id: self.dataplane_peers.label(function_id, target.to_string()),
+                        })
+                        .inc();
+                    self.dataplane_payload_sizes
+                        .get_or_create(&PayloadLabels {
+                            node_id: node_id.to_string(),
+                            function_id: function_id.to_string(),
+                            direction: Direction::Sent,
+                        })
+                        .observe(*size as f64);
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::DataplaneReceived(source, size, delay) => {
+                if let (Some(node_id), Some(function_id)) = (event_tags.get("NODE_ID"), event_tags.get("FUNCTION_ID")) {
+                    let labels = ChannelLabels {
+                        node_id: node_id.to_string(),
+                        function_id: function_id.to_string(),
+                        channel: String::default(),
+                        peer_id: self.dataplane_peers.label(function_id, source.to_string()),
+                    };
+                    self.dataplane_received.get_or_create(&labels).inc();
+                    self.dataplane_queueing_delays.get_or_create(&labels).observe(delay.as_secs_f64());
+                    self.dataplane_payload_sizes
+                        .get_or_create(&PayloadLabels {
+                            node_id: node_id.to_string(),
+                            function_id: function_id.to_string(),
+                            direction: Direction::Received,
+                        })
+                        .observe(*size as f64);
+                }
+            }
+            crate::telemetry_events::TelemetryEvent::DataplaneDropped(channel, _target, reason) => {
+                if let (Some(node_id), Some(function_id)) = (event_tags.get("NODE_ID"), event_tags.get("FUNCTION_ID")) {
+                    self.dataplane_dropped
+                        .get_or_create(&DropLabels {
+                            node_id: node_id.to_string(),
+                            function_id: function_id.to_string(),
+                            channel: channel.to_string(),
+                            reason: reason.to_string(),
+                        })
+                        .inc();
+                }
+            }
             _ => {
                 return crate::telemetry_events::TelemetryProcessingResult::PASSED;
             }
@@ -167,3 +490,81 @@ impl crate::telemetry_events::EventProcessor for PrometheusEventTarget {
         crate::telemetry_events::TelemetryProcessingResult::FINAL
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_metric_labels() {
+        let labels =
+            |pairs: &[(&str, &str)]| -> crate::telemetry_events::MetricLabels { pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect() };
+
+        assert_eq!(
+            Ok(vec![
+                (String::from("node_id"), String::from("n")),
+                (String::from("function_id"), String::from("f")),
+                (String::from("metric"), String::from("frames")),
+                (String::from("_camera"), String::from("front")),
+                (String::from("lane2"), String::from("")),
+            ]),
+            function_metric_labels("n", "f", "frames", &labels(&[("_camera", "front"), ("lane2", "")]))
+        );
+        assert_eq!(Ok(3), function_metric_labels("n", "f", "frames", &labels(&[])).map(|x| x.len()));
+
+        // Invalid names.
+        for name in ["", "2lane", "lane-2", "lane 2", "càmera"] {
+            assert!(function_metric_labels("n", "f", "frames", &labels(&[(name, "x")])).is_err(), "{}", name);
+        }
+
+        // Reserved names.
+        for name in ["node_id", "function_id", "metric", "le", "quantile", "__name__"] {
+            assert!(function_metric_labels("n", "f", "frames", &labels(&[(name, "x")])).is_err(), "{}", name);
+        }
+
+        // Too many labels.
+        let names = (0..=crate::telemetry_events::MAX_METRIC_LABELS)
+            .map(|i| format!("l{}", i))
+            .collect::<Vec<_>>();
+        let pairs = names.iter().map(|name| (name.as_str(), "x")).collect::<Vec<_>>();
+        assert!(function_metric_labels("n", "f", "frames", &labels(&pairs[1..])).is_ok());
+        assert!(function_metric_labels("n", "f", "frames", &labels(&pairs)).is_err());
+    }
+
+    #[test]
+    fn test_function_metric_series() {
+        let mut series = FunctionMetricSeries::default();
+        let counter = edgeless_api::node_registration::FunctionMetricKind::Counter;
+        let gauge = edgeless_api::node_registration::FunctionMetricKind::Gauge;
+        let labels = |i: usize| vec![(String::from("metric"), format!("m{}", i))];
+
+        for i in 0..crate::telemetry_events::MAX_METRIC_SERIES {
+            assert!(series.admit("f1", counter, &labels(i)));
+        }
+
+        // Known series are still admitted, new ones are not.
+        assert!(series.admit("f1", counter, &labels(0)));
+        assert!(!series.admit("f1", gauge, &labels(0)));
+        assert!(!series.admit("f1", counter, &labels(crate::telemetry_events::MAX_METRIC_SERIES)));
+
+        // The maximum is per function instance.
+        assert!(series.admit("f2", counter, &labels(crate::telemetry_events::MAX_METRIC_SERIES)));
+    }
+    #[test]
+    fn test_dataplane_peers() {
+        let mut peers = DataplanePeers::default();
+        let peer = |i: usize| format!("p{}", i);
+
+        for i in 0..MAX_DATAPLANE_PEERS {
+            assert_eq!(peer(i), peers.label("f1", peer(i)));
+        }
+
+        // Known peers keep their label, new ones are aggregated.
+        assert_eq!(peer(0), peers.label("f1", peer(0)));
+        assert_eq!(OTHER_DATAPLANE_PEERS, peers.label("f1", peer(MAX_DATAPLANE_PEERS)));
+        assert_eq!(OTHER_DATAPLANE_PEERS, peers.label("f1", peer(MAX_DATAPLANE_PEERS + 1)));
+
+        // The maximum is per function instance.
+        assert_eq!(peer(MAX_DATAPLANE_PEERS), peers.label("f2", peer(MAX_DATAPLANE_PEERS)));
+    }
+}
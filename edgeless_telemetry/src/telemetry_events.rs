@@ -3,7 +3,7 @@
 // SPDX-FileCopyrightText: © 2023 Siemens AG
 // SPDX-License-Identifier: MIT
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TelemetryLogLevel {
     Error,
     Warn,
@@ -49,26 +49,162 @@ pub enum FunctionExitStatus {
     CodeError,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Labels of an application-defined metric.
+pub type MetricLabels = std::collections::BTreeMap<String, String>;
+
+/// Maximum number of labels of an application-defined metric.
+pub const MAX_METRIC_LABELS: usize = 8;
+
+/// Maximum number of series of application-defined metrics, i.e., distinct
+/// combinations of kind, name, and labels, per function instance.
+pub const MAX_METRIC_SERIES: usize = 1000;
+
+/// Label names of the application-defined metrics that are set by the node
+/// or have a special meaning in Prometheus.
+const RESERVED_METRIC_LABELS: [&str; 5] = ["node_id", "function_id", "metric", "le", "quantile"];
+
+/// Check the labels of an application-defined metric: there must be at most
+/// `MAX_METRIC_LABELS` of them, their names must match `[a-zA-Z_][a-zA-Z0-9_]*`,
+/// and they cannot be reserved, i.e., used by the node or beginning with `__`.
+pub fn validate_metric_labels(labels: &MetricLabels) -> Result<(), String> {
+    if labels.len() > MAX_METRIC_LABELS {
+        return Err(format!("too many labels: {} > {}", labels.len(), MAX_METRIC_LABELS));
+    }
+    for name in labels.keys() {
+        let mut chars = name.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("invalid label name '{}'", name));
+        }
+        if name.starts_with("__") || RESERVED_METRIC_LABELS.contains(&name.as_str()) {
+            return Err(format!("reserved label name '{}'", name));
+        }
+    }
+    Ok(())
+}
+
+/// Structured fields of a function log entry.
+pub type LogFields = std::collections::BTreeMap<String, String>;
+
+#[derive(Debug, PartialEq)]
 pub enum TelemetryEvent {
     FunctionInstantiate(std::time::Duration),
     FunctionInit(std::time::Duration),
-    FunctionLogEntry(TelemetryLogLevel, String, String), // (_, target, msg)
+    FunctionLogEntry(TelemetryLogLevel, String, String, LogFields), // (_, target, msg, fields)
     FunctionInvocationCompleted(std::time::Duration),
     FunctionStop(std::time::Duration),
     FunctionExit(FunctionExitStatus),
+    FunctionMetric(edgeless_api::node_registration::FunctionMetricKind, String, MetricLabels, f64), // (kind, name, labels, value)
     FunctionTransfer(std::time::Duration),
+    ResourceThrottled(String),                             // (reason)
+    ResourceFailure(String),                               // (reason)
+    DataplaneSent(String, String, usize),                  // (channel, target, payload size)
+    DataplaneReceived(String, usize, std::time::Duration), // (source, payload size, queueing delay)
+    DataplaneDropped(String, String, String),              // (channel, target, reason)
+    Span(TelemetrySpan),
+}
+
+/// Kind of a span, as defined by OpenTelemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Internal,
+    Server,
+    Client,
+    Producer,
+    Consumer,
+}
+
+/// Span of a distributed trace, which is observed when it ends.
+///
+/// If tracing is not enabled for the telemetry handle used to start the
+/// span, then nothing is observed and the metadata of the span are those of
+/// its parent, which are thus propagated unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelemetrySpan {
+    pub name: String,
+    pub kind: SpanKind,
+    /// Trace and span identifiers of this span, to be propagated with the
+    /// events generated within the span.
+    pub metadata: edgeless_api::function_instance::EventMetadata,
+    /// Metadata of the parent span, if this is not the root of the trace.
+    pub parent: Option<edgeless_api::function_instance::EventMetadata>,
+    pub start: std::time::SystemTime,
+    pub end: std::time::SystemTime,
+    pub attributes: std::collections::BTreeMap<String, String>,
+    /// Error message, if the operation failed.
+    pub error: Option<String>,
+    recording: bool,
+}
+
+impl TelemetrySpan {
+    /// Start a new span, child of the given parent, or root of a new trace
+    /// if the parent does not belong to a valid trace.
+    pub fn start(
+        telemetry_handle: &dyn TelemetryHandleAPI,
+        name: &str,
+        kind: SpanKind,
+        parent: &edgeless_api::function_instance::EventMetadata,
+    ) -> Self {
+        let recording = telemetry_handle.tracing_enabled();
+        let now = std::time::SystemTime::now();
+        Self {
+            name: name.to_string(),
+            kind,
+            metadata: if recording { parent.new_child() } else { parent.clone() },
+            parent: if recording && parent.has_trace() { Some(parent.clone()) } else { None },
+            start: now,
+            end: now,
+            attributes: std::collections::BTreeMap::new(),
+            error: None,
+            recording,
+        }
+    }
+
+    /// Start the root span of a new trace.
+    pub fn start_root(telemetry_handle: &dyn TelemetryHandleAPI, name: &str, kind: SpanKind) -> Self {
+        Self::start(
+            telemetry_handle,
+            name,
+            kind,
+            &edgeless_api::function_instance::EventMetadata::empty_new_root(),
+        )
+    }
+
+    pub fn set_attribute(&mut self, key: &str, value: impl ToString) {
+        if self.recording {
+            self.attributes.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    pub fn set_error(&mut self, error: impl ToString) {
+        if self.recording {
+            self.error = Some(error.to_string());
+        }
+    }
+
+    /// End the span and observe it, if tracing is enabled.
+    pub fn end(mut self, telemetry_handle: &mut dyn TelemetryHandleAPI) {
+        if self.recording {
+            self.end = std::time::SystemTime::now();
+            telemetry_handle.observe(TelemetryEvent::Span(self), std::collections::BTreeMap::new());
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct TelemetryHandle {
     handle_tags: std::collections::BTreeMap<String, String>,
     sender: tokio::sync::mpsc::UnboundedSender<TelemetryProcessorInput>,
+    tracing: bool,
 }
 
 pub trait TelemetryHandleAPI: Send {
     fn observe(&mut self, event: TelemetryEvent, event_tags: std::collections::BTreeMap<String, String>);
     fn fork(&mut self, child_tags: std::collections::BTreeMap<String, String>) -> Box<dyn TelemetryHandleAPI>;
+    /// True if the spans of distributed traces are exported.
+    fn tracing_enabled(&self) -> bool {
+        false
+    }
 }
 
 impl TelemetryHandleAPI for TelemetryHandle {
@@ -87,8 +223,13 @@ impl TelemetryHandleAPI for TelemetryHandle {
         Box::new(TelemetryHandle {
             handle_tags: merged_tags,
             sender: self.sender.clone(),
+            tracing: self.tracing,
         })
     }
+
+    fn tracing_enabled(&self) -> bool {
+        self.tracing
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -113,8 +254,12 @@ struct EventLogger {}
 impl EventProcessor for EventLogger {
     fn handle(&mut self, event: &TelemetryEvent, event_tags: &std::collections::BTreeMap<String, String>) -> TelemetryProcessingResult {
         match event {
-            TelemetryEvent::FunctionLogEntry(log_level, target, msg) => {
-                log::log!(to_log_level(log_level), "{}: {}", target, msg);
+            TelemetryEvent::FunctionLogEntry(log_level, target, msg, fields) => {
+                if fields.is_empty() {
+                    log::log!(to_log_level(log_level), "{}: {}", target, msg);
+                } else {
+                    log::log!(to_log_level(log_level), "{}: {} {:?}", target, msg, fields);
+                }
             }
             _ => log::info!("Event: {:?} , tags: {:?}", event, event_tags),
         }
@@ -151,6 +296,7 @@ impl TelemetryProcessorInner {
 
 pub struct TelemetryProcessor {
     sender: tokio::sync::mpsc::UnboundedSender<TelemetryProcessorInput>,
+    tracing: bool,
 }
 
 impl TelemetryProcessor {
@@ -163,8 +309,19 @@ impl TelemetryProcessor {
     ///   (https://prometheus.io/); if empty then the server is not started
     /// - `performance_target`: optional target that collects samples about
     ///   performance-related events
+    /// - `traces_url`: OTLP end-point to which the spans of distributed
+    ///   traces are exported, see `crate::trace_target::TraceEventTarget`;
+    ///   if empty then no spans are created
+    /// - `log_sink`: settings of the sink to which the function log entries
+    ///   are shipped, see `crate::log_target::LogEventTarget`; if the URL
+    ///   is empty then the log entries are not shipped
     ///
-    pub async fn new(prometheus_url: String, performance_target: Option<crate::performance_target::PerformanceTargetInner>) -> anyhow::Result<Self> {
+    pub async fn new(
+        prometheus_url: String,
+        performance_target: Option<crate::performance_target::PerformanceTargetInner>,
+        traces_url: String,
+        log_sink: crate::log_target::LogSinkSettings,
+    ) -> anyhow::Result<Self> {
         let mut processing_chain: Vec<Box<dyn EventProcessor>> = vec![];
 
         // Add the performance target, if present.
@@ -184,6 +341,23 @@ impl TelemetryProcessor {
             }
         }
 
+        // Create and add the trace target, if required.
+        let tracing = !traces_url.is_empty();
+        if tracing {
+            match crate::trace_target::TraceEventTarget::new(&traces_url) {
+                Ok(trace_target) => processing_chain.push(Box::new(trace_target)),
+                Err(err) => anyhow::bail!("could not create the trace exporter: {}", err),
+            }
+        }
+
+        // Create and add the log sink, if required.
+        if !log_sink.url.is_empty() {
+            match crate::log_target::LogEventTarget::new(&log_sink) {
+                Ok(log_target) => processing_chain.push(Box::new(log_target)),
+                Err(err) => anyhow::bail!("could not create the log sink: {}", err),
+            }
+        }
+
         // Created and the log target, if required.
         processing_chain.push(Box::new(EventLogger::default()));
 
@@ -196,13 +370,14 @@ impl TelemetryProcessor {
             inner.run().await;
         });
 
-        Ok(Self { sender })
+        Ok(Self { sender, tracing })
     }
 
     pub fn get_handle(&self, handle_tags: std::collections::BTreeMap<String, String>) -> TelemetryHandle {
         TelemetryHandle {
             handle_tags,
             sender: self.sender.clone(),
+            tracing: self.tracing,
         }
     }
 }
@@ -0,0 +1,334 @@
+// SPDX-FileCopyrightText: © 2023 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+// SPDX-License-Identifier: MIT
+
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::trace_service_client::TraceServiceClient;
+use opentelemetry_proto::tonic::common::v1::{AnyValue, InstrumentationScope, KeyValue, any_value};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use opentelemetry_proto::tonic::trace::v1::span::SpanKind as OtlpSpanKind;
+use opentelemetry_proto::tonic::trace::v1::{ResourceSpans, ScopeSpans, Span, Status, status};
+
+/// Maximum number of spans exported with a single request.
+const MAX_BATCH_SIZE: usize = 512;
+
+/// Maximum time a span waits before being exported.
+const EXPORT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Exports the spans of distributed traces in the OpenTelemetry Protocol
+/// (OTLP) format, either to a collector via gRPC or to a local file.
+///
+/// The spans are exported in batches by a dedicated task, so that the
+/// telemetry processor is never blocked by the exporter.
+pub struct TraceEventTarget {
+    sender: tokio::sync::mpsc::UnboundedSender<(String, Span)>,
+}
+
+/// Destination of the spans.
+enum SpanExporter {
+    /// OTLP/gRPC collector.
+    Collector(TraceServiceClient<tonic::transport::Channel>),
+    /// Local file, where each export request is appended as a JSON line.
+    File(std::path::PathBuf),
+}
+
+impl SpanExporter {
+    /// Create an exporter from a URL, which can be either `http://host:port`
+    /// for an OTLP/gRPC collector or `file://path` for a local file.
+    fn new(url: &str) -> anyhow::Result<Self> {
+        if let Some(path) = url.strip_prefix("file://") {
+            anyhow::ensure!(!path.is_empty(), "empty path in traces URL: {}", url);
+            Ok(Self::File(std::path::PathBuf::from(path)))
+        } else if url.starts_with("http://") || url.starts_with("https://") {
+            let channel = tonic::transport::Endpoint::from_shared(url.to_string())?.connect_lazy();
+            Ok(Self::Collector(TraceServiceClient::new(channel)))
+        } else {
+            anyhow::bail!("unsupported traces URL: {}", url)
+        }
+    }
+
+    async fn export(&mut self, request: ExportTraceServiceRequest) -> anyhow::Result<()> {
+        match self {
+            Self::Collector(client) => {
+                client.export(request).await?;
+            }
+            Self::File(path) => {
+                let mut line = serde_json::to_string(&request)?;
+                line.push('\n');
+                let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(path).await?;
+                tokio::io::AsyncWriteExt::write_all(&mut file, line.as_bytes()).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TraceEventTarget {
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        let mut exporter = SpanExporter::new(url)?;
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<(String, Span)>();
+
+        tokio::spawn(async move {
+            let mut batch = vec![];
+            loop {
+                // Wait for the first span of the next batch.
+                match receiver.recv().await {
+                    Some(span) => batch.push(span),
+                    None => break,
+                }
+
+                // Collect more spans until the batch is full or the interval expires.
+                let deadline = tokio::time::Instant::now() + EXPORT_INTERVAL;
+                let mut closed = false;
+                while batch.len() < MAX_BATCH_SIZE {
+                    match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                        Ok(Some(span)) => batch.push(span),
+                        Ok(None) => {
+                            closed = true;
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                let num_spans = batch.len();
+                if let Err(err) = exporter.export(to_request(std::mem::take(&mut batch))).await {
+                    log::warn!("could not export {} spans: {}", num_spans, err);
+                }
+                if closed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { sender })
+    }
+}
+
+impl crate::telemetry_events::EventProcessor for TraceEventTarget {
+    fn handle(
+        &mut self,
+        event: &crate::telemetry_events::TelemetryEvent,
+        event_tags: &std::collections::BTreeMap<String, String>,
+    ) -> crate::telemetry_events::TelemetryProcessingResult {
+        match event {
+            crate::telemetry_events::TelemetryEvent::Span(span) => {
+                let node_id = event_tags.get("NODE_ID").cloned().unwrap_or_default();
+                let _ = self.sender.send((node_id, to_proto(span, event_tags)));
+                crate::telemetry_events::TelemetryProcessingResult::FINAL
+            }
+            _ => crate::telemetry_events::TelemetryProcessingResult::PASSED,
+        }
+    }
+}
+
+fn string_attribute(key: String, value: String) -> KeyValue {
+    KeyValue {
+        key,
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value)),
+        }),
+    }
+}
+
+fn unix_nanos(t: &std::time::SystemTime) -> u64 {
+    t.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or_default()
+}
+
+/// Convert a span into its OTLP representation, with the tags of the
+/// telemetry handle added as `edgeless.<tag>` attributes.
+fn to_proto(span: &crate::telemetry_events::TelemetrySpan, event_tags: &std::collections::BTreeMap<String, String>) -> Span {
+    let kind = match span.kind {
+        crate::telemetry_events::SpanKind::Internal => OtlpSpanKind::Internal,
+        crate::telemetry_events::SpanKind::Server => OtlpSpanKind::Server,
+        crate::telemetry_events::SpanKind::Client => OtlpSpanKind::Client,
+        crate::telemetry_events::SpanKind::Producer => OtlpSpanKind::Producer,
+        crate::telemetry_events::SpanKind::Consumer => OtlpSpanKind::Consumer,
+    };
+    let attributes = event_tags
+        .iter()
+        .map(|(key, value)| (format!("edgeless.{}", key.to_lowercase()), value.clone()))
+        .chain(span.attributes.iter().map(|(key, value)| (key.clone(), value.clone())))
+        .map(|(key, value)| string_attribute(key, value))
+        .collect();
+    let status = span.error.as_ref().map(|error| Status {
+        message: error.clone(),
+        code: status::StatusCode::Error as i32,
+    });
+
+    Span {
+        trace_id: span.metadata.trace_id().to_bytes().to_vec(),
+        span_id: span.metadata.span_id().to_bytes().to_vec(),
+        parent_span_id: span
+            .parent
+            .as_ref()
+            .map(|parent| parent.span_id().to_bytes().to_vec())
+            .unwrap_or_default(),
+        name: span.name.clone(),
+        kind: kind as i32,
+        start_time_unix_nano: unix_nanos(&span.start),
+        end_time_unix_nano: unix_nanos(&span.end),
+        attributes,
+        status,
+        ..Default::default()
+    }
+}
+
+/// Build an export request from a batch of spans, grouped by node.
+fn to_request(batch: Vec<(String, Span)>) -> ExportTraceServiceRequest {
+    let mut spans_by_node = std::collections::BTreeMap::<String, Vec<Span>>::new();
+    for (node_id, span) in batch {
+        spans_by_node.entry(node_id).or_default().push(span);
+    }
+
+    ExportTraceServiceRequest {
+        resource_spans: spans_by_node
+            .into_iter()
+            .map(|(node_id, spans)| ResourceSpans {
+                resource: Some(Resource {
+                    attributes: vec![
+                        string_attribute(String::from("service.name"), String::from("edgeless_node")),
+                        string_attribute(String::from("edgeless.node_id"), node_id),
+                    ],
+                    ..Default::default()
+                }),
+                scope_spans: vec![ScopeSpans {
+                    scope: Some(InstrumentationScope {
+                        name: String::from("edgeless"),
+                        version: String::from(env!("CARGO_PKG_VERSION")),
+                        ..Default::default()
+                    }),
+                    spans,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry_events::{SpanKind, TelemetryEvent, TelemetryHandleAPI, TelemetrySpan};
+    use edgeless_api::function_instance::EventMetadata;
+
+    /// Handle that collects the observed events.
+    struct TracingHandle {
+        events: Vec<TelemetryEvent>,
+        enabled: bool,
+    }
+
+    impl TracingHandle {
+        fn new(enabled: bool) -> Self {
+            Self { events: vec![], enabled }
+        }
+    }
+
+    impl TelemetryHandleAPI for TracingHandle {
+        fn observe(&mut self, event: TelemetryEvent, _event_tags: std::collections::BTreeMap<String, String>) {
+            self.events.push(event);
+        }
+
+        fn fork(&mut self, _child_tags: std::collections::BTreeMap<String, String>) -> Box<dyn TelemetryHandleAPI> {
+            Box::new(TracingHandle::new(self.enabled))
+        }
+
+        fn tracing_enabled(&self) -> bool {
+            self.enabled
+        }
+    }
+
+    fn ended_span(parent: &EventMetadata) -> TelemetrySpan {
+        let mut handle = TracingHandle::new(true);
+        let mut span = TelemetrySpan::start(&handle, "cast", SpanKind::Producer, parent);
+        span.set_attribute("edgeless.target", "out");
+        span.set_error("no route");
+        span.end(&mut handle);
+        assert_eq!(1, handle.events.len());
+        match handle.events.pop() {
+            Some(TelemetryEvent::Span(span)) => span,
+            _ => panic!("unexpected event"),
+        }
+    }
+
+    #[test]
+    fn test_trace_target_span_parents() {
+        // Span with a parent in a valid trace.
+        let parent = EventMetadata::new_root();
+        let span = ended_span(&parent);
+        assert_eq!(parent.trace_id(), span.metadata.trace_id());
+        assert_ne!(parent.span_id(), span.metadata.span_id());
+        assert_eq!(Some(parent), span.parent);
+
+        // Span without a valid parent: root of a new trace.
+        let span = ended_span(&EventMetadata::empty_new_root());
+        assert!(span.metadata.has_trace());
+        assert!(span.parent.is_none());
+
+        // No span is observed if tracing is disabled, and the parent
+        // metadata are propagated unchanged.
+        let parent = EventMetadata::new_root();
+        let mut handle = TracingHandle::new(false);
+        let span = TelemetrySpan::start(&handle, "call", SpanKind::Client, &parent);
+        assert_eq!(parent, span.metadata);
+        span.end(&mut handle);
+        assert!(handle.events.is_empty());
+    }
+
+    #[test]
+    fn test_trace_target_to_proto() {
+        let parent = EventMetadata::new_root();
+        let span = ended_span(&parent);
+        let event_tags = std::collections::BTreeMap::from([
+            (String::from("NODE_ID"), String::from("node-1")),
+            (String::from("FUNCTION_ID"), String::from("fun-1")),
+        ]);
+
+        let proto = to_proto(&span, &event_tags);
+        assert_eq!(span.metadata.trace_id().to_bytes().to_vec(), proto.trace_id);
+        assert_eq!(span.metadata.span_id().to_bytes().to_vec(), proto.span_id);
+        assert_eq!(parent.span_id().to_bytes().to_vec(), proto.parent_span_id);
+        assert_eq!("cast", proto.name);
+        assert_eq!(OtlpSpanKind::Producer as i32, proto.kind);
+        assert!(proto.start_time_unix_nano <= proto.end_time_unix_nano);
+        assert_eq!(
+            vec!["edgeless.function_id", "edgeless.node_id", "edgeless.target"],
+            proto.attributes.iter().map(|kv| kv.key.as_str()).collect::<Vec<&str>>()
+        );
+        let status = proto.status.clone().unwrap();
+        assert_eq!(status::StatusCode::Error as i32, status.code);
+        assert_eq!("no route", status.message);
+
+        let request = to_request(vec![
+            (String::from("node-1"), proto.clone()),
+            (String::from("node-2"), proto.clone()),
+            (String::from("node-1"), proto),
+        ]);
+        assert_eq!(2, request.resource_spans.len());
+        assert_eq!(2, request.resource_spans[0].scope_spans[0].spans.len());
+        assert_eq!(1, request.resource_spans[1].scope_spans[0].spans.len());
+    }
+
+    #[tokio::test]
+    async fn test_trace_target_file_exporter() {
+        let path = std::env::temp_dir().join(format!("edgeless-traces-{}.json", uuid::Uuid::new_v4()));
+        let url = format!("file://{}", path.display());
+        assert!(SpanExporter::new("file://").is_err());
+        assert!(SpanExporter::new("ftp://localhost").is_err());
+
+        let mut exporter = SpanExporter::new(&url).unwrap();
+        let span = to_proto(&ended_span(&EventMetadata::new_root()), &std::collections::BTreeMap::new());
+        for _ in 0..2 {
+            exporter.export(to_request(vec![(String::from("node-1"), span.clone())])).await.unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let lines = content.lines().collect::<Vec<&str>>();
+        assert_eq!(2, lines.len());
+        for line in lines {
+            let request: ExportTraceServiceRequest = serde_json::from_str(line).unwrap();
+            assert_eq!(vec![span.clone()], request.resource_spans[0].scope_spans[0].spans);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS WorkflowState (
+    id VARCHAR(255) PRIMARY KEY,
+    metadata JSONB NOT NULL
+);
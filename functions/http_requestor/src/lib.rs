@@ -22,6 +22,7 @@ impl EdgeFunction for RequestorFun {
                 body: None,
                 method: edgeless_http::EdgelessHTTPMethod::Get,
                 path: "/users/raphaelhetzel/keys".to_string(),
+                path_params: std::collections::HashMap::new(),
             })
             .as_bytes(),
         );
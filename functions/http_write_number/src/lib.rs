@@ -19,6 +19,7 @@ impl EdgeFunction for HttpWriteNumberFun {
                 body: Some(encoded_message.to_vec()),
                 method: edgeless_http::EdgelessHTTPMethod::Post,
                 path: "".to_string(),
+                path_params: std::collections::HashMap::new(),
             })
             .as_bytes(),
         );
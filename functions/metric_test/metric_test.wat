@@ -0,0 +1,42 @@
+;; SPDX-FileCopyrightText: © 2025 Claudio Cicconetti <c.cicconetti@iit.cnr.it>
+;; SPDX-License-Identifier: MIT
+
+;; Minimal function, written in the WebAssembly text format, that emits
+;; application-defined metrics upon every cast: a valid one followed by
+;; invalid ones, which are expected to be ignored by the node.
+(module
+  (import "env" "telemetry_metric_asm" (func $telemetry_metric (param i32 i32 i32 i32 i32 f64)))
+  (memory (export "memory") 1)
+
+  (data (i32.const 0) "frames")
+  (data (i32.const 16) "camera=front")
+  (data (i32.const 32) "camera")
+  (data (i32.const 48) "node_id=n1")
+
+  ;; Bump allocator, reset at the beginning of every event.
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "edgeless_mem_alloc") (param $size i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $size)))
+    (local.get $ptr))
+  (func (export "edgeless_mem_free") (param i32 i32))
+  (func (export "edgeless_mem_clear")
+    (global.set $next (i32.const 1024)))
+
+  (func (export "handle_init_asm") (param i32 i32 i32 i32))
+  (func (export "handle_stop_asm"))
+  (func (export "handle_call_asm") (param i32 i32 i32 i32 i32 i32) (result i32)
+    (i32.const 0))
+
+  (func (export "handle_cast_asm") (param i32 i32 i32 i32)
+    ;; counter frames{camera="front"} += 2
+    (call $telemetry_metric (i32.const 0) (i32.const 0) (i32.const 6) (i32.const 16) (i32.const 12) (f64.const 2))
+    ;; malformed labels
+    (call $telemetry_metric (i32.const 0) (i32.const 0) (i32.const 6) (i32.const 32) (i32.const 6) (f64.const 1))
+    ;; reserved label
+    (call $telemetry_metric (i32.const 0) (i32.const 0) (i32.const 6) (i32.const 48) (i32.const 10) (f64.const 1))
+    ;; non-finite value
+    (call $telemetry_metric (i32.const 1) (i32.const 0) (i32.const 6) (i32.const 0) (i32.const 0) (f64.const nan))
+    ;; negative counter increment
+    (call $telemetry_metric (i32.const 0) (i32.const 0) (i32.const 6) (i32.const 16) (i32.const 12) (f64.const -1))))
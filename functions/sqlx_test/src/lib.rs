@@ -5,11 +5,7 @@ use edgeless_function::*;
 
 struct SqlxTest;
 
-#[derive(serde::Serialize, serde::Deserialize, Default)]
-struct WorkflowState {
-    id: String,
-    metadata: MyState,
-}
+static ID: std::sync::OnceLock<String> = std::sync::OnceLock::new();
 
 #[derive(serde::Serialize, serde::Deserialize, Default)]
 struct MyState {
@@ -17,13 +13,17 @@ struct MyState {
     bar: String,
 }
 
-fn call_wrapper(msg: &str) -> Option<WorkflowState> {
-    match call("database", msg.as_bytes()) {
+fn call_wrapper(request: serde_json::Value) -> Option<serde_json::Value> {
+    match call("database", request.to_string().as_bytes()) {
         CallRet::Reply(msg) => {
             let reply = std::str::from_utf8(&msg).unwrap_or("not UTF8");
             log::info!("Got Reply: {}", reply);
-            let cur_state: WorkflowState = serde_json::from_str(reply).unwrap_or_default();
-            Some(cur_state)
+            let reply: serde_json::Value = serde_json::from_str(reply).unwrap_or_default();
+            if let Some(error) = reply.get("error") {
+                log::error!("Error from the database: {}", error);
+                return None;
+            }
+            Some(reply)
         }
         CallRet::NoReply => {
             log::warn!("Received empty reply from the database");
@@ -38,23 +38,40 @@ fn call_wrapper(msg: &str) -> Option<WorkflowState> {
 
 impl EdgeFunction for SqlxTest {
     fn handle_cast(_src: InstanceId, message: &[u8]) {
+        let id = ID.get().unwrap();
         let new_value = core::str::from_utf8(message).unwrap().parse::<f32>().unwrap_or_default();
 
+        // Read and update the state within the same transaction.
+        let transaction = match call_wrapper(serde_json::json!({"op": "begin"})) {
+            Some(reply) => reply["transaction"].as_str().unwrap_or_default().to_string(),
+            None => return,
+        };
+        let old_state = call_wrapper(serde_json::json!({
+            "op": "query",
+            "sql": "SELECT metadata FROM WorkflowState WHERE id = $1",
+            "params": [id],
+            "transaction": transaction
+        }))
+        .and_then(|reply| reply["rows"][0]["metadata"].as_str().map(|x| x.to_string()))
+        .and_then(|metadata| serde_json::from_str::<MyState>(&metadata).ok());
+
         // Only update the state if an old one exists.
-        if let Some(old_state) = call_wrapper("SELECT id, metadata FROM WorkflowState WHERE id=$1") {
-            log::info!("wf_id {}, old value: {}, new value: {}", old_state.id, old_state.metadata.foo, new_value);
-            let new_state = MyState {
-                foo: new_value,
-                bar: String::from("subsequent"),
-            };
-            call_wrapper(
-                format!(
-                    "UPDATE WorkflowState SET metadata='{}'  WHERE id = $1",
-                    serde_json::to_string(&new_state).unwrap_or_default()
-                )
-                .as_str(),
-            );
-        }
+        let Some(old_state) = old_state else {
+            call_wrapper(serde_json::json!({"op": "rollback", "transaction": transaction}));
+            return;
+        };
+        log::info!("id {}, old value: {}, new value: {}", id, old_state.foo, new_value);
+        let new_state = MyState {
+            foo: new_value,
+            bar: String::from("subsequent"),
+        };
+        call_wrapper(serde_json::json!({
+            "op": "execute",
+            "sql": "UPDATE WorkflowState SET metadata = $1 WHERE id = $2",
+            "params": [serde_json::to_string(&new_state).unwrap_or_default(), id],
+            "transaction": transaction
+        }));
+        call_wrapper(serde_json::json!({"op": "commit", "transaction": transaction}));
     }
 
     fn handle_call(_src: InstanceId, _message: &[u8]) -> CallRet {
@@ -66,23 +83,22 @@ impl EdgeFunction for SqlxTest {
         edgeless_function::init_logger();
         log::info!("sqlx function init");
 
-        let initial_value = core::str::from_utf8(init_message.unwrap_or_default())
-            .unwrap()
-            .parse::<f32>()
-            .unwrap_or_default();
+        let arguments = edgeless_function::init_payload_to_args(init_message);
+        let id = arguments.get("id").unwrap_or(&"sqlx_test").to_string();
+        let initial_value = arguments.get("initial-value").unwrap_or(&"0.0").parse::<f32>().unwrap_or_default();
 
         let initial_state = MyState {
             foo: initial_value,
             bar: String::from("initial"),
         };
 
-        call_wrapper(
-            format!(
-                "INSERT INTO WorkflowState (id, metadata) Values($1, '{}')",
-                serde_json::to_string(&initial_state).unwrap_or_default()
-            )
-            .as_str(),
-        );
+        call_wrapper(serde_json::json!({
+            "op": "execute",
+            "sql": "INSERT INTO WorkflowState (id, metadata) VALUES ($1, $2)",
+            "params": [id, serde_json::to_string(&initial_state).unwrap_or_default()]
+        }));
+
+        let _ = ID.set(id);
     }
 
     fn handle_stop() {
@@ -92,7 +108,13 @@ impl EdgeFunction for SqlxTest {
         // no way to enforce a specific sequence when terminating instances
         // in a workflow).
         log::info!("Try to clean database");
-        call_wrapper("DELETE FROM WorkflowState WHERE id=$1");
+        if let Some(id) = ID.get() {
+            call_wrapper(serde_json::json!({
+                "op": "execute",
+                "sql": "DELETE FROM WorkflowState WHERE id = $1",
+                "params": [id]
+            }));
+        }
     }
 }
 